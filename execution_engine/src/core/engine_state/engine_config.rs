@@ -8,7 +8,7 @@ use std::collections::BTreeSet;
 use num_rational::Ratio;
 use num_traits::One;
 
-use casper_types::{account::AccountHash, PublicKey};
+use casper_types::{account::AccountHash, ProtocolVersion, PublicKey};
 
 use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
 
@@ -81,6 +81,10 @@ pub struct EngineConfig {
     pub(crate) refund_handling: RefundHandling,
     /// Fee handling.
     pub(crate) fee_handling: FeeHandling,
+    /// The protocol version from which the `casper_get_era_id` host function is available to Wasm.
+    ///
+    /// If `None`, the host function is not available.
+    start_protocol_version_with_get_era_id: Option<ProtocolVersion>,
 }
 
 impl Default for EngineConfig {
@@ -100,6 +104,7 @@ impl Default for EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            start_protocol_version_with_get_era_id: None,
         }
     }
 }
@@ -140,6 +145,7 @@ impl EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            start_protocol_version_with_get_era_id: None,
         }
     }
 
@@ -213,6 +219,12 @@ impl EngineConfig {
         self.fee_handling
     }
 
+    /// Returns the protocol version from which the `casper_get_era_id` host function is available,
+    /// if any.
+    pub fn start_protocol_version_with_get_era_id(&self) -> Option<ProtocolVersion> {
+        self.start_protocol_version_with_get_era_id
+    }
+
     /// Sets the `wasm_config.max_memory` to `new_value`.
     #[cfg(feature = "test-support")]
     pub fn set_max_memory(&mut self, new_value: u32) {
@@ -240,6 +252,7 @@ pub struct EngineConfigBuilder {
     allow_unrestricted_transfers: Option<bool>,
     refund_handling: Option<RefundHandling>,
     fee_handling: Option<FeeHandling>,
+    start_protocol_version_with_get_era_id: Option<ProtocolVersion>,
 }
 
 impl EngineConfigBuilder {
@@ -354,6 +367,15 @@ impl EngineConfigBuilder {
         self
     }
 
+    /// Sets the protocol version from which the `casper_get_era_id` host function is available.
+    pub fn with_start_protocol_version_with_get_era_id(
+        mut self,
+        value: Option<ProtocolVersion>,
+    ) -> Self {
+        self.start_protocol_version_with_get_era_id = value;
+        self
+    }
+
     /// Builds a new [`EngineConfig`] object.
     pub fn build(self) -> EngineConfig {
         let max_query_depth = self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH);
@@ -391,6 +413,7 @@ impl EngineConfigBuilder {
             .vesting_schedule_period_millis
             .unwrap_or(DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS);
         let max_delegators_per_validator = self.max_delegators_per_validator;
+        let start_protocol_version_with_get_era_id = self.start_protocol_version_with_get_era_id;
 
         EngineConfig {
             max_query_depth,
@@ -407,6 +430,7 @@ impl EngineConfigBuilder {
             strict_argument_checking,
             vesting_schedule_period_millis,
             max_delegators_per_validator,
+            start_protocol_version_with_get_era_id,
         }
     }
 }
@@ -148,6 +148,11 @@ impl EngineState<LmdbGlobalState> {
         Ok(())
     }
 
+    /// Flushes the LMDB environment to disk, regardless of whether manual sync is enabled.
+    pub fn sync_environment(&self) -> Result<(), lmdb::Error> {
+        self.state.environment.sync()
+    }
+
     /// Provide a local cached-only version of engine-state.
     pub fn get_scratch_engine_state(&self) -> EngineState<ScratchGlobalState> {
         EngineState {
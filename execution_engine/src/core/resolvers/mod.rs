@@ -21,8 +21,12 @@ pub(crate) fn create_module_resolver(
 ) -> Result<impl ModuleImportResolver + MemoryResolver, ResolverError> {
     // TODO: revisit how protocol_version check here is meant to combine with upgrade
     if protocol_version >= ProtocolVersion::V1_0_0 {
+        let enable_get_era_id = engine_config
+            .start_protocol_version_with_get_era_id()
+            .map_or(false, |start_version| protocol_version >= start_version);
         return Ok(v1_resolver::RuntimeModuleImportResolver::new(
             engine_config.wasm_config().max_memory,
+            enable_get_era_id,
         ));
     }
     Err(ResolverError::UnknownProtocolVersion(protocol_version))
@@ -31,8 +35,10 @@ pub(crate) fn create_module_resolver(
 #[cfg(test)]
 mod tests {
     use casper_types::ProtocolVersion;
+    use casper_wasmi::{Signature, ValueType};
 
     use super::*;
+    use crate::core::engine_state::EngineConfigBuilder;
 
     #[test]
     fn resolve_invalid_module() {
@@ -45,4 +51,37 @@ mod tests {
     fn protocol_version_1_always_resolves() {
         assert!(create_module_resolver(ProtocolVersion::V1_0_0, &EngineConfig::default()).is_ok());
     }
+
+    #[test]
+    fn get_era_id_resolves_only_from_activation_version() {
+        let signature = Signature::new(&[ValueType::I32; 1][..], None);
+        let engine_config = EngineConfigBuilder::new()
+            .with_start_protocol_version_with_get_era_id(Some(ProtocolVersion::from_parts(1, 6, 0)))
+            .build();
+
+        let resolver =
+            create_module_resolver(ProtocolVersion::from_parts(1, 5, 0), &engine_config).unwrap();
+        assert!(resolver
+            .resolve_func("casper_get_era_id", &signature)
+            .is_err());
+
+        let resolver =
+            create_module_resolver(ProtocolVersion::from_parts(1, 6, 0), &engine_config).unwrap();
+        assert!(resolver
+            .resolve_func("casper_get_era_id", &signature)
+            .is_ok());
+    }
+
+    #[test]
+    fn get_era_id_does_not_resolve_without_activation_version() {
+        let signature = Signature::new(&[ValueType::I32; 1][..], None);
+        let resolver = create_module_resolver(
+            ProtocolVersion::from_parts(2, 0, 0),
+            &EngineConfig::default(),
+        )
+        .unwrap();
+        assert!(resolver
+            .resolve_func("casper_get_era_id", &signature)
+            .is_err());
+    }
 }
@@ -12,13 +12,16 @@ use super::{
 pub(crate) struct RuntimeModuleImportResolver {
     memory: RefCell<Option<MemoryRef>>,
     max_memory: u32,
+    /// Whether `casper_get_era_id` is exported to Wasm.
+    enable_get_era_id: bool,
 }
 
 impl RuntimeModuleImportResolver {
-    pub(crate) fn new(max_memory: u32) -> Self {
+    pub(crate) fn new(max_memory: u32, enable_get_era_id: bool) -> Self {
         Self {
             memory: RefCell::new(None),
             max_memory,
+            enable_get_era_id,
         }
     }
 }
@@ -116,6 +119,10 @@ impl ModuleImportResolver for RuntimeModuleImportResolver {
                 Signature::new(&[ValueType::I32; 1][..], None),
                 FunctionIndex::GetBlocktimeIndex.into(),
             ),
+            "casper_get_era_id" if self.enable_get_era_id => FuncInstance::alloc_host(
+                Signature::new(&[ValueType::I32; 1][..], None),
+                FunctionIndex::GetEraIdIndex.into(),
+            ),
             "casper_create_purse" => FuncInstance::alloc_host(
                 Signature::new(&[ValueType::I32; 2][..], Some(ValueType::I32)),
                 FunctionIndex::CreatePurseIndex.into(),
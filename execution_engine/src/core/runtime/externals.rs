@@ -185,6 +185,15 @@ where
                 Ok(None)
             }
 
+            FunctionIndex::GetEraIdIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                // TODO: add cost table entry once we can upgrade safely
+                self.charge_host_function_call(&HostFunction::fixed(10_000), [dest_ptr])?;
+                self.get_era_id(dest_ptr)?;
+                Ok(None)
+            }
+
             FunctionIndex::GasFuncIndex => {
                 let (gas_arg,): (u32,) = Args::parse(args)?;
                 // Gas is special cased internal host function and for accounting purposes it isn't
@@ -41,7 +41,7 @@ use casper_types::{
         HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
     AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractHash,
-    ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, Gas,
+    ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, EraId, Gas,
     GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer,
     TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
 };
@@ -427,6 +427,30 @@ where
             .map_err(|e| Error::Interpreter(e.into()).into())
     }
 
+    /// Writes the id of the era in which the current block is executed into the Wasm memory.
+    fn get_era_id(&mut self, dest_ptr: u32) -> Result<(), Trap> {
+        let auction_contract_hash = self.get_auction_contract()?;
+        let auction_contract = self
+            .context
+            .read_gs_typed::<Contract>(&Key::from(auction_contract_hash))?;
+        let era_id_key = auction_contract
+            .named_keys()
+            .get(auction::ERA_ID_KEY)
+            .copied()
+            .ok_or(Error::NamedKeyNotFound(auction::ERA_ID_KEY.to_string()))?;
+        // The era id URef is private to the auction contract, so it's read bypassing the access
+        // checks; only its value is ever exposed to the caller.
+        let era_id: EraId = match self.context.read_gs_direct(&era_id_key)? {
+            Some(StoredValue::CLValue(cl_value)) => cl_value.into_t().map_err(Error::CLValue)?,
+            Some(_) => return Err(Error::UnexpectedStoredValueVariant.into()),
+            None => return Err(Error::KeyNotFound(era_id_key).into()),
+        };
+        let era_id_bytes = era_id.into_bytes().map_err(Error::BytesRepr)?;
+        self.try_get_memory()?
+            .set(dest_ptr, &era_id_bytes)
+            .map_err(|e| Error::Interpreter(e.into()).into())
+    }
+
     /// Load the uref known by the given name into the Wasm memory
     fn load_call_stack(
         &mut self,
@@ -2,7 +2,10 @@ use std::{
     collections::HashMap,
     mem,
     ops::Deref,
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
 };
 
 use tracing::error;
@@ -32,12 +35,18 @@ type SharedCache = Arc<RwLock<Cache>>;
 
 struct Cache {
     cached_values: HashMap<Key, (bool, StoredValue)>,
+    /// Number of reads served from the cache.
+    hits: AtomicU64,
+    /// Number of reads which had to be served from the trie store.
+    misses: AtomicU64,
 }
 
 impl Cache {
     fn new() -> Self {
         Cache {
             cached_values: HashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
@@ -63,6 +72,18 @@ impl Cache {
     }
 }
 
+/// Statistics of the reads and writes made via a [`ScratchGlobalState`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScratchGlobalStateStats {
+    /// Number of reads served from the in-memory cache.
+    pub cache_hits: u64,
+    /// Number of reads which had to be served from the trie store.
+    pub trie_reads: u64,
+    /// Number of distinct keys written to the in-memory cache, each of which is written to the
+    /// trie store once on committing.  Repeated writes to the same key are only counted once.
+    pub written_keys: u64,
+}
+
 /// Global state implemented against LMDB as a backing data store.
 pub struct ScratchGlobalState {
     /// Underlying, cached stored values.
@@ -103,6 +124,20 @@ impl ScratchGlobalState {
         }
     }
 
+    /// Returns statistics of the reads and writes made so far.
+    pub fn stats(&self) -> ScratchGlobalStateStats {
+        let cache = self.cache.read().unwrap();
+        ScratchGlobalStateStats {
+            cache_hits: cache.hits.load(Ordering::Relaxed),
+            trie_reads: cache.misses.load(Ordering::Relaxed),
+            written_keys: cache
+                .cached_values
+                .values()
+                .filter(|(dirty, _)| *dirty)
+                .count() as u64,
+        }
+    }
+
     /// Consume self and return inner cache.
     pub fn into_inner(self) -> HashMap<Key, StoredValue> {
         let cache = mem::replace(&mut *self.cache.write().unwrap(), Cache::new());
@@ -118,8 +153,13 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
-        if let Some(value) = self.cache.read().unwrap().get(key) {
-            return Ok(Some(value.clone()));
+        {
+            let cache = self.cache.read().unwrap();
+            if let Some(value) = cache.get(key) {
+                cache.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(Some(value.clone()));
+            }
+            cache.misses.fetch_add(1, Ordering::Relaxed);
         }
         let txn = self.environment.create_read_txn()?;
         let ret = match read::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
@@ -208,6 +248,11 @@ impl CommitProvider for ScratchGlobalState {
                 (None, transform) => {
                     // It might be the case that for `Add*` operations we don't have the previous
                     // value in cache yet.
+                    self.cache
+                        .read()
+                        .unwrap()
+                        .misses
+                        .fetch_add(1, Ordering::Relaxed);
                     let txn = self.environment.create_read_txn()?;
                     let updated_value = match read::<
                         Key,
@@ -636,4 +681,34 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn stats_count_cache_hits_and_trie_reads_and_written_keys() {
+        let correlation_id = CorrelationId::new();
+        let TestState { state, root_hash } = create_test_state();
+        let scratch = state.create_scratch();
+        assert_eq!(scratch.stats(), ScratchGlobalStateStats::default());
+
+        // The first read of a key goes to the trie store, subsequent ones are served from cache.
+        let checkout = scratch.checkout(root_hash).unwrap().unwrap();
+        let key = create_test_pairs()[0].key;
+        assert!(checkout.read(correlation_id, &key).unwrap().is_some());
+        assert!(checkout.read(correlation_id, &key).unwrap().is_some());
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &create_test_pairs_updated() {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        scratch.commit(correlation_id, root_hash, effects).unwrap();
+
+        let expected_stats = ScratchGlobalStateStats {
+            cache_hits: 1,
+            trie_reads: 1,
+            written_keys: 3,
+        };
+        assert_eq!(scratch.stats(), expected_stats);
+    }
 }
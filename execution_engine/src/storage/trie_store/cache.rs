@@ -0,0 +1,262 @@
+//! A byte-bounded least-recently-used cache of serialized tries.
+//!
+//! Tries are content-addressed and never removed from the underlying store, so a cached entry is
+//! always valid for its digest and the cache never needs to be invalidated.
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use casper_hashing::Digest;
+use casper_types::bytesrepr::Bytes;
+
+use crate::storage::error;
+
+/// Number of independently locked shards a [`TrieCache`] is split into, so that concurrent reads
+/// of different tries rarely contend for the same lock.
+const SHARD_COUNT: usize = 16;
+
+/// Counters describing the activity of a [`TrieCache`] since the last call to
+/// [`TrieCache::take_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieCacheStats {
+    /// Number of reads served from the cache.
+    pub hits: u64,
+    /// Number of reads which had to go to the underlying store.
+    pub misses: u64,
+    /// Number of entries evicted to stay within the byte budget.
+    pub evictions: u64,
+    /// Total size in bytes of the tries currently held in the cache.
+    pub size_bytes: usize,
+}
+
+/// A least-recently-used cache of the tries whose digests fall into one shard.
+#[derive(Debug, Default)]
+struct Shard {
+    /// Cached tries along with the tick at which they were last used.
+    entries: HashMap<Digest, (Arc<Bytes>, u64)>,
+    /// Last-use ticks mapped to digests; the first entry is the least recently used.
+    recency: BTreeMap<u64, Digest>,
+    next_tick: u64,
+    size_bytes: usize,
+    stats: TrieCacheStats,
+}
+
+impl Shard {
+    fn touch(&mut self, digest: Digest, old_tick: u64) -> u64 {
+        self.recency.remove(&old_tick);
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.recency.insert(tick, digest);
+        tick
+    }
+}
+
+/// A least-recently-used cache of serialized tries, bounded by the total size of the cached
+/// values.
+///
+/// The cache is split into shards by digest, each holding an equal share of the byte budget and
+/// evicting independently.  Cached tries are shared rather than copied on each read.
+#[derive(Debug)]
+pub struct TrieCache {
+    max_shard_size_bytes: usize,
+    shards: Vec<Mutex<Shard>>,
+}
+
+impl TrieCache {
+    /// Creates a new, empty cache holding at most `max_size_bytes` bytes of tries.
+    pub fn new(max_size_bytes: usize) -> Self {
+        Self::with_shard_count(max_size_bytes, SHARD_COUNT)
+    }
+
+    fn with_shard_count(max_size_bytes: usize, shard_count: usize) -> Self {
+        TrieCache {
+            max_shard_size_bytes: max_size_bytes / shard_count,
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(Shard::default()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, digest: &Digest) -> &Mutex<Shard> {
+        let index = digest.as_ref()[0] as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    /// Returns the cached trie bytes for `digest`, marking the entry as most recently used.
+    pub fn get(&self, digest: &Digest) -> Result<Option<Arc<Bytes>>, error::Error> {
+        let mut shard = self.shard(digest).lock()?;
+        let old_tick = match shard.entries.get(digest) {
+            Some((_, tick)) => *tick,
+            None => {
+                shard.stats.misses += 1;
+                return Ok(None);
+            }
+        };
+        shard.stats.hits += 1;
+        let tick = shard.touch(*digest, old_tick);
+        let entry = shard
+            .entries
+            .get_mut(digest)
+            .expect("entry should exist as it was just looked up");
+        entry.1 = tick;
+        Ok(Some(Arc::clone(&entry.0)))
+    }
+
+    /// Inserts trie bytes for `digest`, evicting least recently used entries of its shard until
+    /// the shard fits within its share of the byte budget.  Values larger than that share are not
+    /// cached.
+    pub fn insert(&self, digest: Digest, trie_bytes: Arc<Bytes>) -> Result<(), error::Error> {
+        let len = trie_bytes.len();
+        if len > self.max_shard_size_bytes {
+            return Ok(());
+        }
+        let mut shard = self.shard(&digest).lock()?;
+        if let Some((_, old_tick)) = shard.entries.get(&digest) {
+            let old_tick = *old_tick;
+            let tick = shard.touch(digest, old_tick);
+            if let Some(entry) = shard.entries.get_mut(&digest) {
+                entry.1 = tick;
+            }
+            return Ok(());
+        }
+        while shard.size_bytes + len > self.max_shard_size_bytes {
+            let (oldest_tick, oldest_digest) = match shard.recency.iter().next() {
+                Some((tick, digest)) => (*tick, *digest),
+                None => break,
+            };
+            shard.recency.remove(&oldest_tick);
+            if let Some((evicted, _)) = shard.entries.remove(&oldest_digest) {
+                shard.size_bytes -= evicted.len();
+                shard.stats.evictions += 1;
+            }
+        }
+        let tick = shard.touch(digest, u64::MAX);
+        shard.entries.insert(digest, (trie_bytes, tick));
+        shard.size_bytes += len;
+        Ok(())
+    }
+
+    /// Returns the cached trie bytes for `digest`, falling back to `read` on a miss and caching
+    /// whatever it finds.
+    pub(crate) fn get_or_read<E, F>(
+        &self,
+        digest: &Digest,
+        read: F,
+    ) -> Result<Option<Arc<Bytes>>, E>
+    where
+        E: From<error::Error>,
+        F: FnOnce() -> Result<Option<Bytes>, E>,
+    {
+        if let Some(trie_bytes) = self.get(digest)? {
+            return Ok(Some(trie_bytes));
+        }
+        match read()? {
+            Some(trie_bytes) => {
+                let trie_bytes = Arc::new(trie_bytes);
+                self.insert(*digest, Arc::clone(&trie_bytes))?;
+                Ok(Some(trie_bytes))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the counters accumulated since the previous call and resets them.  The returned
+    /// `size_bytes` is the current size of the cache.
+    pub fn take_stats(&self) -> Result<TrieCacheStats, error::Error> {
+        let mut total = TrieCacheStats::default();
+        for shard in &self.shards {
+            let mut shard = shard.lock()?;
+            let stats = std::mem::take(&mut shard.stats);
+            total.hits += stats.hits;
+            total.misses += stats.misses;
+            total.evictions += stats.evictions;
+            total.size_bytes += shard.size_bytes;
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(n: u8) -> Digest {
+        Digest::hash([n])
+    }
+
+    fn bytes(len: usize) -> Arc<Bytes> {
+        Arc::new(Bytes::from(vec![0u8; len]))
+    }
+
+    #[test]
+    fn should_evict_least_recently_used_entries() {
+        let cache = TrieCache::with_shard_count(30, 1);
+        cache.insert(digest(1), bytes(10)).unwrap();
+        cache.insert(digest(2), bytes(10)).unwrap();
+        cache.insert(digest(3), bytes(10)).unwrap();
+
+        // Using the first entry makes the second one the least recently used.
+        assert!(cache.get(&digest(1)).unwrap().is_some());
+        cache.insert(digest(4), bytes(10)).unwrap();
+
+        assert!(cache.get(&digest(2)).unwrap().is_none());
+        assert!(cache.get(&digest(1)).unwrap().is_some());
+        assert!(cache.get(&digest(3)).unwrap().is_some());
+        assert!(cache.get(&digest(4)).unwrap().is_some());
+
+        let stats = cache.take_stats().unwrap();
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.size_bytes, 30);
+        assert_eq!(cache.take_stats().unwrap().hits, 0);
+    }
+
+    #[test]
+    fn should_not_cache_values_larger_than_budget() {
+        let cache = TrieCache::with_shard_count(10, 1);
+        cache.insert(digest(1), bytes(5)).unwrap();
+        cache.insert(digest(2), bytes(11)).unwrap();
+        assert!(cache.get(&digest(1)).unwrap().is_some());
+        assert!(cache.get(&digest(2)).unwrap().is_none());
+        assert_eq!(cache.take_stats().unwrap().size_bytes, 5);
+    }
+
+    #[test]
+    fn should_share_cached_values() {
+        let cache = TrieCache::with_shard_count(10, 1);
+        let trie_bytes = bytes(5);
+        cache.insert(digest(1), Arc::clone(&trie_bytes)).unwrap();
+        let cached = cache.get(&digest(1)).unwrap().expect("should be cached");
+        assert!(Arc::ptr_eq(&cached, &trie_bytes));
+    }
+
+    #[test]
+    fn should_evict_within_a_shard_only() {
+        let cache = TrieCache::with_shard_count(20, 2);
+        // Find digests which fall into the same and into the other shard.
+        let first = digest(0);
+        let first_shard = first.as_ref()[0] % 2;
+        let same_shard = (1..)
+            .map(digest)
+            .find(|digest| digest.as_ref()[0] % 2 == first_shard)
+            .unwrap();
+        let other_shard = (1..)
+            .map(digest)
+            .find(|digest| digest.as_ref()[0] % 2 != first_shard)
+            .unwrap();
+
+        cache.insert(first, bytes(10)).unwrap();
+        cache.insert(other_shard, bytes(10)).unwrap();
+        assert_eq!(cache.take_stats().unwrap().size_bytes, 20);
+
+        // The first shard is full, so inserting into it evicts its only entry, but leaves the
+        // other shard's entry in place.
+        cache.insert(same_shard, bytes(10)).unwrap();
+        assert!(cache.get(&first).unwrap().is_none());
+        assert!(cache.get(&other_shard).unwrap().is_some());
+        assert!(cache.get(&same_shard).unwrap().is_some());
+        assert_eq!(cache.take_stats().unwrap().evictions, 1);
+    }
+}
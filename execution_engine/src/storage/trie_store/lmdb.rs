@@ -123,15 +123,20 @@ use crate::storage::{
     store::Store,
     transaction_source::{lmdb::LmdbEnvironment, Readable, TransactionSource, Writable},
     trie::{self, LazyTrieLeaf, Trie},
-    trie_store::{self, TrieStore},
+    trie_store::{
+        self,
+        cache::{TrieCache, TrieCacheStats},
+        TrieStore,
+    },
 };
 
 /// An LMDB-backed trie store.
 ///
-/// Wraps [`lmdb::Database`].
+/// Wraps [`lmdb::Database`], optionally fronted by a [`TrieCache`] of recently read tries.
 #[derive(Debug, Clone)]
 pub struct LmdbTrieStore {
     db: Database,
+    cache: Option<Arc<TrieCache>>,
 }
 
 impl LmdbTrieStore {
@@ -143,14 +148,27 @@ impl LmdbTrieStore {
     ) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().create_db(Some(&name), flags)?;
-        Ok(LmdbTrieStore { db })
+        Ok(LmdbTrieStore { db, cache: None })
+    }
+
+    /// Constructor for new `LmdbTrieStore` which caches up to `cache_size_bytes` bytes of
+    /// recently read tries in memory.
+    pub fn new_with_cache(
+        env: &LmdbEnvironment,
+        maybe_name: Option<&str>,
+        flags: DatabaseFlags,
+        cache_size_bytes: usize,
+    ) -> Result<Self, error::Error> {
+        let mut store = Self::new(env, maybe_name, flags)?;
+        store.cache = Some(Arc::new(TrieCache::new(cache_size_bytes)));
+        Ok(store)
     }
 
     /// Constructor for `LmdbTrieStore` which opens an existing lmdb store file.
     pub fn open(env: &LmdbEnvironment, maybe_name: Option<&str>) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().open_db(Some(&name))?;
-        Ok(LmdbTrieStore { db })
+        Ok(LmdbTrieStore { db, cache: None })
     }
 
     fn name(maybe_name: Option<&str>) -> String {
@@ -163,6 +181,15 @@ impl LmdbTrieStore {
     pub fn get_db(&self) -> Database {
         self.db
     }
+
+    /// Returns the trie cache counters accumulated since the previous call, or `None` if the store
+    /// has no cache.
+    pub fn take_cache_stats(&self) -> Result<Option<TrieCacheStats>, error::Error> {
+        self.cache
+            .as_ref()
+            .map(|cache| cache.take_stats())
+            .transpose()
+    }
 }
 
 impl<K, V> Store<Digest, Trie<K, V>> for LmdbTrieStore {
@@ -173,6 +200,48 @@ impl<K, V> Store<Digest, Trie<K, V>> for LmdbTrieStore {
     fn handle(&self) -> Self::Handle {
         self.db
     }
+
+    fn get<T>(&self, txn: &T, key: &Digest) -> Result<Option<Trie<K, V>>, Self::Error>
+    where
+        T: Readable<Handle = Self::Handle>,
+        Digest: AsRef<[u8]>,
+        Trie<K, V>: bytesrepr::FromBytes,
+        Self::Error: From<T::Error>,
+    {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => {
+                return match txn.read(self.db, key.as_ref())? {
+                    Some(trie_bytes) => Ok(Some(bytesrepr::deserialize_from_slice(trie_bytes)?)),
+                    None => Ok(None),
+                }
+            }
+        };
+        // Deserialize straight from the shared cached bytes rather than copying them.
+        let read = || -> Result<_, Self::Error> { Ok(txn.read(self.db, key.as_ref())?) };
+        match cache.get_or_read(key, read)? {
+            Some(trie_bytes) => Ok(Some(bytesrepr::deserialize_from_slice(
+                trie_bytes.as_slice(),
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_raw<T>(&self, txn: &T, key: &Digest) -> Result<Option<Bytes>, Self::Error>
+    where
+        T: Readable<Handle = Self::Handle>,
+        Digest: AsRef<[u8]>,
+        Self::Error: From<T::Error>,
+    {
+        let read = || -> Result<_, Self::Error> { Ok(txn.read(self.db, key.as_ref())?) };
+        match &self.cache {
+            // Callers take ownership of raw bytes, so a cached trie has to be copied out here.
+            Some(cache) => Ok(cache
+                .get_or_read(key, read)?
+                .map(|trie_bytes| Bytes::clone(&trie_bytes))),
+            None => read(),
+        }
+    }
 }
 
 impl<K, V> TrieStore<K, V> for LmdbTrieStore {}
@@ -274,8 +343,15 @@ impl Store<Digest, Trie<Key, StoredValue>> for ScratchTrieStore {
         match maybe_trie {
             Some((_, trie_bytes)) => Ok(Some(trie_bytes.clone())),
             None => {
-                let handle = self.handle();
-                match txn.read(handle, key.as_ref())? {
+                let read =
+                    || -> Result<_, Self::Error> { Ok(txn.read(self.handle(), key.as_ref())?) };
+                let maybe_trie_bytes = match self.store.cache.as_ref() {
+                    Some(trie_cache) => trie_cache
+                        .get_or_read(key, read)?
+                        .map(|trie_bytes| Bytes::clone(&trie_bytes)),
+                    None => read()?,
+                };
+                match maybe_trie_bytes {
                     Some(trie_bytes) => {
                         match store.entry(*key) {
                             Entry::Occupied(_) => {}
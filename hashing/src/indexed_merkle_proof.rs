@@ -58,10 +58,8 @@ impl FromBytes for IndexedMerkleProof {
 }
 
 impl IndexedMerkleProof {
-    pub(crate) fn new<I>(
-        leaves: I,
-        index: u64,
-    ) -> Result<IndexedMerkleProof, MerkleConstructionError>
+    /// Constructs a Merkle proof of the leaf at `index` in a Merkle tree built from `leaves`.
+    pub fn new<I>(leaves: I, index: u64) -> Result<IndexedMerkleProof, MerkleConstructionError>
     where
         I: IntoIterator<Item = Digest>,
         I::IntoIter: ExactSizeIterator,
@@ -196,7 +194,11 @@ impl IndexedMerkleProof {
         l
     }
 
-    pub(crate) fn verify(&self) -> Result<(), MerkleVerificationError> {
+    /// Verifies that the index is in bounds and that the proof has the expected length.
+    ///
+    /// Note that this does not check the proof against any particular root hash; callers should
+    /// compare [`IndexedMerkleProof::root_hash`] against the expected value.
+    pub fn verify(&self) -> Result<(), MerkleVerificationError> {
         if self.index >= self.count {
             return Err(MerkleVerificationError::IndexOutOfBounds {
                 count: self.count,
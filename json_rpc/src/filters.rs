@@ -7,7 +7,9 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::{stream, StreamExt};
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
 use warp::{
@@ -27,6 +29,8 @@ use crate::{
 };
 
 const CONTENT_TYPE_VALUE: &str = "application/json";
+/// The maximum number of requests from a single batch which are executed concurrently.
+const MAX_CONCURRENT_BATCH_REQUESTS: usize = 10;
 
 /// Returns a boxed warp filter which handles the initial setup.
 ///
@@ -66,12 +70,118 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
-/// handler, and providing a JSON-RPC response (which could be a success or failure).
+/// The body of a JSON-RPC reply: either a single response, or an array of responses to a batch
+/// request.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ResponseBody {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+/// Validates and executes a single JSON-RPC request object.
+///
+/// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
+/// specification, i.e. the request doesn't contain an "id" field.
+async fn handle_request(
+    unvalidated_request: Map<String, Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Result<Response, Rejection> {
+    match Request::new(unvalidated_request, allow_unknown_fields) {
+        Ok(request) => Ok(handlers.handle_request(request).await),
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request");
+            Ok(Response::new_failure(id, error))
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            debug!(?rejection, "rejecting an invalid request");
+            Err(rejection)
+        }
+    }
+}
+
+/// Handles a JSON-RPC batch request, i.e. an array of request objects.
+///
+/// Up to [`MAX_CONCURRENT_BATCH_REQUESTS`] of the requests are executed concurrently and the
+/// responses returned in the order of the requests, omitting those for Notifications.  If the batch
+/// is empty or contains more than `max_batch_size` elements, a single invalid request error is
+/// returned.  If the batch consists only of Notifications, the last rejection is returned, as for a
+/// single Notification.
+async fn handle_batch(
+    batch: Vec<Value>,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> Result<ResponseBody, Rejection> {
+    if batch.is_empty() {
+        debug!("got an empty batch request");
+        let error = Error::new(ReservedErrorCode::InvalidRequest, "Empty batch");
+        return Ok(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    let batch_size = batch.len();
+    if batch_size > max_batch_size {
+        debug!(batch_size, max_batch_size, "got an oversized batch request");
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            format!(
+                "Batch of {} requests exceeds the limit of {}",
+                batch_size, max_batch_size
+            ),
+        );
+        return Ok(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    let results: Vec<_> = stream::iter(batch.into_iter().map(|value| {
+        let handlers = &handlers;
+        async move {
+            match value {
+                Value::Object(unvalidated_request) => {
+                    handle_request(unvalidated_request, handlers, allow_unknown_fields).await
+                }
+                _ => {
+                    debug!("got a batch element which is not a request object");
+                    let error = Error::new(
+                        ReservedErrorCode::InvalidRequest,
+                        "Batch element is not an object",
+                    );
+                    Ok(Response::new_failure(Value::Null, error))
+                }
+            }
+        }
+    }))
+    .buffered(MAX_CONCURRENT_BATCH_REQUESTS)
+    .collect()
+    .await;
+
+    let mut responses = Vec::with_capacity(results.len());
+    let mut maybe_rejection = None;
+    for result in results {
+        match result {
+            Ok(response) => responses.push(response),
+            Err(rejection) => maybe_rejection = Some(rejection),
+        }
+    }
+    match maybe_rejection {
+        Some(rejection) if responses.is_empty() => Err(rejection),
+        _ => Ok(ResponseBody::Batch(responses)),
+    }
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests from the given HTTP body, executing it
+/// using the appropriate handler, and providing a JSON-RPC response (which could be a success or
+/// failure).
 ///
 /// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
-/// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
-/// response is sent to the client.
+/// specification, i.e. the request doesn't contain an "id" field, or if a batch request consists
+/// only of Notifications.  In this case, no JSON-RPC response is sent to the client.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
@@ -79,32 +189,44 @@ async fn handle_body(
     body: Bytes,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
-    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
-        Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
-            Ok(request) => handlers.handle_request(request).await,
-            Err(ErrorOrRejection::Error { id, error }) => {
-                debug!(?error, "got an invalid request");
-                Response::new_failure(id, error)
+    max_batch_size: usize,
+) -> Result<ResponseBody, Rejection> {
+    let is_batch = body
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .map_or(false, |byte| *byte == b'[');
+    if is_batch {
+        return match serde_json::from_slice::<Vec<Value>>(&body) {
+            Ok(batch) => handle_batch(batch, handlers, allow_unknown_fields, max_batch_size).await,
+            Err(error) => {
+                debug!(%error, "got bad json");
+                let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
+                Ok(ResponseBody::Single(Response::new_failure(
+                    Value::Null,
+                    error,
+                )))
             }
-            Err(ErrorOrRejection::Rejection(rejection)) => {
-                debug!(?rejection, "rejecting an invalid request");
-                return Err(rejection);
-            }
-        },
+        };
+    }
+
+    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
+        Ok(unvalidated_request) => {
+            handle_request(unvalidated_request, &handlers, allow_unknown_fields).await?
+        }
         Err(error) => {
             debug!(%error, "got bad json");
             let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
             Response::new_failure(Value::Null, error)
         }
     };
-    Ok(response)
+    Ok(ResponseBody::Single(response))
 }
 
-/// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
-/// executing it using the appropriate handler, and providing a reply.
+/// Returns a boxed warp filter which handles parsing a JSON-RPC request or batch of requests from
+/// the given HTTP body, executing it using the appropriate handler, and providing a reply.
 ///
-/// The reply will normally be built from a JSON-RPC response (which could be a success or failure).
+/// The reply will normally be built from a JSON-RPC response (which could be a success or failure),
+/// or from an array of such responses for a batch request.
 ///
 /// However, the reply could be built from a [`Rejection`] if the request is a Notification as per
 /// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
@@ -112,14 +234,18 @@ async fn handle_body(
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
+///
+/// Batch requests with more than `max_batch_size` elements are answered with a single invalid
+/// request error without any of their elements being executed.
 pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    max_batch_size: usize,
 ) -> BoxedFilter<(WithStatus<reply::Json>,)> {
     body::bytes()
         .and_then(move |body| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(body, handlers, allow_unknown_fields, max_batch_size).await }
         })
         .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
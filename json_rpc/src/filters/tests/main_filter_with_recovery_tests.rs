@@ -16,6 +16,7 @@ use crate::{
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const MAX_BATCH_SIZE: usize = 4;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -55,7 +56,7 @@ fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
     let handlers = handlers.build();
 
-    main_filter(handlers, false)
+    main_filter(handlers, false, MAX_BATCH_SIZE)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +319,105 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // The notification (no "id") gets no response, while the non-object element gets an invalid
+    // request error.  The remaining responses are returned in the order of the requests.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","method":"get good thing","params":["two"]},
+                1,
+                {"jsonrpc":"2.0","id":"b","method":"get good thing"}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let body_bytes = hyper::body::to_bytes(http_response.into_body())
+        .await
+        .unwrap();
+    let rpc_responses: Vec<Response> = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(rpc_responses.len(), 3);
+
+    assert_eq!(rpc_responses[0].id(), "a");
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+
+    assert_eq!(rpc_responses[1].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch element is not an object"
+        )
+    );
+
+    assert_eq!(rpc_responses[2].id(), "b");
+    assert_eq!(
+        rpc_responses[2].error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidParams, "no params")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_empty_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    let http_response = warp::test::request()
+        .body("[]")
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidRequest, "Empty batch")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_oversized_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    let request = r#"{"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]}"#;
+    let batch = format!("[{}]", vec![request; MAX_BATCH_SIZE + 1].join(","));
+    let http_response = warp::test::request()
+        .body(batch)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch of 5 requests exceeds the limit of 4"
+        )
+    );
+}
@@ -10,14 +10,14 @@ use std::{
 
 use backtrace::Backtrace;
 use structopt::StructOpt;
-use tokio::runtime::Builder;
 use tracing::info;
 
-use casper_node::{cli::Cli, MAX_THREAD_COUNT};
+use casper_node::{cli::Cli, in_supervised_dispatch, runtime};
 
 /// Aborting panic hook.
 ///
-/// Will exit the application using `abort` when an error occurs. Always shows a backtrace.
+/// Will exit the application using `abort` when an error occurs, unless the panic occurred in a
+/// supervised component. Always shows a backtrace.
 fn panic_hook(info: &PanicInfo) {
     let backtrace = Backtrace::new();
 
@@ -33,6 +33,11 @@ fn panic_hook(info: &PanicInfo) {
         eprintln!("{}", info);
     }
 
+    // Panics in supervised components are caught by the reactor, which restarts the component.
+    if in_supervised_dispatch() {
+        return;
+    }
+
     // Abort after a panic, even if only a worker thread panicked.
     process::abort()
 }
@@ -42,18 +47,11 @@ fn main() -> anyhow::Result<()> {
     // The exit code is determined in a block to ensure that all acquired resources are dropped
     // before exiting with the given exit code.
     let exit_code = {
-        let num_cpus = num_cpus::get();
-        let runtime = Builder::new_multi_thread()
-            .enable_all()
-            .worker_threads(num_cpus)
-            .max_blocking_threads(MAX_THREAD_COUNT - num_cpus)
-            .build()
-            .unwrap();
-
         panic::set_hook(Box::new(panic_hook));
 
-        // Parse CLI args and run selected subcommand.
+        // Parse CLI args, then build the runtime as configured and run selected subcommand.
         let opts = Cli::from_args();
+        let runtime = runtime::build(&opts.runtime_config()?)?;
 
         runtime.block_on(async { opts.run().await })?
     };
@@ -3,10 +3,16 @@
 //! Most configuration is done via config files (see [`config`](../config/index.html) for details).
 
 pub mod arglang;
+pub mod db;
+mod prune;
+mod replay_era;
+pub mod state_snapshot;
+mod status;
+mod validate_config;
 
 use std::{
     alloc::System,
-    fs,
+    env, fs,
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -18,17 +24,26 @@ use regex::Regex;
 use stats_alloc::{StatsAlloc, INSTRUMENTED_SYSTEM};
 use structopt::StructOpt;
 use toml::{value::Table, Value};
-use tracing::info;
+use tracing::{error, info};
 
+use casper_types::EraId;
+
+use self::{db::DbCommand, state_snapshot::StateCommand};
 use crate::{
     components::network::Identity as NetworkIdentity,
     logging,
-    reactor::{main_reactor, Runner},
+    reactor::{main_reactor, EventTrace, Runner},
+    runtime::{self, Config as RuntimeConfig},
     setup_signal_hooks,
     types::{Chainspec, ChainspecRawBytes, ExitCode},
     utils::{Loadable, WithDir},
 };
 
+/// The prefix of environment variables overriding config values.
+const ENV_OVERRIDE_PREFIX: &str = "CASPER_";
+/// The separator between section and key names in environment variables overriding config values.
+const ENV_OVERRIDE_SEPARATOR: &str = "__";
+
 // We override the standard allocator to gather metrics and tune the allocator via the MALLOC_CONF
 // env var.
 #[global_allocator]
@@ -59,6 +74,16 @@ pub enum Cli {
         /// Overrides and extensions for configuration file entries in the form
         /// <SECTION>.<KEY>=<VALUE>.  For example, '-C=node.chainspec_config_path=chainspec.toml'
         config_ext: Vec<ConfigExt>,
+
+        #[structopt(long, conflicts_with = "compare-events")]
+        /// Traces every dispatched event, along with the seed of the node's RNG, to the given
+        /// file. Intended for debugging only, as the file grows quickly.
+        trace_events: Option<PathBuf>,
+
+        #[structopt(long)]
+        /// Reruns the node using the RNG seed of a file written by '--trace-events', reporting
+        /// the first dispatched event diverging from the traced ones.
+        compare_events: Option<PathBuf>,
     },
     /// Migrate modified values from the old config as required after an upgrade.
     MigrateConfig {
@@ -78,6 +103,56 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Inspect, export or import the block store of a node which is not running.
+    Db {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// The operation on the block store.
+        #[structopt(subcommand)]
+        command: DbCommand,
+    },
+    /// Export or import the global state of a node which is not running.
+    State {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// The operation on the global state.
+        #[structopt(subcommand)]
+        command: StateCommand,
+    },
+    /// Remove block bodies, deploys and global state older than the given number of eras from a
+    /// node which is not running.
+    Prune {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Number of most recent eras to keep.
+        #[structopt(long)]
+        keep_eras: u64,
+    },
+    /// Rebuild an era's Highway protocol state from the units persisted by a node with
+    /// `consensus.highway.persist_units` enabled, and print the blocks in the order in which they
+    /// are finalized.
+    ReplayEra {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// The era to replay.
+        #[structopt(long)]
+        era: u64,
+    },
+    /// Check a config file for problems which would prevent the node from starting, without
+    /// running the node.
+    ValidateConfig {
+        /// Path to configuration file.
+        config: PathBuf,
+    },
+    /// Print the status of a running node, queried via its REST server.
+    Status {
+        /// Address of the node's REST server.
+        #[structopt(long, default_value = "127.0.0.1:8888")]
+        address: String,
+        /// Print the full status as JSON rather than a summary.
+        #[structopt(long)]
+        json: bool,
+    },
 }
 
 #[derive(Debug)]
@@ -142,20 +217,55 @@ impl FromStr for ConfigExt {
 }
 
 impl Cli {
+    /// Returns the thread and runtime config to run the selected command with.
+    ///
+    /// Only the standard mode reads it from the config file; other commands use the defaults.
+    pub fn runtime_config(&self) -> anyhow::Result<RuntimeConfig> {
+        match self {
+            Cli::Standard {
+                config, config_ext, ..
+            } => Ok(Self::load(config, config_ext)?.value().runtime.clone()),
+            _ => Ok(RuntimeConfig::default()),
+        }
+    }
+
     /// Executes selected CLI command.
     pub async fn run(self) -> anyhow::Result<i32> {
         match self {
-            Cli::Standard { config, config_ext } => {
+            Cli::Standard {
+                config,
+                config_ext,
+                trace_events,
+                compare_events,
+            } => {
                 // Setup UNIX signal hooks.
                 setup_signal_hooks();
 
-                let mut validator_config = Self::init(&config, config_ext)?;
+                let mut validator_config = Self::init(&config, &config_ext)?;
 
                 // We use a `ChaCha20Rng` for the production node. For one, we want to completely
                 // eliminate any chance of runtime failures, regardless of how small (these
                 // exist with `OsRng`). Additionally, we want to limit the number of syscalls for
                 // performance reasons.
-                let mut rng = crate::new_rng();
+                //
+                // When tracing events or comparing against a trace, the RNG is seeded
+                // deterministically instead.
+                let (mut rng, event_trace) = match (trace_events, compare_events) {
+                    (Some(path), _) => {
+                        let seed = rand::random();
+                        let event_trace = EventTrace::record(&path, seed)
+                            .with_context(|| format!("could not create {}", path.display()))?;
+                        info!(path = %path.display(), %seed, "tracing events");
+                        (crate::new_rng_from_seed(seed), Some(event_trace))
+                    }
+                    (None, Some(path)) => {
+                        let (event_trace, seed) = EventTrace::compare(&path)
+                            .with_context(|| format!("could not open {}", path.display()))?;
+                        info!(path = %path.display(), %seed, "comparing events against trace");
+                        (crate::new_rng_from_seed(seed), Some(event_trace))
+                    }
+                    (None, None) => (crate::new_rng(), None),
+                };
 
                 let registry = Registry::new();
 
@@ -167,6 +277,7 @@ impl Cli {
                     build_version = %crate::VERSION_STRING.as_str(),
                     "node starting up"
                 );
+                runtime::log_layout();
 
                 if !chainspec.is_valid() {
                     bail!("invalid chainspec");
@@ -180,7 +291,9 @@ impl Cli {
                 ))
                 .context("failed to create a network identity")?;
 
-                let mut main_runner = Runner::<main_reactor::MainReactor>::with_metrics(
+                let shutdown_timeout = validator_config.value().node.shutdown_timeout;
+
+                let mut main_runner = match Runner::<main_reactor::MainReactor>::with_metrics(
                     validator_config,
                     Arc::new(chainspec),
                     Arc::new(chainspec_raw_bytes),
@@ -188,16 +301,30 @@ impl Cli {
                     &mut rng,
                     &registry,
                 )
-                .await?;
+                .await
+                {
+                    Ok(main_runner) => main_runner,
+                    Err(error) => {
+                        let exit_code = error.exit_code();
+                        error!(%error, ?exit_code, "failed to initialize node");
+                        return Ok(exit_code as i32);
+                    }
+                };
+                if let Some(event_trace) = event_trace {
+                    main_runner.set_event_trace(event_trace);
+                }
 
-                let exit_code = main_runner.run(&mut rng).await;
+                let exit_code = main_runner
+                    .run(&mut rng, || Self::load(&config, &config_ext))
+                    .await;
+                main_runner.finalize(shutdown_timeout.into()).await;
                 Ok(exit_code as i32)
             }
             Cli::MigrateConfig {
                 old_config,
                 new_config,
             } => {
-                let new_config = Self::init(&new_config, vec![])?;
+                let new_config = Self::init(&new_config, &[])?;
 
                 let old_root = old_config
                     .parent()
@@ -219,7 +346,7 @@ impl Cli {
                 old_config,
                 new_config,
             } => {
-                let new_config = Self::init(&new_config, vec![])?;
+                let new_config = Self::init(&new_config, &[])?;
 
                 let old_root = old_config
                     .parent()
@@ -237,13 +364,59 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::Db { config, command } => {
+                let config = Self::load(&config, &[])?;
+                db::run(config, command)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::State { config, command } => {
+                let config = Self::load(&config, &[])?;
+                state_snapshot::run(config, command)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::Prune { config, keep_eras } => {
+                let config = Self::load(&config, &[])?;
+                prune::run(config, keep_eras)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ReplayEra { config, era } => {
+                let config = Self::load(&config, &[])?;
+                replay_era::run(config, EraId::new(era))?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ValidateConfig { config } => {
+                if validate_config::run(&config) {
+                    Ok(ExitCode::Success as i32)
+                } else {
+                    Ok(ExitCode::InvalidConfig as i32)
+                }
+            }
+            Cli::Status { address, json } => {
+                status::run(&address, json).await?;
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
     /// Parses the config file for the current version of casper-node, and initializes logging.
     fn init(
         config: &Path,
-        config_ext: Vec<ConfigExt>,
+        config_ext: &[ConfigExt],
+    ) -> anyhow::Result<WithDir<main_reactor::Config>> {
+        let main_config = Self::load(config, config_ext)?;
+        let mut logging_config = main_config.value().logging.clone();
+        if let Some(file_config) = logging_config.file.as_mut() {
+            file_config.path = main_config.with_dir(file_config.path.clone());
+        }
+        logging::init_with_config(&logging_config)?;
+
+        Ok(main_config)
+    }
+
+    /// Parses the config file for the current version of casper-node.
+    fn load(
+        config: &Path,
+        config_ext: &[ConfigExt],
     ) -> anyhow::Result<WithDir<main_reactor::Config>> {
         // Determine the parent directory of the configuration file, if any.
         // Otherwise, we default to `/`.
@@ -261,15 +434,99 @@ impl Cli {
         // defaulted config instance if one is not provided.
         let mut config_table: Value = toml::from_str(&encoded_config)?;
 
-        // If any command line overrides to the config values are passed, apply them.
+        // Apply any overrides from environment variables, then any from the command line, so that
+        // the latter take precedence.
+        let env_vars = env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        apply_env_overrides(&mut config_table, env_vars)?;
         for item in config_ext {
             item.update_toml_table(&mut config_table)?;
         }
 
         // Create main config, including any overridden values.
         let main_config: main_reactor::Config = config_table.try_into()?;
-        logging::init_with_config(&main_config.logging)?;
 
         Ok(WithDir::new(root, main_config))
     }
 }
+
+/// Applies overrides of config values given as environment variables.
+///
+/// Variables named `CASPER_<SECTION>__<KEY>`, e.g. `CASPER_NETWORK__BIND_ADDRESS`, override the
+/// given key in the given section.  Further `__` separators address keys in nested sections, and
+/// names are case-insensitive.  Values use the same syntax as command line overrides (see
+/// [`arglang`]).  Variables with the prefix but without a separator are ignored, as other
+/// `CASPER_` variables are in use.
+fn apply_env_overrides<I>(toml_value: &mut Value, env_vars: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    for (name, value) in env_vars {
+        let path = match name.strip_prefix(ENV_OVERRIDE_PREFIX) {
+            Some(path) if path.contains(ENV_OVERRIDE_SEPARATOR) => path.to_ascii_lowercase(),
+            _ => continue,
+        };
+        let mut keys: Vec<&str> = path.split(ENV_OVERRIDE_SEPARATOR).collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            bail!("invalid config override environment variable {}", name);
+        }
+        // There are at least two keys, since the path contains a separator.
+        let key = keys.pop().unwrap_or_default();
+
+        let mut table = toml_value
+            .as_table_mut()
+            .context("configuration table is not a table")?;
+        for section in keys {
+            table = table
+                .entry(section)
+                .or_insert_with(|| Value::Table(Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("configuration section {} is not a table", section))?;
+        }
+        let value = arglang::parse(&value)
+            .with_context(|| format!("could not parse environment variable {}", name))?;
+        table.insert(key.to_string(), value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_apply_env_overrides() {
+        let mut config: Value =
+            toml::from_str("[network]\nbind_address = '0.0.0.0:34553'\nknown_addresses = []\n")
+                .unwrap();
+        let env_vars = vec![
+            ("CASPER_NETWORK__BIND_ADDRESS", "127.0.0.1:1"),
+            (
+                "CASPER_NETWORK__KNOWN_ADDRESSES",
+                "[1.2.3.4:34553, 5.6.7.8:34553]",
+            ),
+            ("CASPER_EVENT_STREAM_SERVER__EVENT_LOG__PATH", "events"),
+            ("CASPER_CONFIG_DIR", "ignored"),
+            ("OTHER__VARIABLE", "ignored"),
+        ];
+        apply_env_overrides(
+            &mut config,
+            env_vars
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+        .unwrap();
+
+        let expected: Value = toml::from_str(
+            "[network]\nbind_address = '127.0.0.1:1'\n\
+            known_addresses = ['1.2.3.4:34553', '5.6.7.8:34553']\n\
+            [event_stream_server.event_log]\npath = 'events'\n",
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+
+        let invalid = vec![("CASPER_NETWORK__".to_string(), "1".to_string())];
+        assert!(apply_env_overrides(&mut config, invalid).is_err());
+    }
+}
@@ -0,0 +1,715 @@
+//! Offline inspection, export and import of a node's block store.
+//!
+//! The storage is opened from the data directory given by the node's config file, in the same way
+//! as when running the node, but without a hard reset or forced resync.  The node must not be
+//! running against the same data directory at the same time.
+//!
+//! Exported files contain a bincode-encoded [`ExportHeader`] followed by one [`ExportedBlock`] per
+//! block of the exported height range, in ascending order of height.  Imported blocks are verified
+//! before being stored, but are not marked complete since global state is not exported.
+//!
+//! An import must be anchored either to the stored parent of its first block, or to a trusted hash
+//! of its first block given on the command line.  Every further block must be the child of the
+//! previous one, so the whole range is as trustworthy as the anchor.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+
+use casper_hashing::Digest;
+use casper_types::{crypto, EraId, ExecutionResult, PublicKey, U512};
+use num_rational::Ratio;
+
+use crate::{
+    components::storage::Storage,
+    reactor::main_reactor,
+    types::{
+        ApprovalsHash, ApprovalsHashes, Block, BlockHash, BlockSignatures, Chainspec,
+        ChainspecRawBytes, Deploy, DeployHash, FinalizedApprovals, JsonBlock, JsonBlockHeader,
+    },
+    utils::{self, Loadable, WithDir},
+};
+
+/// A query against the block store.
+#[derive(Debug, StructOpt)]
+pub enum DbCommand {
+    /// Print a block, identified by either its hash or its height.  Prints the highest block if
+    /// neither is given.
+    Block {
+        /// Hex-encoded hash of the block.
+        #[structopt(long, conflicts_with = "height")]
+        hash: Option<String>,
+        /// Height of the block.
+        #[structopt(long)]
+        height: Option<u64>,
+    },
+    /// Print a deploy along with its execution results.
+    Deploy {
+        /// Hex-encoded hash of the deploy.
+        hash: String,
+    },
+    /// Print a summary of an era, taken from its switch block.
+    Era {
+        /// ID of the era.
+        era_id: u64,
+    },
+    /// Export a range of blocks, along with their deploys and finality signatures, to a file.
+    Export {
+        /// Height of the first block to export.
+        #[structopt(long)]
+        from: u64,
+        /// Height of the last block to export.
+        #[structopt(long)]
+        to: u64,
+        /// Path of the file to write.
+        #[structopt(long, short)]
+        output: PathBuf,
+    },
+    /// Import blocks previously exported from a node of the same network, verifying them first.
+    Import {
+        /// Path of the file to read.
+        #[structopt(long, short)]
+        input: PathBuf,
+        /// Hex-encoded hash of the first block to import.  Required unless its parent is already
+        /// stored.
+        #[structopt(long)]
+        trusted_hash: Option<String>,
+    },
+}
+
+/// The header of an export file.
+#[derive(Serialize, Deserialize)]
+struct ExportHeader {
+    network_name: String,
+    from: u64,
+    to: u64,
+}
+
+/// A block along with the data required to verify and store it.
+#[derive(Serialize, Deserialize)]
+struct ExportedBlock {
+    block: Block,
+    deploys: Vec<(Deploy, Option<FinalizedApprovals>)>,
+    approvals_hashes: Option<ApprovalsHashes>,
+    block_signatures: Option<BlockSignatures>,
+}
+
+/// A deploy along with its execution results.
+#[derive(Serialize)]
+struct DeployWithExecutionResults {
+    deploy: Deploy,
+    execution_results: Vec<ExecutionResultInBlock>,
+}
+
+/// The result of executing a deploy in a given block.
+#[derive(Serialize)]
+struct ExecutionResultInBlock {
+    block_hash: BlockHash,
+    result: ExecutionResult,
+}
+
+/// A summary of an era, taken from its switch block.
+#[derive(Serialize)]
+struct EraSummary {
+    era_id: EraId,
+    switch_block_hash: BlockHash,
+    switch_block_header: JsonBlockHeader,
+}
+
+/// Opens the storage configured in `config` and prints the result of `command` as JSON, or runs
+/// the export or import.
+pub(super) fn run(config: WithDir<main_reactor::Config>, command: DbCommand) -> anyhow::Result<()> {
+    let (mut storage, chainspec) = open_storage(config)?;
+    let network_name = chainspec.network_config.name.clone();
+    let output = match command {
+        DbCommand::Block { hash, height } => {
+            let maybe_block = match (hash, height) {
+                (Some(hash), _) => storage.read_block(&BlockHash::new(parse_digest(&hash)?))?,
+                (None, Some(height)) => storage.read_block_by_height(height)?,
+                (None, None) => storage.read_highest_block()?,
+            };
+            let block = maybe_block.context("block not found")?;
+            serde_json::to_string_pretty(&JsonBlock::new(&block, None))?
+        }
+        DbCommand::Deploy { hash } => {
+            let deploy_hash = DeployHash::new(parse_digest(&hash)?);
+            let deploy = storage
+                .read_deploy_by_hash(&deploy_hash)?
+                .context("deploy not found")?;
+            let execution_results = storage
+                .read_deploy_metadata(&deploy_hash)?
+                .map(|metadata| {
+                    metadata
+                        .execution_results
+                        .into_iter()
+                        .map(|(block_hash, result)| ExecutionResultInBlock { block_hash, result })
+                        .collect()
+                })
+                .unwrap_or_default();
+            serde_json::to_string_pretty(&DeployWithExecutionResults {
+                deploy,
+                execution_results,
+            })?
+        }
+        DbCommand::Era { era_id } => {
+            let era_id = EraId::new(era_id);
+            let switch_block = storage
+                .read_switch_block_by_era_id(era_id)?
+                .with_context(|| format!("switch block of era {} not found", era_id))?;
+            serde_json::to_string_pretty(&EraSummary {
+                era_id,
+                switch_block_hash: *switch_block.hash(),
+                switch_block_header: switch_block.header().clone().into(),
+            })?
+        }
+        DbCommand::Export { from, to, output } => {
+            export(&storage, network_name, from, to, &output)?;
+            format!("exported blocks {} to {} to {}", from, to, output.display())
+        }
+        DbCommand::Import {
+            input,
+            trusted_hash,
+        } => {
+            let maybe_trusted_hash = trusted_hash
+                .map(|hash| parse_digest(&hash).map(BlockHash::new))
+                .transpose()?;
+            let (from, to) = import(&mut storage, &chainspec, &input, maybe_trusted_hash)?;
+            format!(
+                "imported blocks {} to {} from {}",
+                from,
+                to,
+                input.display()
+            )
+        }
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+/// Writes the blocks with heights `from` to `to` inclusive to the file at `path`.
+///
+/// The blocks are written to a temporary file first, which is renamed to `path` once complete.
+fn export(
+    storage: &Storage,
+    network_name: String,
+    from: u64,
+    to: u64,
+    path: &Path,
+) -> anyhow::Result<()> {
+    ensure!(from <= to, "invalid height range {} to {}", from, to);
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let result = write_export(storage, network_name, from, to, &temp_path)
+        .and_then(|()| fs::rename(&temp_path, path).map_err(anyhow::Error::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Writes the blocks with heights `from` to `to` inclusive to the file at `path`, syncing it to
+/// disk.
+fn write_export(
+    storage: &Storage,
+    network_name: String,
+    from: u64,
+    to: u64,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("could not create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    let header = ExportHeader {
+        network_name,
+        from,
+        to,
+    };
+    bincode::serialize_into(&mut writer, &header)?;
+    for height in from..=to {
+        let block = storage
+            .read_block_by_height(height)?
+            .with_context(|| format!("block at height {} not found", height))?;
+        let mut deploys = vec![];
+        for deploy_hash in block.deploy_and_transfer_hashes() {
+            let deploy = storage
+                .read_deploy_and_finalized_approvals(deploy_hash)?
+                .with_context(|| format!("deploy {} of block {} not found", deploy_hash, height))?;
+            deploys.push(deploy);
+        }
+        let exported_block = ExportedBlock {
+            approvals_hashes: storage.read_approvals_hashes(block.hash())?,
+            block_signatures: storage.read_block_signatures(block.hash())?,
+            block,
+            deploys,
+        };
+        bincode::serialize_into(&mut writer, &exported_block)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    Ok(())
+}
+
+/// Verifies and stores the blocks in the file at `path`, returning the imported height range.
+///
+/// Blocks must belong to the chainspec's network, have consecutive heights and each be the child of
+/// the previous one.  The first block must either be the child of the block already stored below
+/// the imported range, or have the hash `maybe_trusted_hash`.  Each block is stored atomically,
+/// along with its deploys and signatures.
+fn import(
+    storage: &mut Storage,
+    chainspec: &Chainspec,
+    path: &Path,
+    maybe_trusted_hash: Option<BlockHash>,
+) -> anyhow::Result<(u64, u64)> {
+    let network_name = &chainspec.network_config.name;
+    let file = File::open(path).with_context(|| format!("could not open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let header: ExportHeader =
+        bincode::deserialize_from(&mut reader).context("could not read export header")?;
+    ensure!(
+        header.network_name == *network_name,
+        "blocks were exported from network '{}', not '{}'",
+        header.network_name,
+        network_name
+    );
+    ensure!(
+        header.from <= header.to,
+        "invalid height range {} to {}",
+        header.from,
+        header.to
+    );
+
+    let mut maybe_parent_hash = match header.from.checked_sub(1) {
+        Some(parent_height) => storage
+            .read_block_by_height(parent_height)?
+            .map(|parent| *parent.hash()),
+        None => None,
+    };
+    ensure!(
+        maybe_parent_hash.is_some() || maybe_trusted_hash.is_some(),
+        "the parent of block {} is not stored; a trusted hash of block {} is required",
+        header.from,
+        header.from
+    );
+    let mut validator_weights = EraValidatorWeights::new(chainspec);
+    for height in header.from..=header.to {
+        let exported_block: ExportedBlock = bincode::deserialize_from(&mut reader)
+            .with_context(|| format!("could not read block at height {}", height))?;
+        let block = &exported_block.block;
+        if let (Some(trusted_hash), true) = (maybe_trusted_hash, height == header.from) {
+            ensure!(
+                *block.hash() == trusted_hash,
+                "block {} does not match the trusted hash {}",
+                block.hash(),
+                trusted_hash
+            );
+        }
+        let maybe_weights = validator_weights.get(storage, block.header().era_id())?;
+        verify_exported_block(
+            &exported_block,
+            height,
+            maybe_parent_hash.as_ref(),
+            maybe_weights,
+            chainspec.core_config.finality_threshold_fraction,
+        )
+        .with_context(|| format!("invalid block at height {}", height))?;
+        storage.write_exported_block(
+            block,
+            &exported_block.deploys,
+            exported_block.approvals_hashes.as_ref(),
+            exported_block.block_signatures.as_ref(),
+        )?;
+        validator_weights.record(block);
+        maybe_parent_hash = Some(*block.hash());
+    }
+    Ok((header.from, header.to))
+}
+
+/// The validator weights of the eras of imported blocks, taken from the switch blocks preceding
+/// them.
+struct EraValidatorWeights {
+    weights: BTreeMap<EraId, BTreeMap<PublicKey, U512>>,
+    /// The era in which the chainspec's protocol version was activated.
+    activation_era_id: EraId,
+    /// The validators of the activation era, if the upgrade changed them.
+    upgrade_validators: Option<BTreeMap<PublicKey, U512>>,
+}
+
+impl EraValidatorWeights {
+    fn new(chainspec: &Chainspec) -> Self {
+        EraValidatorWeights {
+            weights: BTreeMap::new(),
+            activation_era_id: chainspec.protocol_config.activation_point.era_id(),
+            upgrade_validators: chainspec
+                .protocol_config
+                .global_state_update
+                .as_ref()
+                .and_then(|update| update.validators.clone()),
+        }
+    }
+
+    /// Returns the validator weights of the given era, if its preceding switch block has been
+    /// imported or is stored.
+    fn get(
+        &mut self,
+        storage: &Storage,
+        era_id: EraId,
+    ) -> anyhow::Result<Option<&BTreeMap<PublicKey, U512>>> {
+        if !self.weights.contains_key(&era_id) {
+            if let Some(switch_block) = match era_id.predecessor() {
+                Some(previous_era_id) => storage.read_switch_block_by_era_id(previous_era_id)?,
+                None => None,
+            } {
+                self.record(&switch_block);
+            }
+        }
+        Ok(self.weights.get(&era_id))
+    }
+
+    /// Records the validator weights of the next era if the given block is a switch block.
+    fn record(&mut self, block: &Block) {
+        let next_era_id = block.header().next_block_era_id();
+        let weights = match block.header().next_era_validator_weights() {
+            Some(_)
+                if next_era_id == self.activation_era_id && self.upgrade_validators.is_some() =>
+            {
+                self.upgrade_validators.clone()
+            }
+            maybe_weights => maybe_weights.cloned(),
+        };
+        if let Some(weights) = weights {
+            self.weights.insert(next_era_id, weights);
+        }
+    }
+}
+
+/// Verifies the integrity of an exported block, and that it has the given height and parent.
+///
+/// Deploys must have valid approvals, which must match the block's approvals hashes if exported,
+/// and finality signatures, if any, must be valid and carry sufficient weight among the given
+/// validators of the block's era.
+fn verify_exported_block(
+    exported_block: &ExportedBlock,
+    height: u64,
+    maybe_parent_hash: Option<&BlockHash>,
+    maybe_validator_weights: Option<&BTreeMap<PublicKey, U512>>,
+    finality_threshold_fraction: Ratio<u64>,
+) -> anyhow::Result<()> {
+    let block = &exported_block.block;
+    block.verify()?;
+    ensure!(
+        block.height() == height,
+        "unexpected height {}",
+        block.height()
+    );
+    if let Some(parent_hash) = maybe_parent_hash {
+        ensure!(
+            block.header().parent_hash() == parent_hash,
+            "parent hash {} does not match previous block {}",
+            block.header().parent_hash(),
+            parent_hash
+        );
+    }
+
+    let expected_deploy_hashes: Vec<_> = block.deploy_and_transfer_hashes().collect();
+    let deploy_hashes: Vec<_> = exported_block
+        .deploys
+        .iter()
+        .map(|(deploy, _)| deploy.hash())
+        .collect();
+    ensure!(
+        deploy_hashes == expected_deploy_hashes,
+        "deploys do not match the block body"
+    );
+    for (deploy, maybe_finalized_approvals) in &exported_block.deploys {
+        deploy
+            .is_valid()
+            .with_context(|| format!("invalid deploy {}", deploy.hash()))?;
+        if let Some(finalized_approvals) = maybe_finalized_approvals {
+            ensure!(
+                !finalized_approvals.inner().is_empty(),
+                "empty finalized approvals for deploy {}",
+                deploy.hash()
+            );
+            for approval in finalized_approvals.inner() {
+                crypto::verify(deploy.hash(), approval.signature(), approval.signer())
+                    .with_context(|| {
+                        format!("invalid finalized approval for deploy {}", deploy.hash())
+                    })?;
+            }
+        }
+    }
+
+    if let Some(approvals_hashes) = &exported_block.approvals_hashes {
+        ensure!(
+            approvals_hashes.block_hash() == block.hash(),
+            "approvals hashes belong to another block"
+        );
+        approvals_hashes
+            .verify(block)
+            .context("approvals hashes are not proven by the block's global state")?;
+        ensure!(
+            approvals_hashes.approvals_hashes().len() == exported_block.deploys.len(),
+            "approvals hashes do not match the block body"
+        );
+        for ((deploy, maybe_finalized_approvals), approvals_hash) in exported_block
+            .deploys
+            .iter()
+            .zip(approvals_hashes.approvals_hashes())
+        {
+            // Finalized approvals are only stored if they differ from the deploy's own.
+            let approvals = maybe_finalized_approvals
+                .as_ref()
+                .map_or_else(|| deploy.approvals(), FinalizedApprovals::inner);
+            let computed_approvals_hash = ApprovalsHash::compute(approvals).map_err(|error| {
+                anyhow::anyhow!(
+                    "failed to hash approvals of deploy {}: {}",
+                    deploy.hash(),
+                    error
+                )
+            })?;
+            ensure!(
+                computed_approvals_hash == *approvals_hash,
+                "approvals of deploy {} do not match the block's approvals hashes",
+                deploy.hash()
+            );
+        }
+    }
+    if let Some(block_signatures) = &exported_block.block_signatures {
+        ensure!(
+            block_signatures.block_hash == *block.hash(),
+            "finality signatures belong to another block"
+        );
+        block_signatures.verify()?;
+        let validator_weights = maybe_validator_weights.with_context(|| {
+            format!(
+                "validators of era {} are unknown; import its preceding switch block first",
+                block.header().era_id()
+            )
+        })?;
+        utils::check_sufficient_block_signatures(
+            validator_weights,
+            finality_threshold_fraction,
+            Some(block_signatures),
+        )?;
+    }
+    Ok(())
+}
+
+/// Opens the storage in the same way as the main reactor does, returning it along with the
+/// chainspec.
+pub(super) fn open_storage(
+    config: WithDir<main_reactor::Config>,
+) -> anyhow::Result<(Storage, Chainspec)> {
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(config.dir())?;
+    if !chainspec.is_valid() {
+        bail!("invalid chainspec");
+    }
+    let (root, config) = config.into_parts();
+    let storage_config = WithDir::new(root, config.storage);
+    let storage = Storage::new(
+        &storage_config,
+        None,
+        chainspec.protocol_version(),
+        chainspec.protocol_config.activation_point.era_id(),
+        &chainspec.network_config.name,
+        chainspec.deploy_config.max_ttl.into(),
+        chainspec.core_config.recent_era_count(),
+        None,
+        false,
+    )
+    .context("could not open storage")?;
+    Ok((storage, chainspec))
+}
+
+fn parse_digest(hex: &str) -> anyhow::Result<Digest> {
+    Digest::from_hex(hex).with_context(|| format!("invalid hash '{}'", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeSet, VecDeque},
+        iter,
+    };
+
+    use rand::Rng;
+
+    use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
+    use casper_types::{testing::TestRng, CLValue, Key, SecretKey, StoredValue};
+
+    use super::*;
+    use crate::{
+        components::contract_runtime::APPROVALS_CHECKSUM_NAME,
+        types::{self, Approval, DeployId, FinalitySignature, FinalizedBlock},
+    };
+
+    const FINALITY_THRESHOLD_FRACTION: Ratio<u64> = Ratio::new_raw(1, 3);
+
+    fn random_exported_block(rng: &mut TestRng) -> ExportedBlock {
+        let deploy = Deploy::random(rng);
+        let block = Block::random_with_deploys(rng, iter::once(&deploy));
+        ExportedBlock {
+            block,
+            deploys: vec![(deploy, None)],
+            approvals_hashes: None,
+            block_signatures: None,
+        }
+    }
+
+    /// Adds the approvals hashes of the exported block's deploys, replacing the block with one whose
+    /// global state proves them.
+    fn add_approvals_hashes(rng: &mut TestRng, exported_block: &mut ExportedBlock) {
+        let approvals_hashes: Vec<_> = exported_block
+            .deploys
+            .iter()
+            .map(|(deploy, _)| ApprovalsHash::compute(deploy.approvals()).unwrap())
+            .collect();
+        let deploy_ids = exported_block
+            .deploys
+            .iter()
+            .zip(&approvals_hashes)
+            .map(|((deploy, _), approvals_hash)| DeployId::new(*deploy.hash(), *approvals_hash))
+            .collect();
+        let approvals_checksum = types::compute_approvals_checksum(deploy_ids).unwrap();
+        let checksum_registry: BTreeMap<_, _> =
+            iter::once((APPROVALS_CHECKSUM_NAME.to_string(), approvals_checksum)).collect();
+        let merkle_proof_approvals = TrieMerkleProof::new(
+            Key::ChecksumRegistry,
+            StoredValue::CLValue(CLValue::from_t(checksum_registry).unwrap()),
+            VecDeque::new(),
+        );
+
+        let block = &exported_block.block;
+        let block = Block::new(
+            *block.header().parent_hash(),
+            Digest::hash(rng.gen::<[u8; 32]>()),
+            merkle_proof_approvals.compute_state_hash().unwrap(),
+            FinalizedBlock::from(block.clone()),
+            block.header().next_era_validator_weights().cloned(),
+            block.header().protocol_version(),
+        )
+        .unwrap();
+        exported_block.approvals_hashes = Some(ApprovalsHashes::new(
+            block.hash(),
+            approvals_hashes,
+            merkle_proof_approvals,
+        ));
+        exported_block.block = block;
+    }
+
+    /// Signs the block with the given validators' keys.
+    fn sign(block: &Block, secret_keys: &[SecretKey]) -> BlockSignatures {
+        let era_id = block.header().era_id();
+        let mut block_signatures = BlockSignatures::new(*block.hash(), era_id);
+        for secret_key in secret_keys {
+            let signature =
+                FinalitySignature::create(*block.hash(), era_id, secret_key, secret_key.into());
+            block_signatures.insert_proof(signature.public_key, signature.signature);
+        }
+        block_signatures
+    }
+
+    fn verify(
+        exported_block: &ExportedBlock,
+        maybe_validator_weights: Option<&BTreeMap<PublicKey, U512>>,
+    ) -> anyhow::Result<()> {
+        verify_exported_block(
+            exported_block,
+            exported_block.block.height(),
+            Some(exported_block.block.header().parent_hash()),
+            maybe_validator_weights,
+            FINALITY_THRESHOLD_FRACTION,
+        )
+    }
+
+    #[test]
+    fn should_require_sufficient_signature_weight() {
+        let mut rng = TestRng::new();
+        let secret_keys: Vec<_> = (0..3).map(|_| SecretKey::random(&mut rng)).collect();
+        let validator_weights: BTreeMap<_, _> = secret_keys
+            .iter()
+            .map(|secret_key| (PublicKey::from(secret_key), U512::from(100)))
+            .collect();
+        let mut exported_block = random_exported_block(&mut rng);
+
+        exported_block.block_signatures = Some(sign(&exported_block.block, &secret_keys));
+        assert!(verify(&exported_block, Some(&validator_weights)).is_ok());
+        // The signatures can't be checked if the era's validators are unknown.
+        assert!(verify(&exported_block, None).is_err());
+
+        exported_block.block_signatures = Some(sign(&exported_block.block, &secret_keys[..1]));
+        assert!(verify(&exported_block, Some(&validator_weights)).is_err());
+
+        // Signatures by keys other than the era's validators are bogus.
+        let outsiders = vec![SecretKey::random(&mut rng)];
+        exported_block.block_signatures = Some(sign(&exported_block.block, &outsiders));
+        assert!(verify(&exported_block, Some(&validator_weights)).is_err());
+    }
+
+    #[test]
+    fn should_reject_invalid_finalized_approvals() {
+        let mut rng = TestRng::new();
+        let mut exported_block = random_exported_block(&mut rng);
+        assert!(verify(&exported_block, None).is_ok());
+
+        let (deploy, maybe_finalized_approvals) = &mut exported_block.deploys[0];
+        let valid_approval = Approval::create(deploy.hash(), &SecretKey::random(&mut rng));
+        *maybe_finalized_approvals = Some(FinalizedApprovals::new(BTreeSet::from([
+            valid_approval.clone(),
+        ])));
+        assert!(verify(&exported_block, None).is_ok());
+
+        let other_deploy_hash = DeployHash::random(&mut rng);
+        let invalid_approval = Approval::create(&other_deploy_hash, &SecretKey::random(&mut rng));
+        exported_block.deploys[0].1 = Some(FinalizedApprovals::new(BTreeSet::from([
+            valid_approval,
+            invalid_approval,
+        ])));
+        assert!(verify(&exported_block, None).is_err());
+    }
+
+    #[test]
+    fn should_reject_approvals_not_matching_approvals_hashes() {
+        let mut rng = TestRng::new();
+        let mut exported_block = random_exported_block(&mut rng);
+        add_approvals_hashes(&mut rng, &mut exported_block);
+        assert!(verify(&exported_block, None).is_ok());
+
+        // Validly signed finalized approvals which weren't the ones included in the block.
+        let (deploy, maybe_finalized_approvals) = &mut exported_block.deploys[0];
+        let approval = Approval::create(deploy.hash(), &SecretKey::random(&mut rng));
+        *maybe_finalized_approvals = Some(FinalizedApprovals::new(BTreeSet::from([approval])));
+        assert!(verify(&exported_block, None).is_err());
+
+        // Approvals hashes which aren't proven by the block's global state.
+        let mut exported_block = random_exported_block(&mut rng);
+        add_approvals_hashes(&mut rng, &mut exported_block);
+        let other_block = random_exported_block(&mut rng).block;
+        exported_block.block = other_block;
+        assert!(verify(&exported_block, None).is_err());
+    }
+
+    #[test]
+    fn should_reject_block_with_unexpected_parent() {
+        let mut rng = TestRng::new();
+        let exported_block = random_exported_block(&mut rng);
+        let other_parent_hash = BlockHash::random(&mut rng);
+        assert!(verify_exported_block(
+            &exported_block,
+            exported_block.block.height(),
+            Some(&other_parent_hash),
+            None,
+            FINALITY_THRESHOLD_FRACTION,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! Offline pruning of a node's block store and global state.
+//!
+//! The bodies of all blocks older than the given number of eras are removed, along with their
+//! deploys, execution results, transfers and approvals.  Afterwards, all global state trie nodes
+//! no longer reachable from the state root of any retained block are removed.  Block headers and
+//! finality signatures are kept, so that the chain remains verifiable.
+//!
+//! As there may be hundreds of millions of trie nodes, the reachable ones are not held in memory,
+//! but marked in a temporary database in the storage directory, which is removed afterwards.
+//!
+//! The node must not be running against the same data directory at the same time.  LMDB reuses
+//! the freed space for new data, but the database files themselves do not shrink.
+
+use std::{convert::TryFrom, fs, path::Path};
+
+use anyhow::{ensure, Context};
+use lmdb::{
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, Transaction, WriteFlags,
+};
+
+use casper_execution_engine::storage::{
+    transaction_source::lmdb::LmdbEnvironment, trie::Trie, trie_store::lmdb::LmdbTrieStore,
+};
+use casper_hashing::Digest;
+use casper_types::{bytesrepr, EraId, Key, StoredValue};
+
+use super::{db, state_snapshot};
+use crate::{reactor::main_reactor, utils::WithDir};
+
+/// The number of trie nodes marked or deleted per write transaction.
+const BATCH_SIZE: usize = 10_000;
+
+/// The name of the directory in the storage directory holding the marks of reachable trie nodes.
+const MARKS_DIR_NAME: &str = "prune_marks";
+
+/// One Mebibyte, used to report freed space.
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// Prunes all data older than `keep_eras` eras and prints a summary of the space freed.
+pub(super) fn run(config: WithDir<main_reactor::Config>, keep_eras: u64) -> anyhow::Result<()> {
+    let contract_runtime_config = config.value().contract_runtime;
+    let (mut storage, chainspec) = db::open_storage(config)?;
+
+    let recent_era_count = chainspec.core_config.recent_era_count();
+    ensure!(
+        keep_eras >= recent_era_count,
+        "at least {} eras must be kept for the node to operate",
+        recent_era_count
+    );
+    let highest_block_header = storage
+        .read_highest_block_header()?
+        .context("block store is empty")?;
+    let keep_from_era = EraId::new(
+        highest_block_header
+            .era_id()
+            .value()
+            .saturating_sub(keep_eras),
+    );
+
+    let outcome = storage.prune_blocks_before_era(keep_from_era)?;
+    println!(
+        "removed {} block bodies and {} deploys before era {}, freeing {:.1} MiB",
+        outcome.blocks,
+        outcome.deploys,
+        keep_from_era,
+        outcome.bytes as f64 / MIB
+    );
+
+    let environment = LmdbEnvironment::new(
+        storage.root_path(),
+        contract_runtime_config.max_global_state_size_or_default(),
+        contract_runtime_config.max_readers_or_default(),
+        false,
+    )
+    .context("could not open global state")?;
+    let trie_store =
+        LmdbTrieStore::open(&environment, None).context("could not open global state")?;
+    let (deleted, bytes) = prune_tries(
+        &environment,
+        trie_store.get_db(),
+        &outcome.retained_state_root_hashes,
+        &storage.root_path().join(MARKS_DIR_NAME),
+        contract_runtime_config.max_global_state_size_or_default(),
+    )?;
+    println!(
+        "removed {} unreachable trie nodes, freeing {:.1} MiB",
+        deleted,
+        bytes as f64 / MIB
+    );
+    Ok(())
+}
+
+/// Removes all tries not reachable from any of the given state roots, returning the number of
+/// tries removed and their total size in bytes.
+///
+/// The reachable tries are marked in a temporary database at `marks_dir` with a map size of
+/// `marks_map_size`, which is removed again once the unreachable tries have been deleted.
+fn prune_tries<'a, I>(
+    environment: &LmdbEnvironment,
+    db: Database,
+    state_root_hashes: I,
+    marks_dir: &Path,
+    marks_map_size: usize,
+) -> anyhow::Result<(usize, u64)>
+where
+    I: IntoIterator<Item = &'a Digest>,
+{
+    // Marks left behind by an interrupted run are stale, as the tries may have changed since.
+    if marks_dir.exists() {
+        fs::remove_dir_all(marks_dir)
+            .with_context(|| format!("could not remove {}", marks_dir.display()))?;
+    }
+    fs::create_dir(marks_dir)
+        .with_context(|| format!("could not create {}", marks_dir.display()))?;
+    let outcome = {
+        // The marks are discarded afterwards, so they need not be durable.
+        let marks_env = Environment::new()
+            .set_flags(EnvironmentFlags::NO_SYNC | EnvironmentFlags::NO_META_SYNC)
+            .set_map_size(marks_map_size)
+            .open(marks_dir)
+            .context("could not open the database of reachable tries")?;
+        let marks = marks_env.create_db(None, DatabaseFlags::empty())?;
+        mark_reachable_tries(environment.env(), db, &marks_env, marks, state_root_hashes)?;
+        sweep_unmarked_tries(environment.env(), db, &marks_env, marks)?
+    };
+    fs::remove_dir_all(marks_dir)
+        .with_context(|| format!("could not remove {}", marks_dir.display()))?;
+    Ok(outcome)
+}
+
+/// Marks all tries reachable from the given state roots in `marks`.
+fn mark_reachable_tries<'a, I>(
+    env: &Environment,
+    db: Database,
+    marks_env: &Environment,
+    marks: Database,
+    state_root_hashes: I,
+) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = &'a Digest>,
+{
+    let txn = env.begin_ro_txn()?;
+    let mut marks_txn = marks_env.begin_rw_txn()?;
+    let mut marked = 0;
+    let mut pending: Vec<Digest> = state_root_hashes.into_iter().copied().collect();
+    while let Some(trie_key) = pending.pop() {
+        match marks_txn.put(marks, &trie_key, b"", WriteFlags::NO_OVERWRITE) {
+            Ok(()) => (),
+            // Subtries shared between state roots only need to be visited once.
+            Err(lmdb::Error::KeyExist) => continue,
+            Err(error) => return Err(error.into()),
+        }
+        marked += 1;
+        if marked % BATCH_SIZE == 0 {
+            marks_txn.commit()?;
+            marks_txn = marks_env.begin_rw_txn()?;
+        }
+        let raw = match txn.get(db, &trie_key) {
+            Ok(raw) => raw,
+            // Tries of blocks whose global state was never synced are missing.
+            Err(lmdb::Error::NotFound) => continue,
+            Err(error) => return Err(error.into()),
+        };
+        let trie: Trie<Key, StoredValue> = bytesrepr::deserialize_from_slice(raw)
+            .map_err(|error| anyhow::anyhow!("could not parse trie {}: {}", trie_key, error))?;
+        pending.extend(state_snapshot::trie_children(&trie));
+    }
+    marks_txn.commit()?;
+    Ok(())
+}
+
+/// Deletes all tries not marked in `marks`, returning their number and total size in bytes.
+///
+/// The tries are deleted in batches, each found by resuming the scan of the trie store where the
+/// previous batch ended.
+fn sweep_unmarked_tries(
+    env: &Environment,
+    db: Database,
+    marks_env: &Environment,
+    marks: Database,
+) -> anyhow::Result<(usize, u64)> {
+    let mut deleted = 0;
+    let mut bytes = 0;
+    let mut resume_from: Option<Digest> = None;
+    loop {
+        let mut unmarked = Vec::with_capacity(BATCH_SIZE);
+        let mut next = None;
+        {
+            let txn = env.begin_ro_txn()?;
+            let marks_txn = marks_env.begin_ro_txn()?;
+            let mut cursor = txn.open_ro_cursor(db)?;
+            let rows = match &resume_from {
+                Some(trie_key) => cursor.iter_from(trie_key),
+                None => cursor.iter_start(),
+            };
+            for row in rows {
+                let (raw_key, raw_value) = row?;
+                let trie_key = Digest::try_from(raw_key)
+                    .map_err(|error| anyhow::anyhow!("invalid trie key: {}", error))?;
+                if unmarked.len() == BATCH_SIZE {
+                    next = Some(trie_key);
+                    break;
+                }
+                match marks_txn.get(marks, &trie_key) {
+                    Ok(_) => (),
+                    Err(lmdb::Error::NotFound) => {
+                        unmarked.push(trie_key);
+                        bytes += raw_value.len() as u64;
+                    }
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        }
+
+        let mut txn = env.begin_rw_txn()?;
+        for trie_key in &unmarked {
+            txn.del(db, trie_key, None)?;
+        }
+        txn.commit()?;
+        deleted += unmarked.len();
+
+        match next {
+            Some(trie_key) => resume_from = Some(trie_key),
+            None => return Ok((deleted, bytes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::storage::{
+        store::Store,
+        trie::{Pointer, PointerBlock},
+    };
+    use casper_types::{AccessRights, CLValue, URef};
+
+    use super::*;
+
+    fn put_trie(
+        environment: &LmdbEnvironment,
+        trie_store: &LmdbTrieStore,
+        trie: &Trie<Key, StoredValue>,
+    ) -> Digest {
+        let trie_key = trie.trie_hash().unwrap();
+        let mut txn = environment.env().begin_rw_txn().unwrap();
+        trie_store.put(&mut txn, &trie_key, trie).unwrap();
+        txn.commit().unwrap();
+        trie_key
+    }
+
+    fn leaf(value: u64) -> Trie<Key, StoredValue> {
+        Trie::Leaf {
+            key: Key::URef(URef::new([value as u8; 32], AccessRights::READ)),
+            value: StoredValue::CLValue(CLValue::from_t(value).unwrap()),
+        }
+    }
+
+    #[test]
+    fn should_prune_unreachable_tries() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let environment = LmdbEnvironment::new(tempdir.path(), 10 * 1024 * 1024, 8, false).unwrap();
+        let trie_store = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap();
+
+        let retained_leaf = put_trie(&environment, &trie_store, &leaf(1));
+        let pruned_leaf = put_trie(&environment, &trie_store, &leaf(2));
+        let mut pointer_block = PointerBlock::new();
+        pointer_block[0] = Some(Pointer::LeafPointer(retained_leaf));
+        let retained_root = put_trie(
+            &environment,
+            &trie_store,
+            &Trie::Node {
+                pointer_block: Box::new(pointer_block),
+            },
+        );
+
+        let marks_dir = tempdir.path().join(MARKS_DIR_NAME);
+        let (deleted, bytes) = prune_tries(
+            &environment,
+            trie_store.get_db(),
+            &[retained_root],
+            &marks_dir,
+            10 * 1024 * 1024,
+        )
+        .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(bytes > 0);
+        assert!(!marks_dir.exists());
+
+        let txn = environment.env().begin_ro_txn().unwrap();
+        let db = trie_store.get_db();
+        assert!(txn.get(db, &retained_root).is_ok());
+        assert!(txn.get(db, &retained_leaf).is_ok());
+        assert_eq!(txn.get(db, &pruned_leaf), Err(lmdb::Error::NotFound));
+    }
+
+    #[test]
+    fn should_retain_subtries_shared_between_state_roots() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let environment = LmdbEnvironment::new(tempdir.path(), 10 * 1024 * 1024, 8, false).unwrap();
+        let trie_store = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap();
+
+        // Both retained roots point to the shared leaf, and the pruned root only to the other leaf,
+        // which is also retained via the second root.
+        let shared_leaf = put_trie(&environment, &trie_store, &leaf(1));
+        let other_leaf = put_trie(&environment, &trie_store, &leaf(2));
+        let put_root = |leaves: &[Digest]| {
+            let mut pointer_block = PointerBlock::new();
+            for (index, leaf) in leaves.iter().enumerate() {
+                pointer_block[index] = Some(Pointer::LeafPointer(*leaf));
+            }
+            put_trie(
+                &environment,
+                &trie_store,
+                &Trie::Node {
+                    pointer_block: Box::new(pointer_block),
+                },
+            )
+        };
+        let first_root = put_root(&[shared_leaf]);
+        let second_root = put_root(&[other_leaf, shared_leaf]);
+        let pruned_root = put_root(&[other_leaf]);
+
+        // A stale marks directory left behind by an interrupted run must be ignored.
+        let marks_dir = tempdir.path().join(MARKS_DIR_NAME);
+        fs::create_dir(&marks_dir).unwrap();
+        fs::write(marks_dir.join("data.mdb"), b"stale").unwrap();
+
+        let (deleted, _) = prune_tries(
+            &environment,
+            trie_store.get_db(),
+            &[first_root, second_root],
+            &marks_dir,
+            10 * 1024 * 1024,
+        )
+        .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(!marks_dir.exists());
+
+        let txn = environment.env().begin_ro_txn().unwrap();
+        let db = trie_store.get_db();
+        for retained in [first_root, second_root, shared_leaf, other_leaf] {
+            assert!(txn.get(db, &retained).is_ok());
+        }
+        assert_eq!(txn.get(db, &pruned_root), Err(lmdb::Error::NotFound));
+    }
+}
@@ -0,0 +1,71 @@
+//! Offline replay of an era's Highway protocol state.
+//!
+//! The units, endorsements and evidence of the era are read from the unit log written by a node
+//! with `consensus.highway.persist_units` enabled, and added to a fresh protocol state in the
+//! order in which the node originally added them. The blocks are printed in the order in which
+//! they get finalized, for auditing disputed finality or debugging consensus faults.
+
+use anyhow::{ensure, Context};
+
+use casper_types::EraId;
+
+use super::db;
+use crate::{
+    components::consensus::{self, ChainspecConsensusExt},
+    reactor::main_reactor,
+    types::chainspec::ConsensusProtocolName,
+    utils::WithDir,
+};
+
+/// Replays era `era_id` and prints its finalized blocks.
+pub(super) fn run(config: WithDir<main_reactor::Config>, era_id: EraId) -> anyhow::Result<()> {
+    let consensus_config = config.value().consensus.clone();
+    let (storage, chainspec) = db::open_storage(config)?;
+
+    ensure!(
+        chainspec.core_config.consensus_protocol == ConsensusProtocolName::Highway,
+        "only Highway eras can be replayed"
+    );
+    ensure!(
+        era_id > chainspec.activation_era(),
+        "{} is at or before the last activation point and has no consensus instance",
+        era_id
+    );
+
+    let earliest_era = chainspec.earliest_switch_block_needed(era_id);
+    let switch_blocks = (earliest_era.value()..era_id.value())
+        .map(|era| {
+            storage
+                .read_switch_block_header_by_era_id(EraId::new(era))?
+                .with_context(|| format!("missing switch block of era {}", era))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let replayed = consensus::replay_era(
+        &chainspec,
+        &consensus_config,
+        storage.root_path(),
+        &switch_blocks,
+    )?;
+    println!(
+        "{}: {} finalized blocks",
+        replayed.era_id,
+        replayed.finalized_blocks.len()
+    );
+    for block in &replayed.finalized_blocks {
+        println!(
+            "height {} timestamp {} proposer {} deploys {} transfers {}{}",
+            replayed.start_height.saturating_add(block.relative_height),
+            block.timestamp,
+            block.proposer,
+            block.value.deploy_hashes().count(),
+            block.value.transfer_hashes().count(),
+            if block.terminal_block_data.is_some() {
+                " (switch block)"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
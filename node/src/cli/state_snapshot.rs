@@ -0,0 +1,544 @@
+//! Offline export and import of global state.
+//!
+//! A snapshot holds every trie node reachable from a single state root hash, allowing a node to be
+//! set up with the global state of another node, e.g. to launch a network from a common state or
+//! to recover from the loss of a node's data.  The node must not be running against the same data
+//! directory at the same time.
+//!
+//! Snapshot files contain the bincode-encoded [`SNAPSHOT_FORMAT_VERSION`] and [`SnapshotHeader`],
+//! followed by one bincode-encoded `Some(SnapshotTrie)` per trie node, in depth-first order
+//! starting from the state root, and a final `None`.  Imported trie nodes are verified against
+//! their hashes, and an import only succeeds if the snapshot holds exactly the trie nodes reachable
+//! from its state root.
+//!
+//! Neither export nor import hold a set of all trie nodes in memory: as every subtrie holds leaves
+//! with full keys, a trie node is only reachable via a single path, so only the trie nodes
+//! referenced but not yet visited are tracked.  An import records the keys of the trie nodes it
+//! adds in a journal next to the global state, and if it fails or is interrupted, deletes them
+//! again, so that the global state is left as it was.
+
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context};
+use lmdb::{Database, DatabaseFlags, Environment, Transaction, WriteFlags};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+
+use casper_execution_engine::storage::{
+    transaction_source::lmdb::LmdbEnvironment, trie::Trie, trie_store::lmdb::LmdbTrieStore,
+};
+use casper_hashing::Digest;
+use casper_types::{bytesrepr, Key, StoredValue};
+
+use super::db;
+use crate::{reactor::main_reactor, utils::WithDir};
+
+/// The version of the snapshot file format, to be incremented on any change to it.
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// The number of trie nodes written per write transaction when importing.
+const IMPORT_BATCH_SIZE: usize = 10_000;
+
+/// The name of the file in the storage directory recording the trie nodes added by an import.
+const IMPORT_JOURNAL_FILENAME: &str = "state_import.journal";
+
+/// An export or import of global state.
+#[derive(Debug, StructOpt)]
+pub enum StateCommand {
+    /// Export the global state at a state root hash to a file.
+    Export {
+        /// Hex-encoded state root hash to export.  Defaults to the state root hash of the highest
+        /// block.
+        #[structopt(long)]
+        state_root_hash: Option<String>,
+        /// Path of the file to write.
+        #[structopt(long, short)]
+        output: PathBuf,
+    },
+    /// Import global state previously exported from a node of the same network, verifying it
+    /// first.
+    Import {
+        /// Path of the file to read.
+        #[structopt(long, short)]
+        input: PathBuf,
+    },
+}
+
+/// The header of a snapshot file.
+#[derive(Serialize, Deserialize)]
+struct SnapshotHeader {
+    network_name: String,
+    state_root_hash: Digest,
+}
+
+/// A serialized trie node along with its hash.
+#[derive(Serialize, Deserialize)]
+struct SnapshotTrie {
+    trie_key: Digest,
+    bytes: Vec<u8>,
+}
+
+/// Opens the global state configured in `config` and runs the export or import.
+pub(super) fn run(
+    config: WithDir<main_reactor::Config>,
+    command: StateCommand,
+) -> anyhow::Result<()> {
+    let contract_runtime_config = config.value().contract_runtime;
+    let (storage, chainspec) = db::open_storage(config)?;
+    let network_name = chainspec.network_config.name;
+    let environment = LmdbEnvironment::new(
+        storage.root_path(),
+        contract_runtime_config.max_global_state_size_or_default(),
+        contract_runtime_config.max_readers_or_default(),
+        false,
+    )
+    .context("could not open global state")?;
+    let trie_store = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())
+        .context("could not open global state")?;
+
+    match command {
+        StateCommand::Export {
+            state_root_hash,
+            output,
+        } => {
+            let state_root_hash = match state_root_hash {
+                Some(hex) => Digest::from_hex(&hex)
+                    .with_context(|| format!("invalid state root hash '{}'", hex))?,
+                None => *storage
+                    .read_highest_block_header()?
+                    .context("block store is empty")?
+                    .state_root_hash(),
+            };
+            let file = File::create(&output)
+                .with_context(|| format!("could not create {}", output.display()))?;
+            let mut writer = BufWriter::new(file);
+            let header = SnapshotHeader {
+                network_name,
+                state_root_hash,
+            };
+            write_header(&mut writer, &header)?;
+            let count = export_tries(
+                environment.env(),
+                trie_store.get_db(),
+                state_root_hash,
+                &mut writer,
+            )?;
+            writer.flush()?;
+            println!(
+                "exported {} trie nodes of state root {} to {}",
+                count,
+                state_root_hash,
+                output.display()
+            );
+        }
+        StateCommand::Import { input } => {
+            let file = File::open(&input)
+                .with_context(|| format!("could not open {}", input.display()))?;
+            let mut reader = BufReader::new(file);
+            let header = read_header(&mut reader)?;
+            ensure!(
+                header.network_name == network_name,
+                "global state was exported from network '{}', not '{}'",
+                header.network_name,
+                network_name
+            );
+            let count = import_tries(
+                environment.env(),
+                trie_store.get_db(),
+                header.state_root_hash,
+                &mut reader,
+                &storage.root_path().join(IMPORT_JOURNAL_FILENAME),
+            )?;
+            println!(
+                "imported {} trie nodes of state root {} from {}",
+                count,
+                header.state_root_hash,
+                input.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn write_header<W: Write>(writer: &mut W, header: &SnapshotHeader) -> anyhow::Result<()> {
+    bincode::serialize_into(&mut *writer, &SNAPSHOT_FORMAT_VERSION)?;
+    bincode::serialize_into(&mut *writer, header)?;
+    Ok(())
+}
+
+fn read_header<R: Read>(reader: &mut R) -> anyhow::Result<SnapshotHeader> {
+    let version: u32 = bincode::deserialize_from(&mut *reader)
+        .context("could not read snapshot format version")?;
+    ensure!(
+        version == SNAPSHOT_FORMAT_VERSION,
+        "unsupported snapshot format version {}, expected {}",
+        version,
+        SNAPSHOT_FORMAT_VERSION
+    );
+    bincode::deserialize_from(&mut *reader).context("could not read snapshot header")
+}
+
+/// Returns the hashes of the trie nodes the given trie node points to.
+pub(super) fn trie_children(trie: &Trie<Key, StoredValue>) -> Vec<Digest> {
+    match trie {
+        Trie::Leaf { .. } => vec![],
+        Trie::Node { pointer_block } => pointer_block
+            .as_indexed_pointers()
+            .map(|(_, pointer)| pointer.into_hash())
+            .collect(),
+        Trie::Extension { pointer, .. } => vec![pointer.into_hash()],
+    }
+}
+
+fn parse_trie(trie_key: &Digest, bytes: &[u8]) -> anyhow::Result<Trie<Key, StoredValue>> {
+    bytesrepr::deserialize_from_slice(bytes)
+        .map_err(|error| anyhow::anyhow!("could not parse trie {}: {}", trie_key, error))
+}
+
+/// Writes all trie nodes reachable from `state_root_hash`, returning their number.
+fn export_tries<W: Write>(
+    env: &Environment,
+    db: Database,
+    state_root_hash: Digest,
+    writer: &mut W,
+) -> anyhow::Result<usize> {
+    let txn = env.begin_ro_txn()?;
+    let mut count = 0;
+    let mut pending = vec![state_root_hash];
+    while let Some(trie_key) = pending.pop() {
+        let bytes = match txn.get(db, &trie_key) {
+            Ok(bytes) => bytes,
+            Err(lmdb::Error::NotFound) => bail!(
+                "trie {} is missing, global state at {} is incomplete",
+                trie_key,
+                state_root_hash
+            ),
+            Err(error) => return Err(error.into()),
+        };
+        pending.extend(trie_children(&parse_trie(&trie_key, bytes)?));
+        let snapshot_trie = SnapshotTrie {
+            trie_key,
+            bytes: bytes.to_vec(),
+        };
+        bincode::serialize_into(&mut *writer, &Some(snapshot_trie))?;
+        count += 1;
+    }
+    bincode::serialize_into(&mut *writer, &None::<SnapshotTrie>)?;
+    Ok(count)
+}
+
+/// Verifies and stores the trie nodes read from `reader`, returning their number.
+///
+/// Each trie node must be reachable from `state_root_hash` via the trie nodes preceding it, and
+/// the import fails unless all trie nodes reachable from `state_root_hash` are present.  If the
+/// import fails, the trie nodes it added are deleted again.  A journal left behind by an
+/// interrupted import at `journal_path` is rolled back first.
+fn import_tries<R: Read>(
+    env: &Environment,
+    db: Database,
+    state_root_hash: Digest,
+    reader: &mut R,
+    journal_path: &Path,
+) -> anyhow::Result<usize> {
+    roll_back_import(env, db, journal_path)
+        .context("could not roll back a previously interrupted import")?;
+    match import_journaled_tries(env, db, state_root_hash, reader, journal_path) {
+        Ok(count) => {
+            fs::remove_file(journal_path)
+                .with_context(|| format!("could not remove {}", journal_path.display()))?;
+            Ok(count)
+        }
+        Err(error) => {
+            roll_back_import(env, db, journal_path).with_context(|| {
+                format!("could not roll back the import, which failed: {:#}", error)
+            })?;
+            Err(error)
+        }
+    }
+}
+
+/// Verifies and stores the trie nodes read from `reader`, recording the keys of the ones not
+/// stored before in the journal at `journal_path`.
+///
+/// The journal is synced to disk before each batch of trie nodes is committed, so that it covers
+/// every trie node added even if the import is interrupted.
+fn import_journaled_tries<R: Read>(
+    env: &Environment,
+    db: Database,
+    state_root_hash: Digest,
+    reader: &mut R,
+    journal_path: &Path,
+) -> anyhow::Result<usize> {
+    let journal_file = File::create(journal_path)
+        .with_context(|| format!("could not create {}", journal_path.display()))?;
+    let mut journal = BufWriter::new(journal_file);
+    let mut count = 0;
+    let mut expected = HashSet::from([state_root_hash]);
+    let mut txn = env.begin_rw_txn()?;
+    while let Some(SnapshotTrie { trie_key, bytes }) =
+        bincode::deserialize_from::<_, Option<SnapshotTrie>>(&mut *reader)
+            .with_context(|| format!("could not read trie {}", count))?
+    {
+        ensure!(
+            expected.remove(&trie_key),
+            "trie {} is not referenced by any preceding trie",
+            trie_key
+        );
+        ensure!(
+            Digest::hash_into_chunks_if_necessary(&bytes) == trie_key,
+            "trie {} does not match its hash",
+            trie_key
+        );
+        expected.extend(trie_children(&parse_trie(&trie_key, &bytes)?));
+        match txn.put(db, &trie_key, &bytes, WriteFlags::NO_OVERWRITE) {
+            Ok(()) => journal.write_all(trie_key.as_ref())?,
+            Err(lmdb::Error::KeyExist) => (),
+            Err(error) => return Err(error.into()),
+        }
+        count += 1;
+        if count % IMPORT_BATCH_SIZE == 0 {
+            sync_journal(&mut journal)?;
+            txn.commit()?;
+            txn = env.begin_rw_txn()?;
+        }
+    }
+    ensure!(
+        expected.is_empty(),
+        "snapshot is incomplete, {} referenced tries are missing",
+        expected.len()
+    );
+    sync_journal(&mut journal)?;
+    txn.commit()?;
+    Ok(count)
+}
+
+fn sync_journal(journal: &mut BufWriter<File>) -> anyhow::Result<()> {
+    journal.flush()?;
+    journal.get_ref().sync_data()?;
+    Ok(())
+}
+
+/// Deletes the trie nodes recorded in the journal at `journal_path`, if any, and removes it.
+fn roll_back_import(env: &Environment, db: Database, journal_path: &Path) -> anyhow::Result<()> {
+    let journal_file = match File::open(journal_path) {
+        Ok(journal_file) => journal_file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("could not open {}", journal_path.display()))
+        }
+    };
+    let mut journal = BufReader::new(journal_file);
+    let mut trie_key = [0; Digest::LENGTH];
+    let mut count = 0;
+    let mut txn = env.begin_rw_txn()?;
+    loop {
+        // A truncated last key was never committed, as the journal is synced before each commit.
+        match journal.read_exact(&mut trie_key) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error.into()),
+        }
+        match txn.del(db, &trie_key, None) {
+            Ok(()) | Err(lmdb::Error::NotFound) => (),
+            Err(error) => return Err(error.into()),
+        }
+        count += 1;
+        if count % IMPORT_BATCH_SIZE == 0 {
+            txn.commit()?;
+            txn = env.begin_rw_txn()?;
+        }
+    }
+    txn.commit()?;
+    fs::remove_file(journal_path)
+        .with_context(|| format!("could not remove {}", journal_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::storage::{
+        store::Store,
+        trie::{Pointer, PointerBlock},
+    };
+    use casper_types::{AccessRights, CLValue, URef};
+
+    use super::*;
+
+    fn trie_store() -> (tempfile::TempDir, LmdbEnvironment, LmdbTrieStore) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let environment = LmdbEnvironment::new(tempdir.path(), 10 * 1024 * 1024, 8, false).unwrap();
+        let trie_store = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap();
+        (tempdir, environment, trie_store)
+    }
+
+    fn put_trie(
+        environment: &LmdbEnvironment,
+        trie_store: &LmdbTrieStore,
+        trie: &Trie<Key, StoredValue>,
+    ) -> Digest {
+        let trie_key = trie.trie_hash().unwrap();
+        let mut txn = environment.env().begin_rw_txn().unwrap();
+        trie_store.put(&mut txn, &trie_key, trie).unwrap();
+        txn.commit().unwrap();
+        trie_key
+    }
+
+    fn leaf(value: u64) -> Trie<Key, StoredValue> {
+        Trie::Leaf {
+            key: Key::URef(URef::new([value as u8; 32], AccessRights::READ)),
+            value: StoredValue::CLValue(CLValue::from_t(value).unwrap()),
+        }
+    }
+
+    /// Stores a trie of three leaves and an unrelated leaf, returning the keys of the leaves, the
+    /// root and the unrelated leaf.
+    fn put_tries(
+        environment: &LmdbEnvironment,
+        trie_store: &LmdbTrieStore,
+    ) -> (Vec<Digest>, Digest, Digest) {
+        let leaves: Vec<_> = (0..3)
+            .map(|value| put_trie(environment, trie_store, &leaf(value)))
+            .collect();
+        let unrelated_leaf = put_trie(environment, trie_store, &leaf(42));
+        let mut pointer_block = PointerBlock::new();
+        for (index, leaf) in leaves.iter().enumerate() {
+            pointer_block[index] = Some(Pointer::LeafPointer(*leaf));
+        }
+        let root = put_trie(
+            environment,
+            trie_store,
+            &Trie::Node {
+                pointer_block: Box::new(pointer_block),
+            },
+        );
+        (leaves, root, unrelated_leaf)
+    }
+
+    #[test]
+    fn should_export_and_import_global_state() {
+        let (_source_dir, source_env, source_store) = trie_store();
+        let (leaves, root, unrelated_leaf) = put_tries(&source_env, &source_store);
+
+        let mut snapshot = vec![];
+        let exported =
+            export_tries(source_env.env(), source_store.get_db(), root, &mut snapshot).unwrap();
+        assert_eq!(exported, 4);
+
+        let (target_dir, target_env, target_store) = trie_store();
+        let db = target_store.get_db();
+        let journal_path = target_dir.path().join(IMPORT_JOURNAL_FILENAME);
+        let imported = import_tries(
+            target_env.env(),
+            db,
+            root,
+            &mut snapshot.as_slice(),
+            &journal_path,
+        )
+        .unwrap();
+        assert_eq!(imported, 4);
+        assert!(!journal_path.exists());
+        let txn = target_env.env().begin_ro_txn().unwrap();
+        for trie_key in leaves.iter().chain([&root]) {
+            assert!(txn.get(db, trie_key).is_ok());
+        }
+        assert_eq!(txn.get(db, &unrelated_leaf), Err(lmdb::Error::NotFound));
+        drop(txn);
+
+        // A snapshot of another state root is rejected.
+        let (other_dir, other_env, other_store) = trie_store();
+        let other_journal_path = other_dir.path().join(IMPORT_JOURNAL_FILENAME);
+        assert!(import_tries(
+            other_env.env(),
+            other_store.get_db(),
+            unrelated_leaf,
+            &mut snapshot.as_slice(),
+            &other_journal_path
+        )
+        .is_err());
+
+        // A truncated snapshot is rejected.
+        let truncated = &snapshot[..snapshot.len() - 1];
+        assert!(import_tries(
+            other_env.env(),
+            other_store.get_db(),
+            root,
+            &mut &truncated[..],
+            &other_journal_path
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn should_roll_back_failed_import() {
+        let (_source_dir, source_env, source_store) = trie_store();
+        let (leaves, root, _) = put_tries(&source_env, &source_store);
+        let mut snapshot = vec![];
+        export_tries(source_env.env(), source_store.get_db(), root, &mut snapshot).unwrap();
+
+        // The target already holds one of the leaves.
+        let (target_dir, target_env, target_store) = trie_store();
+        let db = target_store.get_db();
+        let journal_path = target_dir.path().join(IMPORT_JOURNAL_FILENAME);
+        put_trie(&target_env, &target_store, &leaf(0));
+
+        let truncated = &snapshot[..snapshot.len() - 1];
+        assert!(import_tries(
+            target_env.env(),
+            db,
+            root,
+            &mut &truncated[..],
+            &journal_path
+        )
+        .is_err());
+        assert!(!journal_path.exists());
+
+        let assert_only_stored_leaf_left = || {
+            let txn = target_env.env().begin_ro_txn().unwrap();
+            assert!(txn.get(db, &leaves[0]).is_ok());
+            for trie_key in leaves[1..].iter().chain([&root]) {
+                assert_eq!(txn.get(db, trie_key), Err(lmdb::Error::NotFound));
+            }
+        };
+        assert_only_stored_leaf_left();
+
+        // The journal of an interrupted import is rolled back.
+        import_tries(
+            target_env.env(),
+            db,
+            root,
+            &mut snapshot.as_slice(),
+            &journal_path,
+        )
+        .unwrap();
+        let journal: Vec<u8> = leaves[1..]
+            .iter()
+            .chain([&root])
+            .flat_map(|trie_key| trie_key.value())
+            .collect();
+        fs::write(&journal_path, journal).unwrap();
+        roll_back_import(target_env.env(), db, &journal_path).unwrap();
+        assert!(!journal_path.exists());
+        assert_only_stored_leaf_left();
+    }
+
+    #[test]
+    fn should_reject_other_snapshot_format_versions() {
+        let header = SnapshotHeader {
+            network_name: "casper-test".to_string(),
+            state_root_hash: Digest::hash([1; 32]),
+        };
+        let mut bytes = vec![];
+        write_header(&mut bytes, &header).unwrap();
+        let read = read_header(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.network_name, header.network_name);
+        assert_eq!(read.state_root_hash, header.state_root_hash);
+
+        let mut other_version = bincode::serialize(&(SNAPSHOT_FORMAT_VERSION + 1)).unwrap();
+        other_version.extend(bincode::serialize(&header).unwrap());
+        assert!(read_header(&mut other_version.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Querying the status of a running node via its REST server.
+//!
+//! The status is requested from the REST server's `/status` endpoint using a minimal HTTP/1.1
+//! client, and printed either as a human-readable summary or as the JSON returned by the node.
+//! The response is handled as untyped JSON, so that nodes of other versions can be queried too.
+
+use std::{fmt::Write as _, time::Duration};
+
+use anyhow::{bail, Context};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time,
+};
+
+use crate::components::rest_server::STATUS_API_PATH;
+
+/// The maximum time allowed to query the node.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Requests the status from the REST server at `address` and prints it.
+pub(super) async fn run(address: &str, json: bool) -> anyhow::Result<()> {
+    let body = time::timeout(REQUEST_TIMEOUT, fetch_status(address))
+        .await
+        .with_context(|| format!("timed out querying {}", address))??;
+    let status: Value = serde_json::from_str(&body).context("could not parse node status")?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+    } else {
+        print!("{}", summarize(&status));
+    }
+    Ok(())
+}
+
+/// Sends a `GET` request for the status to the REST server at `address` and returns the response
+/// body.
+async fn fetch_status(address: &str) -> anyhow::Result<String> {
+    let host = address.trim_start_matches("http://").trim_end_matches('/');
+    let mut stream = TcpStream::connect(host)
+        .await
+        .with_context(|| format!("could not connect to {}", host))?;
+    let request = format!(
+        "GET /{} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        STATUS_API_PATH, host
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = vec![];
+    let _ = stream.read_to_end(&mut response).await?;
+    parse_response(&response)
+}
+
+/// Extracts the body from a complete HTTP/1.1 response, failing on non-success status codes.
+fn parse_response(response: &[u8]) -> anyhow::Result<String> {
+    let response = String::from_utf8_lossy(response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .context("malformed HTTP response")?;
+    let mut head_lines = head.lines();
+    let status_line = head_lines.next().unwrap_or_default();
+    let status_code = status_line
+        .split_whitespace()
+        .nth(1)
+        .context("malformed HTTP status line")?;
+    if status_code != "200" {
+        bail!("node responded with '{}'", status_line);
+    }
+    if head_lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    }) {
+        bail!("chunked HTTP responses are not supported");
+    }
+    Ok(body.to_string())
+}
+
+/// Returns a human-readable summary of the given status.
+fn summarize(status: &Value) -> String {
+    fn field(value: &Value) -> String {
+        match value {
+            Value::Null => "none".to_string(),
+            Value::String(string) => string.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn sync(value: &Value) -> String {
+        if value.is_null() {
+            return "idle".to_string();
+        }
+        format!(
+            "height {}, {}",
+            field(&value["block_height"]),
+            field(&value["acquisition_state"])
+        )
+    }
+
+    let block = &status["last_added_block_info"];
+    let peer_count = status["peers"].as_array().map_or(0, Vec::len);
+    let range = &status["available_block_range"];
+
+    let mut summary = String::new();
+    let mut line = |label: &str, value: String| {
+        let _ = writeln!(summary, "{:<20}{}", label, value);
+    };
+    line("Version:", field(&status["build_version"]));
+    line("API version:", field(&status["api_version"]));
+    line("Chainspec:", field(&status["chainspec_name"]));
+    line("Reactor state:", field(&status["reactor_state"]));
+    line("Block height:", field(&block["height"]));
+    line("Era:", field(&block["era_id"]));
+    line("Block hash:", field(&block["hash"]));
+    line("Block timestamp:", field(&block["timestamp"]));
+    line("Peers:", peer_count.to_string());
+    line(
+        "Available blocks:",
+        format!("{} to {}", field(&range["low"]), field(&range["high"])),
+    );
+    line("Forward sync:", sync(&status["block_sync"]["forward"]));
+    line(
+        "Historical sync:",
+        sync(&status["block_sync"]["historical"]),
+    );
+    line("Uptime:", field(&status["uptime"]));
+    line("Last progress:", field(&status["last_progress"]));
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_response() {
+        let response = b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\r\n{\"a\":1}";
+        assert_eq!(parse_response(response).unwrap(), "{\"a\":1}");
+
+        let response = b"HTTP/1.1 404 Not Found\r\n\r\n";
+        assert!(parse_response(response).is_err());
+
+        let response =
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n7\r\n{\"a\":1}\r\n0\r\n";
+        assert!(parse_response(response).is_err());
+
+        assert!(parse_response(b"garbage").is_err());
+    }
+
+    #[test]
+    fn should_summarize_status() {
+        let status = json!({
+            "peers": [{ "node_id": "tls:0101..0101", "address": "127.0.0.1:54321" }],
+            "build_version": "1.0.0",
+            "last_added_block_info": { "era_id": 1, "height": 10 },
+            "reactor_state": "KeepUp",
+            "block_sync": { "historical": null, "forward": {
+                "block_height": 11,
+                "acquisition_state": "have block body"
+            }}
+        });
+        let summary = summarize(&status);
+        assert!(summary.contains("Version:            1.0.0\n"));
+        assert!(summary.contains("Reactor state:      KeepUp\n"));
+        assert!(summary.contains("Block height:       10\n"));
+        assert!(summary.contains("Era:                1\n"));
+        assert!(summary.contains("Peers:              1\n"));
+        assert!(summary.contains("Chainspec:          none\n"));
+        assert!(summary.contains("Forward sync:       height 11, have block body\n"));
+        assert!(summary.contains("Historical sync:    idle\n"));
+    }
+}
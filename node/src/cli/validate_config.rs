@@ -0,0 +1,278 @@
+//! Validation of a node's config file without running the node.
+//!
+//! The config is loaded exactly as when running the node, and then cross-checked against the
+//! chainspec and the environment: keys and certificates must load, listening addresses must
+//! resolve and be bindable, and configured directories must be usable.  Problems which would
+//! prevent the node from starting are reported as errors, others as warnings.
+
+use std::{
+    io,
+    net::TcpListener,
+    path::{Path, PathBuf},
+};
+
+use super::Cli;
+use crate::{
+    components::network::Identity as NetworkIdentity,
+    reactor::main_reactor,
+    types::{Chainspec, ChainspecRawBytes},
+    utils::{self, Loadable, WithDir},
+};
+
+/// The errors and warnings found while validating a config.
+#[derive(Default)]
+struct Report {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl Report {
+    fn error(&mut self, message: String) {
+        self.errors.push(message);
+    }
+
+    fn warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+}
+
+/// Validates the config file at `config_path`, printing all problems found.
+///
+/// Returns `true` if no errors were found.
+pub(super) fn run(config_path: &Path) -> bool {
+    let report = validate(config_path);
+    for warning in &report.warnings {
+        println!("warning: {}", warning);
+    }
+    for error in &report.errors {
+        println!("error: {}", error);
+    }
+    if report.errors.is_empty() {
+        println!("{} is valid", config_path.display());
+        true
+    } else {
+        println!(
+            "{} is invalid: {} error(s) found",
+            config_path.display(),
+            report.errors.len()
+        );
+        false
+    }
+}
+
+fn validate(config_path: &Path) -> Report {
+    let mut report = Report::default();
+
+    // Parsing covers unknown fields and malformed values, such as an invalid trusted hash.
+    let config = match Cli::load(config_path, &[]) {
+        Ok(config) => config,
+        Err(error) => {
+            report.error(format!("could not load config: {:#}", error));
+            return report;
+        }
+    };
+    let root = config.dir();
+    let config = config.value();
+
+    check_chainspec(&mut report, root, config);
+
+    if config.node.mode.is_validator() {
+        if let Err(error) = config.consensus.load_keys(root) {
+            report.error(format!("could not load consensus secret key: {}", error));
+        }
+    }
+    if let Some(discovery) = &config.node.trusted_hash_discovery {
+        if discovery.min_agreement == 0 || discovery.min_agreement > discovery.peer_count {
+            report.error(format!(
+                "node.trusted_hash_discovery.min_agreement must be between 1 and peer_count ({})",
+                discovery.peer_count
+            ));
+        }
+    }
+    if let Err(error) = NetworkIdentity::from_config(WithDir::new(root, config.network.clone())) {
+        report.error(format!("could not load network identity: {}", error));
+    }
+
+    check_bindable(
+        &mut report,
+        "network.bind_address",
+        &config.network.bind_address,
+    );
+    if let Err(error) = utils::resolve_address(&config.network.public_address) {
+        report.error(format!("invalid network.public_address: {}", error));
+    }
+    if config.network.known_addresses.is_empty() {
+        report.warning("network.known_addresses is empty".to_string());
+    }
+    for known_address in &config.network.known_addresses {
+        if let Err(error) = utils::resolve_address(known_address) {
+            report.warning(format!(
+                "invalid entry in network.known_addresses: {}",
+                error
+            ));
+        }
+    }
+
+    if config.rest_server.enable_server {
+        check_bindable(
+            &mut report,
+            "rest_server.address",
+            &config.rest_server.address,
+        );
+    }
+    if config.rpc_server.enable_server {
+        check_bindable(
+            &mut report,
+            "rpc_server.address",
+            &config.rpc_server.address,
+        );
+    }
+    if config.speculative_exec_server.enable_server {
+        check_bindable(
+            &mut report,
+            "speculative_exec_server.address",
+            &config.speculative_exec_server.address,
+        );
+    }
+    if config.event_stream_server.enable_server {
+        check_bindable(
+            &mut report,
+            "event_stream_server.address",
+            &config.event_stream_server.address,
+        );
+    }
+
+    check_dir(&mut report, "storage.path", root.join(&config.storage.path));
+    if let Some(log_file) = &config.logging.file {
+        if let Some(dir) = root.join(&log_file.path).parent() {
+            check_dir(&mut report, "logging.file.path", dir.to_path_buf());
+        }
+    }
+    if let Some(event_log) = &config.event_stream_server.event_log {
+        check_dir(
+            &mut report,
+            "event_stream_server.event_log.path",
+            root.join(&event_log.path),
+        );
+    }
+    if config.diagnostics_port.enabled {
+        let socket_path = root.join(&config.diagnostics_port.socket_path);
+        if let Some(parent) = socket_path.parent() {
+            if !parent.is_dir() {
+                report.error(format!(
+                    "diagnostics_port.socket_path: directory {} does not exist",
+                    parent.display()
+                ));
+            }
+        }
+    }
+
+    report
+}
+
+/// Checks that the chainspec next to the config is valid and compatible with the config.
+fn check_chainspec(report: &mut Report, root: &Path, config: &main_reactor::Config) {
+    let chainspec = match <(Chainspec, ChainspecRawBytes)>::from_path(root) {
+        Ok((chainspec, _)) => chainspec,
+        Err(error) => {
+            report.error(format!("could not load chainspec: {}", error));
+            return;
+        }
+    };
+    if !chainspec.is_valid() {
+        report.error("invalid chainspec".to_string());
+    }
+    if config.deploy_acceptor.timestamp_leeway > chainspec.deploy_config.max_timestamp_leeway {
+        report.warning(format!(
+            "deploy_acceptor.timestamp_leeway of {} exceeds the chainspec's maximum of {} and \
+            will be reduced",
+            config.deploy_acceptor.timestamp_leeway, chainspec.deploy_config.max_timestamp_leeway
+        ));
+    }
+}
+
+/// Checks that `address` resolves and can be bound to.
+///
+/// An address already in use is only a warning, as it is usually held by the running node whose
+/// config is being validated.
+fn check_bindable(report: &mut Report, field: &str, address: &str) {
+    let socket_address = match utils::resolve_address(address) {
+        Ok(socket_address) => socket_address,
+        Err(error) => {
+            report.error(format!("invalid {}: {}", field, error));
+            return;
+        }
+    };
+    match TcpListener::bind(socket_address) {
+        Ok(_) => (),
+        Err(error) if error.kind() == io::ErrorKind::AddrInUse => report.warning(format!(
+            "{}: {} is already in use, possibly by a running node",
+            field, socket_address
+        )),
+        Err(error) => report.error(format!(
+            "{}: cannot bind to {}: {}",
+            field, socket_address, error
+        )),
+    }
+}
+
+/// Checks that `path` is either an existing directory or can be created as one.
+fn check_dir(report: &mut Report, field: &str, path: PathBuf) {
+    if path.is_dir() {
+        return;
+    }
+    if path.exists() {
+        report.error(format!("{}: {} is not a directory", field, path.display()));
+        return;
+    }
+    let mut maybe_ancestor = path.parent();
+    while let Some(ancestor) = maybe_ancestor {
+        if ancestor.is_dir() {
+            return;
+        }
+        if ancestor.exists() {
+            break;
+        }
+        maybe_ancestor = ancestor.parent();
+    }
+    report.error(format!(
+        "{}: {} cannot be created as a directory",
+        field,
+        path.display()
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_check_bindable() {
+        let mut report = Report::default();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        check_bindable(&mut report, "in_use", &address);
+        check_bindable(&mut report, "free", "127.0.0.1:0");
+        check_bindable(&mut report, "malformed", "not an address");
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("in_use:"));
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("invalid malformed:"));
+    }
+
+    #[test]
+    fn should_check_dir() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let file = tempdir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+
+        let mut report = Report::default();
+        check_dir(&mut report, "existing", tempdir.path().to_path_buf());
+        check_dir(&mut report, "creatable", tempdir.path().join("a/b"));
+        check_dir(&mut report, "file", file.clone());
+        check_dir(&mut report, "below_file", file.join("a"));
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors[0].starts_with("file:"));
+        assert!(report.errors[1].starts_with("below_file:"));
+    }
+}
@@ -47,6 +47,7 @@
 pub(crate) mod block_accumulator;
 pub(crate) mod block_synchronizer;
 pub(crate) mod block_validator;
+pub(crate) mod clock_monitor;
 pub mod consensus;
 pub mod contract_runtime;
 pub(crate) mod deploy_acceptor;
@@ -60,6 +61,7 @@ pub(crate) mod gossiper;
 pub mod in_memory_network;
 pub(crate) mod metrics;
 pub(crate) mod network;
+pub(crate) mod notifier;
 pub(crate) mod rest_server;
 pub mod rpc_server;
 pub(crate) mod shutdown_trigger;
@@ -11,7 +11,7 @@ mod sync_instruction;
 mod tests;
 
 use std::{
-    collections::{btree_map, BTreeMap, VecDeque},
+    collections::{btree_map, BTreeMap, BTreeSet, VecDeque},
     convert::TryInto,
     sync::Arc,
 };
@@ -513,6 +513,56 @@ impl BlockAccumulator {
             .map(|acceptor| acceptor.peers().iter().cloned().collect())
     }
 
+    /// Returns the hash and height of the highest block created within `max_block_age` which at
+    /// least `min_agreement` of the `validator_peers` have informed us about, along with the
+    /// number of those peers.
+    ///
+    /// Returns `None` until at least `peer_count` distinct validator peers have informed us about
+    /// such recent blocks.  Other peers are ignored, since node IDs cost nothing to create.  The
+    /// block's finality is not checked, as the validator weights required to do so are only known
+    /// once a trusted block has been synced.
+    pub(crate) fn peer_agreed_block(
+        &self,
+        validator_peers: &BTreeSet<NodeId>,
+        peer_count: usize,
+        min_agreement: usize,
+        max_block_age: TimeDiff,
+    ) -> Option<(BlockHash, u64, usize)> {
+        let recent_acceptors: Vec<_> = self
+            .block_acceptors
+            .values()
+            .filter_map(|acceptor| {
+                let timestamp = acceptor.block_timestamp()?;
+                let height = acceptor.block_height()?;
+                let agreeing_peers = acceptor.peers().intersection(validator_peers).count();
+                (timestamp.elapsed() <= max_block_age).then_some((acceptor, height, agreeing_peers))
+            })
+            .collect();
+        let reporting_peers: BTreeSet<_> = recent_acceptors
+            .iter()
+            .flat_map(|(acceptor, _, _)| acceptor.peers().intersection(validator_peers))
+            .collect();
+        if reporting_peers.len() < peer_count {
+            return None;
+        }
+        recent_acceptors
+            .into_iter()
+            .filter(|(_, _, agreeing_peers)| *agreeing_peers >= min_agreement)
+            .max_by_key(|(_, height, _)| *height)
+            .map(|(acceptor, height, agreeing_peers)| {
+                (acceptor.block_hash(), height, agreeing_peers)
+            })
+    }
+
+    /// Returns the timestamp of the newest block any peer has informed us about, if any.
+    pub(crate) fn highest_peer_block_timestamp(&self) -> Option<Timestamp> {
+        self.block_acceptors
+            .values()
+            .filter(|acceptor| !acceptor.peers().is_empty())
+            .filter_map(BlockAcceptor::block_timestamp)
+            .max()
+    }
+
     fn is_stale(&mut self) -> bool {
         // we expect to be receiving gossiped blocks from other nodes
         // if we haven't received any messages describing higher blocks
@@ -595,7 +645,12 @@ impl BlockAccumulator {
         }
     }
 
-    fn next_syncable_block_hash(&self, parent_block_hash: BlockHash) -> Option<BlockHash> {
+    /// Returns the hash of the child of the given block, if it is known and has sufficient
+    /// finality.
+    pub(crate) fn next_syncable_block_hash(
+        &self,
+        parent_block_hash: BlockHash,
+    ) -> Option<BlockHash> {
         let child_hash = self.block_children.get(&parent_block_hash)?;
         let block_acceptor = self.block_acceptors.get(child_hash)?;
         if block_acceptor.has_sufficient_finality() {
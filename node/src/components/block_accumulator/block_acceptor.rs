@@ -352,6 +352,12 @@ impl BlockAcceptor {
             .map(|meta_block| meta_block.block.header().height())
     }
 
+    pub(super) fn block_timestamp(&self) -> Option<Timestamp> {
+        self.meta_block
+            .as_ref()
+            .map(|meta_block| meta_block.block.header().timestamp())
+    }
+
     pub(super) fn block_hash(&self) -> BlockHash {
         self.block_hash
     }
@@ -388,6 +388,132 @@ fn acceptor_get_peers() {
     assert_eq!(acceptor.peers(), &BTreeSet::from([first_peer, second_peer]));
 }
 
+#[test]
+fn accumulator_peer_agreed_block() {
+    let mut rng = TestRng::new();
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let config = Config::default();
+    let block_time = config.purge_interval / 2;
+    let metrics_registry = Registry::new();
+    let mut accumulator = BlockAccumulator::new(
+        config,
+        validator_matrix,
+        1,
+        block_time,
+        VALIDATOR_SLOTS,
+        &metrics_registry,
+    )
+    .unwrap();
+    let max_block_age = TimeDiff::from_seconds(60);
+    let peers: Vec<_> = (0..3).map(|_| NodeId::random(&mut rng)).collect();
+    let validator_peers: BTreeSet<_> = peers.iter().copied().collect();
+    let sybils: Vec<_> = (0..3).map(|_| NodeId::random(&mut rng)).collect();
+
+    // Two peers agree on the lower block, only one reports the higher block.
+    let lower_block = generate_non_genesis_block(&mut rng);
+    let higher_block = generate_next_block(&mut rng, &lower_block);
+    let mut lower_acceptor = block_acceptor(lower_block.clone());
+    lower_acceptor.register_peer(peers[0]);
+    lower_acceptor.register_peer(peers[1]);
+    let mut higher_acceptor = block_acceptor(higher_block.clone());
+    higher_acceptor.register_peer(peers[2]);
+    // Peers that aren't validators don't count, no matter how many there are.
+    for sybil in &sybils {
+        higher_acceptor.register_peer(*sybil);
+    }
+    accumulator
+        .block_acceptors
+        .insert(*lower_block.hash(), lower_acceptor);
+    accumulator
+        .block_acceptors
+        .insert(*higher_block.hash(), higher_acceptor);
+
+    // Not enough peers have reported blocks yet.
+    assert_eq!(
+        accumulator.peer_agreed_block(&validator_peers, 4, 2, max_block_age),
+        None
+    );
+    // The highest block with sufficient agreement is chosen.
+    assert_eq!(
+        accumulator.peer_agreed_block(&validator_peers, 3, 2, max_block_age),
+        Some((*lower_block.hash(), lower_block.height(), 2))
+    );
+    assert_eq!(
+        accumulator.peer_agreed_block(&validator_peers, 3, 1, max_block_age),
+        Some((*higher_block.hash(), higher_block.height(), 1))
+    );
+    assert_eq!(
+        accumulator.peer_agreed_block(&validator_peers, 3, 3, max_block_age),
+        None
+    );
+
+    // Once the second peer also reports the higher block, it is chosen.
+    accumulator
+        .block_acceptors
+        .get_mut(higher_block.hash())
+        .unwrap()
+        .register_peer(peers[1]);
+    assert_eq!(
+        accumulator.peer_agreed_block(&validator_peers, 3, 2, max_block_age),
+        Some((*higher_block.hash(), higher_block.height(), 2))
+    );
+}
+
+#[test]
+fn accumulator_highest_peer_block_timestamp() {
+    let mut rng = TestRng::new();
+    let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    let config = Config::default();
+    let block_time = config.purge_interval / 2;
+    let metrics_registry = Registry::new();
+    let mut accumulator = BlockAccumulator::new(
+        config,
+        validator_matrix,
+        1,
+        block_time,
+        VALIDATOR_SLOTS,
+        &metrics_registry,
+    )
+    .unwrap();
+    assert_eq!(accumulator.highest_peer_block_timestamp(), None);
+
+    let lower_block = generate_non_genesis_block(&mut rng);
+    let higher_block = generate_next_block(&mut rng, &lower_block);
+    let mut lower_acceptor = block_acceptor(lower_block.clone());
+    lower_acceptor.register_peer(NodeId::random(&mut rng));
+    accumulator
+        .block_acceptors
+        .insert(*lower_block.hash(), lower_acceptor);
+    assert_eq!(
+        accumulator.highest_peer_block_timestamp(),
+        Some(lower_block.header().timestamp())
+    );
+
+    // A block no peer has informed us about is not taken into account.
+    accumulator
+        .block_acceptors
+        .insert(*higher_block.hash(), block_acceptor(higher_block.clone()));
+    assert_eq!(
+        accumulator.highest_peer_block_timestamp(),
+        Some(lower_block.header().timestamp())
+    );
+
+    accumulator
+        .block_acceptors
+        .get_mut(higher_block.hash())
+        .unwrap()
+        .register_peer(NodeId::random(&mut rng));
+    assert_eq!(
+        accumulator.highest_peer_block_timestamp(),
+        Some(
+            lower_block
+                .header()
+                .timestamp()
+                .max(higher_block.header().timestamp())
+        )
+    );
+}
+
 #[test]
 fn acceptor_register_finality_signature() {
     let mut rng = TestRng::new();
@@ -55,7 +55,8 @@ use crate::{
         sync_leap_validation_metadata::SyncLeapValidationMetaData, ApprovalsHashes, Block,
         BlockExecutionResultsOrChunk, BlockHash, BlockHeader, BlockSignatures, Chainspec, Deploy,
         FinalitySignature, FinalitySignatureId, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, SyncLeap, SyncLeapIdentifier, TrieOrChunk, ValidatorMatrix,
+        MetaBlockState, NodeId, SignedEraValidators, SyncLeap, SyncLeapIdentifier, TrieOrChunk,
+        ValidatorMatrix,
     },
     NodeRng,
 };
@@ -118,6 +119,7 @@ pub(crate) trait ReactorEvent:
     + From<FetcherRequest<TrieOrChunk>>
     + From<FetcherRequest<BlockExecutionResultsOrChunk>>
     + From<FetcherRequest<SyncLeap>>
+    + From<FetcherRequest<SignedEraValidators>>
     + From<BlockAccumulatorRequest>
     + From<PeerBehaviorAnnouncement>
     + From<StorageRequest>
@@ -143,6 +145,7 @@ impl<REv> ReactorEvent for REv where
         + From<FetcherRequest<TrieOrChunk>>
         + From<FetcherRequest<BlockExecutionResultsOrChunk>>
         + From<FetcherRequest<SyncLeap>>
+        + From<FetcherRequest<SignedEraValidators>>
         + From<BlockAccumulatorRequest>
         + From<PeerBehaviorAnnouncement>
         + From<StorageRequest>
@@ -538,6 +541,7 @@ impl BlockSynchronizer {
         let need_next_interval = self.config.need_next_interval.into();
         let mut results = Effects::new();
         let max_simultaneous_peers = self.max_simultaneous_peers;
+        let validator_matrix = self.validator_matrix.clone();
         let mut builder_needs_next = |builder: &mut BlockBuilder, chainspec: Arc<Chainspec>| {
             if builder.check_latch(latch_reset_interval)
                 || builder.is_finished()
@@ -719,10 +723,11 @@ impl BlockSynchronizer {
                     if builder.should_fetch_execution_state() {
                         builder.latch();
                         // the accumulator may or may not have peers for an older block,
-                        // so we're going to also get a random sampling from networking
+                        // so we're going to also get peers from networking; global state is
+                        // fetched in many small round trips, so prefer the most responsive ones
                         results.extend(
                             effect_builder
-                                .get_fully_connected_peers(max_simultaneous_peers as usize)
+                                .get_low_rtt_peers(max_simultaneous_peers as usize)
                                 .event(move |peers| Event::NetworkPeers(block_hash, peers)),
                         )
                     }
@@ -741,17 +746,41 @@ impl BlockSynchronizer {
                         era_id
                     );
                     builder.latch_by(peers.len());
-                    results.extend(peers.into_iter().flat_map(|node_id| {
-                        effect_builder
-                            .fetch::<SyncLeap>(
-                                SyncLeapIdentifier::sync_to_historical(builder.block_hash()),
-                                node_id,
-                                Box::new(SyncLeapValidationMetaData::from_chainspec(
-                                    chainspec.as_ref(),
-                                )),
-                            )
-                            .event(Event::SyncLeapFetched)
-                    }))
+                    // if we trust the validators of the preceding era, the validators of this one
+                    // can be proven by the preceding era's switch block; this doesn't hold for
+                    // the era in which the current protocol version activated, as the upgrade may
+                    // have changed the validator set
+                    let maybe_trusted_weights = era_id
+                        .predecessor()
+                        .filter(|_| era_id != chainspec.protocol_config.activation_point.era_id())
+                        .and_then(|predecessor| validator_matrix.validator_weights(predecessor));
+                    let block_hash = builder.block_hash();
+                    if let Some(trusted_weights) = maybe_trusted_weights {
+                        results.extend(peers.into_iter().flat_map(|node_id| {
+                            effect_builder
+                                .fetch::<SignedEraValidators>(
+                                    era_id,
+                                    node_id,
+                                    Box::new(trusted_weights.clone()),
+                                )
+                                .event(move |result| Event::SignedEraValidatorsFetched {
+                                    block_hash,
+                                    result,
+                                })
+                        }))
+                    } else {
+                        results.extend(peers.into_iter().flat_map(|node_id| {
+                            effect_builder
+                                .fetch::<SyncLeap>(
+                                    SyncLeapIdentifier::sync_to_historical(block_hash),
+                                    node_id,
+                                    Box::new(SyncLeapValidationMetaData::from_chainspec(
+                                        chainspec.as_ref(),
+                                    )),
+                                )
+                                .event(Event::SyncLeapFetched)
+                        }))
+                    }
                 }
                 NeedNext::SwitchToHaveStrictFinality(block_hash, _) => {
                     // Don't set the latch since this is an internal state transition
@@ -1025,6 +1054,52 @@ impl BlockSynchronizer {
         }
     }
 
+    fn signed_era_validators_fetched(
+        &mut self,
+        block_hash: BlockHash,
+        result: Result<FetchedData<SignedEraValidators>, FetcherError<SignedEraValidators>>,
+    ) {
+        let (maybe_era_validators, maybe_peer_id) = match result {
+            Ok(FetchedData::FromPeer { item, peer }) => {
+                debug!(
+                    "BlockSynchronizer: fetched validators of {} from peer {}",
+                    item.era_id(),
+                    peer
+                );
+                (Some(item), Some(peer))
+            }
+            Ok(FetchedData::FromStorage { item }) => (Some(item), None),
+            Err(err) => {
+                debug!(%err, "BlockSynchronizer: failed to fetch era validators");
+                if err.is_peer_fault() {
+                    (None, Some(*err.peer()))
+                } else {
+                    (None, None)
+                }
+            }
+        };
+        if let Some(era_validators) = &maybe_era_validators {
+            // the item has been validated against the trusted weights of the preceding era
+            if let Some(validator_weights) = era_validators.validator_weights() {
+                self.validator_matrix
+                    .register_validator_weights(era_validators.era_id(), validator_weights.clone());
+            }
+        }
+        let validator_matrix = &self.validator_matrix.clone();
+        if let Some(builder) = self.get_builder(block_hash, true) {
+            match (maybe_era_validators, maybe_peer_id) {
+                (None, Some(peer_id)) => builder.demote_peer(peer_id),
+                (None, None) => {}
+                (Some(_), maybe_peer_id) => {
+                    if let Some(peer_id) = maybe_peer_id {
+                        builder.promote_peer(peer_id);
+                    }
+                    builder.register_era_validator_weights(validator_matrix);
+                }
+            }
+        }
+    }
+
     fn global_state_synced(
         &mut self,
         block_hash: BlockHash,
@@ -1389,6 +1464,7 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     | Event::ApprovalsHashesFetched(_)
                     | Event::FinalitySignatureFetched(_)
                     | Event::SyncLeapFetched(_)
+                    | Event::SignedEraValidatorsFetched { .. }
                     | Event::GlobalStateSynced { .. }
                     | Event::GotExecutionResultsChecksum { .. }
                     | Event::DeployFetched { .. }
@@ -1551,6 +1627,10 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     self.sync_leap_fetched(result);
                     self.need_next(effect_builder, rng)
                 }
+                Event::SignedEraValidatorsFetched { block_hash, result } => {
+                    self.signed_era_validators_fetched(block_hash, result);
+                    self.need_next(effect_builder, rng)
+                }
                 // we use the existence of n execution results checksum as an expedient way to
                 // determine if a block is post-1.4
                 Event::GotExecutionResultsChecksum { block_hash, result } => {
@@ -16,7 +16,7 @@ use crate::{
     effect::requests::BlockSynchronizerRequest,
     types::{
         ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHash, BlockHeader, Deploy,
-        FinalitySignature, FinalizedBlock, LegacyDeploy, NodeId, SyncLeap,
+        FinalitySignature, FinalizedBlock, LegacyDeploy, NodeId, SignedEraValidators, SyncLeap,
     },
 };
 
@@ -47,6 +47,10 @@ pub(crate) enum Event {
     FinalitySignatureFetched(FetchResult<FinalitySignature>),
     #[from]
     SyncLeapFetched(FetchResult<SyncLeap>),
+    SignedEraValidatorsFetched {
+        block_hash: BlockHash,
+        result: FetchResult<SignedEraValidators>,
+    },
     GlobalStateSynced {
         block_hash: BlockHash,
         #[serde(skip_serializing)]
@@ -128,6 +132,13 @@ impl Display for Event {
             Event::SyncLeapFetched(Err(fetcher_error)) => {
                 write!(f, "{}", fetcher_error)
             }
+            Event::SignedEraValidatorsFetched {
+                block_hash: _,
+                result,
+            } => match result {
+                Ok(fetched_item) => write!(f, "{}", fetched_item),
+                Err(fetcher_error) => write!(f, "{}", fetcher_error),
+            },
             Event::GlobalStateSynced {
                 block_hash: _,
                 result,
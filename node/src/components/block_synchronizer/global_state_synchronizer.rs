@@ -22,7 +22,8 @@ use crate::{
     effect::{
         announcements::PeerBehaviorAnnouncement,
         requests::{
-            ContractRuntimeRequest, FetcherRequest, SyncGlobalStateRequest, TrieAccumulatorRequest,
+            ContractRuntimeRequest, FetcherRequest, NetworkInfoRequest, SyncGlobalStateRequest,
+            TrieAccumulatorRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
@@ -627,6 +628,7 @@ where
     REv: From<TrieAccumulatorRequest>
         + From<ContractRuntimeRequest>
         + From<FetcherRequest<TrieOrChunk>>
+        + From<NetworkInfoRequest>
         + From<PeerBehaviorAnnouncement>
         + Send,
 {
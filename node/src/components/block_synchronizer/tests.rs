@@ -29,8 +29,8 @@ use crate::{
     reactor::{EventQueueHandle, QueueKind, Scheduler},
     tls::KeyFingerprint,
     types::{
-        chainspec::LegacyRequiredFinality, BlockExecutionResultsOrChunkId, DeployId,
-        TestBlockBuilder, ValueOrChunk,
+        chainspec::LegacyRequiredFinality, ActivationPoint, BlockExecutionResultsOrChunkId,
+        BlockHeaderWithMetadata, DeployId, TestBlockBuilder, ValueOrChunk,
     },
     utils,
 };
@@ -52,6 +52,7 @@ enum MockReactorEvent {
     TrieOrChunkFetcherRequest(FetcherRequest<TrieOrChunk>),
     BlockExecutionResultsOrChunkFetcherRequest(FetcherRequest<BlockExecutionResultsOrChunk>),
     SyncLeapFetcherRequest(FetcherRequest<SyncLeap>),
+    SignedEraValidatorsFetcherRequest(FetcherRequest<SignedEraValidators>),
     ApprovalsHashesFetcherRequest(FetcherRequest<ApprovalsHashes>),
     NetworkInfoRequest(NetworkInfoRequest),
     BlockAccumulatorRequest(BlockAccumulatorRequest),
@@ -489,7 +490,7 @@ async fn synchronizer_doesnt_busy_loop_without_peers() {
         // Explicitly verify the two effects are indeed asking networking and accumulator for peers.
         assert_matches!(
             events[0],
-            MockReactorEvent::NetworkInfoRequest(NetworkInfoRequest::FullyConnectedPeers {
+            MockReactorEvent::NetworkInfoRequest(NetworkInfoRequest::LowRttPeers {
                 count,
                 ..
             }) if count == MAX_SIMULTANEOUS_PEERS as usize
@@ -659,12 +660,14 @@ async fn should_not_stall_after_registering_new_era_validator_weights() {
         "should return no effects while latched"
     );
 
-    // bleed off the event q, checking the expected event kind
+    // bleed off the event q, checking the expected event kind; if the random block is in era 1,
+    // its validators are fetched proven by the already known ones of era 0 instead of a sync leap
     for effect in effects {
         tokio::spawn(effect);
         let event = mock_reactor.crank().await;
         match event {
-            MockReactorEvent::SyncLeapFetcherRequest(_) => (),
+            MockReactorEvent::SyncLeapFetcherRequest(_)
+            | MockReactorEvent::SignedEraValidatorsFetcherRequest(_) => (),
             _ => panic!("unexpected event: {:?}", event),
         };
     }
@@ -718,6 +721,110 @@ async fn should_not_stall_after_registering_new_era_validator_weights() {
     );
 }
 
+#[tokio::test]
+async fn should_fetch_era_validators_proven_by_preceding_era() {
+    let mut rng = TestRng::new();
+    let mock_reactor = MockReactor::new();
+    let test_env =
+        TestEnv::random(&mut rng).with_block(TestBlockBuilder::new().era(5).build(&mut rng));
+    let peers = test_env.peers();
+    let block = test_env.block();
+    let block_hash = *block.hash();
+    let era_id = block.header().era_id();
+
+    // Set up a validator matrix which only knows the validators of the preceding era.
+    let mut validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+    validator_matrix.register_validator_weights(
+        era_id.predecessor().unwrap(),
+        iter::once((ALICE_PUBLIC_KEY.clone(), 100.into())).collect(),
+    );
+    let mut block_synchronizer =
+        BlockSynchronizer::new_initialized(&mut rng, validator_matrix.clone(), Config::default());
+    Arc::get_mut(&mut block_synchronizer.chainspec)
+        .unwrap()
+        .protocol_config
+        .activation_point = ActivationPoint::EraId(EraId::from(0));
+
+    block_synchronizer.register_block_by_hash(block_hash, true);
+    block_synchronizer.register_peers(block_hash, peers.clone());
+    block_synchronizer
+        .historical
+        .as_mut()
+        .expect("should have historical builder")
+        .register_block_header(block.header().clone(), None)
+        .expect("should register block header");
+
+    // The era validators should be fetched rather than a sync leap.
+    let effects = block_synchronizer.need_next(mock_reactor.effect_builder(), &mut rng);
+    assert_eq!(effects.len(), MAX_SIMULTANEOUS_PEERS as usize);
+    for effect in effects {
+        tokio::spawn(effect);
+        let event = mock_reactor.crank().await;
+        assert_matches!(
+            event,
+            MockReactorEvent::SignedEraValidatorsFetcherRequest(FetcherRequest {
+                id,
+                peer,
+                validation_metadata,
+                ..
+            }) if peers.contains(&peer)
+                && id == era_id
+                && validation_metadata.era_id() == era_id.predecessor().unwrap()
+        );
+    }
+
+    // Simulate a successful fetch of the switch block of the preceding era.
+    let next_era_validators: BTreeMap<_, _> = test_env
+        .validator_keys()
+        .iter()
+        .map(|key| (PublicKey::from(key.as_ref()), U512::from(100)))
+        .collect();
+    let switch_block = TestBlockBuilder::new()
+        .era(4)
+        .switch_block(true)
+        .next_era_validator_weights(next_era_validators.clone())
+        .build(&mut rng);
+    let era_validators = SignedEraValidators::new(BlockHeaderWithMetadata {
+        block_header: switch_block.header().clone(),
+        block_signatures: BlockSignatures::new(
+            *switch_block.hash(),
+            switch_block.header().era_id(),
+        ),
+    });
+    block_synchronizer.handle_event(
+        mock_reactor.effect_builder(),
+        &mut rng,
+        Event::SignedEraValidatorsFetched {
+            block_hash,
+            result: Ok(FetchedData::FromPeer {
+                item: Box::new(era_validators),
+                peer: peers[0],
+            }),
+        },
+    );
+
+    // The validators are registered, both in the matrix and in the builder.
+    let next_era_validators: Vec<_> = next_era_validators.into_keys().collect();
+    assert_eq!(validator_matrix.public_keys(&era_id), next_era_validators);
+    let need_next = block_synchronizer
+        .historical
+        .as_mut()
+        .expect("should have historical builder")
+        .block_acquisition_action(&mut rng, MAX_SIMULTANEOUS_PEERS)
+        .need_next();
+    match need_next {
+        NeedNext::FinalitySignatures(hash, era, missing_signatures) => {
+            assert_eq!((hash, era), (block_hash, era_id));
+            // signatures already requested after registering the validators are left out
+            assert!(!missing_signatures.is_empty());
+            assert!(missing_signatures
+                .iter()
+                .all(|key| next_era_validators.contains(key)));
+        }
+        other => panic!("should need finality sigs, got {:?}", other),
+    }
+}
+
 #[test]
 fn duplicate_register_block_not_allowed_if_builder_is_not_failed() {
     let mut rng = TestRng::new();
@@ -766,12 +873,12 @@ async fn historical_sync_gets_peers_form_both_connected_peers_and_accumulator()
     let events = mock_reactor.process_effects(effects).await;
 
     // The first thing the synchronizer should do is get peers.
-    // For the historical flow, the synchronizer will get a random sampling of the connected
-    // peers and also ask the accumulator to provide peers from which it has received information
-    // for the block that is being synchronized.
+    // For the historical flow, the synchronizer will get the connected peers with the lowest
+    // round-trip times and also ask the accumulator to provide peers from which it has received
+    // information for the block that is being synchronized.
     assert_matches!(
         events[0],
-        MockReactorEvent::NetworkInfoRequest(NetworkInfoRequest::FullyConnectedPeers {
+        MockReactorEvent::NetworkInfoRequest(NetworkInfoRequest::LowRttPeers {
             count,
             ..
         }) if count == MAX_SIMULTANEOUS_PEERS as usize
@@ -8,7 +8,6 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
-use rand::seq::SliceRandom;
 use serde::Serialize;
 use thiserror::Error;
 use tracing::{debug, error, trace, warn};
@@ -26,7 +25,7 @@ use crate::{
     },
     effect::{
         announcements::PeerBehaviorAnnouncement,
-        requests::{FetcherRequest, TrieAccumulatorRequest},
+        requests::{FetcherRequest, NetworkInfoRequest, TrieAccumulatorRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{NodeId, TrieOrChunk, TrieOrChunkId},
@@ -132,6 +131,8 @@ pub(super) struct TrieAccumulator {
 pub(crate) enum Event {
     #[from]
     Request(TrieAccumulatorRequest),
+    /// The request's peers have been ordered by round-trip time, lowest first.
+    PeersRanked(TrieAccumulatorRequest),
     TrieOrChunkFetched {
         id: TrieOrChunkId,
         fetch_result: FetchResult<TrieOrChunk>,
@@ -142,6 +143,7 @@ impl fmt::Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Event::Request(_) => write!(f, "trie fetcher request"),
+            Event::PeersRanked(_) => write!(f, "ranked peers for trie fetcher request"),
             Event::TrieOrChunkFetched { id, .. } => {
                 write!(f, "got a result for trie or chunk {}", id)
             }
@@ -259,24 +261,43 @@ impl TrieAccumulator {
 
 impl<REv> Component<REv> for TrieAccumulator
 where
-    REv: From<FetcherRequest<TrieOrChunk>> + From<PeerBehaviorAnnouncement> + Send,
+    REv: From<FetcherRequest<TrieOrChunk>>
+        + From<NetworkInfoRequest>
+        + From<PeerBehaviorAnnouncement>
+        + Send,
 {
     type Event = Event;
 
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        rng: &mut NodeRng,
+        _rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
         trace!(?event, "TrieAccumulator: handling event");
         match event {
             Event::Request(TrieAccumulatorRequest {
+                hash,
+                responder,
+                peers,
+            }) => {
+                // Tries are fetched in many small round trips, so prefer the most responsive
+                // peers.
+                effect_builder.rank_peers_by_rtt(peers).event(move |peers| {
+                    Event::PeersRanked(TrieAccumulatorRequest {
+                        hash,
+                        peers,
+                        responder,
+                    })
+                })
+            }
+            Event::PeersRanked(TrieAccumulatorRequest {
                 hash,
                 responder,
                 mut peers,
             }) => {
-                peers.shuffle(rng);
+                // Peers are tried from the back of the list.
+                peers.reverse();
                 let trie_id = TrieOrChunkId(0, hash);
                 let peer = match peers.last() {
                     Some(peer) => *peer,
@@ -12,9 +12,16 @@ use futures::channel::oneshot;
 #[derive(Debug)]
 enum ReactorEvent {
     FetcherRequest(FetcherRequest<TrieOrChunk>),
+    NetworkInfoRequest(NetworkInfoRequest),
     PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
 }
 
+impl From<NetworkInfoRequest> for ReactorEvent {
+    fn from(req: NetworkInfoRequest) -> ReactorEvent {
+        ReactorEvent::NetworkInfoRequest(req)
+    }
+}
+
 impl From<PeerBehaviorAnnouncement> for ReactorEvent {
     fn from(req: PeerBehaviorAnnouncement) -> ReactorEvent {
         ReactorEvent::PeerBehaviorAnnouncement(req)
@@ -266,3 +273,47 @@ async fn trie_returned_when_all_chunks_fetched() {
     let result_trie = receiver.await.unwrap().expect("Expected trie").trie;
     assert_eq!(*result_trie, TrieRaw::new(Bytes::from(data)));
 }
+
+#[tokio::test]
+async fn request_fetches_from_lowest_rtt_peer() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let mut trie_accumulator = TrieAccumulator::new();
+
+    let (_, chunk_ids, _) = test_chunks_with_proof(1);
+    let peers: Vec<NodeId> = (0..3).map(|_| NodeId::random(&mut rng)).collect();
+    let (sender, _receiver) = oneshot::channel();
+    let event = Event::Request(TrieAccumulatorRequest {
+        hash: *chunk_ids[0].digest(),
+        peers: peers.clone(),
+        responder: Responder::without_shutdown(sender),
+    });
+
+    // The request should make the trie accumulator ask the network to rank the peers.
+    let mut effects = trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    let join_handle = tokio::spawn(async move { effects.remove(0).await });
+    let ((_ancestor, reactor_event), _) = reactor.scheduler.pop().await;
+    match reactor_event {
+        ReactorEvent::NetworkInfoRequest(NetworkInfoRequest::RankByRtt {
+            peers: requested,
+            responder,
+        }) => {
+            assert_eq!(requested, peers);
+            // Respond with the last peer being the most responsive one.
+            responder
+                .respond(peers.iter().rev().copied().collect())
+                .await;
+        }
+        _ => unreachable!(),
+    }
+    let mut events = join_handle.await.unwrap();
+    assert_eq!(events.len(), 1);
+
+    // The first chunk should be fetched from the peer with the lowest round-trip time.
+    let mut effects =
+        trie_accumulator.handle_event(reactor.effect_builder(), &mut rng, events.remove(0));
+    assert_eq!(effects.len(), 1);
+    tokio::spawn(async move { effects.remove(0).await });
+    reactor.expect_fetch_event(&chunk_ids[0], &peers[2]).await;
+}
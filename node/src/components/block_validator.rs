@@ -3,9 +3,20 @@
 //! The block validator checks whether all the deploys included in the block payload exist, either
 //! locally or on the network.
 //!
+//! Missing deploys are fetched from the peer which sent us the proposed block (normally its
+//! proposer) first, falling back to any other peers which claim to hold them.
+//!
 //! When multiple requests are made to validate the same block payload, they will eagerly return
-//! true if valid, but only fail if all sources have been exhausted. This is only relevant when
-//! calling for validation of the same proposed block multiple times at the same time.
+//! true if valid, but only fail if all sources have been exhausted or the configured validation
+//! timeout has elapsed. This is only relevant when calling for validation of the same proposed
+//! block multiple times at the same time.
+//!
+//! A timer is set whenever the deadline for validating a block starts, so that a validation is
+//! answered once the timeout elapses even if no fetch responses arrive in the meantime.
+//!
+//! Once all deploys are present, their approvals are checked against the associated keys and
+//! deployment thresholds of their accounts in global state, and the block is invalid if any
+//! deploy's approvals are insufficient.
 
 mod config;
 mod event;
@@ -13,12 +24,15 @@ mod state;
 #[cfg(test)]
 mod tests;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use tracing::{debug, error, warn};
 
-use casper_types::Timestamp;
+use casper_types::{account::AccountHash, PublicKey, TimeDiff, Timestamp};
 
 use crate::{
     components::{
@@ -27,18 +41,20 @@ use crate::{
         Component,
     },
     effect::{
-        requests::{BlockValidationRequest, FetcherRequest, StorageRequest},
+        requests::{
+            BlockValidationRequest, ContractRuntimeRequest, FetcherRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{
-        ApprovalsHash, Chainspec, Deploy, DeployHashWithApprovals, DeployId, DeployOrTransferHash,
-        NodeId,
+        ApprovalsHash, Chainspec, Deploy, DeployHash, DeployHashWithApprovals, DeployId,
+        DeployOrTransferHash, NodeId,
     },
     NodeRng,
 };
 pub use config::Config;
 pub(crate) use event::Event;
-use state::{AddResponderResult, BlockValidationState, MaybeStartFetching};
+use state::{AddResponderResult, BlockValidationState, DeployAuthorization, MaybeStartFetching};
 
 const COMPONENT_NAME: &str = "block_validator";
 
@@ -70,6 +86,8 @@ pub(crate) struct BlockValidator {
     #[data_size(skip)]
     chainspec: Arc<Chainspec>,
     config: Config,
+    /// Approvals by any of these accounts always satisfy a deploy's account.
+    administrators: BTreeSet<AccountHash>,
     /// State of validation of a specific block.
     validation_states: HashMap<ProposedBlock<ClContext>, BlockValidationState>,
 }
@@ -77,9 +95,16 @@ pub(crate) struct BlockValidator {
 impl BlockValidator {
     /// Creates a new block validator instance.
     pub(crate) fn new(chainspec: Arc<Chainspec>, config: Config) -> Self {
+        let administrators = chainspec
+            .core_config
+            .administrators
+            .iter()
+            .map(PublicKey::to_account_hash)
+            .collect();
         BlockValidator {
             chainspec,
             config,
+            administrators,
             validation_states: HashMap::new(),
         }
     }
@@ -101,8 +126,18 @@ impl BlockValidator {
                 responder,
             } = request;
             debug!(%sender, %block, "already validating proposed block");
-            match state.add_responder(responder) {
-                AddResponderResult::Added => {}
+            let mut effects = match state.add_responder(responder, self.config.validation_timeout) {
+                AddResponderResult::Added { deadline_restarted } => {
+                    if deadline_restarted {
+                        set_validation_timer(
+                            effect_builder,
+                            block.clone(),
+                            self.config.validation_timeout,
+                        )
+                    } else {
+                        Effects::new()
+                    }
+                }
                 AddResponderResult::ValidationCompleted {
                     responder,
                     response_to_send,
@@ -110,10 +145,10 @@ impl BlockValidator {
                     debug!(%response_to_send, "proposed block validation already completed");
                     return MaybeHandled::Handled(responder.respond(response_to_send).ignore());
                 }
-            }
+            };
             state.add_holder(sender);
 
-            let effects = match state.start_fetching() {
+            effects.extend(match state.start_fetching() {
                 MaybeStartFetching::Start {
                     holder,
                     missing_deploys,
@@ -132,7 +167,7 @@ impl BlockValidator {
                     error!("proposed block validation already completed - noop");
                     Effects::new()
                 }
-            };
+            });
             MaybeHandled::Handled(effects)
         } else {
             MaybeHandled::NotHandled(request)
@@ -153,13 +188,26 @@ impl BlockValidator {
     {
         debug!(%sender, %block, "validating new proposed block");
         debug_assert!(!self.validation_states.contains_key(&block));
-        let (mut state, maybe_responder) =
-            BlockValidationState::new(&block, sender, responder, self.chainspec.as_ref());
+        let (mut state, maybe_responder) = BlockValidationState::new(
+            &block,
+            sender,
+            responder,
+            self.chainspec.as_ref(),
+            self.config.validation_timeout,
+        );
         let effects = match state.start_fetching() {
             MaybeStartFetching::Start {
                 holder,
                 missing_deploys,
-            } => fetch_deploys(effect_builder, holder, missing_deploys),
+            } => {
+                let mut effects = fetch_deploys(effect_builder, holder, missing_deploys);
+                effects.extend(set_validation_timer(
+                    effect_builder,
+                    block.clone(),
+                    self.config.validation_timeout,
+                ));
+                effects
+            }
             MaybeStartFetching::ValidationSucceeded => {
                 debug!("no deploys - block validation complete");
                 debug_assert!(maybe_responder.is_some());
@@ -219,7 +267,11 @@ impl BlockValidator {
         result: FetchResult<Deploy>,
     ) -> Effects<Event>
     where
-        REv: From<Event> + From<FetcherRequest<Deploy>> + Send,
+        REv: From<Event>
+            + From<FetcherRequest<Deploy>>
+            + From<StorageRequest>
+            + From<ContractRuntimeRequest>
+            + Send,
     {
         match &result {
             Ok(FetchedData::FromPeer { peer, .. }) => {
@@ -244,6 +296,20 @@ impl BlockValidator {
                         .flat_map(|state| state.try_mark_invalid(&dt_hash));
                     return respond(false, responders);
                 }
+                if let Err(error) = item.is_within_size_limits(&self.chainspec.deploy_config) {
+                    warn!(
+                        deploy = %item,
+                        %dt_hash,
+                        %error,
+                        "deploy exceeds size limits",
+                    );
+                    // Hard failure - change state to Invalid.
+                    let responders = self
+                        .validation_states
+                        .values_mut()
+                        .flat_map(|state| state.try_mark_invalid(&dt_hash));
+                    return respond(false, responders);
+                }
                 let deploy_footprint = match item.footprint() {
                     Ok(footprint) => footprint,
                     Err(error) => {
@@ -262,12 +328,23 @@ impl BlockValidator {
                     }
                 };
 
+                let now = Timestamp::now();
                 let mut effects = Effects::new();
-                for state in self.validation_states.values_mut() {
+                for (block, state) in self.validation_states.iter_mut() {
                     let responders = state.try_add_deploy_footprint(&dt_hash, &deploy_footprint);
-                    if !responders.is_empty() {
+                    if let Some(authorizations) = state.take_authorizations_to_check() {
+                        effects.extend(check_authorizations(
+                            effect_builder,
+                            block.clone(),
+                            authorizations,
+                            self.administrators.clone(),
+                        ));
+                    } else if !responders.is_empty() {
                         let is_valid = matches!(state, BlockValidationState::Valid(_));
                         effects.extend(respond(is_valid, responders));
+                    } else if state.timed_out(now) {
+                        debug!(%state, "timed out validating proposed block - responding `false`");
+                        effects.extend(respond(false, state.take_responders()));
                     }
                 }
                 effects
@@ -276,12 +353,22 @@ impl BlockValidator {
                 match error {
                     fetcher::Error::Absent { peer, .. }
                     | fetcher::Error::Rejected { peer, .. }
-                    | fetcher::Error::TimedOut { peer, .. } => {
+                    | fetcher::Error::TimedOut { peer, .. }
+                    | fetcher::Error::Undecodable { peer, .. } => {
                         // Soft failure - just mark the holder as failed and see if we can start
                         // fetching using a different holder.
+                        let now = Timestamp::now();
                         let mut effects = Effects::new();
                         self.validation_states.values_mut().for_each(|state| {
                             state.try_mark_holder_failed(&peer);
+                            if state.timed_out(now) {
+                                debug!(
+                                    %state,
+                                    "timed out validating proposed block - responding `false`"
+                                );
+                                effects.extend(respond(false, state.take_responders()));
+                                return;
+                            }
                             match state.start_fetching() {
                                 MaybeStartFetching::Start {
                                     holder,
@@ -325,6 +412,36 @@ impl BlockValidator {
             }
         }
     }
+
+    fn handle_validation_timeout(&mut self, block: &ProposedBlock<ClContext>) -> Effects<Event> {
+        match self.validation_states.get_mut(block) {
+            Some(state) if state.timed_out(Timestamp::now()) => {
+                debug!(%state, "timed out validating proposed block - responding `false`");
+                respond(false, state.take_responders())
+            }
+            Some(_) | None => Effects::new(),
+        }
+    }
+
+    fn handle_authorizations_checked(
+        &mut self,
+        block: &ProposedBlock<ClContext>,
+        unauthorized: HashSet<DeployHash>,
+    ) -> Effects<Event> {
+        let state = match self.validation_states.get_mut(block) {
+            Some(state) => state,
+            None => return Effects::new(),
+        };
+        let authorized = unauthorized.is_empty();
+        if !authorized {
+            warn!(
+                %block,
+                ?unauthorized,
+                "proposed block contains deploys with insufficient approvals"
+            );
+        }
+        respond(authorized, state.authorizations_checked(authorized))
+    }
 }
 
 impl<REv> Component<REv> for BlockValidator
@@ -333,6 +450,7 @@ where
         + From<BlockValidationRequest>
         + From<FetcherRequest<Deploy>>
         + From<StorageRequest>
+        + From<ContractRuntimeRequest>
         + Send,
 {
     type Event = Event;
@@ -355,6 +473,11 @@ where
             Event::DeployFetched { dt_hash, result } => {
                 self.handle_deploy_fetched(effect_builder, dt_hash, result)
             }
+            Event::ValidationTimeout { block } => self.handle_validation_timeout(&block),
+            Event::AuthorizationsChecked {
+                block,
+                unauthorized,
+            } => self.handle_authorizations_checked(&block, unauthorized),
         }
     }
 
@@ -363,6 +486,20 @@ where
     }
 }
 
+/// Raises a `ValidationTimeout` event for the given block once `validation_timeout` has elapsed.
+fn set_validation_timer<REv>(
+    effect_builder: EffectBuilder<REv>,
+    block: ProposedBlock<ClContext>,
+    validation_timeout: TimeDiff,
+) -> Effects<Event>
+where
+    REv: Send,
+{
+    effect_builder
+        .set_timeout(validation_timeout.into())
+        .event(move |_| Event::ValidationTimeout { block })
+}
+
 fn fetch_deploys<REv>(
     effect_builder: EffectBuilder<REv>,
     holder: NodeId,
@@ -382,6 +519,25 @@ where
         .collect()
 }
 
+/// Checks the deploys' approvals against their accounts in global state, raising an
+/// `AuthorizationsChecked` event with the deploys whose approvals are insufficient.
+fn check_authorizations<REv>(
+    effect_builder: EffectBuilder<REv>,
+    block: ProposedBlock<ClContext>,
+    authorizations: Vec<DeployAuthorization>,
+    administrators: BTreeSet<AccountHash>,
+) -> Effects<Event>
+where
+    REv: From<StorageRequest> + From<ContractRuntimeRequest> + Send,
+{
+    effect_builder
+        .get_unauthorized_deploys(authorizations, administrators)
+        .event(move |unauthorized| Event::AuthorizationsChecked {
+            block,
+            unauthorized,
+        })
+}
+
 fn respond(
     is_valid: bool,
     responders: impl IntoIterator<Item = Responder<bool>>,
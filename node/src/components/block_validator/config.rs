@@ -1,16 +1,29 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::TimeDiff;
+
+const DEFAULT_VALIDATION_TIMEOUT_SECS: u32 = 60;
+
 /// Configuration options for block validation.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     pub max_completed_entries: u32,
+    /// Maximum time to wait for the deploys of a proposed block to be fetched before responding
+    /// that the block could not be validated.
+    #[serde(default = "default_validation_timeout")]
+    pub validation_timeout: TimeDiff,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             max_completed_entries: 3,
+            validation_timeout: default_validation_timeout(),
         }
     }
 }
+
+fn default_validation_timeout() -> TimeDiff {
+    TimeDiff::from_seconds(DEFAULT_VALIDATION_TIMEOUT_SECS)
+}
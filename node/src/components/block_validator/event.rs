@@ -1,9 +1,14 @@
+use std::collections::HashSet;
+
 use derive_more::{Display, From};
 
 use crate::{
-    components::fetcher::FetchResult,
+    components::{
+        consensus::{ClContext, ProposedBlock},
+        fetcher::FetchResult,
+    },
     effect::requests::BlockValidationRequest,
-    types::{Deploy, DeployOrTransferHash},
+    types::{Deploy, DeployHash, DeployOrTransferHash},
 };
 
 #[derive(Debug, From, Display)]
@@ -16,4 +21,15 @@ pub(crate) enum Event {
         dt_hash: DeployOrTransferHash,
         result: FetchResult<Deploy>,
     },
+
+    /// The deadline for validating the given block may have passed.
+    #[display(fmt = "validation of {} may have timed out", block)]
+    ValidationTimeout { block: ProposedBlock<ClContext> },
+
+    /// The approvals of the given block's deploys have been checked against their accounts.
+    #[display(fmt = "checked deploy authorizations of {}", block)]
+    AuthorizationsChecked {
+        block: ProposedBlock<ClContext>,
+        unauthorized: HashSet<DeployHash>,
+    },
 }
@@ -7,19 +7,20 @@ use std::{
 use datasize::DataSize;
 use tracing::{debug, error, warn};
 
-use casper_types::Timestamp;
+use casper_types::{account::AccountHash, PublicKey, TimeDiff, Timestamp};
 
-#[cfg(test)]
-use crate::types::DeployHash;
 use crate::{
     components::consensus::{ClContext, ProposedBlock},
     effect::Responder,
     types::{
         appendable_block::AppendableBlock, Approval, ApprovalsHash, Chainspec, DeployFootprint,
-        DeployHashWithApprovals, DeployOrTransferHash, NodeId,
+        DeployHash, DeployHashWithApprovals, DeployOrTransferHash, NodeId,
     },
 };
 
+/// A deploy's hash and account, along with the account hashes of its approvals' signers.
+pub(super) type DeployAuthorization = (DeployHash, PublicKey, BTreeSet<AccountHash>);
+
 /// The state of a peer which claims to be a holder of the deploys.
 #[derive(Clone, Copy, Eq, PartialEq, DataSize, Debug)]
 pub(super) enum HolderState {
@@ -34,8 +35,9 @@ pub(super) enum HolderState {
 
 /// The return type of `BlockValidationState::add_responder`.
 pub(super) enum AddResponderResult {
-    /// The responder was added, meaning validation is still ongoing.
-    Added,
+    /// The responder was added, meaning validation is still ongoing.  If there were no responders
+    /// awaiting an answer, the deadline was restarted.
+    Added { deadline_restarted: bool },
     /// Validation is completed, so the responder should be called with the provided value.
     ValidationCompleted {
         responder: Responder<bool>,
@@ -88,10 +90,28 @@ pub(super) enum BlockValidationState {
         /// The set of approvals contains approvals from deploys that would be finalized with the
         /// block.
         missing_deploys: HashMap<DeployOrTransferHash, ApprovalInfo>,
+        /// The peer which first sent us the proposed block, typically its proposer.  It is asked
+        /// for missing deploys before any other holder.
+        proposer: NodeId,
         /// The set of peers which each claim to hold all the deploys.
         holders: HashMap<NodeId, HolderState>,
         /// A list of responders that are awaiting an answer.
         responders: Vec<Responder<bool>>,
+        /// The time after which the current responders are answered `false` rather than waiting
+        /// for further fetch attempts.
+        deadline: Timestamp,
+        /// The authorizations of the deploys added to the appendable block so far.
+        authorizations: Vec<DeployAuthorization>,
+    },
+    /// All deploys are present and fit in the block, and their approvals are being checked against
+    /// their accounts' associated keys and deployment thresholds.
+    CheckingAuthorizations {
+        /// The timestamp of the proposed block.
+        timestamp: Timestamp,
+        /// The authorizations to check, or `None` once the check has been started.
+        authorizations: Option<Vec<DeployAuthorization>>,
+        /// A list of responders that are awaiting an answer.
+        responders: Vec<Responder<bool>>,
     },
     /// The proposed block with the given timestamp is valid.
     Valid(Timestamp),
@@ -114,6 +134,7 @@ impl BlockValidationState {
         sender: NodeId,
         responder: Responder<bool>,
         chainspec: &Chainspec,
+        validation_timeout: TimeDiff,
     ) -> (Self, Option<Responder<bool>>) {
         let deploy_count = block.deploys().len() + block.transfers().len();
         if deploy_count == 0 {
@@ -163,23 +184,44 @@ impl BlockValidationState {
         let state = BlockValidationState::InProgress {
             appendable_block,
             missing_deploys,
+            proposer: sender,
             holders: iter::once((sender, HolderState::Unasked)).collect(),
             responders: vec![responder],
+            deadline: Timestamp::now() + validation_timeout,
+            authorizations: vec![],
         };
 
         (state, None)
     }
 
     /// Adds the given responder to the collection if the current state is `InProgress` and returns
-    /// `Added`.
+    /// `Added`.  If there were no responders awaiting an answer, the deadline is restarted.
     ///
     /// If the state is not `InProgress`, `ValidationCompleted` is returned with the responder and
     /// the value which should be provided to the responder.
-    pub(super) fn add_responder(&mut self, responder: Responder<bool>) -> AddResponderResult {
+    pub(super) fn add_responder(
+        &mut self,
+        responder: Responder<bool>,
+        validation_timeout: TimeDiff,
+    ) -> AddResponderResult {
         match self {
-            BlockValidationState::InProgress { responders, .. } => {
+            BlockValidationState::InProgress {
+                responders,
+                deadline,
+                ..
+            } => {
+                let deadline_restarted = responders.is_empty();
+                if deadline_restarted {
+                    *deadline = Timestamp::now() + validation_timeout;
+                }
+                responders.push(responder);
+                AddResponderResult::Added { deadline_restarted }
+            }
+            BlockValidationState::CheckingAuthorizations { responders, .. } => {
                 responders.push(responder);
-                AddResponderResult::Added
+                AddResponderResult::Added {
+                    deadline_restarted: false,
+                }
             }
             BlockValidationState::Valid(_) => AddResponderResult::ValidationCompleted {
                 responder,
@@ -212,6 +254,9 @@ impl BlockValidationState {
                     entry.insert(HolderState::Unasked);
                 }
             },
+            BlockValidationState::CheckingAuthorizations { .. } => {
+                debug!(state = %self, "all deploys already held - not adding holder");
+            }
             BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => {
                 error!(state = %self, "unexpected state when adding holder");
             }
@@ -231,15 +276,17 @@ impl BlockValidationState {
 
     /// Returns fetch info based on the current state:
     ///   * if `InProgress` and there are no holders `Asked` (i.e. no ongoing fetches) and at least
-    ///     one `Unasked` holder, returns `Start`
+    ///     one `Unasked` holder, returns `Start`, preferring the proposer if it is `Unasked`
     ///   * if `InProgress` and any holder `Asked`, returns `Ongoing`
     ///   * if `InProgress` and all holders `Failed`, returns `Unable`
+    ///   * if `CheckingAuthorizations`, returns `Ongoing`
     ///   * if `Valid` or `Invalid`, returns `ValidationSucceeded` or `ValidationFailed`
     ///     respectively
     pub(super) fn start_fetching(&mut self) -> MaybeStartFetching {
         match self {
             BlockValidationState::InProgress {
                 missing_deploys,
+                proposer,
                 holders,
                 ..
             } => {
@@ -259,9 +306,13 @@ impl BlockValidationState {
                     }
                 }
 
-                let holder = match unasked {
-                    Some(peer) => peer,
-                    None => return MaybeStartFetching::Unable,
+                let holder = if holders.get(proposer) == Some(&HolderState::Unasked) {
+                    *proposer
+                } else {
+                    match unasked {
+                        Some(peer) => peer,
+                        None => return MaybeStartFetching::Unable,
+                    }
                 };
                 // Mark the holder as `Asked`.  Safe to `expect` as we just found the entry above.
                 *holders.get_mut(&holder).expect("must be in set") = HolderState::Asked;
@@ -274,6 +325,7 @@ impl BlockValidationState {
                     missing_deploys,
                 }
             }
+            BlockValidationState::CheckingAuthorizations { .. } => MaybeStartFetching::Ongoing,
             BlockValidationState::Valid(_) => MaybeStartFetching::ValidationSucceeded,
             BlockValidationState::Invalid(_) => MaybeStartFetching::ValidationFailed,
         }
@@ -281,13 +333,34 @@ impl BlockValidationState {
 
     pub(super) fn take_responders(&mut self) -> Vec<Responder<bool>> {
         match self {
-            BlockValidationState::InProgress { responders, .. } => mem::take(responders),
+            BlockValidationState::InProgress { responders, .. }
+            | BlockValidationState::CheckingAuthorizations { responders, .. } => {
+                mem::take(responders)
+            }
             BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => vec![],
         }
     }
 
+    /// Returns `true` if the current state is `InProgress`, has responders awaiting an answer and
+    /// its deadline has passed.
+    pub(super) fn timed_out(&self, now: Timestamp) -> bool {
+        match self {
+            BlockValidationState::InProgress {
+                responders,
+                deadline,
+                ..
+            } => !responders.is_empty() && *deadline <= now,
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => false,
+        }
+    }
+
     /// If the current state is `InProgress` and `dt_hash` is present, tries to add the footprint to
     /// the appendable block to continue validation of the proposed block.
+    ///
+    /// Once all deploys have been added, the state becomes `CheckingAuthorizations`, and the
+    /// deploys' authorizations should be checked via `take_authorizations_to_check`.
     pub(super) fn try_add_deploy_footprint(
         &mut self,
         dt_hash: &DeployOrTransferHash,
@@ -298,6 +371,7 @@ impl BlockValidationState {
                 appendable_block,
                 missing_deploys,
                 responders,
+                authorizations,
                 ..
             } => {
                 let approvals_info = match missing_deploys.remove(dt_hash) {
@@ -307,6 +381,11 @@ impl BlockValidationState {
                         return vec![];
                     }
                 };
+                let authorization_keys = approvals_info
+                    .approvals
+                    .iter()
+                    .map(|approval| approval.signer().to_account_hash())
+                    .collect();
                 // Try adding the footprint to the appendable block to see if the block remains
                 // valid.
                 let dhwa =
@@ -319,6 +398,11 @@ impl BlockValidationState {
                 };
                 match add_result {
                     Ok(()) => {
+                        authorizations.push((
+                            *dt_hash.deploy_hash(),
+                            footprint.header.account().clone(),
+                            authorization_keys,
+                        ));
                         if !missing_deploys.is_empty() {
                             // The appendable block is still valid, but we still have missing
                             // deploys - nothing further to do here.
@@ -331,10 +415,14 @@ impl BlockValidationState {
                         }
                         debug!(
                             block_timestamp = %appendable_block.timestamp(),
-                            "no further missing deploys - block validation complete"
+                            "no further missing deploys - checking deploy authorizations"
                         );
-                        let new_state = BlockValidationState::Valid(appendable_block.timestamp());
-                        (new_state, mem::take(responders))
+                        let new_state = BlockValidationState::CheckingAuthorizations {
+                            timestamp: appendable_block.timestamp(),
+                            authorizations: Some(mem::take(authorizations)),
+                            responders: mem::take(responders),
+                        };
+                        (new_state, vec![])
                     }
                     Err(error) => {
                         warn!(%dt_hash, ?footprint, %error, "block invalid");
@@ -343,12 +431,48 @@ impl BlockValidationState {
                     }
                 }
             }
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => return vec![],
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => return vec![],
         };
         *self = new_state;
         responders
     }
 
+    /// If the current state is `CheckingAuthorizations` and the check hasn't been started yet,
+    /// returns the authorizations to check.
+    pub(super) fn take_authorizations_to_check(&mut self) -> Option<Vec<DeployAuthorization>> {
+        match self {
+            BlockValidationState::CheckingAuthorizations { authorizations, .. } => {
+                authorizations.take()
+            }
+            BlockValidationState::InProgress { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => None,
+        }
+    }
+
+    /// If the current state is `CheckingAuthorizations`, sets the state to `Valid` if all the
+    /// deploys are `authorized` or `Invalid` otherwise, and returns the responders.
+    pub(super) fn authorizations_checked(&mut self, authorized: bool) -> Vec<Responder<bool>> {
+        let (timestamp, responders) = match self {
+            BlockValidationState::CheckingAuthorizations {
+                timestamp,
+                responders,
+                ..
+            } => (*timestamp, mem::take(responders)),
+            BlockValidationState::InProgress { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => return vec![],
+        };
+        *self = if authorized {
+            BlockValidationState::Valid(timestamp)
+        } else {
+            BlockValidationState::Invalid(timestamp)
+        };
+        responders
+    }
+
     /// If the current state is `InProgress` and `dt_hash` is present, sets the state to `Invalid`
     /// and returns the responders.
     pub(super) fn try_mark_invalid(
@@ -367,7 +491,9 @@ impl BlockValidationState {
                 }
                 (appendable_block.timestamp(), mem::take(responders))
             }
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => return vec![],
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => return vec![],
         };
         *self = BlockValidationState::Valid(timestamp);
         responders
@@ -375,7 +501,8 @@ impl BlockValidationState {
 
     pub(super) fn block_timestamp_if_completed(&self) -> Option<Timestamp> {
         match self {
-            BlockValidationState::InProgress { .. } => None,
+            BlockValidationState::InProgress { .. }
+            | BlockValidationState::CheckingAuthorizations { .. } => None,
             BlockValidationState::Valid(timestamp) | BlockValidationState::Invalid(timestamp) => {
                 Some(*timestamp)
             }
@@ -391,7 +518,9 @@ impl BlockValidationState {
                 .keys()
                 .map(|dt_hash| *dt_hash.deploy_hash())
                 .collect(),
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => vec![],
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => vec![],
         }
     }
 
@@ -399,21 +528,28 @@ impl BlockValidationState {
     pub(super) fn holders_mut(&mut self) -> Option<&mut HashMap<NodeId, HolderState>> {
         match self {
             BlockValidationState::InProgress { holders, .. } => Some(holders),
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => None,
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => None,
         }
     }
 
     #[cfg(test)]
     pub(super) fn responder_count(&self) -> usize {
         match self {
-            BlockValidationState::InProgress { responders, .. } => responders.len(),
+            BlockValidationState::InProgress { responders, .. }
+            | BlockValidationState::CheckingAuthorizations { responders, .. } => responders.len(),
             BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => 0,
         }
     }
 
     #[cfg(test)]
     pub(super) fn completed(&self) -> bool {
-        !matches!(self, BlockValidationState::InProgress { .. })
+        !matches!(
+            self,
+            BlockValidationState::InProgress { .. }
+                | BlockValidationState::CheckingAuthorizations { .. }
+        )
     }
 }
 
@@ -425,6 +561,7 @@ impl Display for BlockValidationState {
                 missing_deploys,
                 holders,
                 responders,
+                ..
             } => {
                 write!(
                     formatter,
@@ -435,6 +572,18 @@ impl Display for BlockValidationState {
                     responders.len()
                 )
             }
+            BlockValidationState::CheckingAuthorizations {
+                timestamp,
+                responders,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "BlockValidationState::CheckingAuthorizations({}, {} responders)",
+                    timestamp,
+                    responders.len()
+                )
+            }
             BlockValidationState::Valid(timestamp) => {
                 write!(formatter, "BlockValidationState::Valid({timestamp})")
             }
@@ -521,6 +670,7 @@ mod tests {
                 NodeId::random(&mut self.rng),
                 new_responder(),
                 &self.chainspec,
+                TimeDiff::from_seconds(60),
             )
         }
 
@@ -591,6 +741,7 @@ mod tests {
             NodeId::random(&mut fixture.rng),
             new_responder(),
             &fixture.chainspec,
+            TimeDiff::from_seconds(60),
         );
 
         assert!(matches!(state, BlockValidationState::Invalid(_)));
@@ -616,9 +767,9 @@ mod tests {
                 assert_eq!(holders.values().next().unwrap(), &HolderState::Unasked);
                 assert_eq!(responders.len(), 1);
             }
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => {
-                panic!("unexpected state")
-            }
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => panic!("unexpected state"),
         }
         assert!(maybe_responder.is_none());
     }
@@ -630,15 +781,20 @@ mod tests {
         assert!(matches!(state, BlockValidationState::InProgress { .. }));
         assert_eq!(state.responder_count(), 1);
 
-        let add_responder_result = state.add_responder(new_responder());
-        assert!(matches!(add_responder_result, AddResponderResult::Added));
+        let add_responder_result = state.add_responder(new_responder(), TimeDiff::from_seconds(60));
+        assert!(matches!(
+            add_responder_result,
+            AddResponderResult::Added {
+                deadline_restarted: false
+            }
+        ));
         assert_eq!(state.responder_count(), 2);
     }
 
     #[test]
     fn should_not_add_responder_if_valid() {
         let mut state = BlockValidationState::Valid(Timestamp::from(1000));
-        let add_responder_result = state.add_responder(new_responder());
+        let add_responder_result = state.add_responder(new_responder(), TimeDiff::from_seconds(60));
         assert!(matches!(
             add_responder_result,
             AddResponderResult::ValidationCompleted {
@@ -652,7 +808,7 @@ mod tests {
     #[test]
     fn should_not_add_responder_if_invalid() {
         let mut state = BlockValidationState::Invalid(Timestamp::from(1000));
-        let add_responder_result = state.add_responder(new_responder());
+        let add_responder_result = state.add_responder(new_responder(), TimeDiff::from_seconds(60));
         assert!(matches!(
             add_responder_result,
             AddResponderResult::ValidationCompleted {
@@ -856,14 +1012,62 @@ mod tests {
             ));
         }
 
-        // The final deploy should cause the state to go to `Valid` and the responders to be
-        // returned.
+        // The final deploy should cause the state to go to `CheckingAuthorizations`, still without
+        // returning responders.
         let (dt_hash, footprint) = footprints.pop().unwrap();
         let responders = state.try_add_deploy_footprint(&dt_hash, &footprint);
+        assert!(responders.is_empty());
+        assert!(matches!(
+            state,
+            BlockValidationState::CheckingAuthorizations { .. }
+        ));
+
+        // The authorizations of all deploys should be taken exactly once.
+        let authorizations = state.take_authorizations_to_check().unwrap();
+        assert_eq!(authorizations.len(), 4);
+        assert!(state.take_authorizations_to_check().is_none());
+        assert_eq!(state.start_fetching(), MaybeStartFetching::Ongoing);
+
+        // Once the deploys are found to be authorized, the state should go to `Valid` and the
+        // responders should be returned.
+        let responders = state.authorizations_checked(true);
         assert_eq!(responders.len(), 1);
         assert!(matches!(state, BlockValidationState::Valid(_)));
     }
 
+    #[test]
+    fn state_should_change_to_invalid_if_unauthorized() {
+        let mut fixture = Fixture::new();
+        let (mut state, _maybe_responder) = fixture.new_state(2, 2);
+        for (dt_hash, footprint) in fixture.footprints() {
+            let responders = state.try_add_deploy_footprint(&dt_hash, &footprint);
+            assert!(responders.is_empty());
+        }
+        let authorizations = state.take_authorizations_to_check().unwrap();
+        for (deploy_hash, public_key, authorization_keys) in authorizations {
+            let deploy = fixture
+                .deploys
+                .iter()
+                .chain(fixture.transfers.iter())
+                .find(|deploy| *deploy.hash() == deploy_hash)
+                .unwrap();
+            assert_eq!(&public_key, deploy.header().account());
+            assert!(authorization_keys.contains(&public_key.to_account_hash()));
+        }
+
+        // A responder added while checking authorizations should also be answered.
+        let add_responder_result = state.add_responder(new_responder(), TimeDiff::from_seconds(60));
+        assert!(matches!(
+            add_responder_result,
+            AddResponderResult::Added {
+                deadline_restarted: false
+            }
+        ));
+        let responders = state.authorizations_checked(false);
+        assert_eq!(responders.len(), 2);
+        assert!(matches!(state, BlockValidationState::Invalid(_)));
+    }
+
     #[test]
     fn unrelated_deploy_added_should_not_change_state() {
         let mut fixture = Fixture::new();
@@ -879,9 +1083,9 @@ mod tests {
                 missing_deploys.clone(),
                 holders.clone(),
             ),
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => {
-                panic!("unexpected state")
-            }
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => panic!("unexpected state"),
         };
 
         // Create a new, random deploy.
@@ -903,9 +1107,9 @@ mod tests {
                 assert_eq!(&missing_deploys_before, missing_deploys);
                 assert_eq!(&holders_before, holders);
             }
-            BlockValidationState::Valid(_) | BlockValidationState::Invalid(_) => {
-                panic!("unexpected state")
-            }
+            BlockValidationState::CheckingAuthorizations { .. }
+            | BlockValidationState::Valid(_)
+            | BlockValidationState::Invalid(_) => panic!("unexpected state"),
         };
     }
 
@@ -938,4 +1142,43 @@ mod tests {
         assert_eq!(responders.len(), 1);
         assert!(matches!(state, BlockValidationState::Invalid(_)));
     }
+
+    #[test]
+    fn start_fetching_should_prefer_proposer() {
+        let mut fixture = Fixture::new();
+        let (mut state, _maybe_responder) = fixture.new_state(2, 2);
+        let proposer = *state.holders_mut().unwrap().keys().next().unwrap();
+        for _ in 0..3 {
+            state.add_holder(NodeId::random(&mut fixture.rng));
+        }
+
+        match state.start_fetching() {
+            MaybeStartFetching::Start { holder, .. } => assert_eq!(holder, proposer),
+            _ => panic!("unexpected return value"),
+        }
+    }
+
+    #[test]
+    fn should_time_out_after_deadline() {
+        let mut fixture = Fixture::new();
+        let (mut state, _maybe_responder) = fixture.new_state(2, 2);
+        let now = Timestamp::now();
+        assert!(!state.timed_out(now));
+        assert!(state.timed_out(now + TimeDiff::from_seconds(61)));
+
+        // Once the responders have been answered, the state should no longer be timed out, and
+        // adding a new responder should restart the deadline.
+        let _ = state.take_responders();
+        assert!(!state.timed_out(now + TimeDiff::from_seconds(61)));
+        let add_responder_result =
+            state.add_responder(new_responder(), TimeDiff::from_seconds(120));
+        assert!(matches!(
+            add_responder_result,
+            AddResponderResult::Added {
+                deadline_restarted: true
+            }
+        ));
+        assert!(!state.timed_out(now + TimeDiff::from_seconds(61)));
+        assert!(state.timed_out(now + TimeDiff::from_seconds(300)));
+    }
 }
@@ -1,13 +1,15 @@
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{BTreeMap, HashSet, VecDeque},
+    iter,
     sync::Arc,
     time::Duration,
 };
 
 use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
 use casper_types::{
-    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, testing::TestRng,
-    RuntimeArgs, SecretKey, TimeDiff, U512,
+    account::Account, bytesrepr::Bytes, contracts::NamedKeys, runtime_args,
+    system::standard_payment::ARG_AMOUNT, testing::TestRng, AccessRights, RuntimeArgs, SecretKey,
+    TimeDiff, URef, U512,
 };
 use derive_more::From;
 use itertools::Itertools;
@@ -18,7 +20,7 @@ use crate::{
         fetcher::{self, FetchItem},
     },
     reactor::{EventQueueHandle, QueueKind, Scheduler},
-    types::{BlockPayload, ChainspecRawBytes, DeployHash, DeployHashWithApprovals},
+    types::{Block, BlockPayload, ChainspecRawBytes, DeployHash, DeployHashWithApprovals},
     utils::{self, Loadable},
 };
 
@@ -32,6 +34,8 @@ enum ReactorEvent {
     Fetcher(FetcherRequest<Deploy>),
     #[from]
     Storage(StorageRequest),
+    #[from]
+    ContractRuntime(ContractRuntimeRequest),
 }
 
 impl From<BlockValidationRequest> for ReactorEvent {
@@ -99,6 +103,51 @@ impl MockReactor {
             }
         }
     }
+
+    /// Answers the requests made while checking deploy authorizations, as if global state held
+    /// only the given `accounts`.
+    async fn expect_authorization_check(&self, rng: &mut TestRng, accounts: &[Account]) {
+        let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
+        if let ReactorEvent::Storage(StorageRequest::GetHighestCompleteBlockHeader { responder }) =
+            reactor_event
+        {
+            responder
+                .respond(Some(Block::random(rng).take_header()))
+                .await;
+        } else {
+            panic!("unexpected event: {:?}", reactor_event);
+        }
+        let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
+        if let ReactorEvent::ContractRuntime(ContractRuntimeRequest::GetAccounts {
+            account_hashes,
+            responder,
+            ..
+        }) = reactor_event
+        {
+            let found = accounts
+                .iter()
+                .filter(|account| account_hashes.contains(&account.account_hash()))
+                .map(|account| (account.account_hash(), account.clone()))
+                .collect::<BTreeMap<_, _>>();
+            responder.respond(Ok(found)).await;
+        } else {
+            panic!("unexpected event: {:?}", reactor_event);
+        }
+    }
+}
+
+/// Returns an account with its default associated key for each of the given deploys.
+fn accounts_of<'a>(deploys: impl IntoIterator<Item = &'a Deploy>) -> Vec<Account> {
+    deploys
+        .into_iter()
+        .map(|deploy| {
+            Account::create(
+                deploy.header().account().to_account_hash(),
+                NamedKeys::new(),
+                URef::new([0; 32], AccessRights::READ_ADD_WRITE),
+            )
+        })
+        .collect()
 }
 
 pub(super) fn new_proposed_block(
@@ -172,6 +221,19 @@ async fn validate_block(
     timestamp: Timestamp,
     deploys: Vec<Deploy>,
     transfers: Vec<Deploy>,
+) -> bool {
+    let accounts = accounts_of(deploys.iter().chain(transfers.iter()));
+    validate_block_with_accounts(rng, timestamp, deploys, transfers, &accounts).await
+}
+
+/// Validates a block using a `BlockValidator` component, with only the given `accounts` in global
+/// state, and returns the result.
+async fn validate_block_with_accounts(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    deploys: Vec<Deploy>,
+    transfers: Vec<Deploy>,
+    accounts: &[Account],
 ) -> bool {
     // Assemble the block to be validated.
     let deploys_for_block = deploys
@@ -196,7 +258,7 @@ async fn validate_block(
     let validation_result =
         tokio::spawn(effect_builder.validate_block(bob_node_id, proposed_block.clone()));
     let event = reactor.expect_block_validator_event().await;
-    let effects = block_validator.handle_event(effect_builder, rng, event);
+    let mut effects = block_validator.handle_event(effect_builder, rng, event);
 
     // If validity could already be determined, the effect will be the validation response.
     if block_validator
@@ -211,7 +273,9 @@ async fn validate_block(
         return validation_result.await.unwrap();
     }
 
-    // Otherwise the effects must be requests to fetch the block's deploys.
+    // Otherwise the effects must be requests to fetch the block's deploys, followed by the
+    // validation timer, which isn't needed here.
+    let _timer = effects.pop();
     let fetch_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
 
     // We make our mock reactor answer with the expected deploys and transfers:
@@ -223,7 +287,8 @@ async fn validate_block(
         .await;
 
     // The resulting `FetchResult`s are passed back into the component. When any deploy turns out
-    // to be invalid, or once all of them have been validated, the component will respond.
+    // to be invalid the component will respond, and once all of them have been validated it will
+    // check their authorizations.
     let mut effects = Effects::new();
     for fetch_result in fetch_results {
         let events = fetch_result.await.unwrap();
@@ -233,6 +298,23 @@ async fn validate_block(
         }));
     }
 
+    if !block_validator
+        .validation_states
+        .values()
+        .all(BlockValidationState::completed)
+    {
+        // The only effect is the authorization check, which results in the validation response.
+        assert_eq!(1, effects.len());
+        let check = tokio::spawn(effects.pop().unwrap());
+        reactor.expect_authorization_check(rng, accounts).await;
+        let events = check.await.unwrap();
+        assert_eq!(1, events.len());
+        effects = events
+            .into_iter()
+            .flat_map(|event| block_validator.handle_event(effect_builder, rng, event))
+            .collect();
+    }
+
     // We expect exactly one effect: the validation response. This will resolve the result.
     assert_eq!(1, effects.len());
     for effect in effects {
@@ -316,6 +398,53 @@ async fn transfer_deploy_mixup_and_replay() {
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
 
+/// Verifies that a block is invalid if any of its deploys' approvals don't satisfy their accounts.
+#[tokio::test]
+async fn unauthorized_deploys() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from_millis(200);
+    let timestamp = Timestamp::from(1000);
+    let deploys = vec![
+        new_deploy(&mut rng, timestamp, ttl),
+        new_deploy(&mut rng, timestamp, ttl),
+    ];
+    let transfers = vec![new_transfer(&mut rng, timestamp, ttl)];
+
+    // With every account present, the block is valid.
+    let accounts = accounts_of(deploys.iter().chain(transfers.iter()));
+    assert!(
+        validate_block_with_accounts(
+            &mut rng,
+            timestamp,
+            deploys.clone(),
+            transfers.clone(),
+            &accounts
+        )
+        .await
+    );
+
+    // A deploy whose account doesn't exist can't be authorized.
+    let accounts = accounts_of(deploys.iter().skip(1).chain(transfers.iter()));
+    assert!(
+        !validate_block_with_accounts(
+            &mut rng,
+            timestamp,
+            deploys.clone(),
+            transfers.clone(),
+            &accounts
+        )
+        .await
+    );
+
+    // Nor can one signed by a key which isn't associated with its account.
+    let mut other_deploy = new_deploy(&mut rng, timestamp, ttl);
+    let mut accounts = accounts_of(deploys.iter().chain(transfers.iter()));
+    accounts.extend(accounts_of(iter::once(&other_deploy)));
+    other_deploy.sign(&SecretKey::random(&mut rng));
+    let deploys = vec![deploys[0].clone(), other_deploy];
+    assert!(!validate_block_with_accounts(&mut rng, timestamp, deploys, transfers, &accounts).await);
+}
+
 /// Verifies that the block validator fetches from multiple peers.
 #[tokio::test]
 async fn should_fetch_from_multiple_peers() {
@@ -364,7 +493,10 @@ async fn should_fetch_from_multiple_peers() {
             let event = reactor.expect_block_validator_event().await;
             let effects = block_validator.handle_event(effect_builder, &mut rng, event);
             if index == 0 {
-                assert_eq!(effects.len(), 6);
+                // The last effect is the validation timer, which isn't needed here.
+                let mut effects = effects;
+                assert_eq!(effects.len(), 7);
+                let _timer = effects.pop();
                 fetch_effects.extend(effects);
             } else {
                 assert!(effects.is_empty());
@@ -469,12 +601,24 @@ async fn should_fetch_from_multiple_peers() {
             assert_eq!(1, events.len());
             // The event should be `DeployFetched`.
             let event = events.pop().unwrap();
-            // Once the block is deemed valid (i.e. when the final missing deploy is successfully
-            // fetched) the effects will be three validation responses.
+            // Once the final missing deploy is successfully fetched, the only effect will be the
+            // authorization check.
             effects.extend(block_validator.handle_event(effect_builder, &mut rng, event));
-            assert!(effects.is_empty() || effects.len() == peer_count as usize);
+            assert!(effects.len() <= 1);
         }
 
+        // Once the deploys are found to be authorized, the effects will be three validation
+        // responses.
+        assert_eq!(effects.len(), 1);
+        let check = tokio::spawn(effects.pop().unwrap());
+        reactor
+            .expect_authorization_check(&mut rng, &accounts_of(deploys.iter().chain(&transfers)))
+            .await;
+        let mut events = check.await.unwrap();
+        assert_eq!(events.len(), 1);
+        let effects = block_validator.handle_event(effect_builder, &mut rng, events.pop().unwrap());
+        assert_eq!(effects.len(), peer_count as usize);
+
         for effect in effects {
             tokio::spawn(effect).await.unwrap();
         }
@@ -486,3 +630,53 @@ async fn should_fetch_from_multiple_peers() {
     .await
     .expect("should not hang");
 }
+
+/// Verifies that a block validation times out even if no fetch responses arrive.
+#[tokio::test]
+async fn should_time_out_without_fetch_responses() {
+    let _ = crate::logging::init();
+    tokio::time::timeout(Duration::from_secs(5), async move {
+        let mut rng = TestRng::new();
+        let deploy = new_deploy(&mut rng, 900.into(), TimeDiff::from_seconds(200));
+        let proposed_block = new_proposed_block(
+            1000.into(),
+            vec![DeployHashWithApprovals::from(&deploy)],
+            vec![],
+        );
+
+        // Create the reactor and component.
+        let reactor = MockReactor::new();
+        let effect_builder =
+            EffectBuilder::new(EventQueueHandle::without_shutdown(reactor.scheduler));
+        let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        let config = Config {
+            validation_timeout: TimeDiff::from_millis(10),
+            ..Config::default()
+        };
+        let mut block_validator = BlockValidator::new(Arc::new(chainspec), config);
+
+        let validation_result =
+            tokio::spawn(effect_builder.validate_block(NodeId::random(&mut rng), proposed_block));
+        let event = reactor.expect_block_validator_event().await;
+        let mut effects = block_validator.handle_event(effect_builder, &mut rng, event);
+
+        // The effects are a request to fetch the deploy, which is never answered, and the
+        // validation timer.
+        assert_eq!(effects.len(), 2);
+        let timer = effects.pop().unwrap();
+        let mut events = tokio::spawn(timer).await.unwrap();
+        assert_eq!(events.len(), 1);
+        let event = events.pop().unwrap();
+        assert!(matches!(event, Event::ValidationTimeout { .. }));
+
+        // Once timed out, the only effect is the validation response.
+        let effects = block_validator.handle_event(effect_builder, &mut rng, event);
+        assert_eq!(effects.len(), 1);
+        for effect in effects {
+            tokio::spawn(effect).await.unwrap();
+        }
+        assert!(!validation_result.await.unwrap());
+    })
+    .await
+    .expect("should not hang");
+}
@@ -0,0 +1,268 @@
+//! Clock monitor component.
+//!
+//! Consensus relies on the validators' wall clocks being roughly in sync.  The clock monitor
+//! periodically estimates the offset of this node's clock from the clocks of the connected
+//! validators, based on the timestamps they sent in their handshakes and pongs, and announces
+//! whenever the offset starts or stops exceeding the chainspec's `core.max_clock_drift`.
+//!
+//! Only peers that authenticated with the consensus key of an active or upcoming validator are
+//! taken into account, so that arbitrary peers cannot skew the estimate.
+
+mod metrics;
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use prometheus::Registry;
+use serde::Serialize;
+use tracing::{debug, error, info, warn};
+
+use casper_types::TimeDiff;
+
+use crate::{
+    components::{Component, ComponentState, InitializedComponent},
+    effect::{
+        announcements::ClockDriftAnnouncement, requests::NetworkInfoRequest, EffectBuilder,
+        EffectExt, Effects,
+    },
+    reactor::main_reactor::MainEvent,
+    types::{Chainspec, NodeId},
+    NodeRng,
+};
+use metrics::Metrics;
+
+const COMPONENT_NAME: &str = "clock_monitor";
+
+/// How often the clock offset is estimated.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The minimum number of peers with a known clock offset required for an estimate.
+const MIN_SAMPLES: usize = 3;
+
+/// `ClockMonitor` events.
+#[derive(Debug, Serialize)]
+pub(crate) enum Event {
+    /// Start monitoring the clock.
+    Initialize,
+    /// Request the peers' clock offsets from the network.
+    CheckClock,
+    /// The estimated offsets of the connected validators' clocks from ours, in milliseconds.
+    GotPeerClockOffsets(BTreeMap<NodeId, i64>),
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Initialize => write!(formatter, "start monitoring the clock"),
+            Event::CheckClock => write!(formatter, "check clock"),
+            Event::GotPeerClockOffsets(offsets) => {
+                write!(formatter, "got clock offsets of {} peers", offsets.len())
+            }
+        }
+    }
+}
+
+#[derive(DataSize, Debug)]
+pub(crate) struct ClockMonitor {
+    /// The maximum tolerated offset; zero disables the check.
+    max_clock_drift: TimeDiff,
+    /// Whether the latest estimate exceeded `max_clock_drift`.
+    drift_exceeded: bool,
+    state: ComponentState,
+    #[data_size(skip)]
+    metrics: Metrics,
+}
+
+impl ClockMonitor {
+    pub(crate) fn new(
+        chainspec: &Chainspec,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(ClockMonitor {
+            max_clock_drift: chainspec.core_config.max_clock_drift,
+            drift_exceeded: false,
+            state: ComponentState::Uninitialized,
+            metrics: Metrics::new(registry)?,
+        })
+    }
+
+    fn start_monitoring<REv>(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
+    where
+        REv: From<NetworkInfoRequest> + Send,
+    {
+        if self.state != ComponentState::Initializing {
+            return Effects::new();
+        }
+        <Self as InitializedComponent<MainEvent>>::set_state(self, ComponentState::Initialized);
+        self.check_clock(effect_builder)
+    }
+
+    fn check_clock<REv>(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
+    where
+        REv: From<NetworkInfoRequest> + Send,
+    {
+        let mut effects = effect_builder
+            .get_peer_clock_offsets()
+            .event(Event::GotPeerClockOffsets);
+        effects.extend(
+            effect_builder
+                .set_timeout(CHECK_INTERVAL)
+                .event(|_| Event::CheckClock),
+        );
+        effects
+    }
+
+    fn handle_peer_clock_offsets<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        offsets: BTreeMap<NodeId, i64>,
+    ) -> Effects<Event>
+    where
+        REv: From<ClockDriftAnnouncement> + Send,
+    {
+        let sample_count = offsets.len();
+        let offset = match estimate_offset(offsets.into_values().collect()) {
+            Some(offset) => offset,
+            None => {
+                debug!(
+                    sample_count,
+                    "too few validator clock offsets to estimate clock drift"
+                );
+                return Effects::new();
+            }
+        };
+        self.metrics.clock_offset_ms.set(offset);
+
+        let max_millis = self.max_clock_drift.millis();
+        let drift_exceeded = max_millis > 0 && offset.unsigned_abs() > max_millis;
+        if drift_exceeded == self.drift_exceeded {
+            return Effects::new();
+        }
+        self.drift_exceeded = drift_exceeded;
+        self.metrics
+            .clock_drift_exceeded
+            .set(i64::from(drift_exceeded));
+        if drift_exceeded {
+            warn!(
+                offset_ms = offset,
+                max_clock_drift = %self.max_clock_drift,
+                "clock drift exceeds tolerance; check the system clock"
+            );
+        } else {
+            info!(offset_ms = offset, "clock drift within tolerance again");
+        }
+        effect_builder
+            .announce_clock_drift(offset, drift_exceeded)
+            .ignore()
+    }
+}
+
+/// Returns the median of the given clock offsets, or `None` if there are fewer than
+/// `MIN_SAMPLES`.
+///
+/// The median keeps a minority of peers with badly set clocks from skewing the estimate.
+fn estimate_offset(mut offsets: Vec<i64>) -> Option<i64> {
+    if offsets.len() < MIN_SAMPLES {
+        return None;
+    }
+    offsets.sort_unstable();
+    Some(offsets[offsets.len() / 2])
+}
+
+impl<REv> Component<REv> for ClockMonitor
+where
+    REv: From<Event> + From<NetworkInfoRequest> + From<ClockDriftAnnouncement> + Send,
+{
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match &self.state {
+            ComponentState::Fatal(msg) => {
+                error!(
+                    msg,
+                    ?event,
+                    name = <Self as Component<MainEvent>>::name(self),
+                    "should not handle this event when this component has fatal error"
+                );
+                Effects::new()
+            }
+            ComponentState::Uninitialized => {
+                warn!(
+                    ?event,
+                    name = <Self as Component<MainEvent>>::name(self),
+                    "should not handle this event when component is uninitialized"
+                );
+                Effects::new()
+            }
+            ComponentState::Initializing => match event {
+                Event::Initialize => self.start_monitoring(effect_builder),
+                Event::CheckClock | Event::GotPeerClockOffsets(_) => {
+                    warn!(
+                        ?event,
+                        name = <Self as Component<MainEvent>>::name(self),
+                        "should not handle this event when component is pending initialization"
+                    );
+                    Effects::new()
+                }
+            },
+            ComponentState::Initialized => match event {
+                Event::Initialize => {
+                    error!(
+                        ?event,
+                        name = <Self as Component<MainEvent>>::name(self),
+                        "component already initialized"
+                    );
+                    Effects::new()
+                }
+                Event::CheckClock => self.check_clock(effect_builder),
+                Event::GotPeerClockOffsets(offsets) => {
+                    self.handle_peer_clock_offsets(effect_builder, offsets)
+                }
+            },
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
+
+impl<REv> InitializedComponent<REv> for ClockMonitor
+where
+    REv: From<Event> + From<NetworkInfoRequest> + From<ClockDriftAnnouncement> + Send,
+{
+    fn state(&self) -> &ComponentState {
+        &self.state
+    }
+
+    fn set_state(&mut self, new_state: ComponentState) {
+        info!(
+            ?new_state,
+            name = <Self as Component<MainEvent>>::name(self),
+            "component state changed"
+        );
+
+        self.state = new_state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_estimate_median_offset() {
+        assert_eq!(None, estimate_offset(vec![100, -100]));
+        assert_eq!(Some(5), estimate_offset(vec![60_000, 5, -3]));
+        assert_eq!(Some(10), estimate_offset(vec![10, -50_000, 12, 7]));
+    }
+}
@@ -0,0 +1,44 @@
+use prometheus::{IntGauge, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the clock monitor component.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// The estimated offset of the peers' clocks from ours, in milliseconds.
+    pub(super) clock_offset_ms: IntGauge,
+    /// 1 if the estimated offset exceeds the chainspec's maximum clock drift, otherwise 0.
+    pub(super) clock_drift_exceeded: IntGauge,
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates a new instance of the clock monitor metrics.
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let clock_offset_ms = IntGauge::new(
+            "clock_monitor_offset_ms".to_string(),
+            "estimated offset of the peers' clocks from ours in milliseconds".to_string(),
+        )?;
+        let clock_drift_exceeded = IntGauge::new(
+            "clock_monitor_drift_exceeded".to_string(),
+            "1 if the estimated clock offset exceeds the maximum clock drift, otherwise 0"
+                .to_string(),
+        )?;
+
+        registry.register(Box::new(clock_offset_ms.clone()))?;
+        registry.register(Box::new(clock_drift_exceeded.clone()))?;
+
+        Ok(Metrics {
+            clock_offset_ms,
+            clock_drift_exceeded,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.clock_offset_ms);
+        unregister_metric!(self.registry, self.clock_drift_exceeded);
+    }
+}
@@ -17,6 +17,7 @@ pub(crate) mod tests;
 mod traits;
 pub mod utils;
 mod validator_change;
+mod validator_performance;
 
 use std::{
     borrow::Cow,
@@ -60,7 +61,7 @@ use traits::Context;
 pub use cl_context::ClContext;
 pub(crate) use config::{ChainspecConsensusExt, Config};
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
-pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor, SerializedMessage};
+pub(crate) use era_supervisor::{debug::EraDump, replay_era, EraSupervisor, SerializedMessage};
 #[cfg(test)]
 pub(crate) use highway_core::highway::Vertex as HighwayVertex;
 pub(crate) use leader_sequence::LeaderSequence;
@@ -68,6 +69,7 @@ pub(crate) use protocols::highway::max_rounds_per_era;
 #[cfg(test)]
 pub(crate) use protocols::highway::HighwayMessage;
 pub(crate) use validator_change::ValidatorChange;
+pub(crate) use validator_performance::{EraPerformance, ValidatorPerformance};
 
 const COMPONENT_NAME: &str = "consensus";
 
@@ -178,6 +180,8 @@ pub(crate) enum Event {
         faulty_num: usize,
         delay: Duration,
     },
+    /// Our clock started or stopped exceeding the maximum clock drift.
+    ClockDrift { exceeds_tolerance: bool },
     /// Dump state for debugging purposes.
     #[from]
     DumpState(DumpConsensusStateRequest),
@@ -300,6 +304,15 @@ impl Display for Event {
                 "Deactivate old {} unless additional faults are observed; faults so far: {}",
                 era_id, faulty_num
             ),
+            Event::ClockDrift { exceeds_tolerance } => write!(
+                f,
+                "clock drift {} tolerance",
+                if *exceeds_tolerance {
+                    "exceeds"
+                } else {
+                    "within"
+                }
+            ),
             Event::DumpState(req) => Display::fmt(req, f),
         }
     }
@@ -468,11 +481,19 @@ where
                 faulty_num,
                 delay,
             } => self.handle_deactivate_era(effect_builder, era_id, faulty_num, delay),
+            Event::ClockDrift { exceeds_tolerance } => {
+                self.set_clock_drift_exceeded(exceeds_tolerance);
+                Effects::new()
+            }
             Event::ConsensusRequest(ConsensusRequest::Status(responder)) => self.status(responder),
             Event::ConsensusRequest(ConsensusRequest::ValidatorChanges(responder)) => {
                 let validator_changes = self.get_validator_changes();
                 responder.respond(validator_changes).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::ValidatorPerformance(responder)) => {
+                let validator_performance = self.get_validator_performance();
+                responder.respond(validator_performance).ignore()
+            }
             Event::DumpState(req @ DumpConsensusStateRequest { era_id, .. }) => {
                 let current_era = match self.current_era() {
                     None => {
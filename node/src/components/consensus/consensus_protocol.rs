@@ -110,7 +110,8 @@ pub struct EraReport<VID> {
     /// This is a measure of the value of each validator's contribution to consensus, in
     /// fractions of the configured maximum block reward.
     pub(crate) rewards: BTreeMap<VID, u64>,
-    /// Validators that haven't produced any unit during the era.
+    /// Validators that haven't produced any unit during the era, or that failed to propose blocks
+    /// in most of the rounds they were the leader of.
     pub(crate) inactive_validators: Vec<VID>,
 }
 
@@ -165,7 +166,8 @@ impl<VID> EraReport<VID> {
 pub(crate) struct TerminalBlockData<C: Context> {
     /// The rewards for participating in consensus.
     pub(crate) rewards: BTreeMap<C::ValidatorId, u64>,
-    /// The list of validators that haven't produced any units.
+    /// The list of validators that haven't produced any units, or failed to propose blocks in
+    /// most of their rounds.
     pub(crate) inactive_validators: Vec<C::ValidatorId>,
 }
 
@@ -212,6 +214,10 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     ValidateConsensusValue {
         sender: NodeId,
         proposed_block: ProposedBlock<C>,
+        /// The validator who created the proposal, if known.
+        proposer: Option<C::ValidatorId>,
+        /// The time at which the proposal's round started, if known.
+        round_start: Option<Timestamp>,
     },
     /// New direct evidence was added against the given validator.
     NewEvidence(C::ValidatorId),
@@ -315,6 +321,9 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Returns the list of all validators that were observed as faulty in this consensus instance.
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId>;
 
+    /// Returns the number of messages seen from each validator in this consensus instance.
+    fn units_seen(&self) -> BTreeMap<C::ValidatorId, u64>;
+
     /// Returns whether this instance of a protocol is an active validator.
     fn is_active(&self) -> bool;
 
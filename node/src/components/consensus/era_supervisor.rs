@@ -9,10 +9,11 @@
 
 pub(super) mod debug;
 mod era;
+mod pending_messages;
 
 use std::{
     cmp,
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryInto,
     fmt::{self, Debug, Formatter},
     fs, io,
@@ -23,7 +24,7 @@ use std::{
 
 use anyhow::Error;
 use datasize::DataSize;
-use futures::FutureExt;
+use futures::{future::BoxFuture, FutureExt};
 use itertools::Itertools;
 use prometheus::Registry;
 use rand::Rng;
@@ -31,7 +32,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp};
+use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp, U512};
 
 use crate::{
     components::{
@@ -44,7 +45,8 @@ use crate::{
             metrics::Metrics,
             validator_change::{ValidatorChange, ValidatorChanges},
             ActionId, ChainspecConsensusExt, Config, ConsensusMessage, ConsensusRequestMessage,
-            Event, HighwayProtocol, NewBlockPayload, ReactorEventT, ResolveValidity, TimerId, Zug,
+            EraPerformance, Event, HighwayProtocol, NewBlockPayload, ReactorEventT,
+            ResolveValidity, TimerId, ValidatorPerformance, Zug,
         },
         network::blocklist::BlocklistJustification,
     },
@@ -55,14 +57,17 @@ use crate::{
     },
     failpoints::Failpoint,
     fatal, protocol,
+    reactor::Finalize,
     types::{
-        chainspec::ConsensusProtocolName, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
-        DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, MetaBlockState, NodeId,
+        chainspec::{ConsensusProtocolName, DeployConfig},
+        BlockHash, BlockHeader, Chainspec, Deploy, DeployHash, DeployOrTransferHash,
+        FinalizedApprovals, FinalizedBlock, MetaBlockState, NodeId,
     },
     NodeRng,
 };
 
 pub use self::era::Era;
+use self::pending_messages::PendingMessages;
 use crate::components::consensus::error::CreateNewEraError;
 
 use super::traits::ConsensusNetworkMessage;
@@ -81,6 +86,11 @@ pub(super) const PAST_EVIDENCE_ERAS: u64 = 1;
 /// The more recent half of these is active: it contains units and can still accept further units.
 /// The older half is in evidence-only state, and only used to validate cited evidence.
 pub(super) const PAST_OPEN_ERAS: u64 = 2 * PAST_EVIDENCE_ERAS;
+/// The maximum total size in bytes of the consensus messages from a single sender that are
+/// buffered for eras that are not initialized yet.
+const MAX_PENDING_BYTES_PER_SENDER: usize = 1024 * 1024;
+/// The folder in the storage directory where unit files and logs are stored.
+const UNIT_FILES_FOLDER: &str = "unit_files";
 
 #[derive(DataSize)]
 pub struct EraSupervisor {
@@ -96,6 +106,9 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: BTreeMap<EraId, Era>,
+    /// Consensus messages for future eras, e.g. received while the node is still joining. They are
+    /// handled once the era is initialized.
+    pending_messages: PendingMessages,
     secret_signing_key: Arc<SecretKey>,
     public_signing_key: PublicKey,
     chainspec: Arc<Chainspec>,
@@ -114,6 +127,9 @@ pub struct EraSupervisor {
     /// The path to the folder where unit files will be stored.
     unit_files_folder: PathBuf,
     last_progress: Timestamp,
+    /// Whether our clock is estimated to be further off from the validators' clocks than the
+    /// chainspec's `max_clock_drift`. If so, we don't propose blocks.
+    clock_drift_exceeded: bool,
 
     /// Failpoints
     pub(super) message_delay_failpoint: Failpoint<u64>,
@@ -127,6 +143,19 @@ impl Debug for EraSupervisor {
     }
 }
 
+impl Finalize for EraSupervisor {
+    /// Closes the open eras' unit logs, waiting for all recorded vertices to be written.
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        async move {
+            // Dropping the eras joins the unit log writer threads, which may block on disk I/O.
+            if let Err(error) = tokio::task::spawn_blocking(move || drop(self)).await {
+                error!(%error, "could not close consensus unit logs cleanly");
+            }
+        }
+        .boxed()
+    }
+}
+
 impl EraSupervisor {
     /// Creates a new `EraSupervisor`, starting in the indicated current era.
     #[allow(clippy::too_many_arguments)]
@@ -138,13 +167,14 @@ impl EraSupervisor {
         chainspec: Arc<Chainspec>,
         registry: &Registry,
     ) -> Result<Self, Error> {
-        let unit_files_folder = storage_dir.join("unit_files");
+        let unit_files_folder = storage_dir.join(UNIT_FILES_FOLDER);
         std::fs::create_dir_all(&unit_files_folder)?;
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = Metrics::new(registry)?;
 
         let era_supervisor = Self {
             open_eras: Default::default(),
+            pending_messages: PendingMessages::new(MAX_PENDING_BYTES_PER_SENDER),
             secret_signing_key,
             public_signing_key,
             chainspec,
@@ -154,6 +184,7 @@ impl EraSupervisor {
             unit_files_folder,
             next_executed_height: 0,
             last_progress: Timestamp::now(),
+            clock_drift_exceeded: false,
             message_delay_failpoint: Failpoint::new("consensus.message_delay"),
             proposal_delay_failpoint: Failpoint::new("consensus.proposal_delay"),
         };
@@ -246,6 +277,11 @@ impl EraSupervisor {
         Some(effects)
     }
 
+    /// Sets whether our clock is estimated to exceed the maximum clock drift.
+    pub(super) fn set_clock_drift_exceeded(&mut self, exceeds_tolerance: bool) {
+        self.clock_drift_exceeded = exceeds_tolerance;
+    }
+
     /// Returns a list of status changes of active validators.
     pub(super) fn get_validator_changes(
         &self,
@@ -331,7 +367,10 @@ impl EraSupervisor {
     ) -> Effects<Event> {
         match self.create_new_era(switch_blocks, now) {
             Ok((era_id, outcomes)) => {
-                self.handle_consensus_outcomes(effect_builder, rng, era_id, outcomes)
+                let mut effects =
+                    self.handle_consensus_outcomes(effect_builder, rng, era_id, outcomes);
+                effects.extend(self.handle_pending_messages(effect_builder, rng, era_id));
+                effects
             }
             Err(err) => fatal!(
                 effect_builder,
@@ -342,6 +381,80 @@ impl EraSupervisor {
         }
     }
 
+    /// Handles the messages buffered for the newly initialized era `era_id`, and drops the ones
+    /// for eras that will not be initialized anymore.
+    fn handle_pending_messages<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        era_id: EraId,
+    ) -> Effects<Event> {
+        let mut effects = Effects::new();
+        let pending = self.pending_messages.take(era_id);
+        if !pending.is_empty() {
+            info!(
+                era = era_id.value(),
+                count = pending.len(),
+                "handling buffered consensus messages"
+            );
+        }
+        for (sender, payload) in pending {
+            effects.extend(self.delegate_to_era(
+                effect_builder,
+                rng,
+                era_id,
+                move |consensus, rng| {
+                    consensus.handle_message(rng, sender, payload, Timestamp::now())
+                },
+            ));
+        }
+        if let Some(current_era_id) = self.current_era() {
+            let earliest_open_era = self.chainspec.earliest_relevant_era(current_era_id);
+            self.pending_messages
+                .retain_eras(earliest_open_era..=current_era_id.successor());
+        }
+        effects
+    }
+
+    /// Buffers a message for an era that is not initialized yet, if it is the era after the current
+    /// one and the sender hasn't exceeded its limit.
+    ///
+    /// While no era is initialized yet, e.g. while joining, messages for any era are buffered, and
+    /// the ones that turn out to be too far in the future are dropped once an era is initialized.
+    fn buffer_pending_message(
+        &mut self,
+        era_id: EraId,
+        sender: NodeId,
+        payload: SerializedMessage,
+    ) {
+        if let Some(current_era_id) = self.current_era() {
+            if era_id <= current_era_id {
+                self.log_missing_era(era_id);
+                return;
+            }
+            if era_id > current_era_id.successor() {
+                debug!(
+                    era = era_id.value(),
+                    current_era = current_era_id.value(),
+                    "dropping message for uninitialized era; too far in the future"
+                );
+                return;
+            }
+        }
+        if !self.pending_messages.push(era_id, sender, payload) {
+            debug!(
+                era = era_id.value(),
+                %sender,
+                "dropping message for uninitialized era; sender exceeded its buffer limit"
+            );
+            return;
+        }
+        trace!(
+            era = era_id.value(),
+            "buffering message for uninitialized era"
+        );
+    }
+
     fn make_latest_era_current<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -409,42 +522,22 @@ impl EraSupervisor {
             .ok_or(CreateNewEraError::AttemptedToCreateEraWithNoSwitchBlocks)?;
         let era_id = key_block.era_id().successor();
 
-        let chainspec_hash = self.chainspec.hash();
-        let key_block_hash = key_block.block_hash();
-        let instance_id = instance_id(chainspec_hash, era_id, key_block_hash);
-
         if self.open_eras.contains_key(&era_id) {
             debug!(era = era_id.value(), "era already exists");
             return Ok((era_id, vec![]));
         }
 
-        let era_end = key_block.era_end().ok_or_else(|| {
-            CreateNewEraError::LastBlockHeaderNotASwitchBlock {
-                era_id,
-                last_block_header: Box::new(key_block.clone()),
-            }
-        })?;
-
-        let earliest_era = self.chainspec.earliest_switch_block_needed(era_id);
-        let switch_blocks_needed = era_id.value().saturating_sub(earliest_era.value()) as usize;
-        let first_idx = switch_blocks
-            .len()
-            .checked_sub(switch_blocks_needed)
-            .ok_or_else(|| CreateNewEraError::InsufficientSwitchBlocks {
-                era_id,
-                switch_blocks: switch_blocks.to_vec(),
-            })?;
-        for (i, switch_block) in switch_blocks[first_idx..].iter().enumerate() {
-            if switch_block.era_id() != earliest_era.saturating_add(i as u64) {
-                return Err(CreateNewEraError::WrongSwitchBlockEra {
-                    era_id,
-                    switch_blocks: switch_blocks.to_vec(),
-                });
-            }
-        }
-
-        let report = era_end.era_report();
-        let validators = era_end.next_era_validator_weights();
+        let NewEraParams {
+            era_id,
+            instance_id,
+            key_block_hash,
+            validators,
+            faulty,
+            inactive,
+            seed,
+            start_height,
+            start_time,
+        } = new_era_params(&self.chainspec, switch_blocks)?;
 
         if let Some(current_era) = self.current_era() {
             if current_era > era_id.saturating_add(PAST_EVIDENCE_ERAS) {
@@ -453,43 +546,11 @@ impl EraSupervisor {
             }
         }
 
-        // Compute the seed for the PRNG from the booking block hash and the accumulated seed.
-        let auction_delay = self.chainspec.core_config.auction_delay as usize;
-        let booking_block_hash =
-            if let Some(booking_block) = switch_blocks.iter().rev().nth(auction_delay) {
-                booking_block.block_hash()
-            } else {
-                // If there's no booking block for the `era_id`
-                // (b/c it would have been from before Genesis, upgrade or emergency restart),
-                // use a "zero" block hash. This should not hurt the security of the leader
-                // selection algorithm.
-                BlockHash::default()
-            };
-        let seed = Self::era_seed(booking_block_hash, key_block.accumulated_seed());
-
-        // The beginning of the new era is marked by the key block.
-        #[allow(clippy::arithmetic_side_effects)] // Block height should never reach u64::MAX.
-        let start_height = key_block.height() + 1;
-        let start_time = key_block.timestamp();
-
-        // Validators that were inactive in the previous era will be excluded from leader selection
-        // in the new era.
-        let inactive = report.inactive_validators.iter().cloned().collect();
-
-        // Validators that were only exposed as faulty after the booking block are still in the new
-        // era's validator set but get banned.
-        let blocks_after_booking_block = switch_blocks.iter().rev().take(auction_delay);
-        let faulty = blocks_after_booking_block
-            .filter_map(|switch_block| switch_block.era_end())
-            .flat_map(|era_end| era_end.era_report().equivocators.clone())
-            .collect();
-
         info!(
             ?validators,
             %start_time,
             %now,
             %start_height,
-            %chainspec_hash,
             %key_block_hash,
             %instance_id,
             %seed,
@@ -510,6 +571,7 @@ impl EraSupervisor {
         let (consensus, outcomes) = match self.chainspec.core_config.consensus_protocol {
             ConsensusProtocolName::Highway => HighwayProtocol::new_boxed(
                 instance_id,
+                era_id,
                 validators.clone(),
                 &faulty,
                 &inactive,
@@ -519,6 +581,14 @@ impl EraSupervisor {
                 start_time,
                 seed,
                 now,
+                self.config
+                    .highway
+                    .persist_units
+                    .then(|| unit_log_file(&self.unit_files_folder, era_id, &instance_id)),
+                self.config
+                    .highway
+                    .snapshot_interval
+                    .map(|_| snapshot_file(&self.unit_files_folder, &instance_id)),
             ),
             ConsensusProtocolName::Zug => Zug::new_boxed(
                 instance_id,
@@ -535,13 +605,16 @@ impl EraSupervisor {
             ),
         };
 
+        let performance =
+            EraPerformance::read_from(&performance_file(&self.unit_files_folder, &instance_id));
         let era = Era::new(
             consensus,
             start_time,
             start_height,
             faulty,
             inactive,
-            validators.clone(),
+            validators,
+            performance,
         );
         let _ = self.open_eras.insert(era_id, era);
 
@@ -587,6 +660,7 @@ impl EraSupervisor {
             self.open_eras.retain(|era_id, era| {
                 if earliest_open_era > *era_id {
                     trace!(era = era_id.value(), "removing obsolete era");
+                    era.cancel_timers();
                     removed_instance_ids.push(*era.consensus.instance_id());
                     false
                 } else if earliest_active_era > *era_id {
@@ -604,6 +678,29 @@ impl EraSupervisor {
                         err => warn!(?err, "could not delete unit hash file"),
                     }
                 }
+                let path = snapshot_file(&self.unit_files_folder, &instance_id);
+                if let Err(err) = fs::remove_file(path) {
+                    match err.kind() {
+                        io::ErrorKind::NotFound => {}
+                        err => warn!(?err, "could not delete protocol state snapshot"),
+                    }
+                }
+                let path = performance_file(&self.unit_files_folder, &instance_id);
+                if let Err(err) = fs::remove_file(path) {
+                    match err.kind() {
+                        io::ErrorKind::NotFound => {}
+                        err => warn!(?err, "could not delete validator performance"),
+                    }
+                }
+            }
+            // Unit logs are kept for longer than the other unit files, so that the eras can still
+            // be replayed after they have been closed.
+            let retention_eras = self.config.highway.unit_log_retention_eras;
+            if retention_eras > 0 {
+                delete_unit_logs_before(
+                    &self.unit_files_folder,
+                    current_era.saturating_sub(retention_eras.saturating_sub(1)),
+                );
             }
         }
 
@@ -619,6 +716,13 @@ impl EraSupervisor {
         ))
     }
 
+    /// Writes the validators' performance recorded in the specified era to its file.
+    fn write_performance(&self, era_id: EraId) {
+        let era = self.era(era_id);
+        let path = performance_file(&self.unit_files_folder, era.consensus.instance_id());
+        era.recorded_performance().write_to(&path);
+    }
+
     /// Applies `f` to the consensus protocol of the specified era.
     fn delegate_to_era<REv: ReactorEventT, F>(
         &mut self,
@@ -674,6 +778,9 @@ impl EraSupervisor {
                 "timer called with long delay"
             );
         }
+        if let Some(era) = self.open_eras.get_mut(&era_id) {
+            era.remove_timer(timestamp, timer_id);
+        }
         self.delegate_to_era(effect_builder, rng, era_id, move |consensus, rng| {
             consensus.handle_timer(timestamp, now, timer_id, rng)
         })
@@ -702,6 +809,10 @@ impl EraSupervisor {
             ConsensusMessage::Protocol { era_id, payload } => {
                 trace!(era = era_id.value(), "received a consensus message");
 
+                if !self.open_eras.contains_key(&era_id) {
+                    self.buffer_pending_message(era_id, sender, payload);
+                    return Effects::new();
+                }
                 self.delegate_to_era(effect_builder, rng, era_id, move |consensus, rng| {
                     consensus.handle_message(rng, sender, payload, Timestamp::now())
                 })
@@ -1021,19 +1132,47 @@ impl EraSupervisor {
                 .ignore()
             }
             ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => {
+                let era = match self.open_eras.get_mut(&era_id) {
+                    Some(era) => era,
+                    None => {
+                        self.log_missing_era(era_id);
+                        return Effects::new();
+                    }
+                };
+                // An identical timer would only cause the protocol to handle the same timestamp
+                // twice.
+                if era.has_timer(timestamp, timer_id) {
+                    trace!(
+                        era = era_id.value(), timer_id = timer_id.0, %timestamp,
+                        "timer already scheduled"
+                    );
+                    return Effects::new();
+                }
                 let timediff = timestamp.saturating_diff(Timestamp::now());
-                effect_builder
-                    .set_timeout(timediff.into())
-                    .event(move |_| Event::Timer {
-                        era_id,
-                        timestamp,
-                        timer_id,
-                    })
+                let (timer, timeout) = effect_builder.set_cancellable_timeout(timediff.into());
+                era.add_timer(timestamp, timer_id, timer);
+                timeout.events(move |elapsed| {
+                    elapsed
+                        .map(|_| Event::Timer {
+                            era_id,
+                            timestamp,
+                            timer_id,
+                        })
+                        .into_iter()
+                })
             }
             ProtocolOutcome::QueueAction(action_id) => effect_builder
                 .immediately()
                 .event(move |()| Event::Action { era_id, action_id }),
             ProtocolOutcome::CreateNewBlock(block_context, proposal_expiry) => {
+                if self.clock_drift_exceeded {
+                    warn!(
+                        %era_id,
+                        timestamp = %block_context.timestamp(),
+                        "not proposing a block: clock drift exceeds tolerance"
+                    );
+                    return Effects::new();
+                }
                 let accusations = self
                     .iter_past(era_id, PAST_EVIDENCE_ERAS)
                     .flat_map(|e_id| self.era(e_id).consensus.validators_with_evidence())
@@ -1075,6 +1214,12 @@ impl EraSupervisor {
                     debug!(era = era_id.value(), "finalized block in old era");
                     return Effects::new();
                 }
+                if self
+                    .era_mut(era_id)
+                    .record_finalized_block(&proposer, relative_height)
+                {
+                    self.write_performance(era_id);
+                }
                 let era = self.open_eras.get_mut(&era_id).unwrap();
                 era.add_accusations(&equivocators);
                 era.add_accusations(value.accusations());
@@ -1147,12 +1292,31 @@ impl EraSupervisor {
             ProtocolOutcome::ValidateConsensusValue {
                 sender,
                 proposed_block,
+                proposer,
+                round_start,
             } => {
                 if era_id.saturating_add(PAST_EVIDENCE_ERAS) < current_era
                     || !self.open_eras.contains_key(&era_id)
                 {
                     return Effects::new(); // Outdated era; we don't need the value anymore.
                 }
+                if let Some(proposer) = proposer {
+                    // The proposal is late if it was made more than the minimum block time after
+                    // its round started. If the round start is unknown, we give it the benefit of
+                    // the doubt.
+                    let timestamp = proposed_block.context().timestamp();
+                    let late = round_start.map_or(false, |round_start| {
+                        timestamp
+                            > round_start
+                                .saturating_add(self.chainspec.core_config.minimum_block_time)
+                    });
+                    if self
+                        .era_mut(era_id)
+                        .record_proposal(&proposer, timestamp, late)
+                    {
+                        self.write_performance(era_id);
+                    }
+                }
                 let missing_evidence: Vec<PublicKey> = proposed_block
                     .value()
                     .accusations()
@@ -1180,10 +1344,26 @@ impl EraSupervisor {
                     let msg = ConsensusMessage::EvidenceRequest { era_id, pub_key };
                     effects.extend(effect_builder.send_message(sender, msg.into()).ignore());
                 }
+                if self.era(era_id).is_known_valid(&proposed_block) {
+                    debug!(%sender, "proposed block already validated in this era");
+                    effects.extend(self.resolve_validity(
+                        effect_builder,
+                        rng,
+                        ResolveValidity {
+                            era_id,
+                            sender,
+                            proposed_block,
+                            valid: true,
+                        },
+                    ));
+                    return effects;
+                }
+                let deploy_config = self.chainspec.deploy_config;
                 effects.extend(
                     async move {
                         check_deploys_for_replay_in_previous_eras_and_validate_block(
                             effect_builder,
+                            deploy_config,
                             era_id,
                             sender,
                             proposed_block,
@@ -1238,6 +1418,16 @@ impl EraSupervisor {
         }
     }
 
+    /// Returns the participation of each validator in each open era.
+    pub(super) fn get_validator_performance(
+        &self,
+    ) -> BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>> {
+        self.open_eras
+            .iter()
+            .map(|(era_id, era)| (*era_id, era.performance()))
+            .collect()
+    }
+
     pub(super) fn status(
         &self,
         responder: Responder<Option<(PublicKey, Option<TimeDiff>)>>,
@@ -1376,6 +1566,216 @@ async fn execute_finalized_block<REv>(
         .await
 }
 
+/// The blocks finalized in an era, as reconstructed from its unit log.
+pub(crate) struct ReplayedEra {
+    pub(crate) era_id: EraId,
+    pub(crate) start_height: u64,
+    pub(crate) finalized_blocks: Vec<CpFinalizedBlock<ClContext>>,
+}
+
+/// Rebuilds the Highway protocol state of the era following the last of the given switch blocks
+/// from the unit log in the given storage directory, and returns the blocks it finalized.
+pub(crate) fn replay_era(
+    chainspec: &Chainspec,
+    config: &Config,
+    storage_dir: &Path,
+    switch_blocks: &[BlockHeader],
+) -> Result<ReplayedEra, Error> {
+    let NewEraParams {
+        era_id,
+        instance_id,
+        validators,
+        faulty,
+        inactive,
+        seed,
+        start_height,
+        start_time,
+        ..
+    } = new_era_params(chainspec, switch_blocks)?;
+    let path = unit_log_file(&storage_dir.join(UNIT_FILES_FOLDER), era_id, &instance_id);
+    let (mut highway, _) = HighwayProtocol::<ClContext>::new(
+        instance_id,
+        era_id,
+        validators,
+        &faulty,
+        &inactive,
+        chainspec,
+        config,
+        None,
+        start_time,
+        seed,
+        start_time,
+    );
+    let finalized_blocks = highway
+        .replay_unit_log(
+            &path,
+            chainspec.network_config.maximum_net_message_size as usize,
+        )
+        .map_err(|err| anyhow::anyhow!("could not replay {}: {}", path.display(), err))?;
+    Ok(ReplayedEra {
+        era_id,
+        start_height,
+        finalized_blocks,
+    })
+}
+
+/// Returns the path to the log of all vertices added to the era's Highway protocol state.
+pub(crate) fn unit_log_file(
+    unit_files_folder: &Path,
+    era_id: EraId,
+    instance_id: &Digest,
+) -> PathBuf {
+    unit_files_folder.join(format!("units_{}_{:?}.log", era_id.value(), instance_id))
+}
+
+/// Returns the era of the unit log with the given file name, or `None` if it is not a unit log.
+fn unit_log_era(file_name: &str) -> Option<EraId> {
+    let era_id = file_name
+        .strip_prefix("units_")?
+        .strip_suffix(".log")?
+        .split('_')
+        .next()?
+        .parse()
+        .ok()?;
+    Some(EraId::new(era_id))
+}
+
+/// Deletes the unit logs of all eras before `earliest_era`.
+///
+/// The logs are found by their file names, so that logs written before a restart are deleted, too.
+fn delete_unit_logs_before(unit_files_folder: &Path, earliest_era: EraId) {
+    let entries = match fs::read_dir(unit_files_folder) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!(?err, "could not list unit files");
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let is_obsolete = entry
+            .file_name()
+            .to_str()
+            .and_then(unit_log_era)
+            .map_or(false, |era_id| era_id < earliest_era);
+        if is_obsolete {
+            if let Err(err) = fs::remove_file(entry.path()) {
+                warn!(?err, path = ?entry.path(), "could not delete unit log");
+            }
+        }
+    }
+}
+
+/// Returns the path to the latest snapshot of the era's Highway protocol state.
+fn snapshot_file(unit_files_folder: &Path, instance_id: &Digest) -> PathBuf {
+    unit_files_folder.join(format!("snapshot_{:?}.dat", instance_id))
+}
+
+/// Returns the path to the validators' performance recorded by the era supervisor in the era.
+fn performance_file(unit_files_folder: &Path, instance_id: &Digest) -> PathBuf {
+    unit_files_folder.join(format!("performance_{:?}.dat", instance_id))
+}
+
+/// The inputs for a new era's consensus instance, derived from the preceding switch blocks.
+pub(crate) struct NewEraParams {
+    pub(crate) era_id: EraId,
+    pub(crate) instance_id: Digest,
+    pub(crate) key_block_hash: BlockHash,
+    pub(crate) validators: BTreeMap<PublicKey, U512>,
+    pub(crate) faulty: HashSet<PublicKey>,
+    pub(crate) inactive: HashSet<PublicKey>,
+    pub(crate) seed: u64,
+    pub(crate) start_height: u64,
+    pub(crate) start_time: Timestamp,
+}
+
+/// Computes the inputs for the era following the last of the given switch blocks. The switch
+/// blocks must contain the most recent `auction_delay + 1` ones, in order, but at most as far back
+/// as to the last activation point.
+pub(crate) fn new_era_params(
+    chainspec: &Chainspec,
+    switch_blocks: &[BlockHeader],
+) -> Result<NewEraParams, CreateNewEraError> {
+    let key_block = switch_blocks
+        .last()
+        .ok_or(CreateNewEraError::AttemptedToCreateEraWithNoSwitchBlocks)?;
+    let era_id = key_block.era_id().successor();
+
+    let key_block_hash = key_block.block_hash();
+    let instance_id = instance_id(chainspec.hash(), era_id, key_block_hash);
+
+    let era_end =
+        key_block
+            .era_end()
+            .ok_or_else(|| CreateNewEraError::LastBlockHeaderNotASwitchBlock {
+                era_id,
+                last_block_header: Box::new(key_block.clone()),
+            })?;
+
+    let earliest_era = chainspec.earliest_switch_block_needed(era_id);
+    let switch_blocks_needed = era_id.value().saturating_sub(earliest_era.value()) as usize;
+    let first_idx = switch_blocks
+        .len()
+        .checked_sub(switch_blocks_needed)
+        .ok_or_else(|| CreateNewEraError::InsufficientSwitchBlocks {
+            era_id,
+            switch_blocks: switch_blocks.to_vec(),
+        })?;
+    for (i, switch_block) in switch_blocks[first_idx..].iter().enumerate() {
+        if switch_block.era_id() != earliest_era.saturating_add(i as u64) {
+            return Err(CreateNewEraError::WrongSwitchBlockEra {
+                era_id,
+                switch_blocks: switch_blocks.to_vec(),
+            });
+        }
+    }
+
+    let report = era_end.era_report();
+    let validators = era_end.next_era_validator_weights().clone();
+
+    // Compute the seed for the PRNG from the booking block hash and the accumulated seed.
+    let auction_delay = chainspec.core_config.auction_delay as usize;
+    let booking_block_hash =
+        if let Some(booking_block) = switch_blocks.iter().rev().nth(auction_delay) {
+            booking_block.block_hash()
+        } else {
+            // If there's no booking block for the `era_id`
+            // (b/c it would have been from before Genesis, upgrade or emergency restart),
+            // use a "zero" block hash. This should not hurt the security of the leader
+            // selection algorithm.
+            BlockHash::default()
+        };
+    let seed = EraSupervisor::era_seed(booking_block_hash, key_block.accumulated_seed());
+
+    // The beginning of the new era is marked by the key block.
+    #[allow(clippy::arithmetic_side_effects)] // Block height should never reach u64::MAX.
+    let start_height = key_block.height() + 1;
+    let start_time = key_block.timestamp();
+
+    // Validators that were inactive in the previous era will be excluded from leader selection
+    // in the new era.
+    let inactive = report.inactive_validators.iter().cloned().collect();
+
+    // Validators that were only exposed as faulty after the booking block are still in the new
+    // era's validator set but get banned.
+    let blocks_after_booking_block = switch_blocks.iter().rev().take(auction_delay);
+    let faulty = blocks_after_booking_block
+        .filter_map(|switch_block| switch_block.era_end())
+        .flat_map(|era_end| era_end.era_report().equivocators.clone())
+        .collect();
+
+    Ok(NewEraParams {
+        era_id,
+        instance_id,
+        key_block_hash,
+        validators,
+        faulty,
+        inactive,
+        seed,
+        start_height,
+        start_time,
+    })
+}
+
 /// Computes the instance ID for an era, given the era ID and the chainspec hash.
 fn instance_id(chainspec_hash: Digest, era_id: EraId, key_block_hash: BlockHash) -> Digest {
     Digest::hash_pair(
@@ -1388,8 +1788,12 @@ fn instance_id(chainspec_hash: Digest, era_id: EraId, key_block_hash: BlockHash)
 /// previous eras. This is done by repeatedly querying storage for deploy metadata. When metadata is
 /// found storage is queried again to get the era id for the included deploy. That era id must *not*
 /// be less than the current era, otherwise the deploy is a replay attack.
+///
+/// Once the block validator has fetched all the block's deploys, their dependencies are checked
+/// too, if the chainspec enforces dependencies in the block's era.
 async fn check_deploys_for_replay_in_previous_eras_and_validate_block<REv>(
     effect_builder: EffectBuilder<REv>,
+    deploy_config: DeployConfig,
     proposed_block_era_id: EraId,
     sender: NodeId,
     proposed_block: ProposedBlock<ClContext>,
@@ -1425,10 +1829,38 @@ where
     }
 
     let sender_for_validate_block: NodeId = sender;
-    let valid = effect_builder
+    let mut valid = effect_builder
         .validate_block(sender_for_validate_block, proposed_block.clone())
         .await;
 
+    if valid && deploy_config.dependencies_enforced_in(proposed_block_era_id) {
+        match find_unmet_deploy_dependency(effect_builder, deploy_config.max_ttl, &proposed_block)
+            .await
+        {
+            None => {}
+            Some(UnmetDependency::MissingDeploy(deploy_hash)) => {
+                info!(
+                    %sender,
+                    %deploy_hash,
+                    "cannot check dependencies of missing deploy in block"
+                );
+                valid = false;
+            }
+            Some(UnmetDependency::NotFinalized {
+                deploy_hash,
+                dependency,
+            }) => {
+                info!(
+                    %sender,
+                    %deploy_hash,
+                    %dependency,
+                    "block contains a deploy with an unmet dependency"
+                );
+                valid = false;
+            }
+        }
+    }
+
     Event::ResolveValidity(ResolveValidity {
         era_id: proposed_block_era_id,
         sender,
@@ -1437,6 +1869,91 @@ where
     })
 }
 
+/// A deploy in a proposed block whose dependencies are not known to be met.
+enum UnmetDependency {
+    /// The deploy is not in storage, so its dependencies are unknown.
+    MissingDeploy(DeployHash),
+    /// The dependency has neither been executed in a recent block nor is included in one of the
+    /// block's ancestors.
+    NotFinalized {
+        deploy_hash: DeployHash,
+        dependency: DeployHash,
+    },
+}
+
+/// Returns a deploy in the proposed block whose dependencies are not all met, i.e. included in one
+/// of the block's ancestors or executed in a block at most `max_ttl` older than the proposed one.
+///
+/// Every node keeps at least `max_ttl` worth of blocks for replay protection, so restricting the
+/// lookup to that window makes the outcome depend only on the chain, not on how much history the
+/// validating node happens to have. Dependencies executed before the window count as unmet.
+///
+/// The block's deploys are expected to be in storage already, as the block validator fetches them.
+/// If one is missing nevertheless, its dependencies are treated as unmet.
+async fn find_unmet_deploy_dependency<REv>(
+    effect_builder: EffectBuilder<REv>,
+    max_ttl: TimeDiff,
+    proposed_block: &ProposedBlock<ClContext>,
+) -> Option<UnmetDependency>
+where
+    REv: From<StorageRequest>,
+{
+    let deploy_hashes: Vec<DeployHash> = proposed_block
+        .value()
+        .deploy_and_transfer_hashes()
+        .copied()
+        .collect();
+    let ancestor_deploys: HashSet<DeployHash> = proposed_block
+        .context()
+        .ancestor_values()
+        .iter()
+        .flat_map(|ancestor| ancestor.deploy_and_transfer_hashes().copied())
+        .collect();
+
+    let mut dependencies = vec![];
+    let deploys = effect_builder
+        .get_deploys_from_storage(deploy_hashes.clone())
+        .await;
+    for (deploy_hash, maybe_deploy) in deploy_hashes.into_iter().zip(deploys) {
+        let deploy = match maybe_deploy {
+            Some(deploy) => deploy.into_naive(),
+            None => return Some(UnmetDependency::MissingDeploy(deploy_hash)),
+        };
+        dependencies.extend(
+            deploy
+                .header()
+                .dependencies()
+                .iter()
+                .filter(|dependency| !ancestor_deploys.contains(dependency))
+                .map(|dependency| (deploy_hash, *dependency)),
+        );
+    }
+    if dependencies.is_empty() {
+        return None;
+    }
+
+    let executed = effect_builder
+        .get_executed_deploys(
+            dependencies
+                .iter()
+                .map(|(_, dependency)| *dependency)
+                .collect(),
+        )
+        .await;
+    let earliest_timestamp = proposed_block.context().timestamp().saturating_sub(max_ttl);
+    dependencies
+        .into_iter()
+        .find(|(_, dependency)| {
+            executed.get(dependency).map_or(true, |block_header| {
+                block_header.timestamp() < earliest_timestamp
+            })
+        })
+        .map(|(deploy_hash, dependency)| UnmetDependency::NotFinalized {
+            deploy_hash,
+            dependency,
+        })
+}
+
 impl ProposedBlock<ClContext> {
     /// If this block contains a deploy that's also present in an ancestor, this returns the deploy
     /// hash, otherwise `None`.
@@ -6,10 +6,13 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-use casper_types::{EraId, PublicKey, Timestamp, U512};
+use casper_types::{EraId, PublicKey, TimeDiff, Timestamp, U512};
 use serde::Serialize;
 
-use crate::components::consensus::{highway_core::State, ClContext, HighwayProtocol};
+use crate::components::consensus::{
+    highway_core::{highway::Dependency, Observation, State},
+    ClContext, HighwayProtocol,
+};
 
 use super::Era;
 
@@ -37,6 +40,11 @@ pub(crate) struct EraDump<'a> {
 
     /// The state of the highway instance associated with the era.
     pub(crate) highway_state: &'a State<ClContext>,
+
+    /// The highway instance itself, used to summarize the parts of the state outside of
+    /// `highway_state`.
+    #[serde(skip)]
+    highway: &'a HighwayProtocol<ClContext>,
 }
 
 impl<'a> Display for EraDump<'a> {
@@ -65,6 +73,98 @@ impl<'a> EraDump<'a> {
             accusations: &era.accusations,
             validators: &era.validators,
             highway_state: highway.highway().state(),
+            highway,
         })
     }
+
+    /// Returns a summary of the era's protocol state, small enough to be inspected by hand.
+    pub(crate) fn summary(&self) -> EraSummary<'a> {
+        let state = self.highway_state;
+        let panorama = state.panorama();
+        let latest_unit = panorama
+            .iter_correct(state)
+            .max_by_key(|unit| unit.round_id());
+        let validators = self
+            .highway
+            .highway()
+            .validators()
+            .enumerate_ids()
+            .filter_map(|(idx, public_key)| {
+                let observation = panorama.get(idx)?;
+                let known_units = match observation {
+                    Observation::None => Some(0),
+                    Observation::Correct(hash) => state.unit(hash).seq_number.checked_add(1),
+                    Observation::Faulty => None,
+                };
+                Some(ValidatorSummary {
+                    public_key,
+                    observation,
+                    known_units,
+                    last_seen: state.last_seen(idx),
+                })
+            })
+            .collect();
+        let pending_dependencies = self
+            .highway
+            .pending_dependencies()
+            .map(|(dependency, waiting_vertices)| PendingDependency {
+                dependency,
+                waiting_vertices,
+            })
+            .collect();
+
+        EraSummary {
+            id: self.id,
+            start_time: self.start_time,
+            start_height: self.start_height,
+            latest_round_id: latest_unit.map(|unit| unit.round_id()),
+            latest_round_len: latest_unit.map(|unit| unit.round_len()),
+            unit_count: state.unit_count(),
+            validators,
+            pending_dependencies,
+        }
+    }
+}
+
+/// Summary of an era's protocol state, for debugging stalled finality.
+#[derive(Debug, Serialize)]
+pub(crate) struct EraSummary<'a> {
+    /// The era that is being summarized.
+    pub(crate) id: EraId,
+    /// The scheduled starting time of this era.
+    pub(crate) start_time: Timestamp,
+    /// The height of this era's first block.
+    pub(crate) start_height: u64,
+    /// The round of the most recent unit in the panorama, if any.
+    pub(crate) latest_round_id: Option<Timestamp>,
+    /// The round length of the most recent unit in the panorama, if any.
+    pub(crate) latest_round_len: Option<TimeDiff>,
+    /// The total number of units in the protocol state.
+    pub(crate) unit_count: usize,
+    /// The panorama entry of each validator.
+    pub(crate) validators: Vec<ValidatorSummary<'a>>,
+    /// The dependencies the synchronizer is still waiting for.
+    pub(crate) pending_dependencies: Vec<PendingDependency<'a>>,
+}
+
+/// A single validator's entry in an `EraSummary`.
+#[derive(Debug, Serialize)]
+pub(crate) struct ValidatorSummary<'a> {
+    /// The validator's public key.
+    pub(crate) public_key: &'a PublicKey,
+    /// The validator's latest unit in the panorama, or whether it is faulty.
+    pub(crate) observation: &'a Observation<ClContext>,
+    /// The number of units known by this validator, or `None` if it equivocated.
+    pub(crate) known_units: Option<u64>,
+    /// The latest timestamp at which the validator was seen online.
+    pub(crate) last_seen: Timestamp,
+}
+
+/// A dependency the synchronizer is waiting for.
+#[derive(Debug, Serialize)]
+pub(crate) struct PendingDependency<'a> {
+    /// The missing unit, evidence, endorsement or ping.
+    pub(crate) dependency: &'a Dependency<ClContext>,
+    /// The number of vertices that cannot be added until it arrives.
+    pub(crate) waiting_vertices: u64,
 }
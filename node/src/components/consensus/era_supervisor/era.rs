@@ -10,10 +10,14 @@ use tracing::{debug, warn};
 
 use casper_types::{PublicKey, Timestamp, U512};
 
-use crate::components::consensus::{
-    cl_context::ClContext,
-    consensus_protocol::{ConsensusProtocol, ProposedBlock},
-    protocols::{highway::HighwayProtocol, zug::Zug},
+use crate::{
+    components::consensus::{
+        cl_context::ClContext,
+        consensus_protocol::{ConsensusProtocol, ProposedBlock},
+        protocols::{highway::HighwayProtocol, zug::Zug},
+        EraPerformance, TimerId, ValidatorPerformance,
+    },
+    effect::TimerHandle,
 };
 
 const CASPER_ENABLE_DETAILED_CONSENSUS_METRICS_ENV_VAR: &str =
@@ -52,6 +56,9 @@ pub struct Era {
     pub(crate) start_height: u64,
     /// Pending blocks, waiting for validation and dependencies.
     pub(crate) validation_states: HashMap<ProposedBlock<ClContext>, ValidationState>,
+    /// Blocks which have already been validated successfully in this era, so that they don't need
+    /// to be validated again if received repeatedly.
+    valid_blocks: HashSet<ProposedBlock<ClContext>>,
     /// Validators banned in this and the next BONDED_ERAS eras, because they were faulty in the
     /// previous switch block.
     pub(crate) faulty: HashSet<PublicKey>,
@@ -61,6 +68,11 @@ pub struct Era {
     pub(crate) accusations: HashSet<PublicKey>,
     /// The validator weights.
     pub(crate) validators: BTreeMap<PublicKey, U512>,
+    /// The validators' participation in this era so far, except for the data tracked by the
+    /// consensus protocol instance itself.
+    performance: EraPerformance,
+    /// The timers scheduled by the consensus protocol instance which have not expired yet.
+    timers: HashMap<(Timestamp, TimerId), TimerHandle>,
 }
 
 impl Era {
@@ -71,16 +83,20 @@ impl Era {
         faulty: HashSet<PublicKey>,
         cannot_propose: HashSet<PublicKey>,
         validators: BTreeMap<PublicKey, U512>,
+        performance: EraPerformance,
     ) -> Self {
         Era {
             consensus,
             start_time,
             start_height,
             validation_states: HashMap::new(),
+            valid_blocks: HashSet::new(),
             faulty,
             cannot_propose,
             accusations: HashSet::new(),
             validators,
+            performance,
+            timers: HashMap::new(),
         }
     }
 
@@ -94,6 +110,11 @@ impl Era {
             .insert(proposed_block, ValidationState::new(missing_evidence));
     }
 
+    /// Returns `true` if the block has already been validated successfully in this era.
+    pub(crate) fn is_known_valid(&self, proposed_block: &ProposedBlock<ClContext>) -> bool {
+        self.valid_blocks.contains(proposed_block)
+    }
+
     /// Marks the dependencies of blocks on evidence against validator `pub_key` as resolved and
     /// returns all valid blocks that have no missing dependencies left.
     pub(crate) fn resolve_evidence_and_mark_faulty(
@@ -121,6 +142,7 @@ impl Era {
         valid: bool,
     ) -> bool {
         if valid {
+            self.valid_blocks.insert(proposed_block.clone());
             if let Some(vs) = self.validation_states.get_mut(proposed_block) {
                 if !vs.missing_evidence.is_empty() {
                     vs.validated = true;
@@ -149,6 +171,88 @@ impl Era {
     pub(crate) fn validators(&self) -> &BTreeMap<PublicKey, U512> {
         &self.validators
     }
+
+    /// Records a proposal by `proposer` with the given timestamp, received for validation.
+    ///
+    /// Returns `true` if the validators' performance changed.
+    pub(crate) fn record_proposal(
+        &mut self,
+        proposer: &PublicKey,
+        timestamp: Timestamp,
+        late: bool,
+    ) -> bool {
+        self.validators.contains_key(proposer)
+            && self.performance.record_proposal(proposer, timestamp, late)
+    }
+
+    /// Records a finalized block proposed by `proposer`, at the given height relative to the era's
+    /// first block.
+    ///
+    /// Returns `true` if the validators' performance changed.
+    pub(crate) fn record_finalized_block(
+        &mut self,
+        proposer: &PublicKey,
+        relative_height: u64,
+    ) -> bool {
+        self.validators.contains_key(proposer)
+            && self
+                .performance
+                .record_finalized_block(proposer, relative_height)
+    }
+
+    /// Returns the validators' performance recorded by the era supervisor.
+    pub(crate) fn recorded_performance(&self) -> &EraPerformance {
+        &self.performance
+    }
+
+    /// Returns `true` if a timer with the given ID is already scheduled for `timestamp`.
+    pub(crate) fn has_timer(&self, timestamp: Timestamp, timer_id: TimerId) -> bool {
+        self.timers.contains_key(&(timestamp, timer_id))
+    }
+
+    /// Records a timer scheduled by the consensus protocol instance, so it can be cancelled once
+    /// the era is removed.
+    pub(crate) fn add_timer(
+        &mut self,
+        timestamp: Timestamp,
+        timer_id: TimerId,
+        timer: TimerHandle,
+    ) {
+        self.timers.insert((timestamp, timer_id), timer);
+    }
+
+    /// Forgets about a timer which has expired.
+    pub(crate) fn remove_timer(&mut self, timestamp: Timestamp, timer_id: TimerId) {
+        self.timers.remove(&(timestamp, timer_id));
+    }
+
+    /// Cancels all timers which have not expired yet.
+    pub(crate) fn cancel_timers(&mut self) {
+        for (_, timer) in self.timers.drain() {
+            timer.cancel();
+        }
+    }
+
+    /// Returns the participation of each of this era's validators so far.
+    pub(crate) fn performance(&self) -> BTreeMap<PublicKey, ValidatorPerformance> {
+        let mut units_seen = self.consensus.units_seen();
+        let with_evidence: HashSet<&PublicKey> = self
+            .consensus
+            .validators_with_evidence()
+            .into_iter()
+            .collect();
+        self.validators
+            .keys()
+            .map(|pub_key| {
+                let mut performance = self.performance.get(pub_key);
+                performance.units_seen = units_seen.remove(pub_key).unwrap_or_default();
+                performance.faulty = self.faulty.contains(pub_key)
+                    || self.accusations.contains(pub_key)
+                    || with_evidence.contains(pub_key);
+                (pub_key.clone(), performance)
+            })
+            .collect()
+    }
 }
 
 impl DataSize for Era {
@@ -164,10 +268,13 @@ impl DataSize for Era {
             start_time,
             start_height,
             validation_states,
+            valid_blocks,
             faulty,
             cannot_propose,
             accusations,
             validators,
+            performance,
+            timers,
         } = self;
 
         // `DataSize` cannot be made object safe due its use of associated constants. We implement
@@ -208,9 +315,12 @@ impl DataSize for Era {
             .saturating_add(start_time.estimate_heap_size())
             .saturating_add(start_height.estimate_heap_size())
             .saturating_add(validation_states.estimate_heap_size())
+            .saturating_add(valid_blocks.estimate_heap_size())
             .saturating_add(faulty.estimate_heap_size())
             .saturating_add(cannot_propose.estimate_heap_size())
             .saturating_add(accusations.estimate_heap_size())
             .saturating_add(validators.estimate_heap_size())
+            .saturating_add(performance.estimate_heap_size())
+            .saturating_add(timers.estimate_heap_size())
     }
 }
@@ -0,0 +1,150 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+};
+
+use datasize::DataSize;
+
+use casper_types::EraId;
+
+use super::SerializedMessage;
+use crate::types::NodeId;
+
+/// Consensus messages for eras that are not initialized yet, e.g. received while the node is still
+/// joining, which are handled once their era is initialized.
+///
+/// The buffered messages' total size is limited per sender, so that a single peer can't make the
+/// buffer grow without bound.
+#[derive(DataSize, Debug)]
+pub(super) struct PendingMessages {
+    /// The buffered messages, by era.
+    by_era: BTreeMap<EraId, Vec<(NodeId, SerializedMessage)>>,
+    /// The total size of the buffered messages from each sender.
+    bytes_by_sender: HashMap<NodeId, usize>,
+    /// The maximum total size of the buffered messages from a single sender.
+    max_bytes_per_sender: usize,
+}
+
+impl PendingMessages {
+    pub(super) fn new(max_bytes_per_sender: usize) -> Self {
+        PendingMessages {
+            by_era: BTreeMap::new(),
+            bytes_by_sender: HashMap::new(),
+            max_bytes_per_sender,
+        }
+    }
+
+    /// Buffers the message, unless that would exceed the sender's limit.  Returns `false` if the
+    /// message was dropped.
+    pub(super) fn push(
+        &mut self,
+        era_id: EraId,
+        sender: NodeId,
+        payload: SerializedMessage,
+    ) -> bool {
+        let size = payload.as_raw().len();
+        let sender_bytes = self
+            .bytes_by_sender
+            .get(&sender)
+            .copied()
+            .unwrap_or(0)
+            .saturating_add(size);
+        if sender_bytes > self.max_bytes_per_sender {
+            return false;
+        }
+        self.bytes_by_sender.insert(sender, sender_bytes);
+        self.by_era
+            .entry(era_id)
+            .or_default()
+            .push((sender, payload));
+        true
+    }
+
+    /// Removes and returns the messages buffered for the given era.
+    pub(super) fn take(&mut self, era_id: EraId) -> Vec<(NodeId, SerializedMessage)> {
+        let messages = self.by_era.remove(&era_id).unwrap_or_default();
+        self.release(&messages);
+        messages
+    }
+
+    /// Drops the messages for all eras outside the given range.
+    pub(super) fn retain_eras(&mut self, eras: RangeInclusive<EraId>) {
+        let (retained, dropped) = std::mem::take(&mut self.by_era)
+            .into_iter()
+            .partition(|(era_id, _)| eras.contains(era_id));
+        self.by_era = retained;
+        let dropped: BTreeMap<_, _> = dropped;
+        for messages in dropped.values() {
+            self.release(messages);
+        }
+    }
+
+    /// Returns the number of buffered messages for the given era.
+    #[cfg(test)]
+    fn count(&self, era_id: EraId) -> usize {
+        self.by_era.get(&era_id).map_or(0, Vec::len)
+    }
+
+    fn release(&mut self, messages: &[(NodeId, SerializedMessage)]) {
+        for (sender, payload) in messages {
+            if let Some(sender_bytes) = self.bytes_by_sender.get_mut(sender) {
+                *sender_bytes = sender_bytes.saturating_sub(payload.as_raw().len());
+                if *sender_bytes == 0 {
+                    self.bytes_by_sender.remove(sender);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    fn message(size: usize) -> SerializedMessage {
+        SerializedMessage(vec![0; size])
+    }
+
+    #[test]
+    fn should_limit_buffered_bytes_per_sender() {
+        let mut rng = TestRng::new();
+        let sender = NodeId::random(&mut rng);
+        let other_sender = NodeId::random(&mut rng);
+        let mut pending = PendingMessages::new(100);
+        let era_id = EraId::new(3);
+
+        assert!(pending.push(era_id, sender, message(60)));
+        assert!(!pending.push(era_id, sender, message(60)));
+        assert!(pending.push(era_id, sender, message(40)));
+        // other senders have their own limit
+        assert!(pending.push(era_id, other_sender, message(100)));
+        assert_eq!(pending.count(era_id), 3);
+
+        // handling the era's messages frees up the senders' limits
+        assert_eq!(pending.take(era_id).len(), 3);
+        assert!(pending.bytes_by_sender.is_empty());
+        assert!(pending.push(era_id, sender, message(100)));
+    }
+
+    #[test]
+    fn should_drop_messages_for_eras_outside_range() {
+        let mut rng = TestRng::new();
+        let sender = NodeId::random(&mut rng);
+        let mut pending = PendingMessages::new(100);
+        for era in 1..=5 {
+            assert!(pending.push(EraId::new(era), sender, message(20)));
+        }
+        assert!(!pending.push(EraId::new(6), sender, message(20)));
+
+        pending.retain_eras(EraId::new(2)..=EraId::new(3));
+        assert_eq!(pending.count(EraId::new(1)), 0);
+        assert_eq!(pending.count(EraId::new(2)), 1);
+        assert_eq!(pending.count(EraId::new(3)), 1);
+        assert_eq!(pending.count(EraId::new(4)), 0);
+        assert_eq!(pending.bytes_by_sender.get(&sender), Some(&40));
+        // the dropped messages no longer count towards the sender's limit
+        assert!(pending.push(EraId::new(3), sender, message(60)));
+    }
+}
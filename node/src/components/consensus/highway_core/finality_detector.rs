@@ -1,12 +1,14 @@
 //! Functions for detecting finality of proposed blocks and calculating rewards.
 
 mod horizon;
+mod liveness;
 mod rewards;
 
 use std::iter;
 
 use datasize::DataSize;
-use tracing::{trace, warn};
+use num_rational::Ratio;
+use tracing::{info, trace, warn};
 
 use casper_types::Timestamp;
 
@@ -20,6 +22,7 @@ use crate::components::consensus::{
     utils::{ValidatorIndex, Weight},
 };
 use horizon::Horizon;
+use liveness::liveness_faulty_validators;
 pub use rewards::{
     assigned_weight_and_latest_unit, compute_rewards, compute_rewards_for, find_max_quora,
     round_participation, RoundParticipation,
@@ -42,6 +45,10 @@ where
     last_finalized: Option<C::Hash>,
     /// The fault tolerance threshold.
     ftt: Weight,
+    /// Validators who don't propose a block in more than this fraction of the rounds they lead are
+    /// reported as inactive in the terminal block.
+    #[data_size(skip)]
+    max_missed_proposals: Ratio<u64>,
 }
 
 impl<C: Context> FinalityDetector<C> {
@@ -50,9 +57,17 @@ impl<C: Context> FinalityDetector<C> {
         FinalityDetector {
             last_finalized: None,
             ftt,
+            max_missed_proposals: Ratio::from_integer(0),
         }
     }
 
+    /// Sets the fraction of their rounds validators can fail to propose in without being reported
+    /// as inactive.
+    pub(crate) fn with_max_missed_proposals(mut self, max_missed_proposals: Ratio<u64>) -> Self {
+        self.max_missed_proposals = max_missed_proposals;
+        self
+    }
+
     /// Returns all blocks that have been finalized since the last call.
     pub(crate) fn run<'a>(
         &'a mut self,
@@ -72,9 +87,9 @@ impl<C: Context> FinalityDetector<C> {
             let to_id = |vidx: ValidatorIndex| highway.validators().id(vidx).unwrap().clone();
             let block = state.block(bhash);
             let unit = state.unit(bhash);
-            let terminal_block_data = state
-                .is_terminal_block(bhash)
-                .then(|| Self::create_terminal_block_data(bhash, unit, highway));
+            let terminal_block_data = state.is_terminal_block(bhash).then(|| {
+                Self::create_terminal_block_data(bhash, unit, highway, self.max_missed_proposals)
+            });
             let finalized_block = FinalizedBlock {
                 value: block.value.clone(),
                 timestamp: unit.timestamp,
@@ -177,10 +192,14 @@ impl<C: Context> FinalityDetector<C> {
 
     /// Creates the information for the terminal block: which validators were inactive, and how
     /// rewards should be distributed.
+    ///
+    /// Validators who failed to propose in more than `max_missed_proposals` of the rounds they
+    /// were the leader of count as inactive, too.
     fn create_terminal_block_data(
         bhash: &C::Hash,
         unit: &Unit<C>,
         highway: &Highway<C>,
+        max_missed_proposals: Ratio<u64>,
     ) -> TerminalBlockData<C> {
         // Safe to unwrap: Index exists, since we have units from them.
         let to_id = |vidx: ValidatorIndex| highway.validators().id(vidx).unwrap().clone();
@@ -201,7 +220,19 @@ impl<C: Context> FinalityDetector<C> {
                 .timestamp
                 .saturating_add(state.params().max_round_length());
         let inactive_validators = if sufficient_time_for_activity {
-            unit.panorama.iter_none().map(to_id).collect()
+            let liveness_faulty = liveness_faulty_validators(state, unit, max_missed_proposals);
+            if !liveness_faulty.is_empty() {
+                let validators: Vec<_> = liveness_faulty.iter().map(|vidx| to_id(*vidx)).collect();
+                info!(
+                    ?validators,
+                    "validators failed to propose in most of their rounds"
+                );
+            }
+            unit.panorama
+                .iter_none()
+                .chain(liveness_faulty)
+                .map(to_id)
+                .collect()
         } else {
             Vec::new()
         };
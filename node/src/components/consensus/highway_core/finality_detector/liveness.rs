@@ -0,0 +1,193 @@
+use std::collections::{BTreeSet, HashSet};
+
+use num_rational::Ratio;
+
+use super::{round_participation, RoundParticipation};
+use crate::components::consensus::{
+    highway_core::state::{State, Unit},
+    traits::Context,
+    utils::{ValidatorIndex, ValidatorMap},
+};
+
+/// The minimum number of rounds a validator must have been the leader of before it can be
+/// considered liveness-faulty. With fewer rounds, a bit of bad luck could be enough.
+const MIN_LED_ROUNDS: u64 = 3;
+
+/// Returns the validators who were the leader in at least `MIN_LED_ROUNDS` of the rounds seen by
+/// `unit`, but failed to propose a block in more than `max_missed` of them. If `max_missed` is
+/// zero, the check is disabled and no validators are returned.
+///
+/// Only the unit's panorama is taken into account, so that all nodes agree on the result. The
+/// unit's own round is excluded, since its proposal might not be cited yet. Validators that are
+/// faulty or haven't produced any units in the panorama are not included: They are reported as
+/// equivocators or inactive validators already.
+pub(crate) fn liveness_faulty_validators<C: Context>(
+    state: &State<C>,
+    unit: &Unit<C>,
+    max_missed: Ratio<u64>,
+) -> Vec<ValidatorIndex> {
+    if max_missed == Ratio::from_integer(0) || max_missed >= Ratio::from_integer(1) {
+        return Vec::new();
+    }
+    let panorama = &unit.panorama;
+
+    // Collect all rounds anyone participated in, and all proposals.
+    let mut round_ids = BTreeSet::new();
+    let mut proposals = HashSet::new();
+    for latest_hash in panorama.iter_correct_hashes() {
+        for (hash, cited) in state.swimlane(latest_hash) {
+            round_ids.insert(cited.round_id());
+            if cited.block == *hash && cited.timestamp == cited.round_id() {
+                proposals.insert((cited.creator, cited.timestamp));
+            }
+        }
+    }
+
+    // Count the rounds in which each validator was the leader, and in which it didn't propose.
+    let mut led = ValidatorMap::from(vec![0u64; panorama.len()]);
+    let mut missed = ValidatorMap::from(vec![0u64; panorama.len()]);
+    for r_id in round_ids.range(..unit.round_id()) {
+        let leader = state.leader(*r_id);
+        if !panorama[leader].is_correct() {
+            continue;
+        }
+        if matches!(
+            round_participation(state, &panorama[leader], *r_id),
+            RoundParticipation::Unassigned
+        ) {
+            // The round ID is not the beginning of one of the leader's rounds.
+            continue;
+        }
+        led[leader] = led[leader].saturating_add(1);
+        if !proposals.contains(&(leader, *r_id)) {
+            missed[leader] = missed[leader].saturating_add(1);
+        }
+    }
+
+    led.enumerate()
+        .zip(missed.iter())
+        .filter(|((_, led), missed)| {
+            **led >= MIN_LED_ROUNDS && Ratio::new(**missed, **led) > max_missed
+        })
+        .map(|((vidx, _), _)| vidx)
+        .collect()
+}
+
+#[allow(unused_qualifications)] // This is to suppress warnings originating in the test macros.
+#[allow(clippy::arithmetic_side_effects)] // Overflows in tests would panic anyway.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::consensus::highway_core::state::{tests::*, Observation};
+
+    /// The length of the rounds in the test states.
+    const ROUND_LEN: u64 = 16;
+
+    /// Creates a state with the given number of rounds. In every round, the leader proposes a block
+    /// at the beginning, unless it is Carol and `carol_proposes` returns `false` for the number of
+    /// rounds she led before. Then everyone creates a witness unit half-way through the round, once
+    /// there is a block to cite.
+    ///
+    /// Returns the state, the hash of a unit in the next round that sees everything, and the number
+    /// of rounds Carol led.
+    fn create_state(
+        rounds: u64,
+        carol_proposes: impl Fn(u64) -> bool,
+    ) -> Result<(State<TestContext>, u64, u64), AddUnitError<TestContext>> {
+        let params = test_params(0).with_end_height(1000);
+        let mut state = State::new(WEIGHTS, params, vec![], vec![]);
+
+        let mut latest: Vec<Observation<TestContext>> = vec![N, N, N];
+        let mut carol_led = 0;
+        for round in 0..rounds {
+            let r_id = round * ROUND_LEN;
+            let leader = state.leader(r_id.into());
+            let proposes = leader != CAROL || carol_proposes(carol_led);
+            if leader == CAROL {
+                carol_led += 1;
+            }
+            if proposes {
+                let (a, b, c) = (latest[0].clone(), latest[1].clone(), latest[2].clone());
+                let proposal = add_unit!(state, leader, r_id, 0u8, 0x1; a, b, c)?;
+                latest[leader.0 as usize] = Observation::Correct(proposal);
+            }
+            // Witness units are ballots, which have to cite a block.
+            if latest.iter().all(|observation| *observation == N) {
+                continue;
+            }
+            for creator in [ALICE, BOB, CAROL] {
+                let (a, b, c) = (latest[0].clone(), latest[1].clone(), latest[2].clone());
+                let witness = add_unit!(state, creator, r_id + ROUND_LEN / 2, 0u8, None; a, b, c)?;
+                latest[creator.0 as usize] = Observation::Correct(witness);
+            }
+        }
+
+        let (a, b, c) = (latest[0].clone(), latest[1].clone(), latest[2].clone());
+        let last = add_unit!(state, ALICE, rounds * ROUND_LEN, 0u8, None; a, b, c)?;
+        Ok((state, last, carol_led))
+    }
+
+    #[test]
+    fn should_detect_validator_that_does_not_propose() -> Result<(), AddUnitError<TestContext>> {
+        let (state, last, carol_led) = create_state(30, |_| false)?;
+        assert!(
+            carol_led >= MIN_LED_ROUNDS,
+            "Carol needs to lead some rounds"
+        );
+        let unit = state.unit(&last);
+
+        let faulty = liveness_faulty_validators(&state, unit, Ratio::new(1, 2));
+        assert_eq!(vec![CAROL], faulty);
+        // With the threshold at 100%, no validator is reported.
+        assert!(liveness_faulty_validators(&state, unit, Ratio::from_integer(1)).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_check_if_disabled() -> Result<(), AddUnitError<TestContext>> {
+        let (state, last, _) = create_state(30, |_| false)?;
+        let unit = state.unit(&last);
+        assert!(liveness_faulty_validators(&state, unit, Ratio::from_integer(0)).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn should_apply_threshold_to_missed_fraction() -> Result<(), AddUnitError<TestContext>> {
+        // Carol proposes in every other round she leads, i.e. misses about half of them.
+        let (state, last, carol_led) = create_state(40, |led| led % 2 == 0)?;
+        assert!(
+            carol_led >= 2 * MIN_LED_ROUNDS,
+            "Carol needs to lead some rounds"
+        );
+        let unit = state.unit(&last);
+
+        assert!(liveness_faulty_validators(&state, unit, Ratio::new(9, 10)).is_empty());
+        assert!(liveness_faulty_validators(&state, unit, Ratio::new(1, 2)).is_empty());
+        assert_eq!(
+            vec![CAROL],
+            liveness_faulty_validators(&state, unit, Ratio::new(1, 4))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_report_validator_with_few_led_rounds() -> Result<(), AddUnitError<TestContext>> {
+        // Find a number of rounds in which Carol leads fewer than `MIN_LED_ROUNDS`.
+        let state = State::<TestContext>::new(WEIGHTS, test_params(0), vec![], vec![]);
+        let carol_led_until = |rounds: u64| {
+            (0..rounds)
+                .filter(|round| state.leader((round * ROUND_LEN).into()) == CAROL)
+                .count() as u64
+        };
+        let rounds = (1..)
+            .take_while(|rounds| carol_led_until(*rounds) < MIN_LED_ROUNDS)
+            .last()
+            .expect("Carol needs to lead some rounds");
+
+        let (state, last, carol_led) = create_state(rounds, |_| false)?;
+        assert!(carol_led < MIN_LED_ROUNDS);
+        let unit = state.unit(&last);
+        assert!(liveness_faulty_validators(&state, unit, Ratio::new(1, 2)).is_empty());
+        Ok(())
+    }
+}
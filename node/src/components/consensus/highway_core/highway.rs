@@ -7,7 +7,7 @@ pub use vertex::{
     Dependency, Endorsements, HashedWireUnit, Ping, SignedWireUnit, Vertex, WireUnit,
 };
 
-use std::path::PathBuf;
+use std::{iter, path::PathBuf};
 
 use datasize::DataSize;
 use thiserror::Error;
@@ -73,7 +73,6 @@ impl<C: Context> PreValidatedVertex<C> {
         self.0.timestamp()
     }
 
-    #[cfg(test)]
     pub(crate) fn into_vertex(self) -> Vertex<C> {
         self.0
     }
@@ -245,7 +244,7 @@ impl<C: Context> Highway<C> {
     /// Returns the next missing dependency, or `None` if all dependencies of `pvv` are satisfied.
     ///
     /// If this returns `None`, `validate_vertex` can be called.
-    pub(super) fn missing_dependency(&self, pvv: &PreValidatedVertex<C>) -> Option<Dependency<C>> {
+    pub(crate) fn missing_dependency(&self, pvv: &PreValidatedVertex<C>) -> Option<Dependency<C>> {
         match pvv.inner() {
             Vertex::Evidence(_) | Vertex::Ping(_) => None,
             Vertex::Endorsements(endorsements) => {
@@ -473,6 +472,30 @@ impl<C: Context> Highway<C> {
         &self.state
     }
 
+    /// Returns all vertices in the protocol state: the direct evidence, followed by the units in
+    /// the order of their timestamps, each followed by its endorsements, if any.
+    ///
+    /// Adding them to a new instance in this order reconstructs the state; only units with equal
+    /// timestamps can come before one of their dependencies.
+    pub(crate) fn vertices(&self) -> Vec<Vertex<C>> {
+        let mut units: Vec<_> = self
+            .state
+            .unit_hashes()
+            .filter_map(|hash| self.state.wire_unit(hash, self.instance_id))
+            .collect();
+        units.sort_by_key(|swunit| (swunit.wire_unit().timestamp, swunit.wire_unit().seq_number));
+        let evidence = self
+            .state
+            .faulty_validators()
+            .filter_map(|idx| self.state.maybe_evidence(idx))
+            .map(|evidence| Vertex::Evidence(evidence.clone()));
+        let units = units.into_iter().flat_map(|swunit| {
+            let endorsements = self.state.maybe_endorsements(&swunit.hash());
+            iter::once(Vertex::Unit(swunit)).chain(endorsements.map(Vertex::Endorsements))
+        });
+        evidence.chain(units).collect()
+    }
+
     /// Sets the pause status: While paused we don't create any new units, just pings.
     pub(crate) fn set_paused(&mut self, paused: bool) {
         if let Some(av) = &mut self.active_validator {
@@ -881,11 +881,15 @@ impl<C: Context> State<C> {
     }
 
     /// Returns the number of units received.
-    #[cfg(test)]
     pub(crate) fn unit_count(&self) -> usize {
         self.units.len()
     }
 
+    /// Returns an iterator over the hashes of all units received.
+    pub(crate) fn unit_hashes(&self) -> impl Iterator<Item = &C::Hash> {
+        self.units.keys()
+    }
+
     /// Returns the set of units (by hash) that are endorsed and seen from the panorama.
     pub fn seen_endorsed(&self, pan: &Panorama<C>) -> BTreeSet<C::Hash> {
         if !ENABLE_ENDORSEMENTS {
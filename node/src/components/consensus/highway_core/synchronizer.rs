@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Debug,
     iter,
 };
@@ -7,7 +7,7 @@ use std::{
 use datasize::DataSize;
 use itertools::Itertools;
 use rand::{thread_rng, RngCore};
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 
 use casper_types::Timestamp;
 
@@ -99,6 +99,32 @@ impl<C: Context> PendingVertices<C> {
         self.0.keys().any(|pvv| &pvv.inner().id() == d)
     }
 
+    /// Returns the ID of every vertex other than evidence received from the given sender,
+    /// together with the time it was received.
+    fn receipts_from(
+        &self,
+        sender: NodeId,
+    ) -> impl Iterator<Item = (Timestamp, Dependency<C>)> + '_ {
+        self.0
+            .iter()
+            .filter(|(pvv, _)| !pvv.inner().is_evidence())
+            .filter_map(move |(pvv, time_by_sender)| {
+                let time_received = time_by_sender.get(&sender)?;
+                Some((*time_received, pvv.inner().id()))
+            })
+    }
+
+    /// Forgets that the vertices with the given IDs were received from the given sender, and
+    /// removes the ones no other sender is holding.
+    fn remove_sender(&mut self, sender: NodeId, ids: &BTreeSet<Dependency<C>>) {
+        self.0.retain(|pvv, time_by_sender| {
+            if ids.contains(&pvv.inner().id()) {
+                time_by_sender.remove(&sender);
+            }
+            !time_by_sender.is_empty()
+        });
+    }
+
     /// Drops all pending vertices other than evidence.
     pub(crate) fn retain_evidence_only(&mut self) {
         self.0.retain(|pvv, _| pvv.inner().is_evidence());
@@ -193,6 +219,16 @@ where
     /// Keeps track of the requests we've sent so far and the recipients.
     /// Used to decide whether we should ask more nodes for a particular dependency.
     requests_sent: BTreeMap<Dependency<C>, HashSet<NodeId>>,
+    /// The number of dependency requests per peer that haven't been answered yet.
+    requests_in_flight: HashMap<NodeId, usize>,
+    /// The maximum number of unanswered dependency requests per peer.
+    max_requests_per_peer: usize,
+    /// The maximum number of vertices per peer waiting for a dependency or for their timestamp.
+    /// If exceeded, the ones received from that peer least recently are dropped.
+    max_pending_vertices_per_peer: usize,
+    /// An upper bound on the number of vertices per peer waiting for a dependency or for their
+    /// timestamp: the number at the last count, plus the ones added since.
+    pending_vertices_per_peer: HashMap<NodeId, usize>,
     /// Boolean flag indicating whether we're synchronizing current era.
     pub(crate) current_era: bool,
 }
@@ -207,16 +243,36 @@ impl<C: Context + 'static> Synchronizer<C> {
             oldest_seen_panorama: iter::repeat(None).take(validator_len).collect(),
             instance_id,
             requests_sent: BTreeMap::new(),
+            requests_in_flight: HashMap::new(),
+            max_requests_per_peer: usize::MAX,
+            max_pending_vertices_per_peer: usize::MAX,
+            pending_vertices_per_peer: HashMap::new(),
             current_era: true,
         }
     }
 
+    /// Sets the maximum number of unanswered dependency requests per peer.
+    pub(crate) fn with_max_requests_per_peer(mut self, max_requests_per_peer: usize) -> Self {
+        self.max_requests_per_peer = max_requests_per_peer;
+        self
+    }
+
+    /// Sets the maximum number of pending vertices per peer.
+    pub(crate) fn with_max_pending_vertices_per_peer(
+        mut self,
+        max_pending_vertices_per_peer: usize,
+    ) -> Self {
+        self.max_pending_vertices_per_peer = max_pending_vertices_per_peer;
+        self
+    }
+
     /// Removes expired pending vertices from the queues, and schedules the next purge.
     pub(crate) fn purge_vertices(&mut self, oldest: Timestamp) {
         info!("purging synchronizer queues");
         let no_deps_expired = self.vertices_no_deps.remove_expired(oldest);
         trace!(?no_deps_expired, "expired no dependencies");
         self.requests_sent.clear();
+        self.requests_in_flight.clear();
         let to_be_added_later_expired =
             Self::remove_expired(&mut self.vertices_to_be_added_later, oldest);
         trace!(
@@ -278,10 +334,12 @@ impl<C: Context + 'static> Synchronizer<C> {
         sender: NodeId,
         pvv: PreValidatedVertex<C>,
     ) {
+        self.mark_received(&pvv);
         self.vertices_to_be_added_later
             .entry(future_timestamp)
             .or_default()
             .add(sender, pvv, now);
+        self.limit_pending_vertices(sender);
     }
 
     /// Schedules calls to `add_vertex` on any vertices in `vertices_to_be_added_later` which are
@@ -316,6 +374,7 @@ impl<C: Context + 'static> Synchronizer<C> {
         now: Timestamp,
     ) -> ProtocolOutcomes<C> {
         self.update_last_seen(&pvv);
+        self.mark_received(&pvv);
         let pv = PendingVertex::new(sender, pvv, now);
         self.schedule_add_vertices(iter::once(pv))
     }
@@ -328,6 +387,26 @@ impl<C: Context + 'static> Synchronizer<C> {
         }
     }
 
+    /// Marks the requests for the given vertex as answered, so they don't count towards the
+    /// peers' limits anymore.
+    fn mark_received(&mut self, pvv: &PreValidatedVertex<C>) {
+        if let Some(peers) = self.requests_sent.remove(&pvv.inner().id()) {
+            self.release_requests(peers);
+        }
+    }
+
+    /// Decrements the number of requests in flight for each of the given peers.
+    fn release_requests(&mut self, peers: HashSet<NodeId>) {
+        for peer in peers {
+            if let Some(count) = self.requests_in_flight.get_mut(&peer) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    self.requests_in_flight.remove(&peer);
+                }
+            }
+        }
+    }
+
     /// Moves all vertices whose known missing dependency is now satisfied into the
     /// `vertices_to_be_added` queue.
     pub(crate) fn remove_satisfied_deps(&mut self, highway: &Highway<C>) -> ProtocolOutcomes<C> {
@@ -339,13 +418,13 @@ impl<C: Context + 'static> Synchronizer<C> {
             .collect_vec();
         // Safe to unwrap: We know the keys exist.
         // TODO: Replace with BTreeMap::drain_filter once stable.
-        let pvs = satisfied_deps
-            .into_iter()
-            .flat_map(|dep| {
-                self.requests_sent.remove(&dep);
-                self.vertices_awaiting_deps.remove(&dep).unwrap()
-            })
-            .collect_vec();
+        let mut pvs = vec![];
+        for dep in satisfied_deps {
+            if let Some(peers) = self.requests_sent.remove(&dep) {
+                self.release_requests(peers);
+            }
+            pvs.extend(self.vertices_awaiting_deps.remove(&dep).unwrap());
+        }
         self.schedule_add_vertices(pvs)
     }
 
@@ -386,6 +465,16 @@ impl<C: Context + 'static> Synchronizer<C> {
                     self.add_missing_dependency(dep.clone(), pv);
                     continue;
                 }
+                if self
+                    .vertices_to_be_added_later
+                    .values()
+                    .any(|pvs| pvs.contains_dependency(&transitive_dependency))
+                {
+                    // `dep` is already downloaded, but its timestamp is in the future. It will be
+                    // added once it's due, so we don't have to request it again either.
+                    self.add_missing_dependency(dep.clone(), pv);
+                    continue;
+                }
                 // We are still missing a dependency. Store the vertex in the map and request
                 // the dependency from the sender.
                 // Make `pv` depend on the direct dependency `dep` and not `transitive_dependency`
@@ -423,7 +512,16 @@ impl<C: Context + 'static> Synchronizer<C> {
                     return (Some(dep_pv), outcomes);
                 }
                 // If we have already requested the dependency from this peer, or from the maximum
-                // number of peers, do nothing.
+                // number of peers, or if the peer hasn't answered the maximum number of requests
+                // yet, do nothing.
+                let in_flight = self.requests_in_flight.get(&sender).copied().unwrap_or(0);
+                if in_flight >= self.max_requests_per_peer {
+                    debug!(
+                        dependency = ?transitive_dependency, %sender,
+                        "too many requests in flight"
+                    );
+                    continue;
+                }
                 let entry = self
                     .requests_sent
                     .entry(transitive_dependency.clone())
@@ -431,6 +529,8 @@ impl<C: Context + 'static> Synchronizer<C> {
                 if entry.len() >= max_requests_for_vertex || !entry.insert(sender) {
                     continue;
                 }
+                self.requests_in_flight
+                    .insert(sender, in_flight.saturating_add(1));
                 // Otherwise request the missing dependency from the sender.
                 let uuid = thread_rng().next_u64();
                 debug!(?uuid, dependency = ?transitive_dependency, %sender, "requesting dependency");
@@ -475,7 +575,65 @@ impl<C: Context + 'static> Synchronizer<C> {
 
     /// Adds a vertex with a known missing dependency to the queue.
     fn add_missing_dependency(&mut self, dep: Dependency<C>, pv: PendingVertex<C>) {
-        self.vertices_awaiting_deps.entry(dep).or_default().push(pv)
+        let sender = *pv.sender();
+        self.vertices_awaiting_deps.entry(dep).or_default().push(pv);
+        self.limit_pending_vertices(sender);
+    }
+
+    /// Counts a vertex from `sender` that is waiting for a dependency or for its timestamp, and
+    /// if the peer exceeds its limit, drops the ones received from it least recently.
+    ///
+    /// Only the peer's own receipts are dropped, so a peer flooding us can't push out the vertices
+    /// received from others, and evidence is never dropped.  The vertices are only counted once
+    /// the upper bound exceeds the limit, and are then reduced to 90% of it, so that the cost of
+    /// counting is spread over many insertions.
+    fn limit_pending_vertices(&mut self, sender: NodeId) {
+        let upper_bound = self.pending_vertices_per_peer.entry(sender).or_default();
+        *upper_bound = upper_bound.saturating_add(1);
+        if *upper_bound <= self.max_pending_vertices_per_peer {
+            return;
+        }
+        let mut receipts: Vec<_> = self
+            .vertices_awaiting_deps
+            .values()
+            .chain(self.vertices_to_be_added_later.values())
+            .flat_map(|pvs| pvs.receipts_from(sender))
+            .collect();
+        let retained = self
+            .max_pending_vertices_per_peer
+            .saturating_sub(self.max_pending_vertices_per_peer / 10);
+        if receipts.len() <= retained {
+            if receipts.is_empty() {
+                self.pending_vertices_per_peer.remove(&sender);
+            } else {
+                self.pending_vertices_per_peer
+                    .insert(sender, receipts.len());
+            }
+            return;
+        }
+        receipts.sort_unstable();
+        let excess = receipts.len().saturating_sub(retained);
+        let evicted: BTreeSet<Dependency<C>> = receipts
+            .into_iter()
+            .take(excess)
+            .map(|(_, id)| id)
+            .collect();
+        warn!(
+            era_id = ?self.instance_id, %sender,
+            count = evicted.len(),
+            "too many pending vertices from peer; dropping the oldest ones"
+        );
+        for pvs in self
+            .vertices_awaiting_deps
+            .values_mut()
+            .chain(self.vertices_to_be_added_later.values_mut())
+        {
+            pvs.remove_sender(sender, &evicted);
+        }
+        self.vertices_awaiting_deps.retain(|_, pvs| !pvs.is_empty());
+        self.vertices_to_be_added_later
+            .retain(|_, pvs| !pvs.is_empty());
+        self.pending_vertices_per_peer.insert(sender, retained);
     }
 
     #[cfg(test)]
@@ -491,6 +649,13 @@ impl<C: Context + 'static> Synchronizer<C> {
         self.vertices_awaiting_deps.contains_key(dep)
     }
 
+    /// Returns the dependencies we are waiting for, with the number of vertices waiting for each.
+    pub(crate) fn pending_dependencies(&self) -> impl Iterator<Item = (&Dependency<C>, u64)> {
+        self.vertices_awaiting_deps
+            .iter()
+            .map(|(dep, pending_vertices)| (dep, pending_vertices.len()))
+    }
+
     /// Drops all vertices that (directly or indirectly) have the specified dependencies, and
     /// returns the set of their senders. If the specified dependencies are known to be invalid,
     /// those senders must be faulty.
@@ -510,6 +675,8 @@ impl<C: Context + 'static> Synchronizer<C> {
         self.vertices_to_be_added_later.clear();
         self.vertices_no_deps.retain_evidence_only();
         self.requests_sent.clear();
+        self.requests_in_flight.clear();
+        self.pending_vertices_per_peer.clear();
     }
 
     /// Schedules vertices to be added to the protocol state.
@@ -351,6 +351,178 @@ fn transitive_proposal_dependency() {
     }
 }
 
+#[test]
+fn limit_requests_per_peer() {
+    let params = test_params(0);
+    let mut state = State::new(WEIGHTS, params.clone(), vec![], vec![]);
+    let util_highway =
+        Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params.clone());
+
+    // Alice   a0 — a1
+    //
+    // Bob     b0 — b1
+    let a0 = add_unit!(state, ALICE, 0xA; N, N, N).unwrap();
+    let b0 = add_unit!(state, BOB, 0xB; N, N, N).unwrap();
+    let a1 = add_unit!(state, ALICE, None; a0, N, N).unwrap();
+    let b1 = add_unit!(state, BOB, None; N, b0, N).unwrap();
+
+    // Returns the WireUnit with the specified hash.
+    let unit = |hash: u64| Vertex::Unit(state.wire_unit(&hash, TEST_INSTANCE_ID).unwrap());
+    // Returns the PreValidatedVertex with the specified hash.
+    let pvv = |hash: u64| util_highway.pre_validate_vertex(unit(hash)).unwrap();
+
+    let peer0 = NodeId::from([0; 64]);
+
+    // Create a synchronizer that allows only one unanswered request per peer.
+    let max_requests_for_vertex = 5;
+    let mut sync = Synchronizer::<TestContext>::new(WEIGHTS.len(), TEST_INSTANCE_ID)
+        .with_max_requests_per_peer(1);
+    let highway = Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params);
+    let now = 0x100.into();
+
+    // `a1` is missing `a0`, which we request from `peer0`.
+    let _ = sync.schedule_add_vertex(peer0, pvv(a1), now);
+    let (pv, outcomes) =
+        sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+    assert!(pv.is_none());
+    assert_targeted_message(&unwrap_single(outcomes), &peer0, Dependency::Unit(a0));
+
+    // `b1` is missing `b0`, but `peer0` hasn't answered our first request yet.
+    let _ = sync.schedule_add_vertex(peer0, pvv(b1), now);
+    let (pv, outcomes) =
+        sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+    assert!(pv.is_none());
+    assert!(outcomes.is_empty(), "unexpected outcomes: {:?}", outcomes);
+
+    // Once `a0` arrives, we can request `b0` the next time `peer0` sends us `b1`.
+    let _ = sync.schedule_add_vertex(peer0, pvv(a0), now);
+    let (pv, outcomes) =
+        sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+    assert_eq!(Dependency::Unit(a0), pv.unwrap().vertex().id());
+    assert!(outcomes.is_empty());
+    let _ = sync.schedule_add_vertex(peer0, pvv(b1), now);
+    let (pv, outcomes) =
+        sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+    assert!(pv.is_none());
+    assert_targeted_message(&unwrap_single(outcomes), &peer0, Dependency::Unit(b0));
+}
+
+#[test]
+fn evict_oldest_pending_vertices() {
+    let params = test_params(0);
+    let mut state = State::new(WEIGHTS, params.clone(), vec![], vec![]);
+    let util_highway =
+        Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params.clone());
+
+    // Alice   a0 — a1
+    //
+    // Bob     b0 — b1
+    //
+    // Carol   c0 — c1
+    let a0 = add_unit!(state, ALICE, 0xA; N, N, N).unwrap();
+    let b0 = add_unit!(state, BOB, 0xB; N, N, N).unwrap();
+    let c0 = add_unit!(state, CAROL, 0xC; N, N, N).unwrap();
+    let a1 = add_unit!(state, ALICE, None; a0, N, N).unwrap();
+    let b1 = add_unit!(state, BOB, None; N, b0, N).unwrap();
+    let c1 = add_unit!(state, CAROL, None; N, N, c0).unwrap();
+
+    // Returns the WireUnit with the specified hash.
+    let unit = |hash: u64| Vertex::Unit(state.wire_unit(&hash, TEST_INSTANCE_ID).unwrap());
+    // Returns the PreValidatedVertex with the specified hash.
+    let pvv = |hash: u64| util_highway.pre_validate_vertex(unit(hash)).unwrap();
+
+    let peer0 = NodeId::from([0; 64]);
+
+    // Create a synchronizer that holds at most two pending vertices per peer.
+    let max_requests_for_vertex = 5;
+    let mut sync = Synchronizer::<TestContext>::new(WEIGHTS.len(), TEST_INSTANCE_ID)
+        .with_max_pending_vertices_per_peer(2);
+    let highway = Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params);
+
+    // `a1`, `b1` and `c1` arrive one after another, all missing their dependencies.
+    for (hash, time) in [(a1, 0x100), (b1, 0x101), (c1, 0x102)] {
+        let _ = sync.schedule_add_vertex(peer0, pvv(hash), time.into());
+        let (pv, _) =
+            sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+        assert!(pv.is_none());
+    }
+
+    // `a1` was received first, so it was dropped to make room for `c1`.
+    let pending_dependencies: BTreeSet<_> = sync
+        .pending_dependencies()
+        .map(|(dep, count)| {
+            assert_eq!(1, count);
+            dep.clone()
+        })
+        .collect();
+    let expected: BTreeSet<_> = [Dependency::Unit(b0), Dependency::Unit(c0)]
+        .into_iter()
+        .collect();
+    assert_eq!(expected, pending_dependencies);
+}
+
+#[test]
+fn evict_only_the_flooding_peers_vertices() {
+    let params = test_params(0);
+    let mut state = State::new(WEIGHTS, params.clone(), vec![], vec![]);
+    let util_highway =
+        Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params.clone());
+
+    // Alice   a0 — a1
+    //
+    // Bob     b0 — b1
+    //
+    // Carol   c0 — c1
+    let a0 = add_unit!(state, ALICE, 0xA; N, N, N).unwrap();
+    let b0 = add_unit!(state, BOB, 0xB; N, N, N).unwrap();
+    let c0 = add_unit!(state, CAROL, 0xC; N, N, N).unwrap();
+    let a1 = add_unit!(state, ALICE, None; a0, N, N).unwrap();
+    let b1 = add_unit!(state, BOB, None; N, b0, N).unwrap();
+    let c1 = add_unit!(state, CAROL, None; N, N, c0).unwrap();
+
+    // Returns the WireUnit with the specified hash.
+    let unit = |hash: u64| Vertex::Unit(state.wire_unit(&hash, TEST_INSTANCE_ID).unwrap());
+    // Returns the PreValidatedVertex with the specified hash.
+    let pvv = |hash: u64| util_highway.pre_validate_vertex(unit(hash)).unwrap();
+
+    let peer0 = NodeId::from([0; 64]);
+    let peer1 = NodeId::from([1; 64]);
+
+    // Create a synchronizer that holds at most two pending vertices per peer.
+    let max_requests_for_vertex = 5;
+    let mut sync = Synchronizer::<TestContext>::new(WEIGHTS.len(), TEST_INSTANCE_ID)
+        .with_max_pending_vertices_per_peer(2);
+    let highway = Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params);
+
+    // `peer1` sends `a1`, then `peer0` sends `a1`, `b1` and `c1`, all missing their dependencies.
+    for (sender, hash, time) in [
+        (peer1, a1, 0x100),
+        (peer0, a1, 0x101),
+        (peer0, b1, 0x102),
+        (peer0, c1, 0x103),
+    ] {
+        let _ = sync.schedule_add_vertex(sender, pvv(hash), time.into());
+        let (pv, _) =
+            sync.pop_vertex_to_add(&highway, &Default::default(), max_requests_for_vertex);
+        assert!(pv.is_none());
+    }
+
+    // `peer0` exceeded its limit, so its oldest receipt, of `a1`, was dropped. But `a1` is kept,
+    // as `peer1` sent it as well.
+    let pending_dependencies: BTreeSet<_> = sync
+        .pending_dependencies()
+        .map(|(dep, _)| dep.clone())
+        .collect();
+    let expected: BTreeSet<_> = [
+        Dependency::Unit(a0),
+        Dependency::Unit(b0),
+        Dependency::Unit(c0),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(expected, pending_dependencies);
+}
+
 fn unwrap_single<T: Debug>(vec: Vec<T>) -> T {
     assert_eq!(
         vec.len(),
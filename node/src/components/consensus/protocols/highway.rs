@@ -3,13 +3,14 @@ mod participation;
 mod round_success_meter;
 #[cfg(test)]
 mod tests;
+mod unit_log;
 
 use std::{
     any::Any,
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
-    iter,
-    path::PathBuf,
+    iter, mem,
+    path::{Path, PathBuf},
 };
 
 use datasize::DataSize;
@@ -19,13 +20,14 @@ use num_traits::CheckedMul;
 use rand::RngCore;
 use tracing::{debug, error, info, trace, warn};
 
-use casper_types::{system::auction::BLOCK_REWARD, TimeDiff, Timestamp, U512};
+use casper_types::{system::auction::BLOCK_REWARD, EraId, TimeDiff, Timestamp, U512};
 
 use crate::{
     components::consensus::{
         config::Config,
         consensus_protocol::{
-            BlockContext, ConsensusProtocol, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome,
+            ProtocolOutcomes,
         },
         era_supervisor::SerializedMessage,
         highway_core::{
@@ -35,7 +37,7 @@ use crate::{
                 Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, ValidVertex,
                 Vertex, VertexError,
             },
-            state::{IndexObservation, IndexPanorama, Observation},
+            state::{self, IndexObservation, IndexPanorama, Observation},
             synchronizer::Synchronizer,
         },
         protocols,
@@ -47,7 +49,11 @@ use crate::{
     NodeRng,
 };
 
-use self::round_success_meter::RoundSuccessMeter;
+use self::{
+    round_success_meter::RoundSuccessMeter,
+    unit_log::{ReadUnitLog, SnapshotWriter, WriteUnitLog},
+};
+pub(crate) use unit_log::UnitLogError;
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
@@ -65,6 +71,8 @@ const TIMER_ID_LOG_PARTICIPATION: TimerId = TimerId(3);
 const TIMER_ID_SYNCHRONIZER_LOG: TimerId = TimerId(4);
 /// The timer to request the latest state from a random peer.
 const TIMER_ID_REQUEST_STATE: TimerId = TimerId(5);
+/// The timer for writing a snapshot of the protocol state to disk.
+const TIMER_ID_SNAPSHOT: TimerId = TimerId(6);
 
 /// The action of adding a vertex from the `vertices_to_be_added` queue.
 pub(crate) const ACTION_ID_VERTEX: ActionId = ActionId(0);
@@ -84,6 +92,10 @@ where
     pvv_cache: HashMap<Dependency<C>, PreValidatedVertex<C>>,
     evidence_only: bool,
     config: config::Config,
+    /// The log every added vertex is appended to, if unit persistence is enabled.
+    unit_log: Option<WriteUnitLog<C>>,
+    /// The writer the protocol state is periodically handed to, if snapshots are enabled.
+    snapshot_writer: Option<SnapshotWriter<C>>,
 }
 
 impl<C: Context + 'static> HighwayProtocol<C> {
@@ -91,6 +103,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
     #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     pub(crate) fn new_boxed(
         instance_id: C::InstanceId,
+        era_id: EraId,
         validator_stakes: BTreeMap<C::ValidatorId, U512>,
         faulty: &HashSet<C::ValidatorId>,
         inactive: &HashSet<C::ValidatorId>,
@@ -100,7 +113,67 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         era_start_time: Timestamp,
         seed: u64,
         now: Timestamp,
+        unit_log_file: Option<PathBuf>,
+        snapshot_file: Option<PathBuf>,
     ) -> (Box<dyn ConsensusProtocol<C>>, ProtocolOutcomes<C>) {
+        let (hw_proto, mut outcomes) = Self::new(
+            instance_id,
+            era_id,
+            validator_stakes,
+            faulty,
+            inactive,
+            chainspec,
+            config,
+            prev_cp,
+            era_start_time,
+            seed,
+            now,
+        );
+        let mut hw_proto = Box::new(hw_proto);
+        if let Some(path) = snapshot_file {
+            // Restore the state before opening the unit log: it already contains these vertices.
+            if path.exists() {
+                let max_vertex_size = chainspec.network_config.maximum_net_message_size as usize;
+                match hw_proto.restore_snapshot(&path, max_vertex_size, now) {
+                    Ok(restored_outcomes) => outcomes.extend(restored_outcomes),
+                    Err(err) => error!(?path, %err, "could not restore protocol state snapshot"),
+                }
+            }
+            if let Some(interval) = config.highway.snapshot_interval {
+                outcomes.push(ProtocolOutcome::ScheduleTimer(
+                    now.saturating_add(interval),
+                    TIMER_ID_SNAPSHOT,
+                ));
+            }
+            match SnapshotWriter::new(path.clone()) {
+                Ok(snapshot_writer) => hw_proto.snapshot_writer = Some(snapshot_writer),
+                Err(err) => error!(?path, %err, "could not start protocol state snapshot writer"),
+            }
+        }
+        if let Some(path) = unit_log_file {
+            match WriteUnitLog::new(&path) {
+                Ok(unit_log) => hw_proto.unit_log = Some(unit_log),
+                Err(err) => error!(?path, %err, "could not open unit log"),
+            }
+        }
+        (hw_proto, outcomes)
+    }
+
+    /// Creates a new `HighwayProtocol` instance.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        instance_id: C::InstanceId,
+        era_id: EraId,
+        validator_stakes: BTreeMap<C::ValidatorId, U512>,
+        faulty: &HashSet<C::ValidatorId>,
+        inactive: &HashSet<C::ValidatorId>,
+        chainspec: &Chainspec,
+        config: &Config,
+        prev_cp: Option<&dyn ConsensusProtocol<C>>,
+        era_start_time: Timestamp,
+        seed: u64,
+        now: Timestamp,
+    ) -> (Self, ProtocolOutcomes<C>) {
         let validators_count = validator_stakes.len();
         let validators = protocols::common::validators::<C>(faulty, inactive, validator_stakes);
         let highway_config = &chainspec.highway_config;
@@ -190,20 +263,146 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         let outcomes = Self::initialize_timers(now, era_start_time, &config.highway);
 
         let highway = Highway::new(instance_id, validators, params);
-        let hw_proto = Box::new(HighwayProtocol {
+        let hw_proto = HighwayProtocol {
             pending_values: HashMap::new(),
-            finality_detector: FinalityDetector::new(ftt),
+            finality_detector: FinalityDetector::new(ftt)
+                .with_max_missed_proposals(highway_config.max_missed_proposals_in(era_id)),
             highway,
             round_success_meter,
-            synchronizer: Synchronizer::new(validators_count, instance_id),
+            synchronizer: Synchronizer::new(validators_count, instance_id)
+                .with_max_requests_per_peer(config.highway.max_requests_per_peer)
+                .with_max_pending_vertices_per_peer(config.highway.max_pending_vertices_per_peer),
             pvv_cache: Default::default(),
             evidence_only: false,
             config: config.highway.clone(),
-        });
+            unit_log: None,
+            snapshot_writer: None,
+        };
 
         (hw_proto, outcomes)
     }
 
+    /// Adds the vertices from a log written with unit persistence enabled to the protocol state,
+    /// in order, and returns the blocks that get finalized as a result.
+    ///
+    /// Consensus values are not validated again: they were validated before the vertices were
+    /// logged. Vertices that are invalid or whose dependencies are missing are skipped.
+    pub(crate) fn replay_unit_log(
+        &mut self,
+        path: &Path,
+        max_vertex_size: usize,
+    ) -> Result<Vec<FinalizedBlock<C>>, UnitLogError> {
+        let mut read_log = ReadUnitLog::new(path, max_vertex_size)?;
+        let mut finalized_blocks = vec![];
+        while let Some(vertex) = read_log.read_next_vertex()? {
+            if self.highway.has_vertex(&vertex) {
+                continue;
+            }
+            let pvv = match self.highway.pre_validate_vertex(vertex) {
+                Ok(pvv) => pvv,
+                Err((vertex, err)) => {
+                    warn!(?vertex, ?err, "skipping invalid vertex in unit log");
+                    continue;
+                }
+            };
+            if let Some(dependency) = self.highway.missing_dependency(&pvv) {
+                warn!(
+                    ?pvv,
+                    ?dependency,
+                    "skipping vertex with missing dependency in unit log"
+                );
+                continue;
+            }
+            let vv = match self.highway.validate_vertex(pvv) {
+                Ok(vv) => vv,
+                Err((pvv, err)) => {
+                    warn!(?pvv, ?err, "skipping invalid vertex in unit log");
+                    continue;
+                }
+            };
+            let timestamp = vv.inner().timestamp().unwrap_or_else(Timestamp::zero);
+            self.highway.add_valid_vertex(vv, timestamp);
+            match self.finality_detector.run(&self.highway) {
+                Ok(blocks) => finalized_blocks.extend(blocks),
+                Err(FttExceeded(weight)) => {
+                    error!(faulty_weight = %weight.0, "too many faulty validators; stopping replay");
+                    break;
+                }
+            }
+        }
+        Ok(finalized_blocks)
+    }
+
+    /// Adds the vertices from a snapshot written by the `SnapshotWriter` to the protocol state.
+    ///
+    /// Like in `replay_unit_log`, consensus values are not validated again. Vertices whose
+    /// dependencies are missing are retried after all others have been added, as long as that
+    /// makes progress.
+    fn restore_snapshot(
+        &mut self,
+        path: &Path,
+        max_vertex_size: usize,
+        now: Timestamp,
+    ) -> Result<ProtocolOutcomes<C>, UnitLogError> {
+        let mut read_log = ReadUnitLog::new(path, max_vertex_size)?;
+        let mut vertices = vec![];
+        while let Some(vertex) = read_log.read_next_vertex()? {
+            vertices.push(vertex);
+        }
+        let mut outcomes = vec![];
+        loop {
+            let vertex_count = vertices.len();
+            for vertex in mem::take(&mut vertices) {
+                if self.highway.has_vertex(&vertex) {
+                    continue;
+                }
+                let pvv = match self.highway.pre_validate_vertex(vertex) {
+                    Ok(pvv) => pvv,
+                    Err((vertex, err)) => {
+                        warn!(?vertex, ?err, "skipping invalid vertex in snapshot");
+                        continue;
+                    }
+                };
+                if self.highway.missing_dependency(&pvv).is_some() {
+                    vertices.push(pvv.into_vertex());
+                    continue;
+                }
+                match self.highway.validate_vertex(pvv) {
+                    Ok(vv) => outcomes.extend(self.add_valid_vertex(vv, now)),
+                    Err((pvv, err)) => warn!(?pvv, ?err, "skipping invalid vertex in snapshot"),
+                }
+            }
+            if vertices.is_empty() || vertices.len() == vertex_count {
+                break;
+            }
+        }
+        if !vertices.is_empty() {
+            warn!(
+                count = vertices.len(),
+                "skipping vertices with missing dependencies in snapshot"
+            );
+        }
+        info!(
+            ?path,
+            unit_count = self.highway.state().unit_hashes().count(),
+            "restored protocol state snapshot"
+        );
+        outcomes.extend(self.detect_finality());
+        Ok(outcomes)
+    }
+
+    /// Hands all vertices in the protocol state to the snapshot writer, to replace the previous
+    /// snapshot.
+    fn write_snapshot(&self) {
+        let snapshot_writer = match &self.snapshot_writer {
+            Some(snapshot_writer) => snapshot_writer,
+            None => return,
+        };
+        if !snapshot_writer.write(self.highway.vertices()) {
+            debug!("skipping protocol state snapshot; still writing the previous one");
+        }
+    }
+
     fn initialize_timers(
         now: Timestamp,
         era_start_time: Timestamp,
@@ -346,6 +545,19 @@ impl<C: Context + 'static> HighwayProtocol<C> {
                 let ancestor_values = self.ancestors(fork_choice).cloned().collect();
                 let block_context = BlockContext::new(timestamp, ancestor_values);
                 let proposed_block = ProposedBlock::new(value.clone(), block_context);
+                let proposer = self
+                    .highway
+                    .validators()
+                    .id(swunit.wire_unit().creator)
+                    .cloned();
+                let round_start = self
+                    .highway
+                    .state()
+                    .params()
+                    .min_round_length()
+                    .millis()
+                    .checked_shl(swunit.wire_unit().round_exp.into())
+                    .map(|round_len| state::round_id(timestamp, TimeDiff::from_millis(round_len)));
                 if self
                     .pending_values
                     .entry(proposed_block.clone())
@@ -355,6 +567,8 @@ impl<C: Context + 'static> HighwayProtocol<C> {
                     outcomes.push(ProtocolOutcome::ValidateConsensusValue {
                         sender,
                         proposed_block,
+                        proposer,
+                        round_start,
                     });
                 }
                 return outcomes;
@@ -402,6 +616,12 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         if self.highway.has_vertex(vv.inner()) {
             return vec![];
         }
+        if let Some(unit_log) = self.unit_log.as_mut() {
+            if let Err(err) = unit_log.record_vertex(vv.inner()) {
+                error!(%err, "could not persist vertex; disabling unit log");
+                self.unit_log = None;
+            }
+        }
         let mut outcomes = ProtocolOutcomes::new();
         if let (Some(value), Some(unit)) = (vv.inner().value(), vv.inner().unit()) {
             // We are adding a proposed block to the protocol state, so we might use it as an
@@ -669,6 +889,12 @@ impl<C: Context + 'static> HighwayProtocol<C> {
     pub(crate) fn highway(&self) -> &Highway<C> {
         &self.highway
     }
+
+    /// Returns the dependencies the synchronizer is waiting for, with the number of vertices
+    /// waiting for each.
+    pub(crate) fn pending_dependencies(&self) -> impl Iterator<Item = (&Dependency<C>, u64)> {
+        self.synchronizer.pending_dependencies()
+    }
 }
 
 #[allow(clippy::arithmetic_side_effects)]
@@ -975,6 +1201,16 @@ where
                 _ => vec![],
             },
             TIMER_ID_REQUEST_STATE => self.handle_request_state_timer(timestamp),
+            TIMER_ID_SNAPSHOT => match self.config.snapshot_interval {
+                Some(interval) if !self.evidence_only && !self.finalized_switch_block() => {
+                    self.write_snapshot();
+                    vec![ProtocolOutcome::ScheduleTimer(
+                        timestamp.saturating_add(interval),
+                        timer_id,
+                    )]
+                }
+                _ => vec![],
+            },
             TIMER_ID_SYNCHRONIZER_LOG => {
                 self.synchronizer.log_len();
                 match self.config.log_synchronizer_interval {
@@ -1121,6 +1357,17 @@ where
         self.highway.validators_with_evidence().collect()
     }
 
+    fn units_seen(&self) -> BTreeMap<C::ValidatorId, u64> {
+        // The next sequence number is the number of units in the validator's swimlane. Faulty
+        // validators have no unique swimlane, and are reported as zero.
+        let state = self.highway.state();
+        self.highway
+            .validators()
+            .enumerate_ids()
+            .map(|(vidx, vid)| (vid.clone(), state.panorama().next_seq_num(state, vidx)))
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
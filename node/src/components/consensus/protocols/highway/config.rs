@@ -24,8 +24,27 @@ pub struct Config {
     pub log_synchronizer_interval: Option<TimeDiff>,
     /// Log the size of every incoming and outgoing serialized unit.
     pub log_unit_sizes: bool,
+    /// Append every vertex added to the protocol state to a log in the `unit_files` folder, so
+    /// that the era can be replayed offline with `casper-node replay-era`.
+    #[serde(default)]
+    pub persist_units: bool,
+    /// The number of most recent eras whose unit logs are kept; older logs are deleted. `0` keeps
+    /// all logs.
+    #[serde(default = "default_unit_log_retention_eras")]
+    pub unit_log_retention_eras: u64,
+    /// Write the active era's protocol state to the `unit_files` folder periodically, with this
+    /// interval, so that a restarting node can restore it instead of downloading it from peers.
+    #[serde(default = "default_snapshot_interval", with = "serde_option_time_diff")]
+    pub snapshot_interval: Option<TimeDiff>,
     /// The maximum number of peers we request the same vertex from in parallel.
     pub max_requests_for_vertex: usize,
+    /// The maximum number of dependency requests a single peer can have outstanding.
+    #[serde(default = "default_max_requests_per_peer")]
+    pub max_requests_per_peer: usize,
+    /// The maximum number of vertices per peer waiting for a dependency or for their timestamp.
+    /// If exceeded, the ones received from that peer least recently are dropped.
+    #[serde(default = "default_max_pending_vertices_per_peer")]
+    pub max_pending_vertices_per_peer: usize,
     /// The maximum number of dependencies we request per validator in a batch.
     /// Limits requests per validator in panorama - in order to get a total number of
     /// requests, multiply by # of validators.
@@ -41,9 +60,30 @@ impl Default for Config {
             log_participation_interval: Some("10sec".parse().unwrap()),
             log_synchronizer_interval: Some("5sec".parse().unwrap()),
             log_unit_sizes: false,
+            persist_units: false,
+            unit_log_retention_eras: default_unit_log_retention_eras(),
+            snapshot_interval: default_snapshot_interval(),
             max_requests_for_vertex: 5,
+            max_requests_per_peer: default_max_requests_per_peer(),
+            max_pending_vertices_per_peer: default_max_pending_vertices_per_peer(),
             max_request_batch_size: 20,
             round_success_meter: RSMConfig::default(),
         }
     }
 }
+
+fn default_unit_log_retention_eras() -> u64 {
+    10
+}
+
+fn default_snapshot_interval() -> Option<TimeDiff> {
+    Some("1min".parse().unwrap())
+}
+
+fn default_max_requests_per_peer() -> usize {
+    50
+}
+
+fn default_max_pending_vertices_per_peer() -> usize {
+    2_000
+}
@@ -1,6 +1,7 @@
 use std::{collections::BTreeSet, sync::Arc};
 
-use casper_types::{testing::TestRng, PublicKey, TimeDiff, Timestamp, U512};
+use casper_types::{testing::TestRng, EraId, PublicKey, TimeDiff, Timestamp, U512};
+use tempfile::tempdir;
 
 use crate::{
     components::consensus::{
@@ -15,7 +16,8 @@ use crate::{
         },
         max_rounds_per_era,
         protocols::highway::{
-            config::Config as HighwayConfig, HighwayMessage, HighwayProtocol, ACTION_ID_VERTEX,
+            config::Config as HighwayConfig, unit_log, HighwayMessage, HighwayProtocol,
+            ACTION_ID_VERTEX,
         },
         tests::utils::{
             new_test_chainspec, ALICE_NODE_ID, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY,
@@ -79,6 +81,7 @@ where
     let start_timestamp: Timestamp = 0.into();
     let (hw_proto, outcomes) = HighwayProtocol::<ClContext>::new_boxed(
         ClContext::hash(INSTANCE_ID_DATA),
+        EraId::new(0),
         weights.into_iter().collect(),
         &init_faulty.into_iter().collect(),
         &None.into_iter().collect(),
@@ -88,6 +91,8 @@ where
         start_timestamp,
         0,
         start_timestamp,
+        None,
+        None,
     );
     // We expect three messages:
     // * log participation timer,
@@ -223,6 +228,59 @@ fn detect_doppelganger() {
     panic!("failed to return DoppelgangerDetected effect");
 }
 
+#[test]
+fn restore_protocol_state_from_snapshot() {
+    let creator: ValidatorIndex = ValidatorIndex(0);
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let state: State<ClContext> = new_test_state(validators.iter().map(|(_pk, w)| *w), 0);
+    let panorama: Panorama<ClContext> = Panorama::from(vec![N]);
+    let seq_number = panorama.next_seq_num(&state, creator);
+    let now = Timestamp::zero();
+    let wunit: WireUnit<ClContext> = WireUnit {
+        panorama,
+        creator,
+        instance_id: ClContext::hash(INSTANCE_ID_DATA),
+        value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+        seq_number,
+        timestamp: now,
+        round_exp: 0,
+        endorsed: BTreeSet::new(),
+    };
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let vertex = Vertex::Unit(SignedWireUnit::new(wunit.into_hashed(), &alice_keypair));
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("snapshot.dat");
+    unit_log::write_snapshot(&path, &[vertex.clone()]).unwrap();
+
+    let chainspec = new_test_chainspec(validators.clone());
+    let (hw_proto, _outcomes) = HighwayProtocol::<ClContext>::new_boxed(
+        ClContext::hash(INSTANCE_ID_DATA),
+        EraId::new(0),
+        validators
+            .into_iter()
+            .map(|(pk, w)| (pk, U512::from(w)))
+            .collect(),
+        &Default::default(),
+        &Default::default(),
+        &chainspec,
+        &Config::default(),
+        None,
+        now,
+        0,
+        now,
+        None,
+        Some(path),
+    );
+    let highway = &hw_proto
+        .as_any()
+        .downcast_ref::<HighwayProtocol<ClContext>>()
+        .unwrap()
+        .highway;
+    assert!(highway.has_vertex(&vertex));
+    assert_eq!(highway.state().unit_hashes().count(), 1);
+}
+
 #[test]
 fn max_rounds_per_era_returns_the_correct_value_for_prod_chainspec_value() {
     let max_rounds_per_era = max_rounds_per_era(
@@ -0,0 +1,391 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+    mem,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender},
+    thread::{self, JoinHandle},
+};
+
+use datasize::DataSize;
+use thiserror::Error;
+use tracing::{debug, error};
+
+use crate::components::consensus::{highway_core::highway::Vertex, traits::Context};
+
+/// The maximum number of serialized vertices waiting to be written by the writer thread.
+const WRITER_QUEUE_CAPACITY: usize = 1024;
+
+/// A log of every vertex added to the Highway protocol state, in the order in which they were
+/// added, so that the protocol state can be reconstructed offline.
+///
+/// Vertices are serialized by the caller, but written to the file by a dedicated thread, so that
+/// disk I/O doesn't block consensus. The thread flushes whenever it has caught up with the caller.
+/// If it falls `WRITER_QUEUE_CAPACITY` vertices behind, recording blocks until it catches up, so
+/// that a slow disk can't make the queue grow without bound.
+#[derive(Debug)]
+pub(crate) struct WriteUnitLog<C: Context> {
+    /// The channel to the writer thread; `None` once dropped.
+    sender: Option<SyncSender<Vec<u8>>>,
+    /// The writer thread, joined on drop so that all recorded vertices are written.
+    writer_thread: Option<JoinHandle<()>>,
+    phantom_context: PhantomData<C>,
+}
+
+impl<C: Context> DataSize for WriteUnitLog<C> {
+    const IS_DYNAMIC: bool = false;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum UnitLogError {
+    #[error("could not open unit log: {0}")]
+    FileCouldntBeOpened(io::Error),
+    #[error("could not serialize vertex: {0}")]
+    CouldntSerialize(bincode::Error),
+    #[error("could not deserialize vertex: {0}")]
+    CouldntDeserialize(bincode::Error),
+    #[error("vertex of {size} bytes exceeds the maximum of {max} bytes")]
+    VertexTooLarge { size: u64, max: usize },
+    #[error("unit log writer thread stopped")]
+    WriterStopped,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl<C: Context> WriteUnitLog<C> {
+    pub(crate) fn new(path: &Path) -> Result<Self, UnitLogError> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(UnitLogError::FileCouldntBeOpened)?;
+        let (sender, receiver) = mpsc::sync_channel(WRITER_QUEUE_CAPACITY);
+        let writer_thread = thread::Builder::new()
+            .name("casper-unit-log".to_string())
+            .spawn(move || run_writer(BufWriter::new(file), receiver))?;
+        Ok(WriteUnitLog {
+            sender: Some(sender),
+            writer_thread: Some(writer_thread),
+            phantom_context: PhantomData,
+        })
+    }
+
+    /// Appends the vertex to the log, prefixed with its serialized size as a `u64`.
+    ///
+    /// Returns an error if the vertex could not be serialized or the writer thread has stopped
+    /// after failing to write to the file.
+    pub(crate) fn record_vertex(&mut self, vertex: &Vertex<C>) -> Result<(), UnitLogError> {
+        let mut entry = vec![];
+        write_vertex(&mut entry, vertex)?;
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(entry).ok())
+            .ok_or(UnitLogError::WriterStopped)
+    }
+}
+
+impl<C: Context> Drop for WriteUnitLog<C> {
+    fn drop(&mut self) {
+        // Closing the channel makes the writer thread exit once it has written everything.
+        drop(self.sender.take());
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                error!("unit log writer thread panicked");
+            }
+        }
+    }
+}
+
+/// Writes the entries received on `receiver` to `writer` until the channel is closed, flushing
+/// whenever no further entries are queued.
+fn run_writer(mut writer: BufWriter<File>, receiver: Receiver<Vec<u8>>) {
+    let write_all = |writer: &mut BufWriter<File>| -> io::Result<()> {
+        while let Ok(entry) = receiver.recv() {
+            writer.write_all(&entry)?;
+            while let Ok(entry) = receiver.try_recv() {
+                writer.write_all(&entry)?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    };
+    if let Err(err) = write_all(&mut writer) {
+        // Dropping the receiver makes subsequent `record_vertex` calls fail.
+        error!(%err, "could not write to unit log");
+    }
+}
+
+/// Writes snapshots of the protocol state to a file on a dedicated thread, so that serializing the
+/// vertices and syncing the file to disk don't block consensus.
+///
+/// Only one snapshot is written at a time: if the thread is still busy with the previous one when
+/// the next one is due, the new one is skipped.
+#[derive(Debug)]
+pub(crate) struct SnapshotWriter<C: Context> {
+    /// The channel to the writer thread; `None` once dropped.
+    sender: Option<SyncSender<Vec<Vertex<C>>>>,
+    /// The writer thread, joined on drop so that a snapshot is never left half-written.
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl<C: Context> DataSize for SnapshotWriter<C> {
+    const IS_DYNAMIC: bool = false;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<C: Context + 'static> SnapshotWriter<C> {
+    pub(crate) fn new(path: PathBuf) -> Result<Self, UnitLogError> {
+        // A zero-capacity channel only accepts a snapshot while the thread is waiting for one.
+        let (sender, receiver) = mpsc::sync_channel(0);
+        let writer_thread = thread::Builder::new()
+            .name("casper-snapshot".to_string())
+            .spawn(move || run_snapshot_writer(&path, receiver))?;
+        Ok(SnapshotWriter {
+            sender: Some(sender),
+            writer_thread: Some(writer_thread),
+        })
+    }
+
+    /// Hands the vertices to the writer thread, to replace the previous snapshot.
+    ///
+    /// Returns `false` if the snapshot was skipped because the previous one is still being written.
+    pub(crate) fn write(&self, vertices: Vec<Vertex<C>>) -> bool {
+        self.sender
+            .as_ref()
+            .map_or(false, |sender| sender.try_send(vertices).is_ok())
+    }
+}
+
+impl<C: Context> Drop for SnapshotWriter<C> {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                error!("snapshot writer thread panicked");
+            }
+        }
+    }
+}
+
+/// Writes each list of vertices received on `receiver` as a snapshot to `path`, until the channel
+/// is closed.
+fn run_snapshot_writer<C: Context>(path: &Path, receiver: Receiver<Vec<Vertex<C>>>) {
+    while let Ok(vertices) = receiver.recv() {
+        match write_snapshot(path, &vertices) {
+            Ok(()) => debug!(
+                ?path,
+                count = vertices.len(),
+                "wrote protocol state snapshot"
+            ),
+            Err(err) => error!(?path, %err, "could not write protocol state snapshot"),
+        }
+    }
+}
+
+/// Writes the vertices to a new file at `path`, in the same format as `WriteUnitLog`, so that they
+/// can be read with `ReadUnitLog`.
+///
+/// The vertices are written to a temporary file first, which then replaces the file at `path`, so
+/// that a crash never leaves an incomplete snapshot behind.
+pub(super) fn write_snapshot<C: Context>(
+    path: &Path,
+    vertices: &[Vertex<C>],
+) -> Result<(), UnitLogError> {
+    let tmp_path = path.with_extension("tmp");
+    let file = File::create(&tmp_path).map_err(UnitLogError::FileCouldntBeOpened)?;
+    let mut writer = BufWriter::new(file);
+    for vertex in vertices {
+        write_vertex(&mut writer, vertex)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Writes the vertex, prefixed with its serialized size as a `u64`.
+fn write_vertex<C: Context, W: Write>(
+    writer: &mut W,
+    vertex: &Vertex<C>,
+) -> Result<(), UnitLogError> {
+    let bytes = bincode::serialize(vertex).map_err(UnitLogError::CouldntSerialize)?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads the vertices from a log written by `WriteUnitLog`.
+#[derive(Debug)]
+pub(crate) struct ReadUnitLog<C: Context> {
+    reader: BufReader<File>,
+    /// The maximum serialized size of a vertex; larger entries are rejected before allocating.
+    max_vertex_size: usize,
+    phantom_context: PhantomData<C>,
+}
+
+impl<C: Context> ReadUnitLog<C> {
+    /// Opens the log at `path`. Since every vertex was received in a network message,
+    /// `max_vertex_size` should be the maximum network message size.
+    pub(crate) fn new(path: &Path, max_vertex_size: usize) -> Result<Self, UnitLogError> {
+        let file = File::open(path).map_err(UnitLogError::FileCouldntBeOpened)?;
+        Ok(ReadUnitLog {
+            reader: BufReader::new(file),
+            max_vertex_size,
+            phantom_context: PhantomData,
+        })
+    }
+
+    /// Reads the next vertex from the log, or returns `Ok(None)` if the end of the log is reached.
+    ///
+    /// An incomplete entry at the end, e.g. if the node was stopped while writing it, is ignored.
+    pub(crate) fn read_next_vertex(&mut self) -> Result<Option<Vertex<C>>, UnitLogError> {
+        let mut size_buf = [0u8; mem::size_of::<u64>()];
+        if !self.read_exact_or_eof(&mut size_buf)? {
+            return Ok(None);
+        }
+        let size = u64::from_le_bytes(size_buf);
+        if size > self.max_vertex_size as u64 {
+            return Err(UnitLogError::VertexTooLarge {
+                size,
+                max: self.max_vertex_size,
+            });
+        }
+        let mut vertex_buf = vec![0; size as usize];
+        if !self.read_exact_or_eof(&mut vertex_buf)? {
+            return Ok(None);
+        }
+        bincode::deserialize(&vertex_buf)
+            .map(Some)
+            .map_err(UnitLogError::CouldntDeserialize)
+    }
+
+    /// Fills `buf`, or returns `Ok(false)` if the end of the file was reached first.
+    fn read_exact_or_eof(&mut self, buf: &mut [u8]) -> Result<bool, UnitLogError> {
+        match self.reader.read_exact(buf) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::OpenOptions, iter};
+
+    use casper_types::{SecretKey, Timestamp};
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::components::consensus::{
+        cl_context::{ClContext, Keypair},
+        highway_core::highway::Ping,
+        utils::ValidatorIndex,
+    };
+
+    const MAX_VERTEX_SIZE: usize = 1024;
+
+    fn pings(count: u32) -> Vec<Vertex<ClContext>> {
+        let keypair = Keypair::from(std::sync::Arc::new(
+            SecretKey::ed25519_from_bytes([3; SecretKey::ED25519_LENGTH]).unwrap(),
+        ));
+        let instance_id = ClContext::hash(&[123u8; 1]);
+        (0..count)
+            .map(|i| {
+                Vertex::Ping(Ping::new(
+                    ValidatorIndex(i),
+                    Timestamp::from(u64::from(i)),
+                    instance_id,
+                    &keypair,
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_read_back_recorded_vertices() {
+        let vertices = pings(3);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("units.log");
+        let read_vertices = || {
+            let mut read_log = ReadUnitLog::<ClContext>::new(&path, MAX_VERTEX_SIZE).unwrap();
+            iter::from_fn(move || read_log.read_next_vertex().unwrap()).collect::<Vec<_>>()
+        };
+
+        let mut write_log = WriteUnitLog::<ClContext>::new(&path).unwrap();
+        for vertex in &vertices {
+            write_log.record_vertex(vertex).unwrap();
+        }
+        // Dropping the log waits for the writer thread to write all vertices.
+        drop(write_log);
+        assert_eq!(vertices, read_vertices());
+
+        // A truncated last entry is skipped.
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        let len = file.metadata().unwrap().len();
+        file.set_len(len - 1).unwrap();
+        assert_eq!(vertices[..2], read_vertices()[..]);
+    }
+
+    #[test]
+    fn should_reject_oversized_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("units.log");
+        fs::write(&path, u64::MAX.to_le_bytes()).unwrap();
+        let mut read_log = ReadUnitLog::<ClContext>::new(&path, MAX_VERTEX_SIZE).unwrap();
+        assert!(matches!(
+            read_log.read_next_vertex(),
+            Err(UnitLogError::VertexTooLarge { size: u64::MAX, .. })
+        ));
+    }
+
+    #[test]
+    fn should_replace_snapshot() {
+        let vertices = pings(3);
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("snapshot.dat");
+        let read_vertices = || {
+            let mut read_log = ReadUnitLog::<ClContext>::new(&path, MAX_VERTEX_SIZE).unwrap();
+            iter::from_fn(move || read_log.read_next_vertex().unwrap()).collect::<Vec<_>>()
+        };
+
+        write_snapshot(&path, &vertices[..2]).unwrap();
+        assert_eq!(vertices[..2], read_vertices()[..]);
+        write_snapshot(&path, &vertices[1..]).unwrap();
+        assert_eq!(vertices[1..], read_vertices()[..]);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn should_write_snapshot_on_writer_thread() {
+        let vertices = pings(3);
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("snapshot.dat");
+
+        let snapshot_writer = SnapshotWriter::<ClContext>::new(path.clone()).unwrap();
+        // The thread might not be waiting for a snapshot yet, so retry until it accepts one.
+        while !snapshot_writer.write(vertices.clone()) {
+            thread::yield_now();
+        }
+        // Dropping the writer waits for the thread to finish writing.
+        drop(snapshot_writer);
+
+        let mut read_log = ReadUnitLog::<ClContext>::new(&path, MAX_VERTEX_SIZE).unwrap();
+        let read_vertices: Vec<_> =
+            iter::from_fn(move || read_log.read_next_vertex().unwrap()).collect();
+        assert_eq!(vertices, read_vertices);
+    }
+}
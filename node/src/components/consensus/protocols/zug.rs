@@ -1755,15 +1755,19 @@ impl<C: Context + 'static> Zug<C> {
         {
             self.log_proposal(&proposal, round_id, "requesting proposal validation");
             let proposed_block = ProposedBlock::new(block, block_context);
+            let round_start = self.round_start(round_id, &proposal);
             if self
                 .proposals_waiting_for_validation
                 .entry(proposed_block.clone())
                 .or_default()
                 .insert((round_id, proposal, sender))
             {
+                let proposer = self.validators.id(self.leader(round_id)).cloned();
                 return vec![ProtocolOutcome::ValidateConsensusValue {
                     sender,
                     proposed_block,
+                    proposer,
+                    round_start,
                 }];
             }
         } else {
@@ -1955,6 +1959,26 @@ impl<C: Context + 'static> Zug<C> {
         Some((None, self.params.start_timestamp()))
     }
 
+    /// Returns the earliest time at which a proposal could be made in the given round, if known.
+    ///
+    /// That is the time at which its parent became old enough, if the parent is from the previous
+    /// round, or the start of the era in the first round. If rounds were skipped since the parent,
+    /// it is unknown when the last of them was skipped.
+    fn round_start(&self, round_id: RoundId, proposal: &HashedProposal<C>) -> Option<Timestamp> {
+        match proposal.maybe_parent_round_id() {
+            None if round_id == 0 => Some(self.params.start_timestamp()),
+            Some(parent_round_id) if parent_round_id.saturating_add(1) == round_id => {
+                let (_, parent) = self.accepted_proposal(parent_round_id)?;
+                Some(
+                    parent
+                        .timestamp()
+                        .saturating_add(self.params.min_block_time()),
+                )
+            }
+            _ => None,
+        }
+    }
+
     /// Returns whether a quorum has voted for `false`.
     fn is_skippable_round(&self, round_id: RoundId) -> bool {
         self.rounds.get(&round_id).and_then(Round::quorum_votes) == Some(false)
@@ -2415,6 +2439,20 @@ where
             .collect()
     }
 
+    fn units_seen(&self) -> BTreeMap<C::ValidatorId, u64> {
+        self.validators
+            .enumerate_ids()
+            .map(|(idx, vid)| {
+                let rounds = self
+                    .rounds
+                    .values()
+                    .filter(|round| round.has_echoed(idx) || round.has_voted(idx))
+                    .count();
+                (vid.clone(), rounds as u64)
+            })
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
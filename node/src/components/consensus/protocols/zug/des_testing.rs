@@ -143,6 +143,7 @@ impl From<ProtocolOutcome<TestContext>> for ZugMessage {
             ProtocolOutcome::ValidateConsensusValue {
                 sender,
                 proposed_block,
+                ..
             } => ZugMessage::ValidateConsensusValue(sender, proposed_block),
             ProtocolOutcome::NewEvidence(vid) => ZugMessage::NewEvidence(vid),
             ProtocolOutcome::SendEvidence(target, vid) => ZugMessage::SendEvidence(target, vid),
@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::Path,
+};
+
+use datasize::DataSize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use casper_types::{PublicKey, Timestamp};
+
+/// A validator's participation in consensus during a single era, as seen by this node.
+#[derive(
+    Clone, Copy, DataSize, Debug, Default, Serialize, Deserialize, JsonSchema, Eq, PartialEq,
+)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorPerformance {
+    /// The number of the validator's consensus messages seen so far: units in Highway, or rounds
+    /// in which it echoed or voted in Zug.
+    pub(crate) units_seen: u64,
+    /// The number of the validator's block proposals received for validation.
+    ///
+    /// Empty proposals don't need validation and are not counted.
+    pub(crate) proposals: u64,
+    /// The number of those proposals which were made more than the minimum block time after the
+    /// start of their round.
+    pub(crate) late_proposals: u64,
+    /// The number of finalized blocks proposed by the validator.
+    pub(crate) blocks_finalized: u64,
+    /// Whether the validator was seen to equivocate, was accused of equivocating, or was banned
+    /// in this era.
+    pub(crate) faulty: bool,
+}
+
+impl ValidatorPerformance {
+    /// Records a proposal received for validation.
+    pub(crate) fn record_proposal(&mut self, late: bool) {
+        self.proposals = self.proposals.saturating_add(1);
+        if late {
+            self.late_proposals = self.late_proposals.saturating_add(1);
+        }
+    }
+
+    /// Records a finalized block proposed by the validator.
+    pub(crate) fn record_finalized_block(&mut self) {
+        self.blocks_finalized = self.blocks_finalized.saturating_add(1);
+    }
+}
+
+/// The validators' participation in an era as recorded by the era supervisor, i.e. except for the
+/// data tracked by the consensus protocol instance itself.
+///
+/// It is written to a file whenever it changes, so that it survives restarts.
+#[derive(Clone, DataSize, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub(crate) struct EraPerformance {
+    /// The recorded counters of each validator.
+    validators: BTreeMap<PublicKey, ValidatorPerformance>,
+    /// The proposer and timestamp of each recorded proposal, so that a proposal received from
+    /// several peers, or again after a restart, is only counted once.
+    proposals: BTreeSet<(PublicKey, Timestamp)>,
+    /// The relative height of the next finalized block to be recorded.
+    next_finalized_height: u64,
+}
+
+impl EraPerformance {
+    /// Reads the record from the given file, or returns an empty one if there is none.
+    pub(crate) fn read_from(path: &Path) -> Self {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    warn!(?err, ?path, "could not read validator performance");
+                }
+                return EraPerformance::default();
+            }
+        };
+        bincode::deserialize(&bytes).unwrap_or_else(|err| {
+            warn!(?err, ?path, "could not deserialize validator performance");
+            EraPerformance::default()
+        })
+    }
+
+    /// Writes the record to the given file.
+    pub(crate) fn write_to(&self, path: &Path) {
+        let bytes = match bincode::serialize(self) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!(?err, "could not serialize validator performance");
+                return;
+            }
+        };
+        if let Err(err) = fs::write(path, bytes) {
+            warn!(?err, ?path, "could not write validator performance");
+        }
+    }
+
+    /// Records a proposal by `proposer` with the given timestamp, received for validation.
+    ///
+    /// Returns `false` if it has already been recorded.
+    pub(crate) fn record_proposal(
+        &mut self,
+        proposer: &PublicKey,
+        timestamp: Timestamp,
+        late: bool,
+    ) -> bool {
+        if !self.proposals.insert((proposer.clone(), timestamp)) {
+            return false;
+        }
+        self.validators
+            .entry(proposer.clone())
+            .or_default()
+            .record_proposal(late);
+        true
+    }
+
+    /// Records a finalized block proposed by `proposer`, at the given height relative to the era's
+    /// first block.
+    ///
+    /// Returns `false` if it has already been recorded.
+    pub(crate) fn record_finalized_block(
+        &mut self,
+        proposer: &PublicKey,
+        relative_height: u64,
+    ) -> bool {
+        if relative_height < self.next_finalized_height {
+            return false;
+        }
+        self.next_finalized_height = relative_height.saturating_add(1);
+        self.validators
+            .entry(proposer.clone())
+            .or_default()
+            .record_finalized_block();
+        true
+    }
+
+    /// Returns the recorded counters of the given validator.
+    pub(crate) fn get(&self, pub_key: &PublicKey) -> ValidatorPerformance {
+        self.validators.get(pub_key).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{testing::TestRng, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn should_count_late_proposals_and_finalized_blocks() {
+        let mut performance = ValidatorPerformance::default();
+        performance.record_proposal(false);
+        performance.record_proposal(true);
+        performance.record_finalized_block();
+
+        assert_eq!(2, performance.proposals);
+        assert_eq!(1, performance.late_proposals);
+        assert_eq!(1, performance.blocks_finalized);
+        assert_eq!(0, performance.units_seen);
+        assert!(!performance.faulty);
+    }
+
+    #[test]
+    fn should_count_each_proposal_and_finalized_block_once_across_restarts() {
+        let mut rng = TestRng::new();
+        let proposer = PublicKey::from(&SecretKey::random(&mut rng));
+        let timestamp = Timestamp::from(1000);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("performance.dat");
+
+        let mut era_performance = EraPerformance::read_from(&path);
+        assert_eq!(EraPerformance::default(), era_performance);
+        assert!(era_performance.record_proposal(&proposer, timestamp, true));
+        assert!(!era_performance.record_proposal(&proposer, timestamp, true));
+        assert!(era_performance.record_finalized_block(&proposer, 0));
+        era_performance.write_to(&path);
+
+        // After a restart, the same proposal and finalized block are reported again.
+        let mut era_performance = EraPerformance::read_from(&path);
+        assert!(!era_performance.record_proposal(&proposer, timestamp, true));
+        assert!(!era_performance.record_finalized_block(&proposer, 0));
+        assert!(era_performance.record_finalized_block(&proposer, 1));
+
+        let performance = era_performance.get(&proposer);
+        assert_eq!(1, performance.proposals);
+        assert_eq!(1, performance.late_proposals);
+        assert_eq!(2, performance.blocks_finalized);
+    }
+}
@@ -20,12 +20,13 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
+use futures::{future::BoxFuture, FutureExt};
 use lmdb::DatabaseFlags;
-use once_cell::sync::Lazy;
 use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{debug, error, info, trace};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -35,14 +36,21 @@ use casper_execution_engine::{
         ChainspecRegistry, DeployItem, EngineConfigBuilder, EngineState, GenesisSuccess,
         SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
     },
-    shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
+    shared::{
+        additive_map::AdditiveMap, newtypes::CorrelationId, system_config::SystemConfig,
+        transform::Transform, wasm_config::WasmConfig,
+    },
     storage::{
         global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
         trie_store::lmdb::LmdbTrieStore,
     },
 };
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, EraId, ProtocolVersion, PublicKey, Timestamp};
+use casper_types::{
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    EraId, ExecutionEffect, Key, ProtocolVersion, PublicKey, StoredValue, Timestamp,
+};
 
 use crate::{
     components::{fetcher::FetchResponse, Component, ComponentState},
@@ -57,6 +65,8 @@ use crate::{
     },
     fatal,
     protocol::Message,
+    reactor::Finalize,
+    runtime,
     types::{
         ActivationPoint, BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, ChunkingError,
         Deploy, FinalizedBlock, MetaBlock, MetaBlockState, TrieOrChunk, TrieOrChunkId,
@@ -88,20 +98,11 @@ pub(crate) enum ContractRuntimeError {
     ChunkingError(#[source] ChunkingError),
 }
 
-/// Maximum number of resource intensive tasks that can be run in parallel.
-///
-/// TODO: Fine tune this constant to the machine executing the node.
-const MAX_PARALLEL_INTENSIVE_TASKS: usize = 4;
-
 pub(crate) const APPROVALS_CHECKSUM_NAME: &str = "approvals_checksum";
 pub(crate) const EXECUTION_RESULTS_CHECKSUM_NAME: &str = "execution_results_checksum";
 
-/// Semaphore enforcing maximum number of parallel resource intensive tasks.
-static INTENSIVE_TASKS_SEMAPHORE: Lazy<tokio::sync::Semaphore> =
-    Lazy::new(|| tokio::sync::Semaphore::new(MAX_PARALLEL_INTENSIVE_TASKS));
-
 /// Asynchronously runs a resource intensive task.
-/// At most `MAX_PARALLEL_INTENSIVE_TASKS` are being run in parallel at any time.
+/// At most `runtime.execution_threads` are being run in parallel at any time.
 ///
 /// The task is a closure that takes no arguments and returns a value.
 /// This function returns a future for that value.
@@ -110,11 +111,7 @@ where
     T: 'static + Send + FnOnce() -> V,
     V: 'static + Send,
 {
-    // This will never panic since the semaphore is never closed.
-    let _permit = INTENSIVE_TASKS_SEMAPHORE.acquire().await.unwrap();
-    tokio::task::spawn_blocking(task)
-        .await
-        .expect("task panicked")
+    runtime::run_on_execution_pool(task).await
 }
 
 #[derive(DataSize, Debug, Clone, Serialize)]
@@ -173,6 +170,20 @@ impl ExecutionPreState {
 
 type ExecQueue = Arc<Mutex<BTreeMap<u64, (FinalizedBlock, Vec<Deploy>, MetaBlockState)>>>;
 
+/// Updates the execution queue gauges from the current contents of the queue.
+fn update_exec_queue_metrics(
+    metrics: &Metrics,
+    queue: &BTreeMap<u64, (FinalizedBlock, Vec<Deploy>, MetaBlockState)>,
+) {
+    metrics
+        .exec_queue_size
+        .set(queue.len().try_into().unwrap_or(i64::MIN));
+    let oldest_height = queue.keys().next().copied().unwrap_or_default();
+    metrics
+        .exec_queue_oldest_height
+        .set(oldest_height.try_into().unwrap_or(i64::MAX));
+}
+
 #[derive(Debug, From, Serialize)]
 pub(crate) enum Event {
     #[from]
@@ -208,6 +219,8 @@ pub(crate) struct ContractRuntime {
 
     /// Finalized blocks waiting for their pre-state hash to start executing.
     exec_queue: ExecQueue,
+    /// The maximum number of blocks held in `exec_queue`.
+    max_exec_queue_size: usize,
     /// Cached instance of a [`SystemContractRegistry`].
     system_contract_registry: Option<SystemContractRegistry>,
     activation_point: ActivationPoint,
@@ -220,6 +233,21 @@ impl Debug for ContractRuntime {
     }
 }
 
+impl Finalize for ContractRuntime {
+    /// Flushes global state to disk, as its LMDB environment is opened with `MAP_ASYNC`.
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        let engine_state = self.engine_state;
+        async move {
+            match tokio::task::spawn_blocking(move || engine_state.sync_environment()).await {
+                Ok(Ok(())) => debug!("synced global state to disk"),
+                Ok(Err(error)) => error!(%error, "could not sync global state to disk"),
+                Err(error) => error!(%error, "could not join global state sync task"),
+            }
+        }
+        .boxed()
+    }
+}
+
 impl<REv> Component<REv> for ContractRuntime
 where
     REv: From<ContractRuntimeRequest>
@@ -266,6 +294,28 @@ impl ContractRuntime {
             .len()
     }
 
+    /// Whether the execution queue holds its maximum number of blocks, in which case no further
+    /// blocks should be synced until execution catches up.
+    pub(crate) fn is_exec_queue_full(&self) -> bool {
+        self.queue_depth() >= self.max_exec_queue_size
+    }
+
+    /// Returns the height of the block needed to resume execution and the hash of its parent, if
+    /// later blocks are waiting in the execution queue but that block is not.
+    pub(crate) fn missing_block_for_execution(&self) -> Option<(u64, BlockHash)> {
+        let pre_state = self.execution_pre_state.lock().expect(
+            "components::contract_runtime: couldn't get execution pre-state; mutex poisoned",
+        );
+        let queue = self
+            .exec_queue
+            .lock()
+            .expect("components::contract_runtime: couldn't get execution queue; mutex poisoned");
+        if queue.is_empty() || queue.contains_key(&pre_state.next_block_height) {
+            return None;
+        }
+        Some((pre_state.next_block_height, pre_state.parent_hash))
+    }
+
     /// Handles an incoming request to get a trie.
     fn handle_trie_request<REv>(
         &self,
@@ -356,6 +406,22 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::GetAccounts {
+                state_root_hash,
+                account_hashes,
+                responder,
+            } => {
+                trace!(%state_root_hash, ?account_hashes, "get accounts");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let start = Instant::now();
+                    let result = Self::get_accounts(&engine_state, state_root_hash, account_hashes);
+                    metrics.run_query.observe(start.elapsed().as_secs_f64());
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::GetBalance {
                 balance_request,
                 responder,
@@ -523,15 +589,25 @@ impl ContractRuntime {
                             finalized_block_height,
                             deploys.len()
                         );
-                        exec_queue
+                        let mut queue = exec_queue
                             .lock()
-                            .expect("components::contract_runtime: couldn't enqueue block for execution; mutex poisoned")
-                            .insert(finalized_block_height, (finalized_block, deploys, meta_block_state));
+                            .expect("components::contract_runtime: couldn't enqueue block for execution; mutex poisoned");
+                        queue.insert(
+                            finalized_block_height,
+                            (finalized_block, deploys, meta_block_state),
+                        );
+                        // finalized blocks are never dropped; the control logic stops syncing
+                        // further blocks while the queue is full instead.
+                        if queue.len() > self.max_exec_queue_size {
+                            warn!(
+                                queue_depth = queue.len(),
+                                max_exec_queue_size = self.max_exec_queue_size,
+                                "ContractRuntime: execution queue exceeds its maximum size"
+                            );
+                        }
+                        update_exec_queue_metrics(&self.metrics, &queue);
                     }
                 }
-                self.metrics
-                    .exec_queue_size
-                    .set(self.queue_depth().try_into().unwrap_or(i64::MIN));
                 effects
             }
             ContractRuntimeRequest::GetBids {
@@ -585,6 +661,21 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::SyncGlobalStateToDisk { responder } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || engine_state.sync_environment()).await;
+                    let synced = match result {
+                        Ok(()) => true,
+                        Err(error) => {
+                            error!(%error, "failed to sync global state to disk");
+                            false
+                        }
+                    };
+                    responder.respond(synced).await
+                }
+                .ignore()
+            }
         }
     }
 }
@@ -611,6 +702,7 @@ impl ContractRuntime {
         allow_unrestricted_transfers: bool,
         refund_handling: RefundHandling,
         fee_handling: FeeHandling,
+        start_protocol_version_with_get_era_id: Option<ProtocolVersion>,
     ) -> Result<Self, ConfigError> {
         // TODO: This is bogus, get rid of this
         let execution_pre_state = Arc::new(Mutex::new(ExecutionPreState {
@@ -627,11 +719,16 @@ impl ContractRuntime {
             contract_runtime_config.manual_sync_enabled_or_default(),
         )?);
 
-        let trie_store = Arc::new(LmdbTrieStore::new(
-            &environment,
-            None,
-            DatabaseFlags::empty(),
-        )?);
+        let trie_store = match contract_runtime_config.trie_cache_size_or_default() {
+            0 => LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())?,
+            trie_cache_size => LmdbTrieStore::new_with_cache(
+                &environment,
+                None,
+                DatabaseFlags::empty(),
+                trie_cache_size,
+            )?,
+        };
+        let trie_store = Arc::new(trie_store);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store)?;
         let engine_config = EngineConfigBuilder::new()
@@ -649,6 +746,7 @@ impl ContractRuntime {
             .with_allow_unrestricted_transfers(allow_unrestricted_transfers)
             .with_refund_handling(refund_handling)
             .with_fee_handling(fee_handling)
+            .with_start_protocol_version_with_get_era_id(start_protocol_version_with_get_era_id)
             .build();
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
@@ -662,6 +760,7 @@ impl ContractRuntime {
             metrics,
             protocol_version,
             exec_queue: Arc::new(Mutex::new(BTreeMap::new())),
+            max_exec_queue_size: contract_runtime_config.max_exec_queue_size_or_default(),
             system_contract_registry: None,
             activation_point,
             prune_batch_size,
@@ -669,6 +768,9 @@ impl ContractRuntime {
     }
 
     /// Commits a genesis request.
+    ///
+    /// If the chainspec contains a global state update, e.g. one exported from another network's
+    /// global state, its entries are written on top of the genesis state.
     pub(crate) fn commit_genesis(
         &self,
         chainspec: &Chainspec,
@@ -692,13 +794,32 @@ impl ContractRuntime {
                 })?,
         );
 
-        let result = self.engine_state.commit_genesis(
+        let mut result = self.engine_state.commit_genesis(
             correlation_id,
             genesis_config_hash,
             protocol_version,
             &ee_config,
             chainspec_registry,
         );
+        if let Ok(success) = result.as_mut() {
+            let imported_state = chainspec.protocol_config.get_update_mapping()?;
+            if !imported_state.is_empty() {
+                info!(
+                    entry_count = imported_state.len(),
+                    genesis_state_hash = %success.post_state_hash,
+                    "importing global state at genesis"
+                );
+                let mut effects = AdditiveMap::new();
+                for (key, stored_value) in imported_state {
+                    effects.insert(key, Transform::Write(stored_value));
+                }
+                success.post_state_hash = self.engine_state.apply_effect(
+                    correlation_id,
+                    success.post_state_hash,
+                    effects,
+                )?;
+            }
+        }
         self.engine_state.flush_environment()?;
         result
     }
@@ -735,9 +856,7 @@ impl ContractRuntime {
                     "components::contract_runtime: couldn't initialize contract runtime block execution queue; mutex poisoned"
                 );
             *exec_queue = exec_queue.split_off(&execution_pre_state.next_block_height);
-            self.metrics
-                .exec_queue_size
-                .set(exec_queue.len().try_into().unwrap_or(i64::MIN));
+            update_exec_queue_metrics(&self.metrics, &exec_queue);
         }
         debug!(next_block_height, "ContractRuntime: set initial state");
     }
@@ -767,12 +886,10 @@ impl ContractRuntime {
     {
         debug!("ContractRuntime: execute_finalized_block_or_requeue");
         let contract_runtime_metrics = metrics.clone();
-        let BlockAndExecutionResults {
-            block,
-            approvals_hashes,
-            execution_results,
-            maybe_step_effect_and_upcoming_era_validators,
-        } = match run_intensive_task(move || {
+        let era_id = finalized_block.era_id();
+        let block_height = finalized_block.height();
+        let (processed_sender, mut processed_receiver) = mpsc::unbounded_channel();
+        let execution = run_intensive_task(move || {
             debug!("ContractRuntime: execute_finalized_block");
             execute_finalized_block(
                 engine_state.as_ref(),
@@ -784,13 +901,44 @@ impl ContractRuntime {
                 activation_point.era_id(),
                 key_block_height_for_activation_point,
                 prune_batch_size,
+                |deploy_hash, deploy_header, execution_result| {
+                    // The receiver is only dropped once execution has finished.
+                    let _ = processed_sender.send((
+                        deploy_hash,
+                        deploy_header.clone(),
+                        execution_result.clone(),
+                    ));
+                },
             )
-        })
-        .await
-        {
+        });
+        // Announce each processed deploy while the rest of the block is still being executed.
+        let announce_processed = async {
+            while let Some((deploy_hash, deploy_header, execution_result)) =
+                processed_receiver.recv().await
+            {
+                effect_builder
+                    .announce_deploy_processed(
+                        era_id,
+                        block_height,
+                        deploy_hash,
+                        Box::new(deploy_header),
+                        Box::new(execution_result),
+                    )
+                    .await;
+            }
+        };
+        let BlockAndExecutionResults {
+            block,
+            approvals_hashes,
+            execution_results,
+            maybe_step_effect_and_upcoming_era_validators,
+        } = match futures::join!(execution, announce_processed).0 {
             Ok(block_and_execution_results) => block_and_execution_results,
             Err(error) => {
                 error!(%error, "failed to execute block");
+                effect_builder
+                    .announce_block_execution_failed(era_id, block_height)
+                    .await;
                 return fatal!(effect_builder, "{}", error).await;
             }
         };
@@ -818,12 +966,14 @@ impl ContractRuntime {
         }
 
         let current_era_id = block.header().era_id();
+        let mut maybe_step_effect = None;
 
         if let Some(StepEffectAndUpcomingEraValidators {
             step_execution_journal,
             mut upcoming_era_validators,
         }) = maybe_step_effect_and_upcoming_era_validators
         {
+            maybe_step_effect = Some(ExecutionEffect::from(&step_execution_journal));
             effect_builder
                 .announce_commit_step_success(current_era_id, step_execution_journal)
                 .await;
@@ -877,6 +1027,11 @@ impl ContractRuntime {
                 .put_execution_results_to_storage(*block.hash(), execution_results_map)
                 .await;
         }
+        if let Some(step_effect) = maybe_step_effect {
+            effect_builder
+                .put_step_effect_to_storage(*block.hash(), step_effect)
+                .await;
+        }
         if meta_block_state
             .register_as_executed()
             .was_already_registered()
@@ -898,10 +1053,11 @@ impl ContractRuntime {
             let queue = &mut *exec_queue
                 .lock()
                 .expect("components::contract_runtime: couldn't get next block for execution; mutex poisoned");
-            queue.remove(&new_execution_pre_state.next_block_height)
+            let next_block = queue.remove(&new_execution_pre_state.next_block_height);
+            update_exec_queue_metrics(&metrics, queue);
+            next_block
         };
         if let Some((finalized_block, deploys, meta_block_state)) = next_block {
-            metrics.exec_queue_size.dec();
             debug!("ContractRuntime: next block enqueue_block_for_execution");
             effect_builder
                 .enqueue_block_for_execution(finalized_block, deploys, meta_block_state)
@@ -951,6 +1107,27 @@ impl ContractRuntime {
         result.map(|option| option.map(|trie_raw| trie_raw.into_inner()))
     }
 
+    /// Reads the given accounts from a single checkout of global state, omitting any not found.
+    fn get_accounts(
+        engine_state: &EngineState<LmdbGlobalState>,
+        state_root_hash: Digest,
+        account_hashes: BTreeSet<AccountHash>,
+    ) -> Result<BTreeMap<AccountHash, Account>, engine_state::Error> {
+        let correlation_id = CorrelationId::new();
+        let mut tracking_copy = engine_state
+            .tracking_copy(state_root_hash)?
+            .ok_or(engine_state::Error::RootNotFound(state_root_hash))?;
+        let mut accounts = BTreeMap::new();
+        for account_hash in account_hashes {
+            if let Some(StoredValue::Account(account)) =
+                tracking_copy.read(correlation_id, &Key::Account(account_hash))?
+            {
+                accounts.insert(account_hash, account);
+            }
+        }
+        Ok(accounts)
+    }
+
     /// Returns the engine state, for testing only.
     #[cfg(test)]
     pub(crate) fn engine_state(&self) -> &Arc<EngineState<LmdbGlobalState>> {
@@ -1071,6 +1248,7 @@ mod trie_chunking_tests {
             true,
             DEFAULT_REFUND_HANDLING,
             DEFAULT_FEE_HANDLING,
+            None,
         )
         .unwrap();
         let empty_state_root = contract_runtime
@@ -7,6 +7,8 @@ const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_TRIE_CACHE_SIZE: usize = 0;
+const DEFAULT_MAX_EXEC_QUEUE_SIZE: usize = 1_000;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -31,6 +33,16 @@ pub struct Config {
     ///
     /// Defaults to `true`.
     pub enable_manual_sync: Option<bool>,
+    /// The maximum total size in bytes of recently read tries to keep cached in memory.  A value
+    /// of 0 disables the cache.
+    ///
+    /// Defaults to 0, i.e. the cache is disabled.
+    pub trie_cache_size: Option<usize>,
+    /// The maximum number of finalized blocks held in memory while waiting for their parent to be
+    /// executed.  While the queue is full, no further blocks are synced.
+    ///
+    /// Defaults to 1,000.
+    pub max_exec_queue_size: Option<usize>,
 }
 
 impl Config {
@@ -58,6 +70,17 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    /// Trie cache size in bytes.
+    pub fn trie_cache_size_or_default(&self) -> usize {
+        self.trie_cache_size.unwrap_or(DEFAULT_TRIE_CACHE_SIZE)
+    }
+
+    /// Maximum number of blocks in the execution queue.
+    pub fn max_exec_queue_size_or_default(&self) -> usize {
+        self.max_exec_queue_size
+            .unwrap_or(DEFAULT_MAX_EXEC_QUEUE_SIZE)
+    }
 }
 
 impl Default for Config {
@@ -67,6 +90,8 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            trie_cache_size: Some(DEFAULT_TRIE_CACHE_SIZE),
+            max_exec_queue_size: Some(DEFAULT_MAX_EXEC_QUEUE_SIZE),
         }
     }
 }
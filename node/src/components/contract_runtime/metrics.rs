@@ -1,4 +1,4 @@
-use prometheus::{self, Gauge, Histogram, IntGauge, Registry};
+use prometheus::{self, Gauge, Histogram, IntCounter, IntGauge, Registry};
 
 use crate::{unregister_metric, utils};
 
@@ -51,10 +51,50 @@ const EXEC_BLOCK_HELP: &str = "time in seconds to execute all deploys in a block
 const LATEST_COMMIT_STEP_NAME: &str = "contract_runtime_latest_commit_step";
 const LATEST_COMMIT_STEP_HELP: &str = "duration in seconds of latest commit step at era end";
 
+const WRITE_SCRATCH_TO_DB_NAME: &str = "contract_runtime_write_scratch_to_db";
+const WRITE_SCRATCH_TO_DB_HELP: &str =
+    "time in seconds to write the global state changes of a block to the database";
+
+const GAS_PER_BLOCK_NAME: &str = "contract_runtime_gas_per_block";
+const GAS_PER_BLOCK_HELP: &str = "gas consumed by executing all deploys in a block";
+
+const EXECUTED_DEPLOYS_NAME: &str = "contract_runtime_executed_deploys";
+const EXECUTED_DEPLOYS_HELP: &str = "total number of deploys executed";
+
+const TRIE_READS_NAME: &str = "contract_runtime_trie_reads";
+const TRIE_READS_HELP: &str =
+    "total number of global state reads during block execution served from the trie store";
+
+const WRITTEN_KEYS_NAME: &str = "contract_runtime_written_keys";
+const WRITTEN_KEYS_HELP: &str = "total number of global state values committed to the trie store \
+    by block execution, counting each key written by a block once";
+
+const CACHE_HITS_NAME: &str = "contract_runtime_cache_hits";
+const CACHE_HITS_HELP: &str =
+    "total number of global state reads during block execution served from the in-memory cache";
+
+const TRIE_CACHE_HITS_NAME: &str = "contract_runtime_trie_cache_hits";
+const TRIE_CACHE_HITS_HELP: &str = "total number of trie reads served from the trie cache";
+
+const TRIE_CACHE_MISSES_NAME: &str = "contract_runtime_trie_cache_misses";
+const TRIE_CACHE_MISSES_HELP: &str =
+    "total number of trie reads which missed the trie cache and went to the database";
+
+const TRIE_CACHE_EVICTIONS_NAME: &str = "contract_runtime_trie_cache_evictions";
+const TRIE_CACHE_EVICTIONS_HELP: &str =
+    "total number of tries evicted from the trie cache to stay within its size limit";
+
+const TRIE_CACHE_SIZE_NAME: &str = "contract_runtime_trie_cache_size_bytes";
+const TRIE_CACHE_SIZE_HELP: &str = "total size in bytes of the tries held in the trie cache";
+
 const EXEC_QUEUE_SIZE_NAME: &str = "execution_queue_size";
 const EXEC_QUEUE_SIZE_HELP: &str =
     "number of blocks that are currently enqueued and waiting for execution";
 
+const EXEC_QUEUE_OLDEST_HEIGHT_NAME: &str = "execution_queue_oldest_height";
+const EXEC_QUEUE_OLDEST_HEIGHT_HELP: &str =
+    "height of the lowest block waiting for execution, or 0 if no block is enqueued";
+
 /// Metrics for the contract runtime component.
 #[derive(Debug)]
 pub struct Metrics {
@@ -69,8 +109,19 @@ pub struct Metrics {
     pub(super) put_trie: Histogram,
     pub(super) get_trie: Histogram,
     pub(super) exec_block: Histogram,
+    pub(super) write_scratch_to_db: Histogram,
+    pub(super) gas_per_block: Histogram,
+    pub(super) executed_deploys: IntCounter,
+    pub(super) trie_reads: IntCounter,
+    pub(super) written_keys: IntCounter,
+    pub(super) cache_hits: IntCounter,
+    pub(super) trie_cache_hits: IntCounter,
+    pub(super) trie_cache_misses: IntCounter,
+    pub(super) trie_cache_evictions: IntCounter,
+    pub(super) trie_cache_size: IntGauge,
     pub(super) latest_commit_step: Gauge,
     pub(super) exec_queue_size: IntGauge,
+    pub(super) exec_queue_oldest_height: IntGauge,
     registry: Registry,
 }
 
@@ -89,12 +140,44 @@ impl Metrics {
         // Anything above that should be a warning signal.
         let tiny_buckets = prometheus::exponential_buckets(0.001, 2.0, 10)?;
 
+        // Start from 10^8 gas (roughly the cost of a native transfer), factor by 10.
+        let gas_buckets = prometheus::exponential_buckets(100_000_000.0, 10.0, 7)?;
+
+        let executed_deploys = IntCounter::new(EXECUTED_DEPLOYS_NAME, EXECUTED_DEPLOYS_HELP)?;
+        registry.register(Box::new(executed_deploys.clone()))?;
+
+        let trie_reads = IntCounter::new(TRIE_READS_NAME, TRIE_READS_HELP)?;
+        registry.register(Box::new(trie_reads.clone()))?;
+
+        let written_keys = IntCounter::new(WRITTEN_KEYS_NAME, WRITTEN_KEYS_HELP)?;
+        registry.register(Box::new(written_keys.clone()))?;
+
+        let cache_hits = IntCounter::new(CACHE_HITS_NAME, CACHE_HITS_HELP)?;
+        registry.register(Box::new(cache_hits.clone()))?;
+
+        let trie_cache_hits = IntCounter::new(TRIE_CACHE_HITS_NAME, TRIE_CACHE_HITS_HELP)?;
+        registry.register(Box::new(trie_cache_hits.clone()))?;
+
+        let trie_cache_misses = IntCounter::new(TRIE_CACHE_MISSES_NAME, TRIE_CACHE_MISSES_HELP)?;
+        registry.register(Box::new(trie_cache_misses.clone()))?;
+
+        let trie_cache_evictions =
+            IntCounter::new(TRIE_CACHE_EVICTIONS_NAME, TRIE_CACHE_EVICTIONS_HELP)?;
+        registry.register(Box::new(trie_cache_evictions.clone()))?;
+
+        let trie_cache_size = IntGauge::new(TRIE_CACHE_SIZE_NAME, TRIE_CACHE_SIZE_HELP)?;
+        registry.register(Box::new(trie_cache_size.clone()))?;
+
         let latest_commit_step = Gauge::new(LATEST_COMMIT_STEP_NAME, LATEST_COMMIT_STEP_HELP)?;
         registry.register(Box::new(latest_commit_step.clone()))?;
 
         let exec_queue_size = IntGauge::new(EXEC_QUEUE_SIZE_NAME, EXEC_QUEUE_SIZE_HELP)?;
         registry.register(Box::new(exec_queue_size.clone()))?;
 
+        let exec_queue_oldest_height =
+            IntGauge::new(EXEC_QUEUE_OLDEST_HEIGHT_NAME, EXEC_QUEUE_OLDEST_HEIGHT_HELP)?;
+        registry.register(Box::new(exec_queue_oldest_height.clone()))?;
+
         Ok(Metrics {
             run_execute: utils::register_histogram_metric(
                 registry,
@@ -160,10 +243,31 @@ impl Metrics {
                 registry,
                 EXEC_BLOCK_NAME,
                 EXEC_BLOCK_HELP,
+                common_buckets.clone(),
+            )?,
+            write_scratch_to_db: utils::register_histogram_metric(
+                registry,
+                WRITE_SCRATCH_TO_DB_NAME,
+                WRITE_SCRATCH_TO_DB_HELP,
                 common_buckets,
             )?,
+            gas_per_block: utils::register_histogram_metric(
+                registry,
+                GAS_PER_BLOCK_NAME,
+                GAS_PER_BLOCK_HELP,
+                gas_buckets,
+            )?,
+            executed_deploys,
+            trie_reads,
+            written_keys,
+            cache_hits,
+            trie_cache_hits,
+            trie_cache_misses,
+            trie_cache_evictions,
+            trie_cache_size,
             latest_commit_step,
             exec_queue_size,
+            exec_queue_oldest_height,
             registry: registry.clone(),
         })
     }
@@ -182,7 +286,18 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.put_trie);
         unregister_metric!(self.registry, self.get_trie);
         unregister_metric!(self.registry, self.exec_block);
+        unregister_metric!(self.registry, self.write_scratch_to_db);
+        unregister_metric!(self.registry, self.gas_per_block);
+        unregister_metric!(self.registry, self.executed_deploys);
+        unregister_metric!(self.registry, self.trie_reads);
+        unregister_metric!(self.registry, self.written_keys);
+        unregister_metric!(self.registry, self.cache_hits);
+        unregister_metric!(self.registry, self.trie_cache_hits);
+        unregister_metric!(self.registry, self.trie_cache_misses);
+        unregister_metric!(self.registry, self.trie_cache_evictions);
+        unregister_metric!(self.registry, self.trie_cache_size);
         unregister_metric!(self.registry, self.latest_commit_step);
         unregister_metric!(self.registry, self.exec_queue_size);
+        unregister_metric!(self.registry, self.exec_queue_oldest_height);
     }
 }
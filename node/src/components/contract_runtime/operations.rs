@@ -14,7 +14,9 @@ use casper_execution_engine::{
         execution,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
-    storage::global_state::{lmdb::LmdbGlobalState, CommitProvider, StateProvider},
+    storage::global_state::{
+        lmdb::LmdbGlobalState, scratch::ScratchGlobalState, CommitProvider, StateProvider,
+    },
 };
 use casper_hashing::Digest;
 use casper_types::{
@@ -88,6 +90,10 @@ fn calculate_prune_eras(
 }
 
 /// Executes a finalized block.
+///
+/// `on_deploy_processed` is called with each deploy's execution result as soon as it has been
+/// executed. The block's effects are only committed to global state once all deploys have been
+/// executed, and not at all if execution fails.
 #[allow(clippy::too_many_arguments)]
 pub fn execute_finalized_block(
     engine_state: &EngineState<LmdbGlobalState>,
@@ -99,6 +105,7 @@ pub fn execute_finalized_block(
     activation_point_era_id: EraId,
     key_block_height_for_activation_point: u64,
     prune_batch_size: u64,
+    mut on_deploy_processed: impl FnMut(types::DeployHash, &DeployHeader, &ExecutionResult),
 ) -> Result<BlockAndExecutionResults, BlockExecutionError> {
     if finalized_block.height() != execution_pre_state.next_block_height {
         return Err(BlockExecutionError::WrongBlockHeight {
@@ -115,6 +122,7 @@ pub fn execute_finalized_block(
     let mut state_root_hash = pre_state_root_hash;
     let mut execution_results: Vec<(_, DeployHeader, ExecutionResult)> =
         Vec::with_capacity(deploys.len());
+    let mut block_gas = U512::zero();
     // Run any deploys that must be executed
     let block_time = finalized_block.timestamp().millis();
     let start = Instant::now();
@@ -153,6 +161,11 @@ pub fn execute_finalized_block(
             deploy_hash.into(),
             result,
         )?;
+        let cost = match &execution_result {
+            ExecutionResult::Failure { cost, .. } | ExecutionResult::Success { cost, .. } => *cost,
+        };
+        block_gas = block_gas.saturating_add(cost);
+        on_deploy_processed(deploy_hash, &deploy_header, &execution_result);
         execution_results.push((deploy_hash, deploy_header, execution_result));
         state_root_hash = state_hash;
     }
@@ -183,6 +196,12 @@ pub fn execute_finalized_block(
 
     if let Some(metrics) = metrics.as_ref() {
         metrics.exec_block.observe(start.elapsed().as_secs_f64());
+        metrics
+            .executed_deploys
+            .inc_by(execution_results.len() as u64);
+        metrics
+            .gas_per_block
+            .observe(u64::try_from(block_gas).unwrap_or(u64::MAX) as f64);
     }
 
     // If the finalized block has an era report, run the auction contract and get the upcoming era
@@ -194,7 +213,7 @@ pub fn execute_finalized_block(
                 execution_journal: step_execution_journal,
             } = commit_step(
                 &scratch_state, // engine_state
-                metrics,
+                metrics.clone(),
                 protocol_version,
                 state_root_hash,
                 era_report,
@@ -203,7 +222,7 @@ pub fn execute_finalized_block(
             )?;
 
             state_root_hash =
-                engine_state.write_scratch_to_db(state_root_hash, scratch_state.into_inner())?;
+                write_scratch_to_db(engine_state, metrics, state_root_hash, scratch_state)?;
 
             // In this flow we execute using a recent state root hash where the system contract
             // registry is guaranteed to exist.
@@ -222,7 +241,7 @@ pub fn execute_finalized_block(
             // Finally, the new state-root-hash from the cumulative changes to global state is
             // returned when they are written to LMDB.
             state_root_hash =
-                engine_state.write_scratch_to_db(state_root_hash, scratch_state.into_inner())?;
+                write_scratch_to_db(engine_state, metrics, state_root_hash, scratch_state)?;
             None
         };
 
@@ -383,6 +402,39 @@ where
     Ok((new_state_root, json_execution_result))
 }
 
+/// Writes the global state changes cached by the scratch engine state to LMDB.
+fn write_scratch_to_db(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: Option<Arc<Metrics>>,
+    state_root_hash: Digest,
+    scratch_state: EngineState<ScratchGlobalState>,
+) -> Result<Digest, engine_state::Error> {
+    let scratch_global_state = scratch_state.into_inner();
+    let stats = scratch_global_state.stats();
+    let start = Instant::now();
+    let result = engine_state.write_scratch_to_db(state_root_hash, scratch_global_state);
+    if let Some(metrics) = metrics {
+        metrics
+            .write_scratch_to_db
+            .observe(start.elapsed().as_secs_f64());
+        metrics.trie_reads.inc_by(stats.trie_reads);
+        metrics.written_keys.inc_by(stats.written_keys);
+        metrics.cache_hits.inc_by(stats.cache_hits);
+        if let Ok(Some(trie_cache_stats)) = engine_state.get_state().trie_store().take_cache_stats()
+        {
+            metrics.trie_cache_hits.inc_by(trie_cache_stats.hits);
+            metrics.trie_cache_misses.inc_by(trie_cache_stats.misses);
+            metrics
+                .trie_cache_evictions
+                .inc_by(trie_cache_stats.evictions);
+            metrics
+                .trie_cache_size
+                .set(trie_cache_stats.size_bytes.try_into().unwrap_or(i64::MAX));
+        }
+    }
+    result
+}
+
 fn commit_transforms<S>(
     engine_state: &EngineState<S>,
     metrics: Option<Arc<Metrics>>,
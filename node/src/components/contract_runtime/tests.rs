@@ -20,7 +20,9 @@ use crate::{
     protocol::Message,
     reactor::{self, EventQueueHandle, ReactorEvent, Runner},
     testing::{self, network::NetworkedReactor, ConditionCheckReactor},
-    types::{BlockPayload, Chainspec, ChainspecRawBytes, Deploy, DeployHashWithApprovals},
+    types::{
+        BlockPayload, Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployHashWithApprovals,
+    },
     utils::{Loadable, WithDir, RESOURCES_PATH},
     NodeRng,
 };
@@ -45,6 +47,8 @@ enum Event {
     StorageRequest(StorageRequest),
     #[from]
     MetaBlockAnnouncement(MetaBlockAnnouncement),
+    #[from]
+    FatalAnnouncement(FatalAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -66,13 +70,18 @@ impl<T: Unhandled> From<T> for Event {
 }
 
 impl Unhandled for ControlAnnouncement {}
-impl Unhandled for FatalAnnouncement {}
 impl Unhandled for NetworkRequest<Message> {}
 impl Unhandled for UnexecutedBlockAnnouncement {}
 
 struct Reactor {
     storage: Storage,
     contract_runtime: ContractRuntime,
+    /// The deploys announced as processed, in order.
+    processed_deploys: Vec<DeployHash>,
+    /// The number of deploys announced as processed when the last meta block was announced.
+    processed_deploys_at_meta_block: Option<usize>,
+    /// The heights of the blocks announced as having failed to execute.
+    failed_block_heights: Vec<u64>,
     _storage_tempdir: TempDir,
 }
 
@@ -125,11 +134,15 @@ impl reactor::Reactor for Reactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.core_config.start_protocol_version_with_get_era_id,
         )?;
 
         let reactor = Reactor {
             storage,
             contract_runtime,
+            processed_deploys: vec![],
+            processed_deploys_at_meta_block: None,
+            failed_block_heights: vec![],
             _storage_tempdir: storage_tempdir,
         };
 
@@ -156,6 +169,15 @@ impl reactor::Reactor for Reactor {
             ),
             Event::ContractRuntimeAnnouncement(announcement) => {
                 info!("{announcement}");
+                match announcement {
+                    ContractRuntimeAnnouncement::DeployProcessed { deploy_hash, .. } => {
+                        self.processed_deploys.push(deploy_hash)
+                    }
+                    ContractRuntimeAnnouncement::BlockExecutionFailed { block_height, .. } => {
+                        self.failed_block_heights.push(block_height)
+                    }
+                    _ => (),
+                }
                 Effects::new()
             }
             Event::Storage(event) => reactor::wrap_effects(
@@ -167,6 +189,11 @@ impl reactor::Reactor for Reactor {
                 self.storage.handle_event(effect_builder, rng, req.into()),
             ),
             Event::MetaBlockAnnouncement(announcement) => {
+                info!("{announcement}");
+                self.processed_deploys_at_meta_block = Some(self.processed_deploys.len());
+                Effects::new()
+            }
+            Event::FatalAnnouncement(announcement) => {
                 info!("{announcement}");
                 Effects::new()
             }
@@ -201,6 +228,49 @@ fn execution_completed(event: &Event) -> bool {
     matches!(event, Event::MetaBlockAnnouncement(_))
 }
 
+/// Returns `count` deploys transferring from the account of `secret_key` to new random public keys.
+fn transfer_deploys(
+    chainspec: &Chainspec,
+    secret_key: &SecretKey,
+    count: usize,
+    rng: &mut NodeRng,
+) -> Vec<Deploy> {
+    let timestamp = Timestamp::now();
+    let ttl = TimeDiff::from_seconds(100);
+    let gas_price = 1;
+    let chain_name = chainspec.network_config.name.clone();
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! {
+          "amount" => U512::from(chainspec.system_costs_config.wasmless_transfer_cost()),
+        },
+    };
+
+    std::iter::repeat_with(|| {
+        let target_public_key = PublicKey::random(rng);
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+              "amount" => U512::from(chainspec.deploy_config.native_transfer_minimum_motes),
+              "target" => target_public_key,
+              "id" => Some(9_u64),
+            },
+        };
+        Deploy::new(
+            timestamp,
+            ttl,
+            gas_price,
+            vec![],
+            chain_name.clone(),
+            payment.clone(),
+            session,
+            secret_key,
+            None,
+        )
+    })
+    .take(count)
+    .collect()
+}
+
 #[tokio::test]
 async fn should_not_set_shared_pre_state_to_lower_block_height() {
     testing::init_logging();
@@ -302,40 +372,7 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
             .join("node-1.pem"),
     )
     .unwrap();
-    let timestamp = Timestamp::now();
-    let ttl = TimeDiff::from_seconds(100);
-    let gas_price = 1;
-    let chain_name = chainspec.network_config.name.clone();
-    let payment = ExecutableDeployItem::ModuleBytes {
-        module_bytes: Bytes::new(),
-        args: runtime_args! {
-          "amount" => U512::from(chainspec.system_costs_config.wasmless_transfer_cost()),
-        },
-    };
-
-    let deploys: Vec<Deploy> = std::iter::repeat_with(|| {
-        let target_public_key = PublicKey::random(rng);
-        let session = ExecutableDeployItem::Transfer {
-            args: runtime_args! {
-              "amount" => U512::from(chainspec.deploy_config.native_transfer_minimum_motes),
-              "target" => target_public_key,
-              "id" => Some(9_u64),
-            },
-        };
-        Deploy::new(
-            timestamp,
-            ttl,
-            gas_price,
-            vec![],
-            chain_name.clone(),
-            payment.clone(),
-            session,
-            &node_1_secret_key,
-            None,
-        )
-    })
-    .take(200)
-    .collect();
+    let deploys = transfer_deploys(&chainspec, &node_1_secret_key, 200, rng);
     let block_payload = BlockPayload::new(
         vec![],
         deploys.iter().map(DeployHashWithApprovals::from).collect(),
@@ -392,3 +429,247 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
         next_block_height
     );
 }
+
+#[tokio::test]
+async fn should_keep_queued_blocks_and_report_missing_block() {
+    testing::init_logging();
+
+    let config = Config {
+        max_global_state_size: Some(100 * 1024 * 1024),
+        max_exec_queue_size: Some(2),
+        ..Config::default()
+    };
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+
+    let mut rng = crate::new_rng();
+    let rng = &mut rng;
+
+    let mut runner: Runner<ConditionCheckReactor<Reactor>> = Runner::new(
+        config,
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        rng,
+    )
+    .await
+    .unwrap();
+
+    runner
+        .reactor_mut()
+        .inner_mut()
+        .contract_runtime
+        .set_initial_state(ExecutionPreState::new(
+            0,
+            Digest::default(),
+            BlockHash::default(),
+            Digest::default(),
+        ));
+
+    // Enqueue blocks 3, 2 and 1 while block 0 is missing.
+    for height in [3, 2, 1] {
+        let finalized_block = FinalizedBlock::new(
+            BlockPayload::default(),
+            None,
+            Timestamp::now(),
+            EraId::new(0),
+            height,
+            PublicKey::System,
+        );
+        runner
+            .process_injected_effects(execute_block(finalized_block, vec![]))
+            .await;
+        runner
+            .crank_until(rng, execution_started, TEST_TIMEOUT)
+            .await;
+    }
+
+    // The queue exceeds its maximum size, but no finalized block is dropped.
+    let contract_runtime = &runner.reactor().inner().contract_runtime;
+    let queued_heights: Vec<u64> = contract_runtime
+        .exec_queue
+        .lock()
+        .unwrap()
+        .keys()
+        .copied()
+        .collect();
+    assert_eq!(queued_heights, vec![1, 2, 3]);
+    assert_eq!(contract_runtime.metrics.exec_queue_size.get(), 3);
+    assert_eq!(contract_runtime.metrics.exec_queue_oldest_height.get(), 1);
+    assert!(contract_runtime.is_exec_queue_full());
+    assert_eq!(
+        contract_runtime.missing_block_for_execution(),
+        Some((0, BlockHash::default()))
+    );
+}
+
+/// A function to be used a condition check, indicating that block execution has failed.
+fn block_execution_failed(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::ContractRuntimeAnnouncement(
+            ContractRuntimeAnnouncement::BlockExecutionFailed { .. }
+        )
+    )
+}
+
+#[tokio::test]
+async fn should_announce_processed_deploys_before_meta_block() {
+    testing::init_logging();
+
+    let config = Config {
+        max_global_state_size: Some(100 * 1024 * 1024),
+        ..Config::default()
+    };
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let chainspec = Arc::new(chainspec);
+    let chainspec_raw_bytes = Arc::new(chainspec_raw_bytes);
+
+    let mut rng = crate::new_rng();
+    let rng = &mut rng;
+
+    let mut runner: Runner<ConditionCheckReactor<Reactor>> = Runner::new(
+        config,
+        Arc::clone(&chainspec),
+        Arc::clone(&chainspec_raw_bytes),
+        rng,
+    )
+    .await
+    .unwrap();
+
+    let post_commit_genesis_state_hash = runner
+        .reactor()
+        .inner()
+        .contract_runtime
+        .commit_genesis(chainspec.as_ref(), chainspec_raw_bytes.as_ref())
+        .unwrap()
+        .post_state_hash;
+    runner
+        .reactor_mut()
+        .inner_mut()
+        .contract_runtime
+        .set_initial_state(ExecutionPreState::new(
+            0,
+            post_commit_genesis_state_hash,
+            BlockHash::default(),
+            Digest::default(),
+        ));
+
+    let block_0 = FinalizedBlock::new(
+        BlockPayload::default(),
+        Some(EraReport::default()),
+        Timestamp::now(),
+        EraId::new(0),
+        0,
+        PublicKey::System,
+    );
+    runner
+        .process_injected_effects(execute_block(block_0, vec![]))
+        .await;
+    runner
+        .crank_until(rng, execution_completed, TEST_TIMEOUT)
+        .await;
+
+    let node_1_secret_key = SecretKey::from_file(
+        RESOURCES_PATH
+            .join("local")
+            .join("secret_keys")
+            .join("node-1.pem"),
+    )
+    .unwrap();
+    let deploys = transfer_deploys(&chainspec, &node_1_secret_key, 3, rng);
+    let deploy_hashes: Vec<DeployHash> = deploys.iter().map(|deploy| *deploy.hash()).collect();
+    let block_payload = BlockPayload::new(
+        vec![],
+        deploys.iter().map(DeployHashWithApprovals::from).collect(),
+        vec![],
+        true,
+    );
+    let block_1 = FinalizedBlock::new(
+        block_payload,
+        None,
+        Timestamp::now(),
+        EraId::new(1),
+        1,
+        PublicKey::System,
+    );
+    runner
+        .process_injected_effects(execute_block(block_1, deploys))
+        .await;
+    runner
+        .crank_until(rng, execution_completed, TEST_TIMEOUT)
+        .await;
+
+    // Every deploy was announced, in order, before the block's meta block.
+    let reactor = runner.reactor().inner();
+    assert_eq!(reactor.processed_deploys, deploy_hashes);
+    assert_eq!(
+        reactor.processed_deploys_at_meta_block,
+        Some(deploy_hashes.len())
+    );
+    assert!(reactor.failed_block_heights.is_empty());
+}
+
+#[tokio::test]
+async fn should_announce_failed_block_execution() {
+    testing::init_logging();
+
+    let config = Config {
+        max_global_state_size: Some(100 * 1024 * 1024),
+        ..Config::default()
+    };
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+
+    let mut rng = crate::new_rng();
+    let rng = &mut rng;
+
+    let mut runner: Runner<ConditionCheckReactor<Reactor>> = Runner::new(
+        config,
+        Arc::new(chainspec),
+        Arc::new(chainspec_raw_bytes),
+        rng,
+    )
+    .await
+    .unwrap();
+
+    // Genesis was never committed, so there is no global state to execute the deploy against.
+    runner
+        .reactor_mut()
+        .inner_mut()
+        .contract_runtime
+        .set_initial_state(ExecutionPreState::new(
+            0,
+            Digest::hash(rng.next_u64().to_le_bytes()),
+            BlockHash::default(),
+            Digest::default(),
+        ));
+
+    let deploy = Deploy::random(rng);
+    let block_payload = BlockPayload::new(
+        vec![],
+        vec![DeployHashWithApprovals::from(&deploy)],
+        vec![],
+        true,
+    );
+    let finalized_block = FinalizedBlock::new(
+        block_payload,
+        None,
+        Timestamp::now(),
+        EraId::new(0),
+        0,
+        PublicKey::System,
+    );
+    runner
+        .process_injected_effects(execute_block(finalized_block, vec![deploy]))
+        .await;
+    runner
+        .crank_until(rng, block_execution_failed, TEST_TIMEOUT)
+        .await;
+
+    // The failure was announced, and no deploy of the failed block was announced as processed.
+    let reactor = runner.reactor().inner();
+    assert_eq!(reactor.failed_block_heights, vec![0]);
+    assert!(reactor.processed_deploys.is_empty());
+    assert_eq!(reactor.processed_deploys_at_meta_block, None);
+}
@@ -5,7 +5,11 @@ mod event;
 mod metrics;
 mod tests;
 
-use std::{collections::BTreeSet, fmt::Debug, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use prometheus::Registry;
@@ -35,9 +39,11 @@ use crate::{
         EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
+    reactor::{self, OperationId},
     types::{
         chainspec::{CoreConfig, DeployConfig},
-        BlockHash, BlockHeader, Chainspec, Deploy, DeployConfigurationFailure, FinalizedApprovals,
+        Approval, BlockHash, BlockHeader, Chainspec, Deploy, DeployConfigurationFailure,
+        DeployHash, FinalizedApprovals, NodeId,
     },
     utils::Source,
     NodeRng,
@@ -50,7 +56,16 @@ const COMPONENT_NAME: &str = "deploy_acceptor";
 
 const ARG_TARGET: &str = "target";
 
-#[derive(Debug, Error, Serialize)]
+/// The outcome of successfully checking a deploy for acceptance.
+#[derive(Clone, Copy, DataSize, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum DeployAcceptance {
+    /// The deploy was stored, and is gossiped if it is new.
+    Accepted,
+    /// The deploy is held until further approvals meet its account's deployment threshold.
+    AwaitingApprovals,
+}
+
+#[derive(Clone, Debug, Error, Serialize)]
 pub(crate) enum Error {
     /// The block chain has no blocks.
     #[error("block chain has no blocks")]
@@ -84,6 +99,34 @@ pub(crate) enum Error {
         /// The timestamp when the node validated the expiry timestamp.
         current_node_timestamp: Timestamp,
     },
+
+    /// The peer which gossiped the deploy has reached its limit of pending deploys.
+    #[error("peer has reached the limit of {limit} pending deploys")]
+    ExceededPendingDeployLimit {
+        /// The configured maximum number of pending deploys per peer.
+        limit: u32,
+    },
+
+    /// The node already holds the maximum number of deploys awaiting further approvals.
+    #[error("node has reached the limit of {limit} deploys awaiting approvals")]
+    ExceededAwaitingApprovalsLimit {
+        /// The configured maximum number of deploys awaiting approvals.
+        limit: u32,
+    },
+
+    /// The deploy's account already has the maximum number of deploys awaiting further approvals.
+    #[error("account has reached the limit of {limit} deploys awaiting approvals")]
+    ExceededAccountAwaitingApprovalsLimit {
+        /// The configured maximum number of deploys awaiting approvals per account.
+        limit: u32,
+    },
+
+    /// No deploy with the given hash is awaiting further approvals.
+    #[error("deploy {deploy_hash} is not awaiting approvals")]
+    NotAwaitingApprovals {
+        /// The hash of the deploy to which approvals were to be added.
+        deploy_hash: DeployHash,
+    },
 }
 
 impl Error {
@@ -146,6 +189,17 @@ pub(crate) enum DeployParameterFailure {
     MissingModuleBytes,
 }
 
+/// Returns the declared payment amount if the deploy's payment is standard payment, i.e. empty
+/// module bytes with a valid "amount" runtime argument.
+fn standard_payment_amount(deploy: &Deploy) -> Option<U512> {
+    match deploy.payment() {
+        ExecutableDeployItem::ModuleBytes { module_bytes, args } if module_bytes.is_empty() => {
+            args.get(ARG_AMOUNT)?.clone().into_t().ok()
+        }
+        _ => None,
+    }
+}
+
 /// A helper trait constraining `DeployAcceptor` compatible reactor events.
 pub(crate) trait ReactorEventT:
     From<Event>
@@ -172,6 +226,10 @@ impl<REv> ReactorEventT for REv where
 ///
 /// It validates a new `Deploy` as far as possible, stores it if valid, then announces the newly-
 /// accepted `Deploy`.
+///
+/// A `Deploy` from a client whose approvals are valid but don't meet its account's deployment
+/// threshold is held instead, until the client adds enough approvals for it to be accepted or it
+/// expires.
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// ```mermaid
 /// flowchart TD
@@ -211,6 +269,11 @@ pub struct DeployAcceptor {
     deploy_config: DeployConfig,
     core_config: CoreConfig,
     max_associated_keys: u32,
+    /// Expiry times of the accepted deploys gossiped to us by each peer.
+    pending_by_peer: HashMap<NodeId, Vec<Timestamp>>,
+    /// Deploys from clients held until further approvals meet their account's deployment
+    /// threshold.
+    awaiting_approvals: HashMap<DeployHash, Arc<Deploy>>,
     #[data_size(skip)]
     metrics: metrics::Metrics,
 }
@@ -228,6 +291,8 @@ impl DeployAcceptor {
             deploy_config: chainspec.deploy_config,
             core_config: chainspec.core_config.clone(),
             max_associated_keys: chainspec.core_config.max_associated_keys,
+            pending_by_peer: HashMap::new(),
+            awaiting_approvals: HashMap::new(),
             metrics: metrics::Metrics::new(registry)?,
         })
     }
@@ -241,10 +306,26 @@ impl DeployAcceptor {
         effect_builder: EffectBuilder<REv>,
         deploy: Arc<Deploy>,
         source: Source,
-        maybe_responder: Option<Responder<Result<(), Error>>>,
+        maybe_responder: Option<Responder<Result<DeployAcceptance, Error>>>,
     ) -> Effects<Event> {
+        // Tag everything resulting from this deploy, so its lifecycle can be followed in the logs.
+        reactor::set_operation_id(OperationId::from(deploy.hash()));
         debug!(%source, %deploy, "checking acceptance");
         let verification_start_timestamp = Timestamp::now();
+
+        if let Source::PeerGossiped(peer) = &source {
+            let limit = self.acceptor_config.max_pending_deploys_per_peer;
+            if self.pending_deploy_count(peer, verification_start_timestamp) >= limit as usize {
+                debug!(%deploy, %peer, "peer has reached its pending deploy limit");
+                return self.handle_invalid_deploy_result(
+                    effect_builder,
+                    Box::new(EventMetadata::new(deploy, source, maybe_responder)),
+                    Error::ExceededPendingDeployLimit { limit },
+                    verification_start_timestamp,
+                );
+            }
+        }
+
         let acceptable_result = deploy.is_config_compliant(
             &self.chain_name,
             &self.deploy_config,
@@ -356,7 +437,7 @@ impl DeployAcceptor {
     fn handle_get_account_result<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        event_metadata: Box<EventMetadata>,
+        mut event_metadata: Box<EventMetadata>,
         block_header: Box<BlockHeader>,
         maybe_account: Option<Account>,
         verification_start_timestamp: Timestamp,
@@ -380,12 +461,7 @@ impl DeployAcceptor {
                 )
             }
             Some(account) => {
-                let authorization_keys = event_metadata
-                    .deploy
-                    .approvals()
-                    .iter()
-                    .map(|approval| approval.signer().to_account_hash())
-                    .collect();
+                let authorization_keys = event_metadata.deploy.authorization_keys();
 
                 let admin_set: BTreeSet<AccountHash> = {
                     self.core_config
@@ -421,6 +497,26 @@ impl DeployAcceptor {
                 }
 
                 if !account.can_deploy_with(&authorization_keys) {
+                    // A client's deploy may be held until further approvals are added, as long as
+                    // the approvals it already has are valid.
+                    if matches!(event_metadata.source, Source::Client)
+                        && self.acceptor_config.max_deploys_awaiting_approvals > 0
+                    {
+                        debug!(?authorization_keys, "deploy awaiting further approvals");
+                        event_metadata.awaiting_approvals = true;
+                        return effect_builder
+                            .check_purse_balance(
+                                *block_header.state_root_hash(),
+                                account.main_purse(),
+                            )
+                            .event(move |maybe_balance_value| Event::GetBalanceResult {
+                                event_metadata,
+                                block_header,
+                                maybe_balance_value,
+                                account_hash: account.account_hash(),
+                                verification_start_timestamp,
+                            });
+                    }
                     let error = Error::parameter_failure(
                         &block_header,
                         DeployParameterFailure::InsufficientDeploySignatureWeight,
@@ -480,7 +576,11 @@ impl DeployAcceptor {
                 )
             }
             Some(balance) => {
-                let has_minimum_balance = balance >= *MAX_PAYMENT;
+                // The balance must cover the standard payment amount, as that is moved to the
+                // payment purse before session code runs and is charged even if session fails.
+                let required_balance = standard_payment_amount(&event_metadata.deploy)
+                    .map_or(*MAX_PAYMENT, |amount| amount.max(*MAX_PAYMENT));
+                let has_minimum_balance = balance >= required_balance;
                 if !has_minimum_balance {
                     let error = Error::parameter_failure(
                         &block_header,
@@ -895,7 +995,25 @@ impl DeployAcceptor {
         event_metadata: Box<EventMetadata>,
         verification_start_timestamp: Timestamp,
     ) -> Effects<Event> {
-        if let Err(deploy_configuration_failure) = event_metadata.deploy.is_valid() {
+        // Verifying the approvals is expensive, so it is done off the reactor thread.
+        let deploy = Arc::clone(&event_metadata.deploy);
+        effect_builder
+            .run_blocking(move || deploy.is_valid())
+            .event(move |result| Event::CryptographyValidated {
+                event_metadata,
+                result,
+                verification_start_timestamp,
+            })
+    }
+
+    fn handle_cryptography_validated<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        result: Result<(), DeployConfigurationFailure>,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        if let Err(deploy_configuration_failure) = result {
             // The client has submitted a deploy with one or more invalid signatures.
             // Return an error to the RPC component via the responder.
             debug!("deploy is cryptographically invalid");
@@ -911,7 +1029,7 @@ impl DeployAcceptor {
         // responder and finish.  Otherwise store the deploy and announce it if required.
         if let Source::SpeculativeExec(_) = event_metadata.source {
             let effects = if let Some(responder) = event_metadata.maybe_responder {
-                responder.respond(Ok(())).ignore()
+                responder.respond(Ok(DeployAcceptance::Accepted)).ignore()
             } else {
                 error!("speculative exec source should always have a responder");
                 Effects::new()
@@ -919,6 +1037,14 @@ impl DeployAcceptor {
             return effects;
         }
 
+        if event_metadata.awaiting_approvals {
+            return self.hold_awaiting_approvals(
+                effect_builder,
+                event_metadata,
+                verification_start_timestamp,
+            );
+        }
+
         effect_builder
             .put_deploy_to_storage(event_metadata.deploy.clone())
             .event(move |is_new| Event::PutToStorageResult {
@@ -928,6 +1054,98 @@ impl DeployAcceptor {
             })
     }
 
+    /// Holds a client's deploy until further approvals meet its account's deployment threshold.
+    fn hold_awaiting_approvals<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        self.purge_expired_awaiting_approvals(verification_start_timestamp);
+        let deploy_hash = *event_metadata.deploy.hash();
+        if !self.awaiting_approvals.contains_key(&deploy_hash) {
+            let limit = self.acceptor_config.max_deploys_awaiting_approvals;
+            if self.awaiting_approvals.len() >= limit as usize {
+                debug!(%deploy_hash, "reached the limit of deploys awaiting approvals");
+                return self.handle_invalid_deploy_result(
+                    effect_builder,
+                    event_metadata,
+                    Error::ExceededAwaitingApprovalsLimit { limit },
+                    verification_start_timestamp,
+                );
+            }
+            let account_limit = self
+                .acceptor_config
+                .max_deploys_awaiting_approvals_per_account;
+            let account = event_metadata.deploy.header().account();
+            let account_count = self
+                .awaiting_approvals
+                .values()
+                .filter(|deploy| deploy.header().account() == account)
+                .count();
+            if account_count >= account_limit as usize {
+                debug!(
+                    %deploy_hash,
+                    %account,
+                    "account reached the limit of deploys awaiting approvals"
+                );
+                return self.handle_invalid_deploy_result(
+                    effect_builder,
+                    event_metadata,
+                    Error::ExceededAccountAwaitingApprovalsLimit {
+                        limit: account_limit,
+                    },
+                    verification_start_timestamp,
+                );
+            }
+        }
+        self.awaiting_approvals
+            .insert(deploy_hash, Arc::clone(&event_metadata.deploy));
+        match event_metadata.maybe_responder {
+            Some(responder) => responder
+                .respond(Ok(DeployAcceptance::AwaitingApprovals))
+                .ignore(),
+            None => Effects::new(),
+        }
+    }
+
+    /// Adds the given approvals to a deploy awaiting approvals, and checks it for acceptance again.
+    ///
+    /// The deploy is only released once it has been stored, so approvals which turn out to be
+    /// invalid leave it awaiting approvals as before.
+    fn add_approvals<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<DeployAcceptance, Error>>,
+    ) -> Effects<Event> {
+        self.purge_expired_awaiting_approvals(Timestamp::now());
+        let mut deploy = match self.awaiting_approvals.get(&deploy_hash) {
+            Some(deploy) => Deploy::clone(deploy),
+            None => {
+                debug!(%deploy_hash, "no such deploy awaiting approvals");
+                return responder
+                    .respond(Err(Error::NotAwaitingApprovals { deploy_hash }))
+                    .ignore();
+            }
+        };
+        let mut all_approvals = deploy.approvals().clone();
+        all_approvals.extend(approvals);
+        deploy.replace_approvals(all_approvals);
+        self.accept(
+            effect_builder,
+            Arc::new(deploy),
+            Source::Client,
+            Some(responder),
+        )
+    }
+
+    fn purge_expired_awaiting_approvals(&mut self, now: Timestamp) {
+        self.awaiting_approvals
+            .retain(|_, deploy| !deploy.header().expired(now));
+    }
+
     fn handle_invalid_deploy_result<REv: ReactorEventT>(
         &self,
         effect_builder: EffectBuilder<REv>,
@@ -939,6 +1157,7 @@ impl DeployAcceptor {
             deploy,
             source,
             maybe_responder,
+            ..
         } = *event_metadata;
         if !matches!(source, Source::SpeculativeExec(_)) {
             self.metrics.observe_rejected(verification_start_timestamp);
@@ -947,29 +1166,52 @@ impl DeployAcceptor {
         if let Some(responder) = maybe_responder {
             // The client has submitted an invalid deploy
             // Return an error to the RPC component via the responder.
-            effects.extend(responder.respond(Err(error)).ignore());
+            effects.extend(responder.respond(Err(error.clone())).ignore());
         }
 
         // If this has NOT been received from the speculative exec server, announce it.
         if !matches!(source, Source::SpeculativeExec(_)) {
             effects.extend(
                 effect_builder
-                    .announce_invalid_deploy(deploy, source)
+                    .announce_invalid_deploy(deploy, source, error)
                     .ignore(),
             );
         }
         effects
     }
 
+    /// Returns the number of unexpired deploys gossiped by `peer` which we accepted, discarding
+    /// any expired ones.
+    fn pending_deploy_count(&mut self, peer: &NodeId, now: Timestamp) -> usize {
+        let count = match self.pending_by_peer.get_mut(peer) {
+            Some(expiry_times) => {
+                expiry_times.retain(|expiry_time| *expiry_time >= now);
+                expiry_times.len()
+            }
+            None => return 0,
+        };
+        if count == 0 {
+            self.pending_by_peer.remove(peer);
+        }
+        count
+    }
+
     fn handle_put_to_storage<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
         is_new: bool,
         verification_start_timestamp: Timestamp,
     ) -> Effects<Event> {
         let mut effects = Effects::new();
+        self.awaiting_approvals.remove(event_metadata.deploy.hash());
         if is_new {
+            if let Source::PeerGossiped(peer) = &event_metadata.source {
+                self.pending_by_peer
+                    .entry(*peer)
+                    .or_default()
+                    .push(event_metadata.deploy.header().expires());
+            }
             effects.extend(
                 effect_builder
                     .announce_new_deploy_accepted(event_metadata.deploy, event_metadata.source)
@@ -997,7 +1239,7 @@ impl DeployAcceptor {
 
         // success
         if let Some(responder) = event_metadata.maybe_responder {
-            effects.extend(responder.respond(Ok(())).ignore());
+            effects.extend(responder.respond(Ok(DeployAcceptance::Accepted)).ignore());
         }
         effects
     }
@@ -1014,6 +1256,7 @@ impl DeployAcceptor {
             deploy,
             source,
             maybe_responder,
+            ..
         } = *event_metadata;
         let mut effects = Effects::new();
         if is_new {
@@ -1026,7 +1269,7 @@ impl DeployAcceptor {
 
         // success
         if let Some(responder) = maybe_responder {
-            effects.extend(responder.respond(Ok(())).ignore());
+            effects.extend(responder.respond(Ok(DeployAcceptance::Accepted)).ignore());
         }
         effects
     }
@@ -1048,6 +1291,11 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 source,
                 maybe_responder: responder,
             } => self.accept(effect_builder, deploy, source, responder),
+            Event::AddApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            } => self.add_approvals(effect_builder, deploy_hash, approvals, responder),
             Event::GetBlockHeaderResult {
                 event_metadata,
                 maybe_block_header,
@@ -1127,6 +1375,16 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 maybe_contract_package,
                 verification_start_timestamp,
             ),
+            Event::CryptographyValidated {
+                event_metadata,
+                result,
+                verification_start_timestamp,
+            } => self.handle_cryptography_validated(
+                effect_builder,
+                event_metadata,
+                result,
+                verification_start_timestamp,
+            ),
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_TIMESTAMP_LEEWAY: &str = "2sec";
+const DEFAULT_MAX_PENDING_DEPLOYS_PER_PEER: u32 = 1_000;
+const DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS: u32 = 0;
+const DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS_PER_ACCOUNT: u32 = 10;
 
 /// Configuration options for accepting deploys.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, DataSize)]
@@ -18,12 +21,43 @@ pub struct Config {
     /// The maximum value to which `timestamp_leeway` can be set is defined by the chainspec
     /// setting `deploys.max_timestamp_leeway`.
     pub timestamp_leeway: TimeDiff,
+    /// The maximum number of unexpired deploys gossiped to us by a single peer which are accepted.
+    ///
+    /// Further deploys gossiped by that peer are rejected until some of its earlier ones expire.
+    #[serde(default = "default_max_pending_deploys_per_peer")]
+    pub max_pending_deploys_per_peer: u32,
+    /// The maximum number of unexpired deploys received from clients which are held until further
+    /// approvals meet their account's deployment threshold.
+    ///
+    /// If `0`, deploys with insufficient approval weight are rejected outright.
+    #[serde(default = "default_max_deploys_awaiting_approvals")]
+    pub max_deploys_awaiting_approvals: u32,
+    /// The maximum number of deploys from a single account which are held until further
+    /// approvals meet the account's deployment threshold.
+    #[serde(default = "default_max_deploys_awaiting_approvals_per_account")]
+    pub max_deploys_awaiting_approvals_per_account: u32,
+}
+
+fn default_max_pending_deploys_per_peer() -> u32 {
+    DEFAULT_MAX_PENDING_DEPLOYS_PER_PEER
+}
+
+fn default_max_deploys_awaiting_approvals() -> u32 {
+    DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS
+}
+
+fn default_max_deploys_awaiting_approvals_per_account() -> u32 {
+    DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS_PER_ACCOUNT
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             timestamp_leeway: TimeDiff::from_str(DEFAULT_TIMESTAMP_LEEWAY).unwrap(),
+            max_pending_deploys_per_peer: DEFAULT_MAX_PENDING_DEPLOYS_PER_PEER,
+            max_deploys_awaiting_approvals: DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS,
+            max_deploys_awaiting_approvals_per_account:
+                DEFAULT_MAX_DEPLOYS_AWAITING_APPROVALS_PER_ACCOUNT,
         }
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display, Formatter},
     sync::Arc,
 };
@@ -12,9 +13,9 @@ use casper_types::{
 
 use super::Source;
 use crate::{
-    components::deploy_acceptor::Error,
+    components::deploy_acceptor::{DeployAcceptance, Error},
     effect::Responder,
-    types::{BlockHeader, Deploy},
+    types::{Approval, BlockHeader, Deploy, DeployConfigurationFailure, DeployHash},
 };
 
 /// A utility struct to hold duplicated information across events.
@@ -22,19 +23,23 @@ use crate::{
 pub(crate) struct EventMetadata {
     pub(crate) deploy: Arc<Deploy>,
     pub(crate) source: Source,
-    pub(crate) maybe_responder: Option<Responder<Result<(), Error>>>,
+    pub(crate) maybe_responder: Option<Responder<Result<DeployAcceptance, Error>>>,
+    /// Whether the deploy is to be held until further approvals meet its account's deployment
+    /// threshold.
+    pub(crate) awaiting_approvals: bool,
 }
 
 impl EventMetadata {
     pub(crate) fn new(
         deploy: Arc<Deploy>,
         source: Source,
-        maybe_responder: Option<Responder<Result<(), Error>>>,
+        maybe_responder: Option<Responder<Result<DeployAcceptance, Error>>>,
     ) -> Self {
         EventMetadata {
             deploy,
             source,
             maybe_responder,
+            awaiting_approvals: false,
         }
     }
 }
@@ -46,7 +51,19 @@ pub(crate) enum Event {
     Accept {
         deploy: Arc<Deploy>,
         source: Source,
-        maybe_responder: Option<Responder<Result<(), Error>>>,
+        maybe_responder: Option<Responder<Result<DeployAcceptance, Error>>>,
+    },
+    /// A client adding approvals to a `Deploy` awaiting further approvals.
+    AddApprovals {
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<DeployAcceptance, Error>>,
+    },
+    /// The result of verifying the `Deploy`'s hash and approvals off the reactor thread.
+    CryptographyValidated {
+        event_metadata: Box<EventMetadata>,
+        result: Result<(), DeployConfigurationFailure>,
+        verification_start_timestamp: Timestamp,
     },
     /// The result of the `DeployAcceptor` putting a `Deploy` to the storage component.
     PutToStorageResult {
@@ -109,6 +126,25 @@ impl Display for Event {
             Event::Accept { deploy, source, .. } => {
                 write!(formatter, "accept {} from {}", deploy.hash(), source)
             }
+            Event::AddApprovals {
+                deploy_hash,
+                approvals,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "add {} approvals to {}",
+                    approvals.len(),
+                    deploy_hash
+                )
+            }
+            Event::CryptographyValidated { event_metadata, .. } => {
+                write!(
+                    formatter,
+                    "validated cryptography of deploy with hash {}",
+                    event_metadata.deploy.hash()
+                )
+            }
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
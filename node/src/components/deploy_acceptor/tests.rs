@@ -169,10 +169,12 @@ enum TestScenario {
     FromClientFutureDatedDeploy,
     FromClientMissingAccount,
     FromClientInsufficientBalance,
+    FromClientInsufficientBalanceForPaymentAmount,
     FromClientValidDeploy,
     FromClientRepeatedValidDeploy,
     FromClientAccountWithInsufficientWeight,
     FromClientAccountWithInvalidAssociatedKeys,
+    FromClientDeployAwaitingApprovals,
     AccountWithUnknownBalance,
     FromClientCustomPaymentContract(ContractScenario),
     FromClientCustomPaymentContractPackage(ContractPackageScenario),
@@ -220,10 +222,12 @@ impl TestScenario {
             | TestScenario::FromClientFutureDatedDeploy
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInsufficientBalance
+            | TestScenario::FromClientInsufficientBalanceForPaymentAmount
             | TestScenario::FromClientValidDeploy
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
+            | TestScenario::FromClientDeployAwaitingApprovals
             | TestScenario::AccountWithUnknownBalance
             | TestScenario::DeployWithoutPaymentAmount
             | TestScenario::DeployWithMangledPaymentAmount
@@ -260,10 +264,14 @@ impl TestScenario {
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::FromClientAccountWithInsufficientWeight
+            | TestScenario::FromClientDeployAwaitingApprovals
             | TestScenario::AccountWithUnknownBalance
             | TestScenario::BalanceCheckForDeploySentByPeer => {
                 Deploy::random_valid_native_transfer(rng)
             }
+            TestScenario::FromClientInsufficientBalanceForPaymentAmount => {
+                Deploy::random_with_standard_payment_amount(rng, MAX_PAYMENT_AMOUNT + 1)
+            }
             TestScenario::DeployWithoutPaymentAmount => Deploy::random_without_payment_amount(rng),
             TestScenario::DeployWithMangledPaymentAmount => {
                 Deploy::random_with_mangled_payment_amount(rng)
@@ -386,11 +394,13 @@ impl TestScenario {
             | TestScenario::ShouldAcceptExpiredDeploySentByPeer=> true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
+            | TestScenario::FromClientInsufficientBalanceForPaymentAmount
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInvalidDeploy
             | TestScenario::FromClientFutureDatedDeploy
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
+            | TestScenario::FromClientDeployAwaitingApprovals // held until approvals are added
             | TestScenario::AccountWithUnknownBalance
             | TestScenario::DeployWithEmptySessionModuleBytes
             | TestScenario::DeployWithNativeTransferInPayment
@@ -434,6 +444,18 @@ impl TestScenario {
         )
     }
 
+    fn acceptor_config(&self) -> Config {
+        match self {
+            // Deploys with insufficient approval weight are only held if enabled in the config,
+            // and otherwise rejected outright.
+            TestScenario::FromClientDeployAwaitingApprovals => Config {
+                max_deploys_awaiting_approvals: 1_000,
+                ..Config::default()
+            },
+            _ => Config::default(),
+        }
+    }
+
     pub(crate) fn create_chainspec_for_test(&self, chainspec: Arc<Chainspec>) -> Arc<Chainspec> {
         match self {
             TestScenario::ShouldAcceptDeployFromAdministrator { administrators, .. }
@@ -482,6 +504,26 @@ fn create_account(account_hash: AccountHash, test_scenario: &TestScenario) -> Ac
                 invalid_action_threshold,
             )
         }
+        TestScenario::FromClientDeployAwaitingApprovals => {
+            // Deploys need to be approved by both the account's key and Bob's.
+            let bob_secret_key = SecretKey::ed25519_from_bytes(BOB_SECRET_KEY_BYTES).unwrap();
+            let mut associated_keys = AssociatedKeys::new(account_hash, Weight::new(1));
+            associated_keys
+                .add_key(
+                    PublicKey::from(&bob_secret_key).to_account_hash(),
+                    Weight::new(1),
+                )
+                .expect("should add key");
+            let action_thresholds = ActionThresholds::new(Weight::new(2), Weight::new(2))
+                .expect("should create action threshold");
+            Account::new(
+                account_hash,
+                BTreeMap::new(),
+                URef::default(),
+                associated_keys,
+                action_thresholds,
+            )
+        }
         _ => Account::create(account_hash, NamedKeys::default(), URef::default()),
     }
 }
@@ -511,7 +553,7 @@ impl reactor::Reactor for Reactor {
         let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
 
         let deploy_acceptor = DeployAcceptor::new(
-            Config::default(),
+            config.acceptor_config(),
             &config.create_chainspec_for_test(Arc::clone(&chainspec)),
             registry,
         )
@@ -750,7 +792,7 @@ fn put_deploy_to_storage(
 fn schedule_accept_deploy(
     deploy: Arc<Deploy>,
     source: Source,
-    responder: Responder<Result<(), super::Error>>,
+    responder: Responder<Result<DeployAcceptance, super::Error>>,
 ) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
     |effect_builder: EffectBuilder<Event>| {
         effect_builder
@@ -767,11 +809,31 @@ fn schedule_accept_deploy(
     }
 }
 
+fn schedule_add_approvals(
+    deploy_hash: DeployHash,
+    approvals: BTreeSet<Approval>,
+    responder: Responder<Result<DeployAcceptance, super::Error>>,
+) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
+    move |effect_builder: EffectBuilder<Event>| {
+        effect_builder
+            .into_inner()
+            .schedule(
+                super::Event::AddApprovals {
+                    deploy_hash,
+                    approvals,
+                    responder,
+                },
+                QueueKind::Validation,
+            )
+            .ignore()
+    }
+}
+
 fn inject_balance_check_for_peer(
     deploy: Arc<Deploy>,
     source: Source,
     rng: &mut TestRng,
-    responder: Responder<Result<(), super::Error>>,
+    responder: Responder<Result<DeployAcceptance, super::Error>>,
 ) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
     let block_header = Box::new(Block::random(rng).header().clone());
     |effect_builder: EffectBuilder<Event>| {
@@ -794,7 +856,7 @@ fn inject_balance_check_for_peer(
 
 async fn run_deploy_acceptor_without_timeout(
     test_scenario: TestScenario,
-) -> Result<(), super::Error> {
+) -> Result<DeployAcceptance, super::Error> {
     let _ = logging::init();
     let mut rng = crate::new_rng();
 
@@ -888,6 +950,7 @@ async fn run_deploy_acceptor_without_timeout(
             | TestScenario::FromClientFutureDatedDeploy
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInsufficientBalance
+            | TestScenario::FromClientInsufficientBalanceForPaymentAmount
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::DeployWithEmptySessionModuleBytes
@@ -1003,6 +1066,12 @@ async fn run_deploy_acceptor_without_timeout(
                     )
                 )
             }
+            // Check that a deploy with insufficient approval weight sent by a client is validated,
+            // after which it is held rather than stored.
+            TestScenario::FromClientDeployAwaitingApprovals => matches!(
+                event,
+                Event::DeployAcceptor(super::Event::CryptographyValidated { .. })
+            ),
             // Check that repeated valid deploys from a client raises `PutToStorageResult` with the
             // `is_new` flag as false.
             TestScenario::FromClientRepeatedValidDeploy => matches!(
@@ -1039,9 +1108,63 @@ async fn run_deploy_acceptor_without_timeout(
     runner
         .reactor_mut()
         .set_condition_checker(Box::new(stopping_condition));
+    crank_until_condition(&mut runner, &mut rng).await;
+
+    let is_in_storage = |runner: &Runner<ConditionCheckReactor<Reactor>>| {
+        runner
+            .reactor()
+            .inner()
+            .storage
+            .get_deploy_by_hash(*deploy.hash())
+            .is_some()
+    };
+
+    // Assert that the deploy is present in the case of a valid deploy.
+    // Conversely, assert its absence in the invalid case.
+    assert_eq!(is_in_storage(&runner), test_scenario.is_valid_deploy_case());
+
+    let result = deploy_receiver.await.unwrap();
+
+    if test_scenario == TestScenario::FromClientDeployAwaitingApprovals {
+        // Adding Bob's approval meets the deployment threshold, so the held deploy is accepted.
+        assert_eq!(result.unwrap(), DeployAcceptance::AwaitingApprovals);
+        let bob_secret_key = SecretKey::ed25519_from_bytes(BOB_SECRET_KEY_BYTES).unwrap();
+        let approvals = BTreeSet::from([Approval::create(deploy.hash(), &bob_secret_key)]);
+        let (approvals_sender, approvals_receiver) = oneshot::channel();
+        runner
+            .process_injected_effects(schedule_add_approvals(
+                *deploy.hash(),
+                approvals,
+                Responder::without_shutdown(approvals_sender),
+            ))
+            .await;
+        runner
+            .reactor_mut()
+            .set_condition_checker(Box::new(|event: &Event| {
+                matches!(
+                    event,
+                    Event::DeployAcceptorAnnouncement(
+                        DeployAcceptorAnnouncement::AcceptedNewDeploy {
+                            source: Source::Client,
+                            ..
+                        }
+                    )
+                )
+            }));
+        crank_until_condition(&mut runner, &mut rng).await;
+        assert!(is_in_storage(&runner));
+        return approvals_receiver.await.unwrap();
+    }
+
+    result
+}
 
+async fn crank_until_condition(
+    runner: &mut Runner<ConditionCheckReactor<Reactor>>,
+    rng: &mut NodeRng,
+) {
     loop {
-        match runner.try_crank(&mut rng).await {
+        match runner.try_crank(rng).await {
             TryCrankOutcome::ProcessedAnEvent => {
                 if runner.reactor().condition_result() {
                     break;
@@ -1052,28 +1175,11 @@ async fn run_deploy_acceptor_without_timeout(
             TryCrankOutcome::Exited => unreachable!(),
         }
     }
-
-    {
-        // Assert that the deploy is present in the case of a valid deploy.
-        // Conversely, assert its absence in the invalid case.
-        let is_in_storage = runner
-            .reactor()
-            .inner()
-            .storage
-            .get_deploy_by_hash(*deploy.hash())
-            .is_some();
-
-        if test_scenario.is_valid_deploy_case() {
-            assert!(is_in_storage)
-        } else {
-            assert!(!is_in_storage)
-        }
-    }
-
-    deploy_receiver.await.unwrap()
 }
 
-async fn run_deploy_acceptor(test_scenario: TestScenario) -> Result<(), super::Error> {
+async fn run_deploy_acceptor(
+    test_scenario: TestScenario,
+) -> Result<DeployAcceptance, super::Error> {
     time::timeout(TIMEOUT, run_deploy_acceptor_without_timeout(test_scenario))
         .await
         .unwrap()
@@ -1181,6 +1287,12 @@ async fn should_reject_valid_deploy_from_client_for_account_with_insufficient_we
     ))
 }
 
+#[tokio::test]
+async fn should_accept_deploy_from_client_once_approvals_meet_threshold() {
+    let result = run_deploy_acceptor(TestScenario::FromClientDeployAwaitingApprovals).await;
+    assert!(matches!(result, Ok(DeployAcceptance::Accepted)))
+}
+
 #[tokio::test]
 async fn should_reject_valid_deploy_from_client_for_insufficient_balance() {
     let result = run_deploy_acceptor(TestScenario::FromClientInsufficientBalance).await;
@@ -1193,6 +1305,19 @@ async fn should_reject_valid_deploy_from_client_for_insufficient_balance() {
     ))
 }
 
+#[tokio::test]
+async fn should_reject_deploy_from_client_for_balance_below_payment_amount() {
+    let result =
+        run_deploy_acceptor(TestScenario::FromClientInsufficientBalanceForPaymentAmount).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployParameters {
+            failure: DeployParameterFailure::InsufficientBalance { .. },
+            ..
+        })
+    ))
+}
+
 #[tokio::test]
 async fn should_reject_valid_deploy_from_client_for_unknown_balance() {
     let result = run_deploy_acceptor(TestScenario::AccountWithUnknownBalance).await;
@@ -1709,3 +1834,31 @@ async fn should_reject_valid_deploy_from_peer_signed_by_non_administrator() {
     let result = run_deploy_acceptor(test_scenario).await;
     assert!(result.is_err())
 }
+
+#[test]
+fn should_forget_expired_deploys_gossiped_by_peer() {
+    let mut rng = TestRng::new();
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut deploy_acceptor =
+        DeployAcceptor::new(Config::default(), &chainspec, &Registry::new()).unwrap();
+
+    let peer = NodeId::random(&mut rng);
+    let now = Timestamp::now();
+    assert_eq!(deploy_acceptor.pending_deploy_count(&peer, now), 0);
+
+    let expiry_times = vec![
+        now + TimeDiff::from_seconds(10),
+        now + TimeDiff::from_seconds(20),
+    ];
+    deploy_acceptor.pending_by_peer.insert(peer, expiry_times);
+    assert_eq!(deploy_acceptor.pending_deploy_count(&peer, now), 2);
+    assert_eq!(
+        deploy_acceptor.pending_deploy_count(&peer, now + TimeDiff::from_seconds(15)),
+        1
+    );
+    assert_eq!(
+        deploy_acceptor.pending_deploy_count(&peer, now + TimeDiff::from_seconds(25)),
+        0
+    );
+    assert!(deploy_acceptor.pending_by_peer.is_empty());
+}
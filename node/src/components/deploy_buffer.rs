@@ -5,8 +5,9 @@ mod metrics;
 mod tests;
 
 use std::{
-    collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     convert::TryInto,
+    fmt::{self, Display, Formatter},
     iter::FromIterator,
     mem,
 };
@@ -15,11 +16,12 @@ use datasize::DataSize;
 use futures::FutureExt;
 use itertools::Itertools;
 use prometheus::Registry;
+use serde::Serialize;
 use smallvec::smallvec;
 use tracing::{debug, error, info, warn};
 
 use casper_hashing::Digest;
-use casper_types::Timestamp;
+use casper_types::{account::AccountHash, PublicKey, Timestamp};
 
 use crate::{
     components::{
@@ -28,8 +30,8 @@ use crate::{
     },
     effect::{
         announcements::DeployBufferAnnouncement,
-        requests::{DeployBufferRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects,
+        requests::{ContractRuntimeRequest, DeployBufferRequest, StorageRequest},
+        EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
     reactor::main_reactor::MainEvent,
@@ -52,11 +54,36 @@ const COMPONENT_NAME: &str = "deploy_buffer";
 
 type FootprintAndApprovals = (DeployFootprint, BTreeSet<Approval>);
 
+/// A summary of the deploys currently known to the deploy buffer.
+#[derive(Clone, Copy, DataSize, Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct DeployBufferStatus {
+    /// Number of deploys in the buffer, including held and dead ones.
+    pub(crate) total: usize,
+    /// Number of deploys which are neither held nor dead, i.e. eligible for proposal.
+    pub(crate) pending: usize,
+    /// Number of deploys held by proposed blocks which have not been finalized yet.
+    pub(crate) held: usize,
+    /// Number of deploys included in finalized blocks, which will never be proposed again.
+    pub(crate) dead: usize,
+}
+
+impl Display for DeployBufferStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} deploys buffered ({} pending, {} held, {} dead)",
+            self.total, self.pending, self.held, self.dead
+        )
+    }
+}
+
 #[derive(DataSize, Debug)]
 pub(crate) struct DeployBuffer {
     state: ComponentState,
     cfg: Config,
     deploy_config: DeployConfig,
+    // Approvals by any of these accounts always satisfy a deploy's account.
+    administrators: BTreeSet<AccountHash>,
     // Keeps track of all deploys the buffer is currently aware of.
     //
     // `hold` and `dead` are used to filter it on demand as necessary.
@@ -73,6 +100,20 @@ pub(crate) struct DeployBuffer {
     hold: BTreeMap<Timestamp, HashSet<DeployHash>>,
     // deploy_hashes that should not be proposed, ever
     dead: HashSet<DeployHash>,
+    // the subset of `dead` which was included in a finalized block, as opposed to e.g. expired
+    finalized: HashSet<DeployHash>,
+    // the number of pending deploys per account, i.e. buffered deploys which are neither dead nor
+    // deferred
+    pending_counts: HashMap<PublicKey, u32>,
+    // deploys registered while their account had `max_pending_deploys_per_account` deploys
+    // pending, in order of registration; they stay buffered, but are only proposed once earlier
+    // deploys of the account are finalized or expire
+    deferred_by_account: HashMap<PublicKey, VecDeque<DeployHash>>,
+    // all deploys in `deferred_by_account`
+    deferred: HashSet<DeployHash>,
+    // deploys already counted as left out of a proposed block due to block limits, so that each is
+    // only counted once however often it is left out
+    exceeding_block_limits: HashSet<DeployHash>,
     // deploy buffer metrics
     #[data_size(skip)]
     metrics: Metrics,
@@ -82,6 +123,7 @@ impl DeployBuffer {
     /// Create a deploy buffer for fun and profit.
     pub(crate) fn new(
         deploy_config: DeployConfig,
+        administrators: BTreeSet<AccountHash>,
         cfg: Config,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
@@ -89,9 +131,15 @@ impl DeployBuffer {
             state: ComponentState::Uninitialized,
             cfg,
             deploy_config,
+            administrators,
             buffer: HashMap::new(),
             hold: BTreeMap::new(),
             dead: HashSet::new(),
+            finalized: HashSet::new(),
+            pending_counts: HashMap::new(),
+            deferred_by_account: HashMap::new(),
+            deferred: HashSet::new(),
+            exceeding_block_limits: HashSet::new(),
             metrics: Metrics::new(registry)?,
         })
     }
@@ -167,6 +215,10 @@ impl DeployBuffer {
         });
         self.hold.retain(|_, remaining| !remaining.is_empty());
 
+        self.finalized
+            .retain(|deploy_hash| !freed.contains_key(deploy_hash));
+        self.exceeding_block_limits
+            .retain(|deploy_hash| !freed.contains_key(deploy_hash));
         // retain all those in `dead` which are not in `freed`, at the same time reducing `freed` to
         // only those entries not also in `dead` - i.e. deploys which expired without being included
         // in a block
@@ -174,11 +226,26 @@ impl DeployBuffer {
             .retain(|deploy_hash| freed.remove(deploy_hash).is_none());
         self.buffer = buffer;
 
+        // release deferred deploys first, so that none of them is made pending in place of an
+        // expired one when it has expired itself
+        let (deferred, not_deferred): (Vec<_>, Vec<_>) = freed
+            .iter()
+            .filter_map(|(deploy_hash, (_, maybe_data))| {
+                maybe_data
+                    .as_ref()
+                    .map(|(footprint, _)| (*deploy_hash, footprint.header.account().clone()))
+            })
+            .partition(|(deploy_hash, _)| self.deferred.contains(deploy_hash));
+        for (deploy_hash, account) in deferred.into_iter().chain(not_deferred) {
+            self.release_pending(&account, &deploy_hash);
+        }
+
         if !freed.is_empty() {
             info!(
                 "DeployBuffer: expiring without executing {} deploy(s)",
                 freed.len()
             );
+            self.metrics.expired_deploys.inc_by(freed.len() as u64);
             debug!(
                 "DeployBuffer: expiring without executing {}",
                 DisplayIter::new(freed.keys())
@@ -211,6 +278,46 @@ impl DeployBuffer {
             .event(move |result| Event::StoredDeploy(deploy_id, result.map(Box::new)))
     }
 
+    /// Checks the approvals of the proposable deploys against their accounts' associated keys and
+    /// deployment thresholds, then responds with an appendable block which skips any deploys that
+    /// would fail authorization.  Such deploys stay buffered, as a later change to their accounts'
+    /// keys may still authorize them.
+    fn request_appendable_block<REv>(
+        &self,
+        timestamp: Timestamp,
+        request_expiry: Timestamp,
+        responder: Responder<AppendableBlock>,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + From<ContractRuntimeRequest> + Send,
+    {
+        let authorizations = self
+            .proposable()
+            .into_iter()
+            .map(|(with_approvals, footprint)| {
+                let authorization_keys = with_approvals
+                    .approvals()
+                    .iter()
+                    .map(|approval| approval.signer().to_account_hash())
+                    .collect();
+                (
+                    *with_approvals.deploy_hash(),
+                    footprint.header.account().clone(),
+                    authorization_keys,
+                )
+            })
+            .collect();
+        effect_builder
+            .get_unauthorized_deploys(authorizations, self.administrators.clone())
+            .event(move |unauthorized| Event::CheckedDeployAuthorizations {
+                timestamp,
+                request_expiry,
+                unauthorized,
+                responder,
+            })
+    }
+
     /// Update buffer considering new stored deploy.
     fn register_deploy(&mut self, deploy: Deploy) {
         let deploy_hash = deploy.hash();
@@ -229,6 +336,7 @@ impl DeployBuffer {
                 return;
             }
         };
+        let account = deploy.header().account().clone();
         let expiry_time = deploy.header().expires();
         let approvals = deploy.approvals().clone();
         match self
@@ -241,8 +349,81 @@ impl DeployBuffer {
             None => {
                 debug!(%deploy_hash, "DeployBuffer: new deploy buffered");
                 self.metrics.total_deploys.inc();
+                self.metrics.pending_deploys.inc();
+                self.add_pending(account, *deploy_hash);
+            }
+        }
+    }
+
+    /// Counts a newly buffered deploy as pending for its account, or defers it if the account
+    /// already has `max_pending_deploys_per_account` deploys pending.
+    fn add_pending(&mut self, account: PublicKey, deploy_hash: DeployHash) {
+        let count = self.pending_counts.entry(account.clone()).or_default();
+        if *count < self.cfg.max_pending_deploys_per_account {
+            *count += 1;
+            return;
+        }
+        debug!(
+            %deploy_hash,
+            %account,
+            "DeployBuffer: account exceeds pending deploy limit, deferring deploy"
+        );
+        self.metrics.deploys_exceeding_account_limit.inc();
+        self.deferred.insert(deploy_hash);
+        self.deferred_by_account
+            .entry(account)
+            .or_default()
+            .push_back(deploy_hash);
+    }
+
+    /// Stops counting a deploy which was finalized or dropped as pending for its account.  The
+    /// account's earliest deferred deploy, if any, becomes pending in its place.
+    fn release_pending(&mut self, account: &PublicKey, deploy_hash: &DeployHash) {
+        let was_deferred = self.deferred.remove(deploy_hash);
+        if let hash_map::Entry::Occupied(mut entry) =
+            self.deferred_by_account.entry(account.clone())
+        {
+            if was_deferred {
+                entry
+                    .get_mut()
+                    .retain(|deferred_hash| deferred_hash != deploy_hash);
+            } else if let Some(next_hash) = entry.get_mut().pop_front() {
+                debug!(deploy_hash = %next_hash, "DeployBuffer: deferred deploy now pending");
+                self.deferred.remove(&next_hash);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                // the account's pending count stays the same
+                return;
+            }
+            if entry.get().is_empty() {
+                entry.remove();
             }
         }
+        if was_deferred {
+            return;
+        }
+        if let hash_map::Entry::Occupied(mut entry) = self.pending_counts.entry(account.clone()) {
+            *entry.get_mut() = entry.get().saturating_sub(1);
+            if *entry.get() == 0 {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Marks a deploy as never to be proposed again.
+    fn mark_dead(&mut self, deploy_hash: DeployHash) {
+        if !self.dead.insert(deploy_hash) {
+            return;
+        }
+        let maybe_account = self
+            .buffer
+            .get(&deploy_hash)
+            .and_then(|(_, maybe_data)| maybe_data.as_ref())
+            .map(|(footprint, _)| footprint.header.account().clone());
+        if let Some(account) = maybe_account {
+            self.release_pending(&account, &deploy_hash);
+        }
     }
 
     /// Update holds considering new proposed block.
@@ -258,14 +439,7 @@ impl DeployBuffer {
                 HashSet::from_iter(proposed_block.value().deploy_and_transfer_hashes().copied()),
             );
         }
-        self.metrics.held_deploys.set(
-            self.hold
-                .values()
-                .map(|deploys| deploys.len())
-                .sum::<usize>()
-                .try_into()
-                .unwrap_or(i64::MIN),
-        );
+        self.update_all_metrics();
     }
 
     fn register_deploys<'a>(
@@ -279,7 +453,8 @@ impl DeployBuffer {
             if !self.buffer.contains_key(deploy_hash) {
                 self.buffer.insert(*deploy_hash, (expiry_timestamp, None));
             }
-            self.dead.insert(*deploy_hash);
+            self.mark_dead(*deploy_hash);
+            self.finalized.insert(*deploy_hash);
         }
         // deploys held for proposed blocks which did not get finalized in time are eligible again
         let (hold, _) = mem::take(&mut self.hold)
@@ -305,13 +480,15 @@ impl DeployBuffer {
         self.register_deploys(timestamp, finalized_block.deploy_and_transfer_hashes());
     }
 
-    /// Returns eligible deploys that are buffered and not held or dead.
+    /// Returns eligible deploys that are buffered and not held, dead or deferred, and whose
+    /// dependencies have all been finalized if the chainspec enforces dependencies.
     fn proposable(&self) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
         debug!("DeployBuffer: getting proposable deploys");
         self.buffer
             .iter()
             .filter(|(dh, _)| !self.hold.values().any(|hs| hs.contains(dh)))
             .filter(|(dh, _)| !self.dead.contains(dh))
+            .filter(|(dh, _)| !self.deferred.contains(dh))
             .filter_map(|(dh, (_, maybe_data))| {
                 maybe_data.as_ref().map(|(footprint, approvals)| {
                     (
@@ -320,9 +497,30 @@ impl DeployBuffer {
                     )
                 })
             })
+            .filter(|(_, footprint)| self.dependencies_finalized(footprint))
             .collect()
     }
 
+    /// Returns `true` if all dependencies of the deploy have been included in a finalized block,
+    /// or if no dependency activation era is configured.
+    ///
+    /// Dependencies which expired or were dropped without being finalized are unmet. Dependencies
+    /// which were finalized longer than `max_ttl` ago are no longer tracked, so a deploy depending
+    /// on them will not be proposed; validators treat those as unmet as well.
+    ///
+    /// This is applied ahead of the activation era, as honoring dependencies early never produces
+    /// a block other validators reject.
+    fn dependencies_finalized(&self, footprint: &DeployFootprint) -> bool {
+        if self.deploy_config.dependencies_activation_era.is_none() {
+            return true;
+        }
+        footprint
+            .header
+            .dependencies()
+            .iter()
+            .all(|dependency| self.finalized.contains(dependency))
+    }
+
     fn buckets(&mut self) -> HashMap<Digest, Vec<(DeployHashWithApprovals, DeployFootprint)>> {
         let proposable = self.proposable();
 
@@ -340,10 +538,22 @@ impl DeployBuffer {
     }
 
     /// Returns a right-sized payload of deploys that can be proposed.
+    #[cfg(test)]
     fn appendable_block(
         &mut self,
         timestamp: Timestamp,
         request_expiry: Timestamp,
+    ) -> AppendableBlock {
+        self.appendable_block_without(timestamp, request_expiry, &HashSet::new())
+    }
+
+    /// Returns a right-sized payload of deploys that can be proposed, skipping the `unauthorized`
+    /// ones.
+    fn appendable_block_without(
+        &mut self,
+        timestamp: Timestamp,
+        request_expiry: Timestamp,
+        unauthorized: &HashSet<DeployHash>,
     ) -> AppendableBlock {
         let mut ret = AppendableBlock::new(self.deploy_config, timestamp);
         if Timestamp::now() >= request_expiry {
@@ -352,8 +562,14 @@ impl DeployBuffer {
         let mut holds = HashSet::new();
         let mut have_hit_transfer_limit = false;
         let mut have_hit_deploy_limit = false;
+        let mut have_hit_block_limit = false;
 
         let mut buckets = self.buckets();
+        let proposable: Vec<DeployHash> = buckets
+            .values()
+            .flatten()
+            .map(|(with_approvals, _)| *with_approvals.deploy_hash())
+            .collect();
         let mut body_hashes_queue: VecDeque<_> = buckets.keys().cloned().collect();
 
         #[cfg(test)]
@@ -382,6 +598,13 @@ impl DeployBuffer {
             // bucket wasn't empty - push the hash back into the queue to be processed again on the
             // next pass
             body_hashes_queue.push_back(body_hash);
+            if unauthorized.contains(with_approvals.deploy_hash()) {
+                debug!(
+                    deploy_hash = %with_approvals.deploy_hash(),
+                    "DeployBuffer: insufficient deploy approvals, not proposing deploy"
+                );
+                continue;
+            }
             if footprint.is_transfer && have_hit_transfer_limit {
                 continue;
             }
@@ -404,14 +627,15 @@ impl DeployBuffer {
                                 ?deploy_hash,
                                 "DeployBuffer: duplicated deploy in deploy buffer"
                             );
-                            self.dead.insert(deploy_hash);
+                            self.mark_dead(deploy_hash);
                         }
                         AddError::Expired => {
                             info!(
                                 ?deploy_hash,
                                 "DeployBuffer: expired deploy in deploy buffer"
                             );
-                            self.dead.insert(deploy_hash);
+                            self.mark_dead(deploy_hash);
+                            self.metrics.expired_deploys.inc();
                         }
                         AddError::InvalidDeploy => {
                             // It should not generally be possible for an invalid deploy to get
@@ -423,9 +647,10 @@ impl DeployBuffer {
                                 ?deploy_hash,
                                 "DeployBuffer: invalid deploy in deploy buffer"
                             );
-                            self.dead.insert(deploy_hash);
+                            self.mark_dead(deploy_hash);
                         }
                         AddError::TransferCount => {
+                            have_hit_block_limit = true;
                             if have_hit_deploy_limit {
                                 info!(
                                     ?deploy_hash,
@@ -436,6 +661,7 @@ impl DeployBuffer {
                             have_hit_transfer_limit = true;
                         }
                         AddError::DeployCount => {
+                            have_hit_block_limit = true;
                             if have_hit_transfer_limit {
                                 info!(
                                     ?deploy_hash,
@@ -447,6 +673,7 @@ impl DeployBuffer {
                         }
                         AddError::ApprovalCount if has_multiple_approvals => {
                             // keep iterating, we can maybe fit in a deploy with fewer approvals
+                            have_hit_block_limit = true;
                         }
                         AddError::ApprovalCount | AddError::GasLimit | AddError::BlockSize => {
                             info!(
@@ -455,6 +682,7 @@ impl DeployBuffer {
                                 "DeployBuffer: a block limit has been reached"
                             );
                             // a block limit has been reached
+                            have_hit_block_limit = true;
                             break;
                         }
                     }
@@ -463,6 +691,22 @@ impl DeployBuffer {
         }
 
         // put a hold on all proposed deploys / transfers and update metrics
+        self.metrics
+            .deploys_per_proposed_block
+            .observe(holds.len() as f64);
+        if have_hit_block_limit {
+            let mut left_out = 0;
+            for deploy_hash in proposable {
+                if !holds.contains(&deploy_hash)
+                    && !unauthorized.contains(&deploy_hash)
+                    && !self.dead.contains(&deploy_hash)
+                    && self.exceeding_block_limits.insert(deploy_hash)
+                {
+                    left_out += 1;
+                }
+            }
+            self.metrics.deploys_exceeding_block_limits.inc_by(left_out);
+        }
         match self.hold.entry(timestamp) {
             btree_map::Entry::Vacant(entry) => {
                 entry.insert(holds);
@@ -487,30 +731,85 @@ impl DeployBuffer {
         ret
     }
 
+    /// Returns the number of deploys in each of the internal structs.
+    fn status(&self) -> DeployBufferStatus {
+        let held: HashSet<&DeployHash> = self.hold.values().flatten().collect();
+        let pending = self
+            .buffer
+            .keys()
+            .filter(|deploy_hash| !held.contains(*deploy_hash) && !self.dead.contains(*deploy_hash))
+            .count();
+        DeployBufferStatus {
+            total: self.buffer.len(),
+            pending,
+            held: self.hold.values().map(|deploys| deploys.len()).sum(),
+            dead: self.dead.len(),
+        }
+    }
+
+    /// Returns the hashes of all deploys which are not dead, i.e. which have neither been included
+    /// in a finalized block nor expired.  Held deploys are included, as their proposed blocks may
+    /// not be finalized.
+    fn pending_deploy_hashes(&self) -> Vec<DeployHash> {
+        self.buffer
+            .keys()
+            .filter(|deploy_hash| !self.dead.contains(*deploy_hash))
+            .copied()
+            .collect()
+    }
+
+    /// Removes all deploys which are neither held by a proposed block nor dead, returning the
+    /// number of removed deploys.
+    fn clear_pending(&mut self) -> usize {
+        let held: HashSet<DeployHash> = self.hold.values().flatten().copied().collect();
+        let dead = &self.dead;
+        let count_before = self.buffer.len();
+        self.buffer
+            .retain(|deploy_hash, _| held.contains(deploy_hash) || dead.contains(deploy_hash));
+        // the remaining live deploys are all held, and are counted as pending
+        self.deferred.clear();
+        self.deferred_by_account.clear();
+        self.pending_counts.clear();
+        for (deploy_hash, (_, maybe_data)) in &self.buffer {
+            if let (false, Some((footprint, _))) = (self.dead.contains(deploy_hash), maybe_data) {
+                *self
+                    .pending_counts
+                    .entry(footprint.header.account().clone())
+                    .or_default() += 1;
+            }
+        }
+        self.update_all_metrics();
+        count_before - self.buffer.len()
+    }
+
     /// Updates all deploy count metrics based on the size of the internal structs.
     fn update_all_metrics(&mut self) {
+        let status = self.status();
         // if number of elements is too high to fit, we overflow the metric
         // intentionally in order to get some indication that something is wrong.
-        self.metrics.held_deploys.set(
-            self.hold
-                .values()
-                .map(|deploys| deploys.len())
-                .sum::<usize>()
-                .try_into()
-                .unwrap_or(i64::MIN),
-        );
+        self.metrics
+            .held_deploys
+            .set(status.held.try_into().unwrap_or(i64::MIN));
         self.metrics
             .dead_deploys
-            .set(self.dead.len().try_into().unwrap_or(i64::MIN));
+            .set(status.dead.try_into().unwrap_or(i64::MIN));
         self.metrics
             .total_deploys
-            .set(self.buffer.len().try_into().unwrap_or(i64::MIN));
+            .set(status.total.try_into().unwrap_or(i64::MIN));
+        self.metrics
+            .pending_deploys
+            .set(status.pending.try_into().unwrap_or(i64::MIN));
     }
 }
 
 impl<REv> InitializedComponent<REv> for DeployBuffer
 where
-    REv: From<Event> + From<DeployBufferAnnouncement> + From<StorageRequest> + Send + 'static,
+    REv: From<Event>
+        + From<DeployBufferAnnouncement>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + Send
+        + 'static,
 {
     fn state(&self) -> &ComponentState {
         &self.state
@@ -529,7 +828,12 @@ where
 
 impl<REv> Component<REv> for DeployBuffer
 where
-    REv: From<Event> + From<DeployBufferAnnouncement> + From<StorageRequest> + Send + 'static,
+    REv: From<Event>
+        + From<DeployBufferAnnouncement>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + Send
+        + 'static,
 {
     type Event = Event;
 
@@ -575,6 +879,7 @@ where
                     Event::Request(_)
                     | Event::ReceiveDeployGossiped(_)
                     | Event::StoredDeploy(_, _)
+                    | Event::CheckedDeployAuthorizations { .. }
                     | Event::BlockProposed(_)
                     | Event::Block(_)
                     | Event::BlockFinalized(_)
@@ -601,9 +906,23 @@ where
                     timestamp,
                     request_expiry,
                     responder,
-                }) => responder
-                    .respond(self.appendable_block(timestamp, request_expiry))
-                    .ignore(),
+                }) => self.request_appendable_block(
+                    timestamp,
+                    request_expiry,
+                    responder,
+                    effect_builder,
+                ),
+                Event::Request(DeployBufferRequest::GetStatus { responder }) => {
+                    responder.respond(self.status()).ignore()
+                }
+                Event::Request(DeployBufferRequest::GetPendingDeployHashes { responder }) => {
+                    responder.respond(self.pending_deploy_hashes()).ignore()
+                }
+                Event::Request(DeployBufferRequest::ClearPending { responder }) => {
+                    let removed = self.clear_pending();
+                    info!(removed, "cleared pending deploys from deploy buffer");
+                    responder.respond(removed).ignore()
+                }
                 Event::BlockFinalized(finalized_block) => {
                     self.register_block_finalized(&finalized_block);
                     Effects::new()
@@ -630,6 +949,18 @@ where
                     }
                     Effects::new()
                 }
+                Event::CheckedDeployAuthorizations {
+                    timestamp,
+                    request_expiry,
+                    unauthorized,
+                    responder,
+                } => responder
+                    .respond(self.appendable_block_without(
+                        timestamp,
+                        request_expiry,
+                        &unauthorized,
+                    ))
+                    .ignore(),
                 Event::Expire => self.expire(effect_builder),
             },
         }
@@ -4,12 +4,22 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_EXPIRY_CHECK_INTERVAL: &str = "1min";
+const DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT: u32 = 100;
 
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The interval of checking for expired deploys.
     pub expiry_check_interval: TimeDiff,
+    /// The maximum number of pending deploys proposable per account.  Further deploys from the
+    /// same account are kept in the buffer, but not proposed until some of its pending deploys are
+    /// included in a block or expire.
+    #[serde(default = "default_max_pending_deploys_per_account")]
+    pub max_pending_deploys_per_account: u32,
+}
+
+fn default_max_pending_deploys_per_account() -> u32 {
+    DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT
 }
 
 impl Config {
@@ -23,6 +33,7 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             expiry_check_interval: DEFAULT_EXPIRY_CHECK_INTERVAL.parse().unwrap(),
+            max_pending_deploys_per_account: DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT,
         }
     }
 }
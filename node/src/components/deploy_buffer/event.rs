@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     fmt::{self, Display, Formatter},
     sync::Arc,
 };
@@ -6,10 +7,14 @@ use std::{
 use datasize::DataSize;
 use derive_more::From;
 
+use casper_types::Timestamp;
+
 use crate::{
     components::consensus::{ClContext, ProposedBlock},
-    effect::requests::DeployBufferRequest,
-    types::{Block, Deploy, DeployId, FinalizedBlock},
+    effect::{requests::DeployBufferRequest, Responder},
+    types::{
+        appendable_block::AppendableBlock, Block, Deploy, DeployHash, DeployId, FinalizedBlock,
+    },
 };
 
 #[derive(Debug, From, DataSize)]
@@ -19,6 +24,12 @@ pub(crate) enum Event {
     Request(DeployBufferRequest),
     ReceiveDeployGossiped(DeployId),
     StoredDeploy(DeployId, Option<Box<Deploy>>),
+    CheckedDeployAuthorizations {
+        timestamp: Timestamp,
+        request_expiry: Timestamp,
+        unauthorized: HashSet<DeployHash>,
+        responder: Responder<AppendableBlock>,
+    },
     BlockProposed(Box<ProposedBlock<ClContext>>),
     Block(Arc<Block>),
     BlockFinalized(Box<FinalizedBlock>),
@@ -34,6 +45,15 @@ impl Display for Event {
             Event::Request(DeployBufferRequest::GetAppendableBlock { .. }) => {
                 write!(formatter, "get appendable block request")
             }
+            Event::Request(DeployBufferRequest::GetStatus { .. }) => {
+                write!(formatter, "get status request")
+            }
+            Event::Request(DeployBufferRequest::GetPendingDeployHashes { .. }) => {
+                write!(formatter, "get pending deploy hashes request")
+            }
+            Event::Request(DeployBufferRequest::ClearPending { .. }) => {
+                write!(formatter, "clear pending request")
+            }
             Event::ReceiveDeployGossiped(deploy_id) => {
                 write!(formatter, "receive deploy gossiped {}", deploy_id)
             }
@@ -45,6 +65,13 @@ impl Display for Event {
                     maybe_deploy.is_some()
                 )
             }
+            Event::CheckedDeployAuthorizations { unauthorized, .. } => {
+                write!(
+                    formatter,
+                    "checked deploy authorizations, {} unauthorized",
+                    unauthorized.len()
+                )
+            }
             Event::BlockProposed(_) => {
                 write!(formatter, "proposed block")
             }
@@ -1,6 +1,15 @@
-use prometheus::{IntGauge, Registry};
+use prometheus::{Histogram, IntCounter, IntGauge, Registry};
 
-use crate::unregister_metric;
+use crate::{unregister_metric, utils};
+
+/// Value of upper bound of the first bucket of the deploys per proposed block histogram.
+const DEPLOYS_PER_BLOCK_BUCKET_START: f64 = 1.0;
+
+/// Multiplier of previous upper bound for next bound.
+const DEPLOYS_PER_BLOCK_BUCKET_FACTOR: f64 = 2.0;
+
+/// Bucket count, with the last bucket going to +Inf which will not be included in the results.
+const DEPLOYS_PER_BLOCK_BUCKET_COUNT: usize = 12;
 
 /// Metrics for the deploy_buffer component.
 #[derive(Debug)]
@@ -11,6 +20,17 @@ pub(super) struct Metrics {
     pub(super) held_deploys: IntGauge,
     /// Number of deploys that should not be included in future proposals ever again.
     pub(super) dead_deploys: IntGauge,
+    /// Number of deploys which are neither held nor dead, i.e. eligible for proposal.
+    pub(super) pending_deploys: IntGauge,
+    /// Number of deploys included in each block proposed by this node.
+    pub(super) deploys_per_proposed_block: Histogram,
+    /// Number of deploys dropped from the buffer because they expired before being finalized.
+    pub(super) expired_deploys: IntCounter,
+    /// Number of distinct proposable deploys left out of a proposed block because a block limit
+    /// was hit.
+    pub(super) deploys_exceeding_block_limits: IntCounter,
+    /// Number of deploys deferred because their account reached the pending deploy limit.
+    pub(super) deploys_exceeding_account_limit: IntCounter,
     registry: Registry,
 }
 
@@ -29,15 +49,52 @@ impl Metrics {
             "deploy_buffer_dead_deploys".to_string(),
             "number of deploys that should not be included in future proposals.".to_string(),
         )?;
+        let pending_deploys = IntGauge::new(
+            "deploy_buffer_pending_deploys".to_string(),
+            "number of deploys eligible for inclusion in future proposals.".to_string(),
+        )?;
+        let expired_deploys = IntCounter::new(
+            "deploy_buffer_expired_deploys".to_string(),
+            "number of deploys dropped from the deploy buffer due to expiry.".to_string(),
+        )?;
+        let deploys_exceeding_block_limits = IntCounter::new(
+            "deploy_buffer_deploys_exceeding_block_limits".to_string(),
+            "number of distinct proposable deploys left out of a proposed block due to block limits."
+                .to_string(),
+        )?;
+        let deploys_exceeding_account_limit = IntCounter::new(
+            "deploy_buffer_deploys_exceeding_account_limit".to_string(),
+            "number of deploys deferred due to the per-account pending deploy limit.".to_string(),
+        )?;
 
         registry.register(Box::new(total_deploys.clone()))?;
         registry.register(Box::new(held_deploys.clone()))?;
         registry.register(Box::new(dead_deploys.clone()))?;
+        registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(expired_deploys.clone()))?;
+        registry.register(Box::new(deploys_exceeding_block_limits.clone()))?;
+        registry.register(Box::new(deploys_exceeding_account_limit.clone()))?;
+
+        let deploys_per_proposed_block = utils::register_histogram_metric(
+            registry,
+            "deploy_buffer_deploys_per_proposed_block",
+            "number of deploys and transfers included in each block proposed by this node.",
+            prometheus::exponential_buckets(
+                DEPLOYS_PER_BLOCK_BUCKET_START,
+                DEPLOYS_PER_BLOCK_BUCKET_FACTOR,
+                DEPLOYS_PER_BLOCK_BUCKET_COUNT,
+            )?,
+        )?;
 
         Ok(Metrics {
             total_deploys,
             held_deploys,
             dead_deploys,
+            pending_deploys,
+            deploys_per_proposed_block,
+            expired_deploys,
+            deploys_exceeding_block_limits,
+            deploys_exceeding_account_limit,
             registry: registry.clone(),
         })
     }
@@ -48,5 +105,10 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.total_deploys);
         unregister_metric!(self.registry, self.held_deploys);
         unregister_metric!(self.registry, self.dead_deploys);
+        unregister_metric!(self.registry, self.pending_deploys);
+        unregister_metric!(self.registry, self.deploys_per_proposed_block);
+        unregister_metric!(self.registry, self.expired_deploys);
+        unregister_metric!(self.registry, self.deploys_exceeding_block_limits);
+        unregister_metric!(self.registry, self.deploys_exceeding_account_limit);
     }
 }
@@ -120,13 +120,42 @@ fn assert_container_sizes(
         deploy_buffer.metrics.dead_deploys.get(),
         expected_dead,
     );
+    let held: HashSet<&DeployHash> = deploy_buffer.hold.values().flatten().collect();
+    let expected_pending = deploy_buffer
+        .buffer
+        .keys()
+        .filter(|deploy_hash| {
+            !held.contains(*deploy_hash) && !deploy_buffer.dead.contains(*deploy_hash)
+        })
+        .count();
+    assert_eq!(
+        deploy_buffer.metrics.pending_deploys.get(),
+        expected_pending as i64,
+        "metrics pending {} != expected {}",
+        deploy_buffer.metrics.pending_deploys.get(),
+        expected_pending,
+    );
+    assert_eq!(
+        deploy_buffer.status(),
+        DeployBufferStatus {
+            total: expected_buffer,
+            pending: expected_pending,
+            held: expected_held,
+            dead: expected_dead,
+        }
+    );
 }
 
 #[test]
 fn register_deploy_and_check_size() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     // Try to register valid deploys
     let num_valid_deploys: usize = rng.gen_range(50..500);
@@ -154,8 +183,13 @@ fn register_deploy_and_check_size() {
 #[test]
 fn register_block_with_valid_deploys() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     let deploys = create_valid_deploys(&mut rng, 10, DeployType::Random, None, None);
     let block = Block::random_with_deploys(&mut rng, deploys.iter());
@@ -167,8 +201,13 @@ fn register_block_with_valid_deploys() {
 #[test]
 fn register_finalized_block_with_valid_deploys() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     let deploys = create_valid_deploys(&mut rng, 10, DeployType::Random, None, None);
     let block = FinalizedBlock::random_with_deploys(&mut rng, deploys.iter());
@@ -180,8 +219,13 @@ fn register_finalized_block_with_valid_deploys() {
 #[test]
 fn get_proposable_deploys() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     // populate deploy buffer with some deploys
     let deploys = create_valid_deploys(&mut rng, 50, DeployType::Random, None, None);
@@ -244,8 +288,13 @@ fn get_appendable_block_with_native_transfers() {
         block_max_approval_count: 210,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
     get_appendable_block(
         &mut rng,
         &mut deploy_buffer,
@@ -263,8 +312,13 @@ fn get_appendable_block_with_standard_deploys() {
         block_max_approval_count: 210,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
     get_appendable_block(
         &mut rng,
         &mut deploy_buffer,
@@ -282,8 +336,13 @@ fn get_appendable_block_with_random_deploys() {
         block_max_approval_count: 210,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
     get_appendable_block(
         &mut rng,
         &mut deploy_buffer,
@@ -308,21 +367,51 @@ fn get_appendable_block(
     let timestamp = Timestamp::now();
     let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
     let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
-    assert!(appendable_block.deploy_and_transfer_set().len() <= deploy_limit,);
+    let proposed_count = appendable_block.deploy_and_transfer_set().len();
+    assert!(proposed_count <= deploy_limit,);
     assert_eq!(deploy_buffer.hold.len(), 1);
-    assert_container_sizes(
-        deploy_buffer,
-        deploys.len(),
-        0,
-        appendable_block.deploy_and_transfer_set().len(),
+    assert_container_sizes(deploy_buffer, deploys.len(), 0, proposed_count);
+
+    // the proposal is recorded, and all deploys which didn't fit are counted as left out
+    let deploys_per_proposed_block = &deploy_buffer.metrics.deploys_per_proposed_block;
+    assert_eq!(deploys_per_proposed_block.get_sample_count(), 1);
+    assert_eq!(
+        deploys_per_proposed_block.get_sample_sum(),
+        proposed_count as f64
+    );
+    assert_eq!(
+        deploy_buffer.metrics.deploys_exceeding_block_limits.get(),
+        (deploys.len() - proposed_count) as u64
+    );
+
+    // once the hold is released, proposing again only counts deploys not left out before
+    let first_proposed = appendable_block.deploy_and_transfer_set().clone();
+    deploy_buffer.hold.clear();
+    let timestamp = timestamp.saturating_add(TimeDiff::from_millis(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    let second_proposed = appendable_block.deploy_and_transfer_set();
+    let ever_left_out = deploys
+        .iter()
+        .filter(|deploy| {
+            !first_proposed.contains(deploy.hash()) || !second_proposed.contains(deploy.hash())
+        })
+        .count();
+    assert_eq!(
+        deploy_buffer.metrics.deploys_exceeding_block_limits.get(),
+        ever_left_out as u64
     );
 }
 
 #[test]
 fn register_deploys_and_blocks() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     // try to register valid deploys
     let num_valid_deploys: usize = rng.gen_range(50..500);
@@ -406,6 +495,167 @@ fn register_deploys_and_blocks() {
     );
 }
 
+#[test]
+fn clear_pending_keeps_held_and_dead_deploys() {
+    let mut rng = TestRng::new();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let pending_deploys = create_valid_deploys(&mut rng, 20, DeployType::Random, None, None);
+    pending_deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    let block_deploys = create_valid_deploys(&mut rng, 5, DeployType::Random, None, None);
+    let block = Block::random_with_deploys(&mut rng, block_deploys.iter());
+    deploy_buffer.register_block(&block);
+
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    let held = appendable_block.deploy_and_transfer_set().len();
+    assert!(held > 0);
+
+    let removed = deploy_buffer.clear_pending();
+    assert_eq!(removed, pending_deploys.len() - held);
+    assert_container_sizes(
+        &deploy_buffer,
+        block_deploys.len() + held,
+        block_deploys.len(),
+        held,
+    );
+}
+
+#[test]
+fn should_only_propose_deploys_with_finalized_dependencies() {
+    let mut rng = TestRng::new();
+    let deploy_config = DeployConfig {
+        dependencies_activation_era: Some(EraId::new(0)),
+        ..Default::default()
+    };
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let dependency = Deploy::random_valid_native_transfer_without_deps(&mut rng);
+    let template = Deploy::random_valid_native_transfer(&mut rng);
+    let dependent = Deploy::new(
+        template.header().timestamp(),
+        template.header().ttl(),
+        template.header().gas_price(),
+        vec![*dependency.hash()],
+        template.header().chain_name().to_string(),
+        template.payment().clone(),
+        template.session().clone(),
+        &SecretKey::random(&mut rng),
+        None,
+    );
+    deploy_buffer.register_deploy(dependency.clone());
+    deploy_buffer.register_deploy(dependent.clone());
+
+    // the dependent deploy is held back until its dependency is finalized
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    let proposed = appendable_block.deploy_and_transfer_set();
+    assert!(proposed.contains(dependency.hash()));
+    assert!(!proposed.contains(dependent.hash()));
+
+    let block = Block::random_with_deploys(&mut rng, iter::once(&dependency));
+    deploy_buffer.register_block(&block);
+
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    assert!(appendable_block
+        .deploy_and_transfer_set()
+        .contains(dependent.hash()));
+}
+
+#[test]
+fn should_not_treat_dropped_dependencies_as_finalized() {
+    let mut rng = TestRng::new();
+    let deploy_config = DeployConfig {
+        dependencies_activation_era: Some(EraId::new(0)),
+        ..Default::default()
+    };
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let dependency = Deploy::random_valid_native_transfer(&mut rng);
+    let template = Deploy::random_valid_native_transfer(&mut rng);
+    let dependent = Deploy::new(
+        template.header().timestamp(),
+        template.header().ttl(),
+        template.header().gas_price(),
+        vec![*dependency.hash()],
+        template.header().chain_name().to_string(),
+        template.payment().clone(),
+        template.session().clone(),
+        &SecretKey::random(&mut rng),
+        None,
+    );
+    deploy_buffer.register_deploy(dependency.clone());
+    deploy_buffer.register_deploy(dependent.clone());
+
+    // the dependency is dropped without ever being finalized, e.g. because it was invalid
+    deploy_buffer.dead.insert(*dependency.hash());
+
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    assert!(appendable_block.deploy_and_transfer_set().is_empty());
+}
+
+#[test]
+fn should_skip_but_keep_unauthorized_deploys() {
+    let mut rng = TestRng::new();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let deploys = create_valid_deploys(&mut rng, 10, DeployType::Transfer, None, None);
+    deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+    let unauthorized: HashSet<_> = deploys
+        .iter()
+        .take(3)
+        .map(|deploy| *deploy.hash())
+        .collect();
+
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block_without(timestamp, expiry, &unauthorized);
+    let proposed = appendable_block.deploy_and_transfer_set();
+    assert_eq!(proposed.len(), deploys.len() - unauthorized.len());
+    assert!(proposed.is_disjoint(&unauthorized));
+
+    // unauthorized deploys are neither held nor dropped, so they can be proposed once authorized
+    assert_container_sizes(&deploy_buffer, deploys.len(), 0, proposed.len());
+    let timestamp = Timestamp::now();
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    assert_eq!(appendable_block.deploy_and_transfer_set(), &unauthorized);
+}
+
 #[test]
 fn should_have_one_bucket_per_distinct_body_hash() {
     let mut rng = TestRng::new();
@@ -417,8 +667,13 @@ fn should_have_one_bucket_per_distinct_body_hash() {
         block_max_approval_count: max_deploy_count + max_transfer_count,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     let secret_key1 = SecretKey::random(&mut rng);
     let ttl = TimeDiff::from_seconds(30);
@@ -521,8 +776,13 @@ fn should_be_empty_if_no_time_until_expiry() {
         block_max_approval_count: max_deploy_count + max_transfer_count,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     let secret_key1 = SecretKey::random(&mut rng);
     let ttl = TimeDiff::from_seconds(30);
@@ -577,10 +837,14 @@ fn should_have_diverse_proposable_blocks_with_stocked_buffer() {
         block_max_approval_count: max_deploy_count + max_transfer_count,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
-
     let cap = (max_deploy_count * 100) as usize;
+    // all deploys share a few accounts, so lift the per-account limit to keep them all proposable
+    let config = Config {
+        max_pending_deploys_per_account: cap as u32,
+        ..Config::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, BTreeSet::new(), config, &Registry::new()).unwrap();
 
     let secret_keys: Vec<SecretKey> = iter::repeat_with(|| SecretKey::random(rng))
         .take(10)
@@ -723,8 +987,13 @@ impl MockReactor {
 #[tokio::test]
 async fn expire_deploys_and_check_announcement() {
     let mut rng = TestRng::new();
-    let mut deploy_buffer =
-        DeployBuffer::new(DeployConfig::default(), Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     let reactor = MockReactor::new();
     let event_queue_handle = EventQueueHandle::without_shutdown(reactor.scheduler);
@@ -775,6 +1044,10 @@ async fn expire_deploys_and_check_announcement() {
     reactor
         .expect_deploy_buffer_expire_announcement(&expired_deploy_hashes)
         .await;
+    assert_eq!(
+        deploy_buffer.metrics.expired_deploys.get(),
+        expired_deploy_hashes.len() as u64
+    );
 
     // the valid deploys should still be in the buffer
     assert_container_sizes(&deploy_buffer, deploys.len(), 0, 0);
@@ -835,8 +1108,13 @@ fn test_buckets_single_hash() {
         block_max_approval_count: 1100,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     register_random_deploys_same_hash(&mut deploy_buffer, 64000, &mut rng);
 
@@ -855,8 +1133,13 @@ fn test_buckets_unique_hashes() {
         block_max_approval_count: 1100,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     register_random_deploys_unique_hashes(&mut deploy_buffer, 64000, &mut rng);
 
@@ -875,8 +1158,13 @@ fn test_buckets_mixed_load() {
         block_max_approval_count: 1100,
         ..Default::default()
     };
-    let mut deploy_buffer =
-        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    let mut deploy_buffer = DeployBuffer::new(
+        deploy_config,
+        BTreeSet::new(),
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
 
     register_random_deploys_unique_hashes(&mut deploy_buffer, 60000, &mut rng);
     register_random_deploys_same_hash(&mut deploy_buffer, 4000, &mut rng);
@@ -886,3 +1174,70 @@ fn test_buckets_mixed_load() {
         Timestamp::now() + TimeDiff::from_millis(16384 / 6),
     );
 }
+
+#[test]
+fn should_limit_pending_deploys_per_account() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        max_pending_deploys_per_account: 2,
+        ..Config::default()
+    };
+    let mut deploy_buffer = DeployBuffer::new(
+        DeployConfig::default(),
+        BTreeSet::new(),
+        config,
+        &Registry::new(),
+    )
+    .unwrap();
+
+    let secret_key = SecretKey::random(&mut rng);
+    let deploys: Vec<_> = (0..3)
+        .map(|_| {
+            let template = Deploy::random_valid_native_transfer(&mut rng);
+            Deploy::new(
+                template.header().timestamp(),
+                template.header().ttl(),
+                template.header().gas_price(),
+                vec![],
+                template.header().chain_name().to_string(),
+                template.payment().clone(),
+                template.session().clone(),
+                &secret_key,
+                None,
+            )
+        })
+        .collect();
+    for deploy in &deploys {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+    let proposable_hashes = |deploy_buffer: &DeployBuffer| -> HashSet<DeployHash> {
+        deploy_buffer
+            .proposable()
+            .into_iter()
+            .map(|(deploy_hash_with_approvals, _)| *deploy_hash_with_approvals.deploy_hash())
+            .collect()
+    };
+
+    // the third deploy from the same account exceeds the limit: it is buffered, but deferred
+    assert_eq!(deploy_buffer.buffer.len(), 3);
+    let proposable = proposable_hashes(&deploy_buffer);
+    assert_eq!(proposable.len(), 2);
+    assert!(!proposable.contains(deploys[2].hash()));
+    assert_eq!(
+        deploy_buffer.metrics.deploys_exceeding_account_limit.get(),
+        1
+    );
+
+    // deploys from other accounts are unaffected
+    let other_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    deploy_buffer.register_deploy(other_deploy.clone());
+    assert!(proposable_hashes(&deploy_buffer).contains(other_deploy.hash()));
+
+    // once one of the account's deploys is finalized, the deferred deploy becomes proposable
+    let block = Block::random_with_deploys(&mut rng, iter::once(&deploys[0]));
+    deploy_buffer.register_block(&block);
+    let proposable = proposable_hashes(&deploy_buffer);
+    assert!(proposable.contains(deploys[1].hash()));
+    assert!(proposable.contains(deploys[2].hash()));
+    assert!(!proposable.contains(deploys[0].hash()));
+}
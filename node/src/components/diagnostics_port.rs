@@ -11,6 +11,7 @@ mod util;
 use std::{
     fmt::{self, Display, Formatter},
     fs, io,
+    os::unix::fs::PermissionsExt,
     path::{Path, PathBuf},
 };
 
@@ -23,9 +24,12 @@ use tracing::{debug, error, info, warn};
 use crate::{
     components::{Component, ComponentState, InitializedComponent, PortBoundComponent},
     effect::{
-        announcements::ControlAnnouncement,
+        announcements::{ControlAnnouncement, PeerBehaviorAnnouncement},
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{
+            BlockSynchronizerRequest, ContractRuntimeRequest, DeployBufferRequest, MetricsRequest,
+            NetworkInfoRequest, ReactorStatusRequest, SetNodeStopRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     reactor::main_reactor::MainEvent,
@@ -39,6 +43,10 @@ use util::ShowUnixAddr;
 
 const COMPONENT_NAME: &str = "diagnostics_port";
 
+/// Permissions of the socket file: as the diagnostics port grants administrative access to the
+/// node, only the user the node runs as may connect, regardless of the configured umask.
+const SOCKET_MODE: u32 = 0o600;
+
 /// Diagnostics port configuration.
 #[derive(Clone, DataSize, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -46,7 +54,8 @@ pub struct Config {
     pub enabled: bool,
     /// Path to listen on.
     pub socket_path: PathBuf,
-    /// `umask` to apply before creating the socket.
+    /// `umask` to apply while creating the socket.  Once created, the socket's permissions are
+    /// restricted to the node's user.
     pub socket_umask: u16,
 }
 
@@ -108,6 +117,13 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<ReactorStatusRequest>
+        + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<MetricsRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<PeerBehaviorAnnouncement>
         + Send,
 {
     type Event = Event;
@@ -162,6 +178,13 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<ReactorStatusRequest>
+        + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<MetricsRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<PeerBehaviorAnnouncement>
         + Send,
 {
     fn state(&self) -> &ComponentState {
@@ -186,6 +209,13 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<ReactorStatusRequest>
+        + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<MetricsRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<PeerBehaviorAnnouncement>
         + Send,
 {
     type Error = Error;
@@ -208,6 +238,7 @@ where
             #[allow(clippy::useless_conversion)]
             cfg.socket_umask.into(),
         )?;
+        restrict_to_owner(&socket_path)?;
         let server = tasks::server(effect_builder, socket_path, listener, shutdown_receiver);
         Ok(server.ignore())
     }
@@ -217,7 +248,10 @@ where
 ///
 /// If the socket already exists, an attempt to delete it is made. Errors during deletion are
 /// ignored, but may cause the subsequent socket opening to fail.
-fn setup_listener<P: AsRef<Path>>(path: P, socket_umask: umask::Mode) -> io::Result<UnixListener> {
+pub(crate) fn setup_listener<P: AsRef<Path>>(
+    path: P,
+    socket_umask: umask::Mode,
+) -> io::Result<UnixListener> {
     let socket_path = path.as_ref();
 
     // This would be racy, but no one is racing us for the socket, so we'll just do a naive
@@ -243,11 +277,16 @@ fn setup_listener<P: AsRef<Path>>(path: P, socket_umask: umask::Mode) -> io::Res
     let listener = UnixListener::bind(socket_path)?;
     drop(umask_guard);
 
-    debug!(local_addr=%ShowUnixAddr(&listener.local_addr()?), "diagnostics port listening");
+    debug!(local_addr=%ShowUnixAddr(&listener.local_addr()?), "unix socket listening");
 
     Ok(listener)
 }
 
+/// Restricts access to the socket at the given path to the user the node runs as.
+fn restrict_to_owner(socket_path: &Path) -> io::Result<()> {
+    fs::set_permissions(socket_path, fs::Permissions::from_mode(SOCKET_MODE))
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -260,7 +299,7 @@ mod tests {
         net::UnixStream,
     };
 
-    use super::setup_listener;
+    use super::{restrict_to_owner, setup_listener, SOCKET_MODE};
 
     #[tokio::test]
     async fn setup_listener_creates_listener() {
@@ -323,4 +362,17 @@ mod tests {
             "did not overwrite previous file"
         );
     }
+
+    #[tokio::test]
+    async fn should_restrict_socket_to_owner_regardless_of_umask() {
+        let tmpdir = tempfile::tempdir().expect("could not create tempdir");
+        let socket_path = tmpdir.path().join("test.socket");
+
+        // A relaxed umask leaves the socket accessible to group and others.
+        let _listener = setup_listener(&socket_path, 0o002).expect("could not setup listener");
+        restrict_to_owner(&socket_path).expect("could not set permissions");
+
+        let meta = fs::metadata(&socket_path).expect("could not get metadata");
+        assert_eq!(meta.permissions().mode() & 0o777, SOCKET_MODE);
+    }
 }
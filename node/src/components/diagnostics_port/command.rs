@@ -87,10 +87,31 @@ pub(super) enum Action {
         /// Era to dump. If omitted, dumps the latest era.
         era: Option<u64>,
     },
+    /// Dump a summary of the consensus protocol state as JSON: the panorama, the number of units
+    /// known per validator, the latest round and the dependencies still being waited for.
+    DumpConsensusSummary {
+        /// Era to summarize. If omitted, summarizes the latest era.
+        era: Option<u64>,
+    },
     /// Dump the event queues.
     DumpQueues,
-    /// Get detailed networking insights.
+    /// Dump the reactor state and the progress of block synchronization.
+    DumpSync,
+    /// Dump the number of deploys held by the deploy buffer.
+    DumpDeployBuffer,
+    /// Get detailed networking insights, including the table of outgoing connections.
     NetInfo,
+    /// Disconnect from a peer and block it for the configured blocklist duration.
+    DropPeer {
+        /// The peer's node ID as shown by `net-info`, or a unique prefix of it.
+        peer: String,
+    },
+    /// Remove all deploys from the deploy buffer which have not been proposed yet.
+    ClearDeployBuffer,
+    /// Flush all buffered writes of storage and global state to disk.
+    SyncStorage,
+    /// Show the current metrics, in the same format as the metrics endpoint.
+    Metrics,
     /// Stop the node at a certain condition.
     Stop {
         /// When to stop the node.
@@ -160,7 +181,22 @@ mod tests {
         let cmd = Command::from_line("dump-consensus 123").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpConsensus { era } if era == Some(123)));
 
+        let cmd = Command::from_line("dump-consensus-summary").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::DumpConsensusSummary { era: None }
+        ));
+
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("dump-sync").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DumpSync));
+
+        let cmd = Command::from_line("dump-deploy-buffer").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DumpDeployBuffer));
+
+        let cmd = Command::from_line("drop-peer tls:4e9a").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DropPeer { ref peer } if peer == "tls:4e9a"));
     }
 }
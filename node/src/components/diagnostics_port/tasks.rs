@@ -22,7 +22,7 @@ use tokio::{
 };
 use tracing::{debug, info, info_span, warn, Instrument};
 
-use casper_types::EraId;
+use casper_types::{EraId, Timestamp};
 use tracing_subscriber::{filter::ParseError, EnvFilter};
 
 use super::{
@@ -30,18 +30,64 @@ use super::{
     util::ShowUnixAddr,
 };
 use crate::{
-    components::consensus::EraDump,
+    components::{
+        block_synchronizer::BlockSynchronizerStatus, consensus::EraDump,
+        network::blocklist::BlocklistJustification,
+    },
     effect::{
-        announcements::{ControlAnnouncement, QueueDumpFormat},
+        announcements::{ControlAnnouncement, PeerBehaviorAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{
+            BlockSynchronizerRequest, ContractRuntimeRequest, DeployBufferRequest, MetricsRequest,
+            NetworkInfoRequest, ReactorStatusRequest, SetNodeStopRequest, StorageRequest,
+        },
         EffectBuilder,
     },
     failpoints::FailpointActivation,
     logging,
+    reactor::main_reactor::ReactorState,
+    types::NodeId,
     utils::{display_error, opt_display::OptDisplay},
 };
 
+/// Synchronization progress of the node.
+#[derive(Debug, Serialize)]
+struct SyncStatus {
+    /// The state of the main reactor.
+    reactor_state: ReactorState,
+    /// The last time the reactor made progress.
+    last_progress: Timestamp,
+    /// The blocks currently being synchronized.
+    block_synchronizer: BlockSynchronizerStatus,
+}
+
+impl Display for SyncStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "reactor state {} (last progress at {})",
+            self.reactor_state, self.last_progress
+        )?;
+        write!(f, "{:?}", self.block_synchronizer)
+    }
+}
+
+/// Finds the single peer whose node ID matches the given hex prefix, which may start with `tls:`.
+fn find_peer<'a, I>(peers: I, prefix: &str) -> Result<NodeId, String>
+where
+    I: IntoIterator<Item = &'a NodeId>,
+{
+    let prefix = prefix.trim_start_matches("tls:").to_lowercase();
+    let mut matches = peers
+        .into_iter()
+        .filter(|node_id| base16::encode_lower(node_id.hash_bytes()).starts_with(&prefix));
+    match (matches.next(), matches.next()) {
+        (Some(node_id), None) => Ok(*node_id),
+        (None, _) => Err(format!("not connected to a peer matching {}", prefix)),
+        (Some(_), Some(_)) => Err(format!("multiple peers match {}", prefix)),
+    }
+}
+
 /// Success or failure response.
 ///
 /// This response is sent back to clients after every operation (unless suppressed in quiet mode),
@@ -204,6 +250,13 @@ impl Session {
             + From<ControlAnnouncement>
             + From<NetworkInfoRequest>
             + From<SetNodeStopRequest>
+            + From<ReactorStatusRequest>
+            + From<BlockSynchronizerRequest>
+            + From<DeployBufferRequest>
+            + From<MetricsRequest>
+            + From<StorageRequest>
+            + From<ContractRuntimeRequest>
+            + From<PeerBehaviorAnnouncement>
             + Send,
     {
         debug!(%line, "line received");
@@ -292,6 +345,28 @@ impl Session {
                             }
                         }
                     }
+                    Action::DumpConsensusSummary { era } => {
+                        let output = effect_builder
+                            .diagnostics_port_dump_consensus_state(
+                                era.map(EraId::new),
+                                serialize_era_summary,
+                            )
+                            .await;
+
+                        match output {
+                            Ok(ref data) => {
+                                self.send_outcome(
+                                    writer,
+                                    &Outcome::success("dumping consensus state summary"),
+                                )
+                                .await?;
+                                writer.write_all(data).await?;
+                            }
+                            Err(err) => {
+                                self.send_outcome(writer, &Outcome::failed(err)).await?;
+                            }
+                        }
+                    }
                     Action::DumpQueues => {
                         // Note: The preferable approach would be to use a tempfile instead of a
                         //       named one in a temporary directory, and return it through the
@@ -318,12 +393,93 @@ impl Session {
                             }
                         };
                     }
+                    Action::DumpSync => {
+                        self.send_outcome(writer, &Outcome::success("dumping sync status"))
+                            .await?;
+                        let (reactor_state, last_progress) =
+                            effect_builder.get_reactor_status().await;
+                        let block_synchronizer =
+                            effect_builder.get_block_synchronizer_status().await;
+                        let status = SyncStatus {
+                            reactor_state,
+                            last_progress,
+                            block_synchronizer,
+                        };
+                        self.send_to_client(writer, &status).await?;
+                    }
+                    Action::DumpDeployBuffer => {
+                        self.send_outcome(
+                            writer,
+                            &Outcome::success("dumping deploy buffer status"),
+                        )
+                        .await?;
+                        let status = effect_builder.get_deploy_buffer_status().await;
+                        self.send_to_client(writer, &status).await?;
+                    }
                     Action::NetInfo => {
                         self.send_outcome(writer, &Outcome::success("collecting insights"))
                             .await?;
                         let insights = effect_builder.get_network_insights().await;
                         self.send_to_client(writer, &insights).await?;
                     }
+                    Action::DropPeer { ref peer } => {
+                        let peers = effect_builder.network_peers().await;
+                        match find_peer(peers.keys(), peer) {
+                            Ok(node_id) => {
+                                effect_builder
+                                    .announce_block_peer_with_justification(
+                                        node_id,
+                                        BlocklistJustification::DroppedByOperator,
+                                    )
+                                    .await;
+                                self.send_outcome(
+                                    writer,
+                                    &Outcome::success(format!("dropping peer {}", node_id)),
+                                )
+                                .await?;
+                            }
+                            Err(reason) => {
+                                self.send_outcome(writer, &Outcome::failed(reason)).await?;
+                            }
+                        }
+                    }
+                    Action::ClearDeployBuffer => {
+                        let removed = effect_builder.clear_pending_deploys().await;
+                        self.send_outcome(
+                            writer,
+                            &Outcome::success(format!(
+                                "removed {} pending deploys from deploy buffer",
+                                removed
+                            )),
+                        )
+                        .await?;
+                    }
+                    Action::SyncStorage => {
+                        let storage_synced = effect_builder.sync_storage().await;
+                        let global_state_synced = effect_builder.sync_global_state_to_disk().await;
+                        let outcome = match (storage_synced, global_state_synced) {
+                            (true, true) => {
+                                Outcome::success("storage and global state synced to disk")
+                            }
+                            (false, true) => Outcome::failed("failed to sync storage"),
+                            (true, false) => Outcome::failed("failed to sync global state"),
+                            (false, false) => {
+                                Outcome::failed("failed to sync storage and global state")
+                            }
+                        };
+                        self.send_outcome(writer, &outcome).await?;
+                    }
+                    Action::Metrics => match effect_builder.get_metrics().await {
+                        Some(metrics) => {
+                            self.send_outcome(writer, &Outcome::success("showing metrics"))
+                                .await?;
+                            self.send_to_client(writer, &metrics).await?;
+                        }
+                        None => {
+                            self.send_outcome(writer, &Outcome::failed("metrics not available"))
+                                .await?;
+                        }
+                    },
                     Action::Stop { at, clear } => {
                         let (msg, stop_at) = if clear {
                             ("clearing stopping point", None)
@@ -477,6 +633,17 @@ enum SetLogFilterError {
     SetFailed(anyhow::Error),
 }
 
+/// Serializes the summary of an `EraDump` as JSON.
+///
+/// The summary is meant to be read by humans, so it is always JSON regardless of the session's
+/// output format.
+fn serialize_era_summary(data: &EraDump<'_>) -> Result<Vec<u8>, Cow<'static, str>> {
+    let mut buf = serde_json::to_vec_pretty(&data.summary())
+        .map_err(|err| Cow::Owned(format!("failed to serialize era summary as JSON: {}", err)))?;
+    buf.push(b'\n');
+    Ok(buf)
+}
+
 /// Sets the global log using the given new directive.
 fn set_log_filter(filter_str: &str) -> Result<(), SetLogFilterError> {
     let new_filter = EnvFilter::try_new(filter_str).map_err(SetLogFilterError::ParseError)?;
@@ -503,6 +670,13 @@ where
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<ReactorStatusRequest>
+        + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<MetricsRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<PeerBehaviorAnnouncement>
         + Send,
 {
     debug!("accepted new connection on diagnostics port");
@@ -547,6 +721,13 @@ pub(super) async fn server<REv>(
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
+        + From<ReactorStatusRequest>
+        + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
+        + From<MetricsRequest>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<PeerBehaviorAnnouncement>
         + Send,
 {
     let handling_shutdown_receiver = shutdown_receiver.clone();
@@ -624,9 +805,13 @@ mod tests {
             Component, InitializedComponent,
         },
         effect::{
-            announcements::ControlAnnouncement,
+            announcements::{ControlAnnouncement, PeerBehaviorAnnouncement},
             diagnostics_port::DumpConsensusStateRequest,
-            requests::{NetworkInfoRequest, SetNodeStopRequest},
+            requests::{
+                BlockSynchronizerRequest, ContractRuntimeRequest, DeployBufferRequest,
+                MetricsRequest, NetworkInfoRequest, ReactorStatusRequest, SetNodeStopRequest,
+                StorageRequest,
+            },
             EffectBuilder, EffectExt, Effects,
         },
         reactor::{
@@ -637,7 +822,7 @@ mod tests {
             self,
             network::{NetworkedReactor, TestingNetwork},
         },
-        types::{Chainspec, ChainspecRawBytes},
+        types::{Chainspec, ChainspecRawBytes, NodeId},
         utils::WeightedRoundRobin,
         NodeRng, WithDir,
     };
@@ -686,6 +871,27 @@ mod tests {
         NetworkInfoRequest(NetworkInfoRequest),
         #[from]
         SetNodeStopRequest(SetNodeStopRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        ReactorStatusRequest(ReactorStatusRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        BlockSynchronizerRequest(BlockSynchronizerRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        DeployBufferRequest(DeployBufferRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        MetricsRequest(MetricsRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        StorageRequest(StorageRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        ContractRuntimeRequest(ContractRuntimeRequest),
+        #[from]
+        #[serde(skip_serializing)]
+        PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
     }
 
     impl Display for Event {
@@ -732,7 +938,14 @@ mod tests {
                 Event::DumpConsensusStateRequest(_)
                 | Event::SetNodeStopRequest(_)
                 | Event::ControlAnnouncement(_)
-                | Event::NetworkInfoRequest(_) => {
+                | Event::NetworkInfoRequest(_)
+                | Event::ReactorStatusRequest(_)
+                | Event::BlockSynchronizerRequest(_)
+                | Event::DeployBufferRequest(_)
+                | Event::MetricsRequest(_)
+                | Event::StorageRequest(_)
+                | Event::ContractRuntimeRequest(_)
+                | Event::PeerBehaviorAnnouncement(_) => {
                     panic!("unexpected: {}", event)
                 }
             }
@@ -870,4 +1083,20 @@ mod tests {
             })
             .await;
     }
+
+    #[test]
+    fn can_find_peer_by_prefix() {
+        let mut rng = TestRng::new();
+        let peers: Vec<NodeId> = (0..10).map(|_| NodeId::random(&mut rng)).collect();
+        let peer = peers[3];
+        let hex = base16::encode_lower(peer.hash_bytes());
+
+        assert_eq!(super::find_peer(&peers, &hex), Ok(peer));
+        assert_eq!(
+            super::find_peer(&peers, &format!("tls:{}", &hex[..16])),
+            Ok(peer)
+        );
+        assert!(super::find_peer(&peers, "").is_err());
+        assert!(super::find_peer(&peers[..3], &hex).is_err());
+    }
 }
@@ -15,12 +15,15 @@
 //!
 //! This component uses a ring buffer for outbound events providing some robustness against
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
-//! has advanced past their last received event.
+//! has advanced past their last received event.  A re-subscribing client can indicate its last
+//! received event either via the `start_from` query or via the standard `Last-Event-ID` header.
 
 mod config;
 mod event;
 mod event_indexer;
+mod event_log;
 mod http_server;
+mod nats_sink;
 mod sse_server;
 #[cfg(test)]
 mod tests;
@@ -28,27 +31,34 @@ mod tests;
 use std::{fmt::Debug, net::SocketAddr, path::PathBuf};
 
 use datasize::DataSize;
-use tokio::sync::{
-    mpsc::{self, UnboundedSender},
-    oneshot,
+use futures::{future::BoxFuture, FutureExt};
+use http::Method;
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        oneshot,
+    },
+    task::JoinHandle,
 };
-use tracing::{error, info, warn};
-use warp::Filter;
+use tracing::{debug, error, info, warn};
+use warp::{Filter, Reply};
 
-use casper_types::ProtocolVersion;
+use casper_types::{ExecutionResult, ExecutionResultFormat, ProtocolVersion};
 
 use super::Component;
 use crate::{
     components::{ComponentState, InitializedComponent, PortBoundComponent},
     effect::{EffectBuilder, Effects},
-    reactor::main_reactor::MainEvent,
+    reactor::{main_reactor::MainEvent, Finalize},
     types::JsonBlock,
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
-pub use config::Config;
+pub use config::{Config, EventLogConfig, NatsConfig};
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
+use event_log::EventLog;
+use nats_sink::NatsSink;
 use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
 
@@ -78,19 +88,26 @@ struct InnerServer {
     sse_data_sender: UnboundedSender<(EventIndex, SseData)>,
     event_indexer: EventIndexer,
     listening_address: SocketAddr,
+    /// The task handle which will only join once the server loop has exited.
+    #[data_size(skip)]
+    server_join_handle: JoinHandle<()>,
 }
 
 #[derive(DataSize, Debug)]
 pub(crate) struct EventStreamServer {
     state: ComponentState,
-    config: Config,
+    config: WithDir<Config>,
     storage_path: PathBuf,
     api_version: ProtocolVersion,
     sse_server: Option<InnerServer>,
 }
 
 impl EventStreamServer {
-    pub(crate) fn new(config: Config, storage_path: PathBuf, api_version: ProtocolVersion) -> Self {
+    pub(crate) fn new(
+        config: WithDir<Config>,
+        storage_path: PathBuf,
+        api_version: ProtocolVersion,
+    ) -> Self {
         EventStreamServer {
             state: ComponentState::Uninitialized,
             config,
@@ -101,17 +118,18 @@ impl EventStreamServer {
     }
 
     fn listen(&mut self) -> Result<(), ListeningError> {
-        let required_address = utils::resolve_address(&self.config.address).map_err(|error| {
+        let config = self.config.value();
+        let required_address = utils::resolve_address(&config.address).map_err(|error| {
             warn!(
                 %error,
-                address=%self.config.address,
+                address=%config.address,
                 "failed to start event stream server, cannot parse address"
             );
             ListeningError::ResolveAddress(error)
         })?;
 
         // Event stream channels and filter.
-        let broadcast_channel_size = self.config.event_stream_buffer_length
+        let broadcast_channel_size = config.event_stream_buffer_length
             * (100 + ADDITIONAL_PERCENT_FOR_BROADCAST_CHANNEL_SIZE)
             / 100;
 
@@ -121,82 +139,79 @@ impl EventStreamServer {
             sse_filter,
         } = ChannelsAndFilter::new(
             broadcast_channel_size as usize,
-            self.config.max_concurrent_subscribers,
+            config.max_concurrent_subscribers,
         );
 
-        let (server_shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let sse_filter = match utils::cors(
+            &config.cors_origin,
+            &config.cors_allowed_methods,
+            &[Method::GET],
+        ) {
+            None => sse_filter
+                .map(|reply| -> Box<dyn Reply> { Box::new(reply) })
+                .boxed(),
+            Some(cors) => sse_filter
+                .with(cors)
+                .map(|reply| -> Box<dyn Reply> { Box::new(reply) })
+                .boxed(),
+        };
 
-        let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
+        let (server_shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let shutdown = async {
+            shutdown_receiver.await.ok();
+        };
 
-        let listening_address = match self.config.cors_origin.as_str() {
-            "" => {
+        let (listening_address, server_with_shutdown) = match &config.tls {
+            None => {
                 let (listening_address, server_with_shutdown) = warp::serve(sse_filter)
-                    .try_bind_with_graceful_shutdown(required_address, async {
-                        shutdown_receiver.await.ok();
-                    })
+                    .try_bind_with_graceful_shutdown(required_address, shutdown)
                     .map_err(|error| ListeningError::Listen {
                         address: required_address,
                         error: Box::new(error),
                     })?;
-
-                tokio::spawn(http_server::run(
-                    self.config.clone(),
-                    self.api_version,
-                    server_with_shutdown,
-                    server_shutdown_sender,
-                    sse_data_receiver,
-                    event_broadcaster,
-                    new_subscriber_info_receiver,
-                ));
-                listening_address
+                (listening_address, server_with_shutdown.boxed())
             }
-            "*" => {
-                let (listening_address, server_with_shutdown) =
-                    warp::serve(sse_filter.with(warp::cors().allow_any_origin()))
-                        .try_bind_with_graceful_shutdown(required_address, async {
-                            shutdown_receiver.await.ok();
-                        })
-                        .map_err(|error| ListeningError::Listen {
-                            address: required_address,
-                            error: Box::new(error),
-                        })?;
-
-                tokio::spawn(http_server::run(
-                    self.config.clone(),
-                    self.api_version,
-                    server_with_shutdown,
-                    server_shutdown_sender,
-                    sse_data_receiver,
-                    event_broadcaster,
-                    new_subscriber_info_receiver,
-                ));
-                listening_address
-            }
-            _ => {
-                let (listening_address, server_with_shutdown) = warp::serve(
-                    sse_filter.with(warp::cors().allow_origin(self.config.cors_origin.as_str())),
-                )
-                .try_bind_with_graceful_shutdown(required_address, async {
-                    shutdown_receiver.await.ok();
-                })
-                .map_err(|error| ListeningError::Listen {
-                    address: required_address,
-                    error: Box::new(error),
-                })?;
-
-                tokio::spawn(http_server::run(
-                    self.config.clone(),
-                    self.api_version,
-                    server_with_shutdown,
-                    server_shutdown_sender,
-                    sse_data_receiver,
-                    event_broadcaster,
-                    new_subscriber_info_receiver,
-                ));
-                listening_address
+            Some(tls) => {
+                let tls = WithDir::new(self.config.dir(), tls.clone());
+                let incoming = utils::start_listening(&config.address, Some(&tls))?;
+                let listening_address = incoming.local_addr();
+                let server_with_shutdown = warp::serve(sse_filter)
+                    .serve_incoming_with_graceful_shutdown(incoming, shutdown);
+                (listening_address, server_with_shutdown.boxed())
             }
         };
 
+        let event_log = config.event_log.as_ref().and_then(|event_log_config| {
+            let dir = self.config.with_dir(event_log_config.path.clone());
+            match EventLog::open(dir.clone(), event_log_config) {
+                Ok(event_log) => Some(event_log),
+                Err(error) => {
+                    error!(
+                        %error,
+                        dir = %dir.display(),
+                        "failed to open event log, events will not be written to disk"
+                    );
+                    None
+                }
+            }
+        });
+
+        let nats_sink = config.nats.as_ref().map(NatsSink::spawn);
+
+        let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
+
+        let server_join_handle = tokio::spawn(http_server::run(
+            config.clone(),
+            self.api_version,
+            event_log,
+            nats_sink,
+            server_with_shutdown,
+            server_shutdown_sender,
+            sse_data_receiver,
+            event_broadcaster,
+            new_subscriber_info_receiver,
+        ));
+
         info!(address=%listening_address, "started event stream server");
 
         let event_indexer = EventIndexer::new(self.storage_path.clone());
@@ -204,11 +219,28 @@ impl EventStreamServer {
         self.sse_server = Some(InnerServer {
             sse_data_sender,
             event_indexer,
+            server_join_handle,
             listening_address,
         });
         Ok(())
     }
 
+    /// Compacts the execution result unless the full transforms are configured to be included,
+    /// returning it along with its format.
+    fn prepare_execution_result(
+        &self,
+        execution_result: Box<ExecutionResult>,
+    ) -> (Box<ExecutionResult>, ExecutionResultFormat) {
+        if self.config.value().include_transforms {
+            (execution_result, ExecutionResultFormat::Full)
+        } else {
+            (
+                Box::new(execution_result.compact()),
+                ExecutionResultFormat::Compact,
+            )
+        }
+    }
+
     /// Broadcasts the SSE data to all clients connected to the event stream.
     fn broadcast(&mut self, sse_data: SseData) -> Effects<Event> {
         if let Some(server) = self.sse_server.as_mut() {
@@ -225,6 +257,34 @@ impl Drop for EventStreamServer {
     }
 }
 
+impl Finalize for EventStreamServer {
+    fn finalize(mut self) -> BoxFuture<'static, ()> {
+        let _ = self.broadcast(SseData::Shutdown);
+        let maybe_server = self.sse_server.take();
+        async move {
+            if let Some(InnerServer {
+                sse_data_sender,
+                event_indexer,
+                server_join_handle,
+                ..
+            }) = maybe_server
+            {
+                // Dropping the sender makes the server loop exit once all queued events have been
+                // handled, and dropping the indexer persists the current event index.
+                drop(sse_data_sender);
+                drop(event_indexer);
+                match server_join_handle.await {
+                    Ok(_) => debug!("event stream server exited cleanly"),
+                    Err(error) => error!(%error, "could not join event stream server task cleanly"),
+                }
+            } else {
+                info!("event stream server was not running, no shutdown performed")
+            }
+        }
+        .boxed()
+    }
+}
+
 impl<REv> Component<REv> for EventStreamServer
 where
     REv: ReactorEventT,
@@ -257,13 +317,16 @@ where
             }
             ComponentState::Initializing => match event {
                 Event::Initialize => {
-                    let (effects, state) = self.bind(self.config.enable_server, _effect_builder);
+                    let (effects, state) =
+                        self.bind(self.config.value().enable_server, _effect_builder);
                     <Self as InitializedComponent<MainEvent>>::set_state(self, state);
                     effects
                 }
                 Event::BlockAdded(_)
                 | Event::DeployAccepted(_)
                 | Event::DeployProcessed { .. }
+                | Event::DeployExecuted { .. }
+                | Event::BlockExecutionFailed { .. }
                 | Event::DeploysExpired(_)
                 | Event::Fault { .. }
                 | Event::FinalitySignature(_)
@@ -295,14 +358,44 @@ where
                     deploy_header,
                     block_hash,
                     execution_result,
-                } => self.broadcast(SseData::DeployProcessed {
-                    deploy_hash: Box::new(deploy_hash),
-                    account: Box::new(deploy_header.account().clone()),
-                    timestamp: deploy_header.timestamp(),
-                    ttl: deploy_header.ttl(),
-                    dependencies: deploy_header.dependencies().clone(),
-                    block_hash: Box::new(block_hash),
+                } => {
+                    let (execution_result, execution_result_format) =
+                        self.prepare_execution_result(execution_result);
+                    self.broadcast(SseData::DeployProcessed {
+                        deploy_hash: Box::new(deploy_hash),
+                        account: Box::new(deploy_header.account().clone()),
+                        timestamp: deploy_header.timestamp(),
+                        ttl: deploy_header.ttl(),
+                        dependencies: deploy_header.dependencies().clone(),
+                        block_hash: Box::new(block_hash),
+                        execution_result,
+                        execution_result_format,
+                    })
+                }
+                Event::DeployExecuted {
+                    era_id,
+                    block_height,
+                    deploy_hash,
+                    deploy_header,
                     execution_result,
+                } => {
+                    let (execution_result, execution_result_format) =
+                        self.prepare_execution_result(execution_result);
+                    self.broadcast(SseData::DeployExecuted {
+                        deploy_hash: Box::new(deploy_hash),
+                        account: Box::new(deploy_header.account().clone()),
+                        era_id,
+                        block_height,
+                        execution_result,
+                        execution_result_format,
+                    })
+                }
+                Event::BlockExecutionFailed {
+                    era_id,
+                    block_height,
+                } => self.broadcast(SseData::BlockExecutionFailed {
+                    era_id,
+                    block_height,
                 }),
                 Event::DeploysExpired(deploy_hashes) => deploy_hashes
                     .into_iter()
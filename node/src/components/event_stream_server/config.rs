@@ -1,6 +1,10 @@
+use std::path::PathBuf;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::HttpTlsConfig;
+
 /// Default binding address for the SSE HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -15,6 +19,9 @@ const DEFAULT_MAX_CONCURRENT_SUBSCRIBERS: u32 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
+/// Default setting for whether to include the full transforms in execution results.
+const DEFAULT_INCLUDE_TRANSFORMS: bool = true;
+
 /// SSE HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -32,8 +39,31 @@ pub struct Config {
     /// Default maximum number of subscribers across all event streams permitted at any one time.
     pub max_concurrent_subscribers: u32,
 
-    /// CORS origin.
+    /// CORS origin.  Empty to disable CORS, `*` to allow any origin, or a comma-separated list of
+    /// allowed origins.
     pub cors_origin: String,
+
+    /// HTTP methods allowed for cross-origin requests.  If empty, only GET is allowed.
+    #[serde(default)]
+    pub cors_allowed_methods: Vec<String>,
+
+    /// TLS configuration.  If absent, the server accepts plain HTTP connections.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
+
+    /// File-backed event log configuration.  If absent, events are not written to disk.
+    #[serde(default)]
+    pub event_log: Option<EventLogConfig>,
+
+    /// NATS publishing configuration.  If absent, events are not published to NATS.
+    #[serde(default)]
+    pub nats: Option<NatsConfig>,
+
+    /// Whether to include the full transforms in execution results.  If `false`, compact execution
+    /// results are sent, in which each transform other than a failure is replaced by the kind of
+    /// change it made to its key.
+    #[serde(default = "default_include_transforms")]
+    pub include_transforms: bool,
 }
 
 impl Config {
@@ -45,6 +75,11 @@ impl Config {
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            cors_allowed_methods: vec![],
+            tls: None,
+            event_log: None,
+            nats: None,
+            include_transforms: DEFAULT_INCLUDE_TRANSFORMS,
         }
     }
 }
@@ -54,3 +89,59 @@ impl Default for Config {
         Config::new()
     }
 }
+
+fn default_include_transforms() -> bool {
+    DEFAULT_INCLUDE_TRANSFORMS
+}
+
+/// Default size in bytes above which the current event log file is rotated.
+const DEFAULT_MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Default number of rotated event log files to retain.
+const DEFAULT_MAX_ROTATED_FILES: u32 = 16;
+
+/// Configuration of the file-backed event log.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct EventLogConfig {
+    /// Directory holding the event log files.  Relative paths are resolved relative to the
+    /// directory containing the config file.
+    pub path: PathBuf,
+
+    /// Size in bytes above which the current event log file is rotated.
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: u64,
+
+    /// Number of rotated event log files to retain.
+    #[serde(default = "default_max_rotated_files")]
+    pub max_rotated_files: u32,
+}
+
+fn default_max_file_size() -> u64 {
+    DEFAULT_MAX_FILE_SIZE
+}
+
+fn default_max_rotated_files() -> u32 {
+    DEFAULT_MAX_ROTATED_FILES
+}
+
+/// Default prefix of the NATS subjects to which events are published.
+const DEFAULT_NATS_SUBJECT_PREFIX: &str = "casper";
+
+/// Configuration of the publishing of events to a NATS server.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct NatsConfig {
+    /// Address of the NATS server.
+    pub address: String,
+
+    /// Prefix of the subjects to which events are published.
+    #[serde(default = "default_nats_subject_prefix")]
+    pub subject_prefix: String,
+}
+
+fn default_nats_subject_prefix() -> String {
+    DEFAULT_NATS_SUBJECT_PREFIX.to_string()
+}
@@ -19,6 +19,17 @@ pub enum Event {
         block_hash: BlockHash,
         execution_result: Box<ExecutionResult>,
     },
+    DeployExecuted {
+        era_id: EraId,
+        block_height: u64,
+        deploy_hash: DeployHash,
+        deploy_header: Box<DeployHeader>,
+        execution_result: Box<ExecutionResult>,
+    },
+    BlockExecutionFailed {
+        era_id: EraId,
+        block_height: u64,
+    },
     DeploysExpired(Vec<DeployHash>),
     Fault {
         era_id: EraId,
@@ -50,6 +61,20 @@ impl Display for Event {
             Event::DeployProcessed { deploy_hash, .. } => {
                 write!(formatter, "deploy processed {}", deploy_hash)
             }
+            Event::DeployExecuted {
+                deploy_hash,
+                block_height,
+                ..
+            } => write!(
+                formatter,
+                "deploy executed {} in block at height {}",
+                deploy_hash, block_height
+            ),
+            Event::BlockExecutionFailed { block_height, .. } => write!(
+                formatter,
+                "execution of block at height {} failed",
+                block_height
+            ),
             Event::Fault {
                 era_id,
                 public_key,
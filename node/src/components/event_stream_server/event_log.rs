@@ -0,0 +1,291 @@
+//! A file-backed log of all events emitted by the event stream server, allowing indexers to
+//! backfill events from local disk after an outage.
+//!
+//! Events are appended as JSON lines of the form `{"id":<EVENT ID>,"data":<EVENT DATA>}` to the
+//! file `events.log`.  Once that file exceeds the configured size it is renamed to
+//! `events.<N>.log`, where `N` increases with each rotation, and a new `events.log` is started.
+//! Only the configured number of rotated files are retained, the oldest being deleted first.
+//!
+//! Events are serialized by the caller, but written to disk by a dedicated thread, so that file
+//! I/O never blocks the event stream server.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread::{self, JoinHandle},
+};
+
+use serde::Serialize;
+use tracing::{debug, error, warn};
+
+use super::{sse_server::Id, EventLogConfig, SseData};
+
+/// The name of the file to which events are currently being appended.
+const CURRENT_FILENAME: &str = "events.log";
+/// The prefix of the names of rotated files.
+const ROTATED_FILENAME_PREFIX: &str = "events.";
+/// The extension of the names of all event log files.
+const FILENAME_EXTENSION: &str = ".log";
+/// The maximum number of serialized events waiting to be written by the writer thread.
+const WRITER_QUEUE_CAPACITY: usize = 10_000;
+
+/// The JSON representation of an event written to the event log.
+#[derive(Serialize)]
+pub(super) struct LoggedEvent<'a> {
+    pub(super) id: Id,
+    pub(super) data: &'a SseData,
+}
+
+/// A log of events, rotated by size and with a bounded number of retained files.
+///
+/// If the writer thread falls `WRITER_QUEUE_CAPACITY` events behind, further events are dropped
+/// until it catches up, so that a slow disk can't make the queue grow without bound.
+pub(super) struct EventLog {
+    dir: PathBuf,
+    /// The channel to the writer thread; `None` once dropped.
+    sender: Option<SyncSender<(Id, Vec<u8>)>>,
+    /// The writer thread, joined on drop so that all queued events are written.
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl EventLog {
+    /// Opens the event log in the given directory, creating the directory if required and
+    /// appending to any existing current file, and spawns its writer thread.
+    pub(super) fn open(dir: PathBuf, config: &EventLogConfig) -> io::Result<Self> {
+        let files = EventLogFiles::open(dir.clone(), config)?;
+        let (sender, receiver) = mpsc::sync_channel(WRITER_QUEUE_CAPACITY);
+        let writer_thread = thread::Builder::new()
+            .name("casper-event-log".to_string())
+            .spawn(move || run_writer(files, receiver))?;
+        Ok(EventLog {
+            dir,
+            sender: Some(sender),
+            writer_thread: Some(writer_thread),
+        })
+    }
+
+    /// Queues the given event to be appended to the log.
+    ///
+    /// Failures are logged rather than returned, as the event stream should be unaffected by
+    /// problems with the event log.
+    pub(super) fn append(&self, id: Id, data: &SseData) {
+        let mut line = match serde_json::to_vec(&LoggedEvent { id, data }) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!(%error, event_id = %id, "failed to serialize event for event log");
+                return;
+            }
+        };
+        line.push(b'\n');
+        let sender = match self.sender.as_ref() {
+            Some(sender) => sender,
+            None => return,
+        };
+        match sender.try_send((id, line)) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => warn!(
+                dir = %self.dir.display(),
+                event_id = %id,
+                "event log writer is falling behind, dropping event"
+            ),
+            Err(TrySendError::Disconnected(_)) => warn!(
+                dir = %self.dir.display(),
+                event_id = %id,
+                "event log writer has stopped, dropping event"
+            ),
+        }
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        // Closing the channel makes the writer thread exit once it has written everything.
+        drop(self.sender.take());
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                error!("event log writer thread panicked");
+            }
+        }
+    }
+}
+
+/// Writes the events received on `receiver` to `files` until the channel is closed, flushing
+/// whenever no further events are queued.
+fn run_writer(mut files: EventLogFiles, receiver: Receiver<(Id, Vec<u8>)>) {
+    while let Ok(entry) = receiver.recv() {
+        files.append(entry);
+        while let Ok(entry) = receiver.try_recv() {
+            files.append(entry);
+        }
+        if let Err(error) = files.current_file.flush() {
+            warn!(%error, dir = %files.dir.display(), "failed to flush event log");
+        }
+    }
+}
+
+/// The files of an event log, written to by its writer thread.
+struct EventLogFiles {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_rotated_files: u32,
+    current_file: BufWriter<File>,
+    current_file_size: u64,
+}
+
+impl EventLogFiles {
+    fn open(dir: PathBuf, config: &EventLogConfig) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let current_file = open_for_append(&dir.join(CURRENT_FILENAME))?;
+        let current_file_size = current_file.metadata()?.len();
+        debug!(dir = %dir.display(), "opened event log");
+        Ok(EventLogFiles {
+            dir,
+            max_file_size: config.max_file_size,
+            max_rotated_files: config.max_rotated_files,
+            current_file: BufWriter::new(current_file),
+            current_file_size,
+        })
+    }
+
+    /// Appends the given serialized event to the current file, rotating it first if it is full.
+    fn append(&mut self, (id, line): (Id, Vec<u8>)) {
+        if let Err(error) = self.try_append(&line) {
+            warn!(
+                %error,
+                dir = %self.dir.display(),
+                event_id = %id,
+                "failed to append to event log"
+            );
+        }
+    }
+
+    fn try_append(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.current_file_size >= self.max_file_size {
+            self.rotate()?;
+        }
+        self.current_file.write_all(line)?;
+        self.current_file_size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Renames the current file to the next rotated file, deletes the oldest rotated files beyond
+    /// the retention limit and starts a new current file.
+    fn rotate(&mut self) -> io::Result<()> {
+        let mut rotated = rotated_files(&self.dir)?;
+        let next_number = rotated.last().map_or(0, |(number, _)| number + 1);
+        let rotated_path = self.dir.join(format!(
+            "{}{}{}",
+            ROTATED_FILENAME_PREFIX, next_number, FILENAME_EXTENSION
+        ));
+        let current_path = self.dir.join(CURRENT_FILENAME);
+        fs::rename(&current_path, &rotated_path)?;
+        rotated.push((next_number, rotated_path));
+
+        let excess = rotated
+            .len()
+            .saturating_sub(self.max_rotated_files as usize);
+        for (_, path) in rotated.drain(..excess) {
+            if let Err(error) = fs::remove_file(&path) {
+                warn!(%error, file = %path.display(), "failed to remove old event log file");
+            }
+        }
+
+        self.current_file.flush()?;
+        self.current_file = BufWriter::new(open_for_append(&current_path)?);
+        self.current_file_size = 0;
+        debug!(dir = %self.dir.display(), "rotated event log");
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Returns the rotated files in the given directory along with their rotation numbers, ordered
+/// oldest first.
+fn rotated_files(dir: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let mut rotated = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let maybe_number = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(ROTATED_FILENAME_PREFIX))
+            .and_then(|name| name.strip_suffix(FILENAME_EXTENSION))
+            .and_then(|number| number.parse::<u64>().ok());
+        if let Some(number) = maybe_number {
+            rotated.push((number, entry.path()));
+        }
+    }
+    rotated.sort_unstable_by_key(|(number, _)| *number);
+    Ok(rotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging;
+
+    fn config(max_file_size: u64, max_rotated_files: u32) -> EventLogConfig {
+        EventLogConfig {
+            path: PathBuf::new(),
+            max_file_size,
+            max_rotated_files,
+        }
+    }
+
+    fn read_ids(path: &Path) -> Vec<Id> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                value["id"].as_u64().unwrap() as Id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_rotate_and_retain_limited_files() {
+        let _ = logging::init();
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_path_buf();
+
+        // A maximum size of one byte causes rotation before every event after the first.
+        let event_log = EventLog::open(dir.clone(), &config(1, 2)).unwrap();
+        for id in 0..5 {
+            event_log.append(id, &SseData::Shutdown);
+        }
+        // Dropping the log waits for the writer thread to write all queued events.
+        drop(event_log);
+
+        assert_eq!(read_ids(&dir.join(CURRENT_FILENAME)), vec![4]);
+        let rotated = rotated_files(&dir).unwrap();
+        let rotated_numbers: Vec<_> = rotated.iter().map(|(number, _)| *number).collect();
+        assert_eq!(rotated_numbers, vec![2, 3]);
+        assert_eq!(read_ids(&rotated[0].1), vec![2]);
+        assert_eq!(read_ids(&rotated[1].1), vec![3]);
+    }
+
+    #[test]
+    fn should_append_to_existing_file_on_reopen() {
+        let _ = logging::init();
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().join("events");
+
+        {
+            let event_log = EventLog::open(dir.clone(), &config(1024, 2)).unwrap();
+            event_log.append(0, &SseData::Shutdown);
+        }
+        {
+            let event_log = EventLog::open(dir.clone(), &config(1024, 2)).unwrap();
+            event_log.append(1, &SseData::Shutdown);
+        }
+
+        assert_eq!(read_ids(&dir.join(CURRENT_FILENAME)), vec![0, 1]);
+        assert!(rotated_files(&dir).unwrap().is_empty());
+    }
+}
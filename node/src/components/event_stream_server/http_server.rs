@@ -10,12 +10,16 @@ use wheelbuf::WheelBuf;
 use casper_types::ProtocolVersion;
 
 use super::{
+    event_log::EventLog,
+    nats_sink::NatsSink,
     sse_server::{BroadcastChannelMessage, Id, NewSubscriberInfo, ServerSentEvent},
     Config, EventIndex, SseData,
 };
 
 /// Run the HTTP server.
 ///
+/// * `event_log`, if provided, is appended to with every event received via the `data_receiver`.
+/// * `nats_sink`, if provided, publishes selected events received via the `data_receiver`.
 /// * `server_with_shutdown` is the actual server as a future which can be gracefully shut down.
 /// * `server_shutdown_sender` is the channel by which the server will be notified to shut down.
 /// * `data_receiver` will provide the server with local events which should then be sent to all
@@ -28,6 +32,8 @@ use super::{
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
+    event_log: Option<EventLog>,
+    nats_sink: Option<NatsSink>,
     server_with_shutdown: impl Future<Output = ()> + Send + 'static,
     server_shutdown_sender: oneshot::Sender<()>,
     mut data_receiver: mpsc::UnboundedReceiver<(EventIndex, SseData)>,
@@ -93,6 +99,12 @@ pub(super) async fn run(
                         Some((event_index, data)) => {
                             // Buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
+                            if let Some(event_log) = event_log.as_ref() {
+                                event_log.append(event_index, &data);
+                            }
+                            if let Some(nats_sink) = nats_sink.as_ref() {
+                                nats_sink.publish(event_index, &data);
+                            }
                             let event = ServerSentEvent { id: Some(event_index), data };
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
@@ -0,0 +1,318 @@
+//! Publishing of selected events emitted by the event stream server to a NATS server.
+//!
+//! `BlockAdded`, `DeployProcessed` and `Step` (i.e. era end) events are published as JSON of the
+//! form `{"id":<EVENT ID>,"data":<EVENT DATA>}` to the subjects `<PREFIX>.BlockAdded`,
+//! `<PREFIX>.DeployProcessed` and `<PREFIX>.Step` respectively.
+//!
+//! Events whose JSON exceeds the `max_payload` advertised by the NATS server are split into chunks
+//! of at most `max_payload` bytes, published in order to the subjects
+//! `<SUBJECT>.chunk.<INDEX>.<COUNT>`, where `INDEX` counts from zero.  Concatenating the chunks'
+//! payloads yields the event's JSON.
+//!
+//! Only the subset of the NATS client protocol required for publishing is implemented.  In
+//! particular, neither authentication nor TLS is supported, so the NATS server should only be
+//! reachable from trusted hosts.  Events are queued while the connection to the NATS server is
+//! being (re-)established, and dropped if the queue is full, so that an unavailable NATS server
+//! never affects the rest of the node.
+//!
+//! Delivery is at most once: events queued or in flight when the connection fails may be lost, as
+//! may events published while no subscriber is connected.  Consumers requiring every event should
+//! backfill from the event stream server or its event log.
+
+use std::{cmp, io, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{tcp::OwnedWriteHalf, TcpStream},
+    select,
+    sync::mpsc::{self, error::TrySendError},
+    time,
+};
+use tracing::{debug, info, warn};
+
+use super::{event_log::LoggedEvent, sse_server::Id, NatsConfig, SseData};
+
+/// The maximum number of events queued for publishing.
+const MAX_QUEUED_EVENTS: usize = 10_000;
+
+/// The maximum payload size assumed if the NATS server doesn't advertise one; the server default.
+const DEFAULT_MAX_PAYLOAD: usize = 1_048_576;
+
+/// The delay before reconnecting after the connection to the NATS server fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A message ready to be published: its subject and payload.
+type Message = (String, Vec<u8>);
+
+/// Publisher of events to a NATS server, running in a background task.
+pub(super) struct NatsSink {
+    subject_prefix: String,
+    message_sender: mpsc::Sender<Message>,
+}
+
+impl NatsSink {
+    /// Spawns the background task connecting and publishing to the configured NATS server.
+    pub(super) fn spawn(config: &NatsConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(MAX_QUEUED_EVENTS);
+        tokio::spawn(run(config.address.clone(), message_receiver));
+        NatsSink {
+            subject_prefix: config.subject_prefix.clone(),
+            message_sender,
+        }
+    }
+
+    /// Queues the given event for publishing if it is of a published type.
+    pub(super) fn publish(&self, id: Id, data: &SseData) {
+        let event_type = match data {
+            SseData::BlockAdded { .. } => "BlockAdded",
+            SseData::DeployProcessed { .. } => "DeployProcessed",
+            SseData::Step { .. } => "Step",
+            SseData::ApiVersion(_)
+            | SseData::DeployAccepted { .. }
+            | SseData::DeployExecuted { .. }
+            | SseData::BlockExecutionFailed { .. }
+            | SseData::DeployExpired { .. }
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Shutdown => return,
+        };
+        let payload = match serde_json::to_vec(&LoggedEvent { id, data }) {
+            Ok(payload) => payload,
+            Err(error) => {
+                warn!(%error, event_id = %id, "failed to serialize event for NATS");
+                return;
+            }
+        };
+        let subject = format!("{}.{}", self.subject_prefix, event_type);
+        match self.message_sender.try_send((subject, payload)) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => {
+                warn!(event_id = %id, "NATS publishing queue full, dropping event");
+            }
+            Err(TrySendError::Closed(_)) => {
+                debug!(event_id = %id, "NATS publishing task stopped, dropping event");
+            }
+        }
+    }
+}
+
+/// Publishes messages received via `message_receiver`, reconnecting to the NATS server whenever
+/// the connection fails.  Exits once all senders have been dropped.
+async fn run(address: String, mut message_receiver: mpsc::Receiver<Message>) {
+    let mut pending = None;
+    loop {
+        let result = match TcpStream::connect(&address).await {
+            Ok(stream) => publish(stream, &mut message_receiver, &mut pending).await,
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(()) => {
+                debug!(%address, "stopped publishing events to NATS");
+                return;
+            }
+            Err(error) => {
+                warn!(%error, %address, "NATS connection failed, will reconnect");
+            }
+        }
+        if !wait_to_reconnect(&mut message_receiver, &mut pending).await {
+            return;
+        }
+    }
+}
+
+/// Waits for `RECONNECT_DELAY`, meanwhile receiving one message into `pending` if it is empty.
+///
+/// Returns `false` if all senders have been dropped.
+async fn wait_to_reconnect(
+    message_receiver: &mut mpsc::Receiver<Message>,
+    pending: &mut Option<Message>,
+) -> bool {
+    let delay = time::sleep(RECONNECT_DELAY);
+    tokio::pin!(delay);
+    loop {
+        select! {
+            _ = &mut delay => return true,
+            maybe_message = message_receiver.recv(), if pending.is_none() => match maybe_message {
+                Some(message) => *pending = Some(message),
+                None => return false,
+            },
+        }
+    }
+}
+
+/// Runs the NATS protocol on the given connection, publishing `pending` and then every received
+/// message, and answering the server's pings.
+///
+/// Returns `Ok` once all senders have been dropped, or an error if the connection fails.
+async fn publish(
+    stream: TcpStream,
+    message_receiver: &mut mpsc::Receiver<Message>,
+    pending: &mut Option<Message>,
+) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    // The server greets each client with an `INFO` message.
+    let max_payload = match lines.next_line().await? {
+        Some(line) if line.starts_with("INFO") => max_payload(&line),
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected NATS greeting: {:?}", other),
+            ))
+        }
+    };
+    let connect = format!(
+        "CONNECT {{\"verbose\":false,\"pedantic\":false,\"name\":\"casper-node\",\
+        \"lang\":\"rust\",\"version\":\"{}\"}}\r\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    writer.write_all(connect.as_bytes()).await?;
+    info!("connected to NATS server");
+
+    if let Some(message) = pending.as_ref() {
+        write_message(&mut writer, message, max_payload).await?;
+        *pending = None;
+    }
+
+    loop {
+        select! {
+            maybe_message = message_receiver.recv() => match maybe_message {
+                Some(message) => {
+                    if let Err(error) = write_message(&mut writer, &message, max_payload).await {
+                        *pending = Some(message);
+                        return Err(error);
+                    }
+                }
+                None => return Ok(()),
+            },
+            maybe_line = lines.next_line() => match maybe_line? {
+                Some(line) if line.starts_with("PING") => writer.write_all(b"PONG\r\n").await?,
+                Some(line) if line.starts_with("-ERR") => warn!(%line, "NATS server error"),
+                Some(_) => (),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "NATS server closed the connection",
+                    ))
+                }
+            },
+        }
+    }
+}
+
+/// Returns the maximum payload size advertised in the server's `INFO` message.
+fn max_payload(info: &str) -> usize {
+    serde_json::from_str::<serde_json::Value>(info.trim_start_matches("INFO").trim())
+        .ok()
+        .and_then(|info| info.get("max_payload")?.as_u64())
+        .map_or(DEFAULT_MAX_PAYLOAD, |max_payload| {
+            cmp::max(max_payload, 1) as usize
+        })
+}
+
+/// Publishes the message, split into chunks if its payload exceeds `max_payload`.
+async fn write_message(
+    writer: &mut OwnedWriteHalf,
+    (subject, payload): &Message,
+    max_payload: usize,
+) -> io::Result<()> {
+    if payload.len() <= max_payload {
+        return write_pub(writer, subject, payload).await;
+    }
+    let count = (payload.len() + max_payload - 1) / max_payload;
+    for (index, chunk) in payload.chunks(max_payload).enumerate() {
+        let chunk_subject = format!("{}.chunk.{}.{}", subject, index, count);
+        write_pub(writer, &chunk_subject, chunk).await?;
+    }
+    Ok(())
+}
+
+async fn write_pub(writer: &mut OwnedWriteHalf, subject: &str, payload: &[u8]) -> io::Result<()> {
+    let header = format!("PUB {} {}\r\n", subject, payload.len());
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(payload).await?;
+    writer.write_all(b"\r\n").await
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncReadExt, net::TcpListener};
+
+    use super::*;
+    use crate::logging;
+
+    #[tokio::test]
+    async fn should_publish_selected_events() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = NatsConfig {
+            address: listener.local_addr().unwrap().to_string(),
+            subject_prefix: "test".to_string(),
+        };
+        let sink = NatsSink::spawn(&config);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream.write_all(b"INFO {}\r\n").await.unwrap();
+
+        let (deploy_accepted, _) = SseData::random_deploy_accepted(&mut rng);
+        sink.publish(0, &deploy_accepted);
+        sink.publish(1, &SseData::random_block_added(&mut rng));
+        drop(sink);
+
+        // Dropping the sink stops the task, closing the connection.
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+        let mut lines = received.split("\r\n");
+        assert!(lines.next().unwrap().starts_with("CONNECT "));
+        let header = lines.next().unwrap();
+        let payload = lines.next().unwrap();
+        assert_eq!(header, format!("PUB test.BlockAdded {}", payload.len()));
+        let value: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(value["id"], 1);
+        assert_eq!(lines.collect::<Vec<_>>(), vec![""]);
+    }
+
+    #[tokio::test]
+    async fn should_chunk_events_exceeding_max_payload() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = NatsConfig {
+            address: listener.local_addr().unwrap().to_string(),
+            subject_prefix: "test".to_string(),
+        };
+        let sink = NatsSink::spawn(&config);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream
+            .write_all(b"INFO {\"max_payload\":100}\r\n")
+            .await
+            .unwrap();
+
+        sink.publish(1, &SseData::random_block_added(&mut rng));
+        drop(sink);
+
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+        let mut lines = received.split("\r\n");
+        assert!(lines.next().unwrap().starts_with("CONNECT "));
+        let mut headers = vec![];
+        let mut payload = String::new();
+        while let Some(header) = lines.next().filter(|header| !header.is_empty()) {
+            let chunk = lines.next().unwrap();
+            assert!(chunk.len() <= 100);
+            headers.push(header.to_string());
+            payload.push_str(chunk);
+        }
+        let count = headers.len();
+        assert!(count > 1);
+        for (index, header) in headers.iter().enumerate() {
+            let expected_subject = format!("test.BlockAdded.chunk.{}.{}", index, count);
+            assert!(header.starts_with(&format!("PUB {} ", expected_subject)));
+        }
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(value["id"], 1);
+    }
+}
@@ -25,17 +25,19 @@ use tracing::{debug, error, info, warn};
 use warp::{
     addr,
     filters::BoxedFilter,
-    path,
+    header, path,
     reject::Rejection,
     reply::Response,
     sse::{self, Event as WarpServerSentEvent},
     Filter, Reply,
 };
 
+use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    account::AccountHash, AsymmetricType, EraId, ExecutionEffect, ExecutionResult,
+    ExecutionResultFormat, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
 };
 
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
@@ -44,15 +46,26 @@ use crate::{testing, types::Block};
 
 /// The URL root path.
 pub const SSE_API_ROOT_PATH: &str = "events";
-/// The URL path part to subscribe to all events other than `DeployAccepted`s and
-/// `FinalitySignature`s.
+/// The URL path part to subscribe to all events other than `DeployAccepted`s,
+/// `FinalitySignature`s, `DeployExecuted`s and `BlockExecutionFailed`s.
 pub const SSE_API_MAIN_PATH: &str = "main";
 /// The URL path part to subscribe to only `DeployAccepted` events.
 pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 /// The URL path part to subscribe to only `FinalitySignature` events.
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
+/// The URL path part to subscribe to only `DeployExecuted` and `BlockExecutionFailed` events.
+pub const SSE_API_EXECUTED_PATH: &str = "executed";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name restricting deploy events to those of the given account,
+/// provided as a hex-encoded public key or a formatted account hash.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name restricting deploy events to those of the given deploy hash.
+pub const DEPLOY_HASH_QUERY_FIELD: &str = "deploy_hash";
+/// The URL query string field name restricting deploy events to transfers if set to `true`.
+pub const TRANSFERS_ONLY_QUERY_FIELD: &str = "transfers_only";
+/// The header sent by reconnecting clients holding the ID of the last event they received.
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
 
 /// The filter associated with `/events/main` path.
 const MAIN_FILTER: [EventFilter; 5] = [
@@ -66,6 +79,11 @@ const MAIN_FILTER: [EventFilter; 5] = [
 const DEPLOYS_FILTER: [EventFilter; 1] = [EventFilter::DeployAccepted];
 /// The filter associated with `/events/sigs` path.
 const SIGNATURES_FILTER: [EventFilter; 1] = [EventFilter::FinalitySignature];
+/// The filter associated with `/events/executed` path.
+const EXECUTED_FILTER: [EventFilter; 2] = [
+    EventFilter::DeployExecuted,
+    EventFilter::BlockExecutionFailed,
+];
 
 /// The "id" field of the events sent on the event stream to clients.
 pub type Id = u32;
@@ -95,9 +113,27 @@ pub enum SseData {
         ttl: TimeDiff,
         dependencies: Vec<DeployHash>,
         block_hash: Box<BlockHash>,
+        #[serde(default, skip_serializing_if = "ExecutionResultFormat::is_full")]
+        execution_result_format: ExecutionResultFormat,
         #[data_size(skip)]
         execution_result: Box<ExecutionResult>,
     },
+    /// The given deploy has been executed as part of the block at the given height, which has not
+    /// been committed yet.  A `DeployProcessed` event follows once the block is complete, unless a
+    /// `BlockExecutionFailed` event for that block height follows instead.
+    DeployExecuted {
+        deploy_hash: Box<DeployHash>,
+        account: Box<PublicKey>,
+        era_id: EraId,
+        block_height: u64,
+        #[serde(default, skip_serializing_if = "ExecutionResultFormat::is_full")]
+        execution_result_format: ExecutionResultFormat,
+        #[data_size(skip)]
+        execution_result: Box<ExecutionResult>,
+    },
+    /// Executing the block at the given height failed, so any `DeployExecuted` events for it are
+    /// void.
+    BlockExecutionFailed { era_id: EraId, block_height: u64 },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
     /// Generic representation of validator's fault in an era.
@@ -125,6 +161,10 @@ impl SseData {
             SseData::BlockAdded { .. } => filter.contains(&EventFilter::BlockAdded),
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
+            SseData::DeployExecuted { .. } => filter.contains(&EventFilter::DeployExecuted),
+            SseData::BlockExecutionFailed { .. } => {
+                filter.contains(&EventFilter::BlockExecutionFailed)
+            }
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
@@ -133,6 +173,73 @@ impl SseData {
     }
 }
 
+/// Server-side restrictions on the deploy events streamed to a client, as requested via the URL
+/// query string.
+///
+/// Events not relating to a deploy are unaffected.  Deploy events which can't be checked against a
+/// restriction, e.g. `DeployExpired` events when an account is specified, are filtered out.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub(super) struct DeployFilter {
+    pub(super) account: Option<AccountHash>,
+    pub(super) deploy_hash: Option<DeployHash>,
+    pub(super) transfers_only: bool,
+}
+
+impl DeployFilter {
+    /// Returns whether the given event satisfies all the restrictions.
+    pub(super) fn matches(&self, data: &SseData) -> bool {
+        let (account, deploy_hash, is_transfer) = match data {
+            SseData::DeployAccepted { deploy } => (
+                Some(deploy.header().account().to_account_hash()),
+                *deploy.hash(),
+                Some(deploy.session().is_transfer()),
+            ),
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                ..
+            }
+            | SseData::DeployExecuted {
+                deploy_hash,
+                account,
+                execution_result,
+                ..
+            } => {
+                let transfers = match execution_result.as_ref() {
+                    ExecutionResult::Success { transfers, .. }
+                    | ExecutionResult::Failure { transfers, .. } => transfers,
+                };
+                (
+                    Some(account.to_account_hash()),
+                    **deploy_hash,
+                    Some(!transfers.is_empty()),
+                )
+            }
+            SseData::DeployExpired { deploy_hash } => (None, *deploy_hash, None),
+            SseData::ApiVersion(_)
+            | SseData::BlockAdded { .. }
+            | SseData::BlockExecutionFailed { .. }
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Step { .. }
+            | SseData::Shutdown => return true,
+        };
+
+        if let Some(required_account) = &self.account {
+            if account.as_ref() != Some(required_account) {
+                return false;
+            }
+        }
+        if let Some(required_deploy_hash) = &self.deploy_hash {
+            if deploy_hash != *required_deploy_hash {
+                return false;
+            }
+        }
+        !self.transfers_only || is_transfer == Some(true)
+    }
+}
+
 #[cfg(test)]
 impl SseData {
     /// Returns a random `SseData::ApiVersion`.
@@ -174,6 +281,28 @@ impl SseData {
             dependencies: deploy.header().dependencies().clone(),
             block_hash: Box::new(BlockHash::random(rng)),
             execution_result: Box::new(rng.gen()),
+            execution_result_format: ExecutionResultFormat::Full,
+        }
+    }
+
+    /// Returns a random `SseData::DeployExecuted`.
+    pub(super) fn random_deploy_executed(rng: &mut TestRng) -> Self {
+        let deploy = Deploy::random(rng);
+        SseData::DeployExecuted {
+            deploy_hash: Box::new(*deploy.hash()),
+            account: Box::new(deploy.header().account().clone()),
+            era_id: EraId::new(rng.gen()),
+            block_height: rng.gen(),
+            execution_result: Box::new(rng.gen()),
+            execution_result_format: ExecutionResultFormat::Full,
+        }
+    }
+
+    /// Returns a random `SseData::BlockExecutionFailed`.
+    pub(super) fn random_block_execution_failed(rng: &mut TestRng) -> Self {
+        SseData::BlockExecutionFailed {
+            era_id: EraId::new(rng.gen()),
+            block_height: rng.gen(),
         }
     }
 
@@ -269,6 +398,8 @@ pub(super) enum EventFilter {
     BlockAdded,
     DeployAccepted,
     DeployProcessed,
+    DeployExecuted,
+    BlockExecutionFailed,
     DeployExpired,
     Fault,
     FinalitySignature,
@@ -311,6 +442,8 @@ async fn filter_map_server_sent_event(
 
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
+        | &SseData::DeployExecuted { .. }
+        | &SseData::BlockExecutionFailed { .. }
         | &SseData::DeployExpired { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
@@ -341,40 +474,74 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
         SSE_API_MAIN_PATH => Some(&MAIN_FILTER[..]),
         SSE_API_DEPLOYS_PATH => Some(&DEPLOYS_FILTER[..]),
         SSE_API_SIGNATURES_PATH => Some(&SIGNATURES_FILTER[..]),
+        SSE_API_EXECUTED_PATH => Some(&EXECUTED_FILTER[..]),
         _ => None,
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the optional starting event ID and the deploy filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
-
-    if query.len() > 1 {
-        return Err(create_422());
+/// Returns a 422 response if `query` contains any field other than "start_from" mapped to a value
+/// representing an event ID, "account" mapped to a public key or account hash, "deploy_hash"
+/// mapped to a deploy hash, or "transfers_only" mapped to a bool.
+fn parse_query(query: HashMap<String, String>) -> Result<(Option<Id>, DeployFilter), Response> {
+    let mut start_from = None;
+    let mut deploy_filter = DeployFilter::default();
+    for (field, value) in query {
+        match field.as_str() {
+            QUERY_FIELD => {
+                start_from = Some(value.parse::<Id>().map_err(|_| create_422())?);
+            }
+            ACCOUNT_QUERY_FIELD => {
+                let account = AccountHash::from_formatted_str(&value)
+                    .ok()
+                    .or_else(|| {
+                        PublicKey::from_hex(&value)
+                            .ok()
+                            .map(|public_key| public_key.to_account_hash())
+                    })
+                    .ok_or_else(create_422)?;
+                deploy_filter.account = Some(account);
+            }
+            DEPLOY_HASH_QUERY_FIELD => {
+                let digest = Digest::from_hex(&value).map_err(|_| create_422())?;
+                deploy_filter.deploy_hash = Some(DeployHash::new(digest));
+            }
+            TRANSFERS_ONLY_QUERY_FIELD => {
+                deploy_filter.transfers_only = value.parse::<bool>().map_err(|_| create_422())?;
+            }
+            _ => return Err(create_422()),
+        }
     }
+    Ok((start_from, deploy_filter))
+}
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
+/// Extracts the starting event ID from the value of a `Last-Event-ID` header, i.e. the ID following
+/// the last one received by a reconnecting client.
+///
+/// Returns `None` if the header is absent or doesn't represent an event ID, in which case only new
+/// events are streamed to the client.
+fn parse_last_event_id(maybe_last_event_id: Option<String>) -> Option<Id> {
+    let last_event_id = maybe_last_event_id?;
+    match last_event_id.trim().parse::<Id>() {
+        Ok(id) => Some(id.wrapping_add(1)),
+        Err(_) => {
+            debug!(%last_event_id, "ignoring invalid {} header", LAST_EVENT_ID_HEADER);
+            None
+        }
     }
 }
 
 /// Creates a 404 response with a useful error message in the body.
 fn create_404() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}' or '/{root}/{sigs}'\n",
+        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}', '/{root}/{sigs}' or \
+        '/{root}/{executed}'\n",
         root = SSE_API_ROOT_PATH,
         main = SSE_API_MAIN_PATH,
         deploys = SSE_API_DEPLOYS_PATH,
-        sigs = SSE_API_SIGNATURES_PATH
+        sigs = SSE_API_SIGNATURES_PATH,
+        executed = SSE_API_EXECUTED_PATH
     )));
     *response.status_mut() = StatusCode::NOT_FOUND;
     response
@@ -384,8 +551,9 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEY OR ACCOUNT \
+        HASH>', '{}=<DEPLOY HASH>' and '{}=<BOOL>'\n",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD, TRANSFERS_ONLY_QUERY_FIELD
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -419,6 +587,7 @@ impl ChannelsAndFilter {
 
         let serve = move |path_param: String,
                           query: HashMap<String, String>,
+                          maybe_last_event_id: Option<String>,
                           maybe_remote_address: Option<SocketAddr>| {
             let remote_address = match maybe_remote_address {
                 Some(address) => address.to_string(),
@@ -441,8 +610,13 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            // An explicit "start_from" query takes precedence over the `Last-Event-ID` header sent
+            // by clients automatically when reconnecting.
+            let (start_from, deploy_filter) = match parse_query(query) {
+                Ok((Some(id), deploy_filter)) => (Some(id), deploy_filter),
+                Ok((None, deploy_filter)) => {
+                    (parse_last_event_id(maybe_last_event_id), deploy_filter)
+                }
                 Err(error_response) => return error_response,
             };
 
@@ -469,6 +643,7 @@ impl ChannelsAndFilter {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                deploy_filter,
                 remote_address,
             )))
             .into_response()
@@ -479,6 +654,7 @@ impl ChannelsAndFilter {
             .and(path::param::<String>())
             .and(path::end())
             .and(warp::query())
+            .and(header::optional::<String>(LAST_EVENT_ID_HEADER))
             .and(addr::remote())
             .map(serve)
             .or_else(|_| async move { Ok::<_, Rejection>((create_404(),)) })
@@ -505,11 +681,13 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
+/// skipped, and a `DeployFilter` which causes deploy events not matching the client's query to be
 /// skipped.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    deploy_filter: DeployFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
@@ -548,7 +726,7 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // `deploy_filter` and `event_filter`.
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -557,6 +735,12 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
+        .filter(move |result| {
+            future::ready(match result {
+                Ok(event) => deploy_filter.matches(&event.data),
+                Err(_) => true,
+            })
+        })
         .filter_map(move |result| async move {
             match result {
                 Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
@@ -618,6 +802,14 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_deploy_processed(&mut rng),
         };
+        let deploy_executed = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_deploy_executed(&mut rng),
+        };
+        let block_execution_failed = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_block_execution_failed(&mut rng),
+        };
         let deploy_expired = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_deploy_expired(&mut rng),
@@ -639,7 +831,8 @@ mod tests {
             data: SseData::Shutdown,
         };
 
-        // `EventFilter::Main` should only filter out `DeployAccepted`s and `FinalitySignature`s.
+        // `EventFilter::Main` should only filter out `DeployAccepted`s, `FinalitySignature`s and
+        // the events of the opt-in `executed` stream.
         should_not_filter_out(&api_version, &MAIN_FILTER[..]).await;
         should_not_filter_out(&block_added, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..]).await;
@@ -650,6 +843,8 @@ mod tests {
 
         should_filter_out(&deploy_accepted, &MAIN_FILTER[..]).await;
         should_filter_out(&finality_signature, &MAIN_FILTER[..]).await;
+        should_filter_out(&deploy_executed, &MAIN_FILTER[..]).await;
+        should_filter_out(&block_execution_failed, &MAIN_FILTER[..]).await;
 
         // `EventFilter::DeployAccepted` should filter out everything except `ApiVersion`s and
         // `DeployAccepted`s.
@@ -676,6 +871,21 @@ mod tests {
         should_filter_out(&deploy_expired, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..]).await;
+
+        // `EventFilter::Executed` should filter out everything except `ApiVersion`s,
+        // `DeployExecuted`s and `BlockExecutionFailed`s.
+        should_not_filter_out(&api_version, &EXECUTED_FILTER[..]).await;
+        should_not_filter_out(&deploy_executed, &EXECUTED_FILTER[..]).await;
+        should_not_filter_out(&block_execution_failed, &EXECUTED_FILTER[..]).await;
+        should_not_filter_out(&shutdown, &EXECUTED_FILTER[..]).await;
+
+        should_filter_out(&block_added, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&deploy_accepted, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&deploy_processed, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&deploy_expired, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&fault, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&finality_signature, &EXECUTED_FILTER[..]).await;
+        should_filter_out(&step, &EXECUTED_FILTER[..]).await;
     }
 
     /// This test checks that events with incorrect IDs (i.e. no types have an ID except for
@@ -729,6 +939,7 @@ mod tests {
             &MAIN_FILTER[..],
             &DEPLOYS_FILTER[..],
             &SIGNATURES_FILTER[..],
+            &EXECUTED_FILTER[..],
         ] {
             should_filter_out(&malformed_api_version, filter).await;
             should_filter_out(&malformed_block_added, filter).await;
@@ -855,6 +1066,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                DeployFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
@@ -920,4 +1132,64 @@ mod tests {
     async fn should_filter_duplicate_signature_events() {
         should_filter_duplicate_events(SSE_API_SIGNATURES_PATH).await
     }
+
+    #[test]
+    fn should_filter_deploy_events_by_query() {
+        let mut rng = crate::new_rng();
+
+        let transfer = Deploy::random_valid_native_transfer(&mut rng);
+        let transfer_accepted = SseData::DeployAccepted {
+            deploy: Arc::new(transfer.clone()),
+        };
+        let deploy_processed = SseData::random_deploy_processed(&mut rng);
+        let deploy_expired = SseData::random_deploy_expired(&mut rng);
+        let block_added = SseData::random_block_added(&mut rng);
+
+        // An empty filter matches everything.
+        let no_filter = DeployFilter::default();
+        for data in [
+            &transfer_accepted,
+            &deploy_processed,
+            &deploy_expired,
+            &block_added,
+        ] {
+            assert!(no_filter.matches(data));
+        }
+
+        // Filtering by account only matches deploy events of that account.
+        let mut query = HashMap::new();
+        let _ = query.insert(
+            ACCOUNT_QUERY_FIELD.to_string(),
+            transfer.header().account().to_hex(),
+        );
+        let (start_from, account_filter) = parse_query(query).unwrap();
+        assert!(start_from.is_none());
+        assert!(account_filter.matches(&transfer_accepted));
+        assert!(!account_filter.matches(&deploy_processed));
+        assert!(!account_filter.matches(&deploy_expired));
+        assert!(account_filter.matches(&block_added));
+
+        // Filtering by deploy hash only matches deploy events of that deploy.
+        let mut query = HashMap::new();
+        let _ = query.insert(QUERY_FIELD.to_string(), "3".to_string());
+        let _ = query.insert(
+            DEPLOY_HASH_QUERY_FIELD.to_string(),
+            format!("{:x}", transfer.hash().inner()),
+        );
+        let (start_from, deploy_hash_filter) = parse_query(query).unwrap();
+        assert_eq!(start_from, Some(3));
+        assert!(deploy_hash_filter.matches(&transfer_accepted));
+        assert!(!deploy_hash_filter.matches(&deploy_processed));
+        assert!(!deploy_hash_filter.matches(&deploy_expired));
+        assert!(deploy_hash_filter.matches(&block_added));
+
+        // Filtering to transfers only matches deploy events known to be transfers.
+        let transfers_filter = DeployFilter {
+            transfers_only: true,
+            ..DeployFilter::default()
+        };
+        assert!(transfers_filter.matches(&transfer_accepted));
+        assert!(!transfers_filter.matches(&deploy_expired));
+        assert!(transfers_filter.matches(&block_added));
+    }
 }
@@ -27,9 +27,10 @@ use casper_types::testing::TestRng;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD, QUERY_FIELD,
+    SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_EXECUTED_PATH as EXECUTED_PATH,
     SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    SSE_API_SIGNATURES_PATH as SIGS_PATH, TRANSFERS_ONLY_QUERY_FIELD,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -199,7 +200,7 @@ struct TestFixture {
 impl TestFixture {
     /// Constructs a new `TestFixture` including `EVENT_COUNT` random events ready to be served.
     fn new(rng: &mut TestRng) -> Self {
-        const DISTINCT_EVENTS_COUNT: u32 = 7;
+        const DISTINCT_EVENTS_COUNT: u32 = 9;
 
         let _ = logging::init();
         let storage_dir = tempfile::tempdir().unwrap();
@@ -220,6 +221,8 @@ impl TestFixture {
                 4 => SseData::random_fault(rng),
                 5 => SseData::random_step(rng),
                 6 => SseData::random_finality_signature(rng),
+                7 => SseData::random_deploy_executed(rng),
+                8 => SseData::random_block_execution_failed(rng),
                 _ => unreachable!(),
             })
             .collect();
@@ -265,7 +268,7 @@ impl TestFixture {
             ..Default::default()
         };
         let mut server = EventStreamServer::new(
-            config,
+            WithDir::new(self.storage_dir.path(), config),
             self.storage_dir.path().to_path_buf(),
             self.protocol_version,
         );
@@ -619,6 +622,11 @@ async fn should_serve_signature_events_with_no_query() {
     should_serve_events_with_no_query(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_executed_events_with_no_query() {
+    should_serve_events_with_no_query(EXECUTED_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected just before event ID 50
@@ -700,6 +708,40 @@ async fn should_serve_remaining_signature_events_with_query() {
     should_serve_remaining_events_with_query(SIGS_PATH).await;
 }
 
+/// Client setup:
+///   * `<IP:port>/events/main` with header `Last-Event-ID: 24`
+///   * connected just before event ID 50
+///
+/// Expected to receive main events from ID 25 onwards, as a reconnecting client should resume from
+/// the event following the last one it received.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_resume_events_from_last_event_id() {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let connect_at_event_id = BUFFER_LENGTH;
+    let last_event_id = BUFFER_LENGTH / 2 - 1;
+
+    let mut server_behavior = ServerBehavior::new();
+    let barrier = server_behavior.add_client_sync_before_event(connect_at_event_id);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let url = url(server_address, MAIN_PATH, None);
+    let (expected_events, final_id) = fixture.filtered_events(MAIN_PATH, last_event_id + 1);
+    barrier.wait().await;
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("Last-Event-ID", last_event_id.to_string())
+        .send()
+        .await
+        .unwrap();
+    barrier.wait().await;
+    let received_events = handle_response(response, final_id, "client").await.unwrap();
+    fixture.stop_server().await;
+
+    assert_eq!(received_events, expected_events);
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected before first event
@@ -910,8 +952,8 @@ async fn should_handle_bad_url_path() {
     ];
 
     let expected_body = format!(
-        "invalid path: expected '/{0}/{1}', '/{0}/{2}' or '/{0}/{3}'",
-        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH
+        "invalid path: expected '/{0}/{1}', '/{0}/{2}', '/{0}/{3}' or '/{0}/{4}'",
+        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH, EXECUTED_PATH
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -955,11 +997,15 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-an-account", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=not-a-hash", deploys_url, DEPLOY_HASH_QUERY_FIELD),
+        format!("{}?{}=yes", main_url, TRANSFERS_ONLY_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEY OR ACCOUNT \
+        HASH>', '{}=<DEPLOY HASH>' and '{}=<BOOL>'",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD, TRANSFERS_ONLY_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
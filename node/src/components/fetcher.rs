@@ -1,4 +1,5 @@
 mod config;
+mod decode_failures;
 mod error;
 mod event;
 mod fetch_item;
@@ -15,10 +16,12 @@ use std::{collections::HashMap, fmt::Debug, time::Duration};
 
 use datasize::DataSize;
 use prometheus::Registry;
-use tracing::trace;
+use tracing::{trace, warn};
+
+use casper_types::Timestamp;
 
 use crate::{
-    components::Component,
+    components::{network::blocklist::BlocklistJustification, Component},
     effect::{
         announcements::{
             FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement,
@@ -37,6 +40,7 @@ use crate::{
 };
 
 pub(crate) use config::Config;
+use decode_failures::DecodeFailures;
 pub(crate) use error::Error;
 pub(crate) use event::Event;
 pub(crate) use fetch_item::{EmptyValidationMetadata, FetchItem};
@@ -58,7 +62,10 @@ where
     T: FetchItem,
 {
     get_from_peer_timeout: Duration,
+    max_decode_failures_per_peer: u32,
     item_handles: HashMap<T::Id, HashMap<NodeId, ItemHandle<T>>>,
+    /// The number of undecodable responses recently received from each peer.
+    decode_failures: DecodeFailures,
     #[data_size(skip)]
     name: &'static str,
     #[data_size(skip)]
@@ -73,11 +80,27 @@ impl<T: FetchItem> Fetcher<T> {
     ) -> Result<Self, prometheus::Error> {
         Ok(Fetcher {
             get_from_peer_timeout: config.get_from_peer_timeout().into(),
+            max_decode_failures_per_peer: config.max_decode_failures_per_peer(),
             item_handles: HashMap::new(),
+            decode_failures: DecodeFailures::default(),
             name,
             metrics: Metrics::new(name, registry)?,
         })
     }
+
+    /// Applies a reloaded config, affecting subsequent fetches only.
+    pub(crate) fn update_config(&mut self, config: &Config) {
+        self.get_from_peer_timeout = config.get_from_peer_timeout().into();
+        self.max_decode_failures_per_peer = config.max_decode_failures_per_peer();
+    }
+
+    /// Records an undecodable response from `peer`, returning whether the peer has exceeded the
+    /// number of allowed decode failures.
+    fn record_decode_failure(&mut self, peer: NodeId) -> bool {
+        self.metrics.decode_failures.inc();
+        self.decode_failures
+            .record(peer, Timestamp::now(), self.max_decode_failures_per_peer)
+    }
 }
 
 impl<T, REv> Component<REv> for Fetcher<T>
@@ -136,6 +159,9 @@ where
                 }
                 Source::Client | Source::SpeculativeExec(_) | Source::Ourself => Effects::new(),
             },
+            Event::GotValidated { item, peer, result } => {
+                self.got_validated_from_peer(effect_builder, peer, item, result)
+            }
             Event::GotInvalidRemotely { .. } => Effects::new(),
             Event::AbsentRemotely { id, peer } => {
                 trace!(TAG=%T::TAG, %id, %peer, "item absent on the remote node");
@@ -159,6 +185,21 @@ where
                     peer,
                 )
             }
+            Event::UndecodableRemotely { peer } => {
+                let mut effects = self.got_undecodable_from_peer(peer);
+                if self.record_decode_failure(peer) {
+                    warn!(TAG=%T::TAG, %peer, "too many undecodable responses from peer");
+                    effects.extend(
+                        effect_builder
+                            .announce_block_peer_with_justification(
+                                peer,
+                                BlocklistJustification::SentBadItem { tag: T::TAG },
+                            )
+                            .ignore(),
+                    );
+                }
+                effects
+            }
             Event::TimeoutPeer { id, peer } => self.signal(
                 id.clone(),
                 Err(Error::TimedOut {
@@ -6,11 +6,18 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_GET_FROM_PEER_TIMEOUT: &str = "3sec";
+const DEFAULT_MAX_DECODE_FAILURES_PER_PEER: u32 = 3;
+
+fn default_max_decode_failures_per_peer() -> u32 {
+    DEFAULT_MAX_DECODE_FAILURES_PER_PEER
+}
 
 /// Configuration options for fetching.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     get_from_peer_timeout: TimeDiff,
+    #[serde(default = "default_max_decode_failures_per_peer")]
+    max_decode_failures_per_peer: u32,
 }
 
 impl Config {
@@ -18,12 +25,18 @@ impl Config {
     pub fn get_from_peer_timeout(&self) -> TimeDiff {
         self.get_from_peer_timeout
     }
+
+    /// Returns the number of undecodable responses after which a peer is blocked.
+    pub fn max_decode_failures_per_peer(&self) -> u32 {
+        self.max_decode_failures_per_peer
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             get_from_peer_timeout: TimeDiff::from_str(DEFAULT_GET_FROM_PEER_TIMEOUT).unwrap(),
+            max_decode_failures_per_peer: DEFAULT_MAX_DECODE_FAILURES_PER_PEER,
         }
     }
 }
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use datasize::DataSize;
+
+use casper_types::{TimeDiff, Timestamp};
+
+use crate::types::NodeId;
+
+/// How long an undecodable response counts against the peer which sent it.
+const DECODE_FAILURE_EXPIRY: TimeDiff = TimeDiff::from_seconds(600);
+
+/// The number of undecodable responses a fetcher recently received from each peer.
+///
+/// A peer's count is dropped once `DECODE_FAILURE_EXPIRY` has passed since its latest failure, so
+/// only peers which sent an undecodable response lately are held.
+#[derive(DataSize, Debug, Default)]
+pub(super) struct DecodeFailures {
+    /// The number of failures of each peer, and the time of its latest one.
+    by_peer: HashMap<NodeId, (u32, Timestamp)>,
+}
+
+impl DecodeFailures {
+    /// Records an undecodable response from `peer` received at `now`, returning whether the peer
+    /// has reached `max_per_peer` failures. The count of a peer which reached the limit is reset.
+    pub(super) fn record(&mut self, peer: NodeId, now: Timestamp, max_per_peer: u32) -> bool {
+        self.by_peer
+            .retain(|_, (_, latest)| now.saturating_diff(*latest) < DECODE_FAILURE_EXPIRY);
+        let (failures, latest) = self.by_peer.entry(peer).or_insert((0, now));
+        *failures += 1;
+        *latest = now;
+        if *failures < max_per_peer {
+            return false;
+        }
+        self.by_peer.remove(&peer);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_block_after_max_failures() {
+        let mut rng = TestRng::new();
+        let peer = NodeId::random(&mut rng);
+        let mut decode_failures = DecodeFailures::default();
+        let now = Timestamp::now();
+
+        assert!(!decode_failures.record(peer, now, 3));
+        assert!(!decode_failures.record(peer, now, 3));
+        assert!(decode_failures.record(peer, now, 3));
+
+        // The count starts over once the limit was reached.
+        assert!(!decode_failures.record(peer, now, 3));
+    }
+
+    #[test]
+    fn should_forget_expired_failures() {
+        let mut rng = TestRng::new();
+        let peer = NodeId::random(&mut rng);
+        let other_peer = NodeId::random(&mut rng);
+        let mut decode_failures = DecodeFailures::default();
+        let now = Timestamp::now();
+
+        assert!(!decode_failures.record(peer, now, 2));
+        assert!(!decode_failures.record(other_peer, now, 2));
+
+        // Both peers' failures have expired, so neither is held any longer.
+        let later = now + DECODE_FAILURE_EXPIRY;
+        assert!(!decode_failures.record(peer, later, 2));
+        assert!(!decode_failures.by_peer.contains_key(&other_peer));
+        assert!(decode_failures.record(peer, later, 2));
+    }
+}
@@ -16,6 +16,9 @@ pub(crate) enum Error<T: FetchItem> {
     #[error("timed out getting item with id {id:?} from peer {peer:?}")]
     TimedOut { id: Box<T::Id>, peer: NodeId },
 
+    #[error("could not decode response from peer {peer:?} while fetching item with id {id:?}")]
+    Undecodable { id: Box<T::Id>, peer: NodeId },
+
     #[error("could not construct get request for item with id {id:?} for peer {peer:?}")]
     CouldNotConstructGetRequest { id: Box<T::Id>, peer: NodeId },
 
@@ -35,7 +38,10 @@ impl<T: FetchItem> Error<T> {
     pub(crate) fn is_peer_fault(&self) -> bool {
         match self {
             // The peer claimed to have the item, so it should not be absent.
-            Error::Absent { .. } | Error::Rejected { .. } | Error::TimedOut { .. } => true,
+            Error::Absent { .. }
+            | Error::Rejected { .. }
+            | Error::TimedOut { .. }
+            | Error::Undecodable { .. } => true,
             Error::CouldNotConstructGetRequest { .. }
             | Error::ValidationMetadataMismatch { .. } => false,
         }
@@ -46,6 +52,7 @@ impl<T: FetchItem> Error<T> {
             Error::Absent { id, .. } => id,
             Error::Rejected { id, .. } => id,
             Error::TimedOut { id, .. } => id,
+            Error::Undecodable { id, .. } => id,
             Error::CouldNotConstructGetRequest { id, .. } => id,
             Error::ValidationMetadataMismatch { id, .. } => id,
         }
@@ -56,6 +63,7 @@ impl<T: FetchItem> Error<T> {
             Error::Absent { peer, .. }
             | Error::Rejected { peer, .. }
             | Error::TimedOut { peer, .. }
+            | Error::Undecodable { peer, .. }
             | Error::CouldNotConstructGetRequest { peer, .. }
             | Error::ValidationMetadataMismatch { peer, .. } => peer,
         }
@@ -75,6 +83,7 @@ where
             Error::Absent { id, .. }
             | Error::Rejected { id, .. }
             | Error::TimedOut { id, .. }
+            | Error::Undecodable { id, .. }
             | Error::CouldNotConstructGetRequest { id, .. } => id.estimate_heap_size(),
             Error::ValidationMetadataMismatch {
                 id, current, new, ..
@@ -26,6 +26,12 @@ pub(crate) enum Event<T: FetchItem> {
     },
     /// An announcement from a different component that we have accepted and stored the given item.
     GotRemotely { item: Box<T>, source: Source },
+    /// The result of validating an item received from a peer off the reactor thread.
+    GotValidated {
+        item: Box<T>,
+        peer: NodeId,
+        result: Result<(), String>,
+    },
     /// The result of putting the item to storage.
     PutToStorage { item: Box<T>, peer: NodeId },
     /// A different component rejected an item.
@@ -36,25 +42,24 @@ pub(crate) enum Event<T: FetchItem> {
     AbsentRemotely { id: T::Id, peer: NodeId },
     /// An item was available on the remote peer, but it chose to not provide it.
     RejectedRemotely { id: T::Id, peer: NodeId },
+    /// A response from the remote peer could not be decoded.
+    UndecodableRemotely { peer: NodeId },
     /// The timeout has elapsed and we should clean up state.
     TimeoutPeer { id: T::Id, peer: NodeId },
 }
 
 impl<T: FetchItem> Event<T> {
-    pub(crate) fn from_get_response_serialized_item(
-        peer: NodeId,
-        serialized_item: &[u8],
-    ) -> Option<Self> {
+    pub(crate) fn from_get_response_serialized_item(peer: NodeId, serialized_item: &[u8]) -> Self {
         match bincode::deserialize::<FetchResponse<T, T::Id>>(serialized_item) {
-            Ok(FetchResponse::Fetched(item)) => Some(Event::GotRemotely {
+            Ok(FetchResponse::Fetched(item)) => Event::GotRemotely {
                 item: Box::new(item),
                 source: Source::Peer(peer),
-            }),
-            Ok(FetchResponse::NotFound(id)) => Some(Event::AbsentRemotely { id, peer }),
-            Ok(FetchResponse::NotProvided(id)) => Some(Event::RejectedRemotely { id, peer }),
+            },
+            Ok(FetchResponse::NotFound(id)) => Event::AbsentRemotely { id, peer },
+            Ok(FetchResponse::NotProvided(id)) => Event::RejectedRemotely { id, peer },
             Err(error) => {
                 error!("failed to decode {:?} from {}: {:?}", T::TAG, peer, error);
-                None
+                Event::UndecodableRemotely { peer }
             }
         }
     }
@@ -77,7 +82,7 @@ impl From<DeployAcceptorAnnouncement> for Event<Deploy> {
                     source,
                 }
             }
-            DeployAcceptorAnnouncement::InvalidDeploy { deploy, source } => {
+            DeployAcceptorAnnouncement::InvalidDeploy { deploy, source, .. } => {
                 Event::GotInvalidRemotely {
                     id: deploy.fetch_id(),
                     source,
@@ -103,6 +108,16 @@ impl<T: FetchItem> Display for Event<T> {
             Event::GotRemotely { item, source } => {
                 write!(formatter, "got {} from {}", item.fetch_id(), source)
             }
+            Event::GotValidated { item, peer, result } => match result {
+                Ok(()) => write!(formatter, "validated {} from {}", item.fetch_id(), peer),
+                Err(error) => write!(
+                    formatter,
+                    "invalid {} from {}: {}",
+                    item.fetch_id(),
+                    peer,
+                    error
+                ),
+            },
             Event::GotInvalidRemotely { id, source } => {
                 write!(formatter, "invalid item {} from {}", id, source)
             }
@@ -121,6 +136,9 @@ impl<T: FetchItem> Display for Event<T> {
                     id, peer
                 )
             }
+            Event::UndecodableRemotely { peer } => {
+                write!(formatter, "could not decode response from {}", peer)
+            }
             Event::PutToStorage { item, .. } => {
                 write!(formatter, "item {} was put to storage", item.fetch_id())
             }
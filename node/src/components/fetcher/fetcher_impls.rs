@@ -5,5 +5,6 @@ mod block_header_fetcher;
 mod deploy_fetcher;
 mod finality_signature_fetcher;
 mod legacy_deploy_fetcher;
+mod signed_era_validators_fetcher;
 mod sync_leap_fetcher;
 mod trie_or_chunk_fetcher;
@@ -0,0 +1,64 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use futures::FutureExt;
+
+use casper_types::EraId;
+
+use crate::{
+    components::fetcher::{metrics::Metrics, Fetcher, ItemFetcher, ItemHandle, StoringState},
+    effect::{requests::StorageRequest, EffectBuilder},
+    types::{NodeId, SignedEraValidators},
+};
+
+#[async_trait]
+impl ItemFetcher<SignedEraValidators> for Fetcher<SignedEraValidators> {
+    // Era validators are only as trustworthy as the signatures of the switch block, so only the
+    // responder for the peer that provided them may be answered.
+    const SAFE_TO_RESPOND_TO_ALL: bool = false;
+
+    fn item_handles(
+        &mut self,
+    ) -> &mut HashMap<EraId, HashMap<NodeId, ItemHandle<SignedEraValidators>>> {
+        &mut self.item_handles
+    }
+
+    fn metrics(&mut self) -> &Metrics {
+        &self.metrics
+    }
+
+    fn peer_timeout(&self) -> Duration {
+        self.get_from_peer_timeout
+    }
+
+    async fn get_locally<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        id: EraId,
+    ) -> Option<SignedEraValidators> {
+        // The validators of an era are recorded in the switch block of the previous era.
+        let switch_block_era_id = id.predecessor()?;
+        effect_builder
+            .get_switch_block_header_with_metadata_from_storage(switch_block_era_id)
+            .await
+            .map(SignedEraValidators::new)
+    }
+
+    fn put_to_storage<'a, REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item: SignedEraValidators,
+    ) -> StoringState<'a, SignedEraValidators> {
+        StoringState::Enqueued(
+            effect_builder
+                .put_block_header_with_metadata_to_storage(Box::new(item.into_switch_block()))
+                .map(|_| ())
+                .boxed(),
+        )
+    }
+
+    async fn announce_fetched_new_item<REv: Send>(
+        _effect_builder: EffectBuilder<REv>,
+        _item: SignedEraValidators,
+        _peer: NodeId,
+    ) {
+    }
+}
@@ -92,7 +92,7 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
         <T as FetchItem>::Id: 'static,
         REv: From<NetworkRequest<Message>> + Send,
     {
-        let peer_timeout = self.peer_timeout();
+        let (timer, timeout) = effect_builder.set_cancellable_timeout(self.peer_timeout());
         // Capture responder for later signalling.
         let item_handles = self.item_handles();
         match item_handles.entry(id.clone()).or_default().entry(peer) {
@@ -108,10 +108,10 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
                     error!(%error, "failed to fetch");
                     return responder.respond(Err(error)).ignore();
                 }
-                handle.push_responder(responder);
+                handle.push_responder(responder, timer);
             }
             Entry::Vacant(entry) => {
-                entry.insert(ItemHandle::new(validation_metadata, responder));
+                entry.insert(ItemHandle::new(validation_metadata, responder, timer));
             }
         }
         match Message::new_get_request::<T>(&id) {
@@ -119,10 +119,10 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
                 self.metrics().fetch_total.inc();
                 async move {
                     effect_builder.send_message(peer, message).await;
-                    effect_builder.set_timeout(peer_timeout).await
+                    timeout.await
                 }
             }
-            .event(move |_| Event::TimeoutPeer { id, peer }),
+            .events(move |elapsed| elapsed.map(|_| Event::TimeoutPeer { id, peer }).into_iter()),
             Err(error) => {
                 error!(%peer, %error, "failed to construct get request");
 
@@ -161,14 +161,38 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
             }
         };
 
-        if let Err(err) = item.validate(validation_metadata) {
-            debug!(%peer, %err, ?item, "peer sent invalid item");
+        // Validating an item can involve verifying many signatures, so it is done off the reactor
+        // thread.
+        let validation_metadata = validation_metadata.clone();
+        effect_builder
+            .run_blocking(move || {
+                let result = item
+                    .validate(&validation_metadata)
+                    .map_err(|err| err.to_string());
+                (item, result)
+            })
+            .event(move |(item, result)| Event::GotValidated { item, peer, result })
+    }
+
+    /// The item received from `peer` has been validated.
+    fn got_validated_from_peer<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        peer: NodeId,
+        item: Box<T>,
+        result: Result<(), String>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<StorageRequest> + From<PeerBehaviorAnnouncement> + Send,
+    {
+        if let Err(error_msg) = result {
+            debug!(%peer, %error_msg, ?item, "peer sent invalid item");
             effect_builder
                 .announce_block_peer_with_justification(
                     peer,
                     BlocklistJustification::SentInvalidItem {
                         tag: T::TAG,
-                        error_msg: err.to_string(),
+                        error_msg,
                     },
                 )
                 .ignore()
@@ -182,6 +206,29 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
         }
     }
 
+    /// Fails all fetches waiting on `peer`, as its response could not be decoded and it is unknown
+    /// which item it was meant to be.
+    fn got_undecodable_from_peer(&mut self, peer: NodeId) -> Effects<Event<T>> {
+        let ids: Vec<T::Id> = self
+            .item_handles()
+            .iter()
+            .filter(|(_, item_handles)| item_handles.contains_key(&peer))
+            .map(|(id, _)| id.clone())
+            .collect();
+        let mut effects = Effects::new();
+        for id in ids {
+            effects.extend(self.signal(
+                id.clone(),
+                Err(Error::Undecodable {
+                    id: Box::new(id),
+                    peer,
+                }),
+                peer,
+            ));
+        }
+        effects
+    }
+
     /// Sends fetched data to all responders
     fn respond_to_all(&mut self, id: T::Id, fetched_data: FetchedData<T>) -> Effects<Event<T>> {
         let mut effects = Effects::new();
@@ -243,6 +290,7 @@ pub(super) trait ItemFetcher<T: FetchItem + 'static> {
             Err(
                 error @ Error::Absent { .. }
                 | error @ Error::Rejected { .. }
+                | error @ Error::Undecodable { .. }
                 | error @ Error::CouldNotConstructGetRequest { .. }
                 | error @ Error::ValidationMetadataMismatch { .. },
             ) => {
@@ -1,6 +1,7 @@
 use datasize::DataSize;
 
 use super::{FetchItem, FetchResponder};
+use crate::effect::TimerHandle;
 
 #[derive(Debug, DataSize)]
 pub(crate) struct ItemHandle<T>
@@ -9,16 +10,20 @@ where
 {
     validation_metadata: Box<T::ValidationMetadata>,
     responders: Vec<FetchResponder<T>>,
+    /// Timers of the outstanding requests, cancelled once the responders are taken.
+    timers: Vec<TimerHandle>,
 }
 
 impl<T: FetchItem> ItemHandle<T> {
     pub(super) fn new(
         validation_metadata: Box<T::ValidationMetadata>,
         responder: FetchResponder<T>,
+        timer: TimerHandle,
     ) -> Self {
         Self {
             validation_metadata,
             responders: vec![responder],
+            timers: vec![timer],
         }
     }
 
@@ -26,8 +31,9 @@ impl<T: FetchItem> ItemHandle<T> {
         &self.validation_metadata
     }
 
-    pub(super) fn push_responder(&mut self, responder: FetchResponder<T>) {
-        self.responders.push(responder)
+    pub(super) fn push_responder(&mut self, responder: FetchResponder<T>, timer: TimerHandle) {
+        self.responders.push(responder);
+        self.timers.push(timer);
     }
 
     pub(super) fn pop_front_responder(&mut self) -> Option<FetchResponder<T>> {
@@ -38,6 +44,7 @@ impl<T: FetchItem> ItemHandle<T> {
     }
 
     pub(super) fn take_responders(self) -> Vec<FetchResponder<T>> {
+        self.timers.iter().for_each(TimerHandle::cancel);
         self.responders
     }
 
@@ -10,6 +10,8 @@ pub(crate) struct Metrics {
     pub found_on_peer: IntCounter,
     /// Number of fetch requests that timed out.
     pub timeouts: IntCounter,
+    /// Number of responses from peers that could not be decoded.
+    pub decode_failures: IntCounter,
     /// Number of total fetch requests made.
     pub fetch_total: IntCounter,
     /// Reference to the registry for unregistering.
@@ -33,6 +35,10 @@ impl Metrics {
             format!("{}_timeouts", name),
             format!("number of {} fetch requests that timed out", name),
         )?;
+        let decode_failures = IntCounter::new(
+            format!("{}_decode_failures", name),
+            format!("number of {} responses that could not be decoded", name),
+        )?;
         let fetch_total = IntCounter::new(
             format!("{}_fetch_total", name),
             format!("number of {} all fetch requests made", name),
@@ -40,12 +46,14 @@ impl Metrics {
         registry.register(Box::new(found_in_storage.clone()))?;
         registry.register(Box::new(found_on_peer.clone()))?;
         registry.register(Box::new(timeouts.clone()))?;
+        registry.register(Box::new(decode_failures.clone()))?;
         registry.register(Box::new(fetch_total.clone()))?;
 
         Ok(Metrics {
             found_in_storage,
             found_on_peer,
             timeouts,
+            decode_failures,
             fetch_total,
             registry: registry.clone(),
         })
@@ -57,6 +65,7 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.found_in_storage);
         unregister_metric!(self.registry, self.found_on_peer);
         unregister_metric!(self.registry, self.timeouts);
+        unregister_metric!(self.registry, self.decode_failures);
         unregister_metric!(self.registry, self.fetch_total);
     }
 }
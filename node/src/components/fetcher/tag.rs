@@ -53,4 +53,7 @@ pub enum Tag {
     /// The execution results for a single block.
     #[display(fmt = "block execution results")]
     BlockExecutionResults,
+    /// The validators of an era, proven by the preceding switch block and its signatures.
+    #[display(fmt = "era validators")]
+    EraValidators,
 }
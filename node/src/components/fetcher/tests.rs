@@ -33,7 +33,7 @@ use crate::{
     },
     fatal,
     protocol::Message,
-    reactor::{self, EventQueueHandle, Reactor as ReactorTrait, ReactorEvent, Runner},
+    reactor::{self, EventQueueHandle, QueueKind, Reactor as ReactorTrait, ReactorEvent, Runner},
     testing::{
         self,
         network::{NetworkedReactor, TestingNetwork},
@@ -250,10 +250,11 @@ impl ReactorTrait for Reactor {
                 self.storage
                     .handle_event(effect_builder, rng, request.into()),
             ),
+            // Blocked peers are checked for by the tests themselves.
+            Event::BlocklistAnnouncement(_) => Effects::new(),
             Event::TrieDemand(_)
             | Event::ContractRuntimeRequest(_)
             | Event::BlockAccumulatorRequest(_)
-            | Event::BlocklistAnnouncement(_)
             | Event::GossiperIncomingDeploy(_)
             | Event::GossiperIncomingBlock(_)
             | Event::GossiperIncomingFinalitySignature(_)
@@ -338,14 +339,16 @@ impl Reactor {
                         )
                         .ignore();
                     }
-                    Err(error) => {
-                        return fatal!(
-                            effect_builder,
-                            "failed to decode deploy from {}: {}",
+                    Err(_) => {
+                        let event = fetcher::Event::from_get_response_serialized_item(
                             response.sender,
-                            error
-                        )
-                        .ignore();
+                            serialized_item,
+                        );
+                        return self.dispatch_event(
+                            effect_builder,
+                            rng,
+                            Event::DeployFetcher(event),
+                        );
                     }
                 };
 
@@ -430,9 +433,29 @@ async fn store_deploy(
         .await;
 }
 
+/// Delivers a response which can't be decoded from `sender`.
+fn receive_undecodable_response(
+    sender: NodeId,
+) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
+    move |effect_builder: EffectBuilder<Event>| {
+        let response = NetResponseIncoming {
+            sender,
+            message: Box::new(NetResponse::Deploy(Arc::from(vec![255; 8]))),
+        };
+        effect_builder
+            .into_inner()
+            .schedule(
+                Event::NetResponseIncoming(response),
+                QueueKind::NetworkIncoming,
+            )
+            .ignore()
+    }
+}
+
 #[derive(Debug)]
 enum ExpectedFetchedDeployResult {
     TimedOut,
+    Undecodable,
     FromStorage {
         expected_deploy: Box<Deploy>,
     },
@@ -473,6 +496,12 @@ async fn assert_settled(
         // and the TestDeployAcceptor unconditionally accepts the deploy and stores it. For the
         // test, we don't care whether it was stored or not, just that the TimedOut event fired.
         (ExpectedFetchedDeployResult::TimedOut, Some(Err(fetcher::Error::TimedOut { .. })), _) => {}
+        // Undecodable case: the fetch fails as soon as the response is received.
+        (
+            ExpectedFetchedDeployResult::Undecodable,
+            Some(Err(fetcher::Error::Undecodable { .. })),
+            None,
+        ) => {}
         // FromStorage case: expect deploy to correspond to item fetched, as well as stored item
         (
             ExpectedFetchedDeployResult::FromStorage { expected_deploy },
@@ -684,3 +713,85 @@ async fn should_timeout_fetch_from_peer() {
 
     NetworkController::<Message>::remove_active();
 }
+
+#[tokio::test]
+async fn should_fail_fetch_on_undecodable_response_and_block_peer() {
+    const NETWORK_SIZE: usize = 1;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = TestingNetwork::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let deploy_id = deploy.fetch_id();
+    let requesting_node = node_ids[0];
+    // The peer isn't part of the network, so the only response is the one we inject.
+    let peer = NodeId::random(&mut rng);
+
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(deploy_id, peer, Arc::clone(&fetched)),
+        )
+        .await;
+    // Only a response to a request already sent to the peer can fail the fetch.
+    network
+        .crank_until(
+            &requesting_node,
+            &mut rng,
+            move |event: &Event| {
+                matches!(
+                    event,
+                    Event::NetworkRequestMessage(NetworkRequest::SendMessage { dest, .. })
+                        if **dest == peer
+                )
+            },
+            TIMEOUT,
+        )
+        .await;
+    network
+        .process_injected_effect_on(&requesting_node, receive_undecodable_response(peer))
+        .await;
+
+    // The fetch fails without waiting for the timeout.
+    assert_settled(
+        &requesting_node,
+        deploy_id,
+        ExpectedFetchedDeployResult::Undecodable,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    // The peer is blocked once it exceeds the allowed number of decode failures.
+    for _ in 1..Config::default().max_decode_failures_per_peer() {
+        network
+            .process_injected_effect_on(&requesting_node, receive_undecodable_response(peer))
+            .await;
+    }
+    network
+        .crank_until(
+            &requesting_node,
+            &mut rng,
+            move |event: &Event| {
+                matches!(
+                    event,
+                    Event::BlocklistAnnouncement(PeerBehaviorAnnouncement::OffenseCommitted {
+                        offender,
+                        ..
+                    }) if **offender == peer
+                )
+            },
+            TIMEOUT,
+        )
+        .await;
+
+    NetworkController::<Message>::remove_active();
+}
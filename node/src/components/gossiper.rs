@@ -11,8 +11,9 @@ mod provider_impls;
 mod tests;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fmt::{self, Debug, Formatter},
+    mem,
     time::Duration,
 };
 
@@ -26,7 +27,7 @@ use crate::{
         announcements::GossiperAnnouncement,
         incoming::GossiperIncoming,
         requests::{BeginGossipRequest, NetworkRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects, GossipTarget,
+        EffectBuilder, EffectExt, Effects, GossipTarget, TimerHandle,
     },
     types::NodeId,
     utils::Source,
@@ -50,6 +51,8 @@ where
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
     validate_and_store_timeout: Duration,
+    /// Timers checking that peers responded to our gossip, cancelled once gossiping finishes.
+    gossip_timers: HashMap<(T::Id, NodeId), TimerHandle>,
     name: &'static str,
     metrics: Metrics,
 }
@@ -69,11 +72,49 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             gossip_timeout: config.gossip_request_timeout().into(),
             get_from_peer_timeout: config.get_remainder_timeout().into(),
             validate_and_store_timeout: config.validate_and_store_timeout().into(),
+            gossip_timers: HashMap::new(),
             name,
             metrics: Metrics::new(name, registry)?,
         })
     }
 
+    /// Applies a reloaded config, affecting subsequent gossiping only.
+    pub(crate) fn update_config(&mut self, config: Config) {
+        self.table.update_config(config);
+        self.gossip_timeout = config.gossip_request_timeout().into();
+        self.get_from_peer_timeout = config.get_remainder_timeout().into();
+        self.validate_and_store_timeout = config.validate_and_store_timeout().into();
+    }
+
+    /// Discards all gossiping state, leaving the gossiper as if it had just been created.
+    pub(crate) fn reset(&mut self) {
+        self.table.clear();
+        self.gossip_timers
+            .drain()
+            .for_each(|(_, timer)| timer.cancel());
+    }
+
+    /// Cancels outstanding gossip timeouts for the given item and announces that gossiping it has
+    /// finished.
+    fn finish_gossiping<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        self.gossip_timers.retain(|(id, _), timer| {
+            if *id == item_id {
+                timer.cancel();
+                false
+            } else {
+                true
+            }
+        });
+        effect_builder.announce_finished_gossiping(item_id).ignore()
+    }
+
     /// This could be the first time we've encountered this item in the gossiper (e.g. the
     /// `Network` component requesting that we gossip an address, or the `DeployAcceptor` having
     /// accepted a deploy which we received from a client), or it could be the result of this
@@ -106,9 +147,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 )
             }
             GossipAction::Noop => Effects::new(),
-            GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
-            }
+            GossipAction::AnnounceFinished => self.finish_gossiping(effect_builder, item_id),
             GossipAction::GetRemainder { .. } | GossipAction::AwaitingRemainder => {
                 error!("can't be waiting for remainder since we hold the complete data");
                 Effects::new()
@@ -163,11 +202,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 .table
                 .reduce_in_flight_count(&item_id, requested_count - peers.len())
         {
-            effects.extend(
-                effect_builder
-                    .announce_finished_gossiping(item_id.clone())
-                    .ignore(),
-            );
+            effects.extend(self.finish_gossiping(effect_builder, item_id.clone()));
         }
 
         // Remember which peers we *tried* to infect.
@@ -176,12 +211,16 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
 
         // Set timeouts to check later that the specified peers all responded.
         for peer in peers {
+            let (timer, timeout) = effect_builder.set_cancellable_timeout(self.gossip_timeout);
+            if let Some(stale_timer) = self.gossip_timers.insert((item_id.clone(), peer), timer) {
+                stale_timer.cancel();
+            }
             let item_id = item_id.clone();
-            effects.extend(
-                effect_builder
-                    .set_timeout(self.gossip_timeout)
-                    .event(move |_| Event::CheckGossipTimeout { item_id, peer }),
-            )
+            effects.extend(timeout.events(move |elapsed| {
+                elapsed
+                    .map(|_| Event::CheckGossipTimeout { item_id, peer })
+                    .into_iter()
+            }))
         }
 
         effects
@@ -197,6 +236,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
     where
         REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
     {
+        self.gossip_timers.remove(&(item_id.clone(), peer));
         match self.table.check_timeout(&item_id, peer) {
             GossipAction::ShouldGossip(should_gossip) => Self::gossip(
                 effect_builder,
@@ -206,9 +246,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 should_gossip.exclude_peers,
             ),
             GossipAction::Noop => Effects::new(),
-            GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
-            }
+            GossipAction::AnnounceFinished => self.finish_gossiping(effect_builder, item_id),
             GossipAction::GetRemainder { .. } | GossipAction::AwaitingRemainder => {
                 warn!(
                     "can't have gossiped if we don't hold the complete data - likely the timeout \
@@ -255,9 +293,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 effects
             }
 
-            GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
-            }
+            GossipAction::AnnounceFinished => self.finish_gossiping(effect_builder, item_id),
 
             GossipAction::Noop | GossipAction::AwaitingRemainder => Effects::new(),
         }
@@ -339,11 +375,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 let mut effects = effect_builder.send_message(sender, reply).ignore();
 
                 if action == GossipAction::AnnounceFinished {
-                    effects.extend(
-                        effect_builder
-                            .announce_finished_gossiping(item_id.clone())
-                            .ignore(),
-                    );
+                    effects.extend(self.finish_gossiping(effect_builder, item_id.clone()));
                 }
 
                 effects
@@ -359,6 +391,40 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         effects
     }
 
+    /// Handles an incoming gossip request for a small item we aren't gossiping and which must be
+    /// confirmed before we gossip it onwards.
+    ///
+    /// The item is announced the first time we receive it, but isn't gossiped onwards until it is
+    /// confirmed via a `BeginGossipRequest`.
+    fn handle_unconfirmed_gossip<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        sender: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        let is_new = self.table.new_unconfirmed_data(&item_id);
+        let reply = Message::GossipResponse {
+            item_id: item_id.clone(),
+            is_already_held: !is_new,
+        };
+        let mut effects = effect_builder.send_message(sender, reply).ignore();
+        if is_new {
+            debug!(item=%item_id, %sender, "received gossip request for unconfirmed item");
+            self.metrics.items_received.inc();
+            effects.extend(
+                effect_builder
+                    .announce_complete_item_received_via_gossip(item_id)
+                    .ignore(),
+            );
+        } else {
+            trace!(item=%item_id, %sender, "received gossip request for known unconfirmed item");
+        }
+        effects
+    }
+
     /// Handles an incoming gossip response from a peer on the network.
     fn handle_gossip_response<REv>(
         &mut self,
@@ -384,6 +450,11 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             return effects;
         }
 
+        if self.table.has_timed_out(&item_id, &sender) {
+            debug!(item = %item_id, %sender, "got a gossip response after it timed out");
+            self.metrics.late_responders.inc();
+        }
+
         let action = if is_already_held {
             self.table.already_infected(&item_id, sender)
         } else {
@@ -414,7 +485,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             )),
             GossipAction::Noop => (),
             GossipAction::AnnounceFinished => {
-                effects.extend(effect_builder.announce_finished_gossiping(item_id).ignore())
+                effects.extend(self.finish_gossiping(effect_builder, item_id))
             }
             GossipAction::GetRemainder { .. } => {
                 error!("shouldn't try to get remainder as result of receiving a gossip response");
@@ -459,7 +530,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         );
 
         if self.table.force_finish(&item_id) {
-            return effect_builder.announce_finished_gossiping(item_id).ignore();
+            return self.finish_gossiping(effect_builder, item_id);
         }
 
         Effects::new()
@@ -535,13 +606,17 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         REv: From<GossiperAnnouncement<T>> + Send,
     {
         if self.table.finish_if_not_held_by_us(&item_id) {
-            return effect_builder.announce_finished_gossiping(item_id).ignore();
+            return self.finish_gossiping(effect_builder, item_id);
         }
         Effects::new()
     }
 
     /// Updates the gossiper metrics from the state of the gossip table.
-    fn update_gossip_table_metrics(&self) {
+    fn update_gossip_table_metrics(&mut self) {
+        for rounds in self.table.drain_finished_rounds() {
+            self.metrics.items_gossiped.inc();
+            self.metrics.rounds_to_saturation.observe(rounds as f64);
+        }
         self.metrics
             .table_items_current
             .set(self.table.items_current() as i64);
@@ -701,6 +776,11 @@ where
                 Effects::new()
             }
             Event::Incoming(GossiperIncoming::<T> { sender, message }) => match *message {
+                Message::Gossip(item_id)
+                    if T::GOSSIP_ONLY_WHEN_CONFIRMED && !self.table.has_entry(&item_id) =>
+                {
+                    self.handle_unconfirmed_gossip(effect_builder, item_id, sender)
+                }
                 Message::Gossip(item_id) => {
                     let target = <T as SmallGossipItem>::id_as_item(&item_id).gossip_target();
                     let action = self.table.new_complete_data(&item_id, Some(sender), target);
@@ -780,6 +860,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
             gossip_timeout,
             get_from_peer_timeout,
             validate_and_store_timeout,
+            gossip_timers,
             name,
             metrics: _,
         } = self;
@@ -788,6 +869,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
             + gossip_timeout.estimate_heap_size()
             + get_from_peer_timeout.estimate_heap_size()
             + validate_and_store_timeout.estimate_heap_size()
+            + gossip_timers.capacity() * mem::size_of::<((T::Id, NodeId), TimerHandle)>()
             + name.estimate_heap_size()
     }
 }
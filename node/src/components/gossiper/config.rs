@@ -14,6 +14,7 @@ use super::error::Error;
 
 const DEFAULT_INFECTION_TARGET: u8 = 3;
 const DEFAULT_SATURATION_LIMIT_PERCENT: u8 = 80;
+const DEFAULT_MAX_ROUNDS: u8 = 0;
 pub(super) const MAX_SATURATION_LIMIT_PERCENT: u8 = 99;
 pub(super) const DEFAULT_FINISHED_ENTRY_DURATION: &str = "60sec";
 const DEFAULT_GOSSIP_REQUEST_TIMEOUT: &str = "10sec";
@@ -28,6 +29,10 @@ const SMALL_TIMEOUTS_GET_REMAINDER_TIMEOUT: &str = "1sec";
 #[cfg(test)]
 const SMALL_TIMEOUTS_VALIDATE_AND_STORE_TIMEOUT: &str = "1sec";
 
+fn default_max_rounds() -> u8 {
+    DEFAULT_MAX_ROUNDS
+}
+
 /// Configuration options for gossiping.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
@@ -41,6 +46,10 @@ pub struct Config {
     /// holders excluding us since 80% saturation would imply 3 new infections in 15 peers.
     #[serde(deserialize_with = "deserialize_saturation_limit_percent")]
     pub saturation_limit_percent: u8,
+    /// The maximum number of gossip rounds for a single item, i.e. how many times we gossip it
+    /// onwards to a new batch of peers after responses or timeouts.  Unlimited if 0.
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: u8,
     /// The maximum duration in seconds for which to keep finished entries.
     ///
     /// The longer they are retained, the lower the likelihood of re-gossiping a piece of data.
@@ -63,6 +72,7 @@ impl Config {
     pub(crate) fn new(
         infection_target: u8,
         saturation_limit_percent: u8,
+        max_rounds: u8,
         finished_entry_duration: TimeDiff,
         gossip_request_timeout: TimeDiff,
         get_remainder_timeout: TimeDiff,
@@ -74,6 +84,7 @@ impl Config {
         Ok(Config {
             infection_target,
             saturation_limit_percent,
+            max_rounds,
             finished_entry_duration,
             gossip_request_timeout,
             get_remainder_timeout,
@@ -106,6 +117,10 @@ impl Config {
         self.saturation_limit_percent
     }
 
+    pub(crate) fn max_rounds(&self) -> u8 {
+        self.max_rounds
+    }
+
     pub(crate) fn finished_entry_duration(&self) -> TimeDiff {
         self.finished_entry_duration
     }
@@ -128,6 +143,7 @@ impl Default for Config {
         Config {
             infection_target: DEFAULT_INFECTION_TARGET,
             saturation_limit_percent: DEFAULT_SATURATION_LIMIT_PERCENT,
+            max_rounds: DEFAULT_MAX_ROUNDS,
             finished_entry_duration: TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION).unwrap(),
             gossip_request_timeout: TimeDiff::from_str(DEFAULT_GOSSIP_REQUEST_TIMEOUT).unwrap(),
             get_remainder_timeout: TimeDiff::from_str(DEFAULT_GET_REMAINDER_TIMEOUT).unwrap(),
@@ -167,6 +183,7 @@ mod tests {
         let invalid_config = Config {
             infection_target: 3,
             saturation_limit_percent: MAX_SATURATION_LIMIT_PERCENT + 1,
+            max_rounds: DEFAULT_MAX_ROUNDS,
             finished_entry_duration: TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION).unwrap(),
             gossip_request_timeout: TimeDiff::from_str(DEFAULT_GOSSIP_REQUEST_TIMEOUT).unwrap(),
             get_remainder_timeout: TimeDiff::from_str(DEFAULT_GET_REMAINDER_TIMEOUT).unwrap(),
@@ -182,6 +199,7 @@ mod tests {
         assert!(Config::new(
             3,
             MAX_SATURATION_LIMIT_PERCENT + 1,
+            DEFAULT_MAX_ROUNDS,
             TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION).unwrap(),
             TimeDiff::from_str(DEFAULT_GOSSIP_REQUEST_TIMEOUT).unwrap(),
             TimeDiff::from_str(DEFAULT_GET_REMAINDER_TIMEOUT).unwrap(),
@@ -189,4 +207,13 @@ mod tests {
         )
         .is_err())
     }
+
+    #[test]
+    fn config_without_max_rounds_should_parse() {
+        let mut config_as_json = serde_json::to_value(Config::default()).unwrap();
+        config_as_json.as_object_mut().unwrap().remove("max_rounds");
+
+        let config: Config = serde_json::from_value(config_as_json).unwrap();
+        assert_eq!(config.max_rounds, DEFAULT_MAX_ROUNDS);
+    }
 }
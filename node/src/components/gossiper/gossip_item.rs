@@ -29,6 +29,12 @@ pub(crate) trait GossipItem:
 pub(crate) trait LargeGossipItem: GossipItem {}
 
 pub(crate) trait SmallGossipItem: GossipItem {
+    /// Whether an item received via gossip must be confirmed before we gossip it onwards.
+    ///
+    /// If `true`, such an item is only announced; gossiping it onwards starts once another
+    /// component confirms it by requesting us to begin gossiping it.
+    const GOSSIP_ONLY_WHEN_CONFIRMED: bool;
+
     /// Convert a `Self::Id` into `Self`.
     fn id_as_item(id: &Self::Id) -> &Self;
 }
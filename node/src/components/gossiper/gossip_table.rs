@@ -97,6 +97,10 @@ pub(super) struct State {
     target: Option<GossipTarget>,
     /// The set of peers we attempted to infect.
     attempted_to_infect: HashSet<NodeId>,
+    /// The subset of `holders` whose response to our gossip request timed out.
+    timed_out: HashSet<NodeId>,
+    /// The number of gossip rounds we started for this data.
+    rounds: usize,
 }
 
 impl State {
@@ -105,10 +109,21 @@ impl State {
         self.target.is_some()
     }
 
+    /// Whether we already started the maximum number of gossip rounds, if limited.
+    fn rounds_exhausted(&self, max_rounds: usize) -> bool {
+        max_rounds != 0 && self.rounds >= max_rounds
+    }
+
     /// Returns whether we should finish gossiping this data.
-    fn is_finished(&self, infection_target: usize, attempted_to_infect_limit: usize) -> bool {
+    fn is_finished(
+        &self,
+        infection_target: usize,
+        attempted_to_infect_limit: usize,
+        max_rounds: usize,
+    ) -> bool {
         self.infected_by_us.len() >= infection_target
             || self.attempted_to_infect.len() >= attempted_to_infect_limit
+            || (self.rounds_exhausted(max_rounds) && self.in_flight_count == 0)
     }
 
     /// Returns a `GossipAction` derived from the given state.
@@ -116,9 +131,10 @@ impl State {
         &mut self,
         infection_target: usize,
         attempted_to_infect_limit: usize,
+        max_rounds: usize,
         is_new: bool,
     ) -> GossipAction {
-        if self.is_finished(infection_target, attempted_to_infect_limit) {
+        if self.is_finished(infection_target, attempted_to_infect_limit, max_rounds) {
             return GossipAction::Noop;
         }
 
@@ -126,8 +142,9 @@ impl State {
             // The item is held by us, decide whether we should gossip it or not.
             let count =
                 infection_target.saturating_sub(self.in_flight_count + self.infected_by_us.len());
-            if count > 0 {
+            if count > 0 && !self.rounds_exhausted(max_rounds) {
                 self.in_flight_count += count;
+                self.rounds += 1;
                 return GossipAction::ShouldGossip(ShouldGossip {
                     count,
                     target,
@@ -191,16 +208,44 @@ pub(super) struct GossipTable<T> {
     finished: HashSet<T>,
     /// Timeouts for removal of items from the `finished` cache.
     timeouts: Timeouts<T>,
+    /// Data IDs received via gossip which must be confirmed before we gossip them onwards.
+    unconfirmed: HashSet<T>,
+    /// Timeouts for removal of items from the `unconfirmed` cache.
+    unconfirmed_timeouts: Timeouts<T>,
+    /// The number of rounds taken by items which finished since last drained, excluding items we
+    /// never gossiped onwards.
+    finished_rounds: Vec<usize>,
     /// See `Config::infection_target`.
     infection_target: usize,
     /// Derived from `Config::saturation_limit_percent` - we gossip data while the number of
     /// attempts to infect doesn't exceed `attempted_to_infect_limit`.
     attempted_to_infect_limit: usize,
+    /// See `Config::max_rounds`.
+    max_rounds: usize,
     /// See `Config::finished_entry_duration`.
     finished_entry_duration: Duration,
 }
 
 impl<T> GossipTable<T> {
+    /// Applies the limits of an updated config, affecting subsequent gossiping only.
+    pub(super) fn update_config(&mut self, config: Config) {
+        self.infection_target = usize::from(config.infection_target());
+        self.attempted_to_infect_limit = (100 * usize::from(config.infection_target()))
+            / (100 - usize::from(config.saturation_limit_percent()));
+        self.max_rounds = usize::from(config.max_rounds());
+        self.finished_entry_duration = config.finished_entry_duration().into();
+    }
+
+    /// Forgets about all items, both those currently being gossiped and finished ones.
+    pub(super) fn clear(&mut self) {
+        self.current.clear();
+        self.finished.clear();
+        self.timeouts = Timeouts::new();
+        self.unconfirmed.clear();
+        self.unconfirmed_timeouts = Timeouts::new();
+        self.finished_rounds.clear();
+    }
+
     /// Number of items currently being gossiped.
     pub(super) fn items_current(&self) -> usize {
         self.current.len()
@@ -210,6 +255,12 @@ impl<T> GossipTable<T> {
     pub(super) fn items_finished(&self) -> usize {
         self.finished.len()
     }
+
+    /// Returns the number of rounds taken by each item we gossiped onwards which finished since the
+    /// last call.
+    pub(super) fn drain_finished_rounds(&mut self) -> impl Iterator<Item = usize> + '_ {
+        self.finished_rounds.drain(..)
+    }
 }
 
 impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
@@ -221,8 +272,12 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             current: HashMap::new(),
             finished: HashSet::new(),
             timeouts: Timeouts::new(),
+            unconfirmed: HashSet::new(),
+            unconfirmed_timeouts: Timeouts::new(),
+            finished_rounds: Vec::new(),
             infection_target: usize::from(config.infection_target()),
             attempted_to_infect_limit,
+            max_rounds: usize::from(config.max_rounds()),
             finished_entry_duration: config.finished_entry_duration().into(),
         }
     }
@@ -260,6 +315,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.max_rounds,
             is_new,
         );
         let _ = self.current.insert(data_id.clone(), state);
@@ -288,6 +344,8 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             return GossipAction::Noop;
         }
 
+        let _ = self.unconfirmed.remove(data_id);
+
         let update = |state: &mut State| {
             state.holders.extend(maybe_holder);
             state.target = Some(target);
@@ -305,6 +363,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.max_rounds,
             is_new,
         );
         let _ = self.current.insert(data_id.clone(), state);
@@ -312,6 +371,23 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         action
     }
 
+    /// We received data with given ID which must be confirmed before we gossip it onwards.
+    ///
+    /// The data is remembered for `finished_entry_duration`, or until it is confirmed via
+    /// `new_complete_data`.
+    ///
+    /// Returns `true` if the data is new to us.
+    pub(super) fn new_unconfirmed_data(&mut self, data_id: &T) -> bool {
+        self.purge_finished();
+
+        if self.has_entry(data_id) || !self.unconfirmed.insert(data_id.clone()) {
+            return false;
+        }
+        let timeout = Instant::now() + self.finished_entry_duration;
+        self.unconfirmed_timeouts.push(timeout, data_id.clone());
+        true
+    }
+
     pub(super) fn register_infection_attempt<'a>(
         &'a mut self,
         item_id: &T,
@@ -414,6 +490,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             if !state.holders.contains(&peer) {
                 // Add the peer as a holder just to avoid retrying it.
                 let _ = state.holders.insert(peer);
+                let _ = state.timed_out.insert(peer);
                 state.in_flight_count = state.in_flight_count.saturating_sub(1);
             }
         };
@@ -446,6 +523,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             let action = state.action(
                 self.infection_target,
                 self.attempted_to_infect_limit,
+                self.max_rounds,
                 is_new,
             );
             let _ = self.current.insert(data_id.clone(), state);
@@ -461,8 +539,8 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     ///
     /// Returns `true` if there was a current entry for this data.
     pub(super) fn force_finish(&mut self, data_id: &T) -> bool {
-        if self.current.remove(data_id).is_some() {
-            self.insert_to_finished(data_id);
+        if let Some(state) = self.current.remove(data_id) {
+            self.insert_to_finished(data_id, state.rounds);
             return true;
         }
         false
@@ -485,6 +563,14 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         false
     }
 
+    /// Returns `true` if our gossip request for the given item to `peer` timed out while the item
+    /// was still being gossiped.
+    pub(super) fn has_timed_out(&self, data_id: &T, peer: &NodeId) -> bool {
+        self.current
+            .get(data_id)
+            .map_or(false, |state| state.timed_out.contains(peer))
+    }
+
     /// Returns `true` if the given ID is in `current` or `finished`.
     pub(super) fn has_entry(&self, data_id: &T) -> bool {
         self.current.contains_key(data_id) || self.finished.contains(data_id)
@@ -501,38 +587,49 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     ) -> Option<GossipAction> {
         let mut state = self.current.remove(data_id)?;
         update(&mut state);
-        if state.is_finished(self.infection_target, self.attempted_to_infect_limit) {
-            self.insert_to_finished(data_id);
+        if state.is_finished(
+            self.infection_target,
+            self.attempted_to_infect_limit,
+            self.max_rounds,
+        ) {
+            self.insert_to_finished(data_id, state.rounds);
             return Some(GossipAction::AnnounceFinished);
         }
         let is_new = false;
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.max_rounds,
             is_new,
         );
         let _ = self.current.insert(data_id.clone(), state);
         Some(action)
     }
 
-    fn insert_to_finished(&mut self, data_id: &T) {
+    fn insert_to_finished(&mut self, data_id: &T, rounds: usize) {
+        if rounds > 0 {
+            self.finished_rounds.push(rounds);
+        }
         let timeout = Instant::now() + self.finished_entry_duration;
         let _ = self.finished.insert(data_id.clone());
         self.timeouts.push(timeout, data_id.clone());
     }
 
-    /// Retains only those finished entries which still haven't timed out.
+    /// Retains only those finished and unconfirmed entries which still haven't timed out.
     fn purge_finished(&mut self) {
         let now = Instant::now();
 
         for expired_finished in self.timeouts.purge(&now) {
             let _ = self.finished.remove(&expired_finished);
         }
+        for expired_unconfirmed in self.unconfirmed_timeouts.purge(&now) {
+            let _ = self.unconfirmed.remove(&expired_unconfirmed);
+        }
     }
 
     #[cfg(test)]
     pub(super) fn is_empty(&self) -> bool {
-        self.current.is_empty() && self.finished.is_empty()
+        self.current.is_empty() && self.finished.is_empty() && self.unconfirmed.is_empty()
     }
 }
 
@@ -1059,6 +1156,24 @@ mod tests {
         assert!(!gossip_table.force_finish(&data_id));
     }
 
+    #[test]
+    fn should_track_unconfirmed_data_until_confirmed() {
+        let mut rng = crate::new_rng();
+        let data_id: u64 = rng.gen();
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Only the first receipt is new to us, and the data isn't gossiped while unconfirmed.
+        assert!(gossip_table.new_unconfirmed_data(&data_id));
+        assert!(!gossip_table.new_unconfirmed_data(&data_id));
+        assert!(!gossip_table.has_entry(&data_id));
+
+        // Confirming the data starts gossiping it, after which it is no longer unconfirmed.
+        let action = gossip_table.new_complete_data(&data_id, None, GossipTarget::All);
+        assert!(matches!(action, GossipAction::ShouldGossip(_)));
+        assert!(!gossip_table.new_unconfirmed_data(&data_id));
+        assert!(!gossip_table.unconfirmed.contains(&data_id));
+    }
+
     #[test]
     fn should_purge() {
         let _ = logging::init();
@@ -1094,6 +1209,74 @@ mod tests {
         assert!(!gossip_table.finished.contains(&data_id));
     }
 
+    #[test]
+    fn should_forget_everything_when_cleared() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let current_id: u64 = rng.gen();
+        let finished_id: u64 = rng.gen();
+        let unconfirmed_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        let _ = gossip_table.new_data_id(&current_id, node_ids[0]);
+        let _ = gossip_table.new_complete_data(&finished_id, None, GossipTarget::All);
+        assert!(gossip_table.force_finish(&finished_id));
+        assert!(gossip_table.new_unconfirmed_data(&unconfirmed_id));
+
+        gossip_table.clear();
+        assert!(gossip_table.is_empty());
+        assert!(!gossip_table.has_entry(&current_id));
+        assert!(!gossip_table.has_entry(&finished_id));
+
+        // All items are treated as new again.
+        assert!(gossip_table.new_unconfirmed_data(&unconfirmed_id));
+        let action = gossip_table.new_complete_data(&finished_id, None, GossipTarget::All);
+        assert!(matches!(action, GossipAction::ShouldGossip(_)));
+    }
+
+    #[test]
+    fn should_finish_after_max_rounds() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let config = Config {
+            max_rounds: 1,
+            ..Default::default()
+        };
+        let mut gossip_table = GossipTable::new(config);
+
+        // Start the only allowed round.
+        let action = gossip_table.new_complete_data(&data_id, None, GossipTarget::All);
+        let expected = GossipAction::ShouldGossip(ShouldGossip {
+            count: EXPECTED_DEFAULT_INFECTION_TARGET,
+            target: GossipTarget::All,
+            exclude_peers: HashSet::new(),
+            is_already_held: false,
+        });
+        assert_eq!(expected, action);
+
+        // A timed out request would normally trigger a new round, but the rounds are exhausted.
+        let action = gossip_table.check_timeout(&data_id, node_ids[0]);
+        assert_eq!(GossipAction::Noop, action);
+        assert!(gossip_table.has_timed_out(&data_id, &node_ids[0]));
+        assert!(!gossip_table.has_timed_out(&data_id, &node_ids[1]));
+
+        // Once the remaining requests are answered, gossiping finishes.
+        let action = gossip_table.already_infected(&data_id, node_ids[1]);
+        assert_eq!(GossipAction::Noop, action);
+        let action = gossip_table.already_infected(&data_id, node_ids[2]);
+        assert_eq!(GossipAction::AnnounceFinished, action);
+        assert!(gossip_table.finished.contains(&data_id));
+        assert_eq!(
+            vec![1],
+            gossip_table.drain_finished_rounds().collect::<Vec<_>>()
+        );
+        assert_eq!(0, gossip_table.drain_finished_rounds().count());
+    }
+
     #[test]
     fn timeouts_purge_in_order() {
         let mut timeouts = Timeouts::new();
@@ -1,6 +1,11 @@
-use prometheus::{IntCounter, IntGauge, Registry};
+use prometheus::{Histogram, IntCounter, IntGauge, Registry};
 
-use crate::unregister_metric;
+use crate::{unregister_metric, utils};
+
+// We use linear buckets to observe the number of rounds it took to finish gossiping an item.
+const ROUNDS_BUCKET_START: f64 = 1.0;
+const ROUNDS_BUCKET_WIDTH: f64 = 1.0;
+const ROUNDS_BUCKET_COUNT: usize = 10;
 
 /// Metrics for the gossiper component.
 #[derive(Debug)]
@@ -11,6 +16,12 @@ pub(super) struct Metrics {
     pub(super) times_gossiped: IntCounter,
     /// Number of times the process had to pause due to running out of peers.
     pub(super) times_ran_out_of_peers: IntCounter,
+    /// Total number of items this node finished gossiping onwards.
+    pub(super) items_gossiped: IntCounter,
+    /// Number of gossip rounds it took to finish gossiping an item onwards.
+    pub(super) rounds_to_saturation: Histogram,
+    /// Number of gossip responses received after the request had already timed out.
+    pub(super) late_responders: IntCounter,
     /// Number of items in the gossip table that are currently being gossiped.
     pub(super) table_items_current: IntGauge,
     /// Number of items in the gossip table that are finished.
@@ -37,6 +48,17 @@ impl Metrics {
                 name
             ),
         )?;
+        let items_gossiped = IntCounter::new(
+            format!("{}_items_gossiped", name),
+            format!("number of items the {} finished gossiping onwards", name),
+        )?;
+        let late_responders = IntCounter::new(
+            format!("{}_late_responders", name),
+            format!(
+                "number of gossip responses received by the {} after the request timed out",
+                name
+            ),
+        )?;
         let table_items_current = IntGauge::new(
             format!("{}_table_items_current", name),
             format!(
@@ -55,6 +77,8 @@ impl Metrics {
         registry.register(Box::new(items_received.clone()))?;
         registry.register(Box::new(times_gossiped.clone()))?;
         registry.register(Box::new(times_ran_out_of_peers.clone()))?;
+        registry.register(Box::new(items_gossiped.clone()))?;
+        registry.register(Box::new(late_responders.clone()))?;
         registry.register(Box::new(table_items_current.clone()))?;
         registry.register(Box::new(table_items_finished.clone()))?;
 
@@ -62,6 +86,21 @@ impl Metrics {
             items_received,
             times_gossiped,
             times_ran_out_of_peers,
+            items_gossiped,
+            rounds_to_saturation: utils::register_histogram_metric(
+                registry,
+                &format!("{}_rounds_to_saturation", name),
+                &format!(
+                    "number of rounds the {} took to finish gossiping an item onwards",
+                    name
+                ),
+                prometheus::linear_buckets(
+                    ROUNDS_BUCKET_START,
+                    ROUNDS_BUCKET_WIDTH,
+                    ROUNDS_BUCKET_COUNT,
+                )?,
+            )?,
+            late_responders,
             table_items_current,
             table_items_finished,
             registry: registry.clone(),
@@ -74,6 +113,9 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.items_received);
         unregister_metric!(self.registry, self.times_gossiped);
         unregister_metric!(self.registry, self.times_ran_out_of_peers);
+        unregister_metric!(self.registry, self.items_gossiped);
+        unregister_metric!(self.registry, self.rounds_to_saturation);
+        unregister_metric!(self.registry, self.late_responders);
         unregister_metric!(self.registry, self.table_items_current);
         unregister_metric!(self.registry, self.table_items_finished);
     }
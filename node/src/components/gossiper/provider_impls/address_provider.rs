@@ -15,7 +15,7 @@ impl ItemProvider<GossipedAddress>
 {
     async fn is_stored<REv: Send>(
         _effect_builder: EffectBuilder<REv>,
-        item_id: GossipedAddress,
+        item_id: Box<GossipedAddress>,
     ) -> bool {
         error!(%item_id, "address gossiper should never try to check if item is stored");
         false
@@ -23,7 +23,7 @@ impl ItemProvider<GossipedAddress>
 
     async fn get_from_storage<REv: Send>(
         _effect_builder: EffectBuilder<REv>,
-        item_id: GossipedAddress,
+        item_id: Box<GossipedAddress>,
     ) -> Option<Box<GossipedAddress>> {
         error!(%item_id, "address gossiper should never try to get from storage");
         None
@@ -23,7 +23,7 @@ use super::*;
 use crate::{
     components::{
         deploy_acceptor,
-        in_memory_network::{self, InMemoryNetwork, NetworkController},
+        in_memory_network::{self, InMemoryNetwork, NetworkConditions, NetworkController},
         network::{GossipedAddress, Identity as NetworkIdentity},
         storage::{self, Storage},
     },
@@ -286,8 +286,7 @@ impl reactor::Reactor for Reactor {
                 self.dispatch_event(effect_builder, rng, Event::DeployGossiper(event))
             }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::InvalidDeploy {
-                deploy: _,
-                source: _,
+                ..
             }) => Effects::new(),
             Event::DeployGossiperAnnouncement(GossiperAnnouncement::NewItemBody {
                 item,
@@ -385,6 +384,102 @@ async fn should_gossip() {
     }
 }
 
+#[tokio::test]
+async fn should_gossip_despite_latency() {
+    const NETWORK_SIZE: usize = 10;
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    NetworkController::<NodeMessage>::create_active();
+    NetworkController::<NodeMessage>::set_conditions(NetworkConditions {
+        latency: Duration::from_millis(200),
+        jitter: Duration::from_millis(300),
+        ..Default::default()
+    });
+    let mut network = TestingNetwork::<Reactor>::with_virtual_time();
+    let mut rng = crate::new_rng();
+
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Give a deploy to node 0 to be gossiped.
+    let deploy = Arc::new(Deploy::random_valid_native_transfer(&mut rng));
+    let deploy_id = *deploy.hash();
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy))
+        .await;
+
+    // Check every node eventually holds the deploy.
+    let deploy_held = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        nodes.values().all(|runner| {
+            runner
+                .reactor()
+                .inner()
+                .storage
+                .get_deploy_by_hash(deploy_id)
+                .is_some()
+        })
+    };
+    network.settle_on(&mut rng, deploy_held, TIMEOUT).await;
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
+#[tokio::test]
+async fn should_not_gossip_across_partition() {
+    const NETWORK_SIZE: usize = 6;
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(20);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = TestingNetwork::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Split the network in half.
+    let (reachable, unreachable) = node_ids.split_at(NETWORK_SIZE / 2);
+    NetworkController::<NodeMessage>::set_conditions(NetworkConditions {
+        partitions: vec![reachable.iter().copied().collect()],
+        ..Default::default()
+    });
+
+    // Give a deploy to node 0 to be gossiped.
+    let deploy = Arc::new(Deploy::random_valid_native_transfer(&mut rng));
+    let deploy_id = *deploy.hash();
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy))
+        .await;
+
+    // Check the deploy reaches every node in node 0's partition.
+    let holds_deploy = |runner: &Runner<ConditionCheckReactor<Reactor>>| {
+        runner
+            .reactor()
+            .inner()
+            .storage
+            .get_deploy_by_hash(deploy_id)
+            .is_some()
+    };
+    let reachable_ids = reachable.to_vec();
+    network
+        .settle_on(
+            &mut rng,
+            |nodes| {
+                reachable_ids
+                    .iter()
+                    .all(|node_id| holds_deploy(&nodes[node_id]))
+            },
+            TIMEOUT,
+        )
+        .await;
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    // Nodes on the other side of the partition must not have received it.
+    for node_id in unreachable {
+        assert!(!holds_deploy(&network.nodes()[node_id]));
+    }
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
 #[tokio::test]
 async fn should_get_from_alternate_source() {
     const NETWORK_SIZE: usize = 3;
@@ -275,6 +275,20 @@
 //!
 //! # }); // end of tokio::block_on
 //! ```
+//!
+//! # Adverse conditions
+//!
+//! To reproduce issues that only appear on a misbehaving network, the active network can be
+//! degraded at any time using `NetworkController::set_conditions`. The given `NetworkConditions`
+//! apply to every message sent afterwards:
+//!
+//! * every message is delayed by the configured `latency`, plus a random amount of up to `jitter`
+//!   (which may cause messages to arrive out of order),
+//! * every message is dropped with a probability of `loss`, and
+//! * messages between nodes in different `partitions` are dropped entirely.
+//!
+//! All randomness is drawn from the sending node's RNG, and delays are subject to tokio's clock, so
+//! a network running on virtual time (see `TestingNetwork::with_virtual_time`) stays reproducible.
 
 use std::{
     any::Any,
@@ -282,11 +296,15 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Display, Formatter},
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
-use rand::seq::IteratorRandom;
+use rand::{seq::IteratorRandom, Rng};
 use serde::Serialize;
-use tokio::sync::mpsc::{self, error::SendError};
+use tokio::{
+    sync::mpsc::{self, error::SendError},
+    time,
+};
 use tracing::{debug, error, info, warn};
 
 use casper_types::testing::TestRng;
@@ -307,6 +325,51 @@ const COMPONENT_NAME: &str = "in_memory_network";
 /// A network.
 type Network<P> = Arc<RwLock<HashMap<NodeId, mpsc::UnboundedSender<(NodeId, P)>>>>;
 
+/// Conditions shared by all nodes of a network.
+type SharedConditions = Arc<RwLock<NetworkConditions>>;
+
+/// Conditions under which messages are delivered across the in-memory network.
+///
+/// The default is a flawless network: no latency, no loss and no partitions.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NetworkConditions {
+    /// Delay applied to every message.
+    pub(crate) latency: Duration,
+    /// Upper bound of an additional random delay applied to every message.
+    pub(crate) jitter: Duration,
+    /// Probability of a message being dropped, between 0.0 and 1.0.
+    pub(crate) loss: f64,
+    /// Disjoint groups of nodes which cannot communicate with each other.
+    ///
+    /// Nodes not contained in any group form an implicit group of their own.
+    pub(crate) partitions: Vec<HashSet<NodeId>>,
+}
+
+impl NetworkConditions {
+    /// Returns the index of the partition the given node belongs to, if any.
+    fn partition_of(&self, node_id: &NodeId) -> Option<usize> {
+        self.partitions
+            .iter()
+            .position(|partition| partition.contains(node_id))
+    }
+
+    /// Returns whether messages sent from `sender` can reach `dest`.
+    fn can_reach(&self, sender: &NodeId, dest: &NodeId) -> bool {
+        self.partition_of(sender) == self.partition_of(dest)
+    }
+
+    /// Returns the delay for a single message, or `None` if the message should be dropped.
+    fn delay(&self, rng: &mut NodeRng) -> Option<Duration> {
+        if self.loss > 0.0 && rng.gen_bool(self.loss) {
+            return None;
+        }
+        if self.jitter.is_zero() {
+            return Some(self.latency);
+        }
+        Some(self.latency + rng.gen_range(Duration::ZERO..=self.jitter))
+    }
+}
+
 /// An in-memory network events.
 #[derive(Debug, Serialize)]
 pub(crate) struct Event<P>(NetworkRequest<P>);
@@ -336,6 +399,8 @@ thread_local! {
 pub(crate) struct NetworkController<P> {
     /// Channels for network communication.
     nodes: Network<P>,
+    /// Conditions applied to all messages sent across the network.
+    conditions: SharedConditions,
 }
 
 impl<P> NetworkController<P>
@@ -347,6 +412,7 @@ where
         let _ = logging::init();
         NetworkController {
             nodes: Default::default(),
+            conditions: Default::default(),
         }
     }
 
@@ -425,6 +491,29 @@ where
         })
     }
 
+    /// Sets the conditions of the active network, affecting all messages sent from now on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal lock has been poisoned, there is no active network or the active
+    /// network is not of the correct message type.
+    pub(crate) fn set_conditions(conditions: NetworkConditions) {
+        ACTIVE_NETWORK.with(|active_network| {
+            active_network
+                .borrow()
+                .as_ref()
+                .expect("tried to set conditions without active network set")
+                .downcast_ref::<Self>()
+                .expect("active network has wrong message type")
+                .set_conditions_local(conditions)
+        })
+    }
+
+    /// Sets the conditions of this network, affecting all messages sent from now on.
+    pub(crate) fn set_conditions_local(&self, conditions: NetworkConditions) {
+        *self.conditions.write().expect("poisoned lock") = conditions;
+    }
+
     /// Creates a new networking node with a random node ID.
     ///
     /// Returns the already connected new networking component for new node.
@@ -436,7 +525,12 @@ where
     where
         REv: Send + FromIncoming<P>,
     {
-        InMemoryNetwork::new_with_data(event_queue, NodeId::random(rng), self.nodes.clone())
+        InMemoryNetwork::new_with_data(
+            event_queue,
+            NodeId::random(rng),
+            self.nodes.clone(),
+            self.conditions.clone(),
+        )
     }
 }
 
@@ -448,6 +542,9 @@ pub(crate) struct InMemoryNetwork<P> {
 
     /// The nodes map, contains the incoming channel for each virtual node.
     nodes: Network<P>,
+
+    /// Conditions applied to all messages sent across the network.
+    conditions: SharedConditions,
 }
 
 impl<P> InMemoryNetwork<P>
@@ -469,6 +566,7 @@ where
         event_queue: EventQueueHandle<REv>,
         node_id: NodeId,
         nodes: Network<P>,
+        conditions: SharedConditions,
     ) -> Self
     where
         REv: Send + FromIncoming<P>,
@@ -484,7 +582,11 @@ where
 
         tokio::spawn(receiver_task(event_queue, receiver));
 
-        InMemoryNetwork { node_id, nodes }
+        InMemoryNetwork {
+            node_id,
+            nodes,
+            conditions,
+        }
     }
 
     /// Returns this node's ID.
@@ -496,12 +598,14 @@ where
 
 impl<P> InMemoryNetwork<P>
 where
-    P: Display,
+    P: 'static + Send + Display,
 {
     /// Internal helper, sends a payload to a node, ignoring but logging all errors.
     fn send(
         &self,
         nodes: &HashMap<NodeId, mpsc::UnboundedSender<(NodeId, P)>>,
+        conditions: &NetworkConditions,
+        rng: &mut NodeRng,
         dest: NodeId,
         payload: P,
     ) {
@@ -509,22 +613,50 @@ where
             panic!("can't send message to self");
         }
 
-        match nodes.get(&dest) {
-            Some(sender) => {
-                if let Err(SendError((_, msg))) = sender.send((self.node_id, payload)) {
-                    warn!(%dest, %msg, "could not send message (send error)");
+        let sender = match nodes.get(&dest) {
+            Some(sender) => sender.clone(),
+            None => {
+                info!(%dest, %payload, "dropping message to non-existent recipient");
+                return;
+            }
+        };
 
-                    // We do nothing else, the message is just dropped.
-                }
+        if !conditions.can_reach(&self.node_id, &dest) {
+            debug!(%dest, %payload, "dropping message to partitioned recipient");
+            return;
+        }
+
+        let node_id = self.node_id;
+        match conditions.delay(rng) {
+            None => debug!(%dest, %payload, "dropping message due to simulated loss"),
+            Some(delay) if delay.is_zero() => deliver(&sender, node_id, dest, payload),
+            Some(delay) => {
+                tokio::spawn(async move {
+                    time::sleep(delay).await;
+                    deliver(&sender, node_id, dest, payload);
+                });
             }
-            None => info!(%dest, %payload, "dropping message to non-existent recipient"),
         }
     }
 }
 
+/// Hands a payload to the receiving node's channel, ignoring but logging all errors.
+fn deliver<P: Display>(
+    sender: &mpsc::UnboundedSender<(NodeId, P)>,
+    node_id: NodeId,
+    dest: NodeId,
+    payload: P,
+) {
+    if let Err(SendError((_, msg))) = sender.send((node_id, payload)) {
+        warn!(%dest, %msg, "could not send message (send error)");
+
+        // We do nothing else, the message is just dropped.
+    }
+}
+
 impl<P, REv> Component<REv> for InMemoryNetwork<P>
 where
-    P: Display + Clone,
+    P: 'static + Send + Display + Clone,
 {
     type Event = Event<P>;
 
@@ -545,8 +677,8 @@ where
                     panic!("can't send message to self");
                 }
 
-                if let Ok(guard) = self.nodes.read() {
-                    self.send(&guard, *dest, *payload);
+                if let (Ok(guard), Ok(conditions)) = (self.nodes.read(), self.conditions.read()) {
+                    self.send(&guard, &conditions, rng, *dest, *payload);
                 } else {
                     error!("network lock has been poisoned")
                 };
@@ -558,9 +690,9 @@ where
                 auto_closing_responder,
                 era_id: _,
             } => {
-                if let Ok(guard) = self.nodes.read() {
+                if let (Ok(guard), Ok(conditions)) = (self.nodes.read(), self.conditions.read()) {
                     for dest in guard.keys().filter(|&node_id| node_id != &self.node_id) {
-                        self.send(&guard, *dest, *payload.clone());
+                        self.send(&guard, &conditions, rng, *dest, *payload.clone());
                     }
                 } else {
                     error!("network lock has been poisoned")
@@ -575,17 +707,21 @@ where
                 auto_closing_responder,
                 gossip_target: _,
             } => {
-                if let Ok(guard) = self.nodes.read() {
+                if let (Ok(guard), Ok(conditions)) = (self.nodes.read(), self.conditions.read()) {
                     let chosen: HashSet<_> = guard
                         .keys()
-                        .filter(|&node_id| !exclude.contains(node_id) && node_id != &self.node_id)
+                        .filter(|&node_id| {
+                            !exclude.contains(node_id)
+                                && node_id != &self.node_id
+                                && conditions.can_reach(&self.node_id, node_id)
+                        })
                         .cloned()
                         .choose_multiple(rng, count)
                         .into_iter()
                         .collect();
                     // Not terribly efficient, but will always get us the maximum amount of nodes.
                     for dest in chosen.iter() {
-                        self.send(&guard, *dest, *payload.clone());
+                        self.send(&guard, &conditions, rng, *dest, *payload.clone());
                     }
                     auto_closing_responder.respond(chosen).ignore()
                 } else {
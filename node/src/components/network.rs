@@ -27,6 +27,7 @@ mod bincode_format;
 pub(crate) mod blocklist;
 mod chain_info;
 mod config;
+mod cost_budget;
 mod counting_format;
 mod error;
 mod event;
@@ -43,9 +44,10 @@ mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
 mod tests;
+mod versioned_format;
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     io,
     net::{SocketAddr, TcpListener},
@@ -74,11 +76,12 @@ use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
-use casper_types::{EraId, PublicKey, SecretKey};
+use casper_types::{EraId, PublicKey, SecretKey, TimeDiff, Timestamp};
 
 pub(crate) use self::{
     bincode_format::BincodeFormat,
     config::{Config, IdentityConfig},
+    cost_budget::CostWeights,
     error::Error,
     event::Event,
     gossiped_address::GossipedAddress,
@@ -95,13 +98,15 @@ use self::{
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
-    health::{HealthConfig, TaggedTimestamp},
+    gossiped_address::SignedAddress,
+    health::{HealthConfig, Nonce, TaggedTimestamp},
     limiter::Limiter,
     message::NodeKeyPair,
     metrics::Metrics,
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
     symmetry::ConnectionSymmetry,
     tasks::{MessageQueueItem, NetworkContext},
+    versioned_format::{VersionedFormat, TIMED_PONG_VERSION},
 };
 use crate::{
     components::{gossiper::GossipItem, Component, ComponentState, InitializedComponent},
@@ -111,7 +116,7 @@ use crate::{
         AutoClosingResponder, EffectBuilder, EffectExt, Effects, GossipTarget,
     },
     reactor::{Finalize, ReactorEvent},
-    tls,
+    runtime, tls,
     types::{NodeId, ValidatorMatrix},
     utils::{self, display_error, Source},
     NodeRng,
@@ -134,19 +139,33 @@ const BASE_RECONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
 /// Interval during which to perform outgoing manager housekeeping.
 const OUTGOING_MANAGER_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
 
-/// How often to send a ping down a healthy connection.
-const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// Factor by which the number of candidates to choose low round-trip time peers from exceeds the
+/// number of peers requested.
+const LOW_RTT_CANDIDATES_FACTOR: usize = 2;
 
-/// Maximum time for a ping until it connections are severed.
+/// How long an address received via gossip is remembered while we are not connected to it.
 ///
-/// If you are running a network under very extreme conditions, it may make sense to alter these
-/// values, but usually these values should require no changing.
+/// Until it has been confirmed reachable or this duration has passed, the address is not dialed
+/// again, no matter how often it is gossiped to us.
+const GOSSIPED_ADDR_RETENTION: Duration = Duration::from_secs(600);
+
+/// The window over which dials of addresses received via gossip are rate limited.
+const GOSSIPED_ADDR_DIAL_WINDOW: Duration = Duration::from_secs(60);
+
+/// Maximum number of addresses received via gossip to dial within `GOSSIPED_ADDR_DIAL_WINDOW`.
+const MAX_GOSSIPED_ADDR_DIALS_PER_WINDOW: usize = 60;
+
+/// Gossiped addresses signed longer ago than this, or this far in the future, are ignored.
 ///
-/// `PING_TIMEOUT` should be less than `PING_INTERVAL` at all times.
-const PING_TIMEOUT: Duration = Duration::from_secs(6);
+/// Nodes re-sign their address once the signature is older than `GOSSIPED_ADDR_RESIGN_AGE`, so
+/// this only needs to cover that, the time it takes to gossip an address across the network and
+/// clock differences.
+const GOSSIPED_ADDR_MAX_AGE: TimeDiff = TimeDiff::from_seconds(1800);
 
-/// How many pings to send before giving up and dropping the connection.
-const PING_RETRIES: u16 = 5;
+/// Our signed address is re-signed once its signature is older than this.
+///
+/// Until then, we keep gossiping the same item, so peers don't treat it as new every time.
+const GOSSIPED_ADDR_RESIGN_AGE: TimeDiff = TimeDiff::from_seconds(900);
 
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
@@ -180,6 +199,20 @@ where
     /// Tracks nodes that have announced themselves as nodes that are syncing.
     syncing_nodes: HashSet<NodeId>,
 
+    /// Estimated offsets of peers' clocks from ours in milliseconds, taken from their handshakes
+    /// and updated with every timed pong.  Only recorded for peers with a consensus key.
+    peer_clock_offsets: HashMap<NodeId, i64>,
+    /// The wire format versions negotiated on our outgoing connections.
+    peer_wire_format_versions: HashMap<NodeId, u8>,
+
+    /// The consensus keys presented by peers on our outgoing connections.
+    outgoing_consensus_keys: HashMap<NodeId, PublicKey>,
+    /// Addresses received via gossip which we are not connected to yet, with the time we received
+    /// them.  They are gossiped onwards once we have connected to them.
+    gossiped_addrs: HashMap<SocketAddr, (Box<GossipedAddress>, Instant)>,
+    /// Our own address as last gossiped.
+    our_gossiped_address: Option<GossipedAddress>,
+
     channel_management: Option<ChannelManagement>,
 
     /// Networking metrics.
@@ -247,6 +280,13 @@ where
         chain_info_source: C,
         validator_matrix: ValidatorMatrix,
     ) -> Result<Network<REv, P>> {
+        if cfg.ping_timeout >= cfg.ping_interval {
+            return Err(Error::PingTimeoutNotBelowInterval {
+                ping_timeout: cfg.ping_timeout,
+                ping_interval: cfg.ping_interval,
+            });
+        }
+
         let net_metrics = Arc::new(Metrics::new(registry)?);
 
         let outgoing_limiter = Limiter::new(
@@ -268,10 +308,10 @@ where
                 unblock_after: cfg.blocklist_retain_duration.into(),
                 sweep_timeout: cfg.max_addr_pending_time.into(),
                 health: HealthConfig {
-                    ping_interval: PING_INTERVAL,
-                    ping_timeout: PING_TIMEOUT,
-                    ping_retries: PING_RETRIES,
-                    pong_limit: (1 + PING_RETRIES as u32) * 2,
+                    ping_interval: cfg.ping_interval.into(),
+                    ping_timeout: cfg.ping_timeout.into(),
+                    ping_retries: cfg.ping_retries,
+                    pong_limit: (1 + cfg.ping_retries as u32) * 2,
                 },
             },
             net_metrics.create_outgoing_metrics(),
@@ -291,6 +331,11 @@ where
             outgoing_manager,
             connection_symmetries: HashMap::new(),
             syncing_nodes: HashSet::new(),
+            peer_clock_offsets: HashMap::new(),
+            peer_wire_format_versions: HashMap::new(),
+            outgoing_consensus_keys: HashMap::new(),
+            gossiped_addrs: HashMap::new(),
+            our_gossiped_address: None,
             channel_management: None,
             net_metrics,
             outgoing_limiter,
@@ -358,7 +403,7 @@ where
         let (close_incoming_sender, close_incoming_receiver) = watch::channel(());
 
         let context = self.context.clone();
-        let server_join_handle = tokio::spawn(
+        let server_join_handle = runtime::spawn_network(
             tasks::server(
                 context,
                 tokio::net::TcpListener::from_std(listener).map_err(Error::ListenerConversion)?,
@@ -530,6 +575,10 @@ where
                 peer_id: _,
                 ref error,
             } => {
+                if error.is_cross_network() {
+                    self.net_metrics.rejected_cross_network_connections.inc();
+                }
+
                 // TODO: At this point, we could consider blocking peers by [`PeerID`], but this
                 //       feature is not implemented yet.
                 debug!(
@@ -550,6 +599,7 @@ where
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                clock_offset,
                 stream,
             } => {
                 if self.cfg.max_incoming_peer_connections != 0 {
@@ -573,6 +623,10 @@ where
 
                 info!(%public_addr, "new incoming connection established");
 
+                if let (Some(offset), Some(_)) = (clock_offset, &peer_consensus_public_key) {
+                    self.peer_clock_offsets.insert(peer_id, offset);
+                }
+
                 // Learn the address the peer gave us.
                 let dial_requests =
                     self.outgoing_manager
@@ -602,16 +656,18 @@ where
                 // Now we can start the message reader.
                 let boxed_span = Box::new(span.clone());
                 effects.extend(
-                    tasks::message_reader(
-                        self.context.clone(),
-                        stream,
-                        self.incoming_limiter
-                            .create_handle(peer_id, peer_consensus_public_key),
-                        self.channel_management().close_incoming_receiver.clone(),
-                        peer_id,
-                        span.clone(),
+                    runtime::run_on_network(
+                        tasks::message_reader(
+                            self.context.clone(),
+                            stream,
+                            self.incoming_limiter
+                                .create_handle(peer_id, peer_consensus_public_key),
+                            self.channel_management().close_incoming_receiver.clone(),
+                            peer_id,
+                            span.clone(),
+                        )
+                        .instrument(span),
                     )
-                    .instrument(span)
                     .event(move |result| Event::IncomingClosed {
                         result,
                         peer_id: Box::new(peer_id),
@@ -706,6 +762,7 @@ where
     #[allow(clippy::redundant_clone)]
     fn handle_outgoing_connection(
         &mut self,
+        effect_builder: EffectBuilder<REv>,
         outgoing: OutgoingConnection<P>,
         span: Span,
     ) -> Effects<Event<P>> {
@@ -718,6 +775,10 @@ where
                 error,
             } => {
                 debug!(err=%display_error(&error), "outgoing connection failed");
+                if error.is_cross_network() {
+                    self.net_metrics.rejected_cross_network_connections.inc();
+                }
+
                 // We perform blocking first, to not trigger a reconnection before blocking.
                 let mut requests = Vec::new();
 
@@ -755,9 +816,21 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                clock_offset,
+                wire_format_version,
             } => {
                 info!("new outgoing connection established");
 
+                if let (Some(offset), Some(_)) = (clock_offset, &peer_consensus_public_key) {
+                    self.peer_clock_offsets.insert(peer_id, offset);
+                }
+                self.peer_wire_format_versions
+                    .insert(peer_id, wire_format_version);
+                if let Some(public_key) = &peer_consensus_public_key {
+                    self.outgoing_consensus_keys
+                        .insert(peer_id, public_key.clone());
+                }
+
                 let (sender, receiver) = mpsc::unbounded_channel();
                 let handle = OutgoingHandle { sender, peer_addr };
 
@@ -772,6 +845,10 @@ where
 
                 let mut effects = self.process_dial_requests(request);
 
+                if let Some((address, _)) = self.gossiped_addrs.remove(&peer_addr) {
+                    effects.extend(self.gossip_confirmed_address(effect_builder, address, peer_id));
+                }
+
                 // Update connection symmetries.
                 if self
                     .connection_symmetries
@@ -784,14 +861,16 @@ where
                 }
 
                 effects.extend(
-                    tasks::message_sender(
-                        receiver,
-                        sink,
-                        self.outgoing_limiter
-                            .create_handle(peer_id, peer_consensus_public_key),
-                        self.net_metrics.queued_messages.clone(),
+                    runtime::run_on_network(
+                        tasks::message_sender(
+                            receiver,
+                            sink,
+                            self.outgoing_limiter
+                                .create_handle(peer_id, peer_consensus_public_key),
+                            self.net_metrics.queued_messages.clone(),
+                        )
+                        .instrument(span),
                     )
-                    .instrument(span)
                     .event(move |_| Event::OutgoingDropped {
                         peer_id: Box::new(peer_id),
                         peer_addr,
@@ -803,6 +882,124 @@ where
         })
     }
 
+    /// Handles an address received via gossip.
+    ///
+    /// If we are connected to the address already, it is gossiped onwards right away.  Otherwise it
+    /// is dialed and gossiped onwards once we have connected to it, unless we are waiting to
+    /// connect to it already or too many gossiped addresses have been dialed within
+    /// `GOSSIPED_ADDR_DIAL_WINDOW`.  Signed addresses are ignored if they were signed too long ago
+    /// or the signature is invalid.
+    ///
+    /// This keeps a spoofed address gossiped across the network from causing a flood of
+    /// connection attempts to its actual owner.
+    fn handle_peer_address_received(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        gossiped_address: Box<GossipedAddress>,
+    ) -> Effects<Event<P>> {
+        if let Some(signed) = gossiped_address.signed() {
+            let timestamp = signed.timestamp();
+            let now_timestamp = Timestamp::now();
+            if now_timestamp.saturating_diff(timestamp) > GOSSIPED_ADDR_MAX_AGE
+                || timestamp.saturating_diff(now_timestamp) > GOSSIPED_ADDR_MAX_AGE
+            {
+                debug!(%gossiped_address, "ignoring outdated gossiped address");
+                return Effects::new();
+            }
+        }
+
+        let addr = gossiped_address.address();
+        if self.gossiped_addrs.contains_key(&addr) {
+            trace!(%addr, "ignoring gossiped address we are waiting to connect to");
+            return Effects::new();
+        }
+
+        if let Some(peer_id) = self.outgoing_manager.get_connected_peer(addr) {
+            if !has_valid_signature(&gossiped_address) {
+                return Effects::new();
+            }
+            return self.gossip_confirmed_address(effect_builder, gossiped_address, peer_id);
+        }
+
+        let now = Instant::now();
+        let recent_dials = self
+            .gossiped_addrs
+            .values()
+            .filter(|(_, received_at)| {
+                now.saturating_duration_since(*received_at) < GOSSIPED_ADDR_DIAL_WINDOW
+            })
+            .count();
+        if recent_dials >= MAX_GOSSIPED_ADDR_DIALS_PER_WINDOW {
+            debug!(%addr, "too many gossiped addresses dialed recently, ignoring");
+            return Effects::new();
+        }
+
+        if !has_valid_signature(&gossiped_address) {
+            return Effects::new();
+        }
+
+        // If the address is known already, we keep it around anyway, so it is gossiped onwards once
+        // the outgoing manager reconnects to it.
+        let request = self.outgoing_manager.learn_addr(addr, false, now);
+        self.gossiped_addrs.insert(addr, (gossiped_address, now));
+        self.process_dial_requests(request)
+    }
+
+    /// Gossips onwards an address received via gossip which we are connected to, provided the node
+    /// we are connected to is the one that signed it, if it is signed.
+    fn gossip_confirmed_address(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        address: Box<GossipedAddress>,
+        peer_id: NodeId,
+    ) -> Effects<Event<P>> {
+        let peer_consensus_public_key = self.outgoing_consensus_keys.get(&peer_id);
+        let is_signed_by_peer = address.signed().map_or(true, |signed| {
+            signed.is_signed_by(peer_id, peer_consensus_public_key)
+        });
+        if !is_signed_by_peer {
+            warn!(
+                %address,
+                "gossiped address belongs to a different node, not gossiping it onwards"
+            );
+            return Effects::new();
+        }
+        debug!(%address, "gossiped address confirmed reachable");
+        let target = address.gossip_target();
+        effect_builder
+            .begin_gossip(address, Source::Peer(peer_id), target)
+            .ignore()
+    }
+
+    /// Returns our address to gossip.
+    ///
+    /// Our address is signed if we have a consensus key, and gossiped unsigned otherwise.  It is
+    /// only re-signed once the previous signature is older than `GOSSIPED_ADDR_RESIGN_AGE`.
+    fn our_gossiped_address(&mut self) -> GossipedAddress {
+        let now = Timestamp::now();
+        if let Some(our_address) = &self.our_gossiped_address {
+            let is_current = our_address.signed().map_or(true, |signed| {
+                now.saturating_diff(signed.timestamp()) < GOSSIPED_ADDR_RESIGN_AGE
+            });
+            if is_current {
+                return our_address.clone();
+            }
+        }
+
+        let public_addr = self
+            .context
+            .public_addr()
+            .expect("component not initialized properly");
+        let our_address = match self.context.node_key_pair() {
+            Some(key_pair) => {
+                GossipedAddress::create_signed(public_addr, self.context.our_id(), now, key_pair)
+            }
+            None => GossipedAddress::new_unsigned(public_addr),
+        };
+        self.our_gossiped_address = Some(our_address.clone());
+        our_address
+    }
+
     fn handle_network_request(
         &self,
         request: NetworkRequest<P>,
@@ -875,6 +1072,8 @@ where
             .unmark_outgoing(Instant::now());
 
         self.outgoing_limiter.remove_connected_validator(&peer_id);
+        self.peer_wire_format_versions.remove(&peer_id);
+        self.outgoing_consensus_keys.remove(&peer_id);
 
         self.process_dial_requests(requests)
     }
@@ -890,12 +1089,14 @@ where
             trace!(%request, "processing dial request");
             match request {
                 DialRequest::Dial { addr, span } => effects.extend(
-                    tasks::connect_outgoing(self.context.clone(), addr)
-                        .instrument(span.clone())
-                        .event(|outgoing| Event::OutgoingConnection {
-                            outgoing: Box::new(outgoing),
-                            span,
-                        }),
+                    runtime::run_on_network(
+                        tasks::connect_outgoing(self.context.clone(), addr)
+                            .instrument(span.clone()),
+                    )
+                    .event(|outgoing| Event::OutgoingConnection {
+                        outgoing: Box::new(outgoing),
+                        span,
+                    }),
                 ),
                 DialRequest::Disconnect { handle: _, span } => {
                     // Dropping the `handle` is enough to signal the connection to shutdown.
@@ -938,22 +1139,29 @@ where
             }
             Message::Ping { nonce } => {
                 // Send a pong. Incoming pings and pongs are rate limited.
-
-                self.send_message(peer_id, Arc::new(Message::Pong { nonce }), None);
+                let wire_format_version = self
+                    .peer_wire_format_versions
+                    .get(&peer_id)
+                    .copied()
+                    .unwrap_or_default();
+                let pong = if wire_format_version >= TIMED_PONG_VERSION {
+                    Message::TimedPong {
+                        nonce,
+                        timestamp: Timestamp::now(),
+                    }
+                } else {
+                    Message::Pong { nonce }
+                };
+                self.send_message(peer_id, Arc::new(pong), None);
                 Effects::new()
             }
             Message::Pong { nonce } => {
-                // Record the time the pong arrived and forward it to outgoing.
-                let pong = TaggedTimestamp::from_parts(Instant::now(), nonce);
-                if self.outgoing_manager.record_pong(peer_id, pong) {
-                    // Note: We no longer block peers here with a `PongLimitExceeded` for failed
-                    //       pongs, merely warn.
-                    info!(
-                        "peer {} exceeded failed pong limit, or allowed number of pongs",
-                        peer_id // Redundant information due to span, but better safe than sorry.
-                    );
-                }
-
+                self.record_pong(peer_id, nonce);
+                Effects::new()
+            }
+            Message::TimedPong { nonce, timestamp } => {
+                self.record_pong(peer_id, nonce);
+                self.record_timed_pong(peer_id, timestamp);
                 Effects::new()
             }
             Message::Payload(payload) => {
@@ -962,6 +1170,40 @@ where
         })
     }
 
+    /// Records the time a pong arrived and forwards it to outgoing.
+    fn record_pong(&mut self, peer_id: NodeId, nonce: Nonce) {
+        let pong = TaggedTimestamp::from_parts(Instant::now(), nonce);
+        if self.outgoing_manager.record_pong(peer_id, pong) {
+            // Note: We no longer block peers here with a `PongLimitExceeded` for failed
+            //       pongs, merely warn.
+            info!(
+                "peer {} exceeded failed pong limit, or allowed number of pongs",
+                peer_id // Redundant information due to span, but better safe than sorry.
+            );
+        }
+    }
+
+    /// Updates the estimated clock offset of a validator peer from the time it sent a pong.
+    ///
+    /// The peer created the pong roughly half a round trip before it arrived.
+    fn record_timed_pong(&mut self, peer_id: NodeId, timestamp: Timestamp) {
+        if !self
+            .outgoing_limiter
+            .is_active_or_upcoming_validator(&peer_id)
+        {
+            return;
+        }
+        let rtt = match self.outgoing_manager.peer_rtt(peer_id) {
+            Some(rtt) => rtt,
+            None => return,
+        };
+        let sent_at = Timestamp::now()
+            .millis()
+            .saturating_sub(rtt.as_millis() as u64 / 2);
+        let offset = (timestamp.millis() as i64).saturating_sub(sent_at as i64);
+        self.peer_clock_offsets.insert(peer_id, offset);
+    }
+
     /// Emits an announcement that a connection has been completed.
     fn connection_completed(&self, peer_id: NodeId) {
         trace!(num_peers = self.peers().len(), new_peer=%peer_id, "connection complete");
@@ -1016,12 +1258,59 @@ where
             .choose_multiple(rng, count)
     }
 
-    pub(crate) fn has_sufficient_fully_connected_peers(&self) -> bool {
+    /// Returns up to `count` fully-connected peers, chosen at random among the
+    /// `LOW_RTT_CANDIDATES_FACTOR * count` peers with the lowest round-trip times.
+    ///
+    /// Choosing among a larger set of candidates avoids concentrating load on the few peers with
+    /// the lowest round-trip times.
+    pub(crate) fn fully_connected_peers_by_rtt(
+        &self,
+        rng: &mut NodeRng,
+        count: usize,
+    ) -> Vec<NodeId> {
+        let peers = self
+            .connection_symmetries
+            .iter()
+            .filter(|(_, sym)| matches!(sym, ConnectionSymmetry::Symmetric { .. }))
+            .map(|(node_id, _)| *node_id)
+            .collect();
+        let mut candidates = self.rank_by_rtt(rng, peers);
+        candidates.truncate(count.saturating_mul(LOW_RTT_CANDIDATES_FACTOR));
+        candidates.choose_multiple(rng, count).copied().collect()
+    }
+
+    /// Orders the given peers by round-trip time, lowest first.
+    ///
+    /// Peers whose round-trip time is not known come last, in random order.
+    pub(crate) fn rank_by_rtt(&self, rng: &mut NodeRng, mut peers: Vec<NodeId>) -> Vec<NodeId> {
+        peers.shuffle(rng);
+        peers.sort_by_key(|node_id| {
+            self.outgoing_manager
+                .peer_rtt(*node_id)
+                .unwrap_or(Duration::MAX)
+        });
+        peers
+    }
+
+    /// Returns the connected peers which authenticated with the key of an active or upcoming
+    /// validator.
+    pub(crate) fn validator_peers(&self) -> BTreeSet<NodeId> {
+        self.outgoing_consensus_keys
+            .keys()
+            .filter(|node_id| self.outgoing_limiter.is_active_or_upcoming_validator(node_id))
+            .copied()
+            .collect()
+    }
+
+    pub(crate) fn fully_connected_peer_count(&self) -> usize {
         self.connection_symmetries
             .iter()
             .filter(|(_node_id, sym)| matches!(sym, ConnectionSymmetry::Symmetric { .. }))
             .count()
-            >= self.cfg.min_peers_for_initialization as usize
+    }
+
+    pub(crate) fn has_sufficient_fully_connected_peers(&self) -> bool {
+        self.fully_connected_peer_count() >= self.cfg.min_peers_for_initialization as usize
     }
 
     #[cfg(test)]
@@ -1070,6 +1359,17 @@ where
     }
 }
 
+/// Returns `false` if the gossiped address is signed but the signature is invalid.
+fn has_valid_signature(gossiped_address: &GossipedAddress) -> bool {
+    match gossiped_address.signed().map(SignedAddress::verify) {
+        Some(Err(err)) => {
+            warn!(%gossiped_address, %err, "ignoring gossiped address with invalid signature");
+            false
+        }
+        Some(Ok(())) | None => true,
+    }
+}
+
 fn choose_gossip_peers<F>(
     rng: &mut NodeRng,
     gossip_target: GossipTarget,
@@ -1197,7 +1497,7 @@ where
                     span,
                 } => self.handle_incoming_closed(result, *peer_id, peer_addr, *span),
                 Event::OutgoingConnection { outgoing, span } => {
-                    self.handle_outgoing_connection(*outgoing, span)
+                    self.handle_outgoing_connection(effect_builder, *outgoing, span)
                 }
                 Event::OutgoingDropped { peer_id, peer_addr } => {
                     self.handle_outgoing_dropped(*peer_id, peer_addr)
@@ -1212,19 +1512,36 @@ where
                     NetworkInfoRequest::FullyConnectedPeers { count, responder } => responder
                         .respond(self.fully_connected_peers_random(rng, count))
                         .ignore(),
+                    NetworkInfoRequest::LowRttPeers { count, responder } => responder
+                        .respond(self.fully_connected_peers_by_rtt(rng, count))
+                        .ignore(),
+                    NetworkInfoRequest::RankByRtt { peers, responder } => {
+                        responder.respond(self.rank_by_rtt(rng, peers)).ignore()
+                    }
                     NetworkInfoRequest::Insight { responder } => responder
                         .respond(NetworkInsights::collect_from_component(self))
                         .ignore(),
+                    NetworkInfoRequest::PeerClockOffsets { responder } => {
+                        let peers = self.peers();
+                        self.peer_clock_offsets
+                            .retain(|peer_id, _| peers.contains_key(peer_id));
+                        let offsets = self
+                            .peer_clock_offsets
+                            .iter()
+                            .filter(|(peer_id, _)| {
+                                self.outgoing_limiter
+                                    .is_active_or_upcoming_validator(peer_id)
+                            })
+                            .map(|(peer_id, offset)| (*peer_id, *offset))
+                            .collect();
+                        responder.respond(offsets).ignore()
+                    }
                 },
                 Event::GossipOurAddress => {
-                    let our_address = GossipedAddress::new(
-                        self.context
-                            .public_addr()
-                            .expect("component not initialized properly"),
-                    );
-
+                    let our_address = self.our_gossiped_address();
+                    let target = our_address.gossip_target();
                     let mut effects = effect_builder
-                        .begin_gossip(our_address, Source::Ourself, our_address.gossip_target())
+                        .begin_gossip(Box::new(our_address), Source::Ourself, target)
                         .ignore();
                     effects.extend(
                         effect_builder
@@ -1234,16 +1551,14 @@ where
                     effects
                 }
                 Event::PeerAddressReceived(gossiped_address) => {
-                    let requests = self.outgoing_manager.learn_addr(
-                        gossiped_address.into(),
-                        false,
-                        Instant::now(),
-                    );
-                    self.process_dial_requests(requests)
+                    self.handle_peer_address_received(effect_builder, gossiped_address)
                 }
                 Event::SweepOutgoing => {
                     let now = Instant::now();
                     let requests = self.outgoing_manager.perform_housekeeping(rng, now);
+                    self.gossiped_addrs.retain(|_, (_, dialed_at)| {
+                        now.saturating_duration_since(*dialed_at) < GOSSIPED_ADDR_RETENTION
+                    });
 
                     let mut effects = self.process_dial_requests(requests);
 
@@ -1320,19 +1635,21 @@ pub(crate) type FullTransport<P> = tokio_serde::Framed<
     FramedTransport,
     Message<P>,
     Arc<Message<P>>,
-    CountingFormat<BincodeFormat>,
+    CountingFormat<VersionedFormat>,
 >;
 
 pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDelimitedCodec>;
 
 /// Constructs a new full transport on a stream.
 ///
-/// A full transport contains the framing as well as the encoding scheme used to send messages.
+/// A full transport contains the framing as well as the encoding scheme used to send messages, in
+/// the wire format version negotiated during the handshake.
 fn full_transport<P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
     framed: FramedTransport,
     role: Role,
+    wire_format_version: u8,
 ) -> FullTransport<P>
 where
     for<'de> P: Serialize + Deserialize<'de>,
@@ -1340,7 +1657,12 @@ where
 {
     tokio_serde::Framed::new(
         framed,
-        CountingFormat::new(metrics, connection_id, role, BincodeFormat::default()),
+        CountingFormat::new(
+            metrics,
+            connection_id,
+            role,
+            VersionedFormat::new(wire_format_version),
+        ),
     )
 }
 
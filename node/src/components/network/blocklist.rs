@@ -58,6 +58,10 @@ pub(crate) enum BlocklistJustification {
     DishonestPeer,
     /// Peer sent too many finality signatures.
     SentTooManyFinalitySignatures { max_allowed: u32 },
+    /// Peer was dropped by the node operator via the diagnostics port.
+    DroppedByOperator,
+    /// Peer stayed over its message cost budget for too long.
+    ExceededCostBudget { max_cost_per_second: u32 },
 }
 
 impl Display for BlocklistJustification {
@@ -106,6 +110,13 @@ impl Display for BlocklistJustification {
                 f,
                 "sent too many finality signatures: maximum {max_allowed} signatures are allowed"
             ),
+            BlocklistJustification::DroppedByOperator => f.write_str("dropped by operator"),
+            BlocklistJustification::ExceededCostBudget {
+                max_cost_per_second,
+            } => write!(
+                f,
+                "exceeded the message cost budget of {max_cost_per_second} per second"
+            ),
         }
     }
 }
@@ -6,12 +6,13 @@
 use std::net::SocketAddr;
 
 use casper_hashing::Digest;
-use casper_types::ProtocolVersion;
+use casper_types::{ProtocolVersion, Timestamp};
 use datasize::DataSize;
 
 use super::{
     counting_format::ConnectionId,
     message::{ConsensusCertificate, NodeKeyPair},
+    versioned_format::WIRE_FORMAT_VERSION,
     Message,
 };
 use crate::types::Chainspec;
@@ -61,6 +62,8 @@ impl ChainInfo {
                 .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
             is_syncing,
             chainspec_hash: Some(self.chainspec_hash),
+            timestamp: Some(Timestamp::now()),
+            wire_format_version: WIRE_FORMAT_VERSION,
         }
     }
 }
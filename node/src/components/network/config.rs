@@ -6,7 +6,7 @@ use casper_types::{ProtocolVersion, TimeDiff};
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use super::EstimatorWeights;
+use super::{CostWeights, EstimatorWeights};
 
 /// Default binding address.
 ///
@@ -32,6 +32,27 @@ const DEFAULT_MAX_ADDR_PENDING_TIME: TimeDiff = TimeDiff::from_seconds(60);
 /// Default timeout during which the handshake needs to be completed.
 const DEFAULT_HANDSHAKE_TIMEOUT: TimeDiff = TimeDiff::from_seconds(20);
 
+/// Default interval between pings down a healthy connection.
+const DEFAULT_PING_INTERVAL: TimeDiff = TimeDiff::from_seconds(30);
+
+/// Default time within which a ping must be answered.
+const DEFAULT_PING_TIMEOUT: TimeDiff = TimeDiff::from_seconds(6);
+
+/// Default number of unanswered pings after which a connection is dropped.
+const DEFAULT_PING_RETRIES: u16 = 5;
+
+fn default_ping_interval() -> TimeDiff {
+    DEFAULT_PING_INTERVAL
+}
+
+fn default_ping_timeout() -> TimeDiff {
+    DEFAULT_PING_TIMEOUT
+}
+
+fn default_ping_retries() -> u16 {
+    DEFAULT_PING_RETRIES
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -43,16 +64,22 @@ impl Default for Config {
             initial_gossip_delay: DEFAULT_INITIAL_GOSSIP_DELAY,
             max_addr_pending_time: DEFAULT_MAX_ADDR_PENDING_TIME,
             handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            ping_retries: DEFAULT_PING_RETRIES,
             max_incoming_peer_connections: 0,
             max_outgoing_byte_rate_non_validators: 0,
             max_incoming_message_rate_non_validators: 0,
             estimator_weights: Default::default(),
+            max_incoming_cost_per_peer: 0,
+            cost_weights: Default::default(),
             tarpit_version_threshold: None,
             tarpit_duration: TimeDiff::from_seconds(600),
             tarpit_chance: 0.2,
             max_in_flight_demands: 50,
             blocklist_retain_duration: TimeDiff::from_seconds(600),
             identity: None,
+            permissioned: false,
         }
     }
 }
@@ -93,6 +120,16 @@ pub struct Config {
     pub max_addr_pending_time: TimeDiff,
     /// Maximum allowed time for handshake completion.
     pub handshake_timeout: TimeDiff,
+    /// How often to ping a peer to check that the connection is still alive.
+    #[serde(default = "default_ping_interval")]
+    pub ping_interval: TimeDiff,
+    /// Time within which a ping must be answered; must be less than `ping_interval`.
+    #[serde(default = "default_ping_timeout")]
+    pub ping_timeout: TimeDiff,
+    /// Number of consecutive unanswered pings after which a connection is considered dead and
+    /// closed.
+    #[serde(default = "default_ping_retries")]
+    pub ping_retries: u16,
     /// Maximum number of incoming connections per unique peer. Unlimited if `0`.
     pub max_incoming_peer_connections: u16,
     /// Maximum number of bytes per second allowed for non-validating peers. Unlimited if 0.
@@ -101,6 +138,14 @@ pub struct Config {
     pub max_incoming_message_rate_non_validators: u32,
     /// Weight distribution for the payload impact estimator.
     pub estimator_weights: EstimatorWeights,
+    /// Maximum total cost of the messages a single peer may send per second, according to
+    /// `cost_weights`. Peers exceeding it are throttled, and disconnected if they stay over
+    /// budget. Unlimited if `0`.
+    #[serde(default)]
+    pub max_incoming_cost_per_peer: u32,
+    /// The cost of each type of incoming message, counted against `max_incoming_cost_per_peer`.
+    #[serde(default)]
+    pub cost_weights: CostWeights,
     /// The protocol version at which (or under) tarpitting is enabled.
     pub tarpit_version_threshold: Option<ProtocolVersion>,
     /// If tarpitting is enabled, duration for which connections should be kept open.
@@ -116,6 +161,12 @@ pub struct Config {
     /// An identity will be automatically generated when starting up a node if this option is
     /// unspecified.
     pub identity: Option<IdentityConfig>,
+    /// Whether to only connect to peers with certificates signed by the CA given in `identity`.
+    ///
+    /// Peers are then checked already during the TLS handshake, on both incoming and outgoing
+    /// connections, and the node refuses to start without an `identity`.
+    #[serde(default)]
+    pub permissioned: bool,
 }
 
 #[cfg(test)]
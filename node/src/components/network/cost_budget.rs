@@ -0,0 +1,179 @@
+//! Per-peer message cost accounting.
+//!
+//! Every incoming message is assigned a cost based on its type. Each connection has a budget that
+//! refills at a fixed rate per second. A peer exceeding it is throttled by pausing reading from its
+//! connection until the budget has recovered, and disconnected if it stays over budget for too
+//! long.
+//!
+//! Unlike the `Limiter`, which shares a single allowance among all non-validators, the budget is
+//! tracked separately for every connection, so a single peer flooding us with cheap messages cannot
+//! starve the event queue. Consensus messages from active or upcoming validators are never charged.
+
+use std::time::{Duration, Instant};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Amount of unused budget a peer can accumulate, in seconds worth of budget.
+const STORED_BUDGET_SECS: u64 = 2;
+
+/// Duration a peer can be continuously over budget before it is disconnected.
+const MAX_OVER_BUDGET_DURATION: Duration = Duration::from_secs(60);
+
+/// The cost of each category of incoming messages, counted against the sending peer's budget.
+///
+/// A weight of `0` exempts the category.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CostWeights {
+    /// Consensus messages. Never charged to active or upcoming validators.
+    pub consensus: u32,
+    /// Gossip of deploys, blocks, finality signatures and addresses.
+    pub gossip: u32,
+    /// Broadcast finality signatures.
+    pub finality_signatures: u32,
+    /// Requests for data.
+    pub requests: u32,
+    /// Responses to our own requests.
+    pub responses: u32,
+    /// Pings. Pongs answer our own pings and are never charged.
+    pub pings: u32,
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        // Responses answer our own requests, whose rate we control, so they are exempt.
+        CostWeights {
+            consensus: 1,
+            gossip: 1,
+            finality_signatures: 1,
+            requests: 1,
+            responses: 0,
+            pings: 1,
+        }
+    }
+}
+
+/// The result of charging a message to a peer's budget.
+#[derive(Debug, PartialEq)]
+pub(super) enum BudgetOutcome {
+    /// The message is within the peer's budget.
+    Allowed,
+    /// The peer is over budget; reading from it should be paused for the given duration.
+    Throttle(Duration),
+    /// The peer has been over budget for longer than `MAX_OVER_BUDGET_DURATION`.
+    Exceeded,
+}
+
+/// The message cost budget of a single connection.
+#[derive(Debug)]
+pub(super) struct CostBudget {
+    /// The cost per second the peer is allowed to incur. Unlimited if `0`.
+    cost_per_second: u32,
+    /// The currently available budget. Negative if the peer is over budget.
+    available: i64,
+    /// The last time the budget was refilled.
+    last_refill: Instant,
+    /// The time since which the peer has been continuously over budget, if it is.
+    over_budget_since: Option<Instant>,
+}
+
+impl CostBudget {
+    /// Creates a new, full budget allowing `cost_per_second`.
+    pub(super) fn new(cost_per_second: u32, now: Instant) -> Self {
+        CostBudget {
+            cost_per_second,
+            available: Self::max_stored(cost_per_second),
+            last_refill: now,
+            over_budget_since: None,
+        }
+    }
+
+    /// Charges a message with the given cost to the budget.
+    pub(super) fn charge(&mut self, cost: u32, now: Instant) -> BudgetOutcome {
+        if self.cost_per_second == 0 || cost == 0 {
+            return BudgetOutcome::Allowed;
+        }
+
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+        let refill = elapsed.as_nanos() * u128::from(self.cost_per_second) / 1_000_000_000;
+        self.available = self
+            .available
+            .saturating_add(i64::try_from(refill).unwrap_or(i64::MAX))
+            .min(Self::max_stored(self.cost_per_second))
+            .saturating_sub(i64::from(cost));
+
+        if self.available >= 0 {
+            self.over_budget_since = None;
+            return BudgetOutcome::Allowed;
+        }
+
+        let over_budget_since = *self.over_budget_since.get_or_insert(now);
+        if now.saturating_duration_since(over_budget_since) > MAX_OVER_BUDGET_DURATION {
+            return BudgetOutcome::Exceeded;
+        }
+
+        let deficit = self.available.unsigned_abs();
+        BudgetOutcome::Throttle(Duration::from_nanos(
+            deficit.saturating_mul(1_000_000_000) / u64::from(self.cost_per_second),
+        ))
+    }
+
+    /// The maximum amount of budget that can be accumulated.
+    fn max_stored(cost_per_second: u32) -> i64 {
+        i64::from(cost_per_second).saturating_mul(STORED_BUDGET_SECS as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_budget_allows_everything() {
+        let now = Instant::now();
+        let mut budget = CostBudget::new(0, now);
+        for _ in 0..1000 {
+            assert_eq!(budget.charge(u32::MAX, now), BudgetOutcome::Allowed);
+        }
+    }
+
+    #[test]
+    fn throttles_and_disconnects_peer_over_budget() {
+        let start = Instant::now();
+        let mut budget = CostBudget::new(10, start);
+
+        // The stored budget of two seconds is used up first.
+        for _ in 0..20 {
+            assert_eq!(budget.charge(1, start), BudgetOutcome::Allowed);
+        }
+        assert_eq!(
+            budget.charge(5, start),
+            BudgetOutcome::Throttle(Duration::from_millis(500))
+        );
+
+        // Once the budget has recovered, messages are allowed again.
+        let later = start + Duration::from_secs(1);
+        assert_eq!(budget.charge(5, later), BudgetOutcome::Allowed);
+
+        // A peer that stays over budget is eventually disconnected.
+        let mut now = later;
+        let outcome = loop {
+            match budget.charge(20, now) {
+                BudgetOutcome::Allowed => {}
+                BudgetOutcome::Throttle(delay) => now += delay / 2,
+                BudgetOutcome::Exceeded => break now,
+            }
+        };
+        assert!(outcome - later > MAX_OVER_BUDGET_DURATION);
+    }
+
+    #[test]
+    fn missing_cost_weights_should_use_defaults() {
+        let weights: CostWeights = toml::from_str("requests = 5").unwrap();
+        assert_eq!(weights.requests, 5);
+        assert_eq!(weights.consensus, CostWeights::default().consensus);
+        assert_eq!(weights.responses, 0);
+    }
+}
@@ -6,7 +6,7 @@ use serde::Serialize;
 use thiserror::Error;
 
 use casper_hashing::Digest;
-use casper_types::{crypto, ProtocolVersion};
+use casper_types::{crypto, ProtocolVersion, TimeDiff};
 
 use crate::{
     tls::{LoadCertError, ValidationError},
@@ -71,6 +71,12 @@ pub enum Error {
         #[from]
         LoadCertError,
     ),
+    /// The configured ping timeout is not less than the ping interval.
+    #[error("ping timeout {ping_timeout} must be less than ping interval {ping_interval}")]
+    PingTimeoutNotBelowInterval {
+        ping_timeout: TimeDiff,
+        ping_interval: TimeDiff,
+    },
 }
 
 // Manual implementation for `DataSize` - the type contains too many FFI variants that are hard to
@@ -199,6 +205,19 @@ pub enum ConnectionError {
     FailedToReuniteHandshakeSinkAndStream,
 }
 
+impl ConnectionError {
+    /// Returns whether the error indicates that the peer belongs to a different network, i.e. it
+    /// reported a different network name or chainspec hash.
+    pub(super) fn is_cross_network(&self) -> bool {
+        matches!(
+            self,
+            ConnectionError::WrongNetwork(_)
+                | ConnectionError::WrongChainspecHash(_)
+                | ConnectionError::MissingChainspecHash
+        )
+    }
+}
+
 /// IO operation that can time out or close.
 #[derive(Debug, Error)]
 pub enum IoError<E>
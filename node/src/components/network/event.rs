@@ -86,7 +86,7 @@ pub(crate) enum Event<P> {
     GossipOurAddress,
 
     /// We received a peer's public listening address via gossip.
-    PeerAddressReceived(GossipedAddress),
+    PeerAddressReceived(Box<GossipedAddress>),
 
     /// Housekeeping for the outgoing manager.
     SweepOutgoing,
@@ -176,6 +176,8 @@ pub(crate) enum IncomingConnection<P> {
         peer_id: NodeId,
         /// The public key the peer is validating with, if any.
         peer_consensus_public_key: Option<PublicKey>,
+        /// Estimated offset of the peer's clock from ours in milliseconds, if known.
+        clock_offset: Option<i64>,
         /// Stream of incoming messages. for incoming connections.
         #[serde(skip_serializing)]
         stream: SplitStream<FullTransport<P>>,
@@ -199,6 +201,7 @@ impl<P> Display for IncomingConnection<P> {
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                clock_offset: _,
                 stream: _,
             } => {
                 write!(
@@ -251,6 +254,10 @@ pub(crate) enum OutgoingConnection<P> {
         sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
         /// Holds the information whether the remote node is syncing.
         is_syncing: bool,
+        /// Estimated offset of the peer's clock from ours in milliseconds, if known.
+        clock_offset: Option<i64>,
+        /// The wire format version negotiated with the peer.
+        wire_format_version: u8,
     },
 }
 
@@ -272,6 +279,8 @@ impl<P> Display for OutgoingConnection<P> {
                 peer_consensus_public_key,
                 sink: _,
                 is_syncing,
+                clock_offset: _,
+                wire_format_version: _,
             } => {
                 write!(
                     f,
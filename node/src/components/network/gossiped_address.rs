@@ -1,31 +1,144 @@
 use std::{
     fmt::{self, Display, Formatter},
-    net::SocketAddr,
+    net::{SocketAddr, SocketAddrV4, SocketAddrV6},
 };
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::{crypto, PublicKey, Signature, Timestamp};
+
+use super::message::NodeKeyPair;
 use crate::{
     components::gossiper::{GossipItem, SmallGossipItem},
     effect::GossipTarget,
+    types::NodeId,
 };
 
 /// Used to gossip our public listening address to peers.
-#[derive(
-    Copy, Clone, DataSize, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug,
-)]
-pub struct GossipedAddress(SocketAddr);
+///
+/// Nodes with a consensus key sign their address together with their node ID and the time it was
+/// advertised, so that peers can't gossip endpoints on behalf of nodes that don't control them.
+/// Nodes without one gossip the bare address.
+///
+/// The first two variants mirror the encoding of `SocketAddr`, so unsigned addresses are encoded
+/// exactly like the plain addresses gossiped by nodes predating signed addresses.
+#[derive(Clone, DataSize, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug)]
+pub enum GossipedAddress {
+    /// An unsigned IPv4 address.
+    V4(#[data_size(skip)] SocketAddrV4),
+    /// An unsigned IPv6 address.
+    V6(#[data_size(skip)] SocketAddrV6),
+    /// An address signed by the advertising node.
+    Signed(Box<SignedAddress>),
+}
+
+/// A public listening address signed by the advertising node.
+#[derive(Clone, DataSize, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug)]
+pub struct SignedAddress {
+    /// The advertised public listening address.
+    address: SocketAddr,
+    /// The ID of the advertising node, i.e. the fingerprint of its TLS certificate.
+    node_id: NodeId,
+    /// The time at which the address was signed.
+    timestamp: Timestamp,
+    /// The public key of the advertising node.
+    public_key: PublicKey,
+    /// The advertising node's signature over the address, node ID and timestamp.
+    signature: Signature,
+}
 
 impl GossipedAddress {
-    pub(super) fn new(address: SocketAddr) -> Self {
-        GossipedAddress(address)
+    /// Creates a new unsigned gossiped address.
+    pub(super) fn new_unsigned(address: SocketAddr) -> Self {
+        match address {
+            SocketAddr::V4(address) => GossipedAddress::V4(address),
+            SocketAddr::V6(address) => GossipedAddress::V6(address),
+        }
     }
+
+    /// Creates a new gossiped address for the node with the given ID, signed with the given key
+    /// pair.
+    pub(super) fn create_signed(
+        address: SocketAddr,
+        node_id: NodeId,
+        timestamp: Timestamp,
+        key_pair: &NodeKeyPair,
+    ) -> Self {
+        let signature = key_pair.sign(signed_bytes(&address, &node_id, timestamp));
+        GossipedAddress::Signed(Box::new(SignedAddress {
+            address,
+            node_id,
+            timestamp,
+            public_key: key_pair.public_key().clone(),
+            signature,
+        }))
+    }
+
+    /// The advertised address.
+    pub(super) fn address(&self) -> SocketAddr {
+        match self {
+            GossipedAddress::V4(address) => SocketAddr::V4(*address),
+            GossipedAddress::V6(address) => SocketAddr::V6(*address),
+            GossipedAddress::Signed(signed) => signed.address,
+        }
+    }
+
+    /// The signed address, if the address is signed.
+    pub(super) fn signed(&self) -> Option<&SignedAddress> {
+        match self {
+            GossipedAddress::V4(_) | GossipedAddress::V6(_) => None,
+            GossipedAddress::Signed(signed) => Some(signed.as_ref()),
+        }
+    }
+}
+
+impl SignedAddress {
+    /// The time at which the address was signed.
+    pub(super) fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Verifies that the address, node ID and timestamp were signed by the advertising node.
+    pub(super) fn verify(&self) -> Result<(), crypto::Error> {
+        crypto::verify(
+            signed_bytes(&self.address, &self.node_id, self.timestamp),
+            &self.signature,
+            &self.public_key,
+        )
+    }
+
+    /// Returns whether the node we connected to is the one that signed the address, i.e. it
+    /// presented the signed node ID as its TLS identity and the signing key as its consensus key.
+    pub(super) fn is_signed_by(
+        &self,
+        peer_id: NodeId,
+        peer_consensus_public_key: Option<&PublicKey>,
+    ) -> bool {
+        self.node_id == peer_id && peer_consensus_public_key == Some(&self.public_key)
+    }
+}
+
+/// Returns the bytes that are signed by the advertising node.
+fn signed_bytes(address: &SocketAddr, node_id: &NodeId, timestamp: Timestamp) -> Vec<u8> {
+    let mut bytes = address.to_string().into_bytes();
+    bytes.extend(node_id.hash_bytes());
+    bytes.extend(timestamp.millis().to_le_bytes());
+    bytes
 }
 
 impl Display for GossipedAddress {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        write!(formatter, "gossiped-address {}", self.0)
+        match self {
+            GossipedAddress::V4(_) | GossipedAddress::V6(_) => {
+                write!(formatter, "gossiped-address {}", self.address())
+            }
+            GossipedAddress::Signed(signed) => write!(
+                formatter,
+                "gossiped-address {} of {} by {} at {}",
+                signed.address, signed.node_id, signed.public_key, signed.timestamp
+            ),
+        }
     }
 }
 
@@ -33,10 +146,10 @@ impl GossipItem for GossipedAddress {
     const ID_IS_COMPLETE_ITEM: bool = true;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
 
-    type Id = GossipedAddress;
+    type Id = Box<GossipedAddress>;
 
     fn gossip_id(&self) -> Self::Id {
-        *self
+        Box::new(self.clone())
     }
 
     fn gossip_target(&self) -> GossipTarget {
@@ -45,25 +158,99 @@ impl GossipItem for GossipedAddress {
 }
 
 impl SmallGossipItem for GossipedAddress {
+    // Addresses are only gossiped onwards once we could connect to them, so that a spoofed address
+    // doesn't spread across the network.
+    const GOSSIP_ONLY_WHEN_CONFIRMED: bool = true;
+
     fn id_as_item(id: &Self::Id) -> &Self {
         id
     }
 }
 
-impl From<GossipedAddress> for SocketAddr {
-    fn from(gossiped_address: GossipedAddress) -> Self {
-        gossiped_address.0
-    }
-}
-
 mod specimen_support {
     use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
 
-    use super::GossipedAddress;
+    use super::{GossipedAddress, SignedAddress};
 
     impl LargestSpecimen for GossipedAddress {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
-            GossipedAddress::new(LargestSpecimen::largest_specimen(estimator, cache))
+            GossipedAddress::Signed(Box::new(SignedAddress {
+                address: LargestSpecimen::largest_specimen(estimator, cache),
+                node_id: LargestSpecimen::largest_specimen(estimator, cache),
+                timestamp: LargestSpecimen::largest_specimen(estimator, cache),
+                public_key: LargestSpecimen::largest_specimen(estimator, cache),
+                signature: LargestSpecimen::largest_specimen(estimator, cache),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use casper_types::{testing::TestRng, SecretKey};
+
+    use super::*;
+    use crate::components::network::BincodeFormat;
+
+    fn create_signed(rng: &mut TestRng) -> (GossipedAddress, NodeId, PublicKey) {
+        let secret_key = SecretKey::random(rng);
+        let public_key = PublicKey::from(&secret_key);
+        let key_pair = NodeKeyPair::new((Arc::new(secret_key), public_key.clone()));
+        let address: SocketAddr = "127.0.0.1:34553".parse().unwrap();
+        let node_id = NodeId::random(rng);
+        let gossiped_address =
+            GossipedAddress::create_signed(address, node_id, Timestamp::from(1_000), &key_pair);
+        (gossiped_address, node_id, public_key)
+    }
+
+    #[test]
+    fn should_verify_signed_address() {
+        let mut rng = TestRng::new();
+        let (gossiped_address, _, _) = create_signed(&mut rng);
+        let signed = gossiped_address.signed().unwrap();
+        assert!(signed.verify().is_ok());
+
+        // Changing the address, node ID or timestamp invalidates the signature.
+        let mut spoofed = signed.clone();
+        spoofed.address = "127.0.0.2:34553".parse().unwrap();
+        assert!(spoofed.verify().is_err());
+        let mut impersonated = signed.clone();
+        impersonated.node_id = NodeId::random(&mut rng);
+        assert!(impersonated.verify().is_err());
+        let mut replayed = signed.clone();
+        replayed.timestamp = Timestamp::from(2_000);
+        assert!(replayed.verify().is_err());
+    }
+
+    #[test]
+    fn should_only_match_signing_node() {
+        let mut rng = TestRng::new();
+        let (gossiped_address, node_id, public_key) = create_signed(&mut rng);
+        let signed = gossiped_address.signed().unwrap();
+
+        assert!(signed.is_signed_by(node_id, Some(&public_key)));
+        assert!(!signed.is_signed_by(node_id, None));
+        assert!(!signed.is_signed_by(node_id, Some(&PublicKey::random(&mut rng))));
+        assert!(!signed.is_signed_by(NodeId::random(&mut rng), Some(&public_key)));
+    }
+
+    #[test]
+    fn should_read_plain_socket_address() {
+        let format = BincodeFormat::default();
+        for address in ["127.0.0.1:34553", "[::1]:34553"] {
+            let address: SocketAddr = address.parse().unwrap();
+            let serialized = format.serialize_arbitrary(&address).unwrap();
+            let gossiped_address: GossipedAddress =
+                bincode::Options::deserialize(format.0, &serialized).unwrap();
+            assert_eq!(gossiped_address, GossipedAddress::new_unsigned(address));
+            assert_eq!(gossiped_address.address(), address);
+            assert!(gossiped_address.signed().is_none());
+            assert_eq!(
+                format.serialize_arbitrary(&gossiped_address).unwrap(),
+                serialized
+            );
         }
     }
 }
@@ -30,6 +30,8 @@ pub(crate) struct ConnectionHealth {
     pub(crate) invalid_pong_count: u32,
     /// Number of pings that timed out.
     pub(crate) ping_timeouts: u32,
+    /// The round-trip time of the most recent successful ping.
+    pub(crate) last_rtt: Option<Duration>,
 }
 
 /// Health check configuration.
@@ -116,6 +118,7 @@ impl ConnectionHealth {
             last_pong_received: None,
             invalid_pong_count: 0,
             ping_timeouts: 0,
+            last_rtt: None,
         }
     }
 }
@@ -236,6 +239,7 @@ impl ConnectionHealth {
             self.invalid_pong_count = 0;
             self.ping_timeouts = 0;
             self.last_pong_received = Some(tt);
+            self.last_rtt = self.calc_rrt();
             false
         } else {
             self.invalid_pong_count += 1;
@@ -461,6 +465,7 @@ mod tests {
         // Record a reply 500 ms later.
         clock.advance(Duration::from_millis(500));
         assert!(!health.record_pong(&cfg, TaggedTimestamp::from_parts(clock.now(), nonce_1)));
+        assert_eq!(health.last_rtt, Some(Duration::from_millis(500)));
 
         // Our next pong should be 5 seconds later, not 4.5.
         clock.advance(Duration::from_millis(4500));
@@ -475,6 +480,9 @@ mod tests {
             HealthCheckOutcome::SendPing(nonce) => nonce
         );
 
+        // The round-trip time is kept while the next ping is outstanding.
+        assert_eq!(health.last_rtt, Some(Duration::from_millis(500)));
+
         // We test an edge case here where we use the same timestamp for the received pong.
         clock.advance(Duration::from_millis(500));
         assert!(!health.record_pong(&cfg, TaggedTimestamp::from_parts(clock.now(), nonce_2)));
@@ -26,6 +26,8 @@ pub(crate) enum Error {
     LoadCert(#[from] LoadCertError),
     #[error(transparent)]
     LoadSecretKey(#[from] LoadSecretKeyError),
+    #[error("a permissioned network requires a network identity with a CA certificate")]
+    PermissionedWithoutIdentity,
 }
 
 /// An ephemeral [PKey<Private>] and [TlsCert] that identifies this node
@@ -48,6 +50,7 @@ impl Identity {
     pub(crate) fn from_config(config: WithDir<Config>) -> Result<Self, Error> {
         match &config.value().identity {
             Some(identity) => Self::from_identity_config(identity),
+            None if config.value().permissioned => Err(Error::PermissionedWithoutIdentity),
             None => Self::with_generated_certs(),
         }
     }
@@ -111,6 +111,24 @@ impl Limiter {
         }
     }
 
+    /// Returns whether the peer authenticated with the key of an active or upcoming validator.
+    pub(super) fn is_active_or_upcoming_validator(&self, peer_id: &NodeId) -> bool {
+        match self.data.connected_validators.read() {
+            Ok(connected_validators) => {
+                connected_validators
+                    .get(peer_id)
+                    .map_or(false, |public_key| {
+                        self.validator_matrix
+                            .is_active_or_upcoming_validator(public_key)
+                    })
+            }
+            Err(_) => {
+                error!("could not read from connected_validators of limiter, lock poisoned");
+                false
+            }
+        }
+    }
+
     pub(super) fn debug_inspect_unspent_allowance(&self) -> Option<i64> {
         Some(task::block_in_place(move || {
             Handle::current().block_on(async move { self.data.resources.lock().await.available })
@@ -197,6 +215,17 @@ pub(super) struct LimiterHandle {
 }
 
 impl LimiterHandle {
+    /// Returns whether the peer authenticated with the key of an active or upcoming validator.
+    pub(super) fn is_active_or_upcoming_validator(&self) -> bool {
+        self.consumer_id
+            .consensus_key
+            .as_ref()
+            .map_or(false, |public_key| {
+                self.validator_matrix
+                    .is_active_or_upcoming_validator(public_key)
+            })
+    }
+
     /// Waits until the requester is allocated `amount` additional resources.
     pub(super) async fn request_allowance(&self, amount: u32) {
         // As a first step, determine the peer class by checking if our id is in the validator set.
@@ -353,6 +382,30 @@ mod tests {
         assert!(start.elapsed() < SHORT_TIME);
     }
 
+    #[test]
+    fn should_recognize_only_authenticated_validators() {
+        let mut rng = crate::new_rng();
+
+        let secret_key = SecretKey::random(&mut rng);
+        let consensus_key = PublicKey::from(&secret_key);
+        let validator_matrix = ValidatorMatrix::new_with_validator(Arc::new(secret_key));
+        let limiter = Limiter::new(0, new_wait_time_sec(), validator_matrix);
+
+        let validator = NodeId::random(&mut rng);
+        let non_validator = NodeId::random(&mut rng);
+        let anonymous = NodeId::random(&mut rng);
+        let _ = limiter.create_handle(validator, Some(consensus_key));
+        let _ = limiter.create_handle(non_validator, Some(PublicKey::random(&mut rng)));
+        let _ = limiter.create_handle(anonymous, None);
+
+        assert!(limiter.is_active_or_upcoming_validator(&validator));
+        assert!(!limiter.is_active_or_upcoming_validator(&non_validator));
+        assert!(!limiter.is_active_or_upcoming_validator(&anonymous));
+
+        limiter.remove_connected_validator(&validator);
+        assert!(!limiter.is_active_or_upcoming_validator(&validator));
+    }
+
     #[tokio::test]
     async fn inactive_validator_limited() {
         let rng = &mut crate::new_rng();
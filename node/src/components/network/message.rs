@@ -15,9 +15,13 @@ use strum::EnumDiscriminants;
 use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{
+    crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature, Timestamp,
+};
 
-use super::{counting_format::ConnectionId, health::Nonce, BincodeFormat};
+use super::{
+    cost_budget::CostWeights, counting_format::ConnectionId, health::Nonce, BincodeFormat,
+};
 use crate::{
     effect::EffectBuilder,
     protocol,
@@ -55,6 +59,13 @@ pub(crate) enum Message<P> {
         /// Hash of the chainspec the node is running.
         #[serde(default)]
         chainspec_hash: Option<Digest>,
+        /// The sender's wall-clock time when creating the handshake.
+        #[serde(default)]
+        timestamp: Option<Timestamp>,
+        /// Highest wire format version the node supports for messages after the handshake. `0`
+        /// for nodes predating wire format versioning.
+        #[serde(default)]
+        wire_format_version: u8,
     },
     /// A ping request.
     Ping {
@@ -67,6 +78,15 @@ pub(crate) enum Message<P> {
         nonce: Nonce,
     },
     Payload(P),
+    /// A pong response carrying the sender's wall-clock time, sent instead of a `Pong` from wire
+    /// format version 2 onwards.  Appended after `Payload` to leave the encoding of the other
+    /// variants unchanged.
+    TimedPong {
+        /// Nonce to match pong to ping.
+        nonce: Nonce,
+        /// The sender's wall-clock time when creating the pong.
+        timestamp: Timestamp,
+    },
 }
 
 impl<P: Payload> Message<P> {
@@ -74,9 +94,10 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn classify(&self) -> MessageKind {
         match self {
-            Message::Handshake { .. } | Message::Ping { .. } | Message::Pong { .. } => {
-                MessageKind::Protocol
-            }
+            Message::Handshake { .. }
+            | Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::TimedPong { .. } => MessageKind::Protocol,
             Message::Payload(payload) => payload.message_kind(),
         }
     }
@@ -85,11 +106,26 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn is_low_priority(&self) -> bool {
         match self {
-            Message::Handshake { .. } | Message::Ping { .. } | Message::Pong { .. } => false,
+            Message::Handshake { .. }
+            | Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::TimedPong { .. } => false,
             Message::Payload(payload) => payload.is_low_priority(),
         }
     }
 
+    /// Determines whether or not a message may be dropped when the node is overloaded.
+    #[inline]
+    pub(super) fn is_droppable(&self) -> bool {
+        match self {
+            Message::Handshake { .. }
+            | Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::TimedPong { .. } => false,
+            Message::Payload(payload) => payload.is_droppable(),
+        }
+    }
+
     /// Returns the incoming resource estimate of the payload.
     #[inline]
     pub(super) fn payload_incoming_resource_estimate(&self, weights: &EstimatorWeights) -> u32 {
@@ -98,16 +134,29 @@ impl<P: Payload> Message<P> {
             // Ping and Pong have a hardcoded weights. Since every ping will result in a pong being
             // sent as a reply, it has a higher weight.
             Message::Ping { .. } => 2,
-            Message::Pong { .. } => 1,
+            Message::Pong { .. } | Message::TimedPong { .. } => 1,
             Message::Payload(payload) => payload.incoming_resource_estimate(weights),
         }
     }
 
+    /// Returns the cost of the message against the sending peer's budget.
+    #[inline]
+    pub(super) fn incoming_cost(&self, weights: &CostWeights) -> u32 {
+        match self {
+            Message::Handshake { .. } | Message::Pong { .. } | Message::TimedPong { .. } => 0,
+            Message::Ping { .. } => weights.pings,
+            Message::Payload(payload) => payload.incoming_cost(weights),
+        }
+    }
+
     /// Returns whether or not the payload is unsafe for syncing node consumption.
     #[inline]
     pub(super) fn payload_is_unsafe_for_syncing_nodes(&self) -> bool {
         match self {
-            Message::Handshake { .. } | Message::Ping { .. } | Message::Pong { .. } => false,
+            Message::Handshake { .. }
+            | Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::TimedPong { .. } => false,
             Message::Payload(payload) => payload.is_unsafe_for_syncing_peers(),
         }
     }
@@ -124,9 +173,10 @@ impl<P: Payload> Message<P> {
         REv: FromIncoming<P> + Send,
     {
         match self {
-            Message::Handshake { .. } | Message::Ping { .. } | Message::Pong { .. } => {
-                Err(self.into())
-            }
+            Message::Handshake { .. }
+            | Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::TimedPong { .. } => Err(self.into()),
             Message::Payload(payload) => {
                 // Note: For now, the wrapping/unwrap of the payload is a bit unfortunate here.
                 REv::try_demand_from_incoming(effect_builder, sender, payload)
@@ -152,9 +202,14 @@ impl NodeKeyPair {
     }
 
     /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
+    pub(super) fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
         crypto::sign(value, &self.secret_key, &self.public_key)
     }
+
+    /// The public key of this keypair.
+    pub(super) fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
 }
 
 /// Certificate used to indicate that the peer is a validator using the specified public key.
@@ -293,21 +348,28 @@ impl<P: Display> Display for Message<P> {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                timestamp,
+                wire_format_version,
             } => {
                 write!(
                     f,
-                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}",
+                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}, timestamp: {}, wire_format_version: {}",
                     network_name,
                     public_addr,
                     protocol_version,
                     OptDisplay::new(consensus_certificate.as_ref(), "none"),
                     is_syncing,
-                    OptDisplay::new(chainspec_hash.as_ref(), "none")
+                    OptDisplay::new(chainspec_hash.as_ref(), "none"),
+                    OptDisplay::new(timestamp.as_ref(), "none"),
+                    wire_format_version
                 )
             }
             Message::Ping { nonce } => write!(f, "ping({})", nonce),
             Message::Pong { nonce } => write!(f, "pong({})", nonce),
             Message::Payload(payload) => write!(f, "payload: {}", payload),
+            Message::TimedPong { nonce, timestamp } => {
+                write!(f, "pong({}) at {}", nonce, timestamp)
+            }
         }
     }
 }
@@ -367,11 +429,19 @@ pub(crate) trait Payload:
     /// The penalty for resource usage of a message to be applied when processed as incoming.
     fn incoming_resource_estimate(&self, _weights: &EstimatorWeights) -> u32;
 
+    /// The cost of the message against the sending peer's budget.
+    fn incoming_cost(&self, _weights: &CostWeights) -> u32;
+
     /// Determines if the payload should be considered low priority.
     fn is_low_priority(&self) -> bool {
         false
     }
 
+    /// Determines if the payload may be dropped when the node is overloaded.
+    fn is_droppable(&self) -> bool {
+        false
+    }
+
     /// Indicates a message is not safe to send to a syncing node.
     ///
     /// This functionality should be removed once multiplexed networking lands.
@@ -432,6 +502,10 @@ pub struct EstimatorWeights {
     pub approvals_hashes_responses: u32,
     pub execution_results_requests: u32,
     pub execution_results_responses: u32,
+    #[serde(default)]
+    pub era_validators_requests: u32,
+    #[serde(default)]
+    pub era_validators_responses: u32,
 }
 
 mod specimen_support {
@@ -464,6 +538,8 @@ mod specimen_support {
                         consensus_certificate: LargestSpecimen::largest_specimen(estimator, cache),
                         is_syncing: LargestSpecimen::largest_specimen(estimator, cache),
                         chainspec_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                        timestamp: LargestSpecimen::largest_specimen(estimator, cache),
+                        wire_format_version: LargestSpecimen::largest_specimen(estimator, cache),
                     },
                     MessageDiscriminants::Ping => Message::Ping {
                         nonce: LargestSpecimen::largest_specimen(estimator, cache),
@@ -474,6 +550,10 @@ mod specimen_support {
                     MessageDiscriminants::Payload => {
                         Message::Payload(LargestSpecimen::largest_specimen(estimator, cache))
                     }
+                    MessageDiscriminants::TimedPong => Message::TimedPong {
+                        nonce: LargestSpecimen::largest_specimen(estimator, cache),
+                        timestamp: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
                 },
             )
         }
@@ -768,6 +848,8 @@ mod tests {
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
             is_syncing: false,
             chainspec_hash: Some(Digest::hash("example-chainspec")),
+            timestamp: Some(Timestamp::now()),
+            wire_format_version: 1,
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -802,6 +884,8 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            timestamp,
+            wire_format_version,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -809,7 +893,9 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(timestamp.is_none());
+            assert_eq!(wire_format_version, 0);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -826,6 +912,8 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            timestamp,
+            wire_format_version,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -834,7 +922,9 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(timestamp.is_none());
+            assert_eq!(wire_format_version, 0);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -851,6 +941,8 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            timestamp,
+            wire_format_version,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -878,7 +970,9 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(timestamp.is_none());
+            assert_eq!(wire_format_version, 0);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -895,6 +989,8 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            timestamp,
+            wire_format_version,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -922,7 +1018,9 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(timestamp.is_none());
+            assert_eq!(wire_format_version, 0);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
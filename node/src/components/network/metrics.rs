@@ -1,4 +1,4 @@
-use std::sync::Weak;
+use std::{sync::Weak, time::Duration};
 
 use prometheus::{Counter, IntCounter, IntGauge, Registry};
 use tracing::debug;
@@ -67,6 +67,9 @@ pub(super) struct Metrics {
     /// Number of outgoing connections in loopback state.
     pub(super) out_state_loopback: IntGauge,
 
+    /// Number of connections rejected because the peer is on a different network.
+    pub(super) rejected_cross_network_connections: IntCounter,
+
     /// Volume in bytes of incoming messages that are protocol overhead.
     pub(super) in_bytes_protocol: IntCounter,
     /// Volume in bytes of incoming messages with consensus payload.
@@ -114,6 +117,11 @@ pub(super) struct Metrics {
     pub(super) accumulated_outgoing_limiter_delay: Counter,
     /// Total time spent delaying incoming traffic from non-validators due to limiter, in seconds.
     pub(super) accumulated_incoming_limiter_delay: Counter,
+    /// Total time spent delaying incoming traffic from peers over their message cost budget, in
+    /// seconds.
+    pub(super) accumulated_cost_budget_delay: Counter,
+    /// Number of connections closed because the peer stayed over its message cost budget.
+    pub(super) cost_budget_exceeded: IntCounter,
 
     /// Registry instance.
     registry: Registry,
@@ -237,6 +245,11 @@ impl Metrics {
             "number of connections in the loopback state",
         )?;
 
+        let rejected_cross_network_connections = IntCounter::new(
+            "net_rejected_cross_network_connections",
+            "number of connections rejected due to a mismatched network name or chainspec hash",
+        )?;
+
         let in_count_protocol = IntCounter::new(
             "net_in_count_protocol",
             "count of incoming messages that are protocol overhead",
@@ -336,6 +349,14 @@ impl Metrics {
             "accumulated_incoming_limiter_delay",
             "seconds spent delaying incoming traffic from non-validators due to limiter, in seconds."
         )?;
+        let accumulated_cost_budget_delay = Counter::new(
+            "accumulated_cost_budget_delay",
+            "seconds spent delaying incoming traffic from peers over their message cost budget",
+        )?;
+        let cost_budget_exceeded = IntCounter::new(
+            "cost_budget_exceeded",
+            "number of connections closed because the peer stayed over its message cost budget",
+        )?;
 
         registry.register(Box::new(broadcast_requests.clone()))?;
         registry.register(Box::new(direct_message_requests.clone()))?;
@@ -370,6 +391,8 @@ impl Metrics {
         registry.register(Box::new(out_state_blocked.clone()))?;
         registry.register(Box::new(out_state_loopback.clone()))?;
 
+        registry.register(Box::new(rejected_cross_network_connections.clone()))?;
+
         registry.register(Box::new(in_count_protocol.clone()))?;
         registry.register(Box::new(in_count_consensus.clone()))?;
         registry.register(Box::new(in_count_deploy_gossip.clone()))?;
@@ -397,6 +420,8 @@ impl Metrics {
 
         registry.register(Box::new(accumulated_outgoing_limiter_delay.clone()))?;
         registry.register(Box::new(accumulated_incoming_limiter_delay.clone()))?;
+        registry.register(Box::new(accumulated_cost_budget_delay.clone()))?;
+        registry.register(Box::new(cost_budget_exceeded.clone()))?;
 
         Ok(Metrics {
             broadcast_requests,
@@ -428,6 +453,7 @@ impl Metrics {
             out_state_connected,
             out_state_blocked,
             out_state_loopback,
+            rejected_cross_network_connections,
             in_count_protocol,
             in_count_consensus,
             in_count_deploy_gossip,
@@ -452,6 +478,8 @@ impl Metrics {
             requests_for_trie_finished,
             accumulated_outgoing_limiter_delay,
             accumulated_incoming_limiter_delay,
+            accumulated_cost_budget_delay,
+            cost_budget_exceeded,
             registry: registry.clone(),
         })
     }
@@ -584,6 +612,26 @@ impl Metrics {
             debug!("not recording metrics, component already shut down");
         }
     }
+
+    /// Records that reading from a peer was paused because it was over its message cost budget.
+    pub(super) fn record_cost_budget_delay(this: &Weak<Self>, delay: Duration) {
+        if let Some(metrics) = this.upgrade() {
+            metrics
+                .accumulated_cost_budget_delay
+                .inc_by(delay.as_secs_f64());
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records that a connection was closed because the peer stayed over its message cost budget.
+    pub(super) fn record_cost_budget_exceeded(this: &Weak<Self>) {
+        if let Some(metrics) = this.upgrade() {
+            metrics.cost_budget_exceeded.inc();
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
 }
 
 impl Drop for Metrics {
@@ -621,6 +669,8 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.out_state_blocked);
         unregister_metric!(self.registry, self.out_state_loopback);
 
+        unregister_metric!(self.registry, self.rejected_cross_network_connections);
+
         unregister_metric!(self.registry, self.in_count_protocol);
         unregister_metric!(self.registry, self.in_count_consensus);
         unregister_metric!(self.registry, self.in_count_deploy_gossip);
@@ -648,5 +698,7 @@ impl Drop for Metrics {
 
         unregister_metric!(self.registry, self.accumulated_outgoing_limiter_delay);
         unregister_metric!(self.registry, self.accumulated_incoming_limiter_delay);
+        unregister_metric!(self.registry, self.accumulated_cost_budget_delay);
+        unregister_metric!(self.registry, self.cost_budget_exceeded);
     }
 }
@@ -537,6 +537,15 @@ where
         }
     }
 
+    /// Retrieves the ID of the peer connected at the given address, if any.
+    pub(crate) fn get_connected_peer(&self, addr: SocketAddr) -> Option<NodeId> {
+        if let OutgoingState::Connected { peer_id, .. } = self.outgoing.get(&addr)?.state {
+            Some(peer_id)
+        } else {
+            None
+        }
+    }
+
     /// Iterates over all connected peer IDs.
     pub(crate) fn connected_peers(&'_ self) -> impl Iterator<Item = NodeId> + '_ {
         self.routes.keys().cloned()
@@ -682,6 +691,15 @@ where
             })
     }
 
+    /// Returns the round-trip time of the most recent successful ping to the given peer.
+    pub(crate) fn peer_rtt(&self, peer_id: NodeId) -> Option<Duration> {
+        let addr = self.routes.get(&peer_id)?;
+        match self.outgoing.get(addr)?.state {
+            OutgoingState::Connected { ref health, .. } => health.last_rtt,
+            _ => None,
+        }
+    }
+
     /// Records a pong being received.
     pub(super) fn record_pong(&mut self, peer_id: NodeId, pong: TaggedTimestamp) -> bool {
         let addr = if let Some(addr) = self.routes.get(&peer_id) {
@@ -1747,6 +1765,7 @@ mod tests {
                 peer_id,
                 TaggedTimestamp::from_parts(clock.now(), second_nonce),
             ));
+            assert_eq!(manager.peer_rtt(peer_id), Some(Duration::from_secs(1)));
 
             // This resets the "cycle", the next ping is due in 5 seconds.
         }
@@ -1791,6 +1810,7 @@ mod tests {
         );
 
         assert_eq!(dial_addr, addr);
+        assert_eq!(manager.peer_rtt(id), None);
     }
 
     #[test]
@@ -10,7 +10,7 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc, Weak,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bincode::Options;
@@ -22,7 +22,7 @@ use futures::{
 use openssl::{
     pkey::{PKey, Private},
     ssl::Ssl,
-    x509::X509,
+    x509::{X509Ref, X509},
 };
 use prometheus::IntGauge;
 use rand::Rng;
@@ -39,10 +39,12 @@ use tracing::{
     info, trace, warn, Instrument, Span,
 };
 
-use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
+use casper_types::{ProtocolVersion, PublicKey, TimeDiff, Timestamp};
 
 use super::{
+    blocklist::BlocklistJustification,
     chain_info::ChainInfo,
+    cost_budget::{BudgetOutcome, CostBudget, CostWeights},
     counting_format::{ConnectionId, Role},
     error::{ConnectionError, IoError},
     event::{IncomingConnection, OutgoingConnection},
@@ -50,8 +52,8 @@ use super::{
     limiter::LimiterHandle,
     message::NodeKeyPair,
     message_pack_format::MessagePackFormat,
-    EstimatorWeights, Event, FramedTransport, FullTransport, Identity, Message, Metrics, Payload,
-    Transport,
+    versioned_format, EstimatorWeights, Event, FramedTransport, FullTransport, Identity, Message,
+    MessageKind, Metrics, Payload, Transport,
 };
 use crate::{
     components::network::{framed_transport, BincodeFormat, Config, FromIncoming},
@@ -81,6 +83,11 @@ struct HandshakeOutcome {
     peer_consensus_public_key: Option<PublicKey>,
     /// Holds the information whether the remote node is syncing.
     is_peer_syncing: bool,
+    /// Estimated offset of the peer's clock from ours in milliseconds, if the peer sent a
+    /// timestamp.
+    clock_offset: Option<i64>,
+    /// The wire format version to use for all further messages.
+    wire_format_version: u8,
 }
 
 /// Low-level TLS connection function.
@@ -101,14 +108,18 @@ where
         .set_nodelay(true)
         .map_err(ConnectionError::TcpNoDelay)?;
 
-    let mut transport = tls::create_tls_connector(context.our_cert.as_x509(), &context.secret_key)
-        .and_then(|connector| connector.configure())
-        .and_then(|mut config| {
-            config.set_verify_hostname(false);
-            config.into_ssl("this-will-not-be-checked.example.com")
-        })
-        .and_then(|ssl| SslStream::new(ssl, stream))
-        .map_err(ConnectionError::TlsInitialization)?;
+    let mut transport = tls::create_tls_connector(
+        context.our_cert.as_x509(),
+        &context.secret_key,
+        context.tls_authority(),
+    )
+    .and_then(|connector| connector.configure())
+    .and_then(|mut config| {
+        config.set_verify_hostname(false);
+        config.into_ssl("this-will-not-be-checked.example.com")
+    })
+    .and_then(|ssl| SslStream::new(ssl, stream))
+    .map_err(ConnectionError::TlsInitialization)?;
 
     SslStream::connect(Pin::new(&mut transport))
         .await
@@ -163,6 +174,8 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            clock_offset,
+            wire_format_version,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
@@ -179,6 +192,7 @@ where
                 connection_id,
                 framed_transport,
                 Role::Dialer,
+                wire_format_version,
             );
             let (sink, _stream) = full_transport.split();
 
@@ -188,6 +202,8 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                clock_offset,
+                wire_format_version,
             }
         }
         Err(error) => OutgoingConnection::Failed {
@@ -212,6 +228,8 @@ where
     our_cert: Arc<TlsCert>,
     /// TLS certificate authority associated with this node's identity.
     network_ca: Option<Arc<X509>>,
+    /// Whether peers must present a certificate signed by `network_ca` during the TLS handshake.
+    permissioned: bool,
     /// Secret key associated with `our_cert`.
     secret_key: Arc<PKey<Private>>,
     /// Weak reference to the networking metrics shared by all sender/receiver tasks.
@@ -226,6 +244,10 @@ where
     handshake_timeout: TimeDiff,
     /// Weights to estimate payloads with.
     payload_weights: EstimatorWeights,
+    /// Maximum total cost of the messages a single peer may send per second. Unlimited if `0`.
+    max_incoming_cost_per_peer: u32,
+    /// Weights to compute the cost of incoming messages with.
+    cost_weights: CostWeights,
     /// The protocol version at which (or under) tarpitting is enabled.
     tarpit_version_threshold: Option<ProtocolVersion>,
     /// If tarpitting is enabled, duration for which connections should be kept open.
@@ -266,12 +288,15 @@ impl<REv> NetworkContext<REv> {
             event_queue: None,
             our_cert: tls_certificate,
             network_ca,
+            permissioned: cfg.permissioned,
             secret_key,
             net_metrics: Arc::downgrade(net_metrics),
             chain_info,
             node_key_pair,
             handshake_timeout: cfg.handshake_timeout,
             payload_weights: cfg.estimator_weights.clone(),
+            max_incoming_cost_per_peer: cfg.max_incoming_cost_per_peer,
+            cost_weights: cfg.cost_weights.clone(),
             tarpit_version_threshold: cfg.tarpit_version_threshold,
             tarpit_duration: cfg.tarpit_duration,
             tarpit_chance: cfg.tarpit_chance,
@@ -299,6 +324,11 @@ impl<REv> NetworkContext<REv> {
         self.public_addr
     }
 
+    /// Our signing keys, if any.
+    pub(super) fn node_key_pair(&self) -> Option<&NodeKeyPair> {
+        self.node_key_pair.as_ref()
+    }
+
     /// Chain info extract from chainspec.
     pub(super) fn chain_info(&self) -> &ChainInfo {
         &self.chain_info
@@ -315,6 +345,15 @@ impl<REv> NetworkContext<REv> {
         self.network_ca.as_ref()
     }
 
+    /// The CA peers must be signed by during the TLS handshake, if the network is permissioned.
+    fn tls_authority(&self) -> Option<&X509Ref> {
+        if self.permissioned {
+            self.network_ca.as_deref().map(|ca| &**ca)
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn is_syncing(&self) -> &AtomicBool {
         &self.is_syncing
     }
@@ -362,6 +401,8 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: _,
+            clock_offset,
+            wire_format_version,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
@@ -373,6 +414,7 @@ where
                 connection_id,
                 framed_transport,
                 Role::Listener,
+                wire_format_version,
             );
 
             let (_sink, stream) = full_transport.split();
@@ -382,6 +424,7 @@ where
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                clock_offset,
                 stream,
             }
         }
@@ -403,6 +446,7 @@ pub(super) async fn server_setup_tls<REv>(
     let mut tls_stream = tls::create_tls_acceptor(
         context.our_cert.as_x509().as_ref(),
         context.secret_key.as_ref(),
+        context.tls_authority(),
     )
     .and_then(|ssl_acceptor| Ssl::new(ssl_acceptor.context()))
     .and_then(|ssl| SslStream::new(ssl, stream))
@@ -469,6 +513,7 @@ where
     let mut encoder = MessagePackFormat;
 
     // Manually encode a handshake.
+    let sent_at = Timestamp::now();
     let handshake_message = context.chain_info.create_handshake::<P>(
         context.public_addr.expect("component not initialized"),
         context.node_key_pair.as_ref(),
@@ -495,6 +540,7 @@ where
     let remote_message_raw = io_opt_timeout(context.handshake_timeout.into(), stream.next())
         .await
         .map_err(ConnectionError::HandshakeRecv)?;
+    let received_at = Timestamp::now();
 
     // Ensure the handshake was sent correctly.
     let sink = handshake_send
@@ -513,9 +559,11 @@ where
         consensus_certificate,
         is_syncing,
         chainspec_hash,
+        timestamp,
+        wire_format_version,
     } = remote_message
     {
-        debug!(%protocol_version, "handshake received");
+        debug!(%protocol_version, wire_format_version, "handshake received");
 
         // The handshake was valid, we can check the network name.
         if network_name != context.chain_info.network_name {
@@ -566,11 +614,20 @@ where
             .reunite(stream)
             .map_err(|_| ConnectionError::FailedToReuniteHandshakeSinkAndStream)?;
 
+        // Both handshakes are sent concurrently, so the peer created theirs at roughly the midpoint
+        // between us creating ours and receiving theirs.
+        let clock_offset = timestamp.map(|peer_time| {
+            let midpoint = sent_at.millis() / 2 + received_at.millis() / 2;
+            (peer_time.millis() as i64).saturating_sub(midpoint as i64)
+        });
+
         Ok(HandshakeOutcome {
             framed_transport,
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            clock_offset,
+            wire_format_version: versioned_format::negotiate_version(wire_format_version),
         })
     } else {
         // Received a non-handshake, this is an error.
@@ -672,6 +729,7 @@ where
 {
     let demands_in_flight = Arc::new(Semaphore::new(context.max_in_flight_demands));
     let event_queue = context.event_queue.expect("component not initialized");
+    let mut cost_budget = CostBudget::new(context.max_incoming_cost_per_peer, Instant::now());
 
     let read_messages = async move {
         while let Some(msg_result) = stream.next().await {
@@ -681,6 +739,41 @@ where
 
                     let effect_builder = EffectBuilder::new(event_queue);
 
+                    // Charge the message to the peer's budget. While the peer is over budget, we
+                    // stop reading from its connection, which also applies backpressure.
+                    // Validators' consensus traffic is never charged, so that a misconfigured
+                    // budget cannot stall consensus.
+                    let cost = if matches!(msg.classify(), MessageKind::Consensus)
+                        && limiter.is_active_or_upcoming_validator()
+                    {
+                        0
+                    } else {
+                        msg.incoming_cost(&context.cost_weights)
+                    };
+                    match cost_budget.charge(cost, Instant::now()) {
+                        BudgetOutcome::Allowed => {}
+                        BudgetOutcome::Throttle(delay) => {
+                            trace!(?delay, "peer over message cost budget, throttling");
+                            Metrics::record_cost_budget_delay(&context.net_metrics, delay);
+                            tokio::time::sleep(delay).await;
+                        }
+                        BudgetOutcome::Exceeded => {
+                            Metrics::record_cost_budget_exceeded(&context.net_metrics);
+                            effect_builder
+                                .announce_block_peer_with_justification(
+                                    peer_id,
+                                    BlocklistJustification::ExceededCostBudget {
+                                        max_cost_per_second: context.max_incoming_cost_per_peer,
+                                    },
+                                )
+                                .await;
+                            return Err(io::Error::new(
+                                io::ErrorKind::Other,
+                                "peer stayed over its message cost budget",
+                            ));
+                        }
+                    }
+
                     match msg.try_into_demand(effect_builder, peer_id) {
                         Ok((event, wait_for_response)) => {
                             // Note: For now, demands bypass the limiter, as we expect the
@@ -750,6 +843,8 @@ where
 
                             let queue_kind = if msg.is_low_priority() {
                                 QueueKind::NetworkLowPriority
+                            } else if msg.is_droppable() {
+                                QueueKind::NetworkGossip
                             } else {
                                 QueueKind::NetworkIncoming
                             };
@@ -843,3 +938,138 @@ pub(super) async fn message_sender<P>(
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use prometheus::Registry;
+    use tokio::net::TcpListener;
+
+    use casper_hashing::Digest;
+
+    use super::*;
+    use crate::{components::network::versioned_format::WIRE_FORMAT_VERSION, protocol};
+
+    /// A handshake as sent by nodes predating wire format versioning and handshake timestamps.
+    #[derive(Serialize)]
+    enum LegacyMessage {
+        Handshake {
+            network_name: String,
+            public_addr: SocketAddr,
+            protocol_version: ProtocolVersion,
+            consensus_certificate: Option<()>,
+            is_syncing: bool,
+            chainspec_hash: Option<Digest>,
+        },
+    }
+
+    fn create_context(metrics: &Arc<Metrics>) -> NetworkContext<()> {
+        let mut context = NetworkContext::new(
+            Config::default(),
+            Identity::with_generated_certs().expect("could not generate identity"),
+            None,
+            ChainInfo::create_for_testing(),
+            metrics,
+        );
+        context.public_addr = Some((Ipv4Addr::LOCALHOST, 34553).into());
+        context
+    }
+
+    /// Connects `dialer` to `listener` over TLS, returning the framed transports and connection
+    /// IDs of both sides.
+    async fn connect(
+        dialer: &NetworkContext<()>,
+        listener: &NetworkContext<()>,
+    ) -> (
+        (FramedTransport, ConnectionId),
+        (FramedTransport, ConnectionId),
+    ) {
+        let tcp_listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("could not bind listener");
+        let addr = tcp_listener.local_addr().expect("listener has no address");
+
+        let accept = async {
+            let (stream, _) = tcp_listener.accept().await.expect("could not accept");
+            server_setup_tls(listener, stream)
+                .await
+                .expect("server TLS setup failed")
+        };
+        let dial = async {
+            tls_connect(dialer, addr)
+                .await
+                .expect("client TLS setup failed")
+        };
+        let ((listener_id, outgoing), (dialer_id, incoming)) = tokio::join!(dial, accept);
+
+        let max_size = dialer.chain_info.maximum_net_message_size;
+        let outgoing_id = ConnectionId::from_connection(outgoing.ssl(), dialer.our_id, listener_id);
+        let incoming_id = ConnectionId::from_connection(incoming.ssl(), listener.our_id, dialer_id);
+        (
+            (framed_transport(outgoing, max_size), outgoing_id),
+            (framed_transport(incoming, max_size), incoming_id),
+        )
+    }
+
+    #[tokio::test]
+    async fn should_negotiate_highest_common_wire_format_version() {
+        let metrics = Arc::new(Metrics::new(&Registry::new()).expect("could not create metrics"));
+        let dialer = create_context(&metrics);
+        let listener = create_context(&metrics);
+        let ((outgoing, outgoing_id), (incoming, incoming_id)) = connect(&dialer, &listener).await;
+
+        let (dialer_outcome, listener_outcome) = tokio::join!(
+            negotiate_handshake::<protocol::Message, _>(&dialer, outgoing, outgoing_id),
+            negotiate_handshake::<protocol::Message, _>(&listener, incoming, incoming_id)
+        );
+
+        let dialer_outcome = dialer_outcome.expect("dialer handshake failed");
+        let listener_outcome = listener_outcome.expect("listener handshake failed");
+        assert_eq!(dialer_outcome.wire_format_version, WIRE_FORMAT_VERSION);
+        assert_eq!(listener_outcome.wire_format_version, WIRE_FORMAT_VERSION);
+        assert!(dialer_outcome.clock_offset.is_some());
+        assert!(listener_outcome.clock_offset.is_some());
+    }
+
+    #[tokio::test]
+    async fn should_fall_back_to_unversioned_format_for_legacy_peer() {
+        let metrics = Arc::new(Metrics::new(&Registry::new()).expect("could not create metrics"));
+        let dialer = create_context(&metrics);
+        let listener = create_context(&metrics);
+        let ((mut outgoing, _), (incoming, incoming_id)) = connect(&dialer, &listener).await;
+
+        let legacy_handshake = LegacyMessage::Handshake {
+            network_name: dialer.chain_info.network_name.clone(),
+            public_addr: dialer.public_addr.expect("public address not set"),
+            protocol_version: dialer.chain_info.protocol_version,
+            consensus_certificate: None,
+            is_syncing: false,
+            chainspec_hash: Some(dialer.chain_info.chainspec_hash),
+        };
+        let legacy_peer = async move {
+            let serialized = Pin::new(&mut MessagePackFormat)
+                .serialize(&legacy_handshake)
+                .expect("could not encode legacy handshake");
+            outgoing
+                .send(serialized)
+                .await
+                .expect("could not send legacy handshake");
+            // Keep the connection open until the listener's handshake arrived.
+            outgoing
+                .next()
+                .await
+                .expect("connection closed")
+                .expect("could not receive handshake")
+        };
+
+        let (outcome, _) = tokio::join!(
+            negotiate_handshake::<protocol::Message, _>(&listener, incoming, incoming_id),
+            legacy_peer
+        );
+
+        let outcome = outcome.expect("listener handshake failed");
+        assert_eq!(outcome.wire_format_version, 0);
+        assert!(outcome.clock_offset.is_none());
+    }
+}
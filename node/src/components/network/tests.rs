@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 use tracing::{debug, info};
 
-use casper_types::SecretKey;
+use casper_types::{PublicKey, SecretKey};
 
 use super::{
     chain_info::ChainInfo, Config, Event as NetworkEvent, FromIncoming, GossipedAddress, Identity,
@@ -163,6 +163,10 @@ impl Payload for Message {
         0
     }
 
+    fn incoming_cost(&self, _weights: &super::CostWeights) -> u32 {
+        0
+    }
+
     fn is_unsafe_for_syncing_peers(&self) -> bool {
         false
     }
@@ -191,14 +195,15 @@ impl Reactor for TestReactor {
         _event_queue: EventQueueHandle<Self::Event>,
         rng: &mut NodeRng,
     ) -> anyhow::Result<(Self, Effects<Self::Event>)> {
-        let secret_key = SecretKey::random(rng);
+        let secret_key = Arc::new(SecretKey::random(rng));
+        let public_key = PublicKey::from(&*secret_key);
         let mut net = Network::new(
             cfg,
             our_identity,
-            None,
+            Some((secret_key.clone(), public_key)),
             registry,
             ChainInfo::create_for_testing(),
-            ValidatorMatrix::new_with_validator(Arc::new(secret_key)),
+            ValidatorMatrix::new_with_validator(secret_key),
         )?;
         let gossiper_config = gossiper::Config::new_with_small_timeouts();
         let address_gossiper = Gossiper::<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, _>::new(
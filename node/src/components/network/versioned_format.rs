@@ -0,0 +1,194 @@
+//! Versioned wire format encoder.
+//!
+//! Messages sent after the handshake are bincode encoded and length-delimited by the framed
+//! transport. From wire format version 1 onwards, each message is additionally prefixed with the
+//! version it is encoded in, so that a message encoded in a different version than the one
+//! negotiated is rejected outright instead of causing arbitrary decoding failures.
+//!
+//! Both peers announce the highest wire format version they support in their handshake, which is
+//! encoded as message pack and thus tolerates fields being added. The lower of the two versions is
+//! used on the connection. Version 0 is the unprefixed encoding used by peers predating wire
+//! format versioning.
+//!
+//! Note that bincode is not self-describing: within a single version, the fields of existing
+//! messages cannot change without breaking peers. Messages evolve by bumping
+//! [`WIRE_FORMAT_VERSION`] and introducing the changed message as a new variant, which is only
+//! ever sent to peers that negotiated a version knowing it, as is done for `TimedPong`.
+//!
+//! The versions differ as follows:
+//!
+//! * 1: messages are prefixed with the version.
+//! * 2: pings are answered with a `TimedPong`, carrying the sender's wall-clock time.
+
+use std::{io, pin::Pin, sync::Arc};
+
+use bincode::Options;
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_serde::{Deserializer, Serializer};
+
+use super::{BincodeFormat, Message};
+
+/// The highest wire format version supported by this node.
+pub(super) const WIRE_FORMAT_VERSION: u8 = 2;
+
+/// The first wire format version in which pings are answered with a `TimedPong`.
+pub(super) const TIMED_PONG_VERSION: u8 = 2;
+
+/// Returns the wire format version to use with a peer supporting up to `peer_version`.
+pub(super) fn negotiate_version(peer_version: u8) -> u8 {
+    peer_version.min(WIRE_FORMAT_VERSION)
+}
+
+/// Versioned encoder/decoder for messages.
+#[derive(Debug)]
+pub struct VersionedFormat {
+    /// The wire format version negotiated with the peer.
+    version: u8,
+    /// The encoding of the message itself.
+    bincode: BincodeFormat,
+}
+
+impl VersionedFormat {
+    /// Creates a new encoder/decoder for the given wire format version.
+    pub(super) fn new(version: u8) -> Self {
+        VersionedFormat {
+            version,
+            bincode: BincodeFormat::default(),
+        }
+    }
+}
+
+impl<P> Serializer<Arc<Message<P>>> for VersionedFormat
+where
+    Message<P>: Serialize,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        if self.version == 0 {
+            return self.bincode.serialize_arbitrary(&**item).map(Into::into);
+        }
+        let mut serialized = vec![self.version];
+        self.bincode
+            .0
+            .serialize_into(&mut serialized, &**item)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(serialized.into())
+    }
+}
+
+impl<P> Deserializer<Message<P>> for VersionedFormat
+where
+    for<'de> Message<P>: Deserialize<'de>,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        let encoded = if self.version == 0 {
+            &src[..]
+        } else {
+            match src.split_first() {
+                Some((version, encoded)) if *version == self.version => encoded,
+                Some((version, _)) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "received message in wire format version {}, expected {}",
+                            version, self.version
+                        ),
+                    ))
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "received empty message",
+                    ))
+                }
+            }
+        };
+        self.bincode
+            .0
+            .deserialize(encoded)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{testing::TestRng, Timestamp};
+    use rand::Rng;
+
+    use crate::protocol;
+
+    use super::*;
+
+    fn roundtrip(
+        rng: &mut TestRng,
+        sender_version: u8,
+        receiver_version: u8,
+    ) -> Result<Message<protocol::Message>, io::Error> {
+        let message = Arc::new(Message::<protocol::Message>::Ping { nonce: rng.gen() });
+        let serialized = Pin::new(&mut VersionedFormat::new(sender_version)).serialize(&message)?;
+        Pin::new(&mut VersionedFormat::new(receiver_version))
+            .deserialize(&BytesMut::from(&serialized[..]))
+    }
+
+    #[test]
+    fn should_roundtrip_in_negotiated_version() {
+        let mut rng = TestRng::new();
+        assert!(roundtrip(&mut rng, 0, 0).is_ok());
+        assert!(roundtrip(&mut rng, WIRE_FORMAT_VERSION, WIRE_FORMAT_VERSION).is_ok());
+    }
+
+    #[test]
+    fn should_roundtrip_timed_pong() {
+        let mut rng = TestRng::new();
+        let message = Arc::new(Message::<protocol::Message>::TimedPong {
+            nonce: rng.gen(),
+            timestamp: Timestamp::now(),
+        });
+        let mut format = VersionedFormat::new(TIMED_PONG_VERSION);
+        let serialized = Pin::new(&mut format).serialize(&message).unwrap();
+        let deserialized: Message<protocol::Message> = Pin::new(&mut format)
+            .deserialize(&BytesMut::from(&serialized[..]))
+            .unwrap();
+        match (&*message, deserialized) {
+            (
+                Message::TimedPong { nonce, timestamp },
+                Message::TimedPong {
+                    nonce: received_nonce,
+                    timestamp: received_timestamp,
+                },
+            ) => {
+                assert_eq!(*nonce, received_nonce);
+                assert_eq!(*timestamp, received_timestamp);
+            }
+            (_, other) => panic!("unexpected message {}", other),
+        }
+    }
+
+    #[test]
+    fn version_0_should_match_bincode_format() {
+        let mut rng = TestRng::new();
+        let message = Arc::new(Message::<protocol::Message>::Pong { nonce: rng.gen() });
+        let versioned = Pin::new(&mut VersionedFormat::new(0))
+            .serialize(&message)
+            .unwrap();
+        let unversioned = Pin::new(&mut BincodeFormat::default())
+            .serialize(&message)
+            .unwrap();
+        assert_eq!(versioned, unversioned);
+    }
+
+    #[test]
+    fn should_reject_other_version() {
+        let mut rng = TestRng::new();
+        assert!(roundtrip(&mut rng, 0, WIRE_FORMAT_VERSION).is_err());
+        assert!(roundtrip(&mut rng, WIRE_FORMAT_VERSION, WIRE_FORMAT_VERSION + 1).is_err());
+        assert_eq!(negotiate_version(0), 0);
+        assert_eq!(negotiate_version(u8::MAX), WIRE_FORMAT_VERSION);
+    }
+}
@@ -0,0 +1,598 @@
+//! Webhook notifier.
+//!
+//! Notifies operators about the activity of watched accounts by POSTing a JSON notification to the
+//! configured URL for each of the following, once the block containing it is complete:
+//!
+//! * `TransferSent`, for each transfer record with the account as sender
+//! * `TransferReceived`, for each transfer record with the account as recipient or the account's
+//!   main purse as target
+//! * `DeployProcessed`, for each executed deploy sent by the account
+//!
+//! Each request body carries the time it was signed at in its `timestamp` field, and is signed
+//! with the configured secret key.  The hex-encoded signature and the signer's public key are sent
+//! in the `X-Casper-Signature` and `X-Casper-Public-Key` headers respectively.  Failed deliveries
+//! are retried with exponential backoff, up to `max_attempts` times in total.  Each attempt is
+//! signed anew, so receivers can reject stale or replayed requests by their timestamp.
+
+mod config;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use http::header::CONTENT_TYPE;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use casper_types::{
+    account::AccountHash, crypto, AsymmetricType, PublicKey, SecretKey, Timestamp, Transfer, URef,
+    URefAddr, U512,
+};
+
+use crate::{
+    components::Component,
+    effect::{
+        requests::{ContractRuntimeRequest, StorageRequest},
+        EffectBuilder, EffectExt, Effects,
+    },
+    types::{Block, BlockHash, DeployHash},
+    utils::{LoadError, Loadable, WithDir},
+    NodeRng,
+};
+pub use config::{Config, WebhookConfig};
+
+const COMPONENT_NAME: &str = "notifier";
+
+/// The header carrying the hex-encoded signature of the request body.
+const SIGNATURE_HEADER: &str = "X-Casper-Signature";
+/// The header carrying the hex-encoded public key which signed the request body.
+const PUBLIC_KEY_HEADER: &str = "X-Casper-Public-Key";
+
+type LoadKeyError = LoadError<<Arc<SecretKey> as Loadable>::Error>;
+
+/// A deploy executed in a completed block.
+#[derive(DataSize, Debug)]
+pub(crate) struct ProcessedDeploy {
+    pub(crate) deploy_hash: DeployHash,
+    /// The account which sent the deploy.
+    pub(crate) account_hash: AccountHash,
+    /// Whether execution succeeded.
+    pub(crate) success: bool,
+}
+
+/// A notification sent to a webhook.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+struct Notification {
+    account_hash: AccountHash,
+    block_hash: BlockHash,
+    block_height: u64,
+    #[serde(flatten)]
+    kind: NotificationKind,
+}
+
+/// The body of a delivery attempt.
+#[derive(Serialize)]
+struct Payload<'a> {
+    /// The time at which the attempt was signed.
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    notification: &'a Notification,
+}
+
+/// What happened to the watched account.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(tag = "kind")]
+enum NotificationKind {
+    TransferSent {
+        deploy_hash: casper_types::DeployHash,
+        to: Option<AccountHash>,
+        target: URef,
+        amount: U512,
+    },
+    TransferReceived {
+        deploy_hash: casper_types::DeployHash,
+        from: AccountHash,
+        amount: U512,
+    },
+    DeployProcessed {
+        deploy_hash: DeployHash,
+        success: bool,
+    },
+}
+
+/// A notification to be delivered to a URL.
+#[derive(DataSize, Debug)]
+pub(crate) struct Delivery {
+    url: String,
+    #[data_size(skip)]
+    notification: Notification,
+    /// The number of this delivery attempt, starting at 1.
+    attempt: u32,
+}
+
+/// The notifier's event.
+#[derive(Debug)]
+pub(crate) enum Event {
+    /// A block has been completed.
+    BlockCompleted {
+        block: Arc<Block>,
+        processed_deploys: Vec<ProcessedDeploy>,
+    },
+    /// The transfer records of a completed block were read from storage, along with the main
+    /// purses of watched accounts not known before.
+    GotTransfers {
+        block_hash: BlockHash,
+        block_height: u64,
+        processed_deploys: Vec<ProcessedDeploy>,
+        transfers: Vec<Transfer>,
+        main_purses: BTreeMap<AccountHash, URefAddr>,
+    },
+    /// A notification is due to be delivered.
+    Deliver(Box<Delivery>),
+    /// A delivery attempt has finished.
+    DeliveryResult {
+        delivery: Box<Delivery>,
+        result: Result<(), String>,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::BlockCompleted { block, .. } => write!(formatter, "completed block: {}", block),
+            Event::GotTransfers {
+                block_hash,
+                transfers,
+                ..
+            } => write!(
+                formatter,
+                "got {} transfers of {}",
+                transfers.len(),
+                block_hash
+            ),
+            Event::Deliver(delivery) => write!(
+                formatter,
+                "deliver notification to {}, attempt {}",
+                delivery.url, delivery.attempt
+            ),
+            Event::DeliveryResult { delivery, result } => write!(
+                formatter,
+                "delivery result from {}, attempt {}: {:?}",
+                delivery.url, delivery.attempt, result
+            ),
+        }
+    }
+}
+
+/// The webhook notifier component.
+#[derive(DataSize, Debug)]
+pub(crate) struct Notifier {
+    config: Config,
+    /// The keys signing notifications, present only if any webhooks are configured.
+    #[data_size(skip)]
+    keys: Option<(Arc<SecretKey>, PublicKey)>,
+    /// The main purses of watched accounts, read from global state once the account exists.
+    #[data_size(skip)]
+    main_purses: HashMap<AccountHash, URefAddr>,
+    #[data_size(skip)]
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub(crate) fn new(config: WithDir<Config>) -> Result<Self, LoadKeyError> {
+        let (root, config) = config.into_parts();
+        let keys = if config.webhooks.is_empty() {
+            None
+        } else {
+            let secret_key: Arc<SecretKey> = config.secret_key_path.clone().load(root)?;
+            let public_key = PublicKey::from(secret_key.as_ref());
+            Some((secret_key, public_key))
+        };
+        Ok(Notifier {
+            config,
+            keys,
+            main_purses: HashMap::new(),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Returns whether any webhooks are configured, i.e. whether completed blocks are of interest.
+    pub(crate) fn has_webhooks(&self) -> bool {
+        !self.config.webhooks.is_empty()
+    }
+
+    /// Returns the body of a delivery attempt of `notification` signed at `timestamp`, and its
+    /// hex-encoded signature.
+    fn sign(&self, notification: &Notification, timestamp: Timestamp) -> Option<(Vec<u8>, String)> {
+        let (secret_key, public_key) = self.keys.as_ref()?;
+        let payload = Payload {
+            timestamp,
+            notification,
+        };
+        let body = serde_json::to_vec(&payload).expect("should serialize notification");
+        let signature = crypto::sign(&body, secret_key, public_key).to_hex();
+        Some((body, signature))
+    }
+
+    fn deliver(&self, delivery: Box<Delivery>) -> Effects<Event> {
+        let public_key = match &self.keys {
+            Some((_, public_key)) => public_key.to_hex(),
+            None => return Effects::new(),
+        };
+        let (body, signature) = match self.sign(&delivery.notification, Timestamp::now()) {
+            Some(signed) => signed,
+            None => return Effects::new(),
+        };
+        let request = self
+            .client
+            .post(&delivery.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(PUBLIC_KEY_HEADER, public_key)
+            .timeout(self.config.request_timeout.into())
+            .body(body);
+        async move {
+            request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        }
+        .event(move |result| Event::DeliveryResult { delivery, result })
+    }
+
+    /// Returns the delay before the retry following the given failed attempt.
+    fn backoff(&self, failed_attempt: u32) -> Duration {
+        let factor = 1u64 << failed_attempt.saturating_sub(1).min(32);
+        self.config
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.config.max_backoff)
+            .into()
+    }
+}
+
+/// Returns the notifications due to each webhook for a completed block.
+fn notifications(
+    webhooks: &[WebhookConfig],
+    block_hash: BlockHash,
+    block_height: u64,
+    transfers: &[Transfer],
+    processed_deploys: &[ProcessedDeploy],
+    main_purses: &HashMap<AccountHash, URefAddr>,
+) -> Vec<(String, Notification)> {
+    let mut notifications = vec![];
+    for webhook in webhooks {
+        let account_hash = webhook.account_hash;
+        let main_purse = main_purses.get(&account_hash);
+        let mut notify = |kind| {
+            notifications.push((
+                webhook.url.clone(),
+                Notification {
+                    account_hash,
+                    block_hash,
+                    block_height,
+                    kind,
+                },
+            ))
+        };
+        for transfer in transfers {
+            if transfer.from == account_hash {
+                notify(NotificationKind::TransferSent {
+                    deploy_hash: transfer.deploy_hash,
+                    to: transfer.to,
+                    target: transfer.target,
+                    amount: transfer.amount,
+                });
+            }
+            if transfer.to == Some(account_hash) || main_purse == Some(&transfer.target.addr()) {
+                notify(NotificationKind::TransferReceived {
+                    deploy_hash: transfer.deploy_hash,
+                    from: transfer.from,
+                    amount: transfer.amount,
+                });
+            }
+        }
+        for processed_deploy in processed_deploys {
+            if processed_deploy.account_hash == account_hash {
+                notify(NotificationKind::DeployProcessed {
+                    deploy_hash: processed_deploy.deploy_hash,
+                    success: processed_deploy.success,
+                });
+            }
+        }
+    }
+    notifications
+}
+
+impl<REv> Component<REv> for Notifier
+where
+    REv: Send + From<StorageRequest> + From<ContractRuntimeRequest>,
+{
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::BlockCompleted {
+                block,
+                processed_deploys,
+            } => {
+                if self.config.webhooks.is_empty() {
+                    return Effects::new();
+                }
+                let block_hash = *block.hash();
+                let block_height = block.height();
+                let state_root_hash = *block.state_root_hash();
+                let unknown_accounts: BTreeSet<AccountHash> = self
+                    .config
+                    .webhooks
+                    .iter()
+                    .map(|webhook| webhook.account_hash)
+                    .filter(|account_hash| !self.main_purses.contains_key(account_hash))
+                    .collect();
+                async move {
+                    let transfers = effect_builder
+                        .get_block_transfers_from_storage(block_hash)
+                        .await
+                        .unwrap_or_default();
+                    if unknown_accounts.is_empty() {
+                        return (transfers, BTreeMap::new());
+                    }
+                    let main_purses = match effect_builder
+                        .get_accounts_from_global_state(state_root_hash, unknown_accounts)
+                        .await
+                    {
+                        Ok(accounts) => accounts
+                            .into_iter()
+                            .map(|(account_hash, account)| {
+                                (account_hash, account.main_purse().addr())
+                            })
+                            .collect(),
+                        Err(error) => {
+                            warn!(%block_hash, %error, "failed to read watched accounts");
+                            BTreeMap::new()
+                        }
+                    };
+                    (transfers, main_purses)
+                }
+                .event(move |(transfers, main_purses)| Event::GotTransfers {
+                    block_hash,
+                    block_height,
+                    processed_deploys,
+                    transfers,
+                    main_purses,
+                })
+            }
+            Event::GotTransfers {
+                block_hash,
+                block_height,
+                processed_deploys,
+                transfers,
+                main_purses,
+            } => {
+                self.main_purses.extend(main_purses);
+                notifications(
+                    &self.config.webhooks,
+                    block_hash,
+                    block_height,
+                    &transfers,
+                    &processed_deploys,
+                    &self.main_purses,
+                )
+                .into_iter()
+                .flat_map(|(url, notification)| {
+                    self.deliver(Box::new(Delivery {
+                        url,
+                        notification,
+                        attempt: 1,
+                    }))
+                })
+                .collect()
+            }
+            Event::Deliver(delivery) => self.deliver(delivery),
+            Event::DeliveryResult { delivery, result } => match result {
+                Ok(()) => {
+                    debug!(
+                        url = %delivery.url,
+                        attempt = delivery.attempt,
+                        "notification delivered"
+                    );
+                    Effects::new()
+                }
+                Err(error) if delivery.attempt >= self.config.max_attempts => {
+                    warn!(
+                        url = %delivery.url,
+                        attempt = delivery.attempt,
+                        %error,
+                        "failed to deliver notification, giving up"
+                    );
+                    Effects::new()
+                }
+                Err(error) => {
+                    let backoff = self.backoff(delivery.attempt);
+                    debug!(
+                        url = %delivery.url,
+                        attempt = delivery.attempt,
+                        %error,
+                        ?backoff,
+                        "failed to deliver notification, retrying"
+                    );
+                    let delivery = Box::new(Delivery {
+                        attempt: delivery.attempt + 1,
+                        ..*delivery
+                    });
+                    effect_builder
+                        .set_timeout(backoff)
+                        .event(move |_| Event::Deliver(delivery))
+                }
+            },
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use rand::Rng;
+
+    use casper_types::{testing::TestRng, AccessRights, Signature, TimeDiff};
+
+    use super::*;
+
+    #[test]
+    fn should_notify_watched_accounts_only() {
+        let mut rng = TestRng::new();
+        let watched: AccountHash = rng.gen();
+        let other: AccountHash = rng.gen();
+        let webhooks = vec![WebhookConfig {
+            account_hash: watched,
+            url: "http://localhost/hook".to_string(),
+        }];
+        let main_purse = URef::new([3; 32], AccessRights::READ_ADD_WRITE);
+        let main_purses = iter::once((watched, main_purse.addr())).collect();
+        let mut transfer = |from, to, target| {
+            Transfer::new(
+                rng.gen(),
+                from,
+                to,
+                URef::new([1; 32], AccessRights::READ_ADD_WRITE),
+                target,
+                U512::from(100),
+                U512::zero(),
+                None,
+            )
+        };
+        let other_purse = URef::new([2; 32], AccessRights::READ_ADD_WRITE);
+        let transfers = vec![
+            transfer(watched, Some(other), other_purse),
+            transfer(other, Some(watched), other_purse),
+            transfer(
+                other,
+                None,
+                main_purse.with_access_rights(AccessRights::ADD),
+            ),
+            transfer(other, Some(other), other_purse),
+        ];
+        let processed_deploys = vec![
+            ProcessedDeploy {
+                deploy_hash: DeployHash::random(&mut rng),
+                account_hash: watched,
+                success: false,
+            },
+            ProcessedDeploy {
+                deploy_hash: DeployHash::random(&mut rng),
+                account_hash: other,
+                success: true,
+            },
+        ];
+
+        let notifications = notifications(
+            &webhooks,
+            BlockHash::random(&mut rng),
+            7,
+            &transfers,
+            &processed_deploys,
+            &main_purses,
+        );
+
+        let kinds: Vec<_> = notifications
+            .into_iter()
+            .map(|(url, notification)| {
+                assert_eq!(url, "http://localhost/hook");
+                assert_eq!(notification.account_hash, watched);
+                assert_eq!(notification.block_height, 7);
+                notification.kind
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NotificationKind::TransferSent {
+                    deploy_hash: transfers[0].deploy_hash,
+                    to: Some(other),
+                    target: other_purse,
+                    amount: U512::from(100),
+                },
+                NotificationKind::TransferReceived {
+                    deploy_hash: transfers[1].deploy_hash,
+                    from: other,
+                    amount: U512::from(100),
+                },
+                NotificationKind::TransferReceived {
+                    deploy_hash: transfers[2].deploy_hash,
+                    from: other,
+                    amount: U512::from(100),
+                },
+                NotificationKind::DeployProcessed {
+                    deploy_hash: processed_deploys[0].deploy_hash,
+                    success: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_sign_notification_with_timestamp() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let mut notifier = Notifier::new(WithDir::new(".", Config::default())).unwrap();
+        notifier.keys = Some((Arc::new(secret_key), public_key.clone()));
+        let notification = Notification {
+            account_hash: rng.gen(),
+            block_hash: BlockHash::random(&mut rng),
+            block_height: 7,
+            kind: NotificationKind::DeployProcessed {
+                deploy_hash: DeployHash::random(&mut rng),
+                success: true,
+            },
+        };
+        let timestamp = Timestamp::from(1_000);
+
+        let (body, signature) = notifier.sign(&notification, timestamp).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["timestamp"], "1970-01-01T00:00:01.000Z");
+        assert_eq!(json["block_height"], 7);
+        assert_eq!(json["kind"], "DeployProcessed");
+        let signature = Signature::from_hex(signature).unwrap();
+        crypto::verify(&body, &signature, &public_key).unwrap();
+
+        // A later attempt carries a later timestamp, thus differs from the first one.
+        let (later_body, _) = notifier
+            .sign(&notification, timestamp + TimeDiff::from_seconds(1))
+            .unwrap();
+        assert_ne!(body, later_body);
+    }
+
+    #[test]
+    fn backoff_should_double_up_to_maximum() {
+        let config = Config {
+            initial_backoff: TimeDiff::from_seconds(1),
+            max_backoff: TimeDiff::from_seconds(5),
+            ..Config::default()
+        };
+        let notifier = Notifier::new(WithDir::new(".", config)).unwrap();
+
+        assert_eq!(notifier.backoff(1), Duration::from_secs(1));
+        assert_eq!(notifier.backoff(2), Duration::from_secs(2));
+        assert_eq!(notifier.backoff(3), Duration::from_secs(4));
+        assert_eq!(notifier.backoff(4), Duration::from_secs(5));
+        assert_eq!(notifier.backoff(100), Duration::from_secs(5));
+    }
+}
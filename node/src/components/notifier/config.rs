@@ -0,0 +1,57 @@
+use std::str::FromStr;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{account::AccountHash, TimeDiff};
+
+use crate::utils::External;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF: &str = "1sec";
+const DEFAULT_MAX_BACKOFF: &str = "5min";
+const DEFAULT_REQUEST_TIMEOUT: &str = "10sec";
+
+/// An account to watch and the URL to notify about it.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The watched account, as a formatted account hash.
+    pub account_hash: AccountHash,
+    /// The URL to which notifications are POSTed.
+    pub url: String,
+}
+
+/// Configuration options for the notifier.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Path to the secret key used to sign notifications.  Only required if `webhooks` is not
+    /// empty.
+    pub secret_key_path: External,
+    /// Maximum number of attempts to deliver a single notification.
+    pub max_attempts: u32,
+    /// Delay before the first retry of a failed delivery; doubled for each subsequent retry.
+    pub initial_backoff: TimeDiff,
+    /// Upper bound of the delay between retries.
+    pub max_backoff: TimeDiff,
+    /// Timeout of a single delivery attempt.
+    pub request_timeout: TimeDiff,
+    /// The watched accounts.
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            secret_key_path: External::Missing,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: TimeDiff::from_str(DEFAULT_INITIAL_BACKOFF).unwrap(),
+            max_backoff: TimeDiff::from_str(DEFAULT_MAX_BACKOFF).unwrap(),
+            request_timeout: TimeDiff::from_str(DEFAULT_REQUEST_TIMEOUT).unwrap(),
+            webhooks: vec![],
+        }
+    }
+}
@@ -17,20 +17,26 @@
 //!     example: curl -X GET 'http://IP:8888/status'
 //! /metrics : time series data collected from the internals of the node being queried.
 //!     example: curl -X GET 'http://IP:8888/metrics'
+//! /health : whether the node is alive, responding with `503` if its storage is not writable.
+//!     example: curl -X GET 'http://IP:8888/health'
+//! /ready : whether the node is ready to serve clients, responding with `503` while it is still
+//!     catching up, has too few peers or, if a validator, is not participating in consensus.
+//!     example: curl -X GET 'http://IP:8888/ready'
 
 mod config;
 mod event;
 mod filters;
+mod health;
 mod http_server;
 
 use std::{fmt::Debug, time::Instant};
 
 use datasize::DataSize;
 use futures::{future::BoxFuture, join, FutureExt};
+use http::Method;
 use tokio::{sync::oneshot, task::JoinHandle};
 use tracing::{debug, error, info, warn};
 
-use casper_json_rpc::CorsOrigin;
 use casper_types::ProtocolVersion;
 
 use super::Component;
@@ -49,11 +55,13 @@ use crate::{
     },
     reactor::{main_reactor::MainEvent, Finalize},
     types::{ChainspecInfo, StatusFeed},
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
 pub use config::Config;
 pub(crate) use event::Event;
+pub(crate) use filters::STATUS_API_PATH;
+pub(crate) use health::{Health, Readiness};
 
 const COMPONENT_NAME: &str = "rest_server";
 
@@ -107,7 +115,7 @@ pub(crate) struct InnerRestServer {
 pub(crate) struct RestServer {
     /// The component state.
     state: ComponentState,
-    config: Config,
+    config: WithDir<Config>,
     api_version: ProtocolVersion,
     network_name: String,
     node_startup_instant: Instant,
@@ -117,7 +125,7 @@ pub(crate) struct RestServer {
 
 impl RestServer {
     pub(crate) fn new(
-        config: Config,
+        config: WithDir<Config>,
         api_version: ProtocolVersion,
         network_name: String,
         node_startup_instant: Instant,
@@ -165,7 +173,8 @@ where
             }
             ComponentState::Initializing => match event {
                 Event::Initialize => {
-                    let (effects, state) = self.bind(self.config.enable_server, effect_builder);
+                    let (effects, state) =
+                        self.bind(self.config.value().enable_server, effect_builder);
                     <Self as InitializedComponent<MainEvent>>::set_state(self, state);
                     effects
                 }
@@ -242,6 +251,31 @@ where
                     let schema = OPEN_RPC_SCHEMA.clone();
                     responder.respond(schema).ignore()
                 }
+                Event::RestRequest(RestRequest::Health { responder }) => async move {
+                    let storage_writable = effect_builder.check_storage_writable().await;
+                    responder.respond(Health::new(storage_writable)).await;
+                }
+                .ignore(),
+                Event::RestRequest(RestRequest::Ready { responder }) => {
+                    let min_peer_count = self.config.value().ready_min_peers;
+                    async move {
+                        let (peers, consensus_status, (reactor_state, _)) = join!(
+                            effect_builder.network_peers(),
+                            effect_builder.consensus_status(),
+                            effect_builder.get_reactor_status(),
+                        );
+                        let is_validator = consensus_status
+                            .map_or(false, |(_, next_round_length)| next_round_length.is_some());
+                        let readiness = Readiness::new(
+                            reactor_state,
+                            peers.len(),
+                            min_peer_count,
+                            is_validator,
+                        );
+                        responder.respond(readiness).await;
+                    }
+                    .ignore()
+                }
                 Event::GetMetricsResult {
                     text,
                     main_responder,
@@ -285,34 +319,31 @@ where
         &mut self,
         effect_builder: EffectBuilder<REv>,
     ) -> Result<Effects<Self::ComponentEvent>, Self::Error> {
-        let cfg = &self.config;
+        let cfg = self.config.value();
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-        let builder = utils::start_listening(&cfg.address)?;
+        let tls = cfg
+            .tls
+            .clone()
+            .map(|tls| WithDir::new(self.config.dir(), tls));
+        let incoming = utils::start_listening(&cfg.address, tls.as_ref())?;
 
-        let server_join_handle = match cfg.cors_origin.as_str() {
-            "" => Some(tokio::spawn(http_server::run(
-                builder,
-                effect_builder,
-                self.api_version,
-                shutdown_receiver,
-                cfg.qps_limit,
-            ))),
-            "*" => Some(tokio::spawn(http_server::run_with_cors(
-                builder,
+        let cors = utils::cors(&cfg.cors_origin, &cfg.cors_allowed_methods, &[Method::GET]);
+        let server_join_handle = match cors {
+            None => Some(tokio::spawn(http_server::run(
+                incoming,
                 effect_builder,
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
-                CorsOrigin::Any,
             ))),
-            _ => Some(tokio::spawn(http_server::run_with_cors(
-                builder,
+            Some(cors) => Some(tokio::spawn(http_server::run_with_cors(
+                incoming,
                 effect_builder,
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
-                CorsOrigin::Specified(cfg.cors_origin.clone()),
+                cors,
             ))),
         };
 
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::HttpTlsConfig;
+
 /// Default binding address for the REST HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -9,6 +11,12 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
+/// Default number of peers required for the node to report being ready.
+const DEFAULT_READY_MIN_PEERS: usize = 3;
+
+fn default_ready_min_peers() -> usize {
+    DEFAULT_READY_MIN_PEERS
+}
 
 /// REST HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -24,8 +32,21 @@ pub struct Config {
     /// Max rate limit in qps.
     pub qps_limit: u64,
 
-    /// CORS origin.
+    /// CORS origin.  Empty to disable CORS, `*` to allow any origin, or a comma-separated list of
+    /// allowed origins.
     pub cors_origin: String,
+
+    /// HTTP methods allowed for cross-origin requests.  If empty, only GET is allowed.
+    #[serde(default)]
+    pub cors_allowed_methods: Vec<String>,
+
+    /// Minimum number of connected peers for the `/ready` endpoint to report the node as ready.
+    #[serde(default = "default_ready_min_peers")]
+    pub ready_min_peers: usize,
+
+    /// TLS configuration.  If absent, the server accepts plain HTTP connections.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
 }
 
 impl Config {
@@ -36,6 +57,9 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            cors_allowed_methods: vec![],
+            ready_min_peers: DEFAULT_READY_MIN_PEERS,
+            tls: None,
         }
     }
 }
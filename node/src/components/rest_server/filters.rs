@@ -35,6 +35,12 @@ pub const VALIDATOR_CHANGES_API_PATH: &str = "validator-changes";
 /// The chainspec file URL path.
 pub const CHAINSPEC_API_PATH: &str = "chainspec";
 
+/// The liveness URL path.
+pub const HEALTH_API_PATH: &str = "health";
+
+/// The readiness URL path.
+pub const READY_API_PATH: &str = "ready";
+
 pub(super) fn create_status_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
@@ -134,3 +140,50 @@ pub(super) fn create_chainspec_filter<REv: ReactorEventT>(
         })
         .boxed()
 }
+
+pub(super) fn create_health_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(HEALTH_API_PATH))
+        .and_then(move || {
+            effect_builder
+                .make_request(
+                    |responder| RestRequest::Health { responder },
+                    QueueKind::Api,
+                )
+                .map(|health| {
+                    let status = if health.is_healthy() {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    };
+                    Ok::<_, Rejection>(
+                        reply::with_status(reply::json(&health), status).into_response(),
+                    )
+                })
+        })
+        .boxed()
+}
+
+pub(super) fn create_ready_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(READY_API_PATH))
+        .and_then(move || {
+            effect_builder
+                .make_request(|responder| RestRequest::Ready { responder }, QueueKind::Api)
+                .map(|readiness| {
+                    let status = if readiness.is_ready() {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    };
+                    Ok::<_, Rejection>(
+                        reply::with_status(reply::json(&readiness), status).into_response(),
+                    )
+                })
+        })
+        .boxed()
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+
+use crate::reactor::main_reactor::ReactorState;
+
+/// The liveness of the node, as reported by the `/health` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Health {
+    /// Whether the storage folder can be written to.
+    storage_writable: bool,
+}
+
+impl Health {
+    pub(crate) fn new(storage_writable: bool) -> Self {
+        Health { storage_writable }
+    }
+
+    /// Returns whether the node is healthy.
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.storage_writable
+    }
+}
+
+/// Whether the node is ready to serve clients, as reported by the `/ready` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Readiness {
+    /// The state of the main reactor.
+    reactor_state: ReactorState,
+    /// The number of connected peers.
+    peer_count: usize,
+    /// The number of connected peers required to be ready.
+    min_peer_count: usize,
+    /// Whether the node is a validator in the current era.
+    is_validator: bool,
+}
+
+impl Readiness {
+    pub(crate) fn new(
+        reactor_state: ReactorState,
+        peer_count: usize,
+        min_peer_count: usize,
+        is_validator: bool,
+    ) -> Self {
+        Readiness {
+            reactor_state,
+            peer_count,
+            min_peer_count,
+            is_validator,
+        }
+    }
+
+    /// Returns whether the node is ready.
+    ///
+    /// A node is ready once it has caught up with the network and has enough peers. A validator
+    /// additionally needs to be participating in consensus.
+    pub(crate) fn is_ready(&self) -> bool {
+        let synced = match self.reactor_state {
+            ReactorState::KeepUp => !self.is_validator,
+            ReactorState::Validate => true,
+            ReactorState::Initialize
+            | ReactorState::CatchUp
+            | ReactorState::Upgrading
+            | ReactorState::ShutdownForUpgrade => false,
+        };
+        synced && self.peer_count >= self.min_peer_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_require_sync_and_peers_to_be_ready() {
+        assert!(Readiness::new(ReactorState::KeepUp, 3, 3, false).is_ready());
+        assert!(Readiness::new(ReactorState::Validate, 3, 3, true).is_ready());
+        assert!(!Readiness::new(ReactorState::CatchUp, 3, 3, false).is_ready());
+        assert!(!Readiness::new(ReactorState::KeepUp, 2, 3, false).is_ready());
+        // A validator which is only keeping up is not participating in consensus.
+        assert!(!Readiness::new(ReactorState::KeepUp, 3, 3, true).is_ready());
+    }
+}
@@ -1,23 +1,22 @@
 use std::{convert::Infallible, time::Duration};
 
 use futures::{future, TryFutureExt};
-use hyper::server::{conn::AddrIncoming, Builder};
+use hyper::server::Server;
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
 use tracing::{info, warn};
-use warp::Filter;
+use warp::{cors, Filter};
 
-use casper_json_rpc::CorsOrigin;
 use casper_types::ProtocolVersion;
 
 use super::{filters, ReactorEventT};
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils::HttpIncoming};
 
 /// Run the REST HTTP server.
 ///
 /// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
@@ -30,13 +29,17 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
+    let rest_health = filters::create_health_filter(effect_builder);
+    let rest_ready = filters::create_ready_filter(effect_builder);
 
     let service = warp::service(
         rest_status
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
-            .or(rest_chainspec_filter),
+            .or(rest_chainspec_filter)
+            .or(rest_health)
+            .or(rest_ready),
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
@@ -47,8 +50,8 @@ pub(super) async fn run<REv: ReactorEventT>(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(rate_limited_service);
-    info!(address = %server.local_addr(), "started REST server");
+    info!(address = %incoming, "started REST server");
+    let server = Server::builder(incoming).serve(rate_limited_service);
 
     // Shutdown the server gracefully.
     let _ = server
@@ -65,12 +68,12 @@ pub(super) async fn run<REv: ReactorEventT>(
 ///
 /// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run_with_cors<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
-    cors_origin: CorsOrigin,
+    cors: cors::Builder,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
@@ -79,6 +82,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
+    let rest_health = filters::create_health_filter(effect_builder);
+    let rest_ready = filters::create_ready_filter(effect_builder);
 
     let service = warp::service(
         rest_status
@@ -86,10 +91,9 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
             .or(rest_open_rpc)
             .or(rest_validator_changes)
             .or(rest_chainspec_filter)
-            .with(match cors_origin {
-                CorsOrigin::Any => warp::cors().allow_any_origin(),
-                CorsOrigin::Specified(origin) => warp::cors().allow_origin(origin.as_str()),
-            }),
+            .or(rest_health)
+            .or(rest_ready)
+            .with(cors),
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
@@ -100,8 +104,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(rate_limited_service);
-    info!(address = %server.local_addr(), "started REST server");
+    info!(address = %incoming, "started REST server");
+    let server = Server::builder(incoming).serve(rate_limited_service);
 
     // Shutdown the server gracefully.
     let _ = server
@@ -14,15 +14,24 @@
 mod config;
 mod event;
 mod http_server;
+mod rate_limiter;
+mod rosetta_config;
+mod rosetta_server;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
 
-use std::{fmt::Debug, time::Instant};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 
 use datasize::DataSize;
-use futures::join;
-use tracing::{error, info, warn};
+use futures::{
+    future::{self, BoxFuture},
+    join, FutureExt,
+};
+use http::Method;
+use prometheus::Registry;
+use tokio::{sync::oneshot, task::JoinHandle};
+use tracing::{debug, error, info, warn};
 
 use casper_execution_engine::core::engine_state::{
     self, BalanceRequest, BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryRequest,
@@ -39,19 +48,22 @@ use crate::{
     },
     effect::{
         requests::{
-            AcceptDeployRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest,
-            ConsensusRequest, ContractRuntimeRequest, MetricsRequest, NetworkInfoRequest,
-            ReactorStatusRequest, RpcRequest, StorageRequest, UpgradeWatcherRequest,
+            AcceptDeployRequest, AddDeployApprovalsRequest, BlockSynchronizerRequest,
+            ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
+            DeployBufferRequest, MetricsRequest, NetworkInfoRequest, ReactorStatusRequest,
+            RpcRequest, StorageRequest, UpgradeWatcherRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    reactor::main_reactor::MainEvent,
+    reactor::{main_reactor::MainEvent, Finalize},
     types::{ChainspecInfo, StatusFeed},
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
 pub use config::Config;
 pub(crate) use event::Event;
+use rate_limiter::RateLimiter;
+pub use rosetta_config::Config as RosettaConfig;
 pub use speculative_exec_config::Config as SpeculativeExecConfig;
 
 const COMPONENT_NAME: &str = "rpc_server";
@@ -61,6 +73,7 @@ pub(crate) trait ReactorEventT:
     From<Event>
     + From<RpcRequest>
     + From<AcceptDeployRequest>
+    + From<AddDeployApprovalsRequest>
     + From<ChainspecRawBytesRequest>
     + From<UpgradeWatcherRequest>
     + From<ContractRuntimeRequest>
@@ -70,6 +83,7 @@ pub(crate) trait ReactorEventT:
     + From<StorageRequest>
     + From<ReactorStatusRequest>
     + From<BlockSynchronizerRequest>
+    + From<DeployBufferRequest>
     + Send
 {
 }
@@ -78,6 +92,7 @@ impl<REv> ReactorEventT for REv where
     REv: From<Event>
         + From<RpcRequest>
         + From<AcceptDeployRequest>
+        + From<AddDeployApprovalsRequest>
         + From<ChainspecRawBytesRequest>
         + From<UpgradeWatcherRequest>
         + From<ContractRuntimeRequest>
@@ -87,6 +102,7 @@ impl<REv> ReactorEventT for REv where
         + From<StorageRequest>
         + From<ReactorStatusRequest>
         + From<BlockSynchronizerRequest>
+        + From<DeployBufferRequest>
         + Send
         + 'static
 {
@@ -97,13 +113,18 @@ pub(crate) struct RpcServer {
     /// The state.
     state: ComponentState,
     /// The config.
-    config: Config,
+    config: WithDir<Config>,
     /// The config for speculative execution.
-    speculative_exec_config: SpeculativeExecConfig,
+    speculative_exec_config: WithDir<SpeculativeExecConfig>,
+    /// The config for the Rosetta API server.
+    rosetta_config: WithDir<RosettaConfig>,
     /// The api version.
     api_version: ProtocolVersion,
     /// The network name.
     network_name: String,
+    /// The cost of a native transfer in motes, used as the payment of deploys constructed via the
+    /// Rosetta API.
+    native_transfer_cost: u64,
     /// The uptime start.
     node_startup_instant: Instant,
     /// Inner speculative execution JSON-RPC server is present only when enabled
@@ -112,25 +133,39 @@ pub(crate) struct RpcServer {
     /// no fields and no methods because all that is needed to operate it is the
     /// spawned tokio task, so a unit struct will suffice here.
     speculative_exec: Option<()>,
+    /// The rate limiter metrics.
+    #[data_size(skip)]
+    rate_limiter_metrics: Arc<rate_limiter::Metrics>,
+    /// The shutdown senders and task handles of all running servers.
+    #[data_size(skip)]
+    running_servers: Vec<(oneshot::Sender<()>, JoinHandle<()>)>,
 }
 
 impl RpcServer {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        config: Config,
-        speculative_exec_config: SpeculativeExecConfig,
+        config: WithDir<Config>,
+        speculative_exec_config: WithDir<SpeculativeExecConfig>,
+        rosetta_config: WithDir<RosettaConfig>,
         api_version: ProtocolVersion,
         network_name: String,
+        native_transfer_cost: u64,
         node_startup_instant: Instant,
-    ) -> Self {
-        RpcServer {
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(RpcServer {
             state: ComponentState::Uninitialized,
             config,
             speculative_exec_config,
+            rosetta_config,
             api_version,
             network_name,
+            native_transfer_cost,
             node_startup_instant,
             speculative_exec: None,
-        }
+            rate_limiter_metrics: Arc::new(rate_limiter::Metrics::new(registry)?),
+            running_servers: Vec::new(),
+        })
     }
 }
 
@@ -217,7 +252,8 @@ where
             }
             ComponentState::Initializing => match event {
                 Event::Initialize => {
-                    let (effects, state) = self.bind(self.config.enable_server, effect_builder);
+                    let (effects, state) =
+                        self.bind(self.config.value().enable_server, effect_builder);
                     <Self as InitializedComponent<MainEvent>>::set_state(self, state);
                     effects
                 }
@@ -444,37 +480,118 @@ where
         // Set the speculative execution HTTP server up first. The speculative
         // execution server can operate independently from the JSON-RPC server,
         // so we save its state before we construct the `RpcServer`.
-        self.speculative_exec = if self.speculative_exec_config.enable_server {
-            let cfg = &self.speculative_exec_config;
-            let builder = utils::start_listening(&cfg.address)?;
-            tokio::spawn(speculative_exec_server::run(
-                builder,
+        self.speculative_exec = if self.speculative_exec_config.value().enable_server {
+            let cfg = self.speculative_exec_config.value();
+            let tls = cfg
+                .tls
+                .clone()
+                .map(|tls| WithDir::new(self.speculative_exec_config.dir(), tls));
+            let incoming = utils::start_listening(&cfg.address, tls.as_ref())?;
+            let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+            let join_handle = tokio::spawn(speculative_exec_server::run(
+                incoming,
                 effect_builder,
                 self.api_version,
                 cfg.qps_limit,
+                Arc::new(RateLimiter::unlimited()),
                 cfg.max_body_bytes,
-                cfg.cors_origin.clone(),
+                cfg.max_batch_size,
+                utils::cors(&cfg.cors_origin, &cfg.cors_allowed_methods, &[Method::POST]),
+                shutdown_receiver,
             ));
+            self.running_servers.push((shutdown_sender, join_handle));
             Some(())
         } else {
             None
         };
 
-        let cfg = &self.config;
-        let builder = utils::start_listening(&cfg.address)?;
-        tokio::spawn(http_server::run(
-            builder,
+        // The Rosetta API server likewise operates independently from the JSON-RPC server.
+        if self.rosetta_config.value().enable_server {
+            let cfg = self.rosetta_config.value();
+            let tls = cfg
+                .tls
+                .clone()
+                .map(|tls| WithDir::new(self.rosetta_config.dir(), tls));
+            let incoming = utils::start_listening(&cfg.address, tls.as_ref())?;
+            let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+            let join_handle = tokio::spawn(rosetta_server::run(
+                incoming,
+                effect_builder,
+                self.api_version,
+                self.network_name.clone(),
+                self.native_transfer_cost,
+                cfg.qps_limit,
+                cfg.max_body_bytes,
+                utils::cors(&cfg.cors_origin, &cfg.cors_allowed_methods, &[Method::POST]),
+                shutdown_receiver,
+            ));
+            self.running_servers.push((shutdown_sender, join_handle));
+        }
+
+        let cfg = self.config.value();
+        let rate_limiter = Arc::new(RateLimiter::new(
+            cfg.qps_limit_per_ip,
+            cfg.method_qps_limits.clone(),
+            Some(Arc::clone(&self.rate_limiter_metrics)),
+        ));
+        let tls = cfg
+            .tls
+            .clone()
+            .map(|tls| WithDir::new(self.config.dir(), tls));
+        let mut incoming = utils::start_listening(&cfg.address, tls.as_ref())?;
+        // Connections over the unix socket are served by the same server, so that they count
+        // towards the same `qps_limit`.
+        if let Some(unix_socket_path) = &cfg.unix_socket_path {
+            incoming = utils::start_listening_unix(
+                incoming,
+                self.config.with_dir(unix_socket_path.clone()),
+                // Mac OS X / Linux use different types for the mask, so we need to call .into()
+                // here.
+                #[allow(clippy::useless_conversion)]
+                cfg.unix_socket_umask.into(),
+            )?;
+        }
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let join_handle = tokio::spawn(http_server::run(
+            incoming,
             effect_builder,
             self.api_version,
             cfg.qps_limit,
+            rate_limiter,
             cfg.max_body_bytes,
-            cfg.cors_origin.clone(),
+            cfg.max_batch_size,
+            utils::cors(&cfg.cors_origin, &cfg.cors_allowed_methods, &[Method::POST]),
+            shutdown_receiver,
         ));
+        self.running_servers.push((shutdown_sender, join_handle));
 
         Ok(Effects::new())
     }
 }
 
+impl Finalize for RpcServer {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        let join_handles: Vec<_> = self
+            .running_servers
+            .into_iter()
+            .map(|(shutdown_sender, join_handle)| {
+                let _ = shutdown_sender.send(());
+                join_handle
+            })
+            .collect();
+        async move {
+            // Wait for the servers to exit cleanly.
+            for result in future::join_all(join_handles).await {
+                if let Err(error) = result {
+                    error!(%error, "could not join rpc server task cleanly");
+                }
+            }
+            debug!("rpc servers exited");
+        }
+        .boxed()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{rpcs::docs::OPEN_RPC_SCHEMA, testing::assert_schema};
@@ -1,15 +1,25 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::HttpTlsConfig;
+
 /// Default binding address for the JSON-RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default per-client rate limit in qps.  Zero disables per-client rate limiting.
+const DEFAULT_QPS_LIMIT_PER_IP: u64 = 0;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single JSON-RPC batch request.
+const DEFAULT_MAX_BATCH_SIZE: usize = 20;
+/// Default umask applied when creating the unix socket.
+const DEFAULT_UNIX_SOCKET_UMASK: u16 = 0o077;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -24,10 +34,34 @@ pub struct Config {
     pub address: String,
     /// Maximum rate limit in queries per second.
     pub qps_limit: u64,
+    /// Maximum rate limit in queries per second for each client IP address.  Zero means unlimited.
+    #[serde(default)]
+    pub qps_limit_per_ip: u64,
+    /// Maximum rate limits in queries per second for individual JSON-RPC methods, shared across
+    /// all clients.  Methods not listed, or listed with a limit of zero, are unlimited.
+    #[serde(default)]
+    pub method_qps_limits: BTreeMap<String, u64>,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
-    /// CORS origin.
+    /// Maximum number of requests to accept in a single JSON-RPC batch request.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// CORS origin.  Empty to disable CORS, `*` to allow any origin, or a comma-separated list of
+    /// allowed origins.
     pub cors_origin: String,
+    /// HTTP methods allowed for cross-origin requests.  If empty, only POST is allowed.
+    #[serde(default)]
+    pub cors_allowed_methods: Vec<String>,
+    /// TLS configuration.  If absent, the server accepts plain HTTP connections.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
+    /// Path of a unix socket on which to additionally serve the JSON-RPC API.  The socket file is
+    /// removed when the server shuts down.  If absent, no unix socket is used.
+    #[serde(default)]
+    pub unix_socket_path: Option<PathBuf>,
+    /// `umask` to apply before creating the unix socket.
+    #[serde(default = "default_unix_socket_umask")]
+    pub unix_socket_umask: u16,
 }
 
 impl Config {
@@ -37,12 +71,27 @@ impl Config {
             enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            qps_limit_per_ip: DEFAULT_QPS_LIMIT_PER_IP,
+            method_qps_limits: BTreeMap::new(),
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            cors_allowed_methods: vec![],
+            tls: None,
+            unix_socket_path: None,
+            unix_socket_umask: DEFAULT_UNIX_SOCKET_UMASK,
         }
     }
 }
 
+fn default_max_batch_size() -> usize {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+fn default_unix_socket_umask() -> u16 {
+    DEFAULT_UNIX_SOCKET_UMASK
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
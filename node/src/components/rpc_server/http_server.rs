@@ -1,25 +1,33 @@
-use hyper::server::{conn::AddrIncoming, Builder};
+use std::sync::Arc;
 
-use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
+use tokio::sync::oneshot;
+use warp::cors;
+
+use casper_json_rpc::RequestHandlersBuilder;
 use casper_types::ProtocolVersion;
 
 use super::{
+    rate_limiter::RateLimiter,
     rpcs::{
-        account::PutDeploy,
+        account::{PutDeploy, PutDeployApprovals},
         chain::{
-            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+            GetBlock, GetBlockHeader, GetBlockTransfers, GetDeployInclusionProof,
+            GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
         },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges,
+            GetValidatorPerformance,
+        },
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetNamedKeys,
+            GetTrie, QueryBalance, QueryGlobalState,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
     ReactorEventT,
 };
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils::HttpIncoming};
 
 /// The URL path for all JSON-RPC requests.
 pub const RPC_API_PATH: &str = "rpc";
@@ -27,70 +35,199 @@ pub const RPC_API_PATH: &str = "rpc";
 pub const RPC_API_SERVER_NAME: &str = "JSON RPC";
 
 /// Run the JSON-RPC server.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
-    cors_origin: String,
+    max_batch_size: usize,
+    cors: Option<cors::Builder>,
+    shutdown_receiver: oneshot::Receiver<()>,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraSummary::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    PutDeploy::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    PutDeployApprovals::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetBlockTransfers::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetBlockHeader::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetDeployInclusionProof::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetStateRootHash::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetItem::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    QueryGlobalState::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetBalance::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetAccountInfo::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetDeploy::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetPeers::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetStatus::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetEraInfoBySwitchBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetEraSummary::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetAuctionInfo::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetTrie::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetValidatorChanges::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetValidatorPerformance::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    ListRpcs::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetDictionaryItem::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetChainspec::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    QueryBalance::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
+    GetNamedKeys::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
     let handlers = handlers.build();
 
-    match cors_origin.as_str() {
-        "" => {
+    match cors {
+        None => {
             super::rpcs::run(
-                builder,
-                handlers,
-                qps_limit,
-                max_body_bytes,
-                RPC_API_PATH,
-                RPC_API_SERVER_NAME,
-            )
-            .await
-        }
-        "*" => {
-            super::rpcs::run_with_cors(
-                builder,
+                incoming,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
-                CorsOrigin::Any,
+                shutdown_receiver,
             )
             .await
         }
-        _ => {
+        Some(cors) => {
             super::rpcs::run_with_cors(
-                builder,
+                incoming,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
-                CorsOrigin::Specified(cors_origin),
+                cors,
+                shutdown_receiver,
             )
             .await
         }
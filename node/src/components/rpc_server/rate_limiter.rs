@@ -0,0 +1,366 @@
+//! Token-bucket rate limiting for the JSON-RPC server.
+//!
+//! Two independent limits are applied:
+//!   * a per-client limit, keyed by the remote IP address, which is checked for every HTTP request
+//!     before its body is read and which results in an HTTP 429 response when exceeded.  Each
+//!     JSON-RPC call after the first in a batch request is charged against this limit too, and
+//!     results in a JSON-RPC error response when exceeded
+//!   * per-method limits, shared by all clients, which are checked just before a JSON-RPC request
+//!     is dispatched to its handler and which result in a JSON-RPC error response when exceeded
+
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use http::StatusCode;
+use prometheus::{IntCounter, Registry};
+use serde::Serialize;
+use tracing::{debug, trace};
+use warp::{
+    filters::BoxedFilter,
+    reject::{self, Reject},
+    reply::{self, WithStatus},
+    Filter, Rejection,
+};
+
+use crate::unregister_metric;
+
+/// The number of tracked clients above which idle per-client buckets are discarded.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+const IP_RATE_LIMITED_NAME: &str = "rpc_server_ip_rate_limited_requests";
+const IP_RATE_LIMITED_HELP: &str =
+    "number of JSON-RPC HTTP requests rejected due to the per-client rate limit";
+const METHOD_RATE_LIMITED_NAME: &str = "rpc_server_method_rate_limited_requests";
+const METHOD_RATE_LIMITED_HELP: &str =
+    "number of JSON-RPC requests rejected due to a per-method rate limit";
+
+tokio::task_local! {
+    /// The client of the HTTP request being handled by the current task.
+    static CLIENT: Client;
+}
+
+/// The client of an HTTP request, against which the request's JSON-RPC calls are charged.
+struct Client {
+    remote_address: Option<SocketAddr>,
+    /// Whether the token taken by [`filter`] for the HTTP request is yet to be used by a call.
+    prepaid: Cell<bool>,
+}
+
+/// Runs `future`, which handles an HTTP request from `remote_address` already admitted by
+/// [`filter`], such that [`RateLimiter::check_call`] charges every call it makes after the first
+/// against the per-client limit.
+pub(super) async fn with_client<F: Future>(
+    remote_address: Option<SocketAddr>,
+    future: F,
+) -> F::Output {
+    let client = Client {
+        remote_address,
+        prepaid: Cell::new(true),
+    };
+    CLIENT.scope(client, future).await
+}
+
+/// Metrics for the JSON-RPC server's rate limiter.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    ip_rate_limited: IntCounter,
+    method_rate_limited: IntCounter,
+    registry: Registry,
+}
+
+impl Metrics {
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let ip_rate_limited = IntCounter::new(IP_RATE_LIMITED_NAME, IP_RATE_LIMITED_HELP)?;
+        registry.register(Box::new(ip_rate_limited.clone()))?;
+        let method_rate_limited =
+            IntCounter::new(METHOD_RATE_LIMITED_NAME, METHOD_RATE_LIMITED_HELP)?;
+        registry.register(Box::new(method_rate_limited.clone()))?;
+
+        Ok(Metrics {
+            ip_rate_limited,
+            method_rate_limited,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.ip_rate_limited);
+        unregister_metric!(self.registry, self.method_rate_limited);
+    }
+}
+
+/// A token bucket holding up to one second's worth of tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(qps_limit: u64, now: Instant) -> Self {
+        TokenBucket {
+            tokens: qps_limit as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, qps_limit: u64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * qps_limit as f64).min(qps_limit as f64);
+        self.last_refill = now;
+    }
+
+    /// Takes a token if one is available, returning whether it succeeded.
+    fn try_take(&mut self, qps_limit: u64, now: Instant) -> bool {
+        self.refill(qps_limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether the bucket has refilled completely, i.e. whether it is indistinguishable
+    /// from a new one.
+    fn is_full(&mut self, qps_limit: u64, now: Instant) -> bool {
+        self.refill(qps_limit, now);
+        self.tokens >= qps_limit as f64
+    }
+}
+
+/// Per-client and per-method rate limiter for a JSON-RPC server.
+///
+/// A limit of zero means unlimited.
+#[derive(Debug, Default)]
+pub(super) struct RateLimiter {
+    qps_limit_per_ip: u64,
+    method_qps_limits: BTreeMap<String, u64>,
+    ip_buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+    method_buckets: Mutex<HashMap<String, TokenBucket>>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl RateLimiter {
+    pub(super) fn new(
+        qps_limit_per_ip: u64,
+        method_qps_limits: BTreeMap<String, u64>,
+        metrics: Option<Arc<Metrics>>,
+    ) -> Self {
+        RateLimiter {
+            qps_limit_per_ip,
+            method_qps_limits,
+            metrics,
+            ..RateLimiter::default()
+        }
+    }
+
+    /// Returns a rate limiter which never limits any request.
+    pub(super) fn unlimited() -> Self {
+        RateLimiter::default()
+    }
+
+    /// Returns whether a request from the given remote address is within the per-client limit.
+    ///
+    /// Requests with no known remote address are never limited.
+    pub(super) fn check_ip(&self, remote_address: Option<SocketAddr>) -> bool {
+        let ip = match remote_address {
+            Some(address) if self.qps_limit_per_ip != 0 => address.ip(),
+            _ => return true,
+        };
+        let now = Instant::now();
+        let qps_limit = self.qps_limit_per_ip;
+        let mut ip_buckets = self
+            .ip_buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if ip_buckets.len() >= MAX_TRACKED_CLIENTS && !ip_buckets.contains_key(&ip) {
+            ip_buckets.retain(|_, bucket| !bucket.is_full(qps_limit, now));
+        }
+        let allowed = ip_buckets
+            .entry(ip)
+            .or_insert_with(|| TokenBucket::new(qps_limit, now))
+            .try_take(qps_limit, now);
+        if !allowed {
+            debug!(%ip, "rate limited JSON-RPC client");
+            if let Some(metrics) = &self.metrics {
+                metrics.ip_rate_limited.inc();
+            }
+        }
+        allowed
+    }
+
+    /// Returns whether a JSON-RPC call of the given method is within that method's limit and, if it
+    /// is not the first call of its HTTP request, within the per-client limit.
+    ///
+    /// Calls made outside [`with_client`] are only checked against the method's limit.
+    pub(super) fn check_call(&self, method: &str) -> bool {
+        let within_client_limit = CLIENT
+            .try_with(|client| {
+                client.prepaid.replace(false) || self.check_ip(client.remote_address)
+            })
+            .unwrap_or(true);
+        within_client_limit && self.check_method(method)
+    }
+
+    /// Returns whether a request for the given JSON-RPC method is within that method's limit.
+    pub(super) fn check_method(&self, method: &str) -> bool {
+        let qps_limit = match self.method_qps_limits.get(method) {
+            Some(qps_limit) if *qps_limit != 0 => *qps_limit,
+            _ => return true,
+        };
+        let now = Instant::now();
+        let mut method_buckets = self
+            .method_buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let allowed = method_buckets
+            .entry(method.to_string())
+            .or_insert_with(|| TokenBucket::new(qps_limit, now))
+            .try_take(qps_limit, now);
+        if !allowed {
+            debug!(%method, "rate limited JSON-RPC method");
+            if let Some(metrics) = &self.metrics {
+                metrics.method_rate_limited.inc();
+            }
+        }
+        allowed
+    }
+}
+
+/// Rejection raised when a client exceeds the per-client rate limit.
+#[derive(Debug)]
+struct RateLimited;
+
+impl Reject for RateLimited {}
+
+/// Returns a filter which rejects requests from clients exceeding the per-client rate limit.
+///
+/// The remote address is taken from the accepted connection, as it isn't made available to filters
+/// served via `warp::service`.
+pub(super) fn filter(
+    rate_limiter: Arc<RateLimiter>,
+    remote_address: Option<SocketAddr>,
+) -> BoxedFilter<()> {
+    warp::any()
+        .and_then(move || {
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                if rate_limiter.check_ip(remote_address) {
+                    Ok::<_, Rejection>(())
+                } else {
+                    Err(reject::custom(RateLimited))
+                }
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+#[derive(Serialize)]
+struct RateLimitedBody {
+    message: &'static str,
+}
+
+/// Handler converting a [`RateLimited`] rejection into an HTTP 429 response.  All other
+/// rejections are passed through unchanged.
+///
+/// The HTTP response body will be a JSON object of the form:
+/// ```json
+/// { "message": <String> }
+/// ```
+pub(super) async fn handle_rejection(
+    error: Rejection,
+) -> Result<WithStatus<reply::Json>, Rejection> {
+    if error.find::<RateLimited>().is_some() {
+        trace!("rate limited JSON-RPC request");
+        let body = RateLimitedBody {
+            message: "Too many requests",
+        };
+        return Ok(reply::with_status(
+            reply::json(&body),
+            StatusCode::TOO_MANY_REQUESTS,
+        ));
+    }
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn token_bucket_should_refill_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+        assert!(bucket.try_take(2, start));
+        assert!(bucket.try_take(2, start));
+        assert!(!bucket.try_take(2, start));
+        assert!(!bucket.is_full(2, start));
+
+        let later = start + Duration::from_millis(500);
+        assert!(bucket.try_take(2, later));
+        assert!(!bucket.try_take(2, later));
+
+        let much_later = later + Duration::from_secs(10);
+        assert!(bucket.is_full(2, much_later));
+    }
+
+    #[test]
+    fn should_limit_per_ip() {
+        let rate_limiter = RateLimiter::new(1, BTreeMap::new(), None);
+        let first: SocketAddr = "1.2.3.4:5000".parse().unwrap();
+        let second: SocketAddr = "5.6.7.8:5000".parse().unwrap();
+
+        assert!(rate_limiter.check_ip(Some(first)));
+        assert!(!rate_limiter.check_ip(Some(first)));
+        assert!(rate_limiter.check_ip(Some(second)));
+        assert!(rate_limiter.check_ip(None));
+        assert!(rate_limiter.check_method("any_method"));
+    }
+
+    #[tokio::test]
+    async fn should_charge_each_call_after_the_first_against_per_ip_limit() {
+        let rate_limiter = RateLimiter::new(2, BTreeMap::new(), None);
+        let client: SocketAddr = "1.2.3.4:5000".parse().unwrap();
+
+        // The HTTP request itself is charged by the filter, covering its first call.
+        assert!(rate_limiter.check_ip(Some(client)));
+        with_client(Some(client), async {
+            assert!(rate_limiter.check_call("any_method"));
+            assert!(rate_limiter.check_call("any_method"));
+            assert!(!rate_limiter.check_call("any_method"));
+        })
+        .await;
+        assert!(!rate_limiter.check_ip(Some(client)));
+
+        // Calls outside a client's request are not charged against any per-client limit.
+        assert!(rate_limiter.check_call("any_method"));
+    }
+
+    #[test]
+    fn should_limit_per_method() {
+        let mut method_qps_limits = BTreeMap::new();
+        let _ = method_qps_limits.insert("limited".to_string(), 1);
+        let _ = method_qps_limits.insert("unlimited".to_string(), 0);
+        let rate_limiter = RateLimiter::new(0, method_qps_limits, None);
+
+        assert!(rate_limiter.check_method("limited"));
+        assert!(!rate_limiter.check_method("limited"));
+        for _ in 0..10 {
+            assert!(rate_limiter.check_method("unlimited"));
+            assert!(rate_limiter.check_method("unconfigured"));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::HttpTlsConfig;
+
+/// Default binding address for the Rosetta API HTTP server.
+///
+/// Uses a fixed port per node, but binds on any interface.
+const DEFAULT_ADDRESS: &str = "0.0.0.0:8080";
+/// Default rate limit in qps.
+const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default max body bytes (2.5MB).
+const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default CORS origin.
+const DEFAULT_CORS_ORIGIN: &str = "";
+
+/// Rosetta API HTTP server configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable the HTTP server.
+    pub enable_server: bool,
+    /// Address to bind the Rosetta API server to.
+    pub address: String,
+    /// Maximum rate limit in queries per second.
+    pub qps_limit: u64,
+    /// Maximum number of bytes to accept in a single request body.
+    pub max_body_bytes: u32,
+    /// CORS origin.  Empty to disable CORS, `*` to allow any origin, or a comma-separated list of
+    /// allowed origins.
+    pub cors_origin: String,
+    /// HTTP methods allowed for cross-origin requests.  If empty, only POST is allowed.
+    #[serde(default)]
+    pub cors_allowed_methods: Vec<String>,
+    /// TLS configuration.  If absent, the server accepts plain HTTP connections.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
+}
+
+impl Config {
+    /// Creates a default instance for the Rosetta API server.
+    pub fn new() -> Self {
+        Config {
+            enable_server: false,
+            address: DEFAULT_ADDRESS.to_string(),
+            qps_limit: DEFAULT_QPS_LIMIT,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            cors_allowed_methods: vec![],
+            tls: None,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
@@ -0,0 +1,1802 @@
+//! Rosetta API server
+//!
+//! Serves the [Rosetta Data and Construction APIs][1] for native CSPR transfers, backed by storage,
+//! the deploy buffer and the contract runtime:
+//!
+//! * `/network/list`, `/network/status` and `/network/options`
+//! * `/block`, reporting every balance change made by each deploy, as derived from its execution
+//!   effects, and the intended transfer of a failed native transfer deploy as failed operations
+//! * `/account/balance`, for an account hash, a public key or a purse URef, at any available block
+//! * `/mempool` and `/mempool/transaction`, for deploys which are not yet part of a finalized block
+//! * `/construction/derive`, `/preprocess`, `/metadata`, `/payloads`, `/combine`, `/parse`,
+//!   `/hash` and `/submit`, constructing native transfer deploys signed with Ed25519 keys, with
+//!   the deploy JSON encoded as the transaction
+//!
+//! Balance changes of an account's main purse are reported against the account hash, those of any
+//! other purse against the purse's URef.  Balance changes made at the end of an era, i.e.
+//! seigniorage rewards and unbonding payouts, are reported as a transaction of the switch block
+//! identified by the block's hash, derived from the effect of the era's step.  Blocks whose
+//! execution results are stored in compact form can't be reported, as their effects no longer hold
+//! the amounts written, and neither can switch blocks which weren't executed by this node, as their
+//! step effect isn't stored.
+//!
+//! [1]: https://www.rosetta-api.org/docs/welcome.html
+
+mod types;
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    future::Future,
+    iter,
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::{future, FutureExt};
+use http::{header::CONTENT_TYPE, Response};
+use hyper::{server::Server, Body};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{select, sync::oneshot};
+use tower::builder::ServiceBuilder;
+use tracing::{debug, info, warn};
+use warp::{
+    cors,
+    filters::BoxedFilter,
+    http::StatusCode,
+    reject::Rejection,
+    reply::{self, Reply},
+    Filter,
+};
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_hashing::Digest;
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::Bytes,
+    crypto, runtime_args,
+    system::{mint, standard_payment},
+    AccessRights, AsymmetricType, ExecutionEffect, ExecutionResult, Key, ProtocolVersion,
+    PublicKey, RuntimeArgs, Signature, TimeDiff, Timestamp, Transform, URef, URefAddr, U512,
+};
+
+use super::ReactorEventT;
+use crate::{
+    components::deploy_acceptor::DeployAcceptance,
+    effect::EffectBuilder,
+    types::{Approval, Block, BlockHash, Deploy, DeployHash},
+    utils::HttpIncoming,
+};
+use types::{
+    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, Allow, Amount,
+    BlockIdentifier, BlockRequest, BlockResponse, ConstructionCombineRequest,
+    ConstructionCombineResponse, ConstructionDeriveRequest, ConstructionDeriveResponse,
+    ConstructionMetadata, ConstructionMetadataRequest, ConstructionMetadataResponse,
+    ConstructionOptions, ConstructionParseRequest, ConstructionParseResponse,
+    ConstructionPayloadsRequest, ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+    ConstructionPreprocessResponse, ConstructionRequest, Currency, Error, MempoolResponse,
+    MempoolTransactionRequest, MempoolTransactionResponse, MetadataRequest, NetworkIdentifier,
+    NetworkListResponse, NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse, Operation,
+    OperationIdentifier, OperationStatus, PartialBlockIdentifier, Peer, SigningPayload,
+    Transaction, TransactionIdentifier, TransactionIdentifierResponse, Version,
+};
+
+pub const ROSETTA_SERVER_NAME: &str = "Rosetta API";
+
+/// The version of the Rosetta specification implemented.
+const ROSETTA_VERSION: &str = "1.4.13";
+/// The blockchain name reported in network identifiers.
+const BLOCKCHAIN: &str = "casper";
+/// The symbol of the native token.
+const CURRENCY_SYMBOL: &str = "CSPR";
+/// The number of decimals of the native token, i.e. motes per CSPR.
+const CURRENCY_DECIMALS: u32 = 9;
+/// The type of operations reporting a change of a purse's balance made by a deploy.
+const BALANCE_CHANGE_OPERATION_TYPE: &str = "BalanceChange";
+/// The type of operations describing a native transfer, to be constructed or which failed.
+const TRANSFER_OPERATION_TYPE: &str = "Transfer";
+/// The status of operations which changed balances.
+const SUCCESS_OPERATION_STATUS: &str = "Success";
+/// The status of operations which were intended by a failed deploy, but didn't change balances.
+const FAILURE_OPERATION_STATUS: &str = "Failure";
+/// The curve type of Ed25519 public keys.
+const CURVE_EDWARDS25519: &str = "edwards25519";
+/// The curve type of secp256k1 public keys.
+const CURVE_SECP256K1: &str = "secp256k1";
+/// The signature type of Ed25519 signatures, the only type accepted for constructed deploys.
+const SIGNATURE_TYPE_ED25519: &str = "ed25519";
+/// The time to live of constructed deploys.
+const DEPLOY_TTL: TimeDiff = TimeDiff::from_seconds(30 * 60);
+/// The gas price of constructed deploys.
+const DEPLOY_GAS_PRICE: u64 = 1;
+/// How long open connections are drained for on shutdown, before they are dropped.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// The kinds of errors returned by the Rosetta API server.
+#[derive(Clone, Copy, Debug)]
+enum ErrorKind {
+    InvalidNetwork = 1,
+    InvalidBlockIdentifier = 2,
+    BlockNotFound = 3,
+    InvalidAccountIdentifier = 4,
+    AccountNotFound = 5,
+    BalanceUnavailable = 6,
+    InvalidTransaction = 7,
+    TransactionRejected = 8,
+    ExecutionResultsUnavailable = 9,
+    InvalidOperations = 10,
+    InvalidPublicKey = 11,
+    InvalidSignature = 12,
+    TransactionNotFound = 13,
+}
+
+impl ErrorKind {
+    const ALL: [ErrorKind; 13] = [
+        ErrorKind::InvalidNetwork,
+        ErrorKind::InvalidBlockIdentifier,
+        ErrorKind::BlockNotFound,
+        ErrorKind::InvalidAccountIdentifier,
+        ErrorKind::AccountNotFound,
+        ErrorKind::BalanceUnavailable,
+        ErrorKind::InvalidTransaction,
+        ErrorKind::TransactionRejected,
+        ErrorKind::ExecutionResultsUnavailable,
+        ErrorKind::InvalidOperations,
+        ErrorKind::InvalidPublicKey,
+        ErrorKind::InvalidSignature,
+        ErrorKind::TransactionNotFound,
+    ];
+
+    fn message(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidNetwork => "invalid network identifier",
+            ErrorKind::InvalidBlockIdentifier => "invalid block identifier",
+            ErrorKind::BlockNotFound => "block not found",
+            ErrorKind::InvalidAccountIdentifier => "invalid account identifier",
+            ErrorKind::AccountNotFound => "account not found",
+            ErrorKind::BalanceUnavailable => "balance unavailable",
+            ErrorKind::InvalidTransaction => "invalid transaction",
+            ErrorKind::TransactionRejected => "transaction rejected",
+            ErrorKind::ExecutionResultsUnavailable => "execution results unavailable",
+            ErrorKind::InvalidOperations => "invalid operations",
+            ErrorKind::InvalidPublicKey => "invalid public key",
+            ErrorKind::InvalidSignature => "invalid signature",
+            ErrorKind::TransactionNotFound => "transaction not found",
+        }
+    }
+
+    /// Whether the same request may succeed later, e.g. once the node has synced further.
+    fn retriable(self) -> bool {
+        matches!(
+            self,
+            ErrorKind::BlockNotFound | ErrorKind::BalanceUnavailable
+        )
+    }
+
+    fn error(self) -> Error {
+        Error {
+            code: self as u32,
+            message: self.message().to_string(),
+            retriable: self.retriable(),
+            details: None,
+        }
+    }
+
+    fn with_details<T: ToString>(self, details: T) -> Error {
+        Error {
+            details: Some(serde_json::json!({ "error": details.to_string() })),
+            ..self.error()
+        }
+    }
+}
+
+/// Run the Rosetta API server.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly, after
+/// draining open connections for at most `SHUTDOWN_GRACE_PERIOD`.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn run<REv: ReactorEventT>(
+    incoming: HttpIncoming,
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    network_name: String,
+    native_transfer_cost: u64,
+    qps_limit: u64,
+    max_body_bytes: u32,
+    cors: Option<cors::Builder>,
+    shutdown_receiver: oneshot::Receiver<()>,
+) {
+    let network = NetworkIdentifier {
+        blockchain: BLOCKCHAIN.to_string(),
+        network: network_name,
+    };
+    let routes = create_routes(
+        effect_builder,
+        api_version,
+        network,
+        native_transfer_cost,
+        max_body_bytes,
+    );
+
+    let service = match cors {
+        None => warp::service(routes),
+        Some(cors) => warp::service(
+            routes
+                .with(cors.allow_header(CONTENT_TYPE))
+                .map(Reply::into_response)
+                .boxed(),
+        ),
+    };
+
+    let make_svc =
+        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+
+    let rate_limited_service = ServiceBuilder::new()
+        .rate_limit(qps_limit, Duration::from_secs(1))
+        .service(make_svc);
+
+    info!(address = %incoming, "started {} server", ROSETTA_SERVER_NAME);
+    let shutdown = shutdown_receiver.map(|_| ()).shared();
+    let server_with_shutdown = Server::builder(incoming)
+        .serve(rate_limited_service)
+        .with_graceful_shutdown(shutdown.clone());
+    // Requests in flight may wait on a reactor which is shutting down as well, so draining them is
+    // bounded.
+    let drain_timeout = shutdown.then(|()| tokio::time::sleep(SHUTDOWN_GRACE_PERIOD));
+    select! {
+        result = server_with_shutdown => {
+            if let Err(error) = result {
+                warn!(%error, "error running {} server", ROSETTA_SERVER_NAME);
+            }
+        }
+        _ = drain_timeout => {
+            warn!(
+                "{} server didn't drain its connections within {:?}, dropping them",
+                ROSETTA_SERVER_NAME, SHUTDOWN_GRACE_PERIOD
+            );
+        }
+    }
+    info!("{} server shut down", ROSETTA_SERVER_NAME);
+}
+
+fn create_routes<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    network: NetworkIdentifier,
+    native_transfer_cost: u64,
+    max_body_bytes: u32,
+) -> BoxedFilter<(Response<Body>,)> {
+    let network = Arc::new(network);
+
+    let network_list = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("network" / "list").boxed(),
+            max_body_bytes,
+            move |_: MetadataRequest| {
+                let network = Arc::clone(&network);
+                async move {
+                    Ok(NetworkListResponse {
+                        network_identifiers: vec![(*network).clone()],
+                    })
+                }
+            },
+        )
+    };
+    let network_status = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("network" / "status").boxed(),
+            max_body_bytes,
+            move |request: NetworkRequest| {
+                network_status(effect_builder, Arc::clone(&network), request)
+            },
+        )
+    };
+    let network_options = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("network" / "options").boxed(),
+            max_body_bytes,
+            move |request: NetworkRequest| {
+                let network = Arc::clone(&network);
+                async move { network_options(api_version, &network, request) }
+            },
+        )
+    };
+    let block = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("block").boxed(),
+            max_body_bytes,
+            move |request: BlockRequest| block(effect_builder, Arc::clone(&network), request),
+        )
+    };
+    let account_balance = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("account" / "balance").boxed(),
+            max_body_bytes,
+            move |request: AccountBalanceRequest| {
+                account_balance(effect_builder, Arc::clone(&network), request)
+            },
+        )
+    };
+    let mempool = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("mempool").boxed(),
+            max_body_bytes,
+            move |request: NetworkRequest| mempool(effect_builder, Arc::clone(&network), request),
+        )
+    };
+    let mempool_transaction = {
+        let network = Arc::clone(&network);
+        endpoint(
+            warp::path!("mempool" / "transaction").boxed(),
+            max_body_bytes,
+            move |request: MempoolTransactionRequest| {
+                mempool_transaction(effect_builder, Arc::clone(&network), request)
+            },
+        )
+    };
+    let construction_derive = offline_endpoint(
+        warp::path!("construction" / "derive").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_derive,
+    );
+    let construction_preprocess = offline_endpoint(
+        warp::path!("construction" / "preprocess").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_preprocess,
+    );
+    let construction_metadata = offline_endpoint(
+        warp::path!("construction" / "metadata").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        move |network: &NetworkIdentifier, request: ConstructionMetadataRequest| {
+            construction_metadata(network, native_transfer_cost, request)
+        },
+    );
+    let construction_payloads = offline_endpoint(
+        warp::path!("construction" / "payloads").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_payloads,
+    );
+    let construction_combine = offline_endpoint(
+        warp::path!("construction" / "combine").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_combine,
+    );
+    let construction_parse = offline_endpoint(
+        warp::path!("construction" / "parse").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_parse,
+    );
+    let construction_hash = offline_endpoint(
+        warp::path!("construction" / "hash").boxed(),
+        max_body_bytes,
+        Arc::clone(&network),
+        construction_hash,
+    );
+    let construction_submit = endpoint(
+        warp::path!("construction" / "submit").boxed(),
+        max_body_bytes,
+        move |request: ConstructionRequest| {
+            construction_submit(effect_builder, Arc::clone(&network), request)
+        },
+    );
+
+    network_list
+        .or(network_status)
+        .unify()
+        .or(network_options)
+        .unify()
+        .or(block)
+        .unify()
+        .or(account_balance)
+        .unify()
+        .or(mempool)
+        .unify()
+        .or(mempool_transaction)
+        .unify()
+        .or(construction_derive)
+        .unify()
+        .or(construction_preprocess)
+        .unify()
+        .or(construction_metadata)
+        .unify()
+        .or(construction_payloads)
+        .unify()
+        .or(construction_combine)
+        .unify()
+        .or(construction_parse)
+        .unify()
+        .or(construction_hash)
+        .unify()
+        .or(construction_submit)
+        .unify()
+        .boxed()
+}
+
+/// Creates a filter for a POST endpoint at `path`, taking a JSON request and responding with
+/// either the JSON result of `handler` or a JSON error with status 500, as mandated by Rosetta.
+fn endpoint<Req, Res, F, Fut>(
+    path: BoxedFilter<()>,
+    max_body_bytes: u32,
+    handler: F,
+) -> BoxedFilter<(Response<Body>,)>
+where
+    Req: DeserializeOwned + Send + 'static,
+    Res: Serialize,
+    F: Fn(Req) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Result<Res, Error>> + Send,
+{
+    warp::post()
+        .and(path)
+        .and(warp::body::content_length_limit(u64::from(max_body_bytes)))
+        .and(warp::body::json())
+        .and_then(move |request: Req| {
+            let handler = handler.clone();
+            async move {
+                let response = match handler(request).await {
+                    Ok(result) => reply::json(&result).into_response(),
+                    Err(error) => {
+                        debug!(?error, "{} request failed", ROSETTA_SERVER_NAME);
+                        reply::with_status(reply::json(&error), StatusCode::INTERNAL_SERVER_ERROR)
+                            .into_response()
+                    }
+                };
+                Ok::<_, Rejection>(response)
+            }
+        })
+        .boxed()
+}
+
+/// Creates a filter for an endpoint like `endpoint`, whose `handler` only needs the network
+/// identifier, i.e. which works without access to the node's state.
+fn offline_endpoint<Req, Res, F>(
+    path: BoxedFilter<()>,
+    max_body_bytes: u32,
+    network: Arc<NetworkIdentifier>,
+    handler: F,
+) -> BoxedFilter<(Response<Body>,)>
+where
+    Req: DeserializeOwned + Send + 'static,
+    Res: Serialize + Send + 'static,
+    F: Fn(&NetworkIdentifier, Req) -> Result<Res, Error> + Clone + Send + Sync + 'static,
+{
+    endpoint(path, max_body_bytes, move |request: Req| {
+        future::ready(handler(&network, request))
+    })
+}
+
+fn check_network(expected: &NetworkIdentifier, actual: &NetworkIdentifier) -> Result<(), Error> {
+    if expected != actual {
+        return Err(ErrorKind::InvalidNetwork.with_details(format!(
+            "expected {}/{}, got {}/{}",
+            expected.blockchain, expected.network, actual.blockchain, actual.network
+        )));
+    }
+    Ok(())
+}
+
+fn block_identifier(block_hash: &BlockHash, height: u64) -> BlockIdentifier {
+    BlockIdentifier {
+        index: height,
+        hash: base16::encode_lower(block_hash.inner()),
+    }
+}
+
+fn transaction_identifier(deploy_hash: &DeployHash) -> TransactionIdentifier {
+    TransactionIdentifier {
+        hash: base16::encode_lower(deploy_hash.inner()),
+    }
+}
+
+/// The identifier of the transaction holding the balance changes made at the end of the era of the
+/// given switch block.
+fn era_end_transaction_identifier(block_hash: &BlockHash) -> TransactionIdentifier {
+    TransactionIdentifier {
+        hash: base16::encode_lower(block_hash.inner()),
+    }
+}
+
+fn amount(value: String) -> Amount {
+    Amount {
+        value,
+        currency: Currency {
+            symbol: CURRENCY_SYMBOL.to_string(),
+            decimals: CURRENCY_DECIMALS,
+        },
+    }
+}
+
+async fn network_status<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: NetworkRequest,
+) -> Result<NetworkStatusResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    let highest_block = effect_builder
+        .get_highest_complete_block_from_storage()
+        .await
+        .ok_or_else(|| ErrorKind::BlockNotFound.with_details("no complete blocks stored"))?;
+    let oldest_header = effect_builder
+        .get_block_header_at_height_from_storage(
+            effect_builder
+                .get_available_block_range_from_storage()
+                .await
+                .low(),
+            true,
+        )
+        .await
+        .ok_or_else(|| ErrorKind::BlockNotFound.with_details("oldest block not stored"))?;
+    let oldest_block_identifier =
+        block_identifier(&oldest_header.block_hash(), oldest_header.height());
+    // Nodes which synced from a trusted hash may not have the genesis block header.
+    let genesis_block_identifier = effect_builder
+        .get_block_header_at_height_from_storage(0, false)
+        .await
+        .map_or_else(
+            || oldest_block_identifier.clone(),
+            |header| block_identifier(&header.block_hash(), 0),
+        );
+    let peers = effect_builder
+        .network_peers()
+        .await
+        .into_keys()
+        .map(|node_id| Peer {
+            peer_id: node_id.to_string(),
+        })
+        .collect();
+
+    Ok(NetworkStatusResponse {
+        current_block_identifier: block_identifier(highest_block.hash(), highest_block.height()),
+        current_block_timestamp: highest_block.timestamp().millis(),
+        genesis_block_identifier,
+        oldest_block_identifier,
+        peers,
+    })
+}
+
+fn network_options(
+    api_version: ProtocolVersion,
+    network: &NetworkIdentifier,
+    request: NetworkRequest,
+) -> Result<NetworkOptionsResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    Ok(NetworkOptionsResponse {
+        version: Version {
+            rosetta_version: ROSETTA_VERSION.to_string(),
+            node_version: api_version.to_string(),
+        },
+        allow: Allow {
+            operation_statuses: vec![
+                OperationStatus {
+                    status: SUCCESS_OPERATION_STATUS.to_string(),
+                    successful: true,
+                },
+                OperationStatus {
+                    status: FAILURE_OPERATION_STATUS.to_string(),
+                    successful: false,
+                },
+            ],
+            operation_types: vec![
+                BALANCE_CHANGE_OPERATION_TYPE.to_string(),
+                TRANSFER_OPERATION_TYPE.to_string(),
+            ],
+            errors: ErrorKind::ALL.iter().map(|kind| kind.error()).collect(),
+            historical_balance_lookup: true,
+        },
+    })
+}
+
+/// Retrieves the complete block identified by `partial_block_identifier`, or the highest complete
+/// block if neither index nor hash are given.
+async fn get_block<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    partial_block_identifier: &PartialBlockIdentifier,
+) -> Result<Block, Error> {
+    let only_from_available_block_range = true;
+
+    let maybe_block_hash = partial_block_identifier
+        .hash
+        .as_ref()
+        .map(|hash| {
+            Digest::from_hex(hash)
+                .map(BlockHash::new)
+                .map_err(|error| ErrorKind::InvalidBlockIdentifier.with_details(error))
+        })
+        .transpose()?;
+
+    let maybe_block = match (partial_block_identifier.index, maybe_block_hash) {
+        (Some(height), _) => effect_builder
+            .get_block_at_height_with_metadata_from_storage(height, only_from_available_block_range)
+            .await
+            .map(|block_with_metadata| block_with_metadata.block),
+        (None, Some(block_hash)) => effect_builder
+            .get_block_with_metadata_from_storage(block_hash, only_from_available_block_range)
+            .await
+            .map(|block_with_metadata| block_with_metadata.block),
+        (None, None) => {
+            effect_builder
+                .get_highest_complete_block_from_storage()
+                .await
+        }
+    };
+    let block = maybe_block.ok_or_else(|| ErrorKind::BlockNotFound.error())?;
+
+    if let Some(block_hash) = maybe_block_hash {
+        if *block.hash() != block_hash {
+            return Err(ErrorKind::InvalidBlockIdentifier
+                .with_details("block hash does not match block index"));
+        }
+    }
+    Ok(block)
+}
+
+fn execution_effect(execution_result: &ExecutionResult) -> &ExecutionEffect {
+    match execution_result {
+        ExecutionResult::Failure { effect, .. } | ExecutionResult::Success { effect, .. } => effect,
+    }
+}
+
+/// Whether the effect is stored in compact form, in which the values written and added are dropped.
+///
+/// A full effect has no operations, whereas compacting it turns each of its transforms into one.
+fn is_compact(effect: &ExecutionEffect) -> bool {
+    !effect.operations.is_empty()
+}
+
+/// Returns the address of the purse whose balance is stored under the given formatted key, if any.
+fn balance_key(key: &str) -> Option<URefAddr> {
+    match Key::from_formatted_str(key) {
+        Ok(Key::Balance(purse_addr)) => Some(purse_addr),
+        _ => None,
+    }
+}
+
+/// Returns the purses whose balances are written, rather than added to, by the given effect.
+fn written_purses(effect: &ExecutionEffect) -> impl Iterator<Item = URefAddr> + '_ {
+    effect
+        .transforms
+        .iter()
+        .filter_map(|entry| match (balance_key(&entry.key), &entry.transform) {
+            (Some(purse_addr), Transform::WriteCLValue(_)) => Some(purse_addr),
+            _ => None,
+        })
+}
+
+/// Returns the balance changes made by the given effect, as pairs of a purse address and a signed
+/// amount, in the order of the effect's transforms.
+///
+/// Adding to a balance records the amount added, whereas writing it records the new balance, so
+/// `balances` must hold the balance before the effect of every purse written by it.  It is updated
+/// to the balances after the effect.
+fn balance_changes(
+    effect: &ExecutionEffect,
+    balances: &mut HashMap<URefAddr, U512>,
+) -> Vec<(URefAddr, String)> {
+    let mut changes = Vec::new();
+    for entry in &effect.transforms {
+        let purse_addr = match balance_key(&entry.key) {
+            Some(purse_addr) => purse_addr,
+            None => continue,
+        };
+        match &entry.transform {
+            Transform::AddUInt512(added) => {
+                if let Some(balance) = balances.get_mut(&purse_addr) {
+                    *balance = balance.saturating_add(*added);
+                }
+                if !added.is_zero() {
+                    changes.push((purse_addr, added.to_string()));
+                }
+            }
+            Transform::WriteCLValue(cl_value) => {
+                let new_balance: U512 = match cl_value.clone().into_t() {
+                    Ok(new_balance) => new_balance,
+                    Err(_) => continue,
+                };
+                let old_balance = balances.insert(purse_addr, new_balance).unwrap_or_default();
+                if new_balance > old_balance {
+                    changes.push((purse_addr, (new_balance - old_balance).to_string()));
+                } else if new_balance < old_balance {
+                    changes.push((purse_addr, format!("-{}", old_balance - new_balance)));
+                }
+            }
+            _ => (),
+        }
+    }
+    changes
+}
+
+/// Returns the address under which a purse's balance changes are reported: the account hash if it
+/// is the main purse of an account in `main_purses`, or else the purse's URef.
+fn purse_address(purse_addr: &URefAddr, main_purses: &HashMap<URefAddr, AccountHash>) -> String {
+    match main_purses.get(purse_addr) {
+        Some(account_hash) => account_hash.to_formatted_string(),
+        None => URef::new(*purse_addr, AccessRights::READ_ADD_WRITE).to_formatted_string(),
+    }
+}
+
+fn balance_change_operations(
+    changes: &[(URefAddr, String)],
+    main_purses: &HashMap<URefAddr, AccountHash>,
+) -> Vec<Operation> {
+    changes
+        .iter()
+        .zip(0..)
+        .map(|((purse_addr, value), index)| Operation {
+            operation_identifier: OperationIdentifier { index },
+            operation_type: BALANCE_CHANGE_OPERATION_TYPE.to_string(),
+            status: Some(SUCCESS_OPERATION_STATUS.to_string()),
+            account: AccountIdentifier {
+                address: purse_address(purse_addr, main_purses),
+            },
+            amount: amount(value.clone()),
+        })
+        .collect()
+}
+
+/// Returns the accounts whose unbonds or withdrawals are changed by the given effect, i.e. the
+/// accounts which may be paid out unbonded amounts by the step.
+fn unbonding_accounts(effect: &ExecutionEffect) -> impl Iterator<Item = AccountHash> + '_ {
+    effect
+        .transforms
+        .iter()
+        .filter_map(|entry| match Key::from_formatted_str(&entry.key) {
+            Ok(Key::Withdraw(account_hash)) | Ok(Key::Unbond(account_hash)) => Some(account_hash),
+            _ => None,
+        })
+}
+
+/// Returns the transaction holding the balance changes made by the step at the end of the era of
+/// the given switch block.
+///
+/// The step is run after the block's deploys, so `balances` must hold the balances left by them.
+fn era_end_transaction(
+    block_hash: &BlockHash,
+    step_effect: &ExecutionEffect,
+    balances: &mut HashMap<URefAddr, U512>,
+    main_purses: &HashMap<URefAddr, AccountHash>,
+) -> Transaction {
+    let changes = balance_changes(step_effect, balances);
+    Transaction {
+        transaction_identifier: era_end_transaction_identifier(block_hash),
+        operations: balance_change_operations(&changes, main_purses),
+        metadata: None,
+    }
+}
+
+/// A native transfer from an account to another account or to a purse.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct NativeTransfer {
+    from: AccountHash,
+    to: Result<AccountHash, URef>,
+    amount: U512,
+    id: Option<u64>,
+}
+
+/// Returns the native transfer made by the given deploy, or `None` if it is not a native transfer
+/// or its arguments can't be parsed.
+fn native_transfer(deploy: &Deploy) -> Option<NativeTransfer> {
+    let args = match deploy.session() {
+        ExecutableDeployItem::Transfer { args } => args,
+        _ => return None,
+    };
+    let amount = args.get(mint::ARG_AMOUNT)?.clone().into_t().ok()?;
+    let target = args.get(mint::ARG_TARGET)?.clone();
+    let to = if let Ok(account_hash) = target.clone().into_t::<AccountHash>() {
+        Ok(account_hash)
+    } else if let Ok(public_key) = target.clone().into_t::<PublicKey>() {
+        Ok(public_key.to_account_hash())
+    } else if let Ok(bytes) = target.clone().into_t::<[u8; 32]>() {
+        Ok(AccountHash::new(bytes))
+    } else {
+        Err(target.into_t::<URef>().ok()?)
+    };
+    let id = args
+        .get(mint::ARG_ID)
+        .and_then(|id| id.clone().into_t().ok())
+        .flatten();
+    Some(NativeTransfer {
+        from: deploy.header().account().to_account_hash(),
+        to,
+        amount,
+        id,
+    })
+}
+
+/// Returns the operations of a native transfer: a debit of the sender and a credit of the
+/// recipient, with the given status, or none if the transfer has not been executed.
+fn transfer_operations(
+    transfer: &NativeTransfer,
+    first_index: u64,
+    status: Option<&str>,
+) -> [Operation; 2] {
+    let recipient = match transfer.to {
+        Ok(account_hash) => account_hash.to_formatted_string(),
+        Err(purse) => purse.to_formatted_string(),
+    };
+    let operation = |index, address, value| Operation {
+        operation_identifier: OperationIdentifier { index },
+        operation_type: TRANSFER_OPERATION_TYPE.to_string(),
+        status: status.map(str::to_string),
+        account: AccountIdentifier { address },
+        amount: amount(value),
+    };
+    [
+        operation(
+            first_index,
+            transfer.from.to_formatted_string(),
+            format!("-{}", transfer.amount),
+        ),
+        operation(first_index + 1, recipient, transfer.amount.to_string()),
+    ]
+}
+
+/// Parses the operations of a native transfer to be constructed: a debit of the sending account
+/// and a credit of the same amount to the receiving account.
+fn parse_transfer_operations(
+    operations: &[Operation],
+    id: Option<u64>,
+) -> Result<NativeTransfer, Error> {
+    let (debit, credit) = match operations {
+        [first, second] if first.amount.value.starts_with('-') => (first, second),
+        [first, second] => (second, first),
+        _ => {
+            return Err(ErrorKind::InvalidOperations
+                .with_details("expected a debit and a credit operation"))
+        }
+    };
+    for operation in [debit, credit] {
+        if operation.operation_type != TRANSFER_OPERATION_TYPE {
+            return Err(ErrorKind::InvalidOperations.with_details(format!(
+                "unsupported operation type {}",
+                operation.operation_type
+            )));
+        }
+        if operation.amount.currency.symbol != CURRENCY_SYMBOL
+            || operation.amount.currency.decimals != CURRENCY_DECIMALS
+        {
+            return Err(ErrorKind::InvalidOperations.with_details(format!(
+                "unsupported currency {}",
+                operation.amount.currency.symbol
+            )));
+        }
+    }
+    let amount = U512::from_dec_str(&credit.amount.value).ok();
+    let debited = debit
+        .amount
+        .value
+        .strip_prefix('-')
+        .and_then(|value| U512::from_dec_str(value).ok());
+    let amount = match (amount, debited) {
+        (Some(amount), Some(debited)) if amount == debited && !amount.is_zero() => amount,
+        _ => {
+            return Err(ErrorKind::InvalidOperations
+                .with_details("expected a debit and a credit of the same non-zero amount"))
+        }
+    };
+    let account =
+        |operation: &Operation| match parse_address(&operation.account.address)? {
+            Ok(account_hash) => Ok(account_hash),
+            Err(_) => Err(ErrorKind::InvalidAccountIdentifier
+                .with_details("expected an account, not a purse")),
+        };
+    Ok(NativeTransfer {
+        from: account(debit)?,
+        to: Ok(account(credit)?),
+        amount,
+        id,
+    })
+}
+
+async fn block<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: BlockRequest,
+) -> Result<BlockResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    let block = get_block(effect_builder, &request.block_identifier).await?;
+
+    // The execution results are collected in the order in which the deploys were executed, as
+    // balance changes are derived from consecutive writes.
+    let mut execution_results: HashMap<_, _> = effect_builder
+        .get_execution_results_from_storage(*block.hash())
+        .await
+        .ok_or_else(|| ErrorKind::ExecutionResultsUnavailable.error())?
+        .into_iter()
+        .map(|(deploy_hash, deploy_header, execution_result)| {
+            (deploy_hash, (deploy_header, execution_result))
+        })
+        .collect();
+    let mut executed = Vec::new();
+    for deploy_hash in block.deploy_and_transfer_hashes() {
+        let (deploy_header, execution_result) =
+            execution_results.remove(deploy_hash).ok_or_else(|| {
+                ErrorKind::ExecutionResultsUnavailable
+                    .with_details(format!("no execution result of deploy {}", deploy_hash))
+            })?;
+        if is_compact(execution_effect(&execution_result)) {
+            return Err(ErrorKind::ExecutionResultsUnavailable
+                .with_details("execution results are stored in compact form"));
+        }
+        executed.push((*deploy_hash, deploy_header, execution_result));
+    }
+
+    // The effect of the step run at the end of the era, which is stored only if this node executed
+    // the switch block.
+    let maybe_step_effect = if block.header().is_switch_block() {
+        let step_effect = effect_builder
+            .get_step_effect_from_storage(*block.hash())
+            .await
+            .ok_or_else(|| {
+                ErrorKind::ExecutionResultsUnavailable
+                    .with_details("no step effect of the switch block")
+            })?;
+        if is_compact(&step_effect) {
+            return Err(ErrorKind::ExecutionResultsUnavailable
+                .with_details("execution results are stored in compact form"));
+        }
+        Some(step_effect)
+    } else {
+        None
+    };
+
+    // The balances before the block of all purses written in it, as a baseline for the changes.
+    let written: HashSet<_> = executed
+        .iter()
+        .map(|(_, _, execution_result)| execution_effect(execution_result))
+        .chain(maybe_step_effect.iter())
+        .flat_map(written_purses)
+        .collect();
+    let mut balances = HashMap::new();
+    if !written.is_empty() {
+        let parent_state_root_hash = *effect_builder
+            .get_block_header_from_storage(*block.header().parent_hash(), false)
+            .await
+            .ok_or_else(|| {
+                ErrorKind::BalanceUnavailable.with_details("parent block header not stored")
+            })?
+            .state_root_hash();
+        for purse_addr in written {
+            // A purse created in this block has no balance before it.
+            let balance = effect_builder
+                .check_purse_balance(
+                    parent_state_root_hash,
+                    URef::new(purse_addr, AccessRights::READ),
+                )
+                .await
+                .unwrap_or_default();
+            balances.insert(purse_addr, balance);
+        }
+    }
+
+    // The main purses of the deploys' accounts, of the accounts involved in transfers and of those
+    // unbonding at the end of the era.
+    let transfers = effect_builder
+        .get_block_transfers_from_storage(*block.hash())
+        .await
+        .unwrap_or_default();
+    let accounts: HashSet<_> = executed
+        .iter()
+        .map(|(_, deploy_header, _)| deploy_header.account().to_account_hash())
+        .chain(
+            transfers
+                .iter()
+                .flat_map(|transfer| iter::once(transfer.from).chain(transfer.to)),
+        )
+        .chain(maybe_step_effect.iter().flat_map(unbonding_accounts))
+        .collect();
+    let mut main_purses = HashMap::new();
+    for account_hash in accounts {
+        if let Some(account) = effect_builder
+            .get_account_from_global_state(
+                *block.header().state_root_hash(),
+                Key::Account(account_hash),
+            )
+            .await
+        {
+            main_purses.insert(account.main_purse().addr(), account_hash);
+        }
+    }
+
+    // The deploys which failed, to report the transfers they intended.
+    let failed = executed
+        .iter()
+        .filter(|(_, _, execution_result)| {
+            matches!(execution_result, ExecutionResult::Failure { .. })
+        })
+        .map(|(deploy_hash, _, _)| *deploy_hash)
+        .collect();
+    let failed_deploys: HashMap<_, _> = effect_builder
+        .get_deploys_from_storage(failed)
+        .await
+        .into_iter()
+        .flatten()
+        .map(|deploy| {
+            let deploy = deploy.into_naive();
+            (*deploy.hash(), deploy)
+        })
+        .collect();
+
+    let mut transactions: Vec<_> = executed
+        .into_iter()
+        .map(|(deploy_hash, _, execution_result)| {
+            let changes = balance_changes(execution_effect(&execution_result), &mut balances);
+            let mut operations = balance_change_operations(&changes, &main_purses);
+            let metadata = match &execution_result {
+                ExecutionResult::Failure { error_message, .. } => {
+                    if let Some(transfer) =
+                        failed_deploys.get(&deploy_hash).and_then(native_transfer)
+                    {
+                        let first_index = operations.len() as u64;
+                        operations.extend(transfer_operations(
+                            &transfer,
+                            first_index,
+                            Some(FAILURE_OPERATION_STATUS),
+                        ));
+                    }
+                    Some(serde_json::json!({ "error_message": error_message }))
+                }
+                ExecutionResult::Success { .. } => None,
+            };
+            Transaction {
+                transaction_identifier: transaction_identifier(&deploy_hash),
+                operations,
+                metadata,
+            }
+        })
+        .collect();
+    if let Some(step_effect) = &maybe_step_effect {
+        transactions.push(era_end_transaction(
+            block.hash(),
+            step_effect,
+            &mut balances,
+            &main_purses,
+        ));
+    }
+
+    let block_identifier_of_block = block_identifier(block.hash(), block.height());
+    // By Rosetta convention, the genesis block is its own parent.
+    let parent_block_identifier = match block.height().checked_sub(1) {
+        Some(parent_height) => block_identifier(block.header().parent_hash(), parent_height),
+        None => block_identifier_of_block.clone(),
+    };
+
+    Ok(BlockResponse {
+        block: types::Block {
+            block_identifier: block_identifier_of_block,
+            parent_block_identifier,
+            timestamp: block.timestamp().millis(),
+            transactions,
+        },
+    })
+}
+
+/// Parses an account address, given as a formatted account hash, a hex-encoded public key or a
+/// formatted purse URef.
+fn parse_address(address: &str) -> Result<Result<AccountHash, URef>, Error> {
+    if let Ok(account_hash) = AccountHash::from_formatted_str(address) {
+        return Ok(Ok(account_hash));
+    }
+    if let Ok(purse) = URef::from_formatted_str(address) {
+        return Ok(Err(purse));
+    }
+    PublicKey::from_hex(address)
+        .map(|public_key| Ok(public_key.to_account_hash()))
+        .map_err(|_| ErrorKind::InvalidAccountIdentifier.with_details(address))
+}
+
+async fn account_balance<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: AccountBalanceRequest,
+) -> Result<AccountBalanceResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    let block = get_block(effect_builder, &request.block_identifier).await?;
+    let state_root_hash = *block.header().state_root_hash();
+
+    let purse = match parse_address(&request.account_identifier.address)? {
+        Ok(account_hash) => effect_builder
+            .get_account_from_global_state(state_root_hash, Key::Account(account_hash))
+            .await
+            .ok_or_else(|| ErrorKind::AccountNotFound.with_details(account_hash))?
+            .main_purse(),
+        Err(purse) => purse,
+    };
+    let balance: U512 = effect_builder
+        .check_purse_balance(state_root_hash, purse)
+        .await
+        .ok_or_else(|| ErrorKind::BalanceUnavailable.with_details(purse))?;
+
+    Ok(AccountBalanceResponse {
+        block_identifier: block_identifier(block.hash(), block.height()),
+        balances: vec![amount(balance.to_string())],
+    })
+}
+
+async fn mempool<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: NetworkRequest,
+) -> Result<MempoolResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    Ok(MempoolResponse {
+        transaction_identifiers: effect_builder
+            .get_pending_deploy_hashes()
+            .await
+            .iter()
+            .map(transaction_identifier)
+            .collect(),
+    })
+}
+
+async fn mempool_transaction<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: MempoolTransactionRequest,
+) -> Result<MempoolTransactionResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    let deploy_hash = Digest::from_hex(&request.transaction_identifier.hash)
+        .map(DeployHash::new)
+        .map_err(|error| ErrorKind::TransactionNotFound.with_details(error))?;
+    if !effect_builder
+        .get_pending_deploy_hashes()
+        .await
+        .contains(&deploy_hash)
+    {
+        return Err(ErrorKind::TransactionNotFound.error());
+    }
+    let deploy = effect_builder
+        .get_deploys_from_storage(vec![deploy_hash])
+        .await
+        .into_iter()
+        .flatten()
+        .next()
+        .ok_or_else(|| ErrorKind::TransactionNotFound.error())?
+        .into_naive();
+
+    let operations = native_transfer(&deploy)
+        .map(|transfer| transfer_operations(&transfer, 0, None).to_vec())
+        .unwrap_or_default();
+    Ok(MempoolTransactionResponse {
+        transaction: Transaction {
+            transaction_identifier: transaction_identifier(&deploy_hash),
+            operations,
+            metadata: None,
+        },
+    })
+}
+
+fn parse_public_key(public_key: &types::PublicKey) -> Result<PublicKey, Error> {
+    let bytes = base16::decode(&public_key.hex_bytes)
+        .map_err(|error| ErrorKind::InvalidPublicKey.with_details(error))?;
+    match public_key.curve_type.as_str() {
+        CURVE_EDWARDS25519 => PublicKey::ed25519_from_bytes(bytes),
+        CURVE_SECP256K1 => PublicKey::secp256k1_from_bytes(bytes),
+        curve_type => {
+            return Err(ErrorKind::InvalidPublicKey
+                .with_details(format!("unsupported curve type {}", curve_type)))
+        }
+    }
+    .map_err(|error| ErrorKind::InvalidPublicKey.with_details(error))
+}
+
+fn parse_signature(signature: &types::Signature) -> Result<Signature, Error> {
+    if signature.signature_type != SIGNATURE_TYPE_ED25519 {
+        return Err(ErrorKind::InvalidSignature.with_details(format!(
+            "unsupported signature type {}",
+            signature.signature_type
+        )));
+    }
+    let bytes = base16::decode(&signature.hex_bytes)
+        .map_err(|error| ErrorKind::InvalidSignature.with_details(error))?;
+    Signature::ed25519_from_bytes(bytes)
+        .map_err(|error| ErrorKind::InvalidSignature.with_details(error))
+}
+
+/// Parses a JSON encoded deploy, with or without approvals.
+fn parse_deploy(transaction: &str) -> Result<Deploy, Error> {
+    let deploy: Deploy = serde_json::from_str(transaction)
+        .map_err(|error| ErrorKind::InvalidTransaction.with_details(error))?;
+    deploy
+        .has_valid_hash()
+        .map_err(|error| ErrorKind::InvalidTransaction.with_details(error))?;
+    Ok(deploy)
+}
+
+fn encode_deploy(deploy: &Deploy) -> Result<String, Error> {
+    serde_json::to_string(deploy).map_err(|error| ErrorKind::InvalidTransaction.with_details(error))
+}
+
+fn construction_derive(
+    network: &NetworkIdentifier,
+    request: ConstructionDeriveRequest,
+) -> Result<ConstructionDeriveResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let public_key = parse_public_key(&request.public_key)?;
+    Ok(ConstructionDeriveResponse {
+        account_identifier: AccountIdentifier {
+            address: public_key.to_account_hash().to_formatted_string(),
+        },
+    })
+}
+
+fn construction_preprocess(
+    network: &NetworkIdentifier,
+    request: ConstructionPreprocessRequest,
+) -> Result<ConstructionPreprocessResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let transfer = parse_transfer_operations(&request.operations, request.metadata.transfer_id)?;
+    Ok(ConstructionPreprocessResponse {
+        options: ConstructionOptions {
+            transfer_id: transfer.id,
+        },
+        required_public_keys: vec![AccountIdentifier {
+            address: transfer.from.to_formatted_string(),
+        }],
+    })
+}
+
+fn construction_metadata(
+    network: &NetworkIdentifier,
+    native_transfer_cost: u64,
+    request: ConstructionMetadataRequest,
+) -> Result<ConstructionMetadataResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    Ok(ConstructionMetadataResponse {
+        metadata: ConstructionMetadata {
+            chain_name: network.network.clone(),
+            payment_amount: native_transfer_cost.to_string(),
+            transfer_id: request.options.transfer_id,
+        },
+        suggested_fee: vec![amount(native_transfer_cost.to_string())],
+    })
+}
+
+fn construction_payloads(
+    network: &NetworkIdentifier,
+    request: ConstructionPayloadsRequest,
+) -> Result<ConstructionPayloadsResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let transfer = parse_transfer_operations(&request.operations, request.metadata.transfer_id)?;
+    let payment_amount = U512::from_dec_str(&request.metadata.payment_amount).map_err(|_| {
+        ErrorKind::InvalidOperations.with_details("invalid payment amount in metadata")
+    })?;
+    let mut account = None;
+    for public_key in &request.public_keys {
+        let public_key = parse_public_key(public_key)?;
+        if public_key.to_account_hash() == transfer.from {
+            account = Some(public_key);
+        }
+    }
+    let account = match account {
+        Some(account @ PublicKey::Ed25519(_)) => account,
+        Some(_) => {
+            return Err(ErrorKind::InvalidPublicKey
+                .with_details("only Ed25519 keys can sign deploys constructed via Rosetta"))
+        }
+        None => {
+            return Err(ErrorKind::InvalidPublicKey
+                .with_details("the public key of the sending account is missing"))
+        }
+    };
+
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! {
+            standard_payment::ARG_AMOUNT => payment_amount,
+        },
+    };
+    let args = match transfer.to {
+        Ok(account_hash) => runtime_args! {
+            mint::ARG_AMOUNT => transfer.amount,
+            mint::ARG_TARGET => account_hash,
+            mint::ARG_ID => transfer.id,
+        },
+        Err(purse) => runtime_args! {
+            mint::ARG_AMOUNT => transfer.amount,
+            mint::ARG_TARGET => purse,
+            mint::ARG_ID => transfer.id,
+        },
+    };
+    let deploy = Deploy::new_unsigned(
+        Timestamp::now(),
+        DEPLOY_TTL,
+        DEPLOY_GAS_PRICE,
+        vec![],
+        request.metadata.chain_name,
+        payment,
+        ExecutableDeployItem::Transfer { args },
+        account,
+    );
+
+    Ok(ConstructionPayloadsResponse {
+        unsigned_transaction: encode_deploy(&deploy)?,
+        payloads: vec![SigningPayload {
+            hex_bytes: base16::encode_lower(deploy.hash().inner()),
+            account_identifier: AccountIdentifier {
+                address: transfer.from.to_formatted_string(),
+            },
+            signature_type: SIGNATURE_TYPE_ED25519.to_string(),
+        }],
+    })
+}
+
+fn construction_combine(
+    network: &NetworkIdentifier,
+    request: ConstructionCombineRequest,
+) -> Result<ConstructionCombineResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let mut deploy = parse_deploy(&request.unsigned_transaction)?;
+    let mut approvals = deploy.approvals().clone();
+    for signature in &request.signatures {
+        let signer = parse_public_key(&signature.public_key)?;
+        let signature = parse_signature(signature)?;
+        crypto::verify(deploy.hash(), &signature, &signer)
+            .map_err(|error| ErrorKind::InvalidSignature.with_details(error))?;
+        approvals.insert(Approval::from_parts(signer, signature));
+    }
+    deploy.replace_approvals(approvals);
+
+    Ok(ConstructionCombineResponse {
+        signed_transaction: encode_deploy(&deploy)?,
+    })
+}
+
+fn construction_parse(
+    network: &NetworkIdentifier,
+    request: ConstructionParseRequest,
+) -> Result<ConstructionParseResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let deploy = parse_deploy(&request.transaction)?;
+    let transfer = native_transfer(&deploy).ok_or_else(|| {
+        ErrorKind::InvalidTransaction.with_details("only native transfers can be parsed")
+    })?;
+    let account_identifier_signers = if request.signed {
+        deploy
+            .approvals()
+            .iter()
+            .map(|approval| AccountIdentifier {
+                address: approval.signer().to_account_hash().to_formatted_string(),
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok(ConstructionParseResponse {
+        operations: transfer_operations(&transfer, 0, None).to_vec(),
+        account_identifier_signers,
+    })
+}
+
+fn construction_hash(
+    network: &NetworkIdentifier,
+    request: ConstructionRequest,
+) -> Result<TransactionIdentifierResponse, Error> {
+    check_network(network, &request.network_identifier)?;
+
+    let deploy = parse_deploy(&request.signed_transaction)?;
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: transaction_identifier(deploy.hash()),
+    })
+}
+
+async fn construction_submit<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    network: Arc<NetworkIdentifier>,
+    request: ConstructionRequest,
+) -> Result<TransactionIdentifierResponse, Error> {
+    check_network(&network, &request.network_identifier)?;
+
+    let deploy = parse_deploy(&request.signed_transaction)?;
+    let deploy_hash = *deploy.hash();
+    let acceptance = effect_builder
+        .try_accept_deploy(Arc::new(deploy), None)
+        .await
+        .map_err(|error| {
+            debug!(%deploy_hash, %error, "the deploy submitted via Rosetta was invalid");
+            ErrorKind::TransactionRejected.with_details(error)
+        })?;
+    // A deploy held for further approvals has been accepted, and is proposed once they are added
+    // under the same deploy hash.
+    if acceptance == DeployAcceptance::AwaitingApprovals {
+        debug!(%deploy_hash, "the deploy submitted via Rosetta is awaiting approvals");
+    }
+
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: transaction_identifier(&deploy_hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use casper_types::{testing::TestRng, CLValue, SecretKey, TransformEntry};
+
+    use super::*;
+
+    fn network() -> NetworkIdentifier {
+        NetworkIdentifier {
+            blockchain: BLOCKCHAIN.to_string(),
+            network: "casper-test".to_string(),
+        }
+    }
+
+    fn rosetta_public_key(public_key: &PublicKey) -> types::PublicKey {
+        types::PublicKey {
+            hex_bytes: base16::encode_lower(&Vec::<u8>::from(public_key)),
+            curve_type: CURVE_EDWARDS25519.to_string(),
+        }
+    }
+
+    fn balance_entry(purse_addr: URefAddr, transform: Transform) -> TransformEntry {
+        TransformEntry {
+            key: Key::Balance(purse_addr).to_formatted_string(),
+            transform,
+        }
+    }
+
+    #[test]
+    fn should_derive_balance_changes_from_writes_and_adds() {
+        let mut rng = TestRng::new();
+        let source: URefAddr = rng.gen();
+        let target: URefAddr = rng.gen();
+        let new_purse: URefAddr = rng.gen();
+        let effect = ExecutionEffect::new(vec![
+            balance_entry(
+                source,
+                Transform::WriteCLValue(CLValue::from_t(U512::from(700)).unwrap()),
+            ),
+            balance_entry(target, Transform::AddUInt512(U512::from(300))),
+            balance_entry(
+                new_purse,
+                Transform::WriteCLValue(CLValue::from_t(U512::from(50)).unwrap()),
+            ),
+            TransformEntry {
+                key: Key::Account(rng.gen()).to_formatted_string(),
+                transform: Transform::AddUInt512(U512::from(1)),
+            },
+        ]);
+        assert!(!is_compact(&effect));
+        let mut written: Vec<_> = written_purses(&effect).collect();
+        written.sort_unstable();
+        let mut expected_written = vec![source, new_purse];
+        expected_written.sort_unstable();
+        assert_eq!(written, expected_written);
+
+        // The new purse has no balance before the effect.
+        let mut balances: HashMap<_, _> = iter::once((source, U512::from(1_000))).collect();
+        let changes = balance_changes(&effect, &mut balances);
+        assert_eq!(
+            changes,
+            vec![
+                (source, "-300".to_string()),
+                (target, "300".to_string()),
+                (new_purse, "50".to_string()),
+            ]
+        );
+        assert_eq!(balances[&source], U512::from(700));
+        assert_eq!(balances[&new_purse], U512::from(50));
+
+        // A compact effect holds no amounts to derive the changes from.
+        assert!(is_compact(&effect.compact()));
+    }
+
+    #[test]
+    fn should_report_main_purses_against_their_accounts() {
+        let mut rng = TestRng::new();
+        let main_purse: URefAddr = rng.gen();
+        let other_purse: URefAddr = rng.gen();
+        let account_hash: AccountHash = rng.gen();
+        let main_purses: HashMap<_, _> = iter::once((main_purse, account_hash)).collect();
+
+        let operations = balance_change_operations(
+            &[
+                (main_purse, "-5".to_string()),
+                (other_purse, "5".to_string()),
+            ],
+            &main_purses,
+        );
+        assert_eq!(operations[0].operation_identifier.index, 0);
+        assert_eq!(operations[1].operation_identifier.index, 1);
+        assert_eq!(
+            operations[0].account.address,
+            account_hash.to_formatted_string()
+        );
+        assert_eq!(
+            parse_address(&operations[1].account.address).unwrap(),
+            Err(URef::new(other_purse, AccessRights::READ_ADD_WRITE))
+        );
+        assert!(operations
+            .iter()
+            .all(|operation| operation.status.as_deref() == Some(SUCCESS_OPERATION_STATUS)));
+    }
+
+    #[test]
+    fn should_report_era_end_balance_changes_of_switch_block() {
+        let mut rng = TestRng::new();
+        let block_hash = BlockHash::random(&mut rng);
+        let unbonder: AccountHash = rng.gen();
+        let unbonder_main_purse: URefAddr = rng.gen();
+        let bonding_purse: URefAddr = rng.gen();
+
+        // A deploy of the switch block debits the unbonder's main purse, which the step then pays
+        // out the unbonded amount to, while a validator is rewarded.
+        let deploy_effect = ExecutionEffect::new(vec![balance_entry(
+            unbonder_main_purse,
+            Transform::WriteCLValue(CLValue::from_t(U512::from(900)).unwrap()),
+        )]);
+        let step_effect = ExecutionEffect::new(vec![
+            TransformEntry {
+                key: Key::Withdraw(unbonder).to_formatted_string(),
+                transform: Transform::WriteWithdraw(vec![]),
+            },
+            balance_entry(
+                unbonder_main_purse,
+                Transform::WriteCLValue(CLValue::from_t(U512::from(1_400)).unwrap()),
+            ),
+            balance_entry(bonding_purse, Transform::AddUInt512(U512::from(20))),
+        ]);
+        assert_eq!(
+            unbonding_accounts(&step_effect).collect::<Vec<_>>(),
+            vec![unbonder]
+        );
+
+        let mut balances: HashMap<_, _> =
+            iter::once((unbonder_main_purse, U512::from(1_000))).collect();
+        let deploy_changes = balance_changes(&deploy_effect, &mut balances);
+        assert_eq!(
+            deploy_changes,
+            vec![(unbonder_main_purse, "-100".to_string())]
+        );
+
+        // The era end changes follow on from the balances left by the deploys.
+        let main_purses: HashMap<_, _> = iter::once((unbonder_main_purse, unbonder)).collect();
+        let transaction =
+            era_end_transaction(&block_hash, &step_effect, &mut balances, &main_purses);
+        assert_eq!(
+            transaction.transaction_identifier.hash,
+            base16::encode_lower(block_hash.inner())
+        );
+        let operations: Vec<_> = transaction
+            .operations
+            .iter()
+            .map(|operation| {
+                (
+                    operation.account.address.clone(),
+                    operation.amount.value.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                (unbonder.to_formatted_string(), "500".to_string()),
+                (
+                    URef::new(bonding_purse, AccessRights::READ_ADD_WRITE).to_formatted_string(),
+                    "20".to_string()
+                ),
+            ]
+        );
+        assert_eq!(balances[&unbonder_main_purse], U512::from(1_400));
+    }
+
+    #[test]
+    fn transfer_operations_should_balance() {
+        let mut rng = TestRng::new();
+        let mut transfer = NativeTransfer {
+            from: rng.gen(),
+            to: Ok(rng.gen()),
+            amount: U512::from(1_000),
+            id: None,
+        };
+
+        let [debit, credit] = transfer_operations(&transfer, 4, Some(FAILURE_OPERATION_STATUS));
+        assert_eq!(debit.operation_identifier.index, 4);
+        assert_eq!(credit.operation_identifier.index, 5);
+        assert_eq!(debit.account.address, transfer.from.to_formatted_string());
+        assert_eq!(
+            credit.account.address,
+            transfer.to.unwrap().to_formatted_string()
+        );
+        assert_eq!(debit.amount.value, "-1000");
+        assert_eq!(credit.amount.value, "1000");
+        assert_eq!(debit.status.as_deref(), Some(FAILURE_OPERATION_STATUS));
+
+        // Transfers to a purse credit the purse itself.
+        let purse = URef::new(rng.gen(), AccessRights::READ_ADD_WRITE);
+        transfer.to = Err(purse);
+        let [_, credit] = transfer_operations(&transfer, 0, None);
+        assert_eq!(credit.account.address, purse.to_formatted_string());
+        assert_eq!(credit.status, None);
+    }
+
+    #[test]
+    fn should_reject_unbalanced_transfer_operations() {
+        let mut rng = TestRng::new();
+        let transfer = NativeTransfer {
+            from: rng.gen(),
+            to: Ok(rng.gen()),
+            amount: U512::from(1_000),
+            id: Some(7),
+        };
+        let operations = transfer_operations(&transfer, 0, None);
+        assert_eq!(
+            parse_transfer_operations(&operations, Some(7)).unwrap(),
+            transfer
+        );
+
+        let mut unbalanced = operations.clone();
+        unbalanced[1].amount.value = "999".to_string();
+        assert_eq!(
+            parse_transfer_operations(&unbalanced, None)
+                .unwrap_err()
+                .code,
+            ErrorKind::InvalidOperations as u32
+        );
+        assert_eq!(
+            parse_transfer_operations(&operations[..1], None)
+                .unwrap_err()
+                .code,
+            ErrorKind::InvalidOperations as u32
+        );
+    }
+
+    #[test]
+    fn should_construct_and_parse_signed_native_transfer() {
+        let mut rng = TestRng::new();
+        let network = network();
+        let secret_key = SecretKey::random_ed25519(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let transfer = NativeTransfer {
+            from: public_key.to_account_hash(),
+            to: Ok(rng.gen()),
+            amount: U512::from(2_500_000_000u64),
+            id: Some(42),
+        };
+        let operations = transfer_operations(&transfer, 0, None).to_vec();
+
+        let derived = construction_derive(
+            &network,
+            ConstructionDeriveRequest {
+                network_identifier: network.clone(),
+                public_key: rosetta_public_key(&public_key),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            derived.account_identifier.address,
+            transfer.from.to_formatted_string()
+        );
+
+        let preprocessed = construction_preprocess(
+            &network,
+            ConstructionPreprocessRequest {
+                network_identifier: network.clone(),
+                operations: operations.clone(),
+                metadata: ConstructionOptions {
+                    transfer_id: transfer.id,
+                },
+            },
+        )
+        .unwrap();
+        let metadata = construction_metadata(
+            &network,
+            100_000_000,
+            ConstructionMetadataRequest {
+                network_identifier: network.clone(),
+                options: preprocessed.options,
+            },
+        )
+        .unwrap()
+        .metadata;
+        let payloads = construction_payloads(
+            &network,
+            ConstructionPayloadsRequest {
+                network_identifier: network.clone(),
+                operations: operations.clone(),
+                metadata,
+                public_keys: vec![rosetta_public_key(&public_key)],
+            },
+        )
+        .unwrap();
+
+        // The unsigned deploy parses to the same operations, without signers.
+        let parsed = construction_parse(
+            &network,
+            ConstructionParseRequest {
+                network_identifier: network.clone(),
+                signed: false,
+                transaction: payloads.unsigned_transaction.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(parsed.operations, operations);
+        assert!(parsed.account_identifier_signers.is_empty());
+
+        let payload = payloads.payloads[0].clone();
+        let message = base16::decode(&payload.hex_bytes).unwrap();
+        let signature = crypto::sign(&message, &secret_key, &public_key);
+        let combined = construction_combine(
+            &network,
+            ConstructionCombineRequest {
+                network_identifier: network.clone(),
+                unsigned_transaction: payloads.unsigned_transaction,
+                signatures: vec![types::Signature {
+                    signing_payload: payload,
+                    public_key: rosetta_public_key(&public_key),
+                    signature_type: SIGNATURE_TYPE_ED25519.to_string(),
+                    hex_bytes: base16::encode_lower(&Vec::<u8>::from(&signature)),
+                }],
+            },
+        )
+        .unwrap();
+
+        let deploy = parse_deploy(&combined.signed_transaction).unwrap();
+        assert!(deploy.is_valid().is_ok());
+        assert_eq!(native_transfer(&deploy), Some(transfer.clone()));
+        let parsed = construction_parse(
+            &network,
+            ConstructionParseRequest {
+                network_identifier: network.clone(),
+                signed: true,
+                transaction: combined.signed_transaction,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.account_identifier_signers,
+            vec![AccountIdentifier {
+                address: transfer.from.to_formatted_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_addresses() {
+        let mut rng = TestRng::new();
+        let public_key = PublicKey::random(&mut rng);
+        let account_hash = public_key.to_account_hash();
+        let purse = URef::new(rng.gen(), AccessRights::READ_ADD_WRITE);
+
+        assert_eq!(
+            parse_address(&account_hash.to_formatted_string()).unwrap(),
+            Ok(account_hash)
+        );
+        assert_eq!(
+            parse_address(&public_key.to_hex()).unwrap(),
+            Ok(account_hash)
+        );
+        assert_eq!(
+            parse_address(&purse.to_formatted_string()).unwrap(),
+            Err(purse)
+        );
+        assert_eq!(
+            parse_address("not an address").unwrap_err().code,
+            ErrorKind::InvalidAccountIdentifier as u32
+        );
+    }
+}
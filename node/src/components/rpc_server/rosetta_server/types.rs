@@ -0,0 +1,365 @@
+//! Request and response models of the Rosetta API, as defined at
+//! <https://www.rosetta-api.org/docs/api_objects.html>.
+//!
+//! Only the fields used by this node are modelled; unknown fields in requests (e.g. `metadata`)
+//! are ignored.
+
+use serde::{Deserialize, Serialize};
+
+/// Identifies the blockchain and network a request is targeting.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct NetworkIdentifier {
+    pub(super) blockchain: String,
+    pub(super) network: String,
+}
+
+/// Uniquely identifies a block.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct BlockIdentifier {
+    pub(super) index: u64,
+    pub(super) hash: String,
+}
+
+/// Identifies a block by its height, its hash, or if both are absent, the highest block.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub(super) struct PartialBlockIdentifier {
+    #[serde(default)]
+    pub(super) index: Option<u64>,
+    #[serde(default)]
+    pub(super) hash: Option<String>,
+}
+
+/// Uniquely identifies a transaction, i.e. a deploy.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct TransactionIdentifier {
+    pub(super) hash: String,
+}
+
+/// Uniquely identifies an operation within a transaction.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct OperationIdentifier {
+    pub(super) index: u64,
+}
+
+/// Uniquely identifies an account, either by its account hash or by a purse's URef.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct AccountIdentifier {
+    pub(super) address: String,
+}
+
+/// The currency of an amount.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Currency {
+    pub(super) symbol: String,
+    pub(super) decimals: u32,
+}
+
+/// An amount in the smallest unit of a currency, as a signed decimal string.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Amount {
+    pub(super) value: String,
+    pub(super) currency: Currency,
+}
+
+/// A balance change of a single account.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Operation {
+    pub(super) operation_identifier: OperationIdentifier,
+    #[serde(rename = "type")]
+    pub(super) operation_type: String,
+    /// The outcome of the operation; absent in operations which have not been executed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) status: Option<String>,
+    pub(super) account: AccountIdentifier,
+    pub(super) amount: Amount,
+}
+
+/// The operations of a single deploy.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Transaction {
+    pub(super) transaction_identifier: TransactionIdentifier,
+    pub(super) operations: Vec<Operation>,
+    /// Holds the error message of a failed deploy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) metadata: Option<serde_json::Value>,
+}
+
+/// A block and its transactions.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Block {
+    pub(super) block_identifier: BlockIdentifier,
+    pub(super) parent_block_identifier: BlockIdentifier,
+    /// Milliseconds since the Unix epoch.
+    pub(super) timestamp: u64,
+    pub(super) transactions: Vec<Transaction>,
+}
+
+/// A connected peer.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Peer {
+    pub(super) peer_id: String,
+}
+
+/// The versions of the Rosetta API and of the node.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Version {
+    pub(super) rosetta_version: String,
+    pub(super) node_version: String,
+}
+
+/// A status an operation can have, and whether it affects balances.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct OperationStatus {
+    pub(super) status: String,
+    pub(super) successful: bool,
+}
+
+/// What the implementation supports.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Allow {
+    pub(super) operation_statuses: Vec<OperationStatus>,
+    pub(super) operation_types: Vec<String>,
+    pub(super) errors: Vec<Error>,
+    pub(super) historical_balance_lookup: bool,
+}
+
+/// An error returned by any endpoint, with a code unique to its kind.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Error {
+    pub(super) code: u32,
+    pub(super) message: String,
+    pub(super) retriable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) details: Option<serde_json::Value>,
+}
+
+/// Request of "/network/list".
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub(super) struct MetadataRequest {}
+
+/// Request of "/network/status" and "/network/options".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct NetworkRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+}
+
+/// Response of "/network/list".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct NetworkListResponse {
+    pub(super) network_identifiers: Vec<NetworkIdentifier>,
+}
+
+/// Response of "/network/status".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct NetworkStatusResponse {
+    pub(super) current_block_identifier: BlockIdentifier,
+    pub(super) current_block_timestamp: u64,
+    pub(super) genesis_block_identifier: BlockIdentifier,
+    pub(super) oldest_block_identifier: BlockIdentifier,
+    pub(super) peers: Vec<Peer>,
+}
+
+/// Response of "/network/options".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct NetworkOptionsResponse {
+    pub(super) version: Version,
+    pub(super) allow: Allow,
+}
+
+/// Request of "/block".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct BlockRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) block_identifier: PartialBlockIdentifier,
+}
+
+/// Response of "/block".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct BlockResponse {
+    pub(super) block: Block,
+}
+
+/// Request of "/account/balance".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct AccountBalanceRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) account_identifier: AccountIdentifier,
+    #[serde(default)]
+    pub(super) block_identifier: PartialBlockIdentifier,
+}
+
+/// Response of "/account/balance".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct AccountBalanceResponse {
+    pub(super) block_identifier: BlockIdentifier,
+    pub(super) balances: Vec<Amount>,
+}
+
+/// Request of "/construction/hash" and "/construction/submit".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    /// The JSON encoded signed deploy.
+    pub(super) signed_transaction: String,
+}
+
+/// Response of "/construction/hash" and "/construction/submit".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct TransactionIdentifierResponse {
+    pub(super) transaction_identifier: TransactionIdentifier,
+}
+
+/// A public key, as its raw bytes without the tag byte used by Casper's hex encoding.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct PublicKey {
+    pub(super) hex_bytes: String,
+    pub(super) curve_type: String,
+}
+
+/// A payload to be signed by the given account.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct SigningPayload {
+    pub(super) hex_bytes: String,
+    pub(super) account_identifier: AccountIdentifier,
+    pub(super) signature_type: String,
+}
+
+/// A signature of a signing payload.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct Signature {
+    pub(super) signing_payload: SigningPayload,
+    pub(super) public_key: PublicKey,
+    pub(super) signature_type: String,
+    pub(super) hex_bytes: String,
+}
+
+/// The options of a native transfer, passed from "/construction/preprocess" to
+/// "/construction/metadata".
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub(super) struct ConstructionOptions {
+    /// The optional ID of the transfer, recorded in its transfer record.
+    #[serde(default)]
+    pub(super) transfer_id: Option<u64>,
+}
+
+/// The metadata needed to construct a native transfer deploy, returned by
+/// "/construction/metadata".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionMetadata {
+    pub(super) chain_name: String,
+    /// The payment amount in motes, as a decimal string.
+    pub(super) payment_amount: String,
+    #[serde(default)]
+    pub(super) transfer_id: Option<u64>,
+}
+
+/// Request of "/construction/derive".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionDeriveRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) public_key: PublicKey,
+}
+
+/// Response of "/construction/derive".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionDeriveResponse {
+    pub(super) account_identifier: AccountIdentifier,
+}
+
+/// Request of "/construction/preprocess".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionPreprocessRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) operations: Vec<Operation>,
+    #[serde(default)]
+    pub(super) metadata: ConstructionOptions,
+}
+
+/// Response of "/construction/preprocess".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionPreprocessResponse {
+    pub(super) options: ConstructionOptions,
+    pub(super) required_public_keys: Vec<AccountIdentifier>,
+}
+
+/// Request of "/construction/metadata".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionMetadataRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    #[serde(default)]
+    pub(super) options: ConstructionOptions,
+}
+
+/// Response of "/construction/metadata".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionMetadataResponse {
+    pub(super) metadata: ConstructionMetadata,
+    pub(super) suggested_fee: Vec<Amount>,
+}
+
+/// Request of "/construction/payloads".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionPayloadsRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) operations: Vec<Operation>,
+    pub(super) metadata: ConstructionMetadata,
+    pub(super) public_keys: Vec<PublicKey>,
+}
+
+/// Response of "/construction/payloads".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionPayloadsResponse {
+    /// The JSON encoded deploy without approvals.
+    pub(super) unsigned_transaction: String,
+    pub(super) payloads: Vec<SigningPayload>,
+}
+
+/// Request of "/construction/combine".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionCombineRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) unsigned_transaction: String,
+    pub(super) signatures: Vec<Signature>,
+}
+
+/// Response of "/construction/combine".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionCombineResponse {
+    pub(super) signed_transaction: String,
+}
+
+/// Request of "/construction/parse".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionParseRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) signed: bool,
+    /// The JSON encoded deploy, with or without approvals.
+    pub(super) transaction: String,
+}
+
+/// Response of "/construction/parse".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct ConstructionParseResponse {
+    pub(super) operations: Vec<Operation>,
+    #[serde(default)]
+    pub(super) account_identifier_signers: Vec<AccountIdentifier>,
+}
+
+/// Response of "/mempool".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct MempoolResponse {
+    pub(super) transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+/// Request of "/mempool/transaction".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct MempoolTransactionRequest {
+    pub(super) network_identifier: NetworkIdentifier,
+    pub(super) transaction_identifier: TransactionIdentifier,
+}
+
+/// Response of "/mempool/transaction".
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(super) struct MempoolTransactionResponse {
+    pub(super) transaction: Transaction,
+}
@@ -14,23 +14,27 @@ pub mod state;
 use std::{str, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use http::header::ACCEPT_ENCODING;
-use hyper::server::{conn::AddrIncoming, Builder};
+use http::header::{ACCEPT_ENCODING, CONTENT_TYPE};
+use hyper::server::Server;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::oneshot;
-use tower::ServiceBuilder;
+use tower::{Service, ServiceBuilder};
 use tracing::info;
-use warp::Filter;
+use warp::{cors, Filter};
 
-use casper_json_rpc::{
-    CorsOrigin, Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode,
-};
+use casper_json_rpc::{Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode};
 use casper_types::ProtocolVersion;
 
-use super::{ReactorEventT, RpcRequest};
-use crate::effect::EffectBuilder;
+use super::{
+    rate_limiter::{self, RateLimiter},
+    ReactorEventT, RpcRequest,
+};
+use crate::{
+    effect::EffectBuilder,
+    utils::{HttpIncoming, HttpStream},
+};
 pub use common::ErrorData;
 use docs::DocExample;
 pub use error_code::ErrorCode;
@@ -41,6 +45,18 @@ pub use error_code::ErrorCode;
 /// It will be changed to `false` for casper-node v2.0.0.
 const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
 
+/// Returns an error if handling a request for `method` would exceed its configured rate limit, or
+/// the per-client limit if the request is part of a batch.
+fn check_rate_limit(rate_limiter: &RateLimiter, method: &'static str) -> Result<(), Error> {
+    if rate_limiter.check_call(method) {
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorCode::RateLimited,
+        format!("rate limit exceeded for '{}'", method),
+    ))
+}
+
 /// A JSON-RPC requiring the "params" field to be present.
 #[async_trait]
 pub(super) trait RpcWithParams {
@@ -88,11 +104,16 @@ pub(super) trait RpcWithParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        rate_limiter: Arc<RateLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let handler = move |maybe_params| {
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                check_rate_limit(&rate_limiter, Self::METHOD)?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -147,11 +168,16 @@ pub(super) trait RpcWithoutParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        rate_limiter: Arc<RateLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let handler = move |maybe_params| {
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                check_rate_limit(&rate_limiter, Self::METHOD)?;
+                Self::check_no_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -227,11 +253,16 @@ pub(super) trait RpcWithOptionalParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        rate_limiter: Arc<RateLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let handler = move |maybe_params| {
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                check_rate_limit(&rate_limiter, Self::METHOD)?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -255,23 +286,33 @@ pub(super) trait RpcWithOptionalParams {
 }
 
 /// Start JSON RPC server with CORS enabled in a background.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run_with_cors(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     handlers: RequestHandlers,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
     api_path: &'static str,
     server_name: &'static str,
-    cors_header: CorsOrigin,
+    cors: cors::Builder,
+    shutdown_receiver: oneshot::Receiver<()>,
 ) {
-    let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route_with_cors(
-            api_path,
-            max_body_bytes,
-            handlers.clone(),
-            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
-            &cors_header,
-        );
+    let make_svc = hyper::service::make_service_fn(move |connection: &HttpStream| {
+        let remote_address = connection.remote_addr();
+        let service_routes = rate_limiter::filter(Arc::clone(&rate_limiter), remote_address)
+            .and(casper_json_rpc::route(
+                api_path,
+                max_body_bytes,
+                max_batch_size,
+                handlers.clone(),
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            ))
+            .recover(rate_limiter::handle_rejection)
+            .with(cors.clone().allow_header(CONTENT_TYPE))
+            .boxed();
 
         // Supports content negotiation for gzip responses. This is an interim fix until
         // https://github.com/seanmonstar/warp/pull/513 moves forward.
@@ -280,6 +321,10 @@ pub(super) async fn run_with_cors(
             .with(warp::compression::gzip());
 
         let service = warp::service(service_routes_gzip.or(service_routes));
+        // Lets the JSON-RPC calls of each HTTP request be charged against its client's rate limit.
+        let service = hyper::service::service_fn(move |request: http::Request<hyper::Body>| {
+            rate_limiter::with_client(remote_address, service.clone().call(request))
+        });
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
@@ -287,35 +332,43 @@ pub(super) async fn run_with_cors(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(make_svc);
-    info!(address = %server.local_addr(), "started {} server", server_name);
+    info!(address = %incoming, "started {} server", server_name);
+    let server = Server::builder(incoming).serve(make_svc);
 
-    let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
     let server_with_shutdown = server.with_graceful_shutdown(async {
         shutdown_receiver.await.ok();
     });
 
-    let _ = tokio::spawn(server_with_shutdown).await;
-    let _ = shutdown_sender.send(());
+    let _ = server_with_shutdown.await;
     info!("{} server shut down", server_name);
 }
 
 /// Start JSON RPC server in a background.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     handlers: RequestHandlers,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
     api_path: &'static str,
     server_name: &'static str,
+    shutdown_receiver: oneshot::Receiver<()>,
 ) {
-    let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route(
-            api_path,
-            max_body_bytes,
-            handlers.clone(),
-            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
-        );
+    let make_svc = hyper::service::make_service_fn(move |connection: &HttpStream| {
+        let remote_address = connection.remote_addr();
+        let service_routes = rate_limiter::filter(Arc::clone(&rate_limiter), remote_address)
+            .and(casper_json_rpc::route(
+                api_path,
+                max_body_bytes,
+                max_batch_size,
+                handlers.clone(),
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            ))
+            .recover(rate_limiter::handle_rejection)
+            .boxed();
 
         // Supports content negotiation for gzip responses. This is an interim fix until
         // https://github.com/seanmonstar/warp/pull/513 moves forward.
@@ -324,6 +377,10 @@ pub(super) async fn run(
             .with(warp::compression::gzip());
 
         let service = warp::service(service_routes_gzip.or(service_routes));
+        // Lets the JSON-RPC calls of each HTTP request be charged against its client's rate limit.
+        let service = hyper::service::service_fn(move |request: http::Request<hyper::Body>| {
+            rate_limiter::with_client(remote_address, service.clone().call(request))
+        });
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
@@ -331,16 +388,14 @@ pub(super) async fn run(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(make_svc);
-    info!(address = %server.local_addr(), "started {} server", server_name);
+    info!(address = %incoming, "started {} server", server_name);
+    let server = Server::builder(incoming).serve(make_svc);
 
-    let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
     let server_with_shutdown = server.with_graceful_shutdown(async {
         shutdown_receiver.await.ok();
     });
 
-    let _ = tokio::spawn(server_with_shutdown).await;
-    let _ = shutdown_sender.send(());
+    let _ = server_with_shutdown.await;
     info!("{} server shut down", server_name);
 }
 
@@ -356,6 +411,8 @@ mod tests {
     use super::*;
     use crate::types::DeployHash;
 
+    const MAX_BATCH_SIZE: usize = 10;
+
     async fn send_request(
         method: &str,
         maybe_params: Option<&str>,
@@ -392,9 +449,13 @@ mod tests {
             GetDeploy::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -404,6 +465,7 @@ mod tests {
             let params = serde_json::to_string(&GetDeployParams {
                 deploy_hash: DeployHash::default(),
                 finalized_approvals: false,
+                include_transforms: false,
             })
             .unwrap();
             let params = Some(params.as_str());
@@ -430,7 +492,7 @@ mod tests {
                 &Error::new(
                     ReservedErrorCode::InvalidParams,
                     "Failed to parse 'params' field: invalid length 0, expected struct \
-                    GetDeployParams with 2 elements"
+                    GetDeployParams with 3 elements"
                 )
             );
         }
@@ -459,9 +521,13 @@ mod tests {
             GetPeers::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -513,9 +579,13 @@ mod tests {
             GetBlock::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
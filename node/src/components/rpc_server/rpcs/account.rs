@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{str, sync::Arc};
+use std::{collections::BTreeSet, str, sync::Arc};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -18,9 +18,9 @@ use super::{
     Error, ReactorEventT, RpcWithParams,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{deploy_acceptor::DeployAcceptance, rpc_server::rpcs::ErrorCode},
     effect::EffectBuilder,
-    types::{Deploy, DeployHash},
+    types::{Approval, Deploy, DeployHash},
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -29,7 +29,19 @@ static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
 static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().hash(),
+    awaiting_approvals: false,
 });
+static PUT_DEPLOY_APPROVALS_PARAMS: Lazy<PutDeployApprovalsParams> =
+    Lazy::new(|| PutDeployApprovalsParams {
+        deploy_hash: *Deploy::doc_example().hash(),
+        approvals: Deploy::doc_example().approvals().clone(),
+    });
+static PUT_DEPLOY_APPROVALS_RESULT: Lazy<PutDeployApprovalsResult> =
+    Lazy::new(|| PutDeployApprovalsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().hash(),
+        awaiting_approvals: false,
+    });
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -54,6 +66,11 @@ pub struct PutDeployResult {
     pub api_version: ProtocolVersion,
     /// The deploy hash.
     pub deploy_hash: DeployHash,
+    /// Whether the deploy is held until further approvals meet its account's deployment
+    /// threshold, rather than stored and gossiped.  Approvals are added via the
+    /// "account_put_deploy_approvals" RPC.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub awaiting_approvals: bool,
 }
 
 impl DocExample for PutDeployResult {
@@ -83,11 +100,17 @@ impl RpcWithParams for PutDeploy {
             .await;
 
         match accept_deploy_result {
-            Ok(_) => {
-                debug!(%deploy_hash, "deploy was stored");
+            Ok(acceptance) => {
+                let awaiting_approvals = acceptance == DeployAcceptance::AwaitingApprovals;
+                if awaiting_approvals {
+                    debug!(%deploy_hash, "deploy is awaiting approvals");
+                } else {
+                    debug!(%deploy_hash, "deploy was stored");
+                }
                 let result = Self::ResponseResult {
                     api_version,
                     deploy_hash,
+                    awaiting_approvals,
                 };
                 Ok(result)
             }
@@ -97,7 +120,86 @@ impl RpcWithParams for PutDeploy {
                     %error,
                     "the deploy submitted by the client was invalid",
                 );
-                Err(Error::new(ErrorCode::InvalidDeploy, error.to_string()))
+                Err(Error::new(ErrorCode::from(&error), error.to_string()))
+            }
+        }
+    }
+}
+
+/// Params for "account_put_deploy_approvals" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutDeployApprovalsParams {
+    /// The hash of a deploy awaiting further approvals.
+    pub deploy_hash: DeployHash,
+    /// The approvals to add to the deploy.
+    pub approvals: BTreeSet<Approval>,
+}
+
+impl DocExample for PutDeployApprovalsParams {
+    fn doc_example() -> &'static Self {
+        &PUT_DEPLOY_APPROVALS_PARAMS
+    }
+}
+
+/// Result for "account_put_deploy_approvals" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutDeployApprovalsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the deploy is still held until further approvals meet its account's deployment
+    /// threshold, rather than stored and gossiped.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub awaiting_approvals: bool,
+}
+
+impl DocExample for PutDeployApprovalsResult {
+    fn doc_example() -> &'static Self {
+        &PUT_DEPLOY_APPROVALS_RESULT
+    }
+}
+
+/// "account_put_deploy_approvals" RPC
+pub struct PutDeployApprovals {}
+
+#[async_trait]
+impl RpcWithParams for PutDeployApprovals {
+    const METHOD: &'static str = "account_put_deploy_approvals";
+    type RequestParams = PutDeployApprovalsParams;
+    type ResponseResult = PutDeployApprovalsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = params.deploy_hash;
+
+        let add_approvals_result = effect_builder
+            .try_add_deploy_approvals(deploy_hash, params.approvals)
+            .await;
+
+        match add_approvals_result {
+            Ok(acceptance) => {
+                debug!(%deploy_hash, ?acceptance, "approvals were added");
+                let result = Self::ResponseResult {
+                    api_version,
+                    deploy_hash,
+                    awaiting_approvals: acceptance == DeployAcceptance::AwaitingApprovals,
+                };
+                Ok(result)
+            }
+            Err(error) => {
+                debug!(
+                    %deploy_hash,
+                    %error,
+                    "the approvals submitted by the client were not added",
+                );
+                Err(Error::new(ErrorCode::from(&error), error.to_string()))
             }
         }
     }
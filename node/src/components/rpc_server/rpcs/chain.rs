@@ -13,18 +13,22 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::core::engine_state::{self, QueryResult};
-use casper_hashing::Digest;
+use casper_hashing::{Digest, IndexedMerkleProof};
 use casper_types::{Key, ProtocolVersion, Transfer};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{common, state},
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    types::{
+        Block, BlockHash, BlockWithMetadata, Deploy, DeployHash, JsonBlock, JsonBlockHeader,
+        JsonProof,
+    },
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -46,6 +50,35 @@ static GET_BLOCK_TRANSFERS_RESULT: Lazy<GetBlockTransfersResult> =
         block_hash: Some(*Block::doc_example().hash()),
         transfers: Some(vec![Transfer::default()]),
     });
+static GET_BLOCK_HEADER_PARAMS: Lazy<GetBlockHeaderParams> = Lazy::new(|| GetBlockHeaderParams {
+    block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+});
+static GET_BLOCK_HEADER_RESULT: Lazy<GetBlockHeaderResult> = Lazy::new(|| {
+    let json_block = JsonBlock::doc_example();
+    GetBlockHeaderResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: json_block.hash,
+        header: json_block.header.clone(),
+        proofs: json_block.proofs.clone(),
+    }
+});
+static GET_DEPLOY_INCLUSION_PROOF_PARAMS: Lazy<GetDeployInclusionProofParams> =
+    Lazy::new(|| GetDeployInclusionProofParams {
+        block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+        deploy_hash: *Deploy::doc_example().hash(),
+    });
+static GET_DEPLOY_INCLUSION_PROOF_RESULT: Lazy<GetDeployInclusionProofResult> = Lazy::new(|| {
+    let block = Block::doc_example();
+    let body = block.body();
+    GetDeployInclusionProofResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *block.hash(),
+        deploy_merkle_root: body.deploy_merkle_root(),
+        proof: body
+            .deploy_inclusion_proof(Deploy::doc_example().hash())
+            .expect("doc example block should contain doc example deploy"),
+    }
+});
 static GET_STATE_ROOT_HASH_PARAMS: Lazy<GetStateRootHashParams> =
     Lazy::new(|| GetStateRootHashParams {
         block_identifier: BlockIdentifier::Height(Block::doc_example().header().height()),
@@ -185,6 +218,107 @@ impl RpcWithOptionalParams for GetBlock {
     }
 }
 
+/// Params for "chain_get_block_header" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockHeaderParams {
+    /// The block identifier.
+    pub block_identifier: BlockIdentifier,
+}
+
+impl DocExample for GetBlockHeaderParams {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_HEADER_PARAMS
+    }
+}
+
+/// Result for "chain_get_block_header" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockHeaderResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block hash.
+    pub block_hash: BlockHash,
+    /// The block header.
+    pub header: JsonBlockHeader,
+    /// The finality signatures of the block known to this node.
+    pub proofs: Vec<JsonProof>,
+}
+
+impl DocExample for GetBlockHeaderResult {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCK_HEADER_RESULT
+    }
+}
+
+/// "chain_get_block_header" RPC.
+///
+/// Unlike "chain_get_block", this also returns headers of blocks which are not complete on this
+/// node, such as the verified headers stored by a light client.
+pub struct GetBlockHeader {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetBlockHeader {
+    const METHOD: &'static str = "chain_get_block_header";
+    type OptionalRequestParams = GetBlockHeaderParams;
+    type ResponseResult = GetBlockHeaderResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        let maybe_block_hash = match maybe_params.map(|params| params.block_identifier) {
+            Some(BlockIdentifier::Hash(block_hash)) => Some(block_hash),
+            Some(BlockIdentifier::Height(height)) => {
+                match effect_builder
+                    .get_block_header_at_height_from_storage(height, false)
+                    .await
+                {
+                    Some(block_header) => Some(block_header.block_hash()),
+                    None => {
+                        return Err(Error::new(
+                            ErrorCode::NoSuchBlock,
+                            format!("block header at height {} not stored on this node", height),
+                        ))
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let block_header_with_metadata = effect_builder
+            .get_block_header_with_metadata_from_storage(maybe_block_hash)
+            .await
+            .ok_or_else(|| {
+                let message = match maybe_block_hash {
+                    Some(block_hash) => format!(
+                        "block header {:?} not stored on this node",
+                        block_hash.inner()
+                    ),
+                    None => "no block headers stored on this node".to_string(),
+                };
+                Error::new(ErrorCode::NoSuchBlock, message)
+            })?;
+
+        let block_hash = block_header_with_metadata.block_header.block_hash();
+        let proofs = block_header_with_metadata
+            .block_signatures
+            .proofs
+            .into_iter()
+            .map(JsonProof::from)
+            .collect();
+        Ok(Self::ResponseResult {
+            api_version,
+            block_hash,
+            header: JsonBlockHeader::from(block_header_with_metadata.block_header),
+            proofs,
+        })
+    }
+}
+
 /// Params for "chain_get_block_transfers" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -276,6 +410,98 @@ impl RpcWithOptionalParams for GetBlockTransfers {
     }
 }
 
+/// Params for "chain_get_deploy_inclusion_proof" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployInclusionProofParams {
+    /// The block identifier.
+    pub block_identifier: BlockIdentifier,
+    /// The hash of the deploy or transfer included in the block.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetDeployInclusionProofParams {
+    fn doc_example() -> &'static Self {
+        &GET_DEPLOY_INCLUSION_PROOF_PARAMS
+    }
+}
+
+/// Result for "chain_get_deploy_inclusion_proof" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployInclusionProofResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block hash.
+    pub block_hash: BlockHash,
+    /// The Merkle root of the block's deploy and transfer hashes, in execution order.  It is not
+    /// committed to by the block header, so it is only trustworthy once the block body has been
+    /// checked against the header's `body_hash`.
+    pub deploy_merkle_root: Digest,
+    /// The Merkle proof of the deploy against `deploy_merkle_root`.  The first element of the
+    /// proof is the deploy hash itself.
+    pub proof: IndexedMerkleProof,
+}
+
+impl DocExample for GetDeployInclusionProofResult {
+    fn doc_example() -> &'static Self {
+        &GET_DEPLOY_INCLUSION_PROOF_RESULT
+    }
+}
+
+/// "chain_get_deploy_inclusion_proof" RPC.
+///
+/// The block header's `body_hash` is the hash of the serialized block body rather than of
+/// `deploy_merkle_root`, so the proof can't be verified from a header alone: a client holding only
+/// verified headers has to fetch the block body, check it against `body_hash` and recompute the
+/// root from it.
+pub struct GetDeployInclusionProof {}
+
+#[async_trait]
+impl RpcWithParams for GetDeployInclusionProof {
+    const METHOD: &'static str = "chain_get_deploy_inclusion_proof";
+    type RequestParams = GetDeployInclusionProofParams;
+    type ResponseResult = GetDeployInclusionProofResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        // Only the block body is required, so the block need not be complete.
+        let only_from_available_block_range = false;
+
+        let block = common::get_block(
+            Some(params.block_identifier),
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+
+        let proof = block
+            .body()
+            .deploy_inclusion_proof(&params.deploy_hash)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorCode::NoSuchDeploy,
+                    format!(
+                        "{} not included in block {}",
+                        params.deploy_hash,
+                        block.hash()
+                    ),
+                )
+            })?;
+
+        Ok(Self::ResponseResult {
+            api_version,
+            block_hash: *block.hash(),
+            deploy_merkle_root: block.body().deploy_merkle_root(),
+            proof,
+        })
+    }
+}
+
 /// Params for "chain_get_state_root_hash" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -16,14 +16,17 @@ use serde_json::{json, Value};
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
+    account::{PutDeploy, PutDeployApprovals},
     chain::{
-        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+        GetBlock, GetBlockHeader, GetBlockTransfers, GetDeployInclusionProof,
+        GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+    },
+    info::{
+        GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges, GetValidatorPerformance,
     },
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetNamedKeys,
+        QueryBalance, QueryGlobalState,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -69,6 +72,9 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<PutDeployApprovals>(
+        "adds approvals to a Deploy awaiting further approvals before it can be executed",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
@@ -82,6 +88,9 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
+    schema.push_without_params::<GetValidatorPerformance>(
+        "returns the participation of validators in consensus, per era",
+    );
     schema.push_without_params::<GetChainspec>(
         "returns the raw bytes of the chainspec.toml, genesis accounts.toml, and \
         global_state.toml files",
@@ -90,6 +99,12 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_optional_params::<GetBlockTransfers>(
         "returns all transfers for a Block from the network",
     );
+    schema.push_with_optional_params::<GetBlockHeader>(
+        "returns a Block header and its finality signatures from the network",
+    );
+    schema.push_with_params::<GetDeployInclusionProof>(
+        "returns a Merkle proof of the inclusion of a deploy or transfer in a Block",
+    );
     schema.push_with_optional_params::<GetStateRootHash>(
         "returns a state root hash at a given Block",
     );
@@ -109,6 +124,10 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         "returns the era summary at either a specific block (by height or hash), or the most \
         recently added block",
     );
+    schema.push_with_params::<GetNamedKeys>(
+        "returns a page of the named keys of an Account or Contract, sorted by name and \
+        optionally filtered by a name prefix",
+    );
 
     schema
 });
@@ -556,6 +575,12 @@ mod tests {
         assert!(incorrect_optional_params.is_empty())
     }
 
+    #[test]
+    fn check_chain_get_block_header_required_fields() {
+        let incorrect_optional_params = check_optional_params_fields::<GetBlockHeader>();
+        assert!(incorrect_optional_params.is_empty())
+    }
+
     #[test]
     fn check_chain_get_state_root_hash_required_fields() {
         let incorrect_optional_params = check_optional_params_fields::<GetStateRootHash>();
@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 
 use casper_json_rpc::ErrorCodeT;
 
+use crate::{
+    components::deploy_acceptor::{DeployParameterFailure, Error as DeployAcceptorError},
+    types::DeployConfigurationFailure,
+};
+
 /// The various codes which can be returned in the JSON-RPC Response's error object.
 ///
 /// **NOTE:** These values will be changed to lie outside the restricted range as defined in the
@@ -35,6 +40,22 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The given Deploy was rejected as its chain name doesn't match the node's.
+    DeployInvalidChainName = -32013,
+    /// The given Deploy was rejected as its approvals are missing, invalid or insufficient.
+    DeployInvalidApprovals = -32014,
+    /// The given Deploy was rejected as its deploy hash or body hash is incorrect.
+    DeployInvalidHash = -32015,
+    /// The given Deploy was rejected as it, or a part of it, is too large.
+    DeployTooLarge = -32016,
+    /// The given Deploy was rejected as its timestamp is in the future or its TTL is too long.
+    DeployInvalidTimestamp = -32017,
+    /// The given Deploy was rejected as it has expired.
+    DeployExpired = -32018,
+    /// The queried stored value is neither an account nor a contract, so has no named keys.
+    NoNamedKeys = -32019,
+    /// The request was rejected as the rate limit for its method has been exceeded.
+    RateLimited = -32020,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,8 +80,180 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::DeployInvalidChainName => {
+                (error_code as i64, "Invalid Deploy: invalid chain name")
+            }
+            ErrorCode::DeployInvalidApprovals => {
+                (error_code as i64, "Invalid Deploy: invalid approvals")
+            }
+            ErrorCode::DeployInvalidHash => (error_code as i64, "Invalid Deploy: invalid hash"),
+            ErrorCode::DeployTooLarge => (error_code as i64, "Invalid Deploy: too large"),
+            ErrorCode::DeployInvalidTimestamp => (
+                error_code as i64,
+                "Invalid Deploy: invalid timestamp or TTL",
+            ),
+            ErrorCode::DeployExpired => (error_code as i64, "Invalid Deploy: expired"),
+            ErrorCode::NoNamedKeys => (error_code as i64, "No named keys"),
+            ErrorCode::RateLimited => (error_code as i64, "Rate limit exceeded"),
+        }
+    }
+}
+
+impl From<&DeployAcceptorError> for ErrorCode {
+    fn from(error: &DeployAcceptorError) -> Self {
+        match error {
+            DeployAcceptorError::InvalidDeployConfiguration(failure) => match failure {
+                DeployConfigurationFailure::InvalidChainName { .. } => {
+                    ErrorCode::DeployInvalidChainName
+                }
+                DeployConfigurationFailure::EmptyApprovals
+                | DeployConfigurationFailure::InvalidApproval { .. }
+                | DeployConfigurationFailure::ExcessiveApprovals { .. } => {
+                    ErrorCode::DeployInvalidApprovals
+                }
+                DeployConfigurationFailure::InvalidBodyHash
+                | DeployConfigurationFailure::InvalidDeployHash => ErrorCode::DeployInvalidHash,
+                DeployConfigurationFailure::ExcessiveSize(_)
+                | DeployConfigurationFailure::ExcessiveDependencies { .. }
+                | DeployConfigurationFailure::ExcessiveSessionArgsLength { .. }
+                | DeployConfigurationFailure::ExcessivePaymentArgsLength { .. }
+                | DeployConfigurationFailure::ExcessiveSessionModuleBytesLength { .. }
+                | DeployConfigurationFailure::ExcessivePaymentModuleBytesLength { .. } => {
+                    ErrorCode::DeployTooLarge
+                }
+                DeployConfigurationFailure::ExcessiveTimeToLive { .. }
+                | DeployConfigurationFailure::TimestampInFuture { .. } => {
+                    ErrorCode::DeployInvalidTimestamp
+                }
+                DeployConfigurationFailure::MissingPaymentAmount
+                | DeployConfigurationFailure::FailedToParsePaymentAmount
+                | DeployConfigurationFailure::ExceededBlockGasLimit { .. }
+                | DeployConfigurationFailure::MissingTransferAmount
+                | DeployConfigurationFailure::FailedToParseTransferAmount
+                | DeployConfigurationFailure::InsufficientTransferAmount { .. } => {
+                    ErrorCode::InvalidDeploy
+                }
+            },
+            DeployAcceptorError::InvalidDeployParameters { failure, .. } => match failure {
+                DeployParameterFailure::NonexistentAccount { .. } => ErrorCode::NoSuchAccount,
+                DeployParameterFailure::InvalidAssociatedKeys
+                | DeployParameterFailure::InsufficientDeploySignatureWeight => {
+                    ErrorCode::DeployInvalidApprovals
+                }
+                DeployParameterFailure::NonexistentContractAtHash { .. }
+                | DeployParameterFailure::NonexistentContractEntryPoint { .. }
+                | DeployParameterFailure::NonexistentContractPackageAtHash { .. }
+                | DeployParameterFailure::InvalidContractAtVersion { .. }
+                | DeployParameterFailure::InsufficientBalance { .. }
+                | DeployParameterFailure::UnknownBalance { .. }
+                | DeployParameterFailure::InvalidPaymentVariant
+                | DeployParameterFailure::MissingPaymentAmount
+                | DeployParameterFailure::FailedToParsePaymentAmount
+                | DeployParameterFailure::MissingTransferTarget
+                | DeployParameterFailure::MissingModuleBytes => ErrorCode::InvalidDeploy,
+            },
+            DeployAcceptorError::ExpiredDeploy { .. } => ErrorCode::DeployExpired,
+            DeployAcceptorError::EmptyBlockchain
+            | DeployAcceptorError::ExceededPendingDeployLimit { .. }
+            | DeployAcceptorError::ExceededAwaitingApprovalsLimit { .. }
+            | DeployAcceptorError::ExceededAccountAwaitingApprovalsLimit { .. } => {
+                ErrorCode::InvalidDeploy
+            }
+            DeployAcceptorError::NotAwaitingApprovals { .. } => ErrorCode::NoSuchDeploy,
         }
     }
 }
 
 impl ErrorCodeT for ErrorCode {}
+
+#[cfg(test)]
+mod tests {
+    use casper_hashing::Digest;
+    use casper_types::{account::AccountHash, Timestamp};
+
+    use super::*;
+    use crate::types::BlockHash;
+
+    fn invalid_parameters(failure: DeployParameterFailure) -> DeployAcceptorError {
+        DeployAcceptorError::InvalidDeployParameters {
+            state_root_hash: Digest::default(),
+            block_hash: BlockHash::new(Digest::default()),
+            block_height: 1,
+            failure,
+        }
+    }
+
+    #[test]
+    fn should_map_deploy_rejections_to_specific_codes() {
+        let invalid_chain_name = DeployAcceptorError::InvalidDeployConfiguration(
+            DeployConfigurationFailure::InvalidChainName {
+                expected: "casper".to_string(),
+                got: "other".to_string(),
+            },
+        );
+        assert_eq!(
+            ErrorCode::from(&invalid_chain_name),
+            ErrorCode::DeployInvalidChainName
+        );
+
+        let invalid_hash = DeployAcceptorError::InvalidDeployConfiguration(
+            DeployConfigurationFailure::InvalidBodyHash,
+        );
+        assert_eq!(ErrorCode::from(&invalid_hash), ErrorCode::DeployInvalidHash);
+
+        let expired = DeployAcceptorError::ExpiredDeploy {
+            deploy_expiry_timestamp: Timestamp::from(1),
+            current_node_timestamp: Timestamp::from(2),
+        };
+        assert_eq!(ErrorCode::from(&expired), ErrorCode::DeployExpired);
+
+        let insufficient_weight =
+            invalid_parameters(DeployParameterFailure::InsufficientDeploySignatureWeight);
+        assert_eq!(
+            ErrorCode::from(&insufficient_weight),
+            ErrorCode::DeployInvalidApprovals
+        );
+
+        let nonexistent_account = invalid_parameters(DeployParameterFailure::NonexistentAccount {
+            account_hash: AccountHash::new([1; 32]),
+        });
+        assert_eq!(
+            ErrorCode::from(&nonexistent_account),
+            ErrorCode::NoSuchAccount
+        );
+    }
+
+    #[test]
+    fn should_keep_generic_code_for_other_deploy_rejections() {
+        let missing_payment = DeployAcceptorError::InvalidDeployConfiguration(
+            DeployConfigurationFailure::MissingPaymentAmount,
+        );
+        assert_eq!(ErrorCode::from(&missing_payment), ErrorCode::InvalidDeploy);
+
+        let missing_module_bytes = invalid_parameters(DeployParameterFailure::MissingModuleBytes);
+        assert_eq!(
+            ErrorCode::from(&missing_module_bytes),
+            ErrorCode::InvalidDeploy
+        );
+        assert_eq!(
+            ErrorCode::from(&DeployAcceptorError::EmptyBlockchain),
+            ErrorCode::InvalidDeploy
+        );
+    }
+
+    #[test]
+    fn should_use_new_codes_for_deploy_rejections() {
+        let codes: Vec<i64> = [
+            ErrorCode::DeployInvalidChainName,
+            ErrorCode::DeployInvalidApprovals,
+            ErrorCode::DeployInvalidHash,
+            ErrorCode::DeployTooLarge,
+            ErrorCode::DeployInvalidTimestamp,
+            ErrorCode::DeployExpired,
+        ]
+        .into_iter()
+        .map(|error_code| <(i64, &str)>::from(error_code).0)
+        .collect();
+        assert_eq!(codes, (-32018..=-32013).rev().collect::<Vec<_>>());
+    }
+}
@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{collections::BTreeMap, str};
+use std::{collections::BTreeMap, mem, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -11,14 +11,14 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_types::{EraId, ExecutionResult, ExecutionResultFormat, ProtocolVersion, PublicKey};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::consensus::{ValidatorChange, ValidatorPerformance},
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
@@ -30,6 +30,7 @@ use crate::{
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
     deploy_hash: *Deploy::doc_example().hash(),
     finalized_approvals: true,
+    include_transforms: true,
 });
 static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
@@ -37,6 +38,7 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
     execution_results: vec![JsonExecutionResult {
         block_hash: *Block::doc_example().hash(),
         result: ExecutionResult::example().clone(),
+        format: ExecutionResultFormat::Full,
     }],
     block_hash_and_height: None,
 });
@@ -53,6 +55,23 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_VALIDATOR_PERFORMANCE_RESULT: Lazy<GetValidatorPerformanceResult> = Lazy::new(|| {
+    let performance = ValidatorPerformance {
+        units_seen: 120,
+        proposals: 10,
+        late_proposals: 1,
+        blocks_finalized: 9,
+        faulty: false,
+    };
+    let validators = vec![JsonValidatorPerformance::new(
+        PublicKey::doc_example().clone(),
+        performance,
+    )];
+    GetValidatorPerformanceResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        eras: vec![JsonEraPerformance::new(EraId::new(1), validators)],
+    }
+});
 static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     chainspec_bytes: ChainspecRawBytes::new(vec![42, 42].into(), None, None),
@@ -68,6 +87,12 @@ pub struct GetDeployParams {
     /// omitted, returns the deploy with the approvals that were originally received by the node.
     #[serde(default = "finalized_approvals_default")]
     pub finalized_approvals: bool,
+    /// Whether to return the execution results with their full transforms. If `true` or
+    /// omitted, returns the execution results as stored by the node. If `false`, returns compact
+    /// execution results, in which each transform is replaced by the kind of change it made to
+    /// its key.
+    #[serde(default = "include_transforms_default")]
+    pub include_transforms: bool,
 }
 
 /// The default for `GetDeployParams::finalized_approvals`.
@@ -75,6 +100,11 @@ fn finalized_approvals_default() -> bool {
     false
 }
 
+/// The default for `GetDeployParams::include_transforms`.
+fn include_transforms_default() -> bool {
+    true
+}
+
 impl DocExample for GetDeployParams {
     fn doc_example() -> &'static Self {
         &GET_DEPLOY_PARAMS
@@ -89,6 +119,9 @@ pub struct JsonExecutionResult {
     pub block_hash: BlockHash,
     /// Execution result.
     pub result: ExecutionResult,
+    /// The format of the execution result.  Omitted if the result is in full format.
+    #[serde(default, skip_serializing_if = "ExecutionResultFormat::is_full")]
+    pub format: ExecutionResultFormat,
 }
 
 /// Result for "info_get_deploy" RPC response.
@@ -153,11 +186,25 @@ impl RpcWithParams for GetDeploy {
         };
 
         let (execution_results, block_hash_and_height) = match metadata_ext {
-            DeployMetadataExt::Metadata(metadata) => (
-                metadata
-                    .execution_results
+            DeployMetadataExt::Metadata(mut metadata) => (
+                mem::take(&mut metadata.execution_results)
                     .into_iter()
-                    .map(|(block_hash, result)| JsonExecutionResult { block_hash, result })
+                    .map(|(block_hash, result)| {
+                        let format = metadata.execution_result_format(&block_hash);
+                        if params.include_transforms || !format.is_full() {
+                            JsonExecutionResult {
+                                block_hash,
+                                result,
+                                format,
+                            }
+                        } else {
+                            JsonExecutionResult {
+                                block_hash,
+                                result: result.compact(),
+                                format: ExecutionResultFormat::Compact,
+                            }
+                        }
+                    })
                     .collect(),
                 None,
             ),
@@ -348,6 +395,97 @@ impl RpcWithoutParams for GetValidatorChanges {
     }
 }
 
+/// A validator's participation in consensus during an era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonValidatorPerformance {
+    /// The public key of the validator.
+    public_key: PublicKey,
+    /// The validator's participation, as seen by this node.
+    performance: ValidatorPerformance,
+}
+
+impl JsonValidatorPerformance {
+    pub(crate) fn new(public_key: PublicKey, performance: ValidatorPerformance) -> Self {
+        JsonValidatorPerformance {
+            public_key,
+            performance,
+        }
+    }
+}
+
+/// The participation of all validators in a single era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonEraPerformance {
+    /// The era ID.
+    era_id: EraId,
+    /// The participation of each of the era's validators.
+    validators: Vec<JsonValidatorPerformance>,
+}
+
+impl JsonEraPerformance {
+    pub(crate) fn new(era_id: EraId, validators: Vec<JsonValidatorPerformance>) -> Self {
+        JsonEraPerformance { era_id, validators }
+    }
+}
+
+/// Result for the "info_get_validator_performance" RPC.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetValidatorPerformanceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The validators' participation in each era that is still open in consensus.
+    pub eras: Vec<JsonEraPerformance>,
+}
+
+impl GetValidatorPerformanceResult {
+    pub(crate) fn new(
+        api_version: ProtocolVersion,
+        eras: BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>,
+    ) -> Self {
+        let eras = eras
+            .into_iter()
+            .map(|(era_id, validators)| {
+                let validators = validators
+                    .into_iter()
+                    .map(|(public_key, performance)| {
+                        JsonValidatorPerformance::new(public_key, performance)
+                    })
+                    .collect();
+                JsonEraPerformance::new(era_id, validators)
+            })
+            .collect();
+        GetValidatorPerformanceResult { api_version, eras }
+    }
+}
+
+impl DocExample for GetValidatorPerformanceResult {
+    fn doc_example() -> &'static Self {
+        &GET_VALIDATOR_PERFORMANCE_RESULT
+    }
+}
+
+/// "info_get_validator_performance" RPC.
+pub struct GetValidatorPerformance {}
+
+#[async_trait]
+impl RpcWithoutParams for GetValidatorPerformance {
+    const METHOD: &'static str = "info_get_validator_performance";
+    type ResponseResult = GetValidatorPerformanceResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let eras = effect_builder.get_consensus_validator_performance().await;
+        let result = Self::ResponseResult::new(api_version, eras);
+        Ok(result)
+    }
+}
+
 /// Result for the "info_get_chainspec" RPC.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetChainspecResult {
@@ -110,7 +110,7 @@ impl RpcWithParams for SpeculativeExec {
             .await;
 
         if let Err(error) = accept_deploy_result {
-            return Err(Error::new(ErrorCode::InvalidDeploy, error.to_string()));
+            return Err(Error::new(ErrorCode::from(&error), error.to_string()));
         }
 
         let result = effect_builder
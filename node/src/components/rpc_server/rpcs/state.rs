@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{ops::Bound, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -20,6 +20,7 @@ use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
+    contracts::NamedKeys,
     CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
     U512,
 };
@@ -112,6 +113,26 @@ static QUERY_GLOBAL_STATE_RESULT: Lazy<QueryGlobalStateResult> =
         stored_value: StoredValue::Account(JsonAccount::doc_example().clone()),
         merkle_proof: MERKLE_PROOF.clone(),
     });
+static GET_NAMED_KEYS_PARAMS: Lazy<GetNamedKeysParams> = Lazy::new(|| GetNamedKeysParams {
+    state_identifier: Some(GlobalStateIdentifier::BlockHash(
+        *Block::doc_example().hash(),
+    )),
+    key: "account-hash-0909090909090909090909090909090909090909090909090909090909090909"
+        .to_string(),
+    prefix: Some("main_".to_string()),
+    start_after: None,
+    page_size: Some(10),
+});
+static GET_NAMED_KEYS_RESULT: Lazy<GetNamedKeysResult> = Lazy::new(|| GetNamedKeysResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    named_keys: vec![NamedKeyEntry {
+        name: "main_purse".to_string(),
+        key: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
+            .to_string(),
+        uref_access_rights: Some("READ_ADD_WRITE".to_string()),
+    }],
+    next_page_start_after: None,
+});
 static GET_TRIE_PARAMS: Lazy<GetTrieParams> = Lazy::new(|| GetTrieParams {
     trie_key: *Block::doc_example().header().state_root_hash(),
 });
@@ -888,6 +909,178 @@ impl RpcWithParams for QueryGlobalState {
     }
 }
 
+/// The default number of named keys returned by a single "state_get_named_keys" request.
+const DEFAULT_NAMED_KEYS_PAGE_SIZE: u32 = 100;
+/// The maximum number of named keys returned by a single "state_get_named_keys" request.
+const MAX_NAMED_KEYS_PAGE_SIZE: u32 = 1_000;
+
+/// Params for "state_get_named_keys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetNamedKeysParams {
+    /// The identifier used for the query. If none is passed the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The account or contract key as a formatted string.
+    pub key: String,
+    /// If given, only named keys whose names start with this prefix are returned.
+    pub prefix: Option<String>,
+    /// If given, only named keys whose names sort after this one are returned.  Pass the
+    /// `next_page_start_after` value of the previous response to fetch the following page.
+    pub start_after: Option<String>,
+    /// The maximum number of named keys to return.  Defaults to 100, clamped to between 1 and
+    /// 1000.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetNamedKeysParams {
+    fn doc_example() -> &'static Self {
+        &GET_NAMED_KEYS_PARAMS
+    }
+}
+
+/// A single named key returned by the "state_get_named_keys" RPC.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NamedKeyEntry {
+    /// The name of the entry.
+    pub name: String,
+    /// The `casper_types::Key` of the entry as a formatted string.
+    pub key: String,
+    /// The access rights of the entry if its key is a URef.
+    pub uref_access_rights: Option<String>,
+}
+
+/// Result for "state_get_named_keys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetNamedKeysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The named keys of this page, sorted by name.
+    pub named_keys: Vec<NamedKeyEntry>,
+    /// If more matching named keys exist, the name to pass as `start_after` to get the next page.
+    pub next_page_start_after: Option<String>,
+}
+
+impl DocExample for GetNamedKeysResult {
+    fn doc_example() -> &'static Self {
+        &GET_NAMED_KEYS_RESULT
+    }
+}
+
+/// "state_get_named_keys" RPC.
+pub struct GetNamedKeys {}
+
+#[async_trait]
+impl RpcWithParams for GetNamedKeys {
+    const METHOD: &'static str = "state_get_named_keys";
+    type RequestParams = GetNamedKeysParams;
+    type ResponseResult = GetNamedKeysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let state_root_hash = match params.state_identifier {
+            None => match effect_builder
+                .get_highest_complete_block_header_from_storage()
+                .await
+            {
+                None => {
+                    return Err(Error::new(
+                        ErrorCode::NoSuchBlock,
+                        "get-named-keys failed to retrieve highest block header",
+                    ))
+                }
+                Some(block_header) => *block_header.state_root_hash(),
+            },
+            Some(state_identifier) => {
+                get_state_root_hash_and_optional_header(effect_builder, state_identifier)
+                    .await?
+                    .0
+            }
+        };
+
+        let base_key = match Key::from_formatted_str(&params.key)
+            .map_err(|error| format!("failed to parse key: {}", error))
+        {
+            Ok(key) => key,
+            Err(error_msg) => {
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::FailedToParseQueryKey, error_msg));
+            }
+        };
+
+        let (stored_value, _proofs) =
+            run_query(effect_builder, state_root_hash, base_key, vec![]).await?;
+        let named_keys = match &stored_value {
+            DomainStoredValue::Account(account) => account.named_keys(),
+            DomainStoredValue::Contract(contract) => contract.named_keys(),
+            other => {
+                let error_msg = format!(
+                    "expected account or contract under {}, but got {}",
+                    base_key,
+                    other.type_name()
+                );
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::NoNamedKeys, error_msg));
+            }
+        };
+
+        let (named_keys, next_page_start_after) = named_keys_page(
+            named_keys,
+            params.prefix.unwrap_or_default(),
+            params.start_after,
+            params.page_size,
+        );
+
+        let result = Self::ResponseResult {
+            api_version,
+            named_keys,
+            next_page_start_after,
+        };
+        Ok(result)
+    }
+}
+
+/// Returns the page of `named_keys` whose names start with `prefix` and sort after `start_after`,
+/// along with the name to pass as `start_after` for the next page if there are more.
+fn named_keys_page(
+    named_keys: &NamedKeys,
+    prefix: String,
+    start_after: Option<String>,
+    page_size: Option<u32>,
+) -> (Vec<NamedKeyEntry>, Option<String>) {
+    let page_size = page_size
+        .unwrap_or(DEFAULT_NAMED_KEYS_PAGE_SIZE)
+        .clamp(1, MAX_NAMED_KEYS_PAGE_SIZE) as usize;
+    let lower_bound = match start_after {
+        Some(start_after) => Bound::Excluded(start_after),
+        None => Bound::Included(prefix.clone()),
+    };
+    let mut matching = named_keys
+        .range((lower_bound, Bound::Unbounded))
+        .skip_while(|(name, _)| name.as_str() < prefix.as_str())
+        .take_while(|(name, _)| name.starts_with(prefix.as_str()));
+
+    let page: Vec<NamedKeyEntry> = matching
+        .by_ref()
+        .take(page_size)
+        .map(|(name, key)| NamedKeyEntry {
+            name: name.clone(),
+            key: key.to_formatted_string(),
+            uref_access_rights: key.as_uref().map(|uref| uref.access_rights().to_string()),
+        })
+        .collect();
+    let next_page_start_after = match matching.next() {
+        Some(_) => page.last().map(|entry| entry.name.clone()),
+        None => None,
+    };
+    (page, next_page_start_after)
+}
+
 /// Identifier of a purse.
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
@@ -1231,3 +1424,110 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
         GlobalStateIdentifier::StateRootHash(state_root_hash) => Ok((state_root_hash, None)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{AccessRights, URef};
+
+    use super::*;
+
+    fn uref_named_keys(names: &[&str]) -> NamedKeys {
+        names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let uref = URef::new([index as u8; 32], AccessRights::READ_ADD_WRITE);
+                (name.to_string(), Key::URef(uref))
+            })
+            .collect()
+    }
+
+    fn names(page: &[NamedKeyEntry]) -> Vec<&str> {
+        page.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn should_page_through_named_keys() {
+        let named_keys = uref_named_keys(&["a", "b", "c", "d", "e"]);
+
+        let (page, next) = named_keys_page(&named_keys, String::new(), None, Some(2));
+        assert_eq!(names(&page), vec!["a", "b"]);
+        assert_eq!(next.as_deref(), Some("b"));
+
+        let (page, next) = named_keys_page(&named_keys, String::new(), next, Some(2));
+        assert_eq!(names(&page), vec!["c", "d"]);
+        assert_eq!(next.as_deref(), Some("d"));
+
+        let (page, next) = named_keys_page(&named_keys, String::new(), next, Some(2));
+        assert_eq!(names(&page), vec!["e"]);
+        assert!(next.is_none());
+
+        // A full last page has no next page either.
+        let (page, next) = named_keys_page(&named_keys, String::new(), None, Some(5));
+        assert_eq!(page.len(), 5);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn should_filter_named_keys_by_prefix() {
+        let named_keys = uref_named_keys(&["a", "main_1", "main_2", "main_3", "mainz", "z"]);
+
+        let (page, next) = named_keys_page(&named_keys, "main_".to_string(), None, Some(2));
+        assert_eq!(names(&page), vec!["main_1", "main_2"]);
+        assert_eq!(next.as_deref(), Some("main_2"));
+
+        let (page, next) = named_keys_page(&named_keys, "main_".to_string(), next, Some(2));
+        assert_eq!(names(&page), vec!["main_3"]);
+        assert!(next.is_none());
+
+        // A `start_after` sorting before the prefix still only returns matching names.
+        let start_after = Some("a".to_string());
+        let (page, _) = named_keys_page(&named_keys, "main_".to_string(), start_after, None);
+        assert_eq!(names(&page), vec!["main_1", "main_2", "main_3"]);
+
+        let (page, next) = named_keys_page(&named_keys, "none".to_string(), None, None);
+        assert!(page.is_empty());
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn should_clamp_page_size_and_describe_urefs() {
+        let names: Vec<String> = (0..1_500).map(|index| format!("{:04}", index)).collect();
+        let named_keys: NamedKeys = names
+            .iter()
+            .map(|name| (name.clone(), Key::Hash([1; 32])))
+            .collect();
+
+        let (page, _) = named_keys_page(&named_keys, String::new(), None, Some(0));
+        assert_eq!(page.len(), 1);
+        let (page, _) = named_keys_page(&named_keys, String::new(), None, None);
+        assert_eq!(page.len(), DEFAULT_NAMED_KEYS_PAGE_SIZE as usize);
+        let (page, _) = named_keys_page(&named_keys, String::new(), None, Some(u32::MAX));
+        assert_eq!(page.len(), MAX_NAMED_KEYS_PAGE_SIZE as usize);
+        assert!(page[0].uref_access_rights.is_none());
+
+        let named_keys = uref_named_keys(&["purse"]);
+        let (page, _) = named_keys_page(&named_keys, String::new(), None, None);
+        assert_eq!(
+            page,
+            vec![NamedKeyEntry {
+                name: "purse".to_string(),
+                key: "uref-0000000000000000000000000000000000000000000000000000000000000000-007"
+                    .to_string(),
+                uref_access_rights: Some("READ_ADD_WRITE".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_accept_params_without_optional_fields() {
+        let params: GetNamedKeysParams = serde_json::from_value(serde_json::json!({
+            "key": "account-hash-0909090909090909090909090909090909090909090909090909090909090909"
+        }))
+        .unwrap();
+        assert!(params.state_identifier.is_none());
+        assert!(params.prefix.is_none());
+        assert!(params.start_after.is_none());
+        assert!(params.page_size.is_none());
+    }
+}
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::HttpTlsConfig;
+
 /// Default binding address for the speculative execution RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -9,6 +11,8 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:1";
 const DEFAULT_QPS_LIMIT: u64 = 1;
 /// Default max body bytes (2.5MB).
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single JSON-RPC batch request.
+const DEFAULT_MAX_BATCH_SIZE: usize = 1;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -25,8 +29,18 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
-    /// CORS origin.
+    /// Maximum number of requests to accept in a single JSON-RPC batch request.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// CORS origin.  Empty to disable CORS, `*` to allow any origin, or a comma-separated list of
+    /// allowed origins.
     pub cors_origin: String,
+    /// HTTP methods allowed for cross-origin requests.  If empty, only POST is allowed.
+    #[serde(default)]
+    pub cors_allowed_methods: Vec<String>,
+    /// TLS configuration.  If absent, the server accepts plain HTTP connections.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
 }
 
 impl Config {
@@ -37,11 +51,18 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            cors_allowed_methods: vec![],
+            tls: None,
         }
     }
 }
 
+fn default_max_batch_size() -> usize {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
@@ -1,12 +1,16 @@
-use hyper::server::{conn::AddrIncoming, Builder};
+use std::sync::Arc;
 
-use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
+use tokio::sync::oneshot;
+use warp::cors;
+
+use casper_json_rpc::RequestHandlersBuilder;
 use casper_types::ProtocolVersion;
 
-use super::ReactorEventT;
+use super::{rate_limiter::RateLimiter, ReactorEventT};
 use crate::{
     effect::EffectBuilder,
     rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    utils::HttpIncoming,
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -15,51 +19,55 @@ pub const SPECULATIVE_EXEC_API_PATH: &str = "rpc";
 pub const SPECULATIVE_EXEC_SERVER_NAME: &str = "speculative execution";
 
 /// Run the speculative execution server.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
-    cors_origin: String,
+    max_batch_size: usize,
+    cors: Option<cors::Builder>,
+    shutdown_receiver: oneshot::Receiver<()>,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExec::register_as_handler(
+        effect_builder,
+        api_version,
+        Arc::clone(&rate_limiter),
+        &mut handlers,
+    );
     let handlers = handlers.build();
 
-    match cors_origin.as_str() {
-        "" => {
+    match cors {
+        None => {
             super::rpcs::run(
-                builder,
-                handlers,
-                qps_limit,
-                max_body_bytes,
-                SPECULATIVE_EXEC_API_PATH,
-                SPECULATIVE_EXEC_SERVER_NAME,
-            )
-            .await;
-        }
-        "*" => {
-            super::rpcs::run_with_cors(
-                builder,
+                incoming,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
-                CorsOrigin::Any,
+                shutdown_receiver,
             )
             .await
         }
-        _ => {
+        Some(cors) => {
             super::rpcs::run_with_cors(
-                builder,
+                incoming,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
-                CorsOrigin::Specified(cors_origin),
+                cors,
+                shutdown_receiver,
             )
             .await
         }
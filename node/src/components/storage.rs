@@ -38,11 +38,9 @@ mod object_pool;
 #[cfg(test)]
 mod tests;
 
-#[cfg(test)]
-use std::collections::BTreeSet;
 use std::{
     borrow::Cow,
-    collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet},
+    collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
     convert::{TryFrom, TryInto},
     fmt::{self, Display, Formatter},
     fs::{self, OpenOptions},
@@ -51,10 +49,12 @@ use std::{
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use datasize::DataSize;
 use derive_more::From;
+use futures::{future::BoxFuture, FutureExt};
 use itertools::Itertools;
 use lmdb::{
     Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
@@ -71,7 +71,8 @@ use tracing::{debug, error, info, trace, warn};
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::{FromBytes, ToBytes},
-    EraId, ExecutionResult, ProtocolVersion, PublicKey, Timestamp, Transfer, Transform,
+    EraId, ExecutionResult, ExecutionResultFormat, ProtocolVersion, PublicKey, Timestamp, Transfer,
+    Transform,
 };
 
 use crate::{
@@ -89,14 +90,15 @@ use crate::{
     },
     fatal,
     protocol::Message,
+    reactor::Finalize,
     types::{
         ApprovalsHash, ApprovalsHashes, AvailableBlockRange, Block, BlockAndDeploys, BlockBody,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
         BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
         BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployHeader, DeployId,
         DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalizedApprovals, FinalizedBlock, LegacyDeploy, MaxTtl, NodeId, SyncLeap,
-        SyncLeapIdentifier, ValueOrChunk,
+        FinalizedApprovals, FinalizedBlock, LegacyDeploy, MaxTtl, NodeId, SignedEraValidators,
+        SyncLeap, SyncLeapIdentifier, ValueOrChunk,
     },
     utils::{display_error, WithDir},
     NodeRng,
@@ -105,7 +107,7 @@ use disjoint_sequences::{DisjointSequences, Sequence};
 pub use error::FatalStorageError;
 use error::GetRequestError;
 use lmdb_ext::{BytesreprError, LmdbExtError, TransactionExt, WriteTransactionExt};
-use metrics::Metrics;
+use metrics::{Metrics, Operation, BLOCKS_DATABASE, COMPONENT_STATE_DATABASE, DEPLOYS_DATABASE};
 use object_pool::ObjectPool;
 
 const COMPONENT_NAME: &str = "storage";
@@ -129,11 +131,15 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 9;
+const MAX_DB_COUNT: u32 = 11;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
 /// Name of the file created when initializing a force resync.
 const FORCE_RESYNC_FILE_NAME: &str = "force_resync";
+/// Name of the file temporarily created to check whether the storage folder is writable.
+const WRITE_PROBE_FILE_NAME: &str = "write_probe";
+/// Minimum interval between updates of the database size and map utilization metrics.
+const DB_METRICS_UPDATE_INTERVAL: Duration = Duration::from_secs(30);
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -149,6 +155,9 @@ const_assert!(_STORAGE_EVENT_SIZE <= 32);
 
 type FinalizedBlockAndDeploys = (FinalizedBlock, Vec<Deploy>);
 
+/// The size in bytes of each group of databases, labeled as in the storage metrics.
+type DatabaseSizes = [(&'static str, u64); 3];
+
 const STORAGE_FILES: [&str; 5] = [
     "data.lmdb",
     "data.lmdb-lock",
@@ -192,6 +201,14 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The execution results format database, holding the format of each block's execution
+    /// results if they are not stored in full.
+    #[data_size(skip)]
+    execution_results_format_db: Database,
+    /// The step effect database, holding the effect of the step run at the end of the era of each
+    /// switch block.
+    #[data_size(skip)]
+    step_effect_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -206,6 +223,8 @@ pub struct Storage {
     key_block_height_for_activation_point: Option<u64>,
     /// Whether or not memory deduplication is enabled.
     enable_mem_deduplication: bool,
+    /// Whether execution results are stored in compact form.
+    compact_execution_results: bool,
     /// An in-memory pool of already loaded serialized items.
     ///
     /// Keyed by serialized item ID, contains the serialized item.
@@ -310,7 +329,15 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         let result = match event {
-            Event::StorageRequest(req) => self.handle_storage_request(*req),
+            Event::StorageRequest(req) => {
+                let start = Instant::now();
+                let labels = metrics_labels(&req);
+                let result = self.handle_storage_request(*req);
+                if let (Some(metrics), Some((operation, database))) = (&self.metrics, labels) {
+                    metrics.observe_operation(operation, database, start.elapsed());
+                }
+                result
+            }
             Event::NetRequestIncoming(ref incoming) => {
                 match self.handle_net_request_incoming::<REv>(effect_builder, incoming) {
                     Ok(effects) => Ok(effects),
@@ -337,13 +364,20 @@ where
                 }
             }
         };
+        self.update_db_metrics();
 
         // Any error is turned into a fatal effect, the component itself does not panic. Note that
         // we are dropping a lot of responders this way, but since we are crashing with fatal
         // anyway, it should not matter.
         match result {
             Ok(effects) => effects,
-            Err(err) => fatal!(effect_builder, "storage error: {}", err).ignore(),
+            Err(err) => fatal!(
+                effect_builder,
+                exit_code = err.exit_code(),
+                "storage error: {}",
+                err
+            )
+            .ignore(),
         }
     }
 
@@ -352,6 +386,29 @@ where
     }
 }
 
+impl Finalize for Storage {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        // The environment is not shared outside of storage, so we can take ownership of it and
+        // defer flushing it until the returned future is polled, i.e. after all components that
+        // were finalized before us have shut down.
+        match Rc::try_unwrap(self.env) {
+            Ok(env) => async move { sync_env(&env) }.boxed(),
+            Err(env) => {
+                sync_env(&env);
+                async {}.boxed()
+            }
+        }
+    }
+}
+
+/// Flushes all buffered data of the given LMDB environment to disk.
+fn sync_env(env: &Environment) {
+    match env.sync(true) {
+        Ok(()) => debug!("storage flushed to disk"),
+        Err(error) => error!(%error, "failed to flush storage to disk"),
+    }
+}
+
 impl Storage {
     /// Creates a new storage component.
     #[allow(clippy::too_many_arguments)]
@@ -417,6 +474,9 @@ impl Storage {
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
         let approvals_hashes_db =
             env.create_db(Some("approvals_hashes"), DatabaseFlags::empty())?;
+        let execution_results_format_db =
+            env.create_db(Some("execution_results_format"), DatabaseFlags::empty())?;
+        let step_effect_db = env.create_db(Some("step_effects"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -508,6 +568,8 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            execution_results_format_db,
+            step_effect_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
@@ -515,6 +577,7 @@ impl Storage {
             activation_era,
             key_block_height_for_activation_point: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
+            compact_execution_results: config.compact_execution_results,
             serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
             recent_era_count,
             max_ttl,
@@ -566,6 +629,7 @@ impl Storage {
                 }
 
                 component.completed_blocks = sequences;
+                component.update_chain_height_metrics();
             }
             None => {
                 // No state so far. We can make the following observations:
@@ -609,12 +673,16 @@ impl Storage {
         &self,
         key: &K,
     ) -> Result<Option<Vec<u8>>, FatalStorageError> {
+        let start = Instant::now();
         let txn = self.env.begin_ro_txn()?;
         let bytes = match txn.get(self.state_store_db, &key) {
             Ok(slice) => Some(slice.to_owned()),
             Err(lmdb::Error::NotFound) => None,
             Err(err) => return Err(err.into()),
         };
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_operation(Operation::Get, COMPONENT_STATE_DATABASE, start.elapsed());
+        }
         Ok(bytes)
     }
 
@@ -625,6 +693,7 @@ impl Storage {
         key: Cow<'static, [u8]>,
         data: &Vec<u8>,
     ) -> Result<(), FatalStorageError> {
+        let start = Instant::now();
         let mut txn = self.env.begin_rw_txn()?;
 
         // Note: The interface of `lmdb` seems suboptimal: `&K` and `&V` could simply be `&[u8]` for
@@ -632,15 +701,107 @@ impl Storage {
         //       this reason, we need to use actual sized types in the function signature above.
         txn.put(self.state_store_db, &key, data, WriteFlags::default())?;
         txn.commit()?;
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_operation(Operation::Put, COMPONENT_STATE_DATABASE, start.elapsed());
+        }
 
         Ok(())
     }
 
+    /// Updates the database size and map utilization metrics, unless they were updated less than
+    /// `DB_METRICS_UPDATE_INTERVAL` ago.
+    fn update_db_metrics(&mut self) {
+        let due = match &self.metrics {
+            Some(metrics) => metrics.db_stats_updated.map_or(true, |updated| {
+                updated.elapsed() >= DB_METRICS_UPDATE_INTERVAL
+            }),
+            None => false,
+        };
+        if !due {
+            return;
+        }
+        let stats = self.read_db_stats();
+        let metrics = match self.metrics.as_mut() {
+            Some(metrics) => metrics,
+            None => return,
+        };
+        metrics.db_stats_updated = Some(Instant::now());
+        match stats {
+            Ok((sizes, map_utilization)) => {
+                for (database, size) in sizes {
+                    metrics
+                        .db_size
+                        .with_label_values(&[database])
+                        .set(size.try_into().unwrap_or(i64::MAX));
+                }
+                metrics.map_utilization.set(map_utilization);
+            }
+            // Not being able to read the statistics doesn't affect the stored data.
+            Err(error) => warn!(%error, "failed to read storage database statistics"),
+        }
+    }
+
+    /// Returns the size in bytes of the pages used by each group of databases, and the fraction of
+    /// the memory map in use.
+    fn read_db_stats(&self) -> Result<(DatabaseSizes, f64), lmdb::Error> {
+        let txn = self.env.begin_ro_txn()?;
+        let size_of = |dbs: &[Database]| {
+            dbs.iter().try_fold(0u64, |total, db| {
+                let stat = txn.stat(*db)?;
+                let pages = stat.branch_pages() + stat.leaf_pages() + stat.overflow_pages();
+                Ok::<_, lmdb::Error>(
+                    total.saturating_add((pages as u64).saturating_mul(stat.page_size().into())),
+                )
+            })
+        };
+        let sizes = [
+            (
+                BLOCKS_DATABASE,
+                size_of(&[
+                    self.block_header_db,
+                    self.block_body_db,
+                    self.block_metadata_db,
+                    self.approvals_hashes_db,
+                    self.step_effect_db,
+                ])?,
+            ),
+            (
+                DEPLOYS_DATABASE,
+                size_of(&[
+                    self.deploy_db,
+                    self.deploy_metadata_db,
+                    self.transfer_db,
+                    self.finalized_approvals_db,
+                ])?,
+            ),
+            (COMPONENT_STATE_DATABASE, size_of(&[self.state_store_db])?),
+        ];
+        drop(txn);
+
+        let info = self.env.info()?;
+        let page_size = u64::from(self.env.stat()?.page_size());
+        let used = (info.last_pgno() as u64 + 1).saturating_mul(page_size);
+        let map_utilization = used as f64 / info.map_size().max(1) as f64;
+        Ok((sizes, map_utilization))
+    }
+
     /// Returns the path to the storage folder.
     pub(crate) fn root_path(&self) -> &Path {
         &self.root
     }
 
+    /// Returns whether the storage folder is writable, by creating and removing a probe file.
+    fn is_writable(&self) -> bool {
+        let probe_path = self.root.join(WRITE_PROBE_FILE_NAME);
+        match fs::write(&probe_path, b"").and_then(|_| fs::remove_file(&probe_path)) {
+            Ok(()) => true,
+            Err(error) => {
+                warn!(%error, path = %probe_path.display(), "storage folder is not writable");
+                false
+            }
+        }
+    }
+
     fn handle_net_request_incoming<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -773,6 +934,24 @@ impl Storage {
                 let opt_item = self.read_block_execution_results_or_chunk(&item_id)?;
                 let fetch_response = FetchResponse::from_opt(item_id, opt_item);
 
+                Ok(self.update_pool_and_send(
+                    effect_builder,
+                    incoming.sender,
+                    serialized_id,
+                    fetch_response,
+                )?)
+            }
+            NetRequest::EraValidators(ref serialized_id) => {
+                let item_id = decode_item_id::<SignedEraValidators>(serialized_id)?;
+                // The validators of an era are recorded in the switch block of the previous era.
+                let opt_item = match item_id.predecessor() {
+                    Some(switch_block_era_id) => self
+                        .read_switch_block_header_with_metadata(switch_block_era_id)?
+                        .map(SignedEraValidators::new),
+                    None => None,
+                };
+                let fetch_response = FetchResponse::from_opt(item_id, opt_item);
+
                 Ok(self.update_pool_and_send(
                     effect_builder,
                     incoming.sender,
@@ -834,6 +1013,26 @@ impl Storage {
             } => responder
                 .respond(self.get_deploys_era_ids(deploy_hashes))
                 .ignore(),
+            StorageRequest::GetExecutedDeploys {
+                deploy_hashes,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let mut executed = HashMap::new();
+                for deploy_hash in deploy_hashes {
+                    let block_hash = match self.deploy_hash_index.get(&deploy_hash) {
+                        Some(block_hash_height_and_era) => block_hash_height_and_era.block_hash,
+                        None => continue,
+                    };
+                    if let Some(block_header) =
+                        self.get_single_block_header(&mut txn, &block_hash)?
+                    {
+                        executed.insert(deploy_hash, block_header);
+                    }
+                }
+                drop(txn);
+                responder.respond(executed).ignore()
+            }
             StorageRequest::GetBlockHeader {
                 block_hash,
                 only_from_available_block_range,
@@ -889,16 +1088,9 @@ impl Storage {
                 responder,
             } => {
                 let mut txn = self.env.begin_ro_txn()?;
-                let maybe_deploy = match self
-                    .get_deploy_with_finalized_approvals(&mut txn, deploy_id.deploy_hash())?
-                {
-                    None => None,
-                    Some(deploy_with_finalized_approvals) => {
-                        let deploy = deploy_with_finalized_approvals.into_naive();
-                        (deploy.fetch_id() == deploy_id).then_some(deploy)
-                    }
-                };
-                responder.respond(maybe_deploy).ignore()
+                responder
+                    .respond(self.get_deploy_by_id(&mut txn, deploy_id)?)
+                    .ignore()
             }
             StorageRequest::IsDeployStored {
                 deploy_id,
@@ -928,6 +1120,29 @@ impl Storage {
                 txn.commit()?;
                 responder.respond(()).ignore()
             }
+            StorageRequest::PutStepEffect {
+                block_hash,
+                step_effect,
+                responder,
+            } => {
+                let step_effect = if self.compact_execution_results {
+                    step_effect.compact()
+                } else {
+                    step_effect
+                };
+                let mut txn = self.env.begin_rw_txn()?;
+                let _ = txn.put_value(self.step_effect_db, &*block_hash, &step_effect, true)?;
+                txn.commit()?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetStepEffect {
+                block_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let maybe_step_effect = txn.get_value(self.step_effect_db, &block_hash)?;
+                responder.respond(maybe_step_effect).ignore()
+            }
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
@@ -1080,6 +1295,24 @@ impl Storage {
                     }))
                     .ignore()
             }
+            StorageRequest::GetBlockHeaderWithMetadata {
+                block_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let maybe_block_hash =
+                    block_hash.or_else(|| self.block_height_index.values().next_back().copied());
+                let maybe_header_with_metadata = match maybe_block_hash {
+                    Some(block_hash) => {
+                        self.get_single_block_header_with_metadata(&mut txn, &block_hash)?
+                    }
+                    None => None,
+                };
+                responder.respond(maybe_header_with_metadata).ignore()
+            }
+            StorageRequest::GetSwitchBlockHeaderWithMetadata { era_id, responder } => responder
+                .respond(self.read_switch_block_header_with_metadata(era_id)?)
+                .ignore(),
             StorageRequest::PutBlockSignatures {
                 signatures,
                 responder,
@@ -1150,6 +1383,13 @@ impl Storage {
                     }
                 }
             }
+            StorageRequest::PutBlockHeaderWithMetadata {
+                block_header_with_metadata,
+                responder,
+            } => {
+                let outcome = self.put_block_header_with_metadata(&block_header_with_metadata)?;
+                responder.respond(outcome).ignore()
+            }
             StorageRequest::GetAvailableBlockRange { responder } => {
                 responder.respond(self.get_available_block_range()).ignore()
             }
@@ -1184,6 +1424,19 @@ impl Storage {
                     .respond(self.key_block_height_for_activation_point)
                     .ignore()
             }
+            StorageRequest::CheckWritable { responder } => {
+                responder.respond(self.is_writable()).ignore()
+            }
+            StorageRequest::Sync { responder } => {
+                let synced = match self.env.sync(true) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        error!(%error, "failed to sync storage to disk");
+                        false
+                    }
+                };
+                responder.respond(synced).ignore()
+            }
         })
     }
 
@@ -1300,7 +1553,7 @@ impl Storage {
     }
 
     /// Retrieves a approvals hashes by block hash.
-    fn read_approvals_hashes(
+    pub(crate) fn read_approvals_hashes(
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<ApprovalsHashes>, FatalStorageError> {
@@ -1470,9 +1723,25 @@ impl Storage {
         block_hash: &BlockHash,
         execution_results: HashMap<DeployHash, ExecutionResult>,
     ) -> Result<bool, FatalStorageError> {
+        let format = if self.compact_execution_results {
+            ExecutionResultFormat::Compact
+        } else {
+            ExecutionResultFormat::Full
+        };
+        if format.is_full() {
+            let _ = delete_raw(txn, self.execution_results_format_db, block_hash)?;
+        } else {
+            let _ = txn.put_value(self.execution_results_format_db, block_hash, &format, true)?;
+        }
+
         let mut transfers: Vec<Transfer> = vec![];
         for (deploy_hash, execution_result) in execution_results {
             transfers.extend(successful_transfers(&execution_result));
+            let execution_result = if format.is_full() {
+                execution_result
+            } else {
+                execution_result.compact()
+            };
 
             let mut metadata = self
                 .get_deploy_metadata(txn, &deploy_hash)?
@@ -1622,6 +1891,17 @@ impl Storage {
         Ok(switch_block)
     }
 
+    /// Reads the header of the switch block of the given era.
+    pub(crate) fn read_switch_block_header_by_era_id(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<BlockHeader>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let header = self.get_switch_block_header_by_era_id(&mut txn, era_id)?;
+        txn.commit()?;
+        Ok(header)
+    }
+
     /// Returns `count` highest switch block headers, sorted from lowest (oldest) to highest.
     pub(crate) fn read_highest_switch_block_headers(
         &self,
@@ -2103,6 +2383,51 @@ impl Storage {
         Ok(result)
     }
 
+    /// Writes a block header together with its finality signatures in a single transaction,
+    /// merging the signatures with any already stored for the block.
+    fn put_block_header_with_metadata(
+        &mut self,
+        block_header_with_metadata: &BlockHeaderWithMetadata,
+    ) -> Result<bool, FatalStorageError> {
+        let BlockHeaderWithMetadata {
+            block_header,
+            block_signatures,
+        } = block_header_with_metadata;
+        let block_hash = block_header.block_hash();
+        if block_signatures.block_hash != block_hash || block_signatures.proofs.is_empty() {
+            error!(
+                %block_hash,
+                "should not attempt to store a block header without matching signatures"
+            );
+            return Ok(false);
+        }
+
+        let mut txn = self.env.begin_rw_txn()?;
+        let header_outcome =
+            txn.put_value(self.block_header_db, &block_hash, block_header, false)?;
+        let new_signatures =
+            match txn.get_value::<_, BlockSignatures>(self.block_metadata_db, &block_hash)? {
+                None => block_signatures.clone(),
+                Some(mut stored_signatures) => {
+                    for (public_key, signature) in &block_signatures.proofs {
+                        stored_signatures.insert_proof(public_key.clone(), *signature);
+                    }
+                    stored_signatures
+                }
+            };
+        let signatures_outcome =
+            txn.put_value(self.block_metadata_db, &block_hash, &new_signatures, true)?;
+        txn.commit()?;
+
+        // Update the indices if and only if we wrote to storage correctly.
+        insert_to_block_header_indices(
+            &mut self.block_height_index,
+            &mut self.switch_block_era_id_index,
+            block_header,
+        )?;
+        Ok(header_outcome && signatures_outcome)
+    }
+
     /// Writes a single block body in a separate transaction to storage.
     fn put_single_block_body(
         &self,
@@ -2189,6 +2514,33 @@ impl Storage {
         }
     }
 
+    /// Retrieves the deploy with the given ID.
+    ///
+    /// The ID may refer to the deploy with either its original or its finalized approvals.  Both
+    /// are checked, so that a fetcher does not ask peers for a deploy we already hold.
+    fn get_deploy_by_id<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        deploy_id: DeployId,
+    ) -> Result<Option<Deploy>, FatalStorageError> {
+        let deploy: Deploy = match txn.get_value(self.deploy_db, deploy_id.deploy_hash())? {
+            Some(deploy) => deploy,
+            None => return Ok(None),
+        };
+        if deploy.fetch_id() == deploy_id {
+            return Ok(Some(deploy));
+        }
+        let maybe_finalized_approvals: Option<FinalizedApprovals> =
+            txn.get_value(self.finalized_approvals_db, deploy_id.deploy_hash())?;
+        let deploy = match maybe_finalized_approvals {
+            Some(finalized_approvals) => {
+                DeployWithFinalizedApprovals::new(deploy, Some(finalized_approvals)).into_naive()
+            }
+            None => return Ok(None),
+        };
+        Ok((deploy.fetch_id() == deploy_id).then_some(deploy))
+    }
+
     /// Retrieves deploy metadata associated with deploy.
     ///
     /// If no deploy metadata is stored for the specific deploy, an empty metadata instance will be
@@ -2198,7 +2550,31 @@ impl Storage {
         txn: &mut Tx,
         deploy_hash: &DeployHash,
     ) -> Result<Option<DeployMetadata>, FatalStorageError> {
-        Ok(txn.get_value(self.deploy_metadata_db, deploy_hash)?)
+        let mut metadata: DeployMetadata =
+            match txn.get_value(self.deploy_metadata_db, deploy_hash)? {
+                Some(metadata) => metadata,
+                None => return Ok(None),
+            };
+        for block_hash in metadata.execution_results.keys() {
+            if !self
+                .get_execution_results_format(txn, block_hash)?
+                .is_full()
+            {
+                let _ = metadata.compact_blocks.insert(*block_hash);
+            }
+        }
+        Ok(Some(metadata))
+    }
+
+    /// Retrieves the format in which the execution results of the given block are stored.
+    fn get_execution_results_format<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<ExecutionResultFormat, FatalStorageError> {
+        Ok(txn
+            .get_value(self.execution_results_format_db, block_hash)?
+            .unwrap_or_default())
     }
 
     /// Retrieves successful transfers associated with block.
@@ -2269,8 +2645,34 @@ impl Storage {
         Ok(maybe_signatures.and_then(|signatures| signatures.get_finality_signature(public_key)))
     }
 
+    /// Retrieves the switch block header of the given era together with its finality signatures.
+    ///
+    /// Returns `None` if either the header or the signatures are not stored.
+    pub(crate) fn read_switch_block_header_with_metadata(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<BlockHeaderWithMetadata>, FatalStorageError> {
+        let block_hash = match self.switch_block_era_id_index.get(&era_id) {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let block_header = match self.get_single_block_header(&mut txn, block_hash)? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        let block_signatures = match self.get_block_signatures(&mut txn, block_hash)? {
+            Some(block_signatures) if !block_signatures.proofs.is_empty() => block_signatures,
+            _ => return Ok(None),
+        };
+        Ok(Some(BlockHeaderWithMetadata {
+            block_header,
+            block_signatures,
+        }))
+    }
+
     /// Retrieves block signatures for a block with a given block hash.
-    fn read_block_signatures(
+    pub(crate) fn read_block_signatures(
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockSignatures>, FatalStorageError> {
@@ -2287,6 +2689,175 @@ impl Storage {
         Ok(txn.get_value(self.deploy_db, &deploy_hash)?)
     }
 
+    /// Directly returns a deploy along with its finalized approvals, if these differ from the
+    /// deploy's own approvals, from internal store.
+    pub(crate) fn read_deploy_and_finalized_approvals(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<(Deploy, Option<FinalizedApprovals>)>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let deploy = match txn.get_value(self.deploy_db, deploy_hash)? {
+            Some(deploy) => deploy,
+            None => return Ok(None),
+        };
+        let maybe_finalized_approvals = txn.get_value(self.finalized_approvals_db, deploy_hash)?;
+        Ok(Some((deploy, maybe_finalized_approvals)))
+    }
+
+    /// Writes a block exported from another node's storage, along with its deploys, approvals
+    /// hashes and finality signatures.
+    ///
+    /// Everything is written in a single transaction, so either all or none of it is stored.  The
+    /// block is not marked complete, as its global state is not part of the export.  Any data
+    /// still missing is acquired from peers when the node syncs.
+    pub(crate) fn write_exported_block(
+        &mut self,
+        block: &Block,
+        deploys: &[(Deploy, Option<FinalizedApprovals>)],
+        maybe_approvals_hashes: Option<&ApprovalsHashes>,
+        maybe_block_signatures: Option<&BlockSignatures>,
+    ) -> Result<(), FatalStorageError> {
+        block.verify()?;
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        for (deploy, maybe_finalized_approvals) in deploys {
+            let _ = txn.put_value(self.deploy_db, deploy.hash(), deploy, false)?;
+            if let Some(finalized_approvals) = maybe_finalized_approvals {
+                // Only store the finalized approvals if they are different from the original ones.
+                if deploy.approvals() != finalized_approvals.inner() {
+                    let _ = txn.put_value(
+                        self.finalized_approvals_db,
+                        deploy.hash(),
+                        finalized_approvals,
+                        true,
+                    )?;
+                }
+            }
+        }
+        if let Some(approvals_hashes) = maybe_approvals_hashes {
+            let _ = self.write_approvals_hashes(&mut txn, approvals_hashes)?;
+        }
+        if let Some(block_signatures) = maybe_block_signatures {
+            if !block_signatures.proofs.is_empty() {
+                let _ = txn.put_value(
+                    self.block_metadata_db,
+                    &block_signatures.block_hash,
+                    block_signatures,
+                    true,
+                )?;
+            }
+        }
+        // The block goes last, as writing it also updates the in-memory indices.
+        if !self.write_validated_block(&mut txn, block)? {
+            return Err(FatalStorageError::FailedToOverwriteBlock);
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Writes block headers without their bodies, along with any finality signatures, as synced by
+    /// a light client.
+    ///
+    /// The headers must already have been verified, e.g. as part of a sync leap.
+    pub(crate) fn write_signed_block_headers(
+        &mut self,
+        block_headers: Vec<BlockHeader>,
+        block_signatures: &[BlockSignatures],
+    ) -> Result<(), FatalStorageError> {
+        let _ = self.put_block_headers(block_headers)?;
+        let mut txn = self.env.begin_rw_txn()?;
+        for signatures in block_signatures {
+            if !signatures.proofs.is_empty() {
+                let _ = txn.put_value(
+                    self.block_metadata_db,
+                    &signatures.block_hash,
+                    signatures,
+                    true,
+                )?;
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Removes the bodies of all blocks in eras before `keep_from_era`, along with their deploys,
+    /// execution results, transfers and approvals.  Block headers and finality signatures are
+    /// kept, so that the chain remains verifiable.
+    ///
+    /// Pruned blocks are no longer considered complete.
+    pub(crate) fn prune_blocks_before_era(
+        &mut self,
+        keep_from_era: EraId,
+    ) -> Result<PruneOutcome, FatalStorageError> {
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        let mut outcome = PruneOutcome::default();
+        let mut pruned_headers = vec![];
+        let mut retained_body_hashes = HashSet::new();
+        let mut maybe_lowest_retained_height = None;
+        for (height, block_hash) in &self.block_height_index {
+            let block_header = match self.get_single_block_header(&mut txn, block_hash)? {
+                Some(block_header) => block_header,
+                None => continue,
+            };
+            if block_header.era_id() < keep_from_era {
+                pruned_headers.push(block_header);
+            } else {
+                let _ = retained_body_hashes.insert(*block_header.body_hash());
+                let _ = outcome
+                    .retained_state_root_hashes
+                    .insert(*block_header.state_root_hash());
+                let _ = maybe_lowest_retained_height.get_or_insert(*height);
+            }
+        }
+
+        for block_header in pruned_headers {
+            let block_hash = block_header.block_hash();
+            let maybe_block_body: Option<BlockBody> =
+                txn.get_value(self.block_body_db, block_header.body_hash())?;
+            let block_body = match maybe_block_body {
+                Some(block_body) => block_body,
+                None => continue,
+            };
+            for deploy_hash in block_body.deploy_and_transfer_hashes() {
+                outcome.bytes += delete_raw(&mut txn, self.deploy_db, deploy_hash)?;
+                outcome.bytes += delete_raw(&mut txn, self.deploy_metadata_db, deploy_hash)?;
+                outcome.bytes += delete_raw(&mut txn, self.finalized_approvals_db, deploy_hash)?;
+                let _ = self.deploy_hash_index.remove(deploy_hash);
+                outcome.deploys += 1;
+            }
+            // Bodies are content-addressed, so a body may be shared with a retained block.
+            if !retained_body_hashes.contains(block_header.body_hash()) {
+                outcome.bytes +=
+                    delete_raw(&mut txn, self.block_body_db, block_header.body_hash())?;
+            }
+            outcome.bytes += delete_raw(&mut txn, self.transfer_db, &block_hash)?;
+            outcome.bytes += delete_raw(&mut txn, self.approvals_hashes_db, &block_hash)?;
+            outcome.bytes += delete_raw(&mut txn, self.execution_results_format_db, &block_hash)?;
+            outcome.bytes += delete_raw(&mut txn, self.step_effect_db, &block_hash)?;
+            outcome.blocks += 1;
+        }
+        txn.commit()?;
+
+        match maybe_lowest_retained_height {
+            Some(lowest_retained_height) => {
+                self.completed_blocks.truncate_below(lowest_retained_height)
+            }
+            None => self.completed_blocks = Default::default(),
+        }
+        self.persist_completed_blocks()?;
+        Ok(outcome)
+    }
+
+    /// Directly returns the metadata, i.e. the execution results, of a deploy from internal store.
+    pub(crate) fn read_deploy_metadata(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<DeployMetadata>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        self.get_deploy_metadata(&mut txn, deploy_hash)
+    }
+
     /// Stores a set of finalized approvals if they are different to the approvals in the original
     /// deploy and if they are different to existing finalized approvals if any.
     ///
@@ -2609,6 +3180,13 @@ impl Storage {
         request: &BlockExecutionResultsOrChunkId,
     ) -> Result<Option<BlockExecutionResultsOrChunk>, FatalStorageError> {
         let mut txn = self.env.begin_rw_txn()?;
+        // Compact results don't match the checksum in global state, so peers couldn't verify them.
+        if !self
+            .get_execution_results_format(&mut txn, request.block_hash())?
+            .is_full()
+        {
+            return Ok(None);
+        }
         let execution_results = match self.get_execution_results(&mut txn, request.block_hash())? {
             Some(execution_results) => execution_results
                 .into_iter()
@@ -2642,6 +3220,25 @@ impl Storage {
                 metrics.highest_available_block.set(highest_available_block);
                 metrics.lowest_available_block.set(lowest_available_block);
             }
+
+            let highest_block_header = self
+                .env
+                .begin_ro_txn()
+                .map_err(FatalStorageError::from)
+                .and_then(|mut txn| self.get_highest_complete_block_header(&mut txn));
+            match highest_block_header {
+                Ok(Some(header)) => {
+                    metrics
+                        .highest_block_height
+                        .set(header.height().try_into().unwrap_or(i64::MIN));
+                    metrics
+                        .highest_block_era
+                        .set(header.era_id().value().try_into().unwrap_or(i64::MIN));
+                    metrics.time_since_last_block.set(header.timestamp());
+                }
+                Ok(None) => {}
+                Err(error) => warn!(%error, "failed to read highest complete block header"),
+            }
         }
     }
 }
@@ -2654,6 +3251,54 @@ where
     bincode::deserialize(raw).map_err(GetRequestError::MalformedIncomingItemId)
 }
 
+/// Returns the kind of operation a storage request performs and the database it is recorded
+/// against, or `None` if it doesn't access a database.
+fn metrics_labels(req: &StorageRequest) -> Option<(Operation, &'static str)> {
+    let labels = match req {
+        StorageRequest::PutBlock { .. }
+        | StorageRequest::PutApprovalsHashes { .. }
+        | StorageRequest::PutExecutedBlock { .. }
+        | StorageRequest::PutBlockSignatures { .. }
+        | StorageRequest::PutFinalitySignature { .. }
+        | StorageRequest::PutBlockHeader { .. }
+        | StorageRequest::PutBlockHeaderWithMetadata { .. }
+        | StorageRequest::PutStepEffect { .. } => (Operation::Put, BLOCKS_DATABASE),
+        StorageRequest::PutDeploy { .. }
+        | StorageRequest::PutExecutionResults { .. }
+        | StorageRequest::StoreFinalizedApprovals { .. } => (Operation::Put, DEPLOYS_DATABASE),
+        StorageRequest::GetDeploysEraIds { .. }
+        | StorageRequest::GetExecutedDeploys { .. }
+        | StorageRequest::GetDeploys { .. }
+        | StorageRequest::GetLegacyDeploy { .. }
+        | StorageRequest::GetDeploy { .. }
+        | StorageRequest::IsDeployStored { .. }
+        | StorageRequest::GetExecutionResults { .. }
+        | StorageRequest::GetBlockExecutionResultsOrChunk { .. }
+        | StorageRequest::GetDeployAndMetadata { .. } => (Operation::Get, DEPLOYS_DATABASE),
+        StorageRequest::GetBlock { .. }
+        | StorageRequest::IsBlockStored { .. }
+        | StorageRequest::GetApprovalsHashes { .. }
+        | StorageRequest::GetHighestCompleteBlock { .. }
+        | StorageRequest::GetHighestCompleteBlockHeader { .. }
+        | StorageRequest::GetBlockHeader { .. }
+        | StorageRequest::GetBlockHeaderByHeight { .. }
+        | StorageRequest::GetBlockTransfers { .. }
+        | StorageRequest::GetBlockAndMetadataByHash { .. }
+        | StorageRequest::GetFinalitySignature { .. }
+        | StorageRequest::IsFinalitySignatureStored { .. }
+        | StorageRequest::GetBlockAndMetadataByHeight { .. }
+        | StorageRequest::GetHighestBlockWithMetadata { .. }
+        | StorageRequest::GetBlockHeaderWithMetadata { .. }
+        | StorageRequest::GetSwitchBlockHeaderWithMetadata { .. }
+        | StorageRequest::GetBlockSignature { .. }
+        | StorageRequest::GetAvailableBlockRange { .. }
+        | StorageRequest::GetKeyBlockHeightForActivationPoint { .. }
+        | StorageRequest::GetStepEffect { .. } => (Operation::Get, BLOCKS_DATABASE),
+        StorageRequest::CheckWritable { .. } | StorageRequest::Sync { .. } => return None,
+    };
+    Some(labels)
+}
+
 /// Inserts the relevant entries to the two indices.
 ///
 /// If a duplicate entry is encountered, neither index is updated and an error is returned.
@@ -2813,6 +3458,12 @@ pub struct Config {
     pub enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     pub mem_pool_prune_interval: u16,
+    /// Whether to store execution results in compact form, keeping only the kind of change made
+    /// to each key rather than the full transforms.
+    ///
+    /// Compact results can't be served to peers syncing the chain.
+    #[serde(default)]
+    pub compact_execution_results: bool,
 }
 
 impl Default for Config {
@@ -2826,6 +3477,7 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            compact_execution_results: false,
         }
     }
 }
@@ -3063,6 +3715,34 @@ fn initialize_deploy_metadata_db(
     Ok(())
 }
 
+/// The outcome of pruning blocks from storage.
+#[derive(Debug, Default)]
+pub(crate) struct PruneOutcome {
+    /// The number of blocks whose bodies were removed.
+    pub(crate) blocks: u64,
+    /// The number of deploys removed.
+    pub(crate) deploys: u64,
+    /// The total size in bytes of the removed entries.
+    pub(crate) bytes: u64,
+    /// The state root hashes of all retained blocks.
+    pub(crate) retained_state_root_hashes: BTreeSet<Digest>,
+}
+
+/// Deletes the entry under `key` from `db` if it exists, returning the size of its value.
+fn delete_raw<K: AsRef<[u8]>>(
+    txn: &mut RwTransaction,
+    db: Database,
+    key: &K,
+) -> Result<u64, lmdb::Error> {
+    let size = match txn.get(db, key) {
+        Ok(raw) => raw.len() as u64,
+        Err(lmdb::Error::NotFound) => return Ok(0),
+        Err(error) => return Err(error),
+    };
+    txn.del(db, key, None)?;
+    Ok(size)
+}
+
 /// Returns all `Transform::WriteTransfer`s from the execution effects if this is an
 /// `ExecutionResult::Success`, or an empty `Vec` if `ExecutionResult::Failure`.
 pub fn successful_transfers(execution_result: &ExecutionResult) -> Vec<Transfer> {
@@ -208,6 +208,29 @@ impl DisjointSequences {
             true
         })
     }
+
+    /// Reduces the sequence(s), keeping all entries above and including `min_value`.  If
+    /// `min_value` is not already included in a sequence, it will not be added.
+    ///
+    /// If the current lowest value is higher than `min_value`, or if there are no sequences, this
+    /// has no effect.
+    pub(super) fn truncate_below(&mut self, min_value: u64) {
+        self.sequences.retain_mut(|sequence| {
+            if sequence.low >= min_value {
+                // Keep this sequence unchanged.
+                return true;
+            }
+
+            if sequence.high < min_value {
+                // Delete this entire sequence.
+                return false;
+            }
+
+            // This sequence contains `min_value`, so keep the sequence, but raise its low value.
+            sequence.low = min_value;
+            true
+        })
+    }
 }
 #[cfg(test)]
 impl DisjointSequences {
@@ -452,6 +475,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_truncate_below() {
+        const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
+        const SEQ_MID: Sequence = Sequence { high: 6, low: 6 };
+        const SEQ_LOW: Sequence = Sequence { high: 3, low: 1 };
+        let initial_sequences = DisjointSequences {
+            sequences: vec![SEQ_HIGH, SEQ_MID, SEQ_LOW],
+        };
+
+        // Truncating with `min_value` less or equal to current lowest value should be a no-op.
+        let mut disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(0);
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+        disjoint_sequences.truncate_below(SEQ_LOW.low);
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+
+        // Truncating with `min_value` between two sequences should cause the lower sequences to
+        // get removed and the higher ones retained unchanged.
+        disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(SEQ_MID.low - 1);
+        assert_eq!(disjoint_sequences.sequences, vec![SEQ_HIGH, SEQ_MID]);
+
+        // Truncating with `min_value` within a sequence should cause that sequence to get updated.
+        disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(SEQ_HIGH.low + 1);
+        assert_eq!(
+            disjoint_sequences.sequences,
+            vec![new_sequence(SEQ_HIGH.high, SEQ_HIGH.low + 1)]
+        );
+
+        // Truncating with `min_value` higher than the highest value should cause all sequences to
+        // get removed.
+        disjoint_sequences = initial_sequences.clone();
+        disjoint_sequences.truncate_below(SEQ_HIGH.high + 1);
+        assert!(disjoint_sequences.sequences.is_empty());
+    }
+
     #[test]
     fn should_truncate() {
         const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
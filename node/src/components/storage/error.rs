@@ -9,7 +9,7 @@ use casper_types::{bytesrepr, crypto, EraId};
 use super::lmdb_ext::LmdbExtError;
 use crate::types::{
     error::BlockValidationError, BlockBody, BlockHash, BlockHashAndHeight, BlockHeader, DeployHash,
-    FinalitySignature, FinalitySignatureId,
+    ExitCode, FinalitySignature, FinalitySignatureId,
 };
 
 /// A fatal storage component error.
@@ -167,6 +167,43 @@ pub enum FatalStorageError {
     Prometheus(#[from] prometheus::Error),
 }
 
+impl FatalStorageError {
+    /// Returns the exit code the node should terminate with on this error.
+    ///
+    /// Only errors indicating corrupted or inconsistent stored data require a resync; others, such
+    /// as I/O errors or an exhausted database size, may be resolved by restarting the node.
+    pub(crate) fn exit_code(&self) -> ExitCode {
+        match self {
+            FatalStorageError::InternalStorage(
+                LmdbExtError::LmdbCorrupted(_) | LmdbExtError::DataCorrupted(_),
+            )
+            | FatalStorageError::DuplicateBlockIndex { .. }
+            | FatalStorageError::DuplicateEraIdIndex { .. }
+            | FatalStorageError::DuplicateDeployIndex { .. }
+            | FatalStorageError::BlockValidation(_)
+            | FatalStorageError::BlockHeaderNotStoredUnderItsHash { .. }
+            | FatalStorageError::NoBlockHeaderForBlockBody { .. }
+            | FatalStorageError::SignatureVerification(_)
+            | FatalStorageError::CorruptedBlockSignatureIndex { .. }
+            | FatalStorageError::InvalidSwitchBlock(_)
+            | FatalStorageError::UnexpectedBlockBodyPart { .. }
+            | FatalStorageError::UnexpectedDeserializationFailure(_)
+            | FatalStorageError::ApprovalsHashesLengthMismatch { .. } => ExitCode::ResyncRequired,
+            FatalStorageError::CreateDatabaseDirectory(..)
+            | FatalStorageError::InternalStorage(
+                LmdbExtError::ResourceExhausted(_) | LmdbExtError::Other(_),
+            )
+            | FatalStorageError::FailedToOverwriteBlock
+            | FatalStorageError::UnableToMoveFile { .. }
+            | FatalStorageError::MissingStorageFiles { .. }
+            | FatalStorageError::StoredItemSerializationFailure(_)
+            | FatalStorageError::UnexpectedFinalizedApprovals { .. }
+            | FatalStorageError::UnexpectedSerializationFailure(_)
+            | FatalStorageError::Prometheus(_) => ExitCode::Abort,
+        }
+    }
+}
+
 // We wholesale wrap lmdb errors and treat them as internal errors here.
 impl From<lmdb::Error> for FatalStorageError {
     fn from(err: lmdb::Error) -> Self {
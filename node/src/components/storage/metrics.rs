@@ -1,4 +1,19 @@
-use prometheus::{self, IntGauge, Registry};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use prometheus::{
+    self,
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
+
+use casper_types::Timestamp;
 
 use crate::unregister_metric;
 
@@ -13,6 +28,105 @@ const LOWEST_AVAILABLE_BLOCK_NAME: &str = "lowest_available_block_height";
 const LOWEST_AVAILABLE_BLOCK_HELP: &str =
     "lowest height of the available block range (the highest contiguous chain of complete blocks)";
 
+const HIGHEST_BLOCK_HEIGHT_NAME: &str = "highest_block_height";
+const HIGHEST_BLOCK_HEIGHT_HELP: &str = "height of the highest complete block";
+
+const HIGHEST_BLOCK_ERA_NAME: &str = "highest_block_era";
+const HIGHEST_BLOCK_ERA_HELP: &str = "era of the highest complete block";
+
+const TIME_SINCE_LAST_BLOCK_NAME: &str = "time_since_last_block_seconds";
+const TIME_SINCE_LAST_BLOCK_HELP: &str =
+    "seconds elapsed since the timestamp of the highest complete block";
+
+const DB_SIZE_NAME: &str = "storage_db_size_bytes";
+const DB_SIZE_HELP: &str = "size on disk of the storage databases in bytes";
+
+const MAP_UTILIZATION_NAME: &str = "storage_map_utilization";
+const MAP_UTILIZATION_HELP: &str = "fraction of the storage memory map in use";
+
+const PUT_LATENCY_NAME: &str = "storage_put_latency_seconds";
+const PUT_LATENCY_HELP: &str = "time in seconds to handle a storage request writing to a database";
+
+const GET_LATENCY_NAME: &str = "storage_get_latency_seconds";
+const GET_LATENCY_HELP: &str =
+    "time in seconds to handle a storage request reading from a database";
+
+const TRANSACTIONS_NAME: &str = "storage_transactions";
+const TRANSACTIONS_HELP: &str = "number of storage requests handled";
+
+const DATABASE_LABEL: &str = "database";
+const OPERATION_LABEL: &str = "operation";
+
+/// Label of the databases holding blocks, their metadata and approvals hashes.
+pub(super) const BLOCKS_DATABASE: &str = "blocks";
+/// Label of the databases holding deploys, their metadata, transfers and finalized approvals.
+pub(super) const DEPLOYS_DATABASE: &str = "deploys";
+/// Label of the database holding the persisted state of components.
+///
+/// Global state is held by the contract runtime's trie store, not by storage, so it is not covered
+/// by the storage metrics.
+pub(super) const COMPONENT_STATE_DATABASE: &str = "component_state";
+
+/// Latency buckets from 10 µs to about 5 s.
+const LATENCY_BUCKET_START: f64 = 0.000_01;
+const LATENCY_BUCKET_FACTOR: f64 = 2.0;
+const LATENCY_BUCKET_COUNT: usize = 20;
+
+/// The kind of access a storage request makes to a database.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Operation {
+    Get,
+    Put,
+}
+
+impl Operation {
+    fn label(self) -> &'static str {
+        match self {
+            Operation::Get => "get",
+            Operation::Put => "put",
+        }
+    }
+}
+
+/// A gauge holding the number of seconds elapsed since a given timestamp.
+///
+/// The value is computed whenever the gauge is collected, so it keeps increasing between updates.
+#[derive(Clone, Debug)]
+pub(super) struct TimeSinceGauge {
+    gauge: IntGauge,
+    /// Milliseconds since the Unix epoch, or zero if no timestamp has been set yet.
+    since: Arc<AtomicU64>,
+}
+
+impl TimeSinceGauge {
+    fn new(name: &str, help: &str) -> Result<Self, prometheus::Error> {
+        Ok(TimeSinceGauge {
+            gauge: IntGauge::new(name, help)?,
+            since: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Sets the timestamp to measure the elapsed time from.
+    pub(super) fn set(&self, timestamp: Timestamp) {
+        self.since.store(timestamp.millis(), Ordering::Relaxed);
+    }
+}
+
+impl Collector for TimeSinceGauge {
+    fn desc(&self) -> Vec<&Desc> {
+        self.gauge.desc()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let since = self.since.load(Ordering::Relaxed);
+        if since != 0 {
+            let elapsed_secs = Timestamp::now().millis().saturating_sub(since) / 1000;
+            self.gauge.set(elapsed_secs.try_into().unwrap_or(i64::MAX));
+        }
+        self.gauge.collect()
+    }
+}
+
 /// Metrics for the storage component.
 #[derive(Debug)]
 pub struct Metrics {
@@ -20,6 +134,18 @@ pub struct Metrics {
     pub(super) chain_height: IntGauge,
     pub(super) highest_available_block: IntGauge,
     pub(super) lowest_available_block: IntGauge,
+    pub(super) highest_block_height: IntGauge,
+    pub(super) highest_block_era: IntGauge,
+    pub(super) time_since_last_block: TimeSinceGauge,
+    /// Size on disk of the databases, by database.
+    pub(super) db_size: IntGaugeVec,
+    /// Fraction of the LMDB memory map in use.
+    pub(super) map_utilization: Gauge,
+    put_latency: HistogramVec,
+    get_latency: HistogramVec,
+    transactions: IntCounterVec,
+    /// The last time the database sizes and map utilization were updated.
+    pub(super) db_stats_updated: Option<Instant>,
     registry: Registry,
 }
 
@@ -31,18 +157,78 @@ impl Metrics {
             IntGauge::new(HIGHEST_AVAILABLE_BLOCK_NAME, HIGHEST_AVAILABLE_BLOCK_HELP)?;
         let lowest_available_block =
             IntGauge::new(LOWEST_AVAILABLE_BLOCK_NAME, LOWEST_AVAILABLE_BLOCK_HELP)?;
+        let highest_block_height =
+            IntGauge::new(HIGHEST_BLOCK_HEIGHT_NAME, HIGHEST_BLOCK_HEIGHT_HELP)?;
+        let highest_block_era = IntGauge::new(HIGHEST_BLOCK_ERA_NAME, HIGHEST_BLOCK_ERA_HELP)?;
+        let time_since_last_block =
+            TimeSinceGauge::new(TIME_SINCE_LAST_BLOCK_NAME, TIME_SINCE_LAST_BLOCK_HELP)?;
+        let db_size = IntGaugeVec::new(Opts::new(DB_SIZE_NAME, DB_SIZE_HELP), &[DATABASE_LABEL])?;
+        let map_utilization = Gauge::new(MAP_UTILIZATION_NAME, MAP_UTILIZATION_HELP)?;
+        let buckets = prometheus::exponential_buckets(
+            LATENCY_BUCKET_START,
+            LATENCY_BUCKET_FACTOR,
+            LATENCY_BUCKET_COUNT,
+        )?;
+        let put_latency = HistogramVec::new(
+            HistogramOpts::new(PUT_LATENCY_NAME, PUT_LATENCY_HELP).buckets(buckets.clone()),
+            &[DATABASE_LABEL],
+        )?;
+        let get_latency = HistogramVec::new(
+            HistogramOpts::new(GET_LATENCY_NAME, GET_LATENCY_HELP).buckets(buckets),
+            &[DATABASE_LABEL],
+        )?;
+        let transactions = IntCounterVec::new(
+            Opts::new(TRANSACTIONS_NAME, TRANSACTIONS_HELP),
+            &[DATABASE_LABEL, OPERATION_LABEL],
+        )?;
 
         registry.register(Box::new(chain_height.clone()))?;
         registry.register(Box::new(highest_available_block.clone()))?;
         registry.register(Box::new(lowest_available_block.clone()))?;
+        registry.register(Box::new(highest_block_height.clone()))?;
+        registry.register(Box::new(highest_block_era.clone()))?;
+        registry.register(Box::new(time_since_last_block.clone()))?;
+        registry.register(Box::new(db_size.clone()))?;
+        registry.register(Box::new(map_utilization.clone()))?;
+        registry.register(Box::new(put_latency.clone()))?;
+        registry.register(Box::new(get_latency.clone()))?;
+        registry.register(Box::new(transactions.clone()))?;
 
         Ok(Metrics {
             chain_height,
             highest_available_block,
             lowest_available_block,
+            highest_block_height,
+            highest_block_era,
+            time_since_last_block,
+            db_size,
+            map_utilization,
+            put_latency,
+            get_latency,
+            transactions,
+            db_stats_updated: None,
             registry: registry.clone(),
         })
     }
+
+    /// Records a storage operation on the given database which took `elapsed`.
+    pub(super) fn observe_operation(
+        &self,
+        operation: Operation,
+        database: &str,
+        elapsed: Duration,
+    ) {
+        let latency = match operation {
+            Operation::Get => &self.get_latency,
+            Operation::Put => &self.put_latency,
+        };
+        latency
+            .with_label_values(&[database])
+            .observe(elapsed.as_secs_f64());
+        self.transactions
+            .with_label_values(&[database, operation.label()])
+            .inc();
+    }
 }
 
 impl Drop for Metrics {
@@ -50,5 +236,13 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.chain_height);
         unregister_metric!(self.registry, self.highest_available_block);
         unregister_metric!(self.registry, self.lowest_available_block);
+        unregister_metric!(self.registry, self.highest_block_height);
+        unregister_metric!(self.registry, self.highest_block_era);
+        unregister_metric!(self.registry, self.time_since_last_block);
+        unregister_metric!(self.registry, self.db_size);
+        unregister_metric!(self.registry, self.map_utilization);
+        unregister_metric!(self.registry, self.put_latency);
+        unregister_metric!(self.registry, self.get_latency);
+        unregister_metric!(self.registry, self.transactions);
     }
 }
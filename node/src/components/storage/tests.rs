@@ -1,29 +1,33 @@
 //! Unit tests for the storage component.
 
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::{self, File},
+    io,
     iter::{self, FromIterator},
+    path::PathBuf,
     rc::Rc,
     sync::Arc,
 };
 
 use lmdb::Transaction;
+use prometheus::Registry;
 use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use casper_types::{
     generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
-    Transfer, Transform, TransformEntry, URef, U512,
+    EraId, ExecutionEffect, ExecutionResult, ExecutionResultFormat, Key, ProtocolVersion,
+    PublicKey, SecretKey, TimeDiff, Transfer, Transform, TransformEntry, URef, U512,
 };
 
 use super::{
     initialize_block_metadata_db,
     lmdb_ext::{deserialize_internal, serialize_internal, TransactionExt, WriteTransactionExt},
     move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage, FORCE_RESYNC_FILE_NAME,
+    FatalStorageError, Storage, FORCE_RESYNC_FILE_NAME,
 };
 use crate::{
     components::fetcher::{FetchItem, FetchResponse},
@@ -33,11 +37,12 @@ use crate::{
     },
     testing::{ComponentHarness, UnitTestEvent},
     types::{
-        sync_leap_validation_metadata::SyncLeapValidationMetaData, AvailableBlockRange, Block,
-        BlockHash, BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
-        BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployMetadata,
-        DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature, LegacyDeploy,
-        SyncLeapIdentifier, TestBlockBuilder,
+        sync_leap_validation_metadata::SyncLeapValidationMetaData, Approval, AvailableBlockRange,
+        Block, BlockExecutionResultsOrChunkId, BlockHash, BlockHashAndHeight,
+        BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata, BlockSignatures, Chainspec,
+        ChainspecRawBytes, Deploy, DeployHash, DeployMetadata, DeployMetadataExt,
+        DeployWithFinalizedApprovals, ExitCode, FinalitySignature, FinalizedApprovals,
+        LegacyDeploy, SyncLeapIdentifier, TestBlockBuilder,
     },
     utils::{Loadable, WithDir},
 };
@@ -57,6 +62,7 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        compact_execution_results: false,
     }
 }
 
@@ -552,6 +558,72 @@ fn read_block_by_height_with_available_block_range() {
     assert!(get_block_header_at_height(&mut storage, 14, true).is_none());
 }
 
+#[test]
+fn should_update_chain_metrics_on_block_completion() {
+    let mut harness = ComponentHarness::default();
+    let registry = Registry::new();
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), new_config(&harness)),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        Some(&registry),
+        false,
+    )
+    .expect("could not create storage component fixture");
+
+    let block = Arc::new(Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::new(7),
+        33,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        None,
+    ));
+    assert!(put_complete_block(&mut harness, &mut storage, block));
+
+    let metrics = storage.metrics.as_ref().expect("should have metrics");
+    assert_eq!(metrics.highest_block_height.get(), 33);
+    assert_eq!(metrics.highest_block_era.get(), 7);
+    let time_since_last_block = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == "time_since_last_block_seconds")
+        .expect("should have registered time since last block");
+    assert!(
+        time_since_last_block.get_metric()[0]
+            .get_gauge()
+            .get_value()
+            >= 0.0
+    );
+}
+
+#[test]
+fn should_report_database_sizes() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let (sizes, map_utilization) = storage.read_db_stats().expect("should read statistics");
+    let empty_blocks_size = sizes[0].1;
+    assert!(map_utilization > 0.0 && map_utilization <= 1.0);
+
+    let block = Arc::new(Block::random(&mut harness.rng));
+    assert!(put_complete_block(&mut harness, &mut storage, block));
+    let state = vec![1; 4096];
+    storage
+        .write_state_store(Cow::Borrowed(&b"key"[..]), &state)
+        .expect("should write state");
+
+    let (sizes, _) = storage.read_db_stats().expect("should read statistics");
+    let labels: Vec<_> = sizes.iter().map(|(label, _)| *label).collect();
+    assert_eq!(labels, vec!["blocks", "deploys", "component_state"]);
+    assert!(sizes[0].1 > empty_blocks_size);
+    assert!(sizes[2].1 >= state.len() as u64);
+}
+
 #[test]
 fn can_retrieve_block_by_height() {
     let mut harness = ComponentHarness::default();
@@ -844,6 +916,86 @@ fn can_retrieve_store_and_load_deploys() {
     }
 }
 
+#[test]
+fn can_retrieve_deploy_by_original_or_finalized_approvals() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random(&mut harness.rng);
+    assert!(put_deploy(
+        &mut harness,
+        &mut storage,
+        Arc::new(deploy.clone())
+    ));
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let finalized_approvals = BTreeSet::from([Approval::create(deploy.hash(), &secret_key)]);
+    let finalized_deploy = deploy.clone().with_approvals(finalized_approvals.clone());
+    let was_new = harness.send_request(&mut storage, |responder| {
+        StorageRequest::StoreFinalizedApprovals {
+            deploy_hash: *deploy.hash(),
+            finalized_approvals: FinalizedApprovals::new(finalized_approvals),
+            responder,
+        }
+        .into()
+    });
+    assert!(was_new);
+    let other_secret_key = SecretKey::random(&mut harness.rng);
+
+    let mut get_deploy = |deploy_id| {
+        harness.send_request(&mut storage, move |responder| {
+            StorageRequest::GetDeploy {
+                deploy_id,
+                responder,
+            }
+            .into()
+        })
+    };
+
+    assert_eq!(get_deploy(deploy.fetch_id()), Some(deploy.clone()));
+    assert_eq!(
+        get_deploy(finalized_deploy.fetch_id()),
+        Some(finalized_deploy)
+    );
+
+    let other_approvals = BTreeSet::from([Approval::create(deploy.hash(), &other_secret_key)]);
+    let other_deploy = deploy.with_approvals(other_approvals);
+    assert_eq!(get_deploy(other_deploy.fetch_id()), None);
+}
+
+#[test]
+fn should_write_exported_block_with_its_deploys_and_signatures() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random(&mut harness.rng);
+    let block = Block::random_with_deploys(&mut harness.rng, iter::once(&deploy));
+    let block_signatures = random_signatures(&mut harness.rng, &block);
+    storage
+        .write_exported_block(
+            &block,
+            &[(deploy.clone(), None)],
+            None,
+            Some(&block_signatures),
+        )
+        .expect("should write exported block");
+
+    assert_eq!(
+        storage.read_block(block.hash()).unwrap(),
+        Some(block.clone())
+    );
+    assert_eq!(
+        storage.read_deploy_by_hash(deploy.hash()).unwrap(),
+        Some(deploy)
+    );
+    assert_eq!(
+        storage.read_block_signatures(block.hash()).unwrap(),
+        Some(block_signatures)
+    );
+    // The block is not complete, as its global state is not part of the export.
+    assert!(get_highest_complete_block(&mut harness, &mut storage).is_none());
+}
+
 #[test]
 fn should_retrieve_deploys_era_ids() {
     let mut harness = ComponentHarness::default();
@@ -1021,7 +1173,8 @@ fn store_execution_results_for_two_blocks() {
     assert_eq!(
         first_metadata,
         DeployMetadata {
-            execution_results: expected_per_block_results.clone()
+            execution_results: expected_per_block_results.clone(),
+            ..Default::default()
         }
     );
 
@@ -1040,11 +1193,57 @@ fn store_execution_results_for_two_blocks() {
     assert_eq!(
         second_metadata,
         DeployMetadata {
-            execution_results: expected_per_block_results
+            execution_results: expected_per_block_results,
+            ..Default::default()
         }
     );
 }
 
+#[test]
+fn store_compact_execution_results() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+    storage.compact_execution_results = true;
+
+    let deploy = Deploy::random(&mut harness.rng);
+    let block_hash = BlockHash::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+
+    let execution_result: ExecutionResult = harness.rng.gen();
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.hash(), execution_result.clone());
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    // Only the compact result is stored, and it is labelled as such.
+    let (_, metadata_ext) =
+        get_naive_deploy_and_metadata(&mut harness, &mut storage, *deploy.hash())
+            .expect("missing deploy");
+    let mut expected_per_block_results = HashMap::new();
+    expected_per_block_results.insert(block_hash, execution_result.compact());
+    let metadata = match metadata_ext {
+        DeployMetadataExt::Metadata(metadata) => metadata,
+        other => panic!("unexpected metadata: {:?}", other),
+    };
+    assert_eq!(
+        metadata,
+        DeployMetadata {
+            execution_results: expected_per_block_results,
+            compact_blocks: iter::once(block_hash).collect(),
+        }
+    );
+    assert_eq!(
+        metadata.execution_result_format(&block_hash),
+        ExecutionResultFormat::Compact
+    );
+
+    // Compact results are not served, as they can't be verified against the checksum.
+    let id = BlockExecutionResultsOrChunkId::new(block_hash);
+    assert!(storage
+        .read_block_execution_results_or_chunk(&id)
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn store_random_execution_results() {
     let mut harness = ComponentHarness::default();
@@ -1150,7 +1349,8 @@ fn store_random_execution_results() {
         assert_eq!(
             metadata,
             DeployMetadata {
-                execution_results: raw_meta.clone()
+                execution_results: raw_meta.clone(),
+                ..Default::default()
             }
         );
     }
@@ -1988,6 +2188,57 @@ fn should_get_block_header_by_height() {
     assert_eq!(expected_header, maybe_block_header.unwrap());
 }
 
+#[test]
+fn should_put_block_header_with_metadata() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = TestBlockBuilder::new()
+        .switch_block(true)
+        .build(&mut harness.rng);
+    let block_hash = *block.hash();
+    let era_id = block.header().era_id();
+
+    // Some signatures may already be stored for the block.
+    let stored_signatures = random_signatures(&mut harness.rng, &block);
+    assert!(put_block_signatures(
+        &mut harness,
+        &mut storage,
+        stored_signatures.clone()
+    ));
+
+    let block_signatures = random_signatures(&mut harness.rng, &block);
+    let block_header_with_metadata = BlockHeaderWithMetadata {
+        block_header: block.header().clone(),
+        block_signatures: block_signatures.clone(),
+    };
+    let was_stored = harness.send_request(&mut storage, move |responder| {
+        StorageRequest::PutBlockHeaderWithMetadata {
+            block_header_with_metadata: Box::new(block_header_with_metadata),
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    assert!(was_stored);
+
+    // The header is indexed and the new signatures are merged with the stored ones.
+    let switch_block = storage
+        .read_switch_block_header_with_metadata(era_id)
+        .unwrap()
+        .expect("should have switch block");
+    assert_eq!(&switch_block.block_header, block.header());
+    let mut expected_signatures = stored_signatures;
+    for (public_key, signature) in block_signatures.proofs {
+        expected_signatures.insert_proof(public_key, signature);
+    }
+    assert_eq!(switch_block.block_signatures, expected_signatures);
+    assert_eq!(
+        get_block_signatures(&mut storage, block_hash),
+        Some(expected_signatures)
+    );
+}
+
 #[ignore]
 #[test]
 fn check_force_resync_with_marker_file() {
@@ -2235,3 +2486,18 @@ fn should_initialize_block_metadata_db() {
     assert_signatures(&storage, *block_3.hash(), vec![]);
     assert_signatures(&storage, *block_4.hash(), vec![]);
 }
+
+#[test]
+fn should_require_resync_only_for_corrupted_storage() {
+    let corrupted = FatalStorageError::from(lmdb::Error::Corrupted);
+    assert_eq!(corrupted.exit_code(), ExitCode::ResyncRequired);
+
+    let map_full = FatalStorageError::from(lmdb::Error::MapFull);
+    assert_eq!(map_full.exit_code(), ExitCode::Abort);
+
+    let io_error = FatalStorageError::CreateDatabaseDirectory(
+        PathBuf::from("/nonexistent"),
+        io::Error::from(io::ErrorKind::PermissionDenied),
+    );
+    assert_eq!(io_error.exit_code(), ExitCode::Abort);
+}
@@ -57,6 +57,26 @@ fn new_sync_leaper_has_no_activity() {
     assert!(matches!(sync_leaper.leap_status(), LeapState::Idle));
 }
 
+#[test]
+fn purged_sync_leaper_has_no_activity() {
+    let mut rng = TestRng::new();
+
+    let mut sync_leaper = make_sync_leaper(&mut rng);
+
+    let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(BlockHash::random(&mut rng));
+    let peers_to_ask = vec![NodeId::random(&mut rng)];
+    let _ = sync_leaper.register_leap_attempt(sync_leap_identifier, peers_to_ask);
+    assert!(matches!(
+        sync_leaper.leap_status(),
+        LeapState::Awaiting { .. }
+    ));
+
+    // Purging is how the sync leaper is restarted after panicking, and abandons the leap.
+    sync_leaper.purge();
+    assert!(sync_leaper.peers().is_none());
+    assert!(matches!(sync_leaper.leap_status(), LeapState::Idle));
+}
+
 #[test]
 fn register_leap_attempt_no_peers() {
     let mut rng = TestRng::new();
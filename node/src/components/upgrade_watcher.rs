@@ -7,6 +7,8 @@
 //! <https://casperlabs.atlassian.net/wiki/spaces/EN/pages/135528449/Genesis+Process+Specification>
 //! for full details.
 
+mod metrics;
+
 use std::{
     fmt::{self, Display, Formatter},
     fs, io,
@@ -16,6 +18,7 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
+use prometheus::Registry;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -30,16 +33,19 @@ use casper_types::{
 use crate::{
     components::{Component, ComponentState, InitializedComponent},
     effect::{
-        announcements::UpgradeWatcherAnnouncement, requests::UpgradeWatcherRequest, EffectBuilder,
-        EffectExt, Effects,
+        announcements::UpgradeWatcherAnnouncement,
+        requests::{StorageRequest, UpgradeWatcherRequest},
+        EffectBuilder, EffectExt, Effects,
     },
     reactor::main_reactor::MainEvent,
     types::{
         chainspec::{ProtocolConfig, CHAINSPEC_FILENAME},
-        ActivationPoint, Chainspec,
+        ActivationPoint, Chainspec, ChainspecRawBytes,
     },
+    utils::Loadable,
     NodeRng,
 };
+use metrics::Metrics;
 
 const COMPONENT_NAME: &str = "upgrade_watcher";
 
@@ -70,6 +76,11 @@ pub(crate) enum Event {
     CheckForNextUpgrade,
     /// If the result of checking for an upgrade is successful, it is passed here.
     GotNextUpgrade(Option<NextUpgrade>),
+    /// The result of validating the staged upgrade, if any.
+    ValidatedNextUpgrade {
+        maybe_next_upgrade: Option<NextUpgrade>,
+        problems: Vec<String>,
+    },
 }
 
 impl Display for Event {
@@ -90,6 +101,23 @@ impl Display for Event {
             Event::GotNextUpgrade(None) => {
                 write!(formatter, "no upgrade detected")
             }
+            Event::ValidatedNextUpgrade {
+                maybe_next_upgrade: Some(next_upgrade),
+                problems,
+            } => {
+                write!(
+                    formatter,
+                    "validated {} with {} problem(s)",
+                    next_upgrade,
+                    problems.len()
+                )
+            }
+            Event::ValidatedNextUpgrade {
+                maybe_next_upgrade: None,
+                ..
+            } => {
+                write!(formatter, "no upgrade to validate")
+            }
         }
     }
 }
@@ -116,6 +144,10 @@ pub(crate) enum Error {
         error: io::Error,
     },
 
+    /// Failed to register metrics.
+    #[error("failed to register metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
+
     /// No subdirectory representing a semver version was found in the given directory.
     #[error("failed to get a valid version from subdirs in {}", dir.display())]
     NoVersionSubdirFound {
@@ -169,15 +201,21 @@ impl Display for NextUpgrade {
     }
 }
 
-#[derive(Clone, DataSize, Debug)]
+#[derive(DataSize, Debug)]
 pub(crate) struct UpgradeWatcher {
     current_version: ProtocolVersion,
+    network_name: String,
     config: Config,
     /// The path to the folder where all chainspec and upgrade_point files will be stored in
     /// subdirs corresponding to their versions.
     root_dir: PathBuf,
     state: ComponentState,
     next_upgrade: Option<NextUpgrade>,
+    /// The last staged upgrade validated, along with the problems found, so that each problem is
+    /// only warned about once.
+    last_validation: Option<(NextUpgrade, Vec<String>)>,
+    #[data_size(skip)]
+    metrics: Metrics,
 }
 
 impl UpgradeWatcher {
@@ -185,6 +223,7 @@ impl UpgradeWatcher {
         chainspec: &Chainspec,
         config: Config,
         chainspec_dir: P,
+        registry: &Registry,
     ) -> Result<Self, Error> {
         let root_dir = chainspec_dir
             .as_ref()
@@ -195,12 +234,22 @@ impl UpgradeWatcher {
         let current_version = chainspec.protocol_config.version;
         let next_upgrade = next_upgrade(root_dir.clone(), current_version);
 
+        let metrics = Metrics::new(registry)?;
+        if let Some(upgrade) = &next_upgrade {
+            metrics
+                .next_upgrade_era
+                .set(upgrade.activation_point.era_id().value() as i64);
+        }
+
         let upgrade_watcher = UpgradeWatcher {
             current_version,
+            network_name: chainspec.network_config.name.clone(),
             config,
             root_dir,
             state: ComponentState::Uninitialized,
             next_upgrade,
+            last_validation: None,
+            metrics,
         };
 
         Ok(upgrade_watcher)
@@ -222,7 +271,7 @@ impl UpgradeWatcher {
         effect_builder: EffectBuilder<REv>,
     ) -> Effects<Event>
     where
-        REv: From<UpgradeWatcherAnnouncement> + Send,
+        REv: From<UpgradeWatcherAnnouncement> + From<StorageRequest> + Send,
     {
         if self.state != ComponentState::Initializing {
             return Effects::new();
@@ -233,23 +282,40 @@ impl UpgradeWatcher {
 
     fn check_for_next_upgrade<REv>(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
     where
-        REv: From<UpgradeWatcherAnnouncement> + Send,
+        REv: From<UpgradeWatcherAnnouncement> + From<StorageRequest> + Send,
     {
         let root_dir = self.root_dir.clone();
         let current_version = self.current_version;
+        let network_name = self.network_name.clone();
         let mut effects = async move {
-            let maybe_next_upgrade =
-                task::spawn_blocking(move || next_upgrade(root_dir, current_version))
-                    .await
-                    .unwrap_or_else(|error| {
-                        warn!(%error, "failed to join tokio task");
-                        None
-                    });
+            let current_era = effect_builder
+                .get_highest_complete_block_header_from_storage()
+                .await
+                .map(|header| header.era_id());
+            let (maybe_next_upgrade, problems) = task::spawn_blocking(move || {
+                let maybe_next_upgrade = next_upgrade(root_dir.clone(), current_version);
+                let problems = maybe_next_upgrade
+                    .as_ref()
+                    .map(|upgrade| validate_upgrade(&root_dir, upgrade, &network_name, current_era))
+                    .unwrap_or_default();
+                (maybe_next_upgrade, problems)
+            })
+            .await
+            .unwrap_or_else(|error| {
+                warn!(%error, "failed to join tokio task");
+                (None, vec![])
+            });
             effect_builder
                 .upgrade_watcher_announcement(maybe_next_upgrade)
-                .await
+                .await;
+            (maybe_next_upgrade, problems)
         }
-        .ignore();
+        .event(
+            |(maybe_next_upgrade, problems)| Event::ValidatedNextUpgrade {
+                maybe_next_upgrade,
+                problems,
+            },
+        );
 
         effects.extend(
             effect_builder
@@ -281,14 +347,39 @@ impl UpgradeWatcher {
             );
         }
 
+        self.metrics
+            .next_upgrade_era
+            .set(maybe_next_upgrade.map_or(0, |upgrade| {
+                upgrade.activation_point.era_id().value() as i64
+            }));
         self.next_upgrade = maybe_next_upgrade;
         Effects::new()
     }
+
+    fn handle_validated_next_upgrade(
+        &mut self,
+        maybe_next_upgrade: Option<NextUpgrade>,
+        problems: Vec<String>,
+    ) -> Effects<Event> {
+        let valid = maybe_next_upgrade.is_some() && problems.is_empty();
+        self.metrics.next_upgrade_valid.set(i64::from(valid));
+
+        let validation = maybe_next_upgrade.map(|upgrade| (upgrade, problems));
+        if validation != self.last_validation {
+            if let Some((upgrade, problems)) = &validation {
+                for problem in problems {
+                    warn!(%upgrade, %problem, "staged upgrade is invalid");
+                }
+            }
+            self.last_validation = validation;
+        }
+        Effects::new()
+    }
 }
 
 impl<REv> Component<REv> for UpgradeWatcher
 where
-    REv: From<Event> + From<UpgradeWatcherAnnouncement> + Send,
+    REv: From<Event> + From<UpgradeWatcherAnnouncement> + From<StorageRequest> + Send,
 {
     type Event = Event;
 
@@ -318,7 +409,10 @@ where
             }
             ComponentState::Initializing => match event {
                 Event::Initialize => self.start_checking_for_upgrades(effect_builder),
-                Event::Request(_) | Event::CheckForNextUpgrade | Event::GotNextUpgrade(_) => {
+                Event::Request(_)
+                | Event::CheckForNextUpgrade
+                | Event::GotNextUpgrade(_)
+                | Event::ValidatedNextUpgrade { .. } => {
                     warn!(
                         ?event,
                         name = <Self as Component<MainEvent>>::name(self),
@@ -339,6 +433,10 @@ where
                 Event::Request(request) => request.0.respond(self.next_upgrade).ignore(),
                 Event::CheckForNextUpgrade => self.check_for_next_upgrade(effect_builder),
                 Event::GotNextUpgrade(next_upgrade) => self.handle_got_next_upgrade(next_upgrade),
+                Event::ValidatedNextUpgrade {
+                    maybe_next_upgrade,
+                    problems,
+                } => self.handle_validated_next_upgrade(maybe_next_upgrade, problems),
             },
         }
     }
@@ -350,7 +448,7 @@ where
 
 impl<REv> InitializedComponent<REv> for UpgradeWatcher
 where
-    REv: From<Event> + From<UpgradeWatcherAnnouncement> + Send,
+    REv: From<Event> + From<UpgradeWatcherAnnouncement> + From<StorageRequest> + Send,
 {
     fn state(&self) -> &ComponentState {
         &self.state
@@ -485,9 +583,55 @@ fn next_upgrade(dir: PathBuf, current_version: ProtocolVersion) -> Option<NextUp
     Some(NextUpgrade::from(upgrade_point.protocol_config))
 }
 
+/// Checks that the staged upgrade can be activated, returning a description of each problem found.
+///
+/// The full chainspec is parsed and validated, and must be for the same network, with an era as
+/// its activation point which is later than `current_era`, if known.
+fn validate_upgrade(
+    dir: &Path,
+    upgrade: &NextUpgrade,
+    network_name: &str,
+    current_era: Option<EraId>,
+) -> Vec<String> {
+    let subdir = dir.join(dir_name_from_version(upgrade.protocol_version));
+    let chainspec = match <(Chainspec, ChainspecRawBytes)>::from_path(&subdir) {
+        Ok((chainspec, _)) => chainspec,
+        Err(error) => return vec![format!("failed to load chainspec: {}", error)],
+    };
+    debug!(%upgrade, chainspec_hash = %chainspec.hash(), "validating staged upgrade");
+
+    let mut problems = vec![];
+    if !chainspec.is_valid() {
+        problems.push("invalid chainspec".to_string());
+    }
+    if chainspec.network_config.name != network_name {
+        problems.push(format!(
+            "chainspec is for network {}, not {}",
+            chainspec.network_config.name, network_name
+        ));
+    }
+    match upgrade.activation_point {
+        ActivationPoint::Genesis(timestamp) => problems.push(format!(
+            "activation point must be an era, not the genesis timestamp {}",
+            timestamp
+        )),
+        ActivationPoint::EraId(activation_era) => {
+            if let Some(current_era) = current_era {
+                if activation_era <= current_era {
+                    problems.push(format!(
+                        "activation era {} is not later than the current era {}",
+                        activation_era, current_era
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
 #[cfg(test)]
 mod tests {
-    use casper_types::testing::TestRng;
+    use casper_types::{testing::TestRng, Timestamp};
 
     use super::*;
     use crate::{
@@ -675,13 +819,98 @@ mod tests {
         assert!(maybe_next_point(V0_9_9).is_none());
     }
 
+    #[test]
+    fn should_validate_upgrade() {
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let (chainspec, chainspec_raw_bytes) =
+            <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        // Stage the local chainspec file as is, apart from its protocol section, as a serialized
+        // `Chainspec` also contains the accounts, which don't belong in the chainspec file.
+        let mut chainspec_toml: toml::Value =
+            toml::from_slice(chainspec_raw_bytes.chainspec_bytes()).unwrap();
+        chainspec_toml["protocol"]["version"] = toml::Value::String(V1_0_3.to_string());
+        chainspec_toml["protocol"]["activation_point"] = toml::Value::Integer(10);
+        let subdir = tempdir.path().join(dir_name_from_version(V1_0_3));
+        fs::create_dir(&subdir).unwrap();
+        fs::write(
+            subdir.join(CHAINSPEC_FILENAME),
+            toml::to_string_pretty(&chainspec_toml).expect("should encode to toml"),
+        )
+        .expect("should install chainspec");
+        let network_name = chainspec.network_config.name.clone();
+
+        let upgrade = next_upgrade(tempdir.path().to_path_buf(), V1_0_0).unwrap();
+        let no_problems: Vec<String> = vec![];
+        assert_eq!(
+            validate_upgrade(tempdir.path(), &upgrade, &network_name, None),
+            no_problems
+        );
+        let current_era = Some(EraId::from(9));
+        assert_eq!(
+            validate_upgrade(tempdir.path(), &upgrade, &network_name, current_era),
+            no_problems
+        );
+
+        let problems = validate_upgrade(tempdir.path(), &upgrade, "other-network", None);
+        assert_eq!(problems.len(), 1);
+
+        let genesis_upgrade = NextUpgrade::new(ActivationPoint::Genesis(Timestamp::now()), V1_0_3);
+        let problems = validate_upgrade(tempdir.path(), &genesis_upgrade, &network_name, None);
+        assert_eq!(problems.len(), 1);
+
+        for current_era in [10, 11] {
+            let current_era = Some(EraId::from(current_era));
+            let problems = validate_upgrade(tempdir.path(), &upgrade, &network_name, current_era);
+            assert_eq!(problems.len(), 1);
+        }
+    }
+
+    #[test]
+    fn should_remember_last_validation() {
+        let _ = logging::init();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        let mut upgrade_watcher = UpgradeWatcher::new(
+            &chainspec,
+            Config::default(),
+            tempdir.path(),
+            &Registry::new(),
+        )
+        .unwrap();
+
+        let next_upgrade = NextUpgrade::new(ActivationPoint::EraId(EraId::from(10)), V1_0_3);
+        let problems = vec!["invalid chainspec".to_string()];
+        let _ = upgrade_watcher.handle_validated_next_upgrade(Some(next_upgrade), problems.clone());
+        assert_eq!(
+            upgrade_watcher.last_validation,
+            Some((next_upgrade, problems))
+        );
+        assert_eq!(upgrade_watcher.metrics.next_upgrade_valid.get(), 0);
+
+        let _ = upgrade_watcher.handle_validated_next_upgrade(Some(next_upgrade), vec![]);
+        assert_eq!(
+            upgrade_watcher.last_validation,
+            Some((next_upgrade, vec![]))
+        );
+        assert_eq!(upgrade_watcher.metrics.next_upgrade_valid.get(), 1);
+
+        let _ = upgrade_watcher.handle_validated_next_upgrade(None, vec![]);
+        assert!(upgrade_watcher.last_validation.is_none());
+        assert_eq!(upgrade_watcher.metrics.next_upgrade_valid.get(), 0);
+    }
+
     #[test]
     fn should_register_unstaged_upgrade() {
         let _ = logging::init();
         let tempdir = tempfile::tempdir().expect("should create temp dir");
         let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
-        let mut upgrade_watcher =
-            UpgradeWatcher::new(&chainspec, Config::default(), tempdir.path()).unwrap();
+        let mut upgrade_watcher = UpgradeWatcher::new(
+            &chainspec,
+            Config::default(),
+            tempdir.path(),
+            &Registry::new(),
+        )
+        .unwrap();
         assert!(upgrade_watcher.next_upgrade.is_none());
 
         let next_upgrade = NextUpgrade {
@@ -0,0 +1,43 @@
+use prometheus::{IntGauge, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the upgrade watcher component.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// The activation era of the next staged upgrade, or 0 if there is none.
+    pub(super) next_upgrade_era: IntGauge,
+    /// 1 if the next staged upgrade passed validation, 0 if it failed or there is none.
+    pub(super) next_upgrade_valid: IntGauge,
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates a new instance of the upgrade watcher metrics.
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let next_upgrade_era = IntGauge::new(
+            "upgrade_watcher_next_upgrade_era".to_string(),
+            "activation era of the next staged upgrade, or 0 if there is none".to_string(),
+        )?;
+        let next_upgrade_valid = IntGauge::new(
+            "upgrade_watcher_next_upgrade_valid".to_string(),
+            "1 if the next staged upgrade passed validation, otherwise 0".to_string(),
+        )?;
+
+        registry.register(Box::new(next_upgrade_era.clone()))?;
+        registry.register(Box::new(next_upgrade_valid.clone()))?;
+
+        Ok(Metrics {
+            next_upgrade_era,
+            next_upgrade_valid,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.next_upgrade_era);
+        unregister_metric!(self.registry, self.next_upgrade_valid);
+    }
+}
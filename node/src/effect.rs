@@ -98,7 +98,7 @@ pub(crate) mod requests;
 use std::{
     any::type_name,
     borrow::Cow,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
     mem,
@@ -107,11 +107,15 @@ use std::{
 };
 
 use datasize::DataSize;
-use futures::{channel::oneshot, future::BoxFuture, FutureExt};
+use futures::{
+    channel::oneshot,
+    future::{self, AbortHandle, BoxFuture},
+    FutureExt,
+};
 use once_cell::sync::Lazy;
 use serde::{Serialize, Serializer};
 use smallvec::{smallvec, SmallVec};
-use tokio::{sync::Semaphore, time};
+use tokio::{sync::Semaphore, task, time};
 use tracing::{debug, error, warn};
 
 use casper_execution_engine::{
@@ -124,9 +128,11 @@ use casper_execution_engine::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::Account, bytesrepr::Bytes, system::auction::EraValidators, Contract, ContractPackage,
-    EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff, Timestamp, Transfer, URef,
-    U512,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators,
+    Contract, ContractPackage, EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff,
+    Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
@@ -135,9 +141,10 @@ use crate::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange},
+        consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange, ValidatorPerformance},
         contract_runtime::{ContractRuntimeError, EraValidatorsRequest},
         deploy_acceptor,
+        deploy_buffer::DeployBufferStatus,
         diagnostics_port::StopAtSpec,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
@@ -146,36 +153,43 @@ use crate::{
     },
     contract_runtime::SpeculativeExecutionState,
     failpoints::FailpointActivation,
+    logging::LoggingConfig,
     reactor::{main_reactor::ReactorState, EventQueueHandle, QueueKind},
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
+        appendable_block::AppendableBlock, Approval, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
+        BlockHeaderWithMetadata, BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy,
+        DeployHash, DeployHeader, DeployId, DeployMetadataExt, DeployWithFinalizedApprovals,
+        ExitCode, FinalitySignature, FinalitySignatureId, FinalizedApprovals, FinalizedBlock,
+        LegacyDeploy, MetaBlock, MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
     },
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
+    FetcherConfig, GossipConfig,
 };
 use announcements::{
-    BlockAccumulatorAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
-    ControlAnnouncement, DeployAcceptorAnnouncement, DeployBufferAnnouncement, FatalAnnouncement,
+    BlockAccumulatorAnnouncement, ClockDriftAnnouncement, ConfigReloadedAnnouncement,
+    ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+    DeployAcceptorAnnouncement, DeployBufferAnnouncement, FatalAnnouncement,
     FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement,
     MetaBlockAnnouncement, PeerBehaviorAnnouncement, QueueDumpFormat, UnexecutedBlockAnnouncement,
     UpgradeWatcherAnnouncement,
 };
 use diagnostics_port::DumpConsensusStateRequest;
 use requests::{
-    AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest, BlockSynchronizerRequest,
-    BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
-    DeployBufferRequest, FetcherRequest, MakeBlockExecutableRequest, MarkBlockCompletedRequest,
-    MetricsRequest, NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, SetNodeStopRequest,
-    StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest,
+    AcceptDeployRequest, AddDeployApprovalsRequest, BeginGossipRequest, BlockAccumulatorRequest,
+    BlockSynchronizerRequest, BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest,
+    ContractRuntimeRequest, DeployBufferRequest, FetcherRequest, MakeBlockExecutableRequest,
+    MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
+    ReactorStatusRequest, SetNodeStopRequest, StorageRequest, SyncGlobalStateRequest,
+    TrieAccumulatorRequest, UpgradeWatcherRequest,
 };
 
 /// A resource that will never be available, thus trying to acquire it will wait forever.
 static UNOBTAINABLE: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(0));
 
+/// Limits the number of tasks run in parallel by `EffectBuilder::run_blocking` to one per core.
+static COMPUTE_POOL: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(num_cpus::get()));
+
 /// A pinned, boxed future that produces one or more events.
 pub(crate) type Effect<Ev> = BoxFuture<'static, Multiple<Ev>>;
 
@@ -361,6 +375,19 @@ impl<T> Serialize for AutoClosingResponder<T> {
     }
 }
 
+/// Handle to a timer set via [`EffectBuilder::set_cancellable_timeout`].
+///
+/// Dropping the handle does not cancel the timer.
+#[derive(Clone, DataSize, Debug)]
+pub(crate) struct TimerHandle(#[data_size(skip)] AbortHandle);
+
+impl TimerHandle {
+    /// Cancels the timer, preventing it from producing an event if it has not expired yet.
+    pub(crate) fn cancel(&self) {
+        self.0.abort()
+    }
+}
+
 /// Effect extension for futures, used to convert futures into actual effects.
 pub(crate) trait EffectExt: Future + Send {
     /// Finalizes a future into an effect that returns a single event.
@@ -639,13 +666,21 @@ impl<REv> EffectBuilder<REv> {
 
     /// Reports a fatal error.  Normally called via the `crate::fatal!()` macro.
     ///
-    /// Usually causes the node to cease operations quickly and exit/crash.
-    pub(crate) async fn fatal(self, file: &'static str, line: u32, msg: String)
+    /// Usually causes the node to cease operations quickly and exit with the given `exit_code`.
+    pub(crate) async fn fatal(self, file: &'static str, line: u32, msg: String, exit_code: ExitCode)
     where
         REv: From<FatalAnnouncement>,
     {
         self.event_queue
-            .schedule(FatalAnnouncement { file, line, msg }, QueueKind::Control)
+            .schedule(
+                FatalAnnouncement {
+                    file,
+                    line,
+                    msg,
+                    exit_code,
+                },
+                QueueKind::Control,
+            )
             .await
     }
 
@@ -656,6 +691,37 @@ impl<REv> EffectBuilder<REv> {
         Instant::now() - then
     }
 
+    /// Runs a CPU-heavy task, such as signature verification, off the reactor thread.
+    ///
+    /// The task runs on tokio's blocking thread pool, but at most one task per CPU core is run at a
+    /// time; any further tasks wait for a slot to become available.
+    pub(crate) async fn run_blocking<T, V>(self, task: T) -> V
+    where
+        T: FnOnce() -> V + Send + 'static,
+        V: Send + 'static,
+    {
+        // The semaphore is never closed, so acquiring a permit cannot fail.
+        let _permit = COMPUTE_POOL
+            .acquire()
+            .await
+            .expect("compute pool semaphore closed");
+        task::spawn_blocking(task)
+            .await
+            .expect("compute task panicked")
+    }
+
+    /// Sets a timeout which can be cancelled using the returned handle.
+    ///
+    /// The returned future resolves to `None` if the timer was cancelled before it expired, which
+    /// allows components to skip stale timeouts without having to handle a spurious event.
+    pub(crate) fn set_cancellable_timeout(
+        self,
+        timeout: Duration,
+    ) -> (TimerHandle, impl Future<Output = Option<Duration>>) {
+        let (timer, abort_handle) = future::abortable(self.set_timeout(timeout));
+        (TimerHandle(abort_handle), timer.map(Result::ok))
+    }
+
     /// Retrieve a snapshot of the nodes current metrics formatted as string.
     ///
     /// If an error occurred producing the metrics, `None` is returned.
@@ -772,6 +838,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the estimated offsets of the connected peers' clocks from ours, in milliseconds.
+    pub(crate) async fn get_peer_clock_offsets(self) -> BTreeMap<NodeId, i64>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::PeerClockOffsets { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets a map of the current network peers to their socket addresses.
     pub(crate) async fn network_peers(self) -> BTreeMap<NodeId, String>
     where
@@ -796,6 +874,30 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets up to `count` fully-connected network peers, lowest round-trip time first.
+    pub(crate) async fn get_low_rtt_peers(self, count: usize) -> Vec<NodeId>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::LowRttPeers { count, responder },
+            QueueKind::NetworkInfo,
+        )
+        .await
+    }
+
+    /// Orders the given peers by round-trip time, lowest first.
+    pub(crate) async fn rank_peers_by_rtt(self, peers: Vec<NodeId>) -> Vec<NodeId>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::RankByRtt { peers, responder },
+            QueueKind::NetworkInfo,
+        )
+        .await
+    }
+
     /// Announces which deploys have expired.
     pub(crate) async fn announce_expired_deploys(self, hashes: Vec<DeployHash>)
     where
@@ -918,7 +1020,7 @@ impl<REv> EffectBuilder<REv> {
         self,
         deploy: Arc<Deploy>,
         speculative_exec_at_block: Option<Box<BlockHeader>>,
-    ) -> Result<(), deploy_acceptor::Error>
+    ) -> Result<deploy_acceptor::DeployAcceptance, deploy_acceptor::Error>
     where
         REv: From<AcceptDeployRequest>,
     {
@@ -933,6 +1035,26 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Try to add approvals received from the JSON-RPC server to a deploy awaiting approvals.
+    pub(crate) async fn try_add_deploy_approvals(
+        self,
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+    ) -> Result<deploy_acceptor::DeployAcceptance, deploy_acceptor::Error>
+    where
+        REv: From<AddDeployApprovalsRequest>,
+    {
+        self.make_request(
+            |responder| AddDeployApprovalsRequest {
+                deploy_hash,
+                approvals,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Announces that a deploy not previously stored has now been accepted and stored.
     pub(crate) fn announce_new_deploy_accepted(
         self,
@@ -982,12 +1104,17 @@ impl<REv> EffectBuilder<REv> {
         self,
         deploy: Arc<Deploy>,
         source: Source,
+        error: deploy_acceptor::Error,
     ) -> impl Future<Output = ()>
     where
         REv: From<DeployAcceptorAnnouncement>,
     {
         self.event_queue.schedule(
-            DeployAcceptorAnnouncement::InvalidDeploy { deploy, source },
+            DeployAcceptorAnnouncement::InvalidDeploy {
+                deploy,
+                source,
+                error,
+            },
             QueueKind::Validation,
         )
     }
@@ -1005,6 +1132,43 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announces that the estimated clock offset started or stopped exceeding the tolerance.
+    pub(crate) async fn announce_clock_drift(self, offset_ms: i64, exceeds_tolerance: bool)
+    where
+        REv: From<ClockDriftAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ClockDriftAnnouncement {
+                    offset_ms,
+                    exceeds_tolerance,
+                },
+                QueueKind::Control,
+            )
+            .await
+    }
+
+    /// Announces that the node's configuration has been reloaded.
+    pub(crate) async fn announce_config_reloaded(
+        self,
+        logging: LoggingConfig,
+        gossip: GossipConfig,
+        fetcher: FetcherConfig,
+    ) where
+        REv: From<ConfigReloadedAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ConfigReloadedAnnouncement {
+                    logging: Box::new(logging),
+                    gossip: Box::new(gossip),
+                    fetcher: Box::new(fetcher),
+                },
+                QueueKind::Control,
+            )
+            .await
+    }
+
     /// Announces a committed Step success.
     pub(crate) async fn announce_commit_step_success(
         self,
@@ -1024,6 +1188,50 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announces that a deploy of the block being executed was processed.
+    ///
+    /// This is scheduled on the same queue as the block's meta block announcement, so that it is
+    /// always handled first.
+    pub(crate) async fn announce_deploy_processed(
+        self,
+        era_id: EraId,
+        block_height: u64,
+        deploy_hash: DeployHash,
+        deploy_header: Box<DeployHeader>,
+        execution_result: Box<ExecutionResult>,
+    ) where
+        REv: From<ContractRuntimeAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ContractRuntimeAnnouncement::DeployProcessed {
+                    era_id,
+                    block_height,
+                    deploy_hash,
+                    deploy_header,
+                    execution_result,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Announces that executing the block at the given height failed.
+    pub(crate) async fn announce_block_execution_failed(self, era_id: EraId, block_height: u64)
+    where
+        REv: From<ContractRuntimeAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                ContractRuntimeAnnouncement::BlockExecutionFailed {
+                    era_id,
+                    block_height,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Announces validators for upcoming era.
     pub(crate) async fn announce_upcoming_era_validators(
         self,
@@ -1251,6 +1459,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Puts a block header and its finality signatures to storage, in a single transaction.
+    pub(crate) async fn put_block_header_with_metadata_to_storage(
+        self,
+        block_header_with_metadata: Box<BlockHeaderWithMetadata>,
+    ) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutBlockHeaderWithMetadata {
+                block_header_with_metadata,
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
     /// Puts the requested block signatures into storage.
     ///
     /// If `signatures.proofs` is empty, no attempt to store will be made, an error will be logged,
@@ -1323,6 +1549,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Returns the headers of the stored blocks in which the given deploys were executed.
+    pub(crate) async fn get_executed_deploys(
+        self,
+        deploy_hashes: HashSet<DeployHash>,
+    ) -> HashMap<DeployHash, BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetExecutedDeploys {
+                deploy_hashes,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Requests the highest complete block.
     pub(crate) async fn get_highest_complete_block_from_storage(self) -> Option<Block>
     where
@@ -1359,6 +1603,42 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Checks whether the storage folder is writable.
+    pub(crate) async fn check_storage_writable(self) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::CheckWritable { responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Flushes all buffered storage writes to disk, returning whether it succeeded.
+    pub(crate) async fn sync_storage(self) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::Sync { responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Flushes all buffered global state writes to disk, returning whether it succeeded.
+    pub(crate) async fn sync_global_state_to_disk(self) -> bool
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SyncGlobalStateToDisk { responder },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
     /// Synchronize global state under the given root hash.
     pub(crate) async fn sync_global_state(
         self,
@@ -1560,6 +1840,44 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Stores the effect of the step run at the end of the era of the given switch block.
+    pub(crate) async fn put_step_effect_to_storage(
+        self,
+        block_hash: BlockHash,
+        step_effect: ExecutionEffect,
+    ) where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutStepEffect {
+                block_hash: Box::new(block_hash),
+                step_effect,
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Gets the effect of the step run at the end of the era of the given switch block from
+    /// storage.
+    pub(crate) async fn get_step_effect_from_storage(
+        self,
+        block_hash: BlockHash,
+    ) -> Option<ExecutionEffect>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetStepEffect {
+                block_hash,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Gets the requested deploys from the deploy store.
     pub(crate) async fn get_deploy_and_metadata_from_storage(
         self,
@@ -1662,6 +1980,41 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets a block header with its finality signatures, whether or not the block is complete.
+    ///
+    /// If `block_hash` is `None`, the highest stored block header is returned.
+    pub(crate) async fn get_block_header_with_metadata_from_storage(
+        self,
+        block_hash: Option<BlockHash>,
+    ) -> Option<BlockHeaderWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeaderWithMetadata {
+                block_hash,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the switch block header of the given era with its finality signatures.
+    pub(crate) async fn get_switch_block_header_with_metadata_from_storage(
+        self,
+        era_id: EraId,
+    ) -> Option<BlockHeaderWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetSwitchBlockHeaderWithMetadata { era_id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Fetches an item from a fetcher.
     pub(crate) async fn fetch<T>(
         self,
@@ -1724,6 +2077,44 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the number of deploys currently known to the deploy buffer.
+    pub(crate) async fn get_deploy_buffer_status(self) -> DeployBufferStatus
+    where
+        REv: From<DeployBufferRequest>,
+    {
+        self.make_request(
+            |responder| DeployBufferRequest::GetStatus { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the hashes of the deploys in the deploy buffer which have not been included in a
+    /// finalized block and have not expired.
+    pub(crate) async fn get_pending_deploy_hashes(self) -> Vec<DeployHash>
+    where
+        REv: From<DeployBufferRequest>,
+    {
+        self.make_request(
+            |responder| DeployBufferRequest::GetPendingDeployHashes { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Removes all deploys from the deploy buffer which have not been proposed yet, returning the
+    /// number of removed deploys.
+    pub(crate) async fn clear_pending_deploys(self) -> usize
+    where
+        REv: From<DeployBufferRequest>,
+    {
+        self.make_request(
+            |responder| DeployBufferRequest::ClearPending { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Enqueues a finalized block execution.
     pub(crate) async fn enqueue_block_for_execution(
         self,
@@ -1916,6 +2307,88 @@ impl<REv> EffectBuilder<REv> {
         }
     }
 
+    /// Reads the given accounts from global state in a single request, omitting any not found.
+    pub(crate) async fn get_accounts_from_global_state(
+        self,
+        state_root_hash: Digest,
+        account_hashes: BTreeSet<AccountHash>,
+    ) -> Result<BTreeMap<AccountHash, Account>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetAccounts {
+                state_root_hash,
+                account_hashes,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Returns the hashes of the deploys whose approvals don't satisfy the associated keys and
+    /// deployment thresholds of their accounts in global state as of the highest complete block.
+    ///
+    /// Each entry pairs a deploy's hash and account with the account hashes of its approvals'
+    /// signers.  Approvals by any of the `administrators` are always sufficient.  Returns an empty
+    /// set if there is no complete block yet.
+    pub(crate) async fn get_unauthorized_deploys(
+        self,
+        authorizations: Vec<(DeployHash, PublicKey, BTreeSet<AccountHash>)>,
+        administrators: BTreeSet<AccountHash>,
+    ) -> HashSet<DeployHash>
+    where
+        REv: From<StorageRequest> + From<ContractRuntimeRequest>,
+    {
+        let state_root_hash = match self.get_highest_complete_block_header_from_storage().await {
+            Some(block_header) => *block_header.state_root_hash(),
+            None => return HashSet::new(),
+        };
+        let authorizations: Vec<_> = authorizations
+            .into_iter()
+            .filter(|(_, _, authorization_keys)| administrators.is_disjoint(authorization_keys))
+            .collect();
+        if authorizations.is_empty() {
+            return HashSet::new();
+        }
+        let account_hashes = authorizations
+            .iter()
+            .map(|(_, public_key, _)| public_key.to_account_hash())
+            .collect();
+        let accounts = match self
+            .get_accounts_from_global_state(state_root_hash, account_hashes)
+            .await
+        {
+            Ok(accounts) => accounts,
+            Err(error) => {
+                warn!(%error, "failed to read deploy accounts from global state");
+                BTreeMap::new()
+            }
+        };
+        authorizations
+            .into_iter()
+            .filter_map(|(deploy_hash, public_key, authorization_keys)| {
+                let is_authorized = accounts
+                    .get(&public_key.to_account_hash())
+                    .map_or(false, |account| {
+                        account.can_authorize(&authorization_keys)
+                            && account.can_deploy_with(&authorization_keys)
+                    });
+                if is_authorized {
+                    return None;
+                }
+                debug!(
+                    %deploy_hash,
+                    %public_key,
+                    ?authorization_keys,
+                    "deploy approvals don't satisfy the account's associated keys"
+                );
+                Some(deploy_hash)
+            })
+            .collect()
+    }
+
     /// Retrieves the balance of a purse, returns `None` if no purse is present.
     pub(crate) async fn check_purse_balance(
         self,
@@ -2068,6 +2541,17 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns the participation of each validator in each open era, by era ID and public key.
+    pub(crate) async fn get_consensus_validator_performance(
+        self,
+    ) -> BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::ValidatorPerformance, QueueKind::Consensus)
+            .await
+    }
+
     /// Dump consensus state for a specific era, using the supplied function to serialize the
     /// output.
     pub(crate) async fn diagnostics_port_dump_consensus_state(
@@ -2272,9 +2756,72 @@ impl<REv> EffectBuilder<REv> {
 ///
 /// This macro is a convenient wrapper around `EffectBuilder::fatal` that inserts the `file!()` and
 /// `line!()` number automatically.
+///
+/// The node exits with `ExitCode::Abort` unless a different exit code is given, e.g.
+/// `fatal!(effect_builder, exit_code = ExitCode::ResyncRequired, "corrupt storage")`.
 #[macro_export]
 macro_rules! fatal {
+    ($effect_builder:expr, exit_code = $exit_code:expr, $($arg:tt)*) => {
+        $effect_builder.fatal(file!(), line!(), format!($($arg)*), $exit_code)
+    };
     ($effect_builder:expr, $($arg:tt)*) => {
-        $effect_builder.fatal(file!(), line!(), format!($($arg)*))
+        $effect_builder.fatal(
+            file!(),
+            line!(),
+            format!($($arg)*),
+            $crate::types::ExitCode::Abort,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use tokio::time;
+
+    use super::*;
+    use crate::{
+        reactor::{EventQueueHandle, QueueKind, Scheduler},
+        utils,
     };
+
+    fn effect_builder() -> EffectBuilder<u8> {
+        let scheduler = utils::leak(Scheduler::<u8>::new(QueueKind::weights(), None));
+        EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler))
+    }
+
+    #[tokio::test]
+    async fn run_blocking_should_run_task_off_the_calling_thread() {
+        let effect_builder = effect_builder();
+
+        let calling_thread = thread::current().id();
+        let task_thread = effect_builder.run_blocking(|| thread::current().id()).await;
+        assert_ne!(task_thread, calling_thread);
+    }
+
+    #[tokio::test]
+    async fn cancellable_timeout_should_expire_unless_cancelled() {
+        time::pause();
+        let effect_builder = effect_builder();
+
+        let (_timer, timeout) = effect_builder.set_cancellable_timeout(Duration::from_secs(10));
+        assert!(timeout.await.is_some());
+
+        let (timer, timeout) = effect_builder.set_cancellable_timeout(Duration::from_secs(10));
+        let timeout = tokio::spawn(timeout);
+        time::advance(Duration::from_secs(5)).await;
+        timer.cancel();
+        assert_eq!(timeout.await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn dropping_timer_handle_should_not_cancel_timeout() {
+        time::pause();
+        let effect_builder = effect_builder();
+
+        let (timer, timeout) = effect_builder.set_cancellable_timeout(Duration::from_secs(10));
+        drop(timer);
+        assert!(timeout.await.is_some());
+    }
 }
@@ -14,11 +14,12 @@ use datasize::DataSize;
 use itertools::Itertools;
 use serde::Serialize;
 
-use casper_types::{EraId, ExecutionEffect, PublicKey, Timestamp, U512};
+use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey, Timestamp, U512};
 
 use crate::{
     components::{
         consensus::{ClContext, ProposedBlock},
+        deploy_acceptor,
         diagnostics_port::FileSerializer,
         fetcher::FetchItem,
         gossiper::GossipItem,
@@ -27,8 +28,13 @@ use crate::{
     },
     effect::Responder,
     failpoints::FailpointActivation,
-    types::{Block, Deploy, DeployHash, FinalitySignature, FinalizedBlock, MetaBlock, NodeId},
+    logging::LoggingConfig,
+    types::{
+        Block, Deploy, DeployHash, DeployHeader, ExitCode, FinalitySignature, FinalizedBlock,
+        MetaBlock, NodeId,
+    },
     utils::Source,
+    FetcherConfig, GossipConfig,
 };
 
 /// Control announcements are special announcements handled directly by the runtime/runner.
@@ -55,6 +61,7 @@ pub(crate) enum ControlAnnouncement {
         file: &'static str,
         line: u32,
         msg: String,
+        exit_code: ExitCode,
     },
     /// An external event queue dump has been requested.
     QueueDumpRequest {
@@ -76,11 +83,17 @@ impl Debug for ControlAnnouncement {
         match self {
             ControlAnnouncement::ShutdownDueToUserRequest => write!(f, "ShutdownDueToUserRequest"),
             ControlAnnouncement::ShutdownForUpgrade => write!(f, "ShutdownForUpgrade"),
-            ControlAnnouncement::FatalError { file, line, msg } => f
+            ControlAnnouncement::FatalError {
+                file,
+                line,
+                msg,
+                exit_code,
+            } => f
                 .debug_struct("FatalError")
                 .field("file", file)
                 .field("line", line)
                 .field("msg", msg)
+                .field("exit_code", exit_code)
                 .finish(),
             ControlAnnouncement::QueueDumpRequest { .. } => {
                 f.debug_struct("QueueDump").finish_non_exhaustive()
@@ -100,7 +113,9 @@ impl Display for ControlAnnouncement {
                 write!(f, "shutdown due to user request")
             }
             ControlAnnouncement::ShutdownForUpgrade => write!(f, "shutdown for upgrade"),
-            ControlAnnouncement::FatalError { file, line, msg } => {
+            ControlAnnouncement::FatalError {
+                file, line, msg, ..
+            } => {
                 write!(f, "fatal error [{}:{}]: {}", file, line, msg)
             }
             ControlAnnouncement::QueueDumpRequest { .. } => {
@@ -122,6 +137,8 @@ pub(crate) struct FatalAnnouncement {
     pub(crate) file: &'static str,
     pub(crate) line: u32,
     pub(crate) msg: String,
+    /// The exit code the process should terminate with.
+    pub(crate) exit_code: ExitCode,
 }
 
 impl Display for FatalAnnouncement {
@@ -157,6 +174,22 @@ impl Display for UnexecutedBlockAnnouncement {
     }
 }
 
+/// The node's configuration has been reloaded at runtime.
+///
+/// Only carries the values which are safe to change without restarting the node.
+#[derive(Debug, Serialize)]
+pub(crate) struct ConfigReloadedAnnouncement {
+    pub(crate) logging: Box<LoggingConfig>,
+    pub(crate) gossip: Box<GossipConfig>,
+    pub(crate) fetcher: Box<FetcherConfig>,
+}
+
+impl Display for ConfigReloadedAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "config reloaded")
+    }
+}
+
 /// Queue dump format with handler.
 #[derive(Serialize)]
 pub(crate) enum QueueDumpFormat {
@@ -195,6 +228,8 @@ pub(crate) enum DeployAcceptorAnnouncement {
         deploy: Arc<Deploy>,
         /// The source (peer or client) of the deploy.
         source: Source,
+        /// The reason the deploy was rejected.
+        error: deploy_acceptor::Error,
     },
 }
 
@@ -207,12 +242,17 @@ impl Display for DeployAcceptorAnnouncement {
                 deploy.hash(),
                 source
             ),
-            DeployAcceptorAnnouncement::InvalidDeploy { deploy, source } => {
+            DeployAcceptorAnnouncement::InvalidDeploy {
+                deploy,
+                source,
+                error,
+            } => {
                 write!(
                     formatter,
-                    "invalid deploy {} from {}",
+                    "invalid deploy {} from {}: {}",
                     deploy.hash(),
-                    source
+                    source,
+                    error
                 )
             }
         }
@@ -345,6 +385,26 @@ impl Display for UpgradeWatcherAnnouncement {
     }
 }
 
+/// The estimated offset of our clock from the validators' clocks started or stopped exceeding the
+/// chainspec's maximum clock drift.
+#[derive(Debug, Serialize)]
+pub(crate) struct ClockDriftAnnouncement {
+    /// The estimated offset of the peers' clocks from ours, in milliseconds.
+    pub(crate) offset_ms: i64,
+    /// Whether the offset exceeds the maximum clock drift.
+    pub(crate) exceeds_tolerance: bool,
+}
+
+impl Display for ClockDriftAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.exceeds_tolerance {
+            write!(f, "clock offset of {} ms exceeds tolerance", self.offset_ms)
+        } else {
+            write!(f, "clock offset of {} ms within tolerance", self.offset_ms)
+        }
+    }
+}
+
 /// A ContractRuntime announcement.
 #[derive(Debug, Serialize)]
 pub(crate) enum ContractRuntimeAnnouncement {
@@ -355,6 +415,29 @@ pub(crate) enum ContractRuntimeAnnouncement {
         /// The operations and transforms committed to global state.
         execution_effect: ExecutionEffect,
     },
+    /// A deploy of the block being executed was processed.
+    ///
+    /// The block's effects are only committed once all of its deploys are processed, so the
+    /// result isn't part of global state yet, and is discarded if the block fails to execute.
+    DeployProcessed {
+        /// The era of the block being executed.
+        era_id: EraId,
+        /// The height of the block being executed.
+        block_height: u64,
+        /// The hash of the processed deploy.
+        deploy_hash: DeployHash,
+        /// The header of the processed deploy.
+        deploy_header: Box<DeployHeader>,
+        /// The result of executing the deploy.
+        execution_result: Box<ExecutionResult>,
+    },
+    /// Executing a block failed, so none of its processed deploys will be committed.
+    BlockExecutionFailed {
+        /// The era of the block that failed to execute.
+        era_id: EraId,
+        /// The height of the block that failed to execute.
+        block_height: u64,
+    },
     /// New era validators.
     UpcomingEraValidators {
         /// The era id in which the step was committed to global state.
@@ -370,6 +453,20 @@ impl Display for ContractRuntimeAnnouncement {
             ContractRuntimeAnnouncement::CommitStepSuccess { era_id, .. } => {
                 write!(f, "commit step completed for {}", era_id)
             }
+            ContractRuntimeAnnouncement::DeployProcessed {
+                block_height,
+                deploy_hash,
+                ..
+            } => {
+                write!(
+                    f,
+                    "deploy {} processed in block at height {}",
+                    deploy_hash, block_height
+                )
+            }
+            ContractRuntimeAnnouncement::BlockExecutionFailed { block_height, .. } => {
+                write!(f, "execution of block at height {} failed", block_height)
+            }
             ContractRuntimeAnnouncement::UpcomingEraValidators {
                 era_that_is_ending, ..
             } => {
@@ -97,6 +97,7 @@ pub(crate) enum NetRequest {
     SyncLeap(Vec<u8>),
     ApprovalsHashes(Vec<u8>),
     BlockExecutionResults(Vec<u8>),
+    EraValidators(Vec<u8>),
 }
 
 impl Display for NetRequest {
@@ -114,6 +115,7 @@ impl Display for NetRequest {
             NetRequest::BlockExecutionResults(_) => {
                 f.write_str("request for block execution results")
             }
+            NetRequest::EraValidators(_) => f.write_str("request for era validators"),
         }
     }
 }
@@ -129,7 +131,8 @@ impl NetRequest {
             | NetRequest::FinalitySignature(ref id)
             | NetRequest::SyncLeap(ref id)
             | NetRequest::ApprovalsHashes(ref id)
-            | NetRequest::BlockExecutionResults(ref id) => id,
+            | NetRequest::BlockExecutionResults(ref id)
+            | NetRequest::EraValidators(ref id) => id,
         };
         let mut unique_id = Vec::with_capacity(id.len() + 1);
         unique_id.push(self.tag() as u8);
@@ -149,6 +152,7 @@ impl NetRequest {
             NetRequest::SyncLeap(_) => Tag::SyncLeap,
             NetRequest::ApprovalsHashes(_) => Tag::ApprovalsHashes,
             NetRequest::BlockExecutionResults(_) => Tag::BlockExecutionResults,
+            NetRequest::EraValidators(_) => Tag::EraValidators,
         }
     }
 }
@@ -166,6 +170,7 @@ pub(crate) enum NetResponse {
     SyncLeap(Arc<[u8]>),
     ApprovalsHashes(Arc<[u8]>),
     BlockExecutionResults(Arc<[u8]>),
+    EraValidators(Arc<[u8]>),
 }
 
 // `NetResponse` uses `Arcs`, so we count all data as 0.
@@ -192,6 +197,7 @@ impl Display for NetResponse {
             NetResponse::BlockExecutionResults(_) => {
                 f.write_str("response for block execution results")
             }
+            NetResponse::EraValidators(_) => f.write_str("response for era validators"),
         }
     }
 }
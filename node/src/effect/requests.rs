@@ -4,7 +4,7 @@
 //! top-level module documentation for details.
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
     sync::Arc,
@@ -27,8 +27,10 @@ use casper_execution_engine::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Timestamp, Transfer, URef,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators, EraId, ExecutionEffect, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, TimeDiff, Timestamp, Transfer, URef,
 };
 
 use crate::{
@@ -37,13 +39,15 @@ use crate::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, ProposedBlock, ValidatorChange},
+        consensus::{ClContext, ProposedBlock, ValidatorChange, ValidatorPerformance},
         contract_runtime::EraValidatorsRequest,
         deploy_acceptor,
+        deploy_buffer::DeployBufferStatus,
         diagnostics_port::StopAtSpec,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
         network::NetworkInsights,
+        rest_server::{Health, Readiness},
         upgrade_watcher::NextUpgrade,
     },
     contract_runtime::{ContractRuntimeError, SpeculativeExecutionState},
@@ -51,12 +55,12 @@ use crate::{
     reactor::main_reactor::ReactorState,
     rpcs::docs::OpenRpcSchema,
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
+        appendable_block::AppendableBlock, Approval, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlockState,
-        NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
+        BlockHeaderWithMetadata, BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy,
+        DeployHash, DeployHeader, DeployId, DeployMetadataExt, DeployWithFinalizedApprovals,
+        FinalitySignature, FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy,
+        MetaBlockState, NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
     },
     utils::{DisplayIter, Source},
 };
@@ -209,10 +213,27 @@ pub(crate) enum NetworkInfoRequest {
         /// Responder to be called with the peers.
         responder: Responder<Vec<NodeId>>,
     },
+    /// Get up to `count` fully-connected peers, preferring those with the lowest round-trip
+    /// times.
+    LowRttPeers {
+        count: usize,
+        /// Responder to be called with the peers.
+        responder: Responder<Vec<NodeId>>,
+    },
+    /// Order the given peers by round-trip time, lowest first.
+    RankByRtt {
+        peers: Vec<NodeId>,
+        /// Responder to be called with the ordered peers.
+        responder: Responder<Vec<NodeId>>,
+    },
     /// Get detailed insights into the nodes networking.
     Insight {
         responder: Responder<NetworkInsights>,
     },
+    /// Get the estimated offsets of connected validators' clocks from ours, in milliseconds.
+    PeerClockOffsets {
+        responder: Responder<BTreeMap<NodeId, i64>>,
+    },
 }
 
 impl Display for NetworkInfoRequest {
@@ -227,9 +248,24 @@ impl Display for NetworkInfoRequest {
             } => {
                 write!(formatter, "get up to {} fully connected peers", count)
             }
+            NetworkInfoRequest::LowRttPeers {
+                count,
+                responder: _,
+            } => {
+                write!(formatter, "get up to {} low round-trip time peers", count)
+            }
+            NetworkInfoRequest::RankByRtt {
+                peers,
+                responder: _,
+            } => {
+                write!(formatter, "rank {} peers by round-trip time", peers.len())
+            }
             NetworkInfoRequest::Insight { responder: _ } => {
                 formatter.write_str("get networking insights")
             }
+            NetworkInfoRequest::PeerClockOffsets { responder: _ } => {
+                formatter.write_str("get peer clock offsets")
+            }
         }
     }
 }
@@ -323,6 +359,11 @@ pub(crate) enum StorageRequest {
         deploy_hashes: HashSet<DeployHash>,
         responder: Responder<HashSet<EraId>>,
     },
+    /// Retrieve the headers of the stored blocks in which the given deploys were executed.
+    GetExecutedDeploys {
+        deploy_hashes: HashSet<DeployHash>,
+        responder: Responder<HashMap<DeployHash, BlockHeader>>,
+    },
     /// Retrieve block header with given hash.
     GetBlockHeader {
         /// Hash of block to get header of.
@@ -400,6 +441,23 @@ pub(crate) enum StorageRequest {
         block_hash: BlockHash,
         responder: Responder<Option<Vec<(DeployHash, DeployHeader, ExecutionResult)>>>,
     },
+    /// Store the effect of the step run at the end of the era of the given switch block.
+    PutStepEffect {
+        /// Hash of the switch block.
+        block_hash: Box<BlockHash>,
+        /// Effect of the step.
+        step_effect: ExecutionEffect,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Retrieve the effect of the step run at the end of the era of the given switch block.
+    GetStepEffect {
+        /// Hash of the switch block.
+        block_hash: BlockHash,
+        /// Responder to call with the result.  Returns `None` if the step effect doesn't exist in
+        /// local storage.
+        responder: Responder<Option<ExecutionEffect>>,
+    },
     GetBlockExecutionResultsOrChunk {
         /// Request ID.
         id: BlockExecutionResultsOrChunkId,
@@ -451,6 +509,21 @@ pub(crate) enum StorageRequest {
         /// The responder to call the results with.
         responder: Responder<Option<BlockWithMetadata>>,
     },
+    /// Get a block header and its finality signatures, whether or not the block is complete.
+    GetBlockHeaderWithMetadata {
+        /// The hash of the block, or `None` for the highest stored block header.
+        block_hash: Option<BlockHash>,
+        /// The responder to call with the results.
+        responder: Responder<Option<BlockHeaderWithMetadata>>,
+    },
+    /// Get the switch block header of an era and its finality signatures.
+    GetSwitchBlockHeaderWithMetadata {
+        /// The era of the switch block.
+        era_id: EraId,
+        /// The responder to call with the results.  Returns `None` if the switch block header or
+        /// its finality signatures don't exist in local storage.
+        responder: Responder<Option<BlockHeaderWithMetadata>>,
+    },
     /// Get a single finality signature for a block hash.
     GetBlockSignature {
         /// The hash for the request.
@@ -480,6 +553,14 @@ pub(crate) enum StorageRequest {
         /// stored.
         responder: Responder<bool>,
     },
+    /// Store a block header together with its finality signatures, in a single transaction.
+    PutBlockHeaderWithMetadata {
+        /// Block header and signatures that are to be stored.
+        block_header_with_metadata: Box<BlockHeaderWithMetadata>,
+        /// Responder to call with the result, if true then the block header and signatures were
+        /// successfully stored.
+        responder: Responder<bool>,
+    },
     /// Retrieve the height range of fully available blocks (not just block headers). Returns
     /// `[u64::MAX, u64::MAX]` when there are no sequences.
     GetAvailableBlockRange {
@@ -498,6 +579,10 @@ pub(crate) enum StorageRequest {
     },
     /// Retrieve the height of the final block of the previous protocol version, if known.
     GetKeyBlockHeightForActivationPoint { responder: Responder<Option<u64>> },
+    /// Check whether the storage folder is writable.
+    CheckWritable { responder: Responder<bool> },
+    /// Flush all buffered writes to disk.
+    Sync { responder: Responder<bool> },
 }
 
 impl Display for StorageRequest {
@@ -527,6 +612,13 @@ impl Display for StorageRequest {
             StorageRequest::GetDeploysEraIds { deploy_hashes, .. } => {
                 write!(formatter, "get era ids for {} deploys", deploy_hashes.len())
             }
+            StorageRequest::GetExecutedDeploys { deploy_hashes, .. } => {
+                write!(
+                    formatter,
+                    "get executed among {} deploys",
+                    deploy_hashes.len()
+                )
+            }
             StorageRequest::GetBlockHeader { block_hash, .. } => {
                 write!(formatter, "get {}", block_hash)
             }
@@ -555,6 +647,12 @@ impl Display for StorageRequest {
             StorageRequest::GetExecutionResults { block_hash, .. } => {
                 write!(formatter, "get execution results for {}", block_hash)
             }
+            StorageRequest::PutStepEffect { block_hash, .. } => {
+                write!(formatter, "put step effect for {}", block_hash)
+            }
+            StorageRequest::GetStepEffect { block_hash, .. } => {
+                write!(formatter, "get step effect for {}", block_hash)
+            }
             StorageRequest::GetBlockExecutionResultsOrChunk { id, .. } => {
                 write!(formatter, "get block execution results or chunk for {}", id)
             }
@@ -585,6 +683,28 @@ impl Display for StorageRequest {
             StorageRequest::GetHighestBlockWithMetadata { .. } => {
                 write!(formatter, "get highest block with metadata")
             }
+            StorageRequest::GetBlockHeaderWithMetadata {
+                block_hash: Some(block_hash),
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get block header and metadata for block: {}",
+                    block_hash
+                )
+            }
+            StorageRequest::GetBlockHeaderWithMetadata {
+                block_hash: None, ..
+            } => {
+                write!(formatter, "get highest block header with metadata")
+            }
+            StorageRequest::GetSwitchBlockHeaderWithMetadata { era_id, .. } => {
+                write!(
+                    formatter,
+                    "get switch block header and metadata for era {}",
+                    era_id
+                )
+            }
             StorageRequest::GetBlockSignature {
                 block_hash,
                 public_key,
@@ -605,6 +725,16 @@ impl Display for StorageRequest {
             StorageRequest::PutBlockHeader { block_header, .. } => {
                 write!(formatter, "put block header: {}", block_header)
             }
+            StorageRequest::PutBlockHeaderWithMetadata {
+                block_header_with_metadata,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "put block header and metadata: {}",
+                    block_header_with_metadata.block_header
+                )
+            }
             StorageRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range",)
             }
@@ -620,6 +750,10 @@ impl Display for StorageRequest {
                     "get key block height for current activation point"
                 )
             }
+            StorageRequest::CheckWritable { .. } => {
+                write!(formatter, "check whether storage is writable")
+            }
+            StorageRequest::Sync { .. } => write!(formatter, "sync storage to disk"),
         }
     }
 }
@@ -665,6 +799,15 @@ pub(crate) enum DeployBufferRequest {
         request_expiry: Timestamp,
         responder: Responder<AppendableBlock>,
     },
+    GetStatus {
+        responder: Responder<DeployBufferStatus>,
+    },
+    GetPendingDeployHashes {
+        responder: Responder<Vec<DeployHash>>,
+    },
+    ClearPending {
+        responder: Responder<usize>,
+    },
 }
 
 impl Display for DeployBufferRequest {
@@ -681,6 +824,18 @@ impl Display for DeployBufferRequest {
                     timestamp, request_expiry,
                 )
             }
+            DeployBufferRequest::GetStatus { .. } => {
+                write!(formatter, "request for deploy buffer status")
+            }
+            DeployBufferRequest::GetPendingDeployHashes { .. } => {
+                write!(formatter, "request for pending deploy hashes")
+            }
+            DeployBufferRequest::ClearPending { .. } => {
+                write!(
+                    formatter,
+                    "request to clear pending deploys from deploy buffer"
+                )
+            }
         }
     }
 }
@@ -836,6 +991,16 @@ pub(crate) enum RestRequest {
         /// Responder to call with the result
         responder: Responder<OpenRpcSchema>,
     },
+    /// Return whether the node is alive.
+    Health {
+        /// Responder to call with the result.
+        responder: Responder<Health>,
+    },
+    /// Return whether the node is ready to serve clients.
+    Ready {
+        /// Responder to call with the result.
+        responder: Responder<Readiness>,
+    },
 }
 
 impl Display for RestRequest {
@@ -844,6 +1009,8 @@ impl Display for RestRequest {
             RestRequest::Status { .. } => write!(formatter, "get status"),
             RestRequest::Metrics { .. } => write!(formatter, "get metrics"),
             RestRequest::RpcSchema { .. } => write!(formatter, "get openrpc"),
+            RestRequest::Health { .. } => write!(formatter, "get health"),
+            RestRequest::Ready { .. } => write!(formatter, "get readiness"),
         }
     }
 }
@@ -870,6 +1037,15 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the query result.
         responder: Responder<Result<QueryResult, engine_state::Error>>,
     },
+    /// Reads several accounts from global state at once.
+    GetAccounts {
+        /// The state root hash to read the accounts under.
+        state_root_hash: Digest,
+        /// The hashes of the accounts to read.
+        account_hashes: BTreeSet<AccountHash>,
+        /// Responder to call with the accounts found.
+        responder: Responder<Result<BTreeMap<AccountHash, Account>, engine_state::Error>>,
+    },
     /// A balance request.
     GetBalance {
         /// Balance request.
@@ -930,6 +1106,11 @@ pub(crate) enum ContractRuntimeRequest {
         /// Results
         responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
     },
+    /// Flush all buffered global state writes to disk.
+    SyncGlobalStateToDisk {
+        /// Responder to call with whether the flush succeeded.
+        responder: Responder<bool>,
+    },
 }
 
 impl Display for ContractRuntimeRequest {
@@ -943,6 +1124,16 @@ impl Display for ContractRuntimeRequest {
             ContractRuntimeRequest::Query { query_request, .. } => {
                 write!(formatter, "query request: {:?}", query_request)
             }
+            ContractRuntimeRequest::GetAccounts {
+                state_root_hash,
+                account_hashes,
+                ..
+            } => write!(
+                formatter,
+                "get {} accounts under {}",
+                account_hashes.len(),
+                state_root_hash
+            ),
             ContractRuntimeRequest::GetBalance {
                 balance_request, ..
             } => write!(formatter, "balance request: {:?}", balance_request),
@@ -984,6 +1175,9 @@ impl Display for ContractRuntimeRequest {
                     execution_prestate.state_root_hash
                 )
             }
+            ContractRuntimeRequest::SyncGlobalStateToDisk { .. } => {
+                write!(formatter, "sync global state to disk")
+            }
         }
     }
 }
@@ -1076,6 +1270,8 @@ pub(crate) enum ConsensusRequest {
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
     /// Request for a list of validator status changes, by public key.
     ValidatorChanges(Responder<BTreeMap<PublicKey, Vec<(EraId, ValidatorChange)>>>),
+    /// Request for the participation of each validator in each open era.
+    ValidatorPerformance(Responder<BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>>),
 }
 
 /// ChainspecLoader component requests.
@@ -1188,7 +1384,8 @@ impl Display for SetNodeStopRequest {
 pub(crate) struct AcceptDeployRequest {
     pub(crate) deploy: Arc<Deploy>,
     pub(crate) speculative_exec_at_block: Option<Box<BlockHeader>>,
-    pub(crate) responder: Responder<Result<(), deploy_acceptor::Error>>,
+    pub(crate) responder:
+        Responder<Result<deploy_acceptor::DeployAcceptance, deploy_acceptor::Error>>,
 }
 
 impl Display for AcceptDeployRequest {
@@ -1204,3 +1401,23 @@ impl Display for AcceptDeployRequest {
         }
     }
 }
+
+/// A request to add approvals to a deploy awaiting further approvals.
+#[derive(DataSize, Debug, Serialize)]
+pub(crate) struct AddDeployApprovalsRequest {
+    pub(crate) deploy_hash: DeployHash,
+    pub(crate) approvals: BTreeSet<Approval>,
+    pub(crate) responder:
+        Responder<Result<deploy_acceptor::DeployAcceptance, deploy_acceptor::Error>>,
+}
+
+impl Display for AddDeployApprovalsRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "add {} approvals to deploy {}",
+            self.approvals.len(),
+            self.deploy_hash
+        )
+    }
+}
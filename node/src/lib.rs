@@ -33,6 +33,7 @@ pub(crate) mod failpoints;
 pub mod logging;
 pub(crate) mod protocol;
 pub(crate) mod reactor;
+pub mod runtime;
 #[cfg(test)]
 pub(crate) mod testing;
 pub(crate) mod tls;
@@ -41,14 +42,20 @@ pub mod utils;
 
 use std::{
     env,
-    sync::{atomic::AtomicUsize, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize},
+        Arc,
+    },
 };
 
 use ansi_term::Color::Red;
 use once_cell::sync::Lazy;
 #[cfg(not(test))]
 use rand::SeedableRng;
-use signal_hook::{consts::TERM_SIGNALS, flag};
+use signal_hook::{
+    consts::{SIGHUP, SIGUSR1, TERM_SIGNALS},
+    flag,
+};
 use tracing::warn;
 
 pub(crate) use components::{
@@ -64,8 +71,9 @@ pub(crate) use components::{
     fetcher::Config as FetcherConfig,
     gossiper::Config as GossipConfig,
     network::Config as NetworkConfig,
+    notifier::Config as NotifierConfig,
     rest_server::Config as RestServerConfig,
-    rpc_server::{Config as RpcServerConfig, SpeculativeExecConfig},
+    rpc_server::{Config as RpcServerConfig, RosettaConfig, SpeculativeExecConfig},
     upgrade_watcher::Config as UpgradeWatcherConfig,
 };
 pub use components::{
@@ -73,7 +81,7 @@ pub use components::{
     rpc_server::rpcs,
     storage::{self, Config as StorageConfig},
 };
-pub use reactor::main_reactor::Config as MainReactorConfig;
+pub use reactor::{in_supervised_dispatch, main_reactor::Config as MainReactorConfig};
 pub(crate) use types::NodeRng;
 pub use utils::WithDir;
 
@@ -115,6 +123,14 @@ pub(crate) static VERSION_STRING: Lazy<String> = Lazy::new(|| version_string(fal
 pub(crate) static TERMINATION_REQUESTED: Lazy<Arc<AtomicUsize>> =
     Lazy::new(|| Arc::new(AtomicUsize::new(0)));
 
+/// Global flag that indicates the configuration should be reloaded, set upon receiving `SIGHUP`.
+pub(crate) static RELOAD_REQUESTED: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+
+/// Global flag that indicates the debug log filter should be toggled, set upon receiving `SIGUSR1`.
+pub(crate) static LOG_FILTER_TOGGLE_REQUESTED: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+
 /// Setup UNIX signal hooks for current application.
 pub(crate) fn setup_signal_hooks() {
     for signal in TERM_SIGNALS {
@@ -125,6 +141,11 @@ pub(crate) fn setup_signal_hooks() {
         )
         .unwrap_or_else(|error| panic!("failed to register signal {}: {}", signal, error));
     }
+
+    flag::register(SIGHUP, Arc::clone(&*RELOAD_REQUESTED))
+        .unwrap_or_else(|error| panic!("failed to register signal {}: {}", SIGHUP, error));
+    flag::register(SIGUSR1, Arc::clone(&*LOG_FILTER_TOGGLE_REQUESTED))
+        .unwrap_or_else(|error| panic!("failed to register signal {}: {}", SIGUSR1, error));
 }
 
 /// Constructs a new `NodeRng`.
@@ -138,3 +159,17 @@ pub(crate) fn new_rng() -> NodeRng {
 pub(crate) fn new_rng() -> NodeRng {
     NodeRng::new()
 }
+
+/// Constructs a new `NodeRng` from the given seed.
+#[cfg(not(test))]
+pub(crate) fn new_rng_from_seed(seed: u64) -> NodeRng {
+    NodeRng::seed_from_u64(seed)
+}
+
+/// Constructs a new `NodeRng` from the given seed.
+#[cfg(test)]
+pub(crate) fn new_rng_from_seed(seed: u64) -> NodeRng {
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    NodeRng::from_seed(bytes)
+}
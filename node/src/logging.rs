@@ -1,12 +1,24 @@
 //! Logging via the tracing crate.
 
-use std::{env, fmt, io};
+mod rotating_file;
+
+use std::{
+    env, fmt,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
 
 use ansi_term::{Color, Style};
 use anyhow::anyhow;
+use casper_types::TimeDiff;
 use datasize::DataSize;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use smallvec::SmallVec;
 use tracing::{
     field::{Field, Visit},
@@ -14,9 +26,9 @@ use tracing::{
 };
 use tracing_subscriber::{
     fmt::{
-        format::{self, FieldFn, Format, Json, JsonFields, Writer},
+        format::{self, FieldFn, JsonFields, Writer},
         time::{FormatTime, SystemTime},
-        FmtContext, FormatEvent, FormatFields, FormattedFields, Layer,
+        FmtContext, FormatEvent, FormatFields, FormattedFields, Layer, MakeWriter,
     },
     layer::Layered,
     registry::LookupSpan,
@@ -24,7 +36,13 @@ use tracing_subscriber::{
     EnvFilter, Registry,
 };
 
+use rotating_file::RotatingFile;
+
 const LOG_CONFIGURATION_ENVVAR: &str = "RUST_LOG";
+/// Log filter used if neither the environment nor the config specify one.
+const DEFAULT_LOG_FILTER: &str = "warn,casper_node=info";
+/// Log filter toggled to on `SIGUSR1` if the config does not specify one.
+const DEFAULT_DEBUG_LOG_FILTER: &str = "info,casper_node=debug";
 
 const LOG_FIELD_MESSAGE: &str = "message";
 const LOG_FIELD_TARGET: &str = "log.target";
@@ -32,11 +50,20 @@ const LOG_FIELD_MODULE: &str = "log.module_path";
 const LOG_FIELD_FILE: &str = "log.file";
 const LOG_FIELD_LINE: &str = "log.line";
 
+/// Name of the reactor span field holding the component handling the current event.
+pub(crate) const LOG_FIELD_COMPONENT: &str = "component";
+
 /// Global reload handle.
 ///
 /// We use a static variable for the reload handle since our logger instance is also global.
 static RELOAD_HANDLE: OnceCell<ReloadHandle> = OnceCell::new();
 
+/// The logging config currently in effect, used to switch between the regular and debug filters.
+static ACTIVE_CONFIG: Lazy<Mutex<LoggingConfig>> = Lazy::new(Default::default);
+
+/// Whether the debug filter is currently active.
+static DEBUG_FILTER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
 /// Logging configuration.
 #[derive(Clone, DataSize, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -55,6 +82,22 @@ pub struct LoggingConfig {
     /// If set, human-readable formats will abbreviate module names, `foo::bar::baz::bizz` will
     /// turn into `f:b:b:bizz`.
     pub abbreviate_modules: bool,
+
+    /// Log filter directives, e.g. `warn,casper_node=info`.
+    ///
+    /// Ignored if the `RUST_LOG` environment variable is set. Can be changed by reloading the
+    /// config at runtime.
+    pub filter: Option<String>,
+
+    /// Log filter directives to switch to upon receiving `SIGUSR1`, e.g. `info,casper_node=debug`.
+    ///
+    /// Receiving another `SIGUSR1` switches back to the regular filter.
+    pub debug_filter: Option<String>,
+
+    /// File to write the log to, rotated by size and age, instead of `stdout`.
+    ///
+    /// Cannot be changed by reloading the config at runtime.
+    pub file: Option<LogFileConfig>,
 }
 
 impl LoggingConfig {
@@ -65,13 +108,99 @@ impl LoggingConfig {
             format,
             color,
             abbreviate_modules,
+            filter: None,
+            debug_filter: None,
+            file: None,
         }
     }
 }
 
+/// Default size in bytes above which the log file is rotated: 512 MiB.
+const DEFAULT_MAX_LOG_FILE_SIZE: u64 = 512 * 1024 * 1024;
+/// Default number of rotated log files to retain.
+const DEFAULT_MAX_ROTATED_LOG_FILES: u32 = 10;
+
+/// Configuration of the rotated log file.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogFileConfig {
+    /// Path of the log file.  Relative paths are resolved relative to the directory containing
+    /// the config file.
+    pub path: PathBuf,
+
+    /// Size in bytes above which the log file is rotated.
+    #[serde(default = "default_max_log_file_size")]
+    pub max_file_size: u64,
+
+    /// Time after which the log file is rotated regardless of its size, if any.
+    #[serde(default)]
+    pub max_file_age: Option<TimeDiff>,
+
+    /// Number of rotated log files to retain.
+    #[serde(default = "default_max_rotated_log_files")]
+    pub max_rotated_files: u32,
+
+    /// Whether to gzip-compress rotated log files.
+    #[serde(default)]
+    pub compress: bool,
+}
+
+fn default_max_log_file_size() -> u64 {
+    DEFAULT_MAX_LOG_FILE_SIZE
+}
+
+fn default_max_rotated_log_files() -> u32 {
+    DEFAULT_MAX_ROTATED_LOG_FILES
+}
+
+/// The destination of log output: either `stdout` or a rotated log file.
+#[derive(Clone)]
+pub struct LogWriter(Option<Arc<Mutex<RotatingFile>>>);
+
+impl LogWriter {
+    /// Returns a writer to the configured log file, or to `stdout` if none is configured.
+    fn new(config: &LoggingConfig) -> io::Result<Self> {
+        let maybe_file = match &config.file {
+            Some(file_config) => Some(Arc::new(Mutex::new(RotatingFile::open(file_config)?))),
+            None => None,
+        };
+        Ok(LogWriter(maybe_file))
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &self.0 {
+            Some(file) => file.lock().expect("poisoned lock").write(buf),
+            None => io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.0 {
+            Some(file) => file.lock().expect("poisoned lock").flush(),
+            None => io::stdout().flush(),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogWriter {
+    type Writer = LogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
 /// Logging output format.
 ///
 /// Defaults to "text"".
+///
+/// The JSON format emits one object per line, containing `timestamp`, `level`, `target` and
+/// `message` alongside the fields of the event and all enclosing spans. Fields which are named
+/// inconsistently throughout the code are emitted under stable names, see `stable_field_name`.
+/// Events belonging to the same logical operation, such as the handling of a single deploy, share
+/// an `operation_id`.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LoggingFormat {
@@ -255,6 +384,102 @@ where
     }
 }
 
+/// Formats events as single-line JSON objects suitable for log aggregators.
+///
+/// Unlike the stock JSON format of `tracing_subscriber`, span fields are flattened into the
+/// top-level object, so that e.g. the `component` recorded by the reactor can be queried directly.
+pub struct JsonFmtEvent;
+
+/// Returns the name a field is emitted under in JSON output.
+///
+/// Maps the various names used for the same concept throughout the code to a single one.
+fn stable_field_name(name: &str) -> &str {
+    match name {
+        "era_id" => "era",
+        "peer" | "sender" => "peer_id",
+        other => other,
+    }
+}
+
+/// Collects the fields of an event into a JSON object.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl<'a> JsonVisitor<'a> {
+    fn insert(&mut self, field: &Field, value: Value) {
+        match field.name() {
+            LOG_FIELD_MODULE | LOG_FIELD_FILE | LOG_FIELD_LINE => {}
+            LOG_FIELD_TARGET => {
+                self.0.insert("target".to_string(), value);
+            }
+            name => {
+                self.0.insert(stable_field_name(name).to_string(), value);
+            }
+        }
+    }
+}
+
+impl<'a> Visit for JsonVisitor<'a> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into())
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into())
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into())
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into())
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into())
+    }
+}
+
+impl<S> FormatEvent<S, JsonFields> for JsonFmtEvent
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let timestamp = humantime::format_rfc3339_micros(std::time::SystemTime::now());
+
+        let mut object = Map::new();
+        object.insert("timestamp".to_string(), timestamp.to_string().into());
+        object.insert("level".to_string(), meta.level().as_str().into());
+        object.insert("target".to_string(), meta.target().into());
+
+        // Span fields are already stored as JSON objects, outer spans are overridden by inner ones.
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                let span_fields =
+                    extensions
+                        .get::<FormattedFields<JsonFields>>()
+                        .and_then(|fields| {
+                            serde_json::from_str::<Map<String, Value>>(&fields.fields).ok()
+                        });
+                for (name, value) in span_fields.into_iter().flatten() {
+                    object.insert(stable_field_name(&name).to_string(), value);
+                }
+            }
+        }
+
+        event.record(&mut JsonVisitor(&mut object));
+
+        writeln!(writer, "{}", Value::Object(object))
+    }
+}
+
 /// Initializes the logging system with the default parameters.
 ///
 /// See `init_params` for details.
@@ -267,9 +492,16 @@ pub fn init() -> anyhow::Result<()> {
 #[allow(clippy::type_complexity)] // Cannot be helped, unfortunately.
 pub enum ReloadHandle {
     /// Text-logger reload handle.
-    Text(Handle<EnvFilter, Layered<Layer<Registry, FieldFn<FormatDebugFn>, FmtEvent>, Registry>>),
+    Text(
+        Handle<
+            EnvFilter,
+            Layered<Layer<Registry, FieldFn<FormatDebugFn>, FmtEvent, LogWriter>, Registry>,
+        >,
+    ),
     /// JSON-logger reload handle.
-    Json(Handle<EnvFilter, Layered<Layer<Registry, JsonFields, Format<Json>>, Registry>>),
+    Json(
+        Handle<EnvFilter, Layered<Layer<Registry, JsonFields, JsonFmtEvent, LogWriter>, Registry>>,
+    ),
 }
 
 impl ReloadHandle {
@@ -310,6 +542,48 @@ pub fn display_global_env_filter() -> anyhow::Result<String> {
     Ok(formatted)
 }
 
+/// Applies the filter of a reloaded logging config, unless overridden by the environment.
+///
+/// Deactivates the debug filter if it was active.
+pub(crate) fn reload_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
+    *ACTIVE_CONFIG.lock().expect("poisoned lock") = config.clone();
+    let was_debug = DEBUG_FILTER_ACTIVE.swap(false, Ordering::SeqCst);
+    if env::var(LOG_CONFIGURATION_ENVVAR).is_ok() && !was_debug {
+        return Ok(());
+    }
+    reload_global_env_filter(env_filter(config))
+}
+
+/// Switches between the regular and the debug log filter, as triggered by `SIGUSR1`.
+///
+/// Returns whether the debug filter is active afterwards.
+pub(crate) fn toggle_debug_filter() -> anyhow::Result<bool> {
+    let config = ACTIVE_CONFIG.lock().expect("poisoned lock").clone();
+    let debug = !DEBUG_FILTER_ACTIVE.load(Ordering::SeqCst);
+    let new_filter = if debug {
+        EnvFilter::try_new(
+            config
+                .debug_filter
+                .as_deref()
+                .unwrap_or(DEFAULT_DEBUG_LOG_FILTER),
+        )?
+    } else {
+        env_filter(&config)
+    };
+    reload_global_env_filter(new_filter)?;
+    DEBUG_FILTER_ACTIVE.store(debug, Ordering::SeqCst);
+    Ok(debug)
+}
+
+/// Constructs the [`EnvFilter`] to use, preferring the `RUST_LOG` environment variable over the
+/// configured filter.
+fn env_filter(config: &LoggingConfig) -> EnvFilter {
+    match env::var(LOG_CONFIGURATION_ENVVAR) {
+        Ok(directives) => EnvFilter::new(directives),
+        Err(_) => EnvFilter::new(config.filter.as_deref().unwrap_or(DEFAULT_LOG_FILTER)),
+    }
+}
+
 /// Type alias for the formatting function used.
 pub type FormatDebugFn = fn(&mut Writer, &Field, &dyn std::fmt::Debug) -> fmt::Result;
 
@@ -331,22 +605,21 @@ fn format_into_debug_writer(
 /// this outside of the application or testing code, the installed logger is global.
 ///
 /// See the `README.md` for hints on how to configure logging at runtime.
-// The `io::stdout as fn()...` casts are necessary, as is the `FormatDebugFn` cast.
+// The `FormatDebugFn` cast is necessary.
 #[allow(trivial_casts)]
 pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
     let formatter = format::debug_fn(format_into_debug_writer as FormatDebugFn);
+    let writer =
+        LogWriter::new(config).map_err(|error| anyhow!("could not open log file: {}", error))?;
 
-    let filter = EnvFilter::new(
-        env::var(LOG_CONFIGURATION_ENVVAR)
-            .as_deref()
-            .unwrap_or("warn,casper_node=info"),
-    );
+    let filter = env_filter(config);
+    *ACTIVE_CONFIG.lock().expect("poisoned lock") = config.clone();
 
     match config.format {
-        // Setup a new tracing-subscriber writing to `stdout` for logging.
+        // Setup a new tracing-subscriber writing to `stdout` or the log file for logging.
         LoggingFormat::Text => {
             let builder = tracing_subscriber::fmt()
-                .with_writer(io::stdout as fn() -> std::io::Stdout)
+                .with_writer(writer)
                 .with_env_filter(filter)
                 .fmt_fields(formatter)
                 .event_format(FmtEvent::new(config.color, config.abbreviate_modules))
@@ -357,12 +630,13 @@ pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
             Ok(())
         }
 
-        // JSON logging writes to `stdout` as well but uses the JSON format.
+        // JSON logging writes to the same output but uses the JSON format.
         LoggingFormat::Json => {
             let builder = tracing_subscriber::fmt()
-                .with_writer(io::stdout as fn() -> std::io::Stdout)
+                .with_writer(writer)
                 .with_env_filter(filter)
-                .json()
+                .fmt_fields(JsonFields::new())
+                .event_format(JsonFmtEvent)
                 .with_filter_reloading();
             let handle = ReloadHandle::Json(builder.reload_handle());
             builder.try_init().map_err(|error| anyhow!(error))?;
@@ -0,0 +1,257 @@
+//! A log file rotated by size and age, with optional compression of rotated files.
+//!
+//! Output is appended to the configured file.  Once that file exceeds the configured size or age,
+//! it is renamed to `<FILE NAME>.<N>`, where `N` increases with each rotation, and a new file is
+//! started.  If enabled, rotated files are gzip-compressed to `<FILE NAME>.<N>.gz` in a background
+//! thread.  Only the configured number of rotated files are retained, the oldest being deleted
+//! first.
+//!
+//! Errors are reported on stderr rather than logged, as logging from within the log writer would
+//! deadlock.
+
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use flate2::{write::GzEncoder, Compression};
+
+use super::LogFileConfig;
+
+/// The extension appended to the names of compressed rotated files.
+const COMPRESSED_EXTENSION: &str = ".gz";
+/// The extension appended to the names of compressed files while being written.
+const TEMPORARY_EXTENSION: &str = ".tmp";
+
+/// A log file, rotated by size and age, with a bounded number of retained files.
+pub(super) struct RotatingFile {
+    path: PathBuf,
+    max_file_size: u64,
+    max_file_age: Option<Duration>,
+    max_rotated_files: u32,
+    compress: bool,
+    file: File,
+    file_size: u64,
+    opened_at: Instant,
+}
+
+impl RotatingFile {
+    /// Opens the configured log file, creating its directory if required and appending to any
+    /// existing file.
+    ///
+    /// The age of an existing file is measured from the time it is opened.
+    pub(super) fn open(config: &LogFileConfig) -> io::Result<Self> {
+        if let Some(dir) = config.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if config.path.file_name().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid log file path {}", config.path.display()),
+            ));
+        }
+        let file = open_for_append(&config.path)?;
+        let file_size = file.metadata()?.len();
+        Ok(RotatingFile {
+            path: config.path.clone(),
+            max_file_size: config.max_file_size,
+            max_file_age: config.max_file_age.map(Duration::from),
+            max_rotated_files: config.max_rotated_files,
+            compress: config.compress,
+            file,
+            file_size,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn needs_rotation(&self) -> bool {
+        self.file_size >= self.max_file_size
+            || self
+                .max_file_age
+                .map_or(false, |max_age| self.opened_at.elapsed() >= max_age)
+    }
+
+    /// Renames the current file to the next rotated file, deletes the oldest rotated files beyond
+    /// the retention limit, starts a new current file and compresses the rotated file if enabled.
+    fn rotate(&mut self) -> io::Result<()> {
+        let dir = self.dir();
+        let file_name = self.file_name();
+        let mut rotated = rotated_files(&dir, &file_name)?;
+        let next_number = rotated.last().map_or(0, |(number, _)| number + 1);
+        let mut rotated_path = self.path.clone().into_os_string();
+        rotated_path.push(format!(".{}", next_number));
+        let rotated_path = PathBuf::from(rotated_path);
+        fs::rename(&self.path, &rotated_path)?;
+        self.file = open_for_append(&self.path)?;
+        rotated.push((next_number, rotated_path.clone()));
+
+        let excess = rotated
+            .len()
+            .saturating_sub(self.max_rotated_files as usize);
+        for (_, path) in rotated.drain(..excess) {
+            if let Err(error) = fs::remove_file(&path) {
+                eprintln!(
+                    "failed to remove old log file {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+
+        if self.compress && self.max_rotated_files > 0 {
+            thread::spawn(move || {
+                if let Err(error) = compress(&rotated_path) {
+                    eprintln!(
+                        "failed to compress log file {}: {}",
+                        rotated_path.display(),
+                        error
+                    );
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn dir(&self) -> PathBuf {
+        match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    fn file_name(&self) -> OsString {
+        self.path.file_name().unwrap_or_default().to_os_string()
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.needs_rotation() {
+            if let Err(error) = self.rotate() {
+                eprintln!(
+                    "failed to rotate log file {}: {}",
+                    self.path.display(),
+                    error
+                );
+            }
+            // Reset even on failure, so that rotation is not retried for every write.
+            self.file_size = 0;
+            self.opened_at = Instant::now();
+        }
+        // Writing the whole buffer keeps each log line within a single file.
+        self.file.write_all(buf)?;
+        self.file_size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_for_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Returns the rotated, possibly compressed, files of the log file named `file_name` in the given
+/// directory along with their rotation numbers, ordered oldest first.
+fn rotated_files(dir: &Path, file_name: &OsString) -> io::Result<Vec<(u64, PathBuf)>> {
+    let prefix = format!("{}.", file_name.to_string_lossy());
+    let mut rotated = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let entry_name = entry.file_name();
+        let maybe_number = entry_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .map(|name| name.strip_suffix(COMPRESSED_EXTENSION).unwrap_or(name))
+            .and_then(|number| number.parse::<u64>().ok());
+        if let Some(number) = maybe_number {
+            rotated.push((number, entry.path()));
+        }
+    }
+    rotated.sort_unstable_by_key(|(number, _)| *number);
+    Ok(rotated)
+}
+
+/// Compresses the file at `path` to `<PATH>.gz` and removes the original.
+///
+/// The compressed file is written under a temporary name first, so that an interrupted
+/// compression never leaves behind a truncated `.gz` file.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed_path = path.as_os_str().to_os_string();
+    compressed_path.push(COMPRESSED_EXTENSION);
+    let compressed_path = PathBuf::from(compressed_path);
+    let mut temporary_path = compressed_path.clone().into_os_string();
+    temporary_path.push(TEMPORARY_EXTENSION);
+    let temporary_path = PathBuf::from(temporary_path);
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&temporary_path)?, Compression::default());
+    let _ = io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::rename(&temporary_path, &compressed_path)?;
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    fn config(path: PathBuf, max_file_size: u64, max_rotated_files: u32) -> LogFileConfig {
+        LogFileConfig {
+            path,
+            max_file_size,
+            max_file_age: None,
+            max_rotated_files,
+            compress: false,
+        }
+    }
+
+    #[test]
+    fn should_rotate_and_retain_limited_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("logs").join("node.log");
+
+        // A maximum size of one byte causes rotation before every line after the first.  Each line
+        // is written in a single call, as the log formatter does.
+        let mut file = RotatingFile::open(&config(path.clone(), 1, 2)).unwrap();
+        for line in 0..5 {
+            file.write_all(format!("{}\n", line).as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "4\n");
+        let rotated = rotated_files(&tempdir.path().join("logs"), &"node.log".into()).unwrap();
+        let rotated_numbers: Vec<_> = rotated.iter().map(|(number, _)| *number).collect();
+        assert_eq!(rotated_numbers, vec![2, 3]);
+        assert_eq!(fs::read_to_string(&rotated[0].1).unwrap(), "2\n");
+        assert_eq!(fs::read_to_string(&rotated[1].1).unwrap(), "3\n");
+    }
+
+    #[test]
+    fn should_compress_rotated_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("node.log.0");
+        fs::write(&path, "rotated\n").unwrap();
+
+        compress(&path).unwrap();
+
+        assert!(!path.exists());
+        let compressed_path = tempdir.path().join("node.log.0.gz");
+        let mut decompressed = String::new();
+        GzDecoder::new(File::open(&compressed_path).unwrap())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "rotated\n");
+        let rotated = rotated_files(tempdir.path(), &"node.log".into()).unwrap();
+        assert_eq!(rotated, vec![(0, compressed_path)]);
+    }
+}
@@ -17,7 +17,9 @@ use crate::{
         consensus,
         fetcher::{FetchItem, FetchResponse, Tag},
         gossiper,
-        network::{EstimatorWeights, FromIncoming, GossipedAddress, MessageKind, Payload},
+        network::{
+            CostWeights, EstimatorWeights, FromIncoming, GossipedAddress, MessageKind, Payload,
+        },
     },
     effect::{
         incoming::{
@@ -88,6 +90,7 @@ impl Payload for Message {
                 Tag::SyncLeap => MessageKind::BlockTransfer,
                 Tag::ApprovalsHashes => MessageKind::BlockTransfer,
                 Tag::BlockExecutionResults => MessageKind::BlockTransfer,
+                Tag::EraValidators => MessageKind::BlockTransfer,
             },
             Message::FinalitySignature(_) => MessageKind::Consensus,
             Message::FinalitySignatureGossiper(_) => MessageKind::FinalitySignatureGossip,
@@ -111,6 +114,22 @@ impl Payload for Message {
         }
     }
 
+    fn is_droppable(&self) -> bool {
+        // Gossip is redundant, as peers will keep offering the same items, so it is safe to drop
+        // under load.
+        match self {
+            Message::DeployGossiper(_)
+            | Message::BlockGossiper(_)
+            | Message::FinalitySignatureGossiper(_)
+            | Message::AddressGossiper(_) => true,
+            Message::Consensus(_)
+            | Message::ConsensusRequest(_)
+            | Message::GetRequest { .. }
+            | Message::GetResponse { .. }
+            | Message::FinalitySignature(_) => false,
+        }
+    }
+
     #[inline]
     fn incoming_resource_estimate(&self, weights: &EstimatorWeights) -> u32 {
         match self {
@@ -130,6 +149,7 @@ impl Payload for Message {
                 Tag::SyncLeap => weights.sync_leap_requests,
                 Tag::ApprovalsHashes => weights.approvals_hashes_requests,
                 Tag::BlockExecutionResults => weights.execution_results_requests,
+                Tag::EraValidators => weights.era_validators_requests,
             },
             Message::GetResponse { tag, .. } => match tag {
                 Tag::Deploy => weights.deploy_responses,
@@ -141,11 +161,26 @@ impl Payload for Message {
                 Tag::SyncLeap => weights.sync_leap_responses,
                 Tag::ApprovalsHashes => weights.approvals_hashes_responses,
                 Tag::BlockExecutionResults => weights.execution_results_responses,
+                Tag::EraValidators => weights.era_validators_responses,
             },
             Message::FinalitySignature(_) => weights.finality_signature_broadcasts,
         }
     }
 
+    #[inline]
+    fn incoming_cost(&self, weights: &CostWeights) -> u32 {
+        match self {
+            Message::Consensus(_) | Message::ConsensusRequest(_) => weights.consensus,
+            Message::BlockGossiper(_)
+            | Message::DeployGossiper(_)
+            | Message::FinalitySignatureGossiper(_)
+            | Message::AddressGossiper(_) => weights.gossip,
+            Message::GetRequest { .. } => weights.requests,
+            Message::GetResponse { .. } => weights.responses,
+            Message::FinalitySignature(_) => weights.finality_signatures,
+        }
+    }
+
     fn is_unsafe_for_syncing_peers(&self) -> bool {
         match self {
             Message::Consensus(_) => false,
@@ -386,6 +421,11 @@ where
                     message: Box::new(NetRequest::BlockExecutionResults(serialized_id)),
                 }
                 .into(),
+                Tag::EraValidators => NetRequestIncoming {
+                    sender,
+                    message: Box::new(NetRequest::EraValidators(serialized_id)),
+                }
+                .into(),
             },
             Message::GetResponse {
                 tag,
@@ -436,6 +476,11 @@ where
                     message: Box::new(NetResponse::BlockExecutionResults(serialized_item)),
                 }
                 .into(),
+                Tag::EraValidators => NetResponseIncoming {
+                    sender,
+                    message: Box::new(NetResponse::EraValidators(serialized_item)),
+                }
+                .into(),
             },
             Message::FinalitySignature(message) => {
                 FinalitySignatureIncoming { sender, message }.into()
@@ -29,35 +29,43 @@
 //! With all these set up, a reactor can be executed using a [`Runner`], either in a step-wise
 //! manner using [`Runner::crank`] or indefinitely using [`Runner::run`].
 
+mod event_queue_config;
 mod event_queue_metrics;
+mod event_trace;
 pub(crate) mod main_reactor;
 mod queue_kind;
 
 use std::{
     any,
+    cell::Cell,
     collections::HashMap,
     env,
-    fmt::{Debug, Display},
+    fmt::{self, Debug, Display, Formatter},
     io::Write,
     mem,
     num::NonZeroU64,
+    panic::{self, AssertUnwindSafe},
     str::FromStr,
     sync::{atomic::Ordering, Arc},
 };
 
 use datasize::DataSize;
+use enum_iterator::IntoEnumIterator;
 use erased_serde::Serialize as ErasedSerialize;
 #[cfg(test)]
 use fake_instant::FakeClock;
 use futures::{future::BoxFuture, FutureExt};
 use once_cell::sync::Lazy;
-use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use prometheus::{
+    self, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry,
+};
 use quanta::{Clock, IntoNanoseconds};
 use serde::Serialize;
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
 use stats_alloc::{Stats, INSTRUMENTED_SYSTEM};
 use tokio::time::{Duration, Instant};
-use tracing::{debug_span, error, info, instrument, trace, warn, Span};
+use tracing::{debug_span, error, field, info, instrument, trace, warn, Span};
 use tracing_futures::Instrument;
 
 #[cfg(test)]
@@ -70,35 +78,36 @@ use utils::rlimit::{Limit, OpenFiles, ResourceLimit};
 use crate::testing::{network::NetworkedReactor, ConditionCheckReactor};
 use crate::{
     components::{
-        block_accumulator, deploy_acceptor,
         fetcher::{self, FetchItem},
-        network::{blocklist::BlocklistJustification, Identity as NetworkIdentity},
+        network::Identity as NetworkIdentity,
     },
     effect::{
-        announcements::{ControlAnnouncement, PeerBehaviorAnnouncement, QueueDumpFormat},
+        announcements::{ControlAnnouncement, QueueDumpFormat},
         incoming::NetResponse,
         Effect, EffectBuilder, EffectExt, Effects,
     },
     failpoints::FailpointActivation,
+    logging,
     types::{
         ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Chainspec,
-        ChainspecRawBytes, Deploy, ExitCode, FinalitySignature, LegacyDeploy, NodeId, SyncLeap,
-        TrieOrChunk,
+        ChainspecRawBytes, Deploy, DeployHash, ExitCode, FinalitySignature, LegacyDeploy, NodeId,
+        SignedEraValidators, SyncLeap,
     },
     unregister_metric,
     utils::{self, SharedFlag, WeightedRoundRobin},
-    NodeRng, TERMINATION_REQUESTED,
+    NodeRng, LOG_FILTER_TOGGLE_REQUESTED, RELOAD_REQUESTED, TERMINATION_REQUESTED,
 };
+pub use event_queue_config::EventQueueConfig;
+pub(crate) use event_trace::EventTrace;
 pub(crate) use queue_kind::QueueKind;
 
-/// Default threshold for when an event is considered slow.  Can be overridden by setting the env
-/// var `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
-const DEFAULT_DISPATCH_EVENT_THRESHOLD: Duration = Duration::from_secs(1);
+/// Env var overriding the configured threshold for when an event is considered slow, set as
+/// `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
 const DISPATCH_EVENT_THRESHOLD_ENV_VAR: &str = "CL_EVENT_MAX_MICROSECS";
 #[cfg(test)]
 const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
-static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
+static DISPATCH_EVENT_THRESHOLD_OVERRIDE: Lazy<Option<Duration>> = Lazy::new(|| {
     env::var(DISPATCH_EVENT_THRESHOLD_ENV_VAR)
         .map(|threshold_str| {
             let threshold_microsecs = u64::from_str(&threshold_str).unwrap_or_else(|error| {
@@ -109,9 +118,73 @@ static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
             });
             Duration::from_micros(threshold_microsecs)
         })
-        .unwrap_or_else(|_| DEFAULT_DISPATCH_EVENT_THRESHOLD)
+        .ok()
 });
 
+thread_local! {
+    /// Set while an event is dispatched to a component that is restarted if it panics.
+    static IN_SUPERVISED_DISPATCH: Cell<bool> = Cell::new(false);
+
+    /// Operation ID of the event currently being dispatched, inherited by all resulting events.
+    static CURRENT_OPERATION_ID: Cell<Option<OperationId>> = Cell::new(None);
+}
+
+tokio::task_local! {
+    /// Operation ID of the event whose effects are being run, inherited by all events they
+    /// schedule, including requests made through the `EffectBuilder`.
+    static EFFECT_OPERATION_ID: Option<OperationId>;
+}
+
+/// Identifier tying together events belonging to the same logical operation across components.
+///
+/// Once set while handling an event, the operation ID is inherited by all events resulting from
+/// it, e.g. a deploy being received, stored, announced and gossiped. This includes the requests
+/// made by the event's effects and the events their responses result in. It is recorded as the
+/// `operation_id` field of the reactor's dispatch span, so the operation can be followed in logs.
+#[derive(Clone, Copy, Debug, DataSize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) struct OperationId(u64);
+
+impl Display for OperationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl From<&DeployHash> for OperationId {
+    /// Uses the leading eight bytes of the hash, matching the beginning of its hex representation.
+    fn from(deploy_hash: &DeployHash) -> Self {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&deploy_hash.inner().value()[..8]);
+        OperationId(u64::from_be_bytes(bytes))
+    }
+}
+
+/// Sets the operation ID of the event currently being dispatched.
+///
+/// All events resulting from the current event will carry the given operation ID. Has no effect
+/// outside of event dispatching.
+pub(crate) fn set_operation_id(operation_id: OperationId) {
+    CURRENT_OPERATION_ID.with(|current| current.set(Some(operation_id)));
+    Span::current().record("operation_id", field::display(operation_id));
+}
+
+/// Origin of a scheduled event.
+#[derive(Clone, Copy, Debug, Default, DataSize, Serialize)]
+pub(crate) struct EventOrigin {
+    /// ID of the event whose handling resulted in this event, if any.
+    #[data_size(skip)]
+    ancestor: Option<NonZeroU64>,
+    /// Operation ID inherited from the ancestor, if any.
+    operation_id: Option<OperationId>,
+}
+
+/// Returns whether the current thread is dispatching an event to a supervised component.
+///
+/// Used by the panic hook to avoid aborting the process on panics the reactor will recover from.
+pub fn in_supervised_dispatch() -> bool {
+    IN_SUPERVISED_DISPATCH.with(Cell::get)
+}
+
 #[cfg(target_os = "linux")]
 /// The desired limit for open files.
 const TARGET_OPEN_FILES_LIMIT: Limit = 64_000;
@@ -167,9 +240,9 @@ fn adjust_open_files_limit() {
 ///
 /// Components rarely use this, but use a bound `EventQueueHandle` instead.
 ///
-/// Schedule tuples contain an optional ancestor ID and the actual event. The ancestor ID indicates
-/// which potential previous event resulted in the event being created.
-pub(crate) type Scheduler<Ev> = WeightedRoundRobin<(Option<NonZeroU64>, Ev), QueueKind>;
+/// Schedule tuples contain the event's origin and the actual event. The origin indicates which
+/// potential previous event resulted in the event being created, and its operation ID.
+pub(crate) type Scheduler<Ev> = WeightedRoundRobin<(EventOrigin, Ev), QueueKind>;
 
 /// Event queue handle
 ///
@@ -214,25 +287,30 @@ impl<REv> EventQueueHandle<REv> {
 
     /// Schedule an event on a specific queue.
     ///
-    /// The scheduled event will not have an ancestor.
+    /// The scheduled event will not have an ancestor. If scheduled from within an effect, it
+    /// inherits the operation ID of the event the effect resulted from.
     pub(crate) async fn schedule<Ev>(self, event: Ev, queue_kind: QueueKind)
     where
         REv: From<Ev>,
     {
-        self.schedule_with_ancestor(None, event, queue_kind).await
+        let origin = EventOrigin {
+            ancestor: None,
+            operation_id: EFFECT_OPERATION_ID.try_with(|id| *id).ok().flatten(),
+        };
+        self.schedule_with_origin(origin, event, queue_kind).await
     }
 
     /// Schedule an event on a specific queue.
-    pub(crate) async fn schedule_with_ancestor<Ev>(
+    pub(crate) async fn schedule_with_origin<Ev>(
         self,
-        ancestor: Option<NonZeroU64>,
+        origin: EventOrigin,
         event: Ev,
         queue_kind: QueueKind,
     ) where
         REv: From<Ev>,
     {
         self.scheduler
-            .push((ancestor, event.into()), queue_kind)
+            .push((origin, event.into()), queue_kind)
             .await
     }
 
@@ -241,6 +319,11 @@ impl<REv> EventQueueHandle<REv> {
         self.scheduler.event_queues_counts()
     }
 
+    /// Returns number of events dropped so far by each of the scheduler's queues.
+    pub(crate) fn event_queues_shed_counts(&self) -> HashMap<QueueKind, usize> {
+        self.scheduler.shed_counts()
+    }
+
     /// Returns whether the associated reactor is currently shutting down.
     pub(crate) fn shutdown_flag(&self) -> SharedFlag {
         self.is_shutting_down
@@ -293,6 +376,33 @@ pub(crate) trait Reactor: Sized {
         rng: &mut NodeRng,
     ) -> Result<(Self, Effects<Self::Event>), Self::Error>;
 
+    /// Applies a configuration reloaded at runtime.
+    ///
+    /// Reactors are expected to only apply values which are safe to change without a restart and
+    /// ignore all others.
+    fn reload_config(
+        &mut self,
+        _effect_builder: EffectBuilder<Self::Event>,
+        _cfg: Self::Config,
+    ) -> Effects<Self::Event> {
+        Effects::new()
+    }
+
+    /// Returns the policy to apply should the component handling `event` panic.
+    fn supervision_policy(&self, _event: &Self::Event) -> SupervisionPolicy {
+        SupervisionPolicy::Escalate
+    }
+
+    /// Resets the named component to a fresh state after it panicked.
+    ///
+    /// Only called for components named by [`SupervisionPolicy::Restart`].
+    fn restart_component(&mut self, _component: &'static str) {}
+
+    /// Returns the event queue limits to apply to the reactor's scheduler.
+    fn event_queue_config(_cfg: &Self::Config) -> EventQueueConfig {
+        EventQueueConfig::default()
+    }
+
     /// Instructs the reactor to update performance metrics, if any.
     fn update_metrics(&mut self, _event_queue_handle: EventQueueHandle<Self::Event>) {}
 
@@ -303,6 +413,15 @@ pub(crate) trait Reactor: Sized {
     }
 }
 
+/// How a panic raised while a component handles an event is dealt with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum SupervisionPolicy {
+    /// The named component is replaced by a fresh instance and the reactor carries on.
+    Restart(&'static str),
+    /// The panic is treated as fatal, aborting the process.
+    Escalate,
+}
+
 /// A reactor event type.
 pub(crate) trait ReactorEvent: Send + Debug + From<ControlAnnouncement> + 'static {
     /// Returns `true` if the event is a control announcement variant.
@@ -372,11 +491,20 @@ where
     /// Only update reactor metrics if at least this much time has passed.
     event_metrics_min_delay: Duration,
 
+    /// Event id at the time of the last reactor metrics update.
+    last_metrics_event_id: u64,
+
+    /// Events taking longer than this to dispatch are logged as warnings.
+    dispatch_event_threshold: Duration,
+
     /// An accurate, possible TSC-supporting clock.
     clock: Clock,
 
     /// Flag indicating the reactor is being shut down.
     is_shutting_down: SharedFlag,
+
+    /// Trace every dispatched event is recorded to or compared against, if any.
+    event_trace: Option<EventTrace>,
 }
 
 /// Metric data for the Runner
@@ -386,6 +514,12 @@ struct RunnerMetrics {
     events: IntCounter,
     /// Histogram of how long it took to dispatch an event.
     event_dispatch_duration: Histogram,
+    /// Histogram of how long it took to dispatch an event, labeled by the handling component.
+    event_dispatch_duration_by_component: HistogramVec,
+    /// Number of events processed per second, averaged since the last metrics update.
+    events_per_second: IntGauge,
+    /// Number of times a component was restarted after panicking, labeled by the component.
+    component_restarts: IntCounterVec,
     /// Total allocated RAM in bytes, as reported by stats_alloc.
     allocated_ram_bytes: IntGauge,
     /// Total consumed RAM in bytes, as reported by sys-info.
@@ -433,6 +567,48 @@ impl RunnerMetrics {
             ]),
         )?;
 
+        let event_dispatch_duration_by_component = HistogramVec::new(
+            HistogramOpts::new(
+                "event_dispatch_duration_by_component",
+                "time in nanoseconds to dispatch an event, by component",
+            )
+            .buckets(vec![
+                100.0,
+                500.0,
+                1_000.0,
+                5_000.0,
+                10_000.0,
+                20_000.0,
+                50_000.0,
+                100_000.0,
+                200_000.0,
+                300_000.0,
+                400_000.0,
+                500_000.0,
+                600_000.0,
+                700_000.0,
+                800_000.0,
+                900_000.0,
+                1_000_000.0,
+                2_000_000.0,
+                5_000_000.0,
+            ]),
+            &["component"],
+        )?;
+
+        let events_per_second = IntGauge::new(
+            "runner_events_per_second",
+            "number of events handled per second since the last metrics update",
+        )?;
+
+        let component_restarts = IntCounterVec::new(
+            Opts::new(
+                "runner_component_restarts",
+                "number of times a component was restarted after panicking, by component",
+            ),
+            &["component"],
+        )?;
+
         let allocated_ram_bytes =
             IntGauge::new("allocated_ram_bytes", "total allocated ram in bytes")?;
         let consumed_ram_bytes =
@@ -441,6 +617,9 @@ impl RunnerMetrics {
 
         registry.register(Box::new(events.clone()))?;
         registry.register(Box::new(event_dispatch_duration.clone()))?;
+        registry.register(Box::new(event_dispatch_duration_by_component.clone()))?;
+        registry.register(Box::new(events_per_second.clone()))?;
+        registry.register(Box::new(component_restarts.clone()))?;
         registry.register(Box::new(allocated_ram_bytes.clone()))?;
         registry.register(Box::new(consumed_ram_bytes.clone()))?;
         registry.register(Box::new(total_ram_bytes.clone()))?;
@@ -448,6 +627,9 @@ impl RunnerMetrics {
         Ok(RunnerMetrics {
             events,
             event_dispatch_duration,
+            event_dispatch_duration_by_component,
+            events_per_second,
+            component_restarts,
             registry: registry.clone(),
             allocated_ram_bytes,
             consumed_ram_bytes,
@@ -460,6 +642,9 @@ impl Drop for RunnerMetrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.events);
         unregister_metric!(self.registry, self.event_dispatch_duration);
+        unregister_metric!(self.registry, self.event_dispatch_duration_by_component);
+        unregister_metric!(self.registry, self.events_per_second);
+        unregister_metric!(self.registry, self.component_restarts);
         unregister_metric!(self.registry, self.allocated_ram_bytes);
         unregister_metric!(self.registry, self.consumed_ram_bytes);
         unregister_metric!(self.registry, self.total_ram_bytes);
@@ -504,10 +689,17 @@ where
         let event_queue_dump_threshold =
             env::var("CL_EVENT_QUEUE_DUMP_THRESHOLD").map_or(None, |s| s.parse::<usize>().ok());
 
-        let scheduler = utils::leak(Scheduler::new(
-            QueueKind::weights(),
-            event_queue_dump_threshold,
-        ));
+        let event_queue_config = R::event_queue_config(&cfg);
+        let dispatch_event_threshold = DISPATCH_EVENT_THRESHOLD_OVERRIDE
+            .unwrap_or_else(|| event_queue_config.max_event_dispatch_duration.into());
+        let mut scheduler =
+            Scheduler::new(event_queue_config.weights(), event_queue_dump_threshold);
+        for queue_kind in QueueKind::into_enum_iter() {
+            if let Some((limit, policy)) = event_queue_config.limit(queue_kind) {
+                scheduler = scheduler.with_limit(queue_kind, limit, policy);
+            }
+        }
+        let scheduler = utils::leak(scheduler);
         let is_shutting_down = SharedFlag::new();
         let event_queue = EventQueueHandle::new(scheduler, is_shutting_down);
         let (reactor, initial_effects) = R::new(
@@ -525,9 +717,14 @@ where
             initial_effects.len()
         );
         // Run all effects from component instantiation.
-        process_effects(None, scheduler, initial_effects, QueueKind::Regular)
-            .instrument(debug_span!("process initial effects"))
-            .await;
+        process_effects(
+            EventOrigin::default(),
+            scheduler,
+            initial_effects,
+            QueueKind::Regular,
+        )
+        .instrument(debug_span!("process initial effects"))
+        .await;
 
         info!("reactor main loop is ready");
 
@@ -539,15 +736,23 @@ where
             last_metrics: Instant::now(),
             event_metrics_min_delay: Duration::from_secs(30),
             event_metrics_threshold: 1000,
+            last_metrics_event_id: 0,
+            dispatch_event_threshold,
             clock: Clock::new(),
             is_shutting_down,
+            event_trace: None,
         })
     }
 
     /// Processes a single event on the event queue.
     ///
     /// Returns `Some(exit_code)` if processing should stop.
-    #[instrument("dispatch", level = "debug", fields(a, ev = self.current_event_id), skip(self, rng))]
+    #[instrument(
+        "dispatch",
+        level = "debug",
+        fields(a, ev = self.current_event_id, component, operation_id),
+        skip(self, rng)
+    )]
     pub(crate) async fn crank(&mut self, rng: &mut NodeRng) -> Option<ExitCode> {
         self.metrics.events.inc();
 
@@ -560,6 +765,15 @@ where
             if self.last_metrics.elapsed() >= self.event_metrics_min_delay {
                 self.reactor.update_metrics(event_queue);
 
+                let elapsed = self.last_metrics.elapsed().as_secs_f64();
+                let events = self.current_event_id - self.last_metrics_event_id;
+                if elapsed > 0.0 {
+                    self.metrics
+                        .events_per_second
+                        .set((events as f64 / elapsed) as i64);
+                }
+                self.last_metrics_event_id = self.current_event_id;
+
                 // Use a fresh timestamp. This skews the metrics collection interval a little bit,
                 // but ensures that if metrics collection time explodes, we are guaranteed a full
                 // `event_metrics_min_delay` of event processing.
@@ -579,17 +793,25 @@ where
             }
         }
 
-        let ((ancestor, event), queue_kind) = self.scheduler.pop().await;
+        let ((origin, event), queue_kind) = self.scheduler.pop().await;
         trace!(%event, %queue_kind, "current");
         let event_desc = event.description();
+        if let Some(event_trace) = self.event_trace.as_mut() {
+            event_trace.trace(self.current_event_id, queue_kind, &event);
+        }
 
         // Create another span for tracing the processing of one event.
         Span::current().record("ev", self.current_event_id);
+        Span::current().record(logging::LOG_FIELD_COMPONENT, event_desc);
 
         // If we know the ancestor of an event, record it.
-        if let Some(ancestor) = ancestor {
+        if let Some(ancestor) = origin.ancestor {
             Span::current().record("a", ancestor.get());
         }
+        CURRENT_OPERATION_ID.with(|current| current.set(None));
+        if let Some(operation_id) = origin.operation_id {
+            set_operation_id(operation_id);
+        }
 
         // Dispatch the event, then execute the resulting effect.
         let start = self.clock.start();
@@ -615,9 +837,14 @@ where
                 Some(ControlAnnouncement::ShutdownForUpgrade) => {
                     (Effects::new(), Some(ExitCode::Success), QueueKind::Control)
                 }
-                Some(ControlAnnouncement::FatalError { file, line, msg }) => {
-                    error!(%file, %line, %msg, "fatal error via control announcement");
-                    (Effects::new(), Some(ExitCode::Abort), QueueKind::Control)
+                Some(ControlAnnouncement::FatalError {
+                    file,
+                    line,
+                    msg,
+                    exit_code,
+                }) => {
+                    error!(%file, %line, %msg, ?exit_code, "fatal error via control announcement");
+                    (Effects::new(), Some(exit_code), QueueKind::Control)
                 }
                 Some(ControlAnnouncement::QueueDumpRequest {
                     dump_format,
@@ -674,37 +901,99 @@ where
                 }
             }
         } else {
-            (
-                self.reactor.dispatch_event(effect_builder, rng, event),
-                None,
-                queue_kind,
-            )
+            let effects = match self.reactor.supervision_policy(&event) {
+                SupervisionPolicy::Escalate => {
+                    self.reactor.dispatch_event(effect_builder, rng, event)
+                }
+                SupervisionPolicy::Restart(component) => {
+                    let reactor = &mut self.reactor;
+                    IN_SUPERVISED_DISPATCH.with(|flag| flag.set(true));
+                    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                        reactor.dispatch_event(effect_builder, rng, event)
+                    }));
+                    IN_SUPERVISED_DISPATCH.with(|flag| flag.set(false));
+
+                    match outcome {
+                        Ok(effects) => effects,
+                        Err(_) => {
+                            warn!(
+                                %component,
+                                %event_desc,
+                                "component panicked while handling event, restarting it"
+                            );
+                            self.reactor.restart_component(component);
+                            self.metrics
+                                .component_restarts
+                                .with_label_values(&[component])
+                                .inc();
+                            Effects::new()
+                        }
+                    }
+                }
+            };
+            (effects, None, queue_kind)
         };
 
         let end = self.clock.end();
 
         // Warn if processing took a long time, record to histogram.
         let delta = self.clock.delta(start, end);
-        if delta > *DISPATCH_EVENT_THRESHOLD {
+        if delta > self.dispatch_event_threshold {
             warn!(%event_desc, ns = delta.into_nanos(), "event took very long to dispatch");
         }
         self.metrics
             .event_dispatch_duration
             .observe(delta.into_nanos() as f64);
+        self.metrics
+            .event_dispatch_duration_by_component
+            .with_label_values(&[event_desc])
+            .observe(delta.into_nanos() as f64);
 
         // Run effects, with the current event ID as the ancestor for resulting set of events.
+        let origin = EventOrigin {
+            ancestor: NonZeroU64::new(self.current_event_id),
+            operation_id: CURRENT_OPERATION_ID.with(Cell::take),
+        };
+        process_effects(origin, self.scheduler, effects, queue_kind)
+            .in_current_span()
+            .await;
+
+        self.current_event_id += 1;
+
+        maybe_exit_code
+    }
+
+    /// Sets the trace every subsequently dispatched event is recorded to or compared against.
+    pub(crate) fn set_event_trace(&mut self, event_trace: EventTrace) {
+        self.event_trace = Some(event_trace);
+    }
+
+    /// Reloads the configuration and hands it to the reactor.
+    async fn reload_config<F>(&mut self, load_config: &F)
+    where
+        F: Fn() -> anyhow::Result<R::Config>,
+    {
+        let cfg = match load_config() {
+            Ok(cfg) => cfg,
+            Err(error) => {
+                warn!(%error, "failed to reload config, keeping current values");
+                return;
+            }
+        };
+
+        info!("reloading config");
+        let event_queue = EventQueueHandle::new(self.scheduler, self.is_shutting_down);
+        let effects = self
+            .reactor
+            .reload_config(EffectBuilder::new(event_queue), cfg);
         process_effects(
-            NonZeroU64::new(self.current_event_id),
+            EventOrigin::default(),
             self.scheduler,
             effects,
-            queue_kind,
+            QueueKind::Control,
         )
         .in_current_span()
         .await;
-
-        self.current_event_id += 1;
-
-        maybe_exit_code
     }
 
     /// Gets both the allocated and total memory from sys-info + jemalloc
@@ -739,10 +1028,26 @@ where
 
     /// Runs the reactor until `self.crank` returns `Some` or we get interrupted by a termination
     /// signal.
-    pub(crate) async fn run(&mut self, rng: &mut NodeRng) -> ExitCode {
+    ///
+    /// Whenever a reload is requested via `SIGHUP`, `load_config` is called and the resulting
+    /// configuration is passed to the reactor.
+    pub(crate) async fn run<F>(&mut self, rng: &mut NodeRng, load_config: F) -> ExitCode
+    where
+        F: Fn() -> anyhow::Result<R::Config>,
+    {
         loop {
             match TERMINATION_REQUESTED.load(Ordering::SeqCst) as i32 {
                 0 => {
+                    if RELOAD_REQUESTED.swap(false, Ordering::SeqCst) {
+                        self.reload_config(&load_config).await;
+                    }
+                    if LOG_FILTER_TOGGLE_REQUESTED.swap(false, Ordering::SeqCst) {
+                        match logging::toggle_debug_filter() {
+                            Ok(true) => info!("switched to debug log filter"),
+                            Ok(false) => info!("switched back to regular log filter"),
+                            Err(error) => warn!(%error, "failed to toggle log filter"),
+                        }
+                    }
                     if let Some(exit_code) = self.crank(rng).await {
                         self.is_shutting_down.set();
                         break exit_code;
@@ -766,6 +1071,26 @@ where
     }
 }
 
+impl<R> Runner<R>
+where
+    R: Reactor + Finalize,
+{
+    /// Shuts down the reactor gracefully.
+    ///
+    /// Seals the event queue so that no further events are accepted, then finalizes the reactor,
+    /// giving up on waiting for it if `timeout` expires first.
+    pub(crate) async fn finalize(self, timeout: Duration) {
+        self.is_shutting_down.set();
+        self.scheduler.seal();
+
+        info!(?timeout, "finalizing reactor");
+        match tokio::time::timeout(timeout, self.reactor.finalize()).await {
+            Ok(()) => info!("reactor finalized"),
+            Err(_) => warn!(?timeout, "timed out finalizing reactor"),
+        }
+    }
+}
+
 #[cfg(test)]
 #[derive(Eq, PartialEq, Debug)]
 pub(crate) enum TryCrankOutcome {
@@ -824,12 +1149,17 @@ where
 
         let effects = create_effects(effect_builder);
 
-        process_effects(None, self.scheduler, effects, QueueKind::Regular)
-            .instrument(debug_span!(
-                "process injected effects",
-                ev = self.current_event_id
-            ))
-            .await;
+        process_effects(
+            EventOrigin::default(),
+            self.scheduler,
+            effects,
+            QueueKind::Regular,
+        )
+        .instrument(debug_span!(
+            "process injected effects",
+            ev = self.current_event_id
+        ))
+        .await;
     }
 
     /// Processes a single event if there is one and we haven't previously handled an exit code.
@@ -926,7 +1256,7 @@ where
 ///
 /// Result events from processing the events will be scheduled with the given ancestor.
 async fn process_effects<Ev>(
-    ancestor: Option<NonZeroU64>,
+    origin: EventOrigin,
     scheduler: &'static Scheduler<Ev>,
     effects: Effects<Ev>,
     queue_kind: QueueKind,
@@ -934,11 +1264,11 @@ async fn process_effects<Ev>(
     Ev: Send + 'static,
 {
     for effect in effects {
-        tokio::spawn(async move {
+        tokio::spawn(EFFECT_OPERATION_ID.scope(origin.operation_id, async move {
             for event in effect.await {
-                scheduler.push((ancestor, event), queue_kind).await
+                scheduler.push((origin, event), queue_kind).await
             }
-        });
+        }));
     }
 }
 
@@ -970,111 +1300,289 @@ where
         .collect()
 }
 
-fn handle_fetch_response<R, I>(
-    reactor: &mut R,
-    effect_builder: EffectBuilder<<R as Reactor>::Event>,
-    rng: &mut NodeRng,
+/// Decodes a peer's response to a fetch request and hands it to the fetcher.
+///
+/// Items such as blocks can be large, so decoding is done off the reactor thread.
+fn handle_fetch_response<REv, I>(
+    effect_builder: EffectBuilder<REv>,
     sender: NodeId,
-    serialized_item: &[u8],
-) -> Effects<<R as Reactor>::Event>
+    serialized_item: impl AsRef<[u8]> + Send + 'static,
+) -> Effects<REv>
 where
-    I: FetchItem,
-    R: Reactor,
-    <R as Reactor>::Event: From<fetcher::Event<I>> + From<PeerBehaviorAnnouncement>,
+    I: FetchItem + 'static,
+    REv: From<fetcher::Event<I>> + Send,
 {
-    match fetcher::Event::<I>::from_get_response_serialized_item(sender, serialized_item) {
-        Some(fetcher_event) => {
-            Reactor::dispatch_event(reactor, effect_builder, rng, fetcher_event.into())
-        }
-        None => effect_builder
-            .announce_block_peer_with_justification(
-                sender,
-                BlocklistJustification::SentBadItem { tag: I::TAG },
-            )
-            .ignore(),
-    }
+    effect_builder
+        .run_blocking(move || {
+            fetcher::Event::<I>::from_get_response_serialized_item(sender, serialized_item.as_ref())
+        })
+        .event(REv::from)
 }
 
-fn handle_get_response<R>(
-    reactor: &mut R,
-    effect_builder: EffectBuilder<<R as Reactor>::Event>,
-    rng: &mut NodeRng,
+fn handle_get_response<REv>(
+    effect_builder: EffectBuilder<REv>,
     sender: NodeId,
     message: Box<NetResponse>,
-) -> Effects<<R as Reactor>::Event>
+) -> Effects<REv>
 where
-    R: Reactor,
-    <R as Reactor>::Event: From<deploy_acceptor::Event>
-        + From<fetcher::Event<FinalitySignature>>
+    REv: From<fetcher::Event<FinalitySignature>>
         + From<fetcher::Event<Block>>
         + From<fetcher::Event<BlockHeader>>
         + From<fetcher::Event<BlockExecutionResultsOrChunk>>
         + From<fetcher::Event<LegacyDeploy>>
         + From<fetcher::Event<Deploy>>
         + From<fetcher::Event<SyncLeap>>
-        + From<fetcher::Event<TrieOrChunk>>
         + From<fetcher::Event<ApprovalsHashes>>
-        + From<block_accumulator::Event>
-        + From<PeerBehaviorAnnouncement>,
+        + From<fetcher::Event<SignedEraValidators>>
+        + Send,
 {
     match *message {
-        NetResponse::Deploy(ref serialized_item) => handle_fetch_response::<R, Deploy>(
-            reactor,
-            effect_builder,
-            rng,
-            sender,
-            serialized_item,
-        ),
-        NetResponse::LegacyDeploy(ref serialized_item) => handle_fetch_response::<R, LegacyDeploy>(
-            reactor,
-            effect_builder,
-            rng,
-            sender,
-            serialized_item,
-        ),
-        NetResponse::Block(ref serialized_item) => {
-            handle_fetch_response::<R, Block>(reactor, effect_builder, rng, sender, serialized_item)
+        NetResponse::Deploy(serialized_item) => {
+            handle_fetch_response::<REv, Deploy>(effect_builder, sender, serialized_item)
         }
-        NetResponse::BlockHeader(ref serialized_item) => handle_fetch_response::<R, BlockHeader>(
-            reactor,
-            effect_builder,
-            rng,
-            sender,
-            serialized_item,
-        ),
-        NetResponse::FinalitySignature(ref serialized_item) => {
-            handle_fetch_response::<R, FinalitySignature>(
-                reactor,
+        NetResponse::LegacyDeploy(serialized_item) => {
+            handle_fetch_response::<REv, LegacyDeploy>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::Block(serialized_item) => {
+            handle_fetch_response::<REv, Block>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::BlockHeader(serialized_item) => {
+            handle_fetch_response::<REv, BlockHeader>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::FinalitySignature(serialized_item) => {
+            handle_fetch_response::<REv, FinalitySignature>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::SyncLeap(serialized_item) => {
+            handle_fetch_response::<REv, SyncLeap>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::ApprovalsHashes(serialized_item) => {
+            handle_fetch_response::<REv, ApprovalsHashes>(effect_builder, sender, serialized_item)
+        }
+        NetResponse::BlockExecutionResults(serialized_item) => {
+            handle_fetch_response::<REv, BlockExecutionResultsOrChunk>(
                 effect_builder,
-                rng,
                 sender,
                 serialized_item,
             )
         }
-        NetResponse::SyncLeap(ref serialized_item) => handle_fetch_response::<R, SyncLeap>(
-            reactor,
-            effect_builder,
-            rng,
-            sender,
-            serialized_item,
+        NetResponse::EraValidators(serialized_item) => handle_fetch_response::<
+            REv,
+            SignedEraValidators,
+        >(
+            effect_builder, sender, serialized_item
         ),
-        NetResponse::ApprovalsHashes(ref serialized_item) => {
-            handle_fetch_response::<R, ApprovalsHashes>(
-                reactor,
-                effect_builder,
-                rng,
-                sender,
-                serialized_item,
-            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use derive_more::From;
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::{
+        components::fetcher::FetchResponse,
+        utils::{Loadable, Source},
+    };
+
+    #[derive(Debug, From, Serialize)]
+    enum Event {
+        #[from]
+        ControlAnnouncement(ControlAnnouncement),
+        Count,
+        Panic,
+        Unsupervised,
+    }
+
+    impl Display for Event {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                Event::ControlAnnouncement(ctrl_ann) => write!(f, "control: {}", ctrl_ann),
+                Event::Count => write!(f, "count"),
+                Event::Panic => write!(f, "panic"),
+                Event::Unsupervised => write!(f, "unsupervised"),
+            }
         }
-        NetResponse::BlockExecutionResults(ref serialized_item) => {
-            handle_fetch_response::<R, BlockExecutionResultsOrChunk>(
-                reactor,
-                effect_builder,
-                rng,
-                sender,
-                serialized_item,
-            )
+    }
+
+    impl ReactorEvent for Event {
+        fn is_control(&self) -> bool {
+            matches!(self, Event::ControlAnnouncement(_))
+        }
+
+        fn try_into_control(self) -> Option<ControlAnnouncement> {
+            match self {
+                Event::ControlAnnouncement(ctrl_ann) => Some(ctrl_ann),
+                Event::Count | Event::Panic | Event::Unsupervised => None,
+            }
+        }
+    }
+
+    /// Reactor with a single supervised component which counts events until it panics.
+    #[derive(Debug)]
+    struct SupervisingReactor {
+        count: u64,
+        restarts: u64,
+    }
+
+    impl Reactor for SupervisingReactor {
+        type Event = Event;
+        type Config = ();
+        type Error = prometheus::Error;
+
+        fn dispatch_event(
+            &mut self,
+            _effect_builder: EffectBuilder<Event>,
+            _rng: &mut NodeRng,
+            event: Event,
+        ) -> Effects<Event> {
+            match event {
+                Event::ControlAnnouncement(ctrl_ann) => {
+                    unreachable!("unhandled control announcement: {}", ctrl_ann)
+                }
+                Event::Count | Event::Unsupervised => self.count += 1,
+                Event::Panic => panic!("counter panicked"),
+            }
+            Effects::new()
+        }
+
+        fn new(
+            _cfg: Self::Config,
+            _chainspec: Arc<Chainspec>,
+            _chainspec_raw_bytes: Arc<ChainspecRawBytes>,
+            _network_identity: NetworkIdentity,
+            _registry: &Registry,
+            _event_queue: EventQueueHandle<Event>,
+            _rng: &mut NodeRng,
+        ) -> Result<(Self, Effects<Event>), prometheus::Error> {
+            let reactor = SupervisingReactor {
+                count: 0,
+                restarts: 0,
+            };
+            Ok((reactor, Effects::new()))
+        }
+
+        fn supervision_policy(&self, event: &Event) -> SupervisionPolicy {
+            match event {
+                Event::Count | Event::Panic => SupervisionPolicy::Restart("counter"),
+                Event::ControlAnnouncement(_) | Event::Unsupervised => SupervisionPolicy::Escalate,
+            }
+        }
+
+        fn restart_component(&mut self, component: &'static str) {
+            assert_eq!(component, "counter");
+            self.count = 0;
+            self.restarts += 1;
+        }
+    }
+
+    async fn crank_event(runner: &mut Runner<SupervisingReactor>, rng: &mut TestRng, event: Event) {
+        runner
+            .process_injected_effects(|effect_builder| {
+                effect_builder.immediately().event(move |_| event)
+            })
+            .await;
+        // The injected effect runs on a spawned task, so wait for its event rather than only
+        // trying to crank.
+        assert!(runner.crank(rng).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn should_restart_supervised_component_after_panic() {
+        let mut rng = TestRng::new();
+        let (chainspec, chainspec_raw_bytes) =
+            <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        let mut runner: Runner<SupervisingReactor> = Runner::new(
+            (),
+            Arc::new(chainspec),
+            Arc::new(chainspec_raw_bytes),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+
+        crank_event(&mut runner, &mut rng, Event::Count).await;
+        crank_event(&mut runner, &mut rng, Event::Count).await;
+        assert_eq!(runner.reactor().count, 2);
+
+        // The panic is caught, the component is restarted with fresh state and the restart is
+        // counted against it.
+        crank_event(&mut runner, &mut rng, Event::Panic).await;
+        assert_eq!(runner.reactor().count, 0);
+        assert_eq!(runner.reactor().restarts, 1);
+        assert!(!in_supervised_dispatch());
+        let restarts = runner
+            .metrics
+            .component_restarts
+            .with_label_values(&["counter"])
+            .get();
+        assert_eq!(restarts, 1);
+
+        // The reactor carries on handling events.
+        crank_event(&mut runner, &mut rng, Event::Count).await;
+        crank_event(&mut runner, &mut rng, Event::Unsupervised).await;
+        assert_eq!(runner.reactor().count, 2);
+        assert_eq!(runner.reactor().restarts, 1);
+    }
+
+    #[tokio::test]
+    async fn should_decode_fetch_responses_for_the_fetcher() {
+        let mut rng = TestRng::new();
+        let scheduler = utils::leak(Scheduler::<fetcher::Event<Deploy>>::new(
+            QueueKind::weights(),
+            None,
+        ));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        let sender = NodeId::random(&mut rng);
+
+        let deploy = Deploy::random_valid_native_transfer(&mut rng);
+        let serialized_item = FetchResponse::<_, DeployHash>::Fetched(deploy.clone())
+            .to_serialized()
+            .unwrap();
+        let mut effects =
+            handle_fetch_response::<_, Deploy>(effect_builder, sender, serialized_item);
+        let events = effects.pop().unwrap().await;
+        assert!(matches!(
+            &events[..],
+            [fetcher::Event::GotRemotely { item, source: Source::Peer(peer) }]
+                if **item == deploy && *peer == sender
+        ));
+
+        let mut effects = handle_fetch_response::<_, Deploy>(effect_builder, sender, vec![0xff; 3]);
+        let events = effects.pop().unwrap().await;
+        assert!(matches!(
+            &events[..],
+            [fetcher::Event::UndecodableRemotely { peer }] if *peer == sender
+        ));
+    }
+
+    #[tokio::test]
+    async fn events_scheduled_by_effects_should_inherit_operation_id() {
+        let scheduler = utils::leak(Scheduler::<u8>::new(QueueKind::weights(), None));
+        let event_queue = EventQueueHandle::without_shutdown(scheduler);
+        let operation_id = OperationId(42);
+        let origin = EventOrigin {
+            ancestor: NonZeroU64::new(1),
+            operation_id: Some(operation_id),
+        };
+
+        // The effect schedules an event directly, as requests made through the `EffectBuilder`
+        // do, and results in a second event.
+        let effect: Effect<u8> = async move {
+            event_queue.schedule(1, QueueKind::Regular).await;
+            smallvec![2]
         }
+        .boxed();
+        process_effects(origin, scheduler, smallvec![effect], QueueKind::Regular).await;
+
+        let ((request_origin, request), _) = scheduler.pop().await;
+        assert_eq!(request, 1);
+        assert!(request_origin.ancestor.is_none());
+        assert_eq!(request_origin.operation_id, Some(operation_id));
+
+        let ((result_origin, result), _) = scheduler.pop().await;
+        assert_eq!(result, 2);
+        assert_eq!(result_origin.ancestor, NonZeroU64::new(1));
+        assert_eq!(result_origin.operation_id, Some(operation_id));
     }
 }
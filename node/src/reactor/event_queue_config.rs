@@ -0,0 +1,111 @@
+//! Event queue configuration.
+
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use casper_types::TimeDiff;
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{reactor::QueueKind, utils::round_robin::OverflowPolicy};
+
+/// Default maximum number of events held by a queue whose events must be kept.
+const DEFAULT_MAX_QUEUE_SIZE: usize = 100_000;
+/// Default maximum number of events held by a queue whose events may be shed.
+const DEFAULT_MAX_SHEDDABLE_QUEUE_SIZE: usize = 10_000;
+/// Default threshold above which dispatching a single event is considered slow.
+const DEFAULT_MAX_EVENT_DISPATCH_DURATION: &str = "1sec";
+
+/// Event queue configuration.
+#[derive(Clone, DataSize, Debug, Serialize, Deserialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(default, deny_unknown_fields)]
+pub struct EventQueueConfig {
+    /// Maximum number of events held by each queue whose events must not be dropped, such as
+    /// consensus. Once reached, producers wait for events to be processed. `0` means unbounded.
+    pub max_queue_size: usize,
+    /// Maximum number of events held by each queue whose events may be dropped, such as incoming
+    /// gossip. Once reached, new events are shed. `0` means unbounded.
+    pub max_sheddable_queue_size: usize,
+    /// Overrides of the scheduling weights of individual queues, keyed by queue name.
+    ///
+    /// The weight determines how many events are at most processed from a queue during each
+    /// round. Queues not listed keep their built-in weight.
+    pub weights: BTreeMap<String, usize>,
+    /// Dispatching a single event taking longer than this is logged as a warning, since it blocks
+    /// the reactor. Can be overridden by the `CL_EVENT_MAX_MICROSECS` env var.
+    pub max_event_dispatch_duration: TimeDiff,
+}
+
+impl Default for EventQueueConfig {
+    fn default() -> Self {
+        EventQueueConfig {
+            max_queue_size: DEFAULT_MAX_QUEUE_SIZE,
+            max_sheddable_queue_size: DEFAULT_MAX_SHEDDABLE_QUEUE_SIZE,
+            weights: BTreeMap::new(),
+            max_event_dispatch_duration: DEFAULT_MAX_EVENT_DISPATCH_DURATION.parse().unwrap(),
+        }
+    }
+}
+
+impl EventQueueConfig {
+    /// Returns the limit and overflow policy for the given queue, or `None` if it is unbounded.
+    ///
+    /// The control queue is never bounded, as it is required to shut down the reactor.
+    pub(crate) fn limit(&self, queue_kind: QueueKind) -> Option<(NonZeroUsize, OverflowPolicy)> {
+        if queue_kind == QueueKind::Control {
+            return None;
+        }
+
+        if queue_kind.is_sheddable() {
+            NonZeroUsize::new(self.max_sheddable_queue_size)
+                .map(|limit| (limit, OverflowPolicy::Shed))
+        } else {
+            NonZeroUsize::new(self.max_queue_size).map(|limit| (limit, OverflowPolicy::Block))
+        }
+    }
+
+    /// Returns the weights of all queues, with any configured overrides applied.
+    pub(crate) fn weights(&self) -> Vec<(QueueKind, NonZeroUsize)> {
+        for (name, weight) in &self.weights {
+            if QueueKind::from_metrics_name(name).is_none() {
+                warn!(%name, "ignoring weight for unknown event queue");
+            } else if *weight == 0 {
+                warn!(%name, "ignoring zero weight for event queue");
+            }
+        }
+
+        QueueKind::weights()
+            .into_iter()
+            .map(|(queue_kind, default_weight)| {
+                let weight = self
+                    .weights
+                    .get(queue_kind.metrics_name())
+                    .and_then(|weight| NonZeroUsize::new(*weight))
+                    .unwrap_or(default_weight);
+                (queue_kind, weight)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_override_queue_weights() {
+        let mut config = EventQueueConfig::default();
+        config.weights.insert("consensus".to_string(), 32);
+        config.weights.insert("api".to_string(), 0);
+        config.weights.insert("no_such_queue".to_string(), 5);
+
+        let weights: BTreeMap<_, _> = config.weights().into_iter().collect();
+        let defaults: BTreeMap<_, _> = QueueKind::weights().into_iter().collect();
+
+        assert_eq!(weights.len(), defaults.len());
+        assert_eq!(weights[&QueueKind::Consensus].get(), 32);
+        assert_eq!(weights[&QueueKind::Api], defaults[&QueueKind::Api]);
+        assert_eq!(weights[&QueueKind::Fetch], defaults[&QueueKind::Fetch]);
+    }
+}
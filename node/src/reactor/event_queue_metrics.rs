@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use itertools::Itertools;
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{self, IntCounter, IntGauge, Registry};
 use tracing::{debug, error};
 
 use crate::{
@@ -14,6 +14,8 @@ use crate::{
 pub(super) struct EventQueueMetrics {
     /// Per queue kind gauges that measure number of event in the queue.
     event_queue_gauges: HashMap<QueueKind, IntGauge>,
+    /// Per queue kind counters of the events shed from sheddable queues.
+    shed_event_counters: HashMap<QueueKind, IntCounter>,
     /// Total events count.
     event_total: IntGauge,
     /// Instance of registry to unregister from when being dropped.
@@ -27,6 +29,7 @@ impl EventQueueMetrics {
         event_queue_handle: EventQueueHandle<REv>,
     ) -> Result<Self, prometheus::Error> {
         let mut event_queue_gauges: HashMap<QueueKind, IntGauge> = HashMap::new();
+        let mut shed_event_counters: HashMap<QueueKind, IntCounter> = HashMap::new();
         for queue_kind in event_queue_handle.event_queues_counts().keys() {
             let key = format!("scheduler_queue_{}_count", queue_kind.metrics_name());
             let queue_event_counter = IntGauge::new(
@@ -39,6 +42,19 @@ impl EventQueueMetrics {
             registry.register(Box::new(queue_event_counter.clone()))?;
             let result = event_queue_gauges.insert(*queue_kind, queue_event_counter);
             assert!(result.is_none(), "Map keys should not be overwritten.");
+
+            if queue_kind.is_sheddable() {
+                let key = format!("scheduler_queue_{}_shed_count", queue_kind.metrics_name());
+                let queue_shed_counter = IntCounter::new(
+                    key,
+                    format!(
+                        "total number of events dropped due to the reactor {} queue being full",
+                        queue_kind.metrics_name()
+                    ),
+                )?;
+                registry.register(Box::new(queue_shed_counter.clone()))?;
+                shed_event_counters.insert(*queue_kind, queue_shed_counter);
+            }
         }
 
         let event_total = IntGauge::new(
@@ -49,6 +65,7 @@ impl EventQueueMetrics {
 
         Ok(EventQueueMetrics {
             event_queue_gauges,
+            shed_event_counters,
             event_total,
             registry,
         })
@@ -78,6 +95,14 @@ impl EventQueueMetrics {
             })
             .join(",");
 
+        for (queue, shed_count) in event_queue_handle.event_queues_shed_counts() {
+            if let Some(counter) = self.shed_event_counters.get(&queue) {
+                // The scheduler keeps a running total, so only the events shed since the last
+                // collection are added.
+                counter.inc_by((shed_count as u64).saturating_sub(counter.get()));
+            }
+        }
+
         debug!(%total, %event_counts, "Collected new set of event queue sizes metrics.")
     }
 }
@@ -92,5 +117,12 @@ impl Drop for EventQueueMetrics {
                     .unregister(Box::new(queue_gauge.clone()))
                     .unwrap_or_else(|_| error!("unregistering {} failed: was not registered", key))
             });
+        self.shed_event_counters
+            .iter()
+            .for_each(|(key, shed_counter)| {
+                self.registry
+                    .unregister(Box::new(shed_counter.clone()))
+                    .unwrap_or_else(|_| error!("unregistering {} failed: was not registered", key))
+            });
     }
 }
@@ -0,0 +1,200 @@
+//! Tracing of dispatched events, and comparing a run of the node against such a trace.
+//!
+//! When tracing, every event dispatched by the runner is written to a file as a line of JSON,
+//! preceded by a header containing the seed of the node's RNG.  Each record holds the event's
+//! description along with its serialized form.
+//!
+//! A trace is not a complete recording of the node's inputs: events carry responders and can
+//! therefore not be deserialized and injected again, and the contents of many event variants are
+//! skipped when serializing.  Instead, a trace can be compared against a rerun of the node using
+//! the recorded seed, which reports the first dispatched event diverging from the trace.  Events
+//! whose contents are skipped when serializing are only compared by variant.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, LineWriter, Lines, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{error, info, warn};
+
+use casper_types::Timestamp;
+
+use super::QueueKind;
+
+/// First line of an event trace.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    /// Version of the node which created the trace.
+    version: String,
+    /// Seed of the node's RNG.
+    seed: u64,
+}
+
+/// A single dispatched event.
+#[derive(Debug, Serialize)]
+struct Record<'a, E> {
+    /// The event's ID.
+    id: u64,
+    /// Time at which the event was dispatched.
+    timestamp: Timestamp,
+    /// The queue the event was taken from.
+    queue: QueueKind,
+    /// The event's description, which is available even if its contents are not serialized.
+    description: String,
+    /// The event itself.
+    event: &'a E,
+}
+
+/// An event trace, either being written or compared against.
+#[derive(Debug)]
+pub(crate) enum EventTrace {
+    /// Every dispatched event is appended to the trace.
+    ///
+    /// Each record is written out immediately, so the trace is complete even if the node aborts.
+    Record(LineWriter<File>),
+    /// Every dispatched event is compared against the next event of the trace.
+    Compare {
+        /// Remaining lines of the trace.
+        records: Lines<BufReader<File>>,
+        /// Set once a divergence has been reported, after which the trace is no longer consulted.
+        diverged: bool,
+    },
+}
+
+impl EventTrace {
+    /// Creates a new event trace at `path`, recording the given RNG seed.
+    pub(crate) fn record<P: AsRef<Path>>(path: P, seed: u64) -> io::Result<Self> {
+        let mut writer = LineWriter::new(File::create(path)?);
+        let header = Header {
+            version: crate::VERSION_STRING.to_string(),
+            seed,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        Ok(EventTrace::Record(writer))
+    }
+
+    /// Opens the event trace at `path` for comparing, returning it along with the recorded RNG
+    /// seed.
+    pub(crate) fn compare<P: AsRef<Path>>(path: P) -> io::Result<(Self, u64)> {
+        let mut records = BufReader::new(File::open(path)?).lines();
+        let header_line = records
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "empty event trace"))??;
+        let header: Header = serde_json::from_str(&header_line)?;
+        if header.version != *crate::VERSION_STRING {
+            warn!(
+                recorded_version = %header.version,
+                "comparing against event trace recorded by a different node version"
+            );
+        }
+        let event_trace = EventTrace::Compare {
+            records,
+            diverged: false,
+        };
+        Ok((event_trace, header.seed))
+    }
+
+    /// Records or compares a dispatched event.
+    pub(crate) fn trace<E: Serialize + Display>(&mut self, id: u64, queue: QueueKind, event: &E) {
+        match self {
+            EventTrace::Record(writer) => {
+                let record = Record {
+                    id,
+                    timestamp: Timestamp::now(),
+                    queue,
+                    description: event.to_string(),
+                    event,
+                };
+                let result = serde_json::to_writer(&mut *writer, &record)
+                    .map_err(io::Error::from)
+                    .and_then(|_| writer.write_all(b"\n"));
+                if let Err(err) = result {
+                    warn!(%err, %id, "failed to record event");
+                }
+            }
+            EventTrace::Compare { records, diverged } => {
+                if *diverged {
+                    return;
+                }
+                let recorded = match records.next() {
+                    Some(Ok(line)) => serde_json::from_str::<Value>(&line).ok(),
+                    Some(Err(err)) => {
+                        warn!(%err, %id, "failed to read recorded event");
+                        None
+                    }
+                    None => {
+                        info!(%id, "reached end of event trace");
+                        *diverged = true;
+                        return;
+                    }
+                };
+                let actual = serde_json::to_value(event).ok();
+                let matches = recorded.as_ref().map_or(false, |recorded| {
+                    recorded.get("queue") == serde_json::to_value(queue).ok().as_ref()
+                        && recorded.get("event") == actual.as_ref()
+                });
+                if !matches {
+                    let recorded_description = recorded
+                        .as_ref()
+                        .and_then(|recorded| recorded.get("description"));
+                    error!(
+                        %id,
+                        recorded = ?recorded_description,
+                        actual = %event,
+                        "dispatched event diverges from event trace"
+                    );
+                    *diverged = true;
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if a dispatched event diverged from the trace being compared against.
+    #[cfg(test)]
+    fn has_diverged(&self) -> bool {
+        matches!(self, EventTrace::Compare { diverged: true, .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn should_compare_against_recorded_events() {
+        let tempdir = TempDir::new().unwrap();
+        let path = tempdir.path().join("events.jsonl");
+        let events = ["first", "second", "third"];
+
+        let mut event_trace = EventTrace::record(&path, 42).unwrap();
+        for (id, event) in events.iter().enumerate() {
+            event_trace.trace(id as u64, QueueKind::Regular, event);
+        }
+        drop(event_trace);
+
+        // A run dispatching the same events matches the trace.
+        let (mut event_trace, seed) = EventTrace::compare(&path).unwrap();
+        assert_eq!(seed, 42);
+        for (id, event) in events.iter().enumerate() {
+            event_trace.trace(id as u64, QueueKind::Regular, event);
+            assert!(!event_trace.has_diverged());
+        }
+
+        // A run dispatching a different event or taking it from a different queue diverges.
+        let (mut event_trace, _) = EventTrace::compare(&path).unwrap();
+        event_trace.trace(0, QueueKind::Regular, &"first");
+        event_trace.trace(1, QueueKind::Regular, &"other");
+        assert!(event_trace.has_diverged());
+
+        let (mut event_trace, _) = EventTrace::compare(&path).unwrap();
+        event_trace.trace(0, QueueKind::Control, &"first");
+        assert!(event_trace.has_diverged());
+    }
+}
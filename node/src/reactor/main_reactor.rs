@@ -11,6 +11,7 @@ mod utils;
 mod catch_up;
 mod genesis_instruction;
 mod keep_up;
+mod light_client;
 mod reactor_state;
 #[cfg(test)]
 mod tests;
@@ -21,11 +22,12 @@ mod validate;
 use std::{collections::BTreeMap, sync::Arc, time::Instant};
 
 use datasize::DataSize;
+use futures::{future::BoxFuture, FutureExt};
 use memory_metrics::MemoryMetrics;
 use prometheus::Registry;
 use tracing::{debug, error, info, warn};
 
-use casper_types::{EraId, PublicKey, TimeDiff, Timestamp, U512};
+use casper_types::{EraId, ExecutionResult, PublicKey, SecretKey, TimeDiff, Timestamp, U512};
 
 #[cfg(test)]
 use crate::testing::network::NetworkedReactor;
@@ -34,6 +36,7 @@ use crate::{
         block_accumulator::{self, BlockAccumulator},
         block_synchronizer::{self, BlockSynchronizer},
         block_validator::{self, BlockValidator},
+        clock_monitor::ClockMonitor,
         consensus::{self, EraSupervisor},
         contract_runtime::ContractRuntime,
         deploy_acceptor::{self, DeployAcceptor},
@@ -43,6 +46,7 @@ use crate::{
         gossiper::{self, GossipItem, Gossiper},
         metrics::Metrics,
         network::{self, GossipedAddress, Identity as NetworkIdentity, Network},
+        notifier::{self, Notifier},
         rest_server::RestServer,
         rpc_server::RpcServer,
         shutdown_trigger::{self, ShutdownTrigger},
@@ -53,28 +57,35 @@ use crate::{
     },
     effect::{
         announcements::{
-            BlockAccumulatorAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
-            ControlAnnouncement, DeployAcceptorAnnouncement, DeployBufferAnnouncement,
-            FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement,
-            GossiperAnnouncement, MetaBlockAnnouncement, PeerBehaviorAnnouncement,
-            UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
+            BlockAccumulatorAnnouncement, ClockDriftAnnouncement, ConfigReloadedAnnouncement,
+            ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, DeployBufferAnnouncement, FetchedNewBlockAnnouncement,
+            FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement, MetaBlockAnnouncement,
+            PeerBehaviorAnnouncement, UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
         },
         incoming::{NetResponseIncoming, TrieResponseIncoming},
-        requests::{AcceptDeployRequest, ChainspecRawBytesRequest},
+        requests::{
+            AcceptDeployRequest, AddDeployApprovalsRequest, BlockSynchronizerRequest,
+            ChainspecRawBytesRequest, ContractRuntimeRequest,
+        },
         EffectBuilder, EffectExt, Effects, GossipTarget,
     },
     failpoints::FailpointActivation,
-    fatal,
+    fatal, logging,
     protocol::Message,
     reactor::{
         self,
         event_queue_metrics::EventQueueMetrics,
-        main_reactor::{fetchers::Fetchers, upgrade_shutdown::SignatureGossipTracker},
-        EventQueueHandle, QueueKind,
+        main_reactor::{
+            fetchers::Fetchers, light_client::LightClientHeaders,
+            upgrade_shutdown::SignatureGossipTracker,
+        },
+        EventQueueConfig, EventQueueHandle, Finalize, QueueKind, SupervisionPolicy,
     },
     types::{
         Block, BlockHash, Chainspec, ChainspecRawBytes, Deploy, FinalitySignature, MetaBlock,
-        MetaBlockState, SyncHandling, TrieOrChunk, ValidatorMatrix,
+        MetaBlockState, NodeMode, SyncHandling, TrieOrChunk, TrustedHashDiscoveryConfig,
+        ValidatorMatrix,
     },
     utils::{Source, WithDir},
     NodeRng,
@@ -136,11 +147,13 @@ pub(crate) struct MainReactor {
     storage: Storage,
     contract_runtime: ContractRuntime,
     upgrade_watcher: UpgradeWatcher,
+    clock_monitor: ClockMonitor,
     rpc_server: RpcServer,
     rest_server: RestServer,
     event_stream_server: EventStreamServer,
     diagnostics_port: DiagnosticsPort,
     shutdown_trigger: ShutdownTrigger,
+    notifier: Notifier,
     net: Network<MainEvent, Message>,
     consensus: EraSupervisor,
 
@@ -175,6 +188,7 @@ pub(crate) struct MainReactor {
     //   ambient settings / data / load-bearing config
     validator_matrix: ValidatorMatrix,
     trusted_hash: Option<BlockHash>,
+    trusted_hash_discovery: Option<TrustedHashDiscoveryConfig>,
     chainspec: Arc<Chainspec>,
     chainspec_raw_bytes: Arc<ChainspecRawBytes>,
 
@@ -186,10 +200,16 @@ pub(crate) struct MainReactor {
     attempts: usize,
     idle_tolerance: TimeDiff,
     control_logic_default_delay: TimeDiff,
+    historical_sync_interval: TimeDiff,
+    last_historical_sync_start: Timestamp,
     shutdown_for_upgrade_timeout: TimeDiff,
     switched_to_shutdown_for_upgrade: Timestamp,
     upgrade_timeout: TimeDiff,
     sync_handling: SyncHandling,
+    node_mode: NodeMode,
+    light_client_headers: Option<LightClientHeaders>,
+    min_peers_to_validate: usize,
+    max_tip_age_to_validate: Option<TimeDiff>,
     signature_gossip_tracker: SignatureGossipTracker,
     prevent_validator_shutdown: bool,
 }
@@ -226,6 +246,7 @@ impl reactor::Reactor for MainReactor {
                             file: fatal_ann.file,
                             line: fatal_ann.line,
                             msg: fatal_ann.msg,
+                            exit_code: fatal_ann.exit_code,
                         });
                     effect_builder
                         .into_inner()
@@ -309,6 +330,36 @@ impl reactor::Reactor for MainReactor {
                     ),
                 )
             }
+            MainEvent::ClockMonitor(event) => reactor::wrap_effects(
+                MainEvent::ClockMonitor,
+                self.clock_monitor.handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::ClockDriftAnnouncement(ClockDriftAnnouncement {
+                exceeds_tolerance, ..
+            }) => reactor::wrap_effects(
+                MainEvent::Consensus,
+                self.consensus.handle_event(
+                    effect_builder,
+                    rng,
+                    consensus::Event::ClockDrift { exceeds_tolerance },
+                ),
+            ),
+            MainEvent::ConfigReloadedAnnouncement(ConfigReloadedAnnouncement {
+                logging,
+                gossip,
+                fetcher,
+            }) => {
+                if let Err(error) = logging::reload_with_config(&logging) {
+                    warn!(%error, "failed to apply reloaded logging config");
+                }
+                self.address_gossiper.update_config(*gossip);
+                self.deploy_gossiper.update_config(*gossip);
+                self.block_gossiper.update_config(*gossip);
+                self.finality_signature_gossiper.update_config(*gossip);
+                self.fetchers.update_config(&fetcher);
+                info!("applied reloaded config");
+                Effects::new()
+            }
             MainEvent::RpcServer(event) => reactor::wrap_effects(
                 MainEvent::RpcServer,
                 self.rpc_server.handle_event(effect_builder, rng, event),
@@ -334,6 +385,10 @@ impl reactor::Reactor for MainReactor {
                 self.shutdown_trigger
                     .handle_event(effect_builder, rng, event),
             ),
+            MainEvent::Notifier(event) => reactor::wrap_effects(
+                MainEvent::Notifier,
+                self.notifier.handle_event(effect_builder, rng, event),
+            ),
             MainEvent::DiagnosticsPort(event) => reactor::wrap_effects(
                 MainEvent::DiagnosticsPort,
                 self.diagnostics_port
@@ -383,7 +438,7 @@ impl reactor::Reactor for MainReactor {
                     .handle_event(effect_builder, rng, incoming.into()),
             ),
             MainEvent::NetworkPeerProvidingData(NetResponseIncoming { sender, message }) => {
-                reactor::handle_get_response(self, effect_builder, rng, sender, message)
+                reactor::handle_get_response(effect_builder, sender, message)
             }
             MainEvent::AddressGossiper(event) => reactor::wrap_effects(
                 MainEvent::AddressGossiper,
@@ -482,6 +537,13 @@ impl reactor::Reactor for MainReactor {
                 self.block_synchronizer
                     .handle_event(effect_builder, rng, event),
             ),
+            MainEvent::BlockSynchronizerRequest(
+                BlockSynchronizerRequest::NeedNext | BlockSynchronizerRequest::SyncGlobalStates(_),
+            ) if self.node_mode.is_light() => {
+                // a light client never syncs blocks or global state
+                debug!("MainReactor: ignoring block synchronizer request in light mode");
+                Effects::new()
+            }
             MainEvent::BlockSynchronizerRequest(req) => reactor::wrap_effects(
                 MainEvent::BlockSynchronizer,
                 self.block_synchronizer
@@ -700,6 +762,22 @@ impl reactor::Reactor for MainReactor {
                         .handle_event(effect_builder, rng, event),
                 )
             }
+            MainEvent::AddDeployApprovalsRequest(AddDeployApprovalsRequest {
+                deploy_hash,
+                approvals,
+                responder,
+            }) => {
+                let event = deploy_acceptor::Event::AddApprovals {
+                    deploy_hash,
+                    approvals,
+                    responder,
+                };
+                reactor::wrap_effects(
+                    MainEvent::DeployAcceptor,
+                    self.deploy_acceptor
+                        .handle_event(effect_builder, rng, event),
+                )
+            }
             MainEvent::DeployAcceptorAnnouncement(
                 DeployAcceptorAnnouncement::AcceptedNewDeploy { deploy, source },
             ) => {
@@ -748,9 +826,17 @@ impl reactor::Reactor for MainReactor {
                 effects
             }
             MainEvent::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::InvalidDeploy {
-                deploy: _,
-                source: _,
-            }) => Effects::new(),
+                deploy,
+                source,
+                error,
+            }) => {
+                // Clients are informed via the RPC response; deploys gossiped to us are only
+                // logged.
+                if let Source::PeerGossiped(peer) = source {
+                    debug!(deploy_hash = %deploy.hash(), %peer, %error, "rejected gossiped deploy");
+                }
+                Effects::new()
+            }
             MainEvent::DeployGossiper(event) => reactor::wrap_effects(
                 MainEvent::DeployGossiper,
                 self.deploy_gossiper
@@ -818,6 +904,18 @@ impl reactor::Reactor for MainReactor {
                 self.contract_runtime
                     .handle_event(effect_builder, rng, event),
             ),
+            MainEvent::ContractRuntimeRequest(
+                ContractRuntimeRequest::EnqueueBlockForExecution {
+                    finalized_block, ..
+                },
+            ) if self.node_mode.is_light() => {
+                // a light client never executes blocks
+                error!(
+                    block_height = finalized_block.height(),
+                    "MainReactor: not executing block in light mode"
+                );
+                Effects::new()
+            }
             MainEvent::ContractRuntimeRequest(req) => reactor::wrap_effects(
                 MainEvent::ContractRuntime,
                 self.contract_runtime
@@ -836,6 +934,39 @@ impl reactor::Reactor for MainReactor {
                     });
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
+            MainEvent::ContractRuntimeAnnouncement(
+                ContractRuntimeAnnouncement::DeployProcessed {
+                    era_id,
+                    block_height,
+                    deploy_hash,
+                    deploy_header,
+                    execution_result,
+                },
+            ) => {
+                let reactor_event =
+                    MainEvent::EventStreamServer(event_stream_server::Event::DeployExecuted {
+                        era_id,
+                        block_height,
+                        deploy_hash,
+                        deploy_header,
+                        execution_result,
+                    });
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
+            MainEvent::ContractRuntimeAnnouncement(
+                ContractRuntimeAnnouncement::BlockExecutionFailed {
+                    era_id,
+                    block_height,
+                },
+            ) => {
+                let reactor_event = MainEvent::EventStreamServer(
+                    event_stream_server::Event::BlockExecutionFailed {
+                        era_id,
+                        block_height,
+                    },
+                );
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
             MainEvent::ContractRuntimeAnnouncement(
                 ContractRuntimeAnnouncement::UpcomingEraValidators {
                     era_that_is_ending,
@@ -861,12 +992,10 @@ impl reactor::Reactor for MainReactor {
                     .handle_event(effect_builder, rng, demand.into()),
             ),
             MainEvent::TrieResponseIncoming(TrieResponseIncoming { sender, message }) => {
-                reactor::handle_fetch_response::<Self, TrieOrChunk>(
-                    self,
+                reactor::handle_fetch_response::<MainEvent, TrieOrChunk>(
                     effect_builder,
-                    rng,
                     sender,
-                    &message.0,
+                    message.0,
                 )
             }
 
@@ -892,6 +1021,9 @@ impl reactor::Reactor for MainReactor {
             // states of a block after an upgrade and its parent. Once that happens, we can check
             // for the signs of any changes happening during the upgrade and register the correct
             // set of validators in the validators matrix.
+            MainEvent::LightClientHeaderFetched(result) => {
+                self.light_client_header_fetched(effect_builder, rng, result)
+            }
             MainEvent::GotBlockAfterUpgradeEraValidators(
                 era_id,
                 parent_era_validators,
@@ -981,7 +1113,9 @@ impl reactor::Reactor for MainReactor {
             | MainEvent::FinalitySignatureFetcher(..)
             | MainEvent::FinalitySignatureFetcherRequest(..)
             | MainEvent::BlockExecutionResultsOrChunkFetcher(..)
-            | MainEvent::BlockExecutionResultsOrChunkFetcherRequest(..) => self
+            | MainEvent::BlockExecutionResultsOrChunkFetcherRequest(..)
+            | MainEvent::SignedEraValidatorsFetcher(..)
+            | MainEvent::SignedEraValidatorsFetcherRequest(..) => self
                 .fetchers
                 .dispatch_fetcher_event(effect_builder, rng, event),
         }
@@ -1009,7 +1143,20 @@ impl reactor::Reactor for MainReactor {
 
         let trusted_hash = config.value().node.trusted_hash;
         let (root_dir, config) = config.into_parts();
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root_dir)?;
+        let (our_secret_key, our_public_key) = if !config.node.mode.is_validator() {
+            // An ephemeral key never belongs to a validator, so the node never signs anything.
+            let secret_key = SecretKey::generate_ed25519()
+                .map_err(|error| anyhow::anyhow!("could not generate ephemeral key: {}", error))?;
+            let public_key = PublicKey::from(&secret_key);
+            info!(
+                %public_key,
+                mode = ?config.node.mode,
+                "not running as a validator, not loading the consensus secret key"
+            );
+            (Arc::new(secret_key), public_key)
+        } else {
+            config.consensus.load_keys(&root_dir)?
+        };
         let validator_matrix = ValidatorMatrix::new(
             chainspec.core_config.finality_threshold_fraction,
             chainspec
@@ -1065,6 +1212,7 @@ impl reactor::Reactor for MainReactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.core_config.start_protocol_version_with_get_era_id,
         )?;
 
         let network = Network::new(
@@ -1083,26 +1231,30 @@ impl reactor::Reactor for MainReactor {
         )?;
 
         let rpc_server = RpcServer::new(
-            config.rpc_server.clone(),
-            config.speculative_exec_server.clone(),
+            WithDir::new(&root_dir, config.rpc_server.clone()),
+            WithDir::new(&root_dir, config.speculative_exec_server.clone()),
+            WithDir::new(&root_dir, config.rosetta_server.clone()),
             protocol_version,
             chainspec.network_config.name.clone(),
+            u64::from(chainspec.system_costs_config.wasmless_transfer_cost()),
             node_startup_instant,
-        );
+            registry,
+        )?;
         let rest_server = RestServer::new(
-            config.rest_server.clone(),
+            WithDir::new(&root_dir, config.rest_server.clone()),
             protocol_version,
             chainspec.network_config.name.clone(),
             node_startup_instant,
         );
         let event_stream_server = EventStreamServer::new(
-            config.event_stream_server.clone(),
+            WithDir::new(&root_dir, config.event_stream_server.clone()),
             storage.root_path().to_path_buf(),
             protocol_version,
         );
         let diagnostics_port =
             DiagnosticsPort::new(WithDir::new(&root_dir, config.diagnostics_port));
         let shutdown_trigger = ShutdownTrigger::new();
+        let notifier = Notifier::new(WithDir::new(&root_dir, config.notifier.clone()))?;
 
         // local / remote data management
         let sync_leaper = SyncLeaper::new(chainspec.clone(), registry)?;
@@ -1154,12 +1306,27 @@ impl reactor::Reactor for MainReactor {
             registry,
         )?;
         let block_validator = BlockValidator::new(Arc::clone(&chainspec), config.block_validator);
-        let upgrade_watcher =
-            UpgradeWatcher::new(chainspec.as_ref(), config.upgrade_watcher, &root_dir)?;
+        let upgrade_watcher = UpgradeWatcher::new(
+            chainspec.as_ref(),
+            config.upgrade_watcher,
+            &root_dir,
+            registry,
+        )?;
+        let clock_monitor = ClockMonitor::new(chainspec.as_ref(), registry)?;
         let deploy_acceptor =
             DeployAcceptor::new(config.deploy_acceptor, chainspec.as_ref(), registry)?;
-        let deploy_buffer =
-            DeployBuffer::new(chainspec.deploy_config, config.deploy_buffer, registry)?;
+        let administrators = chainspec
+            .core_config
+            .administrators
+            .iter()
+            .map(PublicKey::to_account_hash)
+            .collect();
+        let deploy_buffer = DeployBuffer::new(
+            chainspec.deploy_config,
+            administrators,
+            config.deploy_buffer,
+            registry,
+        )?;
 
         let reactor = MainReactor {
             chainspec,
@@ -1167,6 +1334,7 @@ impl reactor::Reactor for MainReactor {
             storage,
             contract_runtime,
             upgrade_watcher,
+            clock_monitor,
             net: network,
             address_gossiper,
 
@@ -1187,6 +1355,7 @@ impl reactor::Reactor for MainReactor {
             block_synchronizer,
             diagnostics_port,
             shutdown_trigger,
+            notifier,
 
             metrics,
             memory_metrics,
@@ -1198,9 +1367,16 @@ impl reactor::Reactor for MainReactor {
             max_attempts: config.node.max_attempts,
             idle_tolerance: config.node.idle_tolerance,
             control_logic_default_delay: config.node.control_logic_default_delay,
+            historical_sync_interval: config.node.historical_sync_interval,
+            last_historical_sync_start: Timestamp::from(0),
             trusted_hash,
+            trusted_hash_discovery: config.node.trusted_hash_discovery,
             validator_matrix,
             sync_handling: config.node.sync_handling,
+            node_mode: config.node.mode,
+            light_client_headers: None,
+            min_peers_to_validate: config.node.min_peers_to_validate,
+            max_tip_age_to_validate: config.node.max_tip_age_to_validate,
             signature_gossip_tracker: SignatureGossipTracker::new(),
             shutdown_for_upgrade_timeout: config.node.shutdown_for_upgrade_timeout,
             switched_to_shutdown_for_upgrade: Timestamp::from(0),
@@ -1226,6 +1402,56 @@ impl reactor::Reactor for MainReactor {
         Ok((reactor, effects))
     }
 
+    fn reload_config(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        cfg: Self::Config,
+    ) -> Effects<MainEvent> {
+        let (_root_dir, config) = cfg.into_parts();
+        effect_builder
+            .announce_config_reloaded(config.logging, config.gossip, config.fetcher)
+            .ignore()
+    }
+
+    fn supervision_policy(&self, event: &MainEvent) -> SupervisionPolicy {
+        // Gossipers only hold transient state which peers will re-establish, and an abandoned sync
+        // leap is simply reattempted by the control logic, so these are safe to restart.  Other
+        // components either hold state which cannot be recovered or hold responders which other
+        // components are waiting on, so any of them panicking is fatal.
+        match event {
+            MainEvent::AddressGossiper(_)
+            | MainEvent::AddressGossiperIncoming(_)
+            | MainEvent::AddressGossiperCrank(_) => SupervisionPolicy::Restart("address_gossiper"),
+            MainEvent::BlockGossiper(_) | MainEvent::BlockGossiperIncoming(_) => {
+                SupervisionPolicy::Restart("block_gossiper")
+            }
+            MainEvent::DeployGossiper(_) | MainEvent::DeployGossiperIncoming(_) => {
+                SupervisionPolicy::Restart("deploy_gossiper")
+            }
+            MainEvent::FinalitySignatureGossiper(_)
+            | MainEvent::FinalitySignatureGossiperIncoming(_) => {
+                SupervisionPolicy::Restart("finality_signature_gossiper")
+            }
+            MainEvent::SyncLeaper(_) => SupervisionPolicy::Restart("sync_leaper"),
+            _ => SupervisionPolicy::Escalate,
+        }
+    }
+
+    fn restart_component(&mut self, component: &'static str) {
+        match component {
+            "address_gossiper" => self.address_gossiper.reset(),
+            "block_gossiper" => self.block_gossiper.reset(),
+            "deploy_gossiper" => self.deploy_gossiper.reset(),
+            "finality_signature_gossiper" => self.finality_signature_gossiper.reset(),
+            "sync_leaper" => self.sync_leaper.purge(),
+            _ => error!(%component, "cannot restart unknown component"),
+        }
+    }
+
+    fn event_queue_config(cfg: &Self::Config) -> EventQueueConfig {
+        cfg.value().event_queue.clone()
+    }
+
     fn update_metrics(&mut self, event_queue_handle: EventQueueHandle<Self::Event>) {
         self.memory_metrics.estimate(self);
         self.event_queue_metrics
@@ -1242,6 +1468,50 @@ impl reactor::Reactor for MainReactor {
     }
 }
 
+impl Finalize for MainReactor {
+    /// Finalizes the components in dependency order: consensus first, then networking and the
+    /// API servers, and the stores last, so that nothing is still writing to them once they are
+    /// flushed.
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        let MainReactor {
+            consensus,
+            net,
+            rest_server,
+            rpc_server,
+            event_stream_server,
+            contract_runtime,
+            storage,
+            ..
+        } = self;
+
+        let consensus_finalized = consensus.finalize();
+        let net_finalized = net.finalize();
+        let rest_server_finalized = rest_server.finalize();
+        let rpc_server_finalized = rpc_server.finalize();
+        let event_stream_server_finalized = event_stream_server.finalize();
+        let contract_runtime_finalized = contract_runtime.finalize();
+        let storage_finalized = storage.finalize();
+
+        async move {
+            consensus_finalized.await;
+            debug!("consensus finalized");
+            net_finalized.await;
+            debug!("network finalized");
+            rest_server_finalized.await;
+            debug!("rest server finalized");
+            rpc_server_finalized.await;
+            debug!("rpc server finalized");
+            event_stream_server_finalized.await;
+            debug!("event stream server finalized");
+            contract_runtime_finalized.await;
+            debug!("contract runtime finalized");
+            storage_finalized.await;
+            debug!("storage finalized");
+        }
+        .boxed()
+    }
+}
+
 impl MainReactor {
     fn update_validator_weights(
         &mut self,
@@ -1526,6 +1796,30 @@ impl MainReactor {
             ),
         ));
 
+        if self.notifier.has_webhooks() {
+            let processed_deploys = execution_results
+                .iter()
+                .map(
+                    |(deploy_hash, deploy_header, execution_result)| notifier::ProcessedDeploy {
+                        deploy_hash: *deploy_hash,
+                        account_hash: deploy_header.account().to_account_hash(),
+                        success: matches!(execution_result, ExecutionResult::Success { .. }),
+                    },
+                )
+                .collect();
+            effects.extend(reactor::wrap_effects(
+                MainEvent::Notifier,
+                self.notifier.handle_event(
+                    effect_builder,
+                    rng,
+                    notifier::Event::BlockCompleted {
+                        block: Arc::clone(&block),
+                        processed_deploys,
+                    },
+                ),
+            ));
+        }
+
         for (deploy_hash, deploy_header, execution_result) in execution_results {
             let event = event_stream_server::Event::DeployProcessed {
                 deploy_hash,
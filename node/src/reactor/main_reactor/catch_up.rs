@@ -8,16 +8,25 @@ use crate::{
     components::{
         block_accumulator::{SyncIdentifier, SyncInstruction},
         block_synchronizer::BlockSynchronizerProgress,
+        fetcher::{EmptyValidationMetadata, FetchResult, FetchedData},
         sync_leaper,
         sync_leaper::{LeapActivityError, LeapState},
         ValidatorBoundComponent,
     },
     effect::{requests::BlockSynchronizerRequest, EffectBuilder, EffectExt, Effects},
     reactor::{
-        main_reactor::{MainEvent, MainReactor},
+        main_reactor::{
+            light_client::{
+                LightClientHeaders, LightClientHeadersStatus, MAX_PARALLEL_HEADER_FETCHES,
+            },
+            MainEvent, MainReactor,
+        },
         wrap_effects,
     },
-    types::{ActivationPoint, BlockHash, NodeId, SyncLeap, SyncLeapIdentifier},
+    types::{
+        ActivationPoint, BlockHash, BlockHeader, BlockSignatures, NodeId, SyncLeap,
+        SyncLeapIdentifier, TrustedHashDiscoveryConfig,
+    },
     NodeRng,
 };
 
@@ -48,6 +57,11 @@ impl MainReactor {
             block_hash = %sync_identifier.block_hash(),
             "CatchUp: sync identifier"
         );
+        if self.node_mode.is_light() {
+            // a light client only follows the chain's headers, by leaping from the highest
+            // verified header it knows of and filling the gaps between the leap's headers
+            return self.catch_up_light(effect_builder, rng, sync_identifier.block_hash());
+        }
         // we check with the block accumulator before doing sync work as it may be aware of one or
         // more blocks that are higher than our current highest block
         let sync_instruction = self.block_accumulator.sync_instruction(sync_identifier);
@@ -74,6 +88,7 @@ impl MainReactor {
                 // not working on syncing a block (ready to start a new one)
                 match self.trusted_hash {
                     Some(trusted_hash) => self.catch_up_trusted_hash(trusted_hash),
+                    None if self.node_mode.is_light() => self.catch_up_light_no_trusted_hash(),
                     None => self.catch_up_no_trusted_hash(),
                 }
             }
@@ -144,6 +159,23 @@ impl MainReactor {
         }
     }
 
+    fn catch_up_light_no_trusted_hash(&mut self) -> Either<SyncIdentifier, CatchUpInstruction> {
+        // a light client has no complete blocks, but continues from its highest stored header
+        match self.storage.read_highest_block_header() {
+            Ok(Some(block_header)) => {
+                let block_hash = block_header.block_hash();
+                info!(%block_hash, "CatchUp: light client continuing from highest stored header");
+                self.trusted_hash = Some(block_hash);
+                Either::Left(SyncIdentifier::BlockHash(block_hash))
+            }
+            Ok(None) => self.catch_up_no_trusted_hash(),
+            Err(err) => Either::Right(CatchUpInstruction::Fatal(format!(
+                "CatchUp: fatal block store error when attempting to read highest block header: {}",
+                err
+            ))),
+        }
+    }
+
     fn catch_up_check_genesis(&mut self) -> Either<SyncIdentifier, CatchUpInstruction> {
         match self.chainspec.protocol_config.activation_point {
             ActivationPoint::Genesis(timestamp) => {
@@ -170,6 +202,9 @@ impl MainReactor {
             }
             ActivationPoint::EraId(_) => {
                 // no trusted hash, no local block, not genesis
+                if let Some(discovery) = self.trusted_hash_discovery {
+                    return self.catch_up_discover_trusted_hash(discovery);
+                }
                 Either::Right(CatchUpInstruction::Fatal(
                     "CatchUp: cannot proceed without trusted hash".to_string(),
                 ))
@@ -177,6 +212,46 @@ impl MainReactor {
         }
     }
 
+    fn catch_up_discover_trusted_hash(
+        &mut self,
+        discovery: TrustedHashDiscoveryConfig,
+    ) -> Either<SyncIdentifier, CatchUpInstruction> {
+        // the block accumulator collects the blocks peers gossip to us, along with which peers
+        // informed us about each of them; only peers which proved they hold a validator's key
+        // are counted
+        match self.block_accumulator.peer_agreed_block(
+            &self.net.validator_peers(),
+            discovery.peer_count,
+            discovery.min_agreement,
+            discovery.max_block_age,
+        ) {
+            Some((block_hash, block_height, agreeing_peers)) => {
+                warn!(
+                    %block_hash,
+                    block_height,
+                    agreeing_peers,
+                    peer_count = discovery.peer_count,
+                    "CatchUp: no trusted hash configured; trusting block reported by validator \
+                    peers, which is only safe if fewer than {} of them are malicious",
+                    discovery.min_agreement
+                );
+                self.trusted_hash = Some(block_hash);
+                Either::Left(SyncIdentifier::BlockHash(block_hash))
+            }
+            None => {
+                info!(
+                    peer_count = discovery.peer_count,
+                    min_agreement = discovery.min_agreement,
+                    "CatchUp: waiting for validator peers to agree on a trusted hash"
+                );
+                Either::Right(CatchUpInstruction::CheckLater(
+                    "waiting for validator peers to agree on a trusted hash".to_string(),
+                    self.control_logic_default_delay.into(),
+                ))
+            }
+        }
+    }
+
     fn catch_up_trusted_hash(
         &mut self,
         trusted_hash: BlockHash,
@@ -277,8 +352,11 @@ impl MainReactor {
         // register block builder so that control logic can tell that block is Syncing,
         // otherwise block_synchronizer detects as Idle which can cause unnecessary churn
         // on subsequent cranks while leaper is awaiting responses.
-        self.block_synchronizer
-            .register_block_by_hash(block_hash, true);
+        // a light client never syncs blocks, so it doesn't use the block synchronizer.
+        if !self.node_mode.is_light() {
+            self.block_synchronizer
+                .register_block_by_hash(block_hash, true);
+        }
         let leap_status = self.sync_leaper.leap_status();
         info!(%block_hash, %leap_status, "CatchUp: status");
         match leap_status {
@@ -369,6 +447,10 @@ impl MainReactor {
                 .register_era_validator_weights(validator_weights);
         }
 
+        if self.node_mode.is_light() {
+            return self.catch_up_light_leap_received(effect_builder, rng, sync_leap);
+        }
+
         let mut effects = Effects::new();
 
         effects.extend(wrap_effects(
@@ -389,6 +471,198 @@ impl MainReactor {
         CatchUpInstruction::Do(self.control_logic_default_delay.into(), effects)
     }
 
+    fn catch_up_light(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        block_hash: BlockHash,
+    ) -> CatchUpInstruction {
+        match self.light_client_headers.take() {
+            None => self.catch_up_leap(effect_builder, rng, block_hash),
+            Some(light_client_headers) => {
+                self.catch_up_light_link_headers(effect_builder, rng, light_client_headers)
+            }
+        }
+    }
+
+    fn catch_up_light_leap_received(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        sync_leap: SyncLeap,
+    ) -> CatchUpInstruction {
+        // the sync leap has been verified by the fetcher, but it only contains switch block headers
+        // between its trusted and highest block; the headers in between are fetched, and each run
+        // of headers is only stored once it links to the stored ones, so that the stored headers
+        // form a contiguous chain
+        let highest_stored = match self.storage.read_highest_block_header() {
+            Ok(highest_stored) => highest_stored,
+            Err(err) => {
+                return CatchUpInstruction::Fatal(format!(
+                    "CatchUp: fatal block store error when attempting to read highest block \
+                    header: {}",
+                    err
+                ))
+            }
+        };
+        let light_client_headers = LightClientHeaders::new(&sync_leap, highest_stored.as_ref());
+        self.catch_up_light_link_headers(effect_builder, rng, light_client_headers)
+    }
+
+    fn catch_up_light_link_headers(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        mut light_client_headers: LightClientHeaders,
+    ) -> CatchUpInstruction {
+        while let Some((block_headers, block_signatures)) = light_client_headers.take_linked() {
+            if let Err(instruction) =
+                self.catch_up_light_store_headers(block_headers, block_signatures)
+            {
+                return instruction;
+            }
+        }
+        match light_client_headers.status() {
+            LightClientHeadersStatus::Complete => CatchUpInstruction::CheckLater(
+                "light client waiting for new blocks".to_string(),
+                self.chainspec.core_config.minimum_block_time.into(),
+            ),
+            LightClientHeadersStatus::Unlinked => {
+                // the leap's headers are verified, so the stored headers must be on another chain
+                self.attempts += 1;
+                warn!(
+                    remaining_attempts = %self.max_attempts.saturating_sub(self.attempts),
+                    "CatchUp: light client headers don't link to highest stored header",
+                );
+                CatchUpInstruction::CheckLater(
+                    "light client discarded unlinked headers".to_string(),
+                    self.control_logic_default_delay.into(),
+                )
+            }
+            LightClientHeadersStatus::Missing => {
+                let effects =
+                    self.light_client_fetch_headers(effect_builder, rng, &mut light_client_headers);
+                let awaiting_headers = light_client_headers.requested_count() > 0;
+                self.light_client_headers = Some(light_client_headers);
+                if !effects.is_empty() {
+                    CatchUpInstruction::Do(self.control_logic_default_delay.into(), effects)
+                } else if awaiting_headers {
+                    CatchUpInstruction::CheckLater(
+                        "light client is awaiting block headers".to_string(),
+                        self.control_logic_default_delay.into(),
+                    )
+                } else {
+                    CatchUpInstruction::CheckLater(
+                        "no peers".to_string(),
+                        self.chainspec.core_config.minimum_block_time.into(),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Stores a run of verified headers linking to the highest stored header.
+    fn catch_up_light_store_headers(
+        &mut self,
+        block_headers: Vec<BlockHeader>,
+        block_signatures: Vec<BlockSignatures>,
+    ) -> Result<(), CatchUpInstruction> {
+        let block_hash = match block_headers.first() {
+            Some(highest) => highest.block_hash(),
+            None => {
+                return Err(CatchUpInstruction::Fatal(
+                    "CatchUp: light client has no block headers to store".to_string(),
+                ))
+            }
+        };
+        let block_count = block_headers.len();
+        if let Err(err) = self
+            .storage
+            .write_signed_block_headers(block_headers, &block_signatures)
+        {
+            return Err(CatchUpInstruction::Fatal(format!(
+                "CatchUp: could not store verified block headers: {}",
+                err
+            )));
+        }
+        info!(
+            %block_hash,
+            %block_count,
+            "CatchUp: light client stored verified headers"
+        );
+        self.trusted_hash = Some(block_hash);
+        self.last_progress = Timestamp::now();
+        self.attempts = 0;
+        Ok(())
+    }
+
+    /// Requests missing block headers from random peers, up to `MAX_PARALLEL_HEADER_FETCHES` at a
+    /// time.
+    fn light_client_fetch_headers(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        light_client_headers: &mut LightClientHeaders,
+    ) -> Effects<MainEvent> {
+        let capacity =
+            MAX_PARALLEL_HEADER_FETCHES.saturating_sub(light_client_headers.requested_count());
+        let missing: Vec<BlockHash> = light_client_headers.missing().take(capacity).collect();
+        let peers = self.net.fully_connected_peers_random(rng, missing.len());
+        let mut effects = Effects::new();
+        for (block_hash, peer) in missing.into_iter().zip(peers) {
+            debug!(%block_hash, %peer, "CatchUp: light client fetching block header");
+            light_client_headers.set_requested(block_hash);
+            effects.extend(
+                effect_builder
+                    .fetch::<BlockHeader>(block_hash, peer, Box::new(EmptyValidationMetadata))
+                    .event(MainEvent::LightClientHeaderFetched),
+            );
+        }
+        effects
+    }
+
+    pub(super) fn light_client_header_fetched(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        result: FetchResult<BlockHeader>,
+    ) -> Effects<MainEvent> {
+        let mut light_client_headers = match self.light_client_headers.take() {
+            Some(light_client_headers) => light_client_headers,
+            None => {
+                debug!("CatchUp: light client fetched block header after discarding its headers");
+                return Effects::new();
+            }
+        };
+        match result {
+            Ok(FetchedData::FromStorage { item } | FetchedData::FromPeer { item, .. }) => {
+                let block_hash = item.block_hash();
+                if light_client_headers.add_parent(*item) {
+                    self.last_progress = Timestamp::now();
+                } else {
+                    self.attempts += 1;
+                    warn!(%block_hash, "CatchUp: light client fetched unexpected block header");
+                }
+            }
+            Err(error) => {
+                light_client_headers.clear_requested(error.id());
+                self.attempts += 1;
+                warn!(%error, "CatchUp: light client failed to fetch block header");
+            }
+        }
+        // keep fetching while headers are missing; the control logic stores each run once linked
+        let effects = match light_client_headers.status() {
+            LightClientHeadersStatus::Missing => {
+                self.light_client_fetch_headers(effect_builder, rng, &mut light_client_headers)
+            }
+            LightClientHeadersStatus::Complete | LightClientHeadersStatus::Unlinked => {
+                Effects::new()
+            }
+        };
+        self.light_client_headers = Some(light_client_headers);
+        effects
+    }
+
     fn catch_up_block_sync(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
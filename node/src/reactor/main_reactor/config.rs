@@ -4,11 +4,14 @@ use tracing::error;
 
 use crate::{
     logging::LoggingConfig,
+    reactor::EventQueueConfig,
+    runtime::Config as RuntimeConfig,
     types::{Chainspec, NodeConfig},
     BlockAccumulatorConfig, BlockSynchronizerConfig, BlockValidatorConfig, ConsensusConfig,
     ContractRuntimeConfig, DeployAcceptorConfig, DeployBufferConfig, DiagnosticsPortConfig,
-    EventStreamServerConfig, FetcherConfig, GossipConfig, NetworkConfig, RestServerConfig,
-    RpcServerConfig, SpeculativeExecConfig, StorageConfig, UpgradeWatcherConfig,
+    EventStreamServerConfig, FetcherConfig, GossipConfig, NetworkConfig, NotifierConfig,
+    RestServerConfig, RosettaConfig, RpcServerConfig, SpeculativeExecConfig, StorageConfig,
+    UpgradeWatcherConfig,
 };
 
 /// Root configuration.
@@ -32,6 +35,9 @@ pub struct Config {
     pub rpc_server: RpcServerConfig,
     /// Config values for speculative execution.
     pub speculative_exec_server: SpeculativeExecConfig,
+    /// Config values for the Rosetta API server.
+    #[serde(default)]
+    pub rosetta_server: RosettaConfig,
     /// Config values for storage.
     pub storage: StorageConfig,
     /// Config values for gossip.
@@ -54,6 +60,15 @@ pub struct Config {
     pub block_validator: BlockValidatorConfig,
     /// Config values for the upgrade watcher.
     pub upgrade_watcher: UpgradeWatcherConfig,
+    /// Config values for the webhook notifier.
+    #[serde(default)]
+    pub notifier: NotifierConfig,
+    /// Config values for the reactor's event queue.
+    #[serde(default)]
+    pub event_queue: EventQueueConfig,
+    /// Config values for the thread and runtime layout.
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
 }
 
 impl Config {
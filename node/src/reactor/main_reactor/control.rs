@@ -6,9 +6,9 @@ use casper_types::{EraId, PublicKey, Timestamp};
 
 use crate::{
     components::{
-        block_synchronizer, block_synchronizer::BlockSynchronizerProgress, consensus::EraReport,
-        contract_runtime::ExecutionPreState, diagnostics_port, event_stream_server, network,
-        rest_server, rpc_server, upgrade_watcher,
+        block_synchronizer, block_synchronizer::BlockSynchronizerProgress, clock_monitor,
+        consensus::EraReport, contract_runtime::ExecutionPreState, diagnostics_port,
+        event_stream_server, network, rest_server, rpc_server, upgrade_watcher,
     },
     effect::{EffectBuilder, EffectExt, Effects},
     fatal,
@@ -280,6 +280,15 @@ impl MainReactor {
             return Some(effects);
         }
 
+        // the clock monitor relies on the peers' handshakes, so start it after Network
+        if let Some(effects) = utils::initialize_component(
+            effect_builder,
+            &mut self.clock_monitor,
+            MainEvent::ClockMonitor(clock_monitor::Event::Initialize),
+        ) {
+            return Some(effects);
+        }
+
         // bring up the BlockSynchronizer after Network to start it's self-perpetuating
         // dishonest peer announcing behavior
         if let Some(effects) = utils::initialize_component(
@@ -8,6 +8,7 @@ use crate::{
         contract_runtime, contract_runtime::BlockExecutionError, diagnostics_port, network,
         storage, upgrade_watcher,
     },
+    types::ExitCode,
     utils::{ListeningError, LoadError},
 };
 
@@ -63,6 +64,31 @@ pub(crate) enum Error {
     LoadSigningKeyPair(#[from] LoadError<CryptoError>),
 }
 
+impl Error {
+    /// Returns the exit code the process should terminate with when the reactor fails to start.
+    pub(crate) fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Network(
+                network::Error::ListenerCreation(..)
+                | network::Error::ListenerAddr(_)
+                | network::Error::ListenerSetNonBlocking(_)
+                | network::Error::ListenerConversion(_),
+            )
+            | Error::HttpServerListening(_)
+            | Error::DiagnosticsPort(_) => ExitCode::BindFailure,
+            Error::Storage(error) => error.exit_code(),
+            Error::ContractRuntime(_) | Error::LoadSigningKeyPair(_) => ExitCode::InvalidConfig,
+            Error::UpgradeWatcher(_)
+            | Error::Metrics(_)
+            | Error::Network(_)
+            | Error::Consensus(_)
+            | Error::BlockExecution(_)
+            | Error::EngineState(_)
+            | Error::BytesRepr(_) => ExitCode::Abort,
+        }
+    }
+}
+
 impl From<bytesrepr::Error> for Error {
     fn from(err: bytesrepr::Error) -> Self {
         Self::BytesRepr(err)
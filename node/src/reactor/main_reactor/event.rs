@@ -13,18 +13,21 @@ use crate::{
     components::{
         block_accumulator,
         block_synchronizer::{self, GlobalStateSynchronizerEvent, TrieAccumulatorEvent},
-        block_validator, consensus, contract_runtime, deploy_acceptor, deploy_buffer,
-        diagnostics_port, event_stream_server, fetcher, gossiper,
+        block_validator, clock_monitor, consensus, contract_runtime, deploy_acceptor,
+        deploy_buffer, diagnostics_port, event_stream_server,
+        fetcher::{self, FetchResult},
+        gossiper,
         network::{self, GossipedAddress},
-        rest_server, rpc_server, shutdown_trigger, storage, sync_leaper, upgrade_watcher,
+        notifier, rest_server, rpc_server, shutdown_trigger, storage, sync_leaper, upgrade_watcher,
     },
     effect::{
         announcements::{
-            BlockAccumulatorAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
-            ControlAnnouncement, DeployAcceptorAnnouncement, DeployBufferAnnouncement,
-            FatalAnnouncement, FetchedNewBlockAnnouncement,
-            FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement, MetaBlockAnnouncement,
-            PeerBehaviorAnnouncement, UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
+            BlockAccumulatorAnnouncement, ClockDriftAnnouncement, ConfigReloadedAnnouncement,
+            ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, DeployBufferAnnouncement, FatalAnnouncement,
+            FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement,
+            GossiperAnnouncement, MetaBlockAnnouncement, PeerBehaviorAnnouncement,
+            UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
         },
         diagnostics_port::DumpConsensusStateRequest,
         incoming::{
@@ -33,20 +36,20 @@ use crate::{
             TrieResponseIncoming,
         },
         requests::{
-            AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest,
-            BlockSynchronizerRequest, BlockValidationRequest, ChainspecRawBytesRequest,
-            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, FetcherRequest,
-            MakeBlockExecutableRequest, MarkBlockCompletedRequest, MetricsRequest,
-            NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, RestRequest, RpcRequest,
-            SetNodeStopRequest, StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest,
-            UpgradeWatcherRequest,
+            AcceptDeployRequest, AddDeployApprovalsRequest, BeginGossipRequest,
+            BlockAccumulatorRequest, BlockSynchronizerRequest, BlockValidationRequest,
+            ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
+            DeployBufferRequest, FetcherRequest, MakeBlockExecutableRequest,
+            MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
+            ReactorStatusRequest, RestRequest, RpcRequest, SetNodeStopRequest, StorageRequest,
+            SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest,
         },
     },
     protocol::Message,
     reactor::ReactorEvent,
     types::{
         ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Deploy,
-        FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
+        FinalitySignature, LegacyDeploy, SignedEraValidators, SyncLeap, TrieOrChunk,
     },
 };
 
@@ -75,6 +78,12 @@ pub(crate) enum MainEvent {
     #[from]
     UpgradeWatcherAnnouncement(#[serde(skip_serializing)] UpgradeWatcherAnnouncement),
     #[from]
+    ConfigReloadedAnnouncement(#[serde(skip_serializing)] ConfigReloadedAnnouncement),
+    #[from]
+    ClockMonitor(#[serde(skip_serializing)] clock_monitor::Event),
+    #[from]
+    ClockDriftAnnouncement(#[serde(skip_serializing)] ClockDriftAnnouncement),
+    #[from]
     RpcServer(#[serde(skip_serializing)] rpc_server::Event),
     #[from]
     RestServer(#[serde(skip_serializing)] rest_server::Event),
@@ -87,6 +96,8 @@ pub(crate) enum MainEvent {
     #[from]
     ShutdownTrigger(shutdown_trigger::Event),
     #[from]
+    Notifier(#[serde(skip_serializing)] notifier::Event),
+    #[from]
     DiagnosticsPort(diagnostics_port::Event),
     #[from]
     DumpConsensusStateRequest(DumpConsensusStateRequest),
@@ -148,6 +159,13 @@ pub(crate) enum MainEvent {
     #[from]
     ApprovalsHashesFetcherRequest(#[serde(skip_serializing)] FetcherRequest<ApprovalsHashes>),
 
+    #[from]
+    SignedEraValidatorsFetcher(#[serde(skip_serializing)] fetcher::Event<SignedEraValidators>),
+    #[from]
+    SignedEraValidatorsFetcherRequest(
+        #[serde(skip_serializing)] FetcherRequest<SignedEraValidators>,
+    ),
+
     #[from]
     BlockGossiper(#[serde(skip_serializing)] gossiper::Event<Block>),
     #[from]
@@ -187,6 +205,8 @@ pub(crate) enum MainEvent {
     #[from]
     AcceptDeployRequest(AcceptDeployRequest),
     #[from]
+    AddDeployApprovalsRequest(AddDeployApprovalsRequest),
+    #[from]
     DeployAcceptorAnnouncement(#[serde(skip_serializing)] DeployAcceptorAnnouncement),
     #[from]
     DeployGossiper(#[serde(skip_serializing)] gossiper::Event<Deploy>),
@@ -247,6 +267,9 @@ pub(crate) enum MainEvent {
 
     // Event related to figuring out validators for blocks after upgrades.
     GotBlockAfterUpgradeEraValidators(EraId, EraValidators, EraValidators),
+
+    // Event related to a light client filling the gaps between the block headers of a sync leap.
+    LightClientHeaderFetched(#[serde(skip_serializing)] FetchResult<BlockHeader>),
 }
 
 impl ReactorEvent for MainEvent {
@@ -274,9 +297,11 @@ impl ReactorEvent for MainEvent {
             MainEvent::RestServer(_) => "RestServer",
             MainEvent::EventStreamServer(_) => "EventStreamServer",
             MainEvent::UpgradeWatcher(_) => "UpgradeWatcher",
+            MainEvent::ClockMonitor(_) => "ClockMonitor",
             MainEvent::Consensus(_) => "Consensus",
             MainEvent::DeployAcceptor(_) => "DeployAcceptor",
             MainEvent::AcceptDeployRequest(_) => "AcceptDeployRequest",
+            MainEvent::AddDeployApprovalsRequest(_) => "AddDeployApprovalsRequest",
             MainEvent::LegacyDeployFetcher(_) => "LegacyDeployFetcher",
             MainEvent::DeployFetcher(_) => "DeployFetcher",
             MainEvent::DeployGossiper(_) => "DeployGossiper",
@@ -292,7 +317,9 @@ impl ReactorEvent for MainEvent {
             MainEvent::FinalitySignatureFetcher(_) => "FinalitySignatureFetcher",
             MainEvent::SyncLeapFetcher(_) => "SyncLeapFetcher",
             MainEvent::ApprovalsHashesFetcher(_) => "ApprovalsHashesFetcher",
+            MainEvent::SignedEraValidatorsFetcher(_) => "SignedEraValidatorsFetcher",
             MainEvent::ShutdownTrigger(_) => "ShutdownTrigger",
+            MainEvent::Notifier(_) => "Notifier",
             MainEvent::DiagnosticsPort(_) => "DiagnosticsPort",
             MainEvent::NetworkRequest(_) => "NetworkRequest",
             MainEvent::NetworkInfoRequest(_) => "NetworkInfoRequest",
@@ -306,6 +333,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::FinalitySignatureFetcherRequest(_) => "FinalitySignatureFetcherRequest",
             MainEvent::SyncLeapFetcherRequest(_) => "SyncLeapFetcherRequest",
             MainEvent::ApprovalsHashesFetcherRequest(_) => "ApprovalsHashesFetcherRequest",
+            MainEvent::SignedEraValidatorsFetcherRequest(_) => "SignedEraValidatorsFetcherRequest",
             MainEvent::DeployBufferRequest(_) => "DeployBufferRequest",
             MainEvent::BlockValidatorRequest(_) => "BlockValidatorRequest",
             MainEvent::MetricsRequest(_) => "MetricsRequest",
@@ -322,6 +350,8 @@ impl ReactorEvent for MainEvent {
             MainEvent::DeployGossiperAnnouncement(_) => "DeployGossiperAnnouncement",
             MainEvent::AddressGossiperAnnouncement(_) => "AddressGossiperAnnouncement",
             MainEvent::UpgradeWatcherAnnouncement(_) => "UpgradeWatcherAnnouncement",
+            MainEvent::ConfigReloadedAnnouncement(_) => "ConfigReloadedAnnouncement",
+            MainEvent::ClockDriftAnnouncement(_) => "ClockDriftAnnouncement",
             MainEvent::NetworkPeerBehaviorAnnouncement(_) => "BlocklistAnnouncement",
             MainEvent::DeployBufferAnnouncement(_) => "DeployBufferAnnouncement",
             MainEvent::FinalitySignatureFetcherAnnouncement(_) => {
@@ -362,6 +392,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::GotBlockAfterUpgradeEraValidators(_, _, _) => {
                 "GotImmediateSwitchBlockEraValidators"
             }
+            MainEvent::LightClientHeaderFetched(_) => "LightClientHeaderFetched",
         }
     }
 }
@@ -380,9 +411,11 @@ impl Display for MainEvent {
                 write!(f, "event stream server: {}", event)
             }
             MainEvent::UpgradeWatcher(event) => write!(f, "upgrade watcher: {}", event),
+            MainEvent::ClockMonitor(event) => write!(f, "clock monitor: {}", event),
             MainEvent::Consensus(event) => write!(f, "consensus: {}", event),
             MainEvent::DeployAcceptor(event) => write!(f, "deploy acceptor: {}", event),
             MainEvent::AcceptDeployRequest(req) => write!(f, "{}", req),
+            MainEvent::AddDeployApprovalsRequest(req) => write!(f, "{}", req),
             MainEvent::LegacyDeployFetcher(event) => write!(f, "legacy deploy fetcher: {}", event),
             MainEvent::DeployFetcher(event) => write!(f, "deploy fetcher: {}", event),
             MainEvent::DeployGossiper(event) => write!(f, "deploy gossiper: {}", event),
@@ -412,6 +445,9 @@ impl Display for MainEvent {
             MainEvent::ApprovalsHashesFetcher(event) => {
                 write!(f, "approvals hashes fetcher: {}", event)
             }
+            MainEvent::SignedEraValidatorsFetcher(event) => {
+                write!(f, "signed era validators fetcher: {}", event)
+            }
             MainEvent::BlockAccumulator(event) => {
                 write!(f, "block accumulator: {}", event)
             }
@@ -428,6 +464,7 @@ impl Display for MainEvent {
                 write!(f, "block synchronizer request: {}", req)
             }
             MainEvent::ShutdownTrigger(event) => write!(f, "shutdown trigger: {}", event),
+            MainEvent::Notifier(event) => write!(f, "notifier: {}", event),
             MainEvent::DiagnosticsPort(event) => write!(f, "diagnostics port: {}", event),
             MainEvent::NetworkRequest(req) => write!(f, "network request: {}", req),
             MainEvent::NetworkInfoRequest(req) => {
@@ -471,6 +508,9 @@ impl Display for MainEvent {
             MainEvent::ApprovalsHashesFetcherRequest(request) => {
                 write!(f, "approvals hashes fetcher request: {}", request)
             }
+            MainEvent::SignedEraValidatorsFetcherRequest(request) => {
+                write!(f, "signed era validators fetcher request: {}", request)
+            }
             MainEvent::AddressGossiperCrank(request) => {
                 write!(f, "begin address gossip request: {}", request)
             }
@@ -510,6 +550,12 @@ impl Display for MainEvent {
             MainEvent::UpgradeWatcherAnnouncement(ann) => {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
+            MainEvent::ConfigReloadedAnnouncement(ann) => {
+                write!(f, "config reloaded announcement: {}", ann)
+            }
+            MainEvent::ClockDriftAnnouncement(ann) => {
+                write!(f, "clock drift announcement: {}", ann)
+            }
             MainEvent::NetworkPeerBehaviorAnnouncement(ann) => {
                 write!(f, "blocklist announcement: {}", ann)
             }
@@ -546,6 +592,12 @@ impl Display for MainEvent {
                     era_id
                 )
             }
+            MainEvent::LightClientHeaderFetched(Ok(fetched_data)) => {
+                write!(f, "light client fetched block header: {}", fetched_data)
+            }
+            MainEvent::LightClientHeaderFetched(Err(error)) => {
+                write!(f, "light client failed to fetch block header: {}", error)
+            }
         }
     }
 }
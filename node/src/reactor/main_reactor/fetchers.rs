@@ -8,7 +8,7 @@ use crate::{
     reactor::main_reactor::MainEvent,
     types::{
         ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHeader, Deploy,
-        FinalitySignature, LegacyDeploy, SyncLeap, TrieOrChunk,
+        FinalitySignature, LegacyDeploy, SignedEraValidators, SyncLeap, TrieOrChunk,
     },
     utils::Source,
     FetcherConfig, NodeRng,
@@ -25,6 +25,7 @@ pub(super) struct Fetchers {
     deploy_fetcher: Fetcher<Deploy>,
     trie_or_chunk_fetcher: Fetcher<TrieOrChunk>,
     block_execution_results_or_chunk_fetcher: Fetcher<BlockExecutionResultsOrChunk>,
+    signed_era_validators_fetcher: Fetcher<SignedEraValidators>,
 }
 
 impl Fetchers {
@@ -50,9 +51,29 @@ impl Fetchers {
                 config,
                 metrics_registry,
             )?,
+            signed_era_validators_fetcher: Fetcher::new(
+                "signed_era_validators_fetcher",
+                config,
+                metrics_registry,
+            )?,
         })
     }
 
+    /// Applies a reloaded config to all fetchers.
+    pub(super) fn update_config(&mut self, config: &FetcherConfig) {
+        self.sync_leap_fetcher.update_config(config);
+        self.block_header_by_hash_fetcher.update_config(config);
+        self.approvals_hashes_fetcher.update_config(config);
+        self.finality_signature_fetcher.update_config(config);
+        self.legacy_deploy_fetcher.update_config(config);
+        self.block_fetcher.update_config(config);
+        self.deploy_fetcher.update_config(config);
+        self.trie_or_chunk_fetcher.update_config(config);
+        self.block_execution_results_or_chunk_fetcher
+            .update_config(config);
+        self.signed_era_validators_fetcher.update_config(config);
+    }
+
     pub(super) fn dispatch_fetcher_event(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
@@ -156,6 +177,19 @@ impl Fetchers {
                     ),
                 )
             }
+            MainEvent::SignedEraValidatorsFetcher(event) => reactor::wrap_effects(
+                MainEvent::SignedEraValidatorsFetcher,
+                self.signed_era_validators_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::SignedEraValidatorsFetcherRequest(request) => reactor::wrap_effects(
+                MainEvent::SignedEraValidatorsFetcher,
+                self.signed_era_validators_fetcher.handle_event(
+                    effect_builder,
+                    rng,
+                    request.into(),
+                ),
+            ),
 
             // MISC DISPATCHING
             MainEvent::DeployAcceptorAnnouncement(
@@ -80,6 +80,20 @@ impl MainReactor {
             Either::Right(keep_up_instruction) => return keep_up_instruction,
             Either::Left(sync_identifier) => sync_identifier,
         };
+        // if the contract runtime has later blocks queued but lacks the one it needs next, sync
+        // that one first so that execution can resume
+        if let Some(keep_up_instruction) = self.keep_up_missing_block_for_execution(effect_builder)
+        {
+            return keep_up_instruction;
+        }
+        // apply backpressure: finalized blocks waiting for execution are held in memory, so don't
+        // sync any further ones until execution catches up
+        if self.contract_runtime.is_exec_queue_full() {
+            return KeepUpInstruction::CheckLater(
+                "execution queue is full".to_string(),
+                self.control_logic_default_delay.into(),
+            );
+        }
         debug!(
             ?sync_identifier,
             "KeepUp: sync identifier {}",
@@ -137,6 +151,11 @@ impl MainReactor {
             return None;
         }
 
+        if !self.node_mode.is_validator() {
+            // node is configured never to be a validator.
+            return None;
+        }
+
         if self.block_synchronizer.forward_progress().is_active() {
             debug!("KeepUp: still syncing a block");
             return None;
@@ -147,6 +166,47 @@ impl MainReactor {
             debug!("KeepUp: should_validate queue_depth {}", queue_depth);
             return None;
         }
+
+        let peer_count = self.net.fully_connected_peer_count();
+        if peer_count < self.min_peers_to_validate {
+            debug!(
+                peer_count,
+                min_peers_to_validate = self.min_peers_to_validate,
+                "KeepUp: too few peers to validate"
+            );
+            return None;
+        }
+
+        if let Some(max_tip_age) = self.max_tip_age_to_validate {
+            match self.storage.read_highest_complete_block() {
+                Ok(Some(block)) => {
+                    // compare against the newest block our peers know about rather than the wall
+                    // clock: if the whole network has stalled, there is no newer tip to catch up
+                    // to, and validating is the only way to produce one.
+                    if let Some(peer_tip_timestamp) =
+                        self.block_accumulator.highest_peer_block_timestamp()
+                    {
+                        let tip_age =
+                            peer_tip_timestamp.saturating_diff(block.header().timestamp());
+                        if tip_age > max_tip_age {
+                            debug!(
+                                %tip_age,
+                                %max_tip_age,
+                                "KeepUp: highest complete block too far behind peers to validate"
+                            );
+                            return None;
+                        }
+                    }
+                }
+                Ok(None) => return None,
+                Err(error) => {
+                    return Some(KeepUpInstruction::Fatal(format!(
+                        "KeepUp: could not read highest complete block: {}",
+                        error
+                    )))
+                }
+            }
+        }
         match self.create_required_eras(effect_builder, rng) {
             Ok(Some(effects)) => Some(KeepUpInstruction::Validate(effects)),
             Ok(None) => None,
@@ -232,6 +292,34 @@ impl MainReactor {
         SyncIdentifier::SyncedBlockIdentifier(block_hash, block_height, era_id)
     }
 
+    fn keep_up_missing_block_for_execution(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+    ) -> Option<KeepUpInstruction> {
+        let (block_height, parent_hash) = self.contract_runtime.missing_block_for_execution()?;
+        if let BlockSynchronizerProgress::Syncing(..) = self.block_synchronizer.forward_progress() {
+            // let the block being synced complete first
+            return None;
+        }
+        let block_hash = match self.block_accumulator.next_syncable_block_hash(parent_hash) {
+            Some(block_hash) => block_hash,
+            None => {
+                debug!(
+                    block_height,
+                    %parent_hash,
+                    "KeepUp: missing block for execution is not yet known"
+                );
+                return None;
+            }
+        };
+        info!(
+            block_height,
+            %block_hash,
+            "KeepUp: syncing missing block for execution"
+        );
+        self.keep_up_sync_instruction(effect_builder, SyncInstruction::BlockSync { block_hash })
+    }
+
     fn keep_up_sync_instruction(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
@@ -309,6 +397,21 @@ impl MainReactor {
                     sync_era,
                 } => {
                     debug!(%sync_hash, ?sync_era, validator_matrix_eras=?self.validator_matrix.eras(), "KeepUp: historical sync back instruction");
+                    // historical sync is low priority: don't start on a new historical block while
+                    // the forward synchronizer is busy with a block at the tip, and pace
+                    // consecutive historical blocks as configured.
+                    if self.block_synchronizer.forward_progress().is_active() {
+                        debug!(%sync_hash, "KeepUp: forward sync active; deferring historical sync");
+                        return None;
+                    }
+                    let elapsed = self.last_historical_sync_start.elapsed();
+                    if elapsed < self.historical_sync_interval {
+                        debug!(%sync_hash, "KeepUp: pacing historical sync");
+                        return Some(KeepUpInstruction::CheckLater(
+                            format!("historical sync paced {}", sync_hash),
+                            (self.historical_sync_interval - elapsed).into(),
+                        ));
+                    }
                     if self.validator_matrix.has_era(&sync_era) {
                         Some(self.sync_back_register(effect_builder, rng, sync_hash))
                     } else {
@@ -555,6 +658,7 @@ impl MainReactor {
             .block_synchronizer
             .register_block_by_hash(parent_hash, true)
         {
+            self.last_historical_sync_start = Timestamp::now();
             // sync the parent_hash block; we get a random sampling of peers to ask.
             // it is possible that we may get a random sampling that do not have the data
             // we need, but the synchronizer should (eventually) detect that and ask for
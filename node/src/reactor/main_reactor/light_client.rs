@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, HashSet};
+
+use datasize::DataSize;
+
+use crate::types::{BlockHash, BlockHeader, BlockSignatures, SyncLeap};
+
+/// The maximum number of block headers a light client fetches from peers at a time.
+pub(super) const MAX_PARALLEL_HEADER_FETCHES: usize = 16;
+
+/// The state of a light client's chain of block headers.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum LightClientHeadersStatus {
+    /// All headers have linked to the anchor and have been taken to be stored.
+    Complete,
+    /// Headers are missing between the runs, or between the lowest run and the anchor.
+    Missing,
+    /// A run reached the anchor's height, or the height of the run below, without linking to it.
+    Unlinked,
+}
+
+/// The verified block headers of a sync leap, along with those fetched to fill its gaps.
+///
+/// A sync leap only contains the headers of the switch blocks between its trusted block and its
+/// highest block, so a light client fetches the headers in between by hash, walking back via
+/// parent hashes.  As a fetched header's hash is its child's parent hash, it is as trustworthy as
+/// its child.
+///
+/// Each header of the leap whose parent is not in the leap starts a run of linked headers, and all
+/// runs are extended downwards in parallel until they link to the run below.  The lowest run is
+/// taken to be stored as soon as it links to the anchor, which it then becomes, so that the stored
+/// headers always form a contiguous chain.
+#[derive(DataSize, Debug)]
+pub(super) struct LightClientHeaders {
+    /// The hash of the header the lowest run needs to reach: the highest stored header, or if none
+    /// is stored, the lowest header of the sync leap.
+    anchor: BlockHash,
+    /// The height of the anchor.
+    anchor_height: u64,
+    /// The runs of linked headers, keyed by the height of their highest header.  Each run is in
+    /// descending order of height.
+    runs: BTreeMap<u64, Vec<BlockHeader>>,
+    /// The signatures of the sync leap's signed headers not yet taken to be stored.
+    signatures: Vec<BlockSignatures>,
+    /// The hashes of the headers being fetched from peers.
+    requested: HashSet<BlockHash>,
+}
+
+impl LightClientHeaders {
+    /// Creates the runs of the sync leap's headers, to be linked to `highest_stored` if there is a
+    /// stored header.
+    pub(super) fn new(sync_leap: &SyncLeap, highest_stored: Option<&BlockHeader>) -> Self {
+        let anchor_header = highest_stored.unwrap_or_else(|| {
+            sync_leap
+                .headers()
+                .min_by_key(|header| header.height())
+                .unwrap_or(&sync_leap.trusted_block_header)
+        });
+        let anchor_height = anchor_header.height();
+        // Headers below the anchor are not needed, and the highest stored one is already stored.
+        let runs = sync_leap
+            .headers()
+            .filter(|header| {
+                header.height() >= anchor_height
+                    && Some(header.block_hash()) != highest_stored.map(BlockHeader::block_hash)
+            })
+            .map(|header| (header.height(), vec![header.clone()]))
+            .collect();
+        let signatures = sync_leap
+            .signed_block_headers
+            .iter()
+            .map(|signed_block_header| signed_block_header.block_signatures.clone())
+            .collect();
+        let mut headers = LightClientHeaders {
+            anchor: anchor_header.block_hash(),
+            anchor_height,
+            runs,
+            signatures,
+            requested: HashSet::new(),
+        };
+        headers.merge_runs();
+        headers
+    }
+
+    /// Returns the state of the chain.
+    pub(super) fn status(&self) -> LightClientHeadersStatus {
+        let mut lower_top: Option<&BlockHeader> = None;
+        for run in self.runs.values() {
+            let lowest = match run.last() {
+                Some(lowest) => lowest,
+                None => continue,
+            };
+            let lower_height = lower_top.map_or(self.anchor_height, BlockHeader::height);
+            let linked = match lower_top {
+                Some(lower_top) => lowest.parent_hash() == &lower_top.block_hash(),
+                None => self.links_to_anchor(lowest),
+            };
+            if !linked && lowest.height() <= lower_height {
+                return LightClientHeadersStatus::Unlinked;
+            }
+            lower_top = run.first();
+        }
+        if self.runs.is_empty() {
+            LightClientHeadersStatus::Complete
+        } else {
+            LightClientHeadersStatus::Missing
+        }
+    }
+
+    /// Returns the hashes of the missing headers not being fetched yet, lowest first.
+    pub(super) fn missing(&self) -> impl Iterator<Item = BlockHash> + '_ {
+        self.runs
+            .values()
+            .filter_map(|run| run.last())
+            .filter(|lowest| !self.links_to_anchor(lowest))
+            .map(|lowest| *lowest.parent_hash())
+            .filter(|parent_hash| !self.requested.contains(parent_hash))
+    }
+
+    /// Returns the number of headers being fetched from peers.
+    pub(super) fn requested_count(&self) -> usize {
+        self.requested.len()
+    }
+
+    /// Records that the header with the given hash is being fetched from a peer.
+    pub(super) fn set_requested(&mut self, block_hash: BlockHash) {
+        self.requested.insert(block_hash);
+    }
+
+    /// Records that fetching the header with the given hash failed, so that it is fetched again.
+    pub(super) fn clear_requested(&mut self, block_hash: &BlockHash) {
+        self.requested.remove(block_hash);
+    }
+
+    /// Adds the given header if it is the parent of the lowest header of a run, returning whether
+    /// it was added.
+    pub(super) fn add_parent(&mut self, header: BlockHeader) -> bool {
+        let block_hash = header.block_hash();
+        self.requested.remove(&block_hash);
+        let run = match self
+            .runs
+            .values_mut()
+            .find(|run| run.last().map(BlockHeader::parent_hash) == Some(&block_hash))
+        {
+            Some(run) => run,
+            None => return false,
+        };
+        run.push(header);
+        self.merge_runs();
+        true
+    }
+
+    /// Removes and returns the lowest run and its signatures if it links to the anchor.  Its
+    /// highest header becomes the new anchor.
+    pub(super) fn take_linked(&mut self) -> Option<(Vec<BlockHeader>, Vec<BlockSignatures>)> {
+        let (&top_height, run) = self.runs.iter().next()?;
+        if !self.links_to_anchor(run.last()?) {
+            return None;
+        }
+        let run = self.runs.remove(&top_height)?;
+        let top = run.first()?;
+        self.anchor = top.block_hash();
+        self.anchor_height = top.height();
+        let block_hashes: HashSet<_> = run.iter().map(BlockHeader::block_hash).collect();
+        let (signatures, remaining) = self
+            .signatures
+            .drain(..)
+            .partition(|signatures| block_hashes.contains(&signatures.block_hash));
+        self.signatures = remaining;
+        Some((run, signatures))
+    }
+
+    /// Returns whether the given header is or is a child of the anchor, or the genesis block.
+    fn links_to_anchor(&self, header: &BlockHeader) -> bool {
+        header.block_hash() == self.anchor
+            || *header.parent_hash() == self.anchor
+            || header.height() == 0
+    }
+
+    /// Appends each run to the run above if it is the parent of that run's lowest header.
+    fn merge_runs(&mut self) {
+        let top_heights: Vec<u64> = self.runs.keys().copied().collect();
+        for window in top_heights.windows(2) {
+            let (lower_top_height, top_height) = (window[0], window[1]);
+            let links = match (self.runs.get(&lower_top_height), self.runs.get(&top_height)) {
+                (Some(lower_run), Some(run)) => {
+                    run.last().map(BlockHeader::parent_hash)
+                        == lower_run.first().map(BlockHeader::block_hash).as_ref()
+                }
+                _ => false,
+            };
+            if links {
+                let lower_run = self.runs.remove(&lower_top_height).unwrap_or_default();
+                if let Some(run) = self.runs.get_mut(&top_height) {
+                    run.extend(lower_run);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, iter};
+
+    use casper_types::{testing::TestRng, EraId, ProtocolVersion};
+
+    use super::*;
+    use crate::types::{Block, BlockHeaderWithMetadata};
+
+    /// Returns the headers of a chain of `count` blocks, in ascending order of height.
+    fn chain(rng: &mut TestRng, count: u64) -> Vec<BlockHeader> {
+        let mut parent_hash = None;
+        (10..10 + count)
+            .map(|height| {
+                let block = Block::random_with_specifics_and_parent_and_validator_weights(
+                    rng,
+                    EraId::new(1),
+                    height,
+                    ProtocolVersion::V1_0_0,
+                    false,
+                    iter::empty(),
+                    parent_hash,
+                    BTreeMap::new(),
+                );
+                parent_hash = Some(*block.hash());
+                block.take_header()
+            })
+            .collect()
+    }
+
+    fn signed(header: &BlockHeader) -> BlockHeaderWithMetadata {
+        BlockHeaderWithMetadata {
+            block_header: header.clone(),
+            block_signatures: BlockSignatures::new(header.block_hash(), header.era_id()),
+        }
+    }
+
+    /// Returns a sync leap trusting `headers[trusted]`, with the given ancestors and signed headers.
+    fn sync_leap(
+        headers: &[BlockHeader],
+        trusted: usize,
+        ancestors: &[usize],
+        signed_headers: &[usize],
+    ) -> SyncLeap {
+        SyncLeap {
+            trusted_ancestor_only: false,
+            trusted_block_header: headers[trusted].clone(),
+            trusted_ancestor_headers: ancestors
+                .iter()
+                .map(|index| headers[*index].clone())
+                .collect(),
+            signed_block_headers: signed_headers
+                .iter()
+                .map(|index| signed(&headers[*index]))
+                .collect(),
+        }
+    }
+
+    fn heights(headers: &[BlockHeader]) -> Vec<u64> {
+        headers.iter().map(BlockHeader::height).collect()
+    }
+
+    #[test]
+    fn should_fill_gaps_in_parallel_and_take_linked_runs() {
+        let mut rng = TestRng::new();
+        let headers = chain(&mut rng, 8);
+        // The leap trusts block 2, with ancestors back to block 0, and contains block 5 as a switch
+        // block and block 7 as the highest block.
+        let leap = sync_leap(&headers, 2, &[1, 0], &[5, 7]);
+        let mut light_client_headers = LightClientHeaders::new(&leap, None);
+
+        // The run down to the leap's lowest header is linked right away.
+        let (run, signatures) = light_client_headers.take_linked().unwrap();
+        assert_eq!(heights(&run), vec![12, 11, 10]);
+        assert!(signatures.is_empty());
+        assert!(light_client_headers.take_linked().is_none());
+
+        // The gaps below blocks 5 and 7 are filled in parallel.
+        let missing: Vec<_> = light_client_headers.missing().collect();
+        assert_eq!(
+            missing,
+            vec![headers[4].block_hash(), headers[6].block_hash()]
+        );
+        assert!(light_client_headers.add_parent(headers[6].clone()));
+        // Block 6 links the run of block 7 to the run of block 5.
+        let missing: Vec<_> = light_client_headers.missing().collect();
+        assert_eq!(missing, vec![headers[4].block_hash()]);
+        assert!(light_client_headers.add_parent(headers[4].clone()));
+        assert!(light_client_headers.take_linked().is_none());
+        assert!(light_client_headers.add_parent(headers[3].clone()));
+        assert_eq!(
+            light_client_headers.status(),
+            LightClientHeadersStatus::Missing
+        );
+
+        let (run, signatures) = light_client_headers.take_linked().unwrap();
+        assert_eq!(heights(&run), vec![17, 16, 15, 14, 13]);
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(
+            light_client_headers.status(),
+            LightClientHeadersStatus::Complete
+        );
+    }
+
+    #[test]
+    fn should_stop_at_the_highest_stored_header() {
+        let mut rng = TestRng::new();
+        let headers = chain(&mut rng, 6);
+        // Block 2 is the highest stored header, and the leap trusts it.
+        let leap = sync_leap(&headers, 2, &[1, 0], &[5]);
+        let mut light_client_headers = LightClientHeaders::new(&leap, Some(&headers[2]));
+        assert!(light_client_headers.take_linked().is_none());
+
+        assert!(light_client_headers.add_parent(headers[4].clone()));
+        assert!(light_client_headers.add_parent(headers[3].clone()));
+
+        let (run, _) = light_client_headers.take_linked().unwrap();
+        assert_eq!(heights(&run), vec![15, 14, 13]);
+        assert_eq!(
+            light_client_headers.status(),
+            LightClientHeadersStatus::Complete
+        );
+    }
+
+    #[test]
+    fn should_not_return_requested_headers_as_missing() {
+        let mut rng = TestRng::new();
+        let headers = chain(&mut rng, 4);
+        let leap = sync_leap(&headers, 0, &[], &[3]);
+        let mut light_client_headers = LightClientHeaders::new(&leap, None);
+        let _ = light_client_headers.take_linked();
+
+        light_client_headers.set_requested(headers[2].block_hash());
+        assert_eq!(light_client_headers.missing().count(), 0);
+        assert_eq!(light_client_headers.requested_count(), 1);
+
+        // A failed fetch makes the header missing again.
+        light_client_headers.clear_requested(&headers[2].block_hash());
+        let missing: Vec<_> = light_client_headers.missing().collect();
+        assert_eq!(missing, vec![headers[2].block_hash()]);
+
+        // A fetched header is no longer requested.
+        light_client_headers.set_requested(headers[2].block_hash());
+        assert!(light_client_headers.add_parent(headers[2].clone()));
+        assert_eq!(light_client_headers.requested_count(), 0);
+    }
+
+    #[test]
+    fn should_reject_headers_not_linking_to_the_chain() {
+        let mut rng = TestRng::new();
+        let headers = chain(&mut rng, 4);
+        let other_headers = chain(&mut rng, 4);
+        let leap = sync_leap(&headers, 0, &[], &[3]);
+        let mut light_client_headers = LightClientHeaders::new(&leap, None);
+        let _ = light_client_headers.take_linked();
+
+        assert!(!light_client_headers.add_parent(other_headers[2].clone()));
+        let missing: Vec<_> = light_client_headers.missing().collect();
+        assert_eq!(missing, vec![headers[2].block_hash()]);
+    }
+
+    #[test]
+    fn should_detect_chain_not_linking_to_the_highest_stored_header() {
+        let mut rng = TestRng::new();
+        let headers = chain(&mut rng, 4);
+        let other_headers = chain(&mut rng, 4);
+        let leap = sync_leap(&headers, 0, &[], &[3]);
+        let mut light_client_headers = LightClientHeaders::new(&leap, Some(&other_headers[1]));
+
+        assert!(light_client_headers.add_parent(headers[2].clone()));
+        assert_eq!(
+            light_client_headers.status(),
+            LightClientHeadersStatus::Missing
+        );
+        assert!(light_client_headers.add_parent(headers[1].clone()));
+        assert_eq!(
+            light_client_headers.status(),
+            LightClientHeadersStatus::Unlinked
+        );
+        assert!(light_client_headers.take_linked().is_none());
+    }
+}
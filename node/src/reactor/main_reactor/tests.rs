@@ -1,4 +1,11 @@
-use std::{collections::BTreeMap, iter, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    net::SocketAddr,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use either::Either;
 use num::Zero;
@@ -39,7 +46,7 @@ use crate::{
     types::{
         chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
         ActivationPoint, AvailableBlockRange, Block, BlockHash, BlockHeader, BlockPayload,
-        Chainspec, ChainspecRawBytes, Deploy, ExitCode, NodeId, SyncHandling,
+        Chainspec, ChainspecRawBytes, Deploy, ExitCode, NodeId, NodeMode, SyncHandling,
     },
     utils::{External, Loadable, Source, RESOURCES_PATH},
     WithDir,
@@ -69,6 +76,7 @@ enum InitialStakes {
 struct ChainspecOverride {
     minimum_block_time: TimeDiff,
     minimum_era_height: u64,
+    administrators: BTreeSet<PublicKey>,
 }
 
 impl Default for ChainspecOverride {
@@ -76,6 +84,7 @@ impl Default for ChainspecOverride {
         ChainspecOverride {
             minimum_block_time: "1second".parse().unwrap(),
             minimum_era_height: 2,
+            administrators: BTreeSet::new(),
         }
     }
 }
@@ -177,6 +186,7 @@ impl TestFixture {
         let spec_override = spec_override.unwrap_or_default();
         chainspec.core_config.minimum_block_time = spec_override.minimum_block_time;
         chainspec.core_config.minimum_era_height = spec_override.minimum_era_height;
+        chainspec.core_config.administrators = spec_override.administrators;
         chainspec.highway_config.maximum_round_length =
             chainspec.core_config.minimum_block_time * 2;
 
@@ -603,6 +613,7 @@ async fn should_not_historical_sync_no_sync_node() {
     let spec_override = ChainspecOverride {
         minimum_block_time: "4seconds".parse().unwrap(),
         minimum_era_height: 1,
+        ..Default::default()
     };
     let mut fixture = TestFixture::new(initial_stakes, Some(spec_override)).await;
 
@@ -679,6 +690,84 @@ async fn should_not_historical_sync_no_sync_node() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn light_node_should_store_contiguous_headers_only() {
+    let initial_stakes = InitialStakes::Random { count: 5 };
+    let spec_override = ChainspecOverride {
+        minimum_block_time: "4seconds".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut fixture = TestFixture::new(initial_stakes, Some(spec_override)).await;
+
+    // Wait for all nodes to complete block 1.
+    fixture.run_until_block_height(1, ONE_MIN).await;
+
+    // Create a light joiner node.
+    let highest_block = fixture.highest_complete_block();
+    let trusted_hash = *highest_block.hash();
+    let trusted_height = highest_block.height();
+    let secret_key = SecretKey::random(&mut fixture.rng);
+    let (mut config, storage_dir) = fixture.create_node_config(&secret_key, Some(trusted_hash));
+    config.node.mode = NodeMode::Light;
+    let joiner_id = fixture
+        .add_node(Arc::new(secret_key), config, storage_dir)
+        .await;
+
+    let joiner_highest_height = |nodes: &Nodes| {
+        nodes
+            .get(&joiner_id)
+            .expect("should have joiner")
+            .main_reactor()
+            .storage()
+            .read_highest_block_header()
+            .expect("should read highest block header")
+            .map(|block_header| block_header.height())
+    };
+
+    // Run until the joiner has stored the headers of several leaps.
+    let target_height = trusted_height + 6;
+    fixture
+        .try_run_until(
+            |nodes: &Nodes| {
+                joiner_highest_height(nodes).map_or(false, |height| height >= target_height)
+            },
+            ONE_MIN * 2,
+        )
+        .await
+        .unwrap_or_else(|_| {
+            panic!(
+                "timed out waiting for joiner's highest block header to reach {}",
+                target_height
+            )
+        });
+
+    // The joiner must have stored a contiguous chain of headers from the trusted block onwards,
+    // and no complete blocks.
+    let storage = fixture
+        .network
+        .nodes()
+        .get(&joiner_id)
+        .expect("should have joiner")
+        .main_reactor()
+        .storage();
+    let highest_height = joiner_highest_height(fixture.network.nodes()).unwrap();
+    let mut parent_hash = None;
+    for height in trusted_height..=highest_height {
+        let block_header = storage
+            .read_block_header_by_height(height, false)
+            .expect("should read block header")
+            .unwrap_or_else(|| panic!("joiner should have block header at height {}", height));
+        if let Some(parent_hash) = parent_hash {
+            assert_eq!(*block_header.parent_hash(), parent_hash);
+        }
+        parent_hash = Some(block_header.block_hash());
+    }
+    assert!(storage
+        .read_highest_complete_block()
+        .expect("should read highest complete block")
+        .is_none());
+}
+
 #[tokio::test]
 async fn run_equivocator_network() {
     let mut rng = crate::new_rng();
@@ -924,14 +1013,30 @@ async fn dont_upgrade_without_switch_block() {
 
 #[tokio::test]
 async fn should_store_finalized_approvals() {
+    let mut rng = crate::new_rng();
+
     // Set up a network with two nodes where node 0 (Alice) is effectively guaranteed to be the
     // proposer.
-    let initial_stakes = InitialStakes::FromVec(vec![u128::MAX, 1]);
-    let mut fixture = TestFixture::new(initial_stakes, None).await;
-
-    let alice_secret_key = Arc::clone(&fixture.node_contexts[0].secret_key);
+    let alice_secret_key = Arc::new(SecretKey::random(&mut rng));
     let alice_public_key = PublicKey::from(&*alice_secret_key);
-    let bob_secret_key = Arc::clone(&fixture.node_contexts[1].secret_key);
+    let bob_secret_key = Arc::new(SecretKey::random(&mut rng));
+    let bob_public_key = PublicKey::from(&*bob_secret_key);
+
+    let mut stakes = BTreeMap::new();
+    stakes.insert(alice_public_key.clone(), U512::from(u128::MAX));
+    stakes.insert(bob_public_key, U512::one());
+
+    // The deploy's account doesn't exist and its approvals include keys of other accounts, so it is
+    // only proposed if approved by an administrator.
+    let spec_override = ChainspecOverride {
+        administrators: iter::once(alice_public_key.clone()).collect(),
+        ..Default::default()
+    };
+
+    let secret_keys = vec![alice_secret_key.clone(), bob_secret_key.clone()];
+    let mut fixture =
+        TestFixture::new_with_keys(rng, secret_keys, stakes, Some(spec_override)).await;
+
     let charlie_secret_key = Arc::new(SecretKey::random(&mut fixture.rng)); // just for ordering testing purposes
 
     // Wait for all nodes to complete era 0.
@@ -24,6 +24,10 @@ pub enum QueueKind {
     NetworkIncoming,
     /// Network events that are low priority.
     NetworkLowPriority,
+    /// Incoming gossip messages from the network.
+    ///
+    /// Gossip is redundant by nature, so these events may be shed when the queue is full.
+    NetworkGossip,
     /// Network events demand a resource directly.
     NetworkDemand,
     /// Network events that were initiated by the local node, such as outgoing messages.
@@ -66,6 +70,7 @@ impl Display for QueueKind {
             QueueKind::Control => "Control",
             QueueKind::NetworkIncoming => "NetworkIncoming",
             QueueKind::NetworkLowPriority => "NetworkLowPriority",
+            QueueKind::NetworkGossip => "NetworkGossip",
             QueueKind::NetworkDemand => "NetworkDemand",
             QueueKind::Network => "Network",
             QueueKind::NetworkInfo => "NetworkInfo",
@@ -96,6 +101,7 @@ impl QueueKind {
             QueueKind::NetworkInfo => 2,
             QueueKind::NetworkDemand => 2,
             QueueKind::NetworkIncoming => 8,
+            QueueKind::NetworkGossip => 4,
             QueueKind::Network => 4,
             QueueKind::Regular => 4,
             QueueKind::Fetch => 4,
@@ -104,7 +110,8 @@ impl QueueKind {
             QueueKind::ToStorage => 4,
             QueueKind::ContractRuntime => 4,
             QueueKind::SyncGlobalState => 4,
-            QueueKind::Consensus => 4,
+            // Consensus is time critical, falling behind can cause the node to miss rounds.
+            QueueKind::Consensus => 16,
             QueueKind::FinalitySignature => 4,
             QueueKind::Validation => 8,
             QueueKind::Api => 8,
@@ -121,12 +128,28 @@ impl QueueKind {
             .collect()
     }
 
+    /// Returns whether events in this queue may be dropped once the queue is full.
+    ///
+    /// Events in all other queues are kept, blocking their producers instead.
+    pub(crate) fn is_sheddable(self) -> bool {
+        matches!(
+            self,
+            QueueKind::NetworkLowPriority | QueueKind::NetworkGossip
+        )
+    }
+
+    /// Returns the queue kind with the given metrics name, if any.
+    pub(crate) fn from_metrics_name(name: &str) -> Option<Self> {
+        QueueKind::into_enum_iter().find(|queue_kind| queue_kind.metrics_name() == name)
+    }
+
     pub(crate) fn metrics_name(&self) -> &str {
         match self {
             QueueKind::Control => "control",
             QueueKind::NetworkIncoming => "network_incoming",
             QueueKind::NetworkDemand => "network_demands",
             QueueKind::NetworkLowPriority => "network_low_priority",
+            QueueKind::NetworkGossip => "network_gossip",
             QueueKind::Network => "network",
             QueueKind::NetworkInfo => "network_info",
             QueueKind::SyncGlobalState => "sync_global_state",
@@ -0,0 +1,341 @@
+//! Thread and runtime topology of the node.
+//!
+//! The node runs its reactor and components on a main tokio runtime. Optionally, networking tasks
+//! run on a dedicated runtime, so that a busy reactor doesn't delay reading from and writing to
+//! peers. Resource intensive tasks such as block execution run on a fixed-size pool of threads,
+//! which can be pinned to CPU cores.
+//!
+//! The layout is determined once at startup from the `[runtime]` config section; changing it
+//! requires a restart.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    future::Future,
+    io,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use datasize::DataSize;
+use futures::{future::BoxFuture, FutureExt};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    runtime::{Builder, Handle, Runtime},
+    sync::oneshot,
+    task::JoinHandle,
+};
+use tracing::{info, warn};
+
+use crate::MAX_THREAD_COUNT;
+
+/// Default number of threads executing resource intensive tasks.
+const DEFAULT_EXECUTION_THREADS: usize = 4;
+
+/// The dedicated runtime for networking tasks, if configured.
+static NETWORK_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+/// The pool running resource intensive tasks.
+static EXECUTION_POOL: OnceCell<ExecutionPool> = OnceCell::new();
+
+/// The layout set up by [`build`], reported once logging is initialized.
+static LAYOUT: OnceCell<Layout> = OnceCell::new();
+
+/// Thread and runtime configuration.
+#[derive(Clone, DataSize, Debug, Serialize, Deserialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Number of worker threads of the main runtime. `0` means one per CPU core.
+    pub worker_threads: usize,
+    /// Maximum number of threads the main runtime spawns for blocking operations. `0` means the
+    /// remainder of `MAX_THREAD_COUNT` after the worker threads.
+    pub max_blocking_threads: usize,
+    /// Number of worker threads of a dedicated runtime for networking tasks. `0` means networking
+    /// tasks share the main runtime.
+    pub network_threads: usize,
+    /// Number of threads executing resource intensive tasks such as blocks and speculative
+    /// deploys. At least one thread is always started.
+    pub execution_threads: usize,
+    /// Whether to pin each execution thread to its own CPU core, starting from the last core.
+    /// Ignored on platforms other than Linux.
+    pub pin_execution_threads: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            worker_threads: 0,
+            max_blocking_threads: 0,
+            network_threads: 0,
+            execution_threads: DEFAULT_EXECUTION_THREADS,
+            pin_execution_threads: false,
+        }
+    }
+}
+
+/// The effective thread layout, after applying defaults.
+#[derive(Clone, Copy, Debug)]
+struct Layout {
+    cpus: usize,
+    worker_threads: usize,
+    max_blocking_threads: usize,
+    network_threads: usize,
+    execution_threads: usize,
+    pinned_cores: Option<(usize, usize)>,
+}
+
+impl Layout {
+    fn new(config: &Config) -> Self {
+        let cpus = num_cpus::get();
+        let worker_threads = match config.worker_threads {
+            0 => cpus,
+            count => count,
+        }
+        .min(MAX_THREAD_COUNT);
+        let max_blocking_threads = match config.max_blocking_threads {
+            0 => MAX_THREAD_COUNT - worker_threads,
+            count => count,
+        }
+        .max(1);
+        let execution_threads = config.execution_threads.max(1);
+        let pinned_cores = (config.pin_execution_threads && cfg!(target_os = "linux")).then(|| {
+            let first = cpus.saturating_sub(execution_threads);
+            (first, cpus - 1)
+        });
+        Layout {
+            cpus,
+            worker_threads,
+            max_blocking_threads,
+            network_threads: config.network_threads,
+            execution_threads,
+            pinned_cores,
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} cpus, main runtime with {} workers and up to {} blocking threads, ",
+            self.cpus, self.worker_threads, self.max_blocking_threads
+        )?;
+        if self.network_threads == 0 {
+            write!(formatter, "network on main runtime, ")?;
+        } else {
+            write!(
+                formatter,
+                "network runtime with {} workers, ",
+                self.network_threads
+            )?;
+        }
+        write!(formatter, "{} execution threads", self.execution_threads)?;
+        if let Some((first, last)) = self.pinned_cores {
+            write!(formatter, " pinned to cores {}-{}", first, last)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the main runtime and sets up the network runtime and execution pool as configured.
+///
+/// Must be called at most once, before any of the node's components are created.
+pub fn build(config: &Config) -> io::Result<Runtime> {
+    let layout = Layout::new(config);
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .worker_threads(layout.worker_threads)
+        .max_blocking_threads(layout.max_blocking_threads)
+        .build()?;
+
+    if layout.network_threads > 0 {
+        let network_runtime = Builder::new_multi_thread()
+            .enable_all()
+            .worker_threads(layout.network_threads)
+            .thread_name("casper-network")
+            .build()?;
+        if NETWORK_RUNTIME.set(network_runtime).is_err() {
+            return Err(already_built());
+        }
+    }
+
+    let pool = ExecutionPool::new(layout.execution_threads, layout.pinned_cores.is_some())?;
+    if EXECUTION_POOL.set(pool).is_err() || LAYOUT.set(layout).is_err() {
+        return Err(already_built());
+    }
+
+    Ok(runtime)
+}
+
+fn already_built() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "runtime already built")
+}
+
+/// Logs the thread layout set up by [`build`].
+pub(crate) fn log_layout() {
+    if let Some(layout) = LAYOUT.get() {
+        info!(%layout, "thread layout");
+    }
+}
+
+/// Spawns a networking task onto the network runtime, or the current runtime if there is none.
+pub(crate) fn spawn_network<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match NETWORK_RUNTIME.get() {
+        Some(network_runtime) => network_runtime.spawn(future),
+        None => Handle::current().spawn(future),
+    }
+}
+
+/// Runs a networking task on the network runtime, if there is one, returning a future for its
+/// output.
+///
+/// Without a dedicated network runtime, the task is returned as is, to be run wherever the
+/// returned future is polled.
+pub(crate) fn run_on_network<F>(future: F) -> BoxFuture<'static, F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match NETWORK_RUNTIME.get() {
+        Some(network_runtime) => {
+            let join_handle = network_runtime.spawn(future);
+            async move {
+                match join_handle.await {
+                    Ok(output) => output,
+                    Err(error) => panic::resume_unwind(error.into_panic()),
+                }
+            }
+            .boxed()
+        }
+        None => future.boxed(),
+    }
+}
+
+/// Runs a resource intensive task on the execution pool, returning a future for its output.
+///
+/// If the pool wasn't set up by [`build`], e.g. in tests, it is started with the default config.
+pub(crate) async fn run_on_execution_pool<T, V>(task: T) -> V
+where
+    T: 'static + Send + FnOnce() -> V,
+    V: 'static + Send,
+{
+    let pool = EXECUTION_POOL.get_or_init(|| {
+        ExecutionPool::new(DEFAULT_EXECUTION_THREADS, false)
+            .expect("should start execution threads")
+    });
+    let (sender, receiver) = oneshot::channel();
+    pool.execute(Box::new(move || {
+        // The receiver only goes away if the caller isn't interested in the result anymore.
+        let _ = sender.send(task());
+    }));
+    receiver.await.expect("task panicked")
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed-size pool of threads running jobs in the order they are submitted.
+struct ExecutionPool {
+    sender: Sender<Job>,
+}
+
+impl ExecutionPool {
+    /// Starts `threads` threads, optionally pinning each to one of the last `threads` CPU cores.
+    fn new(threads: usize, pin: bool) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let cpus = num_cpus::get();
+        for index in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            let core = pin.then(|| cpus - 1 - index % cpus);
+            thread::Builder::new()
+                .name(format!("casper-exec-{}", index))
+                .spawn(move || Self::run_worker(&receiver, core))?;
+        }
+        Ok(ExecutionPool { sender })
+    }
+
+    fn execute(&self, job: Job) {
+        // The workers never exit while the pool exists, so sending can't fail.
+        let _ = self.sender.send(job);
+    }
+
+    fn run_worker(receiver: &Mutex<Receiver<Job>>, core: Option<usize>) {
+        if let Some(core) = core {
+            if let Err(error) = pin_to_core(core) {
+                warn!(%error, core, "failed to pin execution thread");
+            }
+        }
+        loop {
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            match job {
+                // A panicking job drops its result sender, which is reported to the caller.
+                Ok(job) => {
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+/// Pins the current thread to the given CPU core.
+#[cfg(target_os = "linux")]
+fn pin_to_core(core: usize) -> io::Result<()> {
+    // SAFETY: `cpu_set_t` is a plain bit mask, for which all zeroes is a valid value.
+    let mut cpu_set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    unsafe { libc::CPU_SET(core, &mut cpu_set) };
+    let outcome =
+        unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpu_set) };
+    match outcome {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_core: usize) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_run_tasks_on_execution_pool() {
+        let outputs = futures::future::join_all(
+            (0..10).map(|index| run_on_execution_pool(move || index * 2)),
+        )
+        .await;
+        assert_eq!(outputs, (0..10).map(|index| index * 2).collect::<Vec<_>>());
+
+        // A panicking task doesn't take down the pool.
+        let result = tokio::spawn(run_on_execution_pool(|| panic!("boom"))).await;
+        assert!(result.is_err());
+        assert_eq!(run_on_execution_pool(|| 42).await, 42);
+    }
+
+    #[test]
+    fn should_apply_layout_defaults() {
+        let layout = Layout::new(&Config::default());
+        assert_eq!(layout.worker_threads, num_cpus::get());
+        assert_eq!(
+            layout.max_blocking_threads,
+            MAX_THREAD_COUNT - num_cpus::get()
+        );
+        assert_eq!(layout.execution_threads, DEFAULT_EXECUTION_THREADS);
+        assert!(layout.pinned_cores.is_none());
+    }
+}
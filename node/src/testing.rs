@@ -350,9 +350,17 @@ impl ReactorEvent for UnitTestEvent {
     fn try_into_control(self) -> Option<ControlAnnouncement> {
         match self {
             UnitTestEvent::ControlAnnouncement(ctrl_ann) => Some(ctrl_ann),
-            UnitTestEvent::FatalAnnouncement(FatalAnnouncement { file, line, msg }) => {
-                Some(ControlAnnouncement::FatalError { file, line, msg })
-            }
+            UnitTestEvent::FatalAnnouncement(FatalAnnouncement {
+                file,
+                line,
+                msg,
+                exit_code,
+            }) => Some(ControlAnnouncement::FatalError {
+                file,
+                line,
+                msg,
+                exit_code,
+            }),
             UnitTestEvent::NetworkRequest(_) => None,
         }
     }
@@ -13,7 +13,7 @@ use tracing::debug;
 
 use casper_types::Timestamp;
 
-pub(crate) use crate::components::deploy_acceptor::{Error, Event};
+pub(crate) use crate::components::deploy_acceptor::{DeployAcceptance, Error, Event};
 use crate::{
     components::{deploy_acceptor::EventMetadata, Component},
     effect::{
@@ -56,7 +56,7 @@ impl FakeDeployAcceptor {
         effect_builder: EffectBuilder<REv>,
         deploy: Arc<Deploy>,
         source: Source,
-        maybe_responder: Option<Responder<Result<(), Error>>>,
+        maybe_responder: Option<Responder<Result<DeployAcceptance, Error>>>,
     ) -> Effects<Event> {
         let verification_start_timestamp = Timestamp::now();
         let event_metadata = Box::new(EventMetadata::new(
@@ -83,6 +83,7 @@ impl FakeDeployAcceptor {
             deploy,
             source,
             maybe_responder,
+            ..
         } = *event_metadata;
         let mut effects = Effects::new();
         if is_new {
@@ -94,7 +95,7 @@ impl FakeDeployAcceptor {
         }
 
         if let Some(responder) = maybe_responder {
-            effects.extend(responder.respond(Ok(())).ignore());
+            effects.extend(responder.respond(Ok(DeployAcceptance::Accepted)).ignore());
         }
         effects
     }
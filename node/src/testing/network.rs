@@ -1,4 +1,14 @@
 //! A network of test reactors.
+//!
+//! # Determinism
+//!
+//! Nodes are always cranked in order of their node IDs, so given the same seeded `TestRng`, the
+//! same sequence of events is dispatched on every run. Networks created through
+//! [`TestingNetwork::with_virtual_time`] additionally run on a simulated clock: whenever all nodes
+//! are idle, time jumps straight to the next pending timer instead of actually sleeping, making
+//! tests both fast and independent of the load on the machine running them. Virtual time requires
+//! a single-threaded runtime (the default for `#[tokio::test]`) and reactors which do not perform
+//! real network I/O.
 
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -108,6 +118,33 @@ where
         }
     }
 
+    /// Creates a new network running on virtual time.
+    ///
+    /// See the module documentation for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from within a single-threaded tokio runtime.
+    pub(crate) fn with_virtual_time() -> Self {
+        time::pause();
+        Self::new()
+    }
+
+    /// Returns the IDs of all nodes, in the order they are cranked.
+    fn sorted_node_ids(&self) -> Vec<NodeId> {
+        let mut node_ids: Vec<_> = self.nodes.keys().copied().collect();
+        node_ids.sort();
+        node_ids
+    }
+
+    /// Lets `duration` pass while idling.
+    ///
+    /// When running on virtual time, this returns as soon as the clock has been advanced.
+    async fn idle_for(duration: Duration) {
+        Instant::advance_time(duration.as_millis() as u64);
+        time::sleep(duration).await;
+    }
+
     /// Creates a new networking node on the network.
     ///
     /// # Panics
@@ -202,8 +239,8 @@ where
     /// Panics if any node returns an exit code.
     async fn crank_all(&mut self, rng: &mut TestRng) -> usize {
         let mut event_count = 0;
-        for node in self.nodes.values_mut() {
-            let node_id = node.reactor().node_id();
+        for node_id in self.sorted_node_ids() {
+            let node = self.nodes.get_mut(&node_id).expect("should find node");
             match node
                 .try_crank(rng)
                 .instrument(error_span!("crank", node_id = %node_id))
@@ -254,8 +291,8 @@ where
     ) {
         loop {
             let mut no_events = true;
-            for node in self.nodes.values_mut() {
-                let node_id = node.reactor().node_id();
+            for node_id in self.sorted_node_ids() {
+                let node = self.nodes.get_mut(&node_id).expect("should find node");
                 match node
                     .try_crank(rng)
                     .instrument(error_span!("crank", node_id = %node_id))
@@ -277,8 +314,7 @@ where
             }
 
             if no_events {
-                Instant::advance_time(POLL_INTERVAL.as_millis() as u64);
-                time::sleep(POLL_INTERVAL).await;
+                Self::idle_for(POLL_INTERVAL).await;
                 continue;
             }
         }
@@ -314,8 +350,7 @@ where
                     break;
                 } else {
                     no_events = true;
-                    Instant::advance_time(quiet_for.as_millis() as u64);
-                    time::sleep(quiet_for).await;
+                    Self::idle_for(quiet_for).await;
                 }
             } else {
                 no_events = false;
@@ -373,8 +408,7 @@ where
 
             if self.crank_all(rng).await == 0 {
                 // No events processed, wait for a bit to avoid 100% cpu usage.
-                Instant::advance_time(POLL_INTERVAL.as_millis() as u64);
-                time::sleep(POLL_INTERVAL).await;
+                Self::idle_for(POLL_INTERVAL).await;
             }
         }
     }
@@ -403,8 +437,8 @@ where
             }
 
             let mut event_count = 0;
-            for node in self.nodes.values_mut() {
-                let node_id = node.reactor().node_id();
+            for node_id in self.sorted_node_ids() {
+                let node = self.nodes.get_mut(&node_id).expect("should find node");
                 match node
                     .try_crank(rng)
                     .instrument(error_span!("crank", node_id = %node_id))
@@ -428,8 +462,7 @@ where
 
             if event_count == 0 {
                 // No events processed, wait for a bit to avoid 100% cpu usage.
-                Instant::advance_time(POLL_INTERVAL.as_millis() as u64);
-                time::sleep(POLL_INTERVAL).await;
+                Self::idle_for(POLL_INTERVAL).await;
             }
         }
     }
@@ -497,3 +530,129 @@ where
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::{self, Display, Formatter},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use derive_more::From;
+    use prometheus::Registry;
+    use serde::Serialize;
+    use tokio::time;
+
+    use casper_types::testing::TestRng;
+
+    use super::{NetworkedReactor, TestingNetwork};
+    use crate::{
+        components::network::Identity as NetworkIdentity,
+        effect::{announcements::ControlAnnouncement, EffectBuilder, EffectExt, Effects},
+        reactor::{EventQueueHandle, Reactor, ReactorEvent},
+        types::{Chainspec, ChainspecRawBytes},
+        NodeRng,
+    };
+
+    /// Interval at which the test reactor's timer expires.
+    const TIMER_INTERVAL: Duration = Duration::from_secs(10);
+
+    #[derive(Debug, From, Serialize)]
+    enum Event {
+        #[from]
+        ControlAnnouncement(ControlAnnouncement),
+        TimerExpired,
+    }
+
+    impl Display for Event {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                Event::ControlAnnouncement(ctrl_ann) => write!(f, "control: {}", ctrl_ann),
+                Event::TimerExpired => write!(f, "timer expired"),
+            }
+        }
+    }
+
+    impl ReactorEvent for Event {
+        fn is_control(&self) -> bool {
+            matches!(self, Event::ControlAnnouncement(_))
+        }
+
+        fn try_into_control(self) -> Option<ControlAnnouncement> {
+            match self {
+                Event::ControlAnnouncement(ctrl_ann) => Some(ctrl_ann),
+                Event::TimerExpired => None,
+            }
+        }
+    }
+
+    /// Reactor which does nothing but continuously reset a timer, counting its expirations.
+    #[derive(Debug)]
+    struct TimerReactor {
+        expirations: u64,
+    }
+
+    impl Reactor for TimerReactor {
+        type Event = Event;
+        type Config = ();
+        type Error = prometheus::Error;
+
+        fn dispatch_event(
+            &mut self,
+            effect_builder: EffectBuilder<Event>,
+            _rng: &mut NodeRng,
+            event: Event,
+        ) -> Effects<Event> {
+            match event {
+                Event::ControlAnnouncement(ctrl_ann) => {
+                    unreachable!("unhandled control announcement: {}", ctrl_ann)
+                }
+                Event::TimerExpired => {
+                    self.expirations += 1;
+                    effect_builder
+                        .set_timeout(TIMER_INTERVAL)
+                        .event(|_| Event::TimerExpired)
+                }
+            }
+        }
+
+        fn new(
+            _cfg: Self::Config,
+            _chainspec: Arc<Chainspec>,
+            _chainspec_raw_bytes: Arc<ChainspecRawBytes>,
+            _network_identity: NetworkIdentity,
+            _registry: &Registry,
+            event_queue: EventQueueHandle<Event>,
+            _rng: &mut NodeRng,
+        ) -> Result<(Self, Effects<Event>), prometheus::Error> {
+            let effects = EffectBuilder::new(event_queue)
+                .set_timeout(TIMER_INTERVAL)
+                .event(|_| Event::TimerExpired);
+            Ok((TimerReactor { expirations: 0 }, effects))
+        }
+    }
+
+    impl NetworkedReactor for TimerReactor {}
+
+    #[tokio::test]
+    async fn should_run_on_virtual_time() {
+        let mut rng = TestRng::new();
+        let mut network = TestingNetwork::<TimerReactor>::with_virtual_time();
+        let (node_id, _) = network.add_node(&mut rng).await.unwrap();
+
+        let started = time::Instant::now();
+        let real_started = std::time::Instant::now();
+        network
+            .settle_on(
+                &mut rng,
+                move |nodes| nodes[&node_id].reactor().inner().expirations >= 10,
+                Duration::from_secs(3_600),
+            )
+            .await;
+
+        // Ten expirations take at least 100 seconds of virtual time, but hardly any real time.
+        assert!(started.elapsed() >= TIMER_INTERVAL * 10);
+        assert!(real_started.elapsed() < TIMER_INTERVAL);
+    }
+}
@@ -316,13 +316,15 @@ pub fn generate_node_cert() -> SslResult<(X509, PKey<Private>)> {
 /// The acceptor will restrict TLS parameters to secure one defined in this crate that are
 /// compatible with connectors built with `create_tls_connector`.
 ///
-/// Incoming certificates must still be validated using `validate_cert`.
+/// If an `authority` is given, the handshake fails unless the client presents a certificate signed
+/// by it. Incoming certificates must still be validated using `validate_cert`.
 pub(crate) fn create_tls_acceptor(
     cert: &X509Ref,
     private_key: &PKeyRef<Private>,
+    authority: Option<&X509Ref>,
 ) -> SslResult<SslAcceptor> {
     let mut builder = SslAcceptor::mozilla_modern_v5(SslMethod::tls_server())?;
-    set_context_options(&mut builder, cert, private_key)?;
+    set_context_options(&mut builder, cert, private_key, authority)?;
 
     Ok(builder.build())
 }
@@ -330,13 +332,15 @@ pub(crate) fn create_tls_acceptor(
 /// Creates a TLS acceptor for a client.
 ///
 /// A connector compatible with the acceptor created using `create_tls_acceptor`. Server
-/// certificates must always be validated using `validate_cert` after connecting.
+/// certificates must always be validated using `validate_cert` after connecting, even if the
+/// handshake already checked them against an `authority`.
 pub(crate) fn create_tls_connector(
     cert: &X509Ref,
     private_key: &PKeyRef<Private>,
+    authority: Option<&X509Ref>,
 ) -> SslResult<SslConnector> {
     let mut builder = SslConnector::builder(SslMethod::tls_client())?;
-    set_context_options(&mut builder, cert, private_key)?;
+    set_context_options(&mut builder, cert, private_key, authority)?;
 
     Ok(builder.build())
 }
@@ -348,6 +352,7 @@ fn set_context_options(
     ctx: &mut SslContextBuilder,
     cert: &X509Ref,
     private_key: &PKeyRef<Private>,
+    authority: Option<&X509Ref>,
 ) -> SslResult<()> {
     ctx.set_min_proto_version(Some(SslVersion::TLS1_3))?;
 
@@ -359,7 +364,25 @@ fn set_context_options(
     // no certificate and there will be no error from OpenSSL. For this reason, we pass set `PEER`
     // (causing the request of a cert), but pass all of them through and verify them after the
     // handshake has completed.
-    ctx.set_verify_callback(SslVerifyMode::PEER, |_, _| true);
+    //
+    // With an authority, i.e. in a permissioned network, the handshake fails instead unless the
+    // peer presents a certificate signed by the authority. Like `validate_cert_with_authority`,
+    // only the peer's own certificate is checked, regardless of any chain it sends along.
+    match authority {
+        Some(authority) => {
+            let authority_key = authority.public_key()?;
+            ctx.set_verify_callback(
+                SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+                move |_, store_ctx| {
+                    store_ctx.error_depth() != 0
+                        || store_ctx
+                            .current_cert()
+                            .map_or(false, |cert| cert.verify(&authority_key).unwrap_or(false))
+                },
+            );
+        }
+        None => ctx.set_verify_callback(SslVerifyMode::PEER, |_, _| true),
+    }
 
     Ok(())
 }
@@ -842,6 +865,12 @@ impl Hash for Sha512 {
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
     use super::*;
 
     #[test]
@@ -903,6 +932,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_only_accept_peers_signed_by_authority() {
+        let (ca_cert, ca_private_key) =
+            generate_node_cert().expect("failed to generate key, cert pair");
+        let ca_signed_pair = || {
+            let private_key = generate_private_key().expect("failed to generate private key");
+            let cert = make_ca_signed_cert(private_key.clone(), ca_private_key.clone());
+            (cert, private_key)
+        };
+        let server = ca_signed_pair();
+
+        assert!(handshake_with_authority(
+            &server,
+            ca_signed_pair(),
+            &ca_cert
+        ));
+
+        let self_signed = generate_node_cert().expect("failed to generate key, cert pair");
+        assert!(!handshake_with_authority(&server, self_signed, &ca_cert));
+    }
+
+    /// Performs a TLS handshake between a server and a client both requiring certificates signed
+    /// by `authority`, and returns whether the server accepted the client.
+    fn handshake_with_authority(
+        (server_cert, server_key): &(X509, PKey<Private>),
+        (client_cert, client_key): (X509, PKey<Private>),
+        authority: &X509,
+    ) -> bool {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client_authority = authority.clone();
+        let client = thread::spawn(move || {
+            let mut config =
+                create_tls_connector(&client_cert, &client_key, Some(&client_authority))
+                    .and_then(|connector| connector.configure())
+                    .unwrap();
+            config.set_verify_hostname(false);
+            let stream = TcpStream::connect(addr).unwrap();
+            // Wait for the server to either send a byte or close the connection.
+            if let Ok(mut tls_stream) =
+                config.connect("this-will-not-be-checked.example.com", stream)
+            {
+                let _ = tls_stream.read(&mut [0]);
+            }
+        });
+
+        let acceptor = create_tls_acceptor(server_cert, server_key, Some(authority)).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let accepted = match acceptor.accept(stream) {
+            Ok(mut tls_stream) => tls_stream.write_all(&[1]).is_ok(),
+            Err(_) => false,
+        };
+        client.join().unwrap();
+        accepted
+    }
+
     fn make_ca_signed_cert(private_key: PKey<Private>, ca_private_key: PKey<Private>) -> X509 {
         let mut builder = X509Builder::new().unwrap();
         builder.set_version(2).unwrap();
@@ -15,6 +15,7 @@ mod node_config;
 mod node_id;
 /// Peers map.
 pub mod peers_map;
+mod signed_era_validators;
 mod status_feed;
 mod sync_leap;
 pub(crate) mod sync_leap_validation_metadata;
@@ -32,7 +33,7 @@ pub(crate) use block::{
     MetaBlockState,
 };
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
+    json_compatibility::{JsonBlock, JsonBlockHeader, JsonProof},
     Block, BlockAndDeploys, BlockBody, BlockExecutionResultsOrChunk,
     BlockExecutionResultsOrChunkId, BlockExecutionResultsOrChunkIdDisplay, BlockHash, BlockHeader,
     BlockSignatures, FinalitySignature, FinalizedBlock,
@@ -53,9 +54,10 @@ pub(crate) use deploy::{
 pub use error::BlockValidationError;
 pub use exit_code::ExitCode;
 pub(crate) use max_ttl::MaxTtl;
-pub use node_config::{NodeConfig, SyncHandling};
+pub use node_config::{NodeConfig, NodeMode, SyncHandling, TrustedHashDiscoveryConfig};
 pub(crate) use node_id::NodeId;
 pub use peers_map::PeersMap;
+pub(crate) use signed_era_validators::SignedEraValidators;
 pub use status_feed::{ChainspecInfo, GetStatusResult, StatusFeed};
 pub(crate) use sync_leap::{GlobalStatesMetadata, SyncLeap, SyncLeapIdentifier};
 pub(crate) use validator_matrix::{EraValidatorWeights, SignatureWeight, ValidatorMatrix};
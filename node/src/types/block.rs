@@ -28,7 +28,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::error;
 
-use casper_hashing::{ChunkWithProofVerificationError, Digest};
+use casper_hashing::{ChunkWithProofVerificationError, Digest, IndexedMerkleProof};
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     crypto, EraId, ProtocolVersion, PublicKey, SecretKey, Signature, Timestamp, U512,
@@ -1250,6 +1250,33 @@ impl BlockBody {
             .chain(self.transfer_hashes().iter())
     }
 
+    /// Computes the Merkle root of the deploy and transfer hashes, in the order in which they were
+    /// executed.
+    ///
+    /// The root is not part of the block header, which only commits to the hash of the whole
+    /// serialized body.
+    pub(crate) fn deploy_merkle_root(&self) -> Digest {
+        Digest::hash_merkle_tree(self.deploy_merkle_leaves())
+    }
+
+    /// Returns a Merkle proof of the inclusion of the given deploy or transfer against
+    /// `deploy_merkle_root`, or `None` if it is not in this block.
+    pub(crate) fn deploy_inclusion_proof(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Option<IndexedMerkleProof> {
+        let index = self
+            .deploy_and_transfer_hashes()
+            .position(|hash| hash == deploy_hash)?;
+        IndexedMerkleProof::new(self.deploy_merkle_leaves(), index as u64).ok()
+    }
+
+    fn deploy_merkle_leaves(&self) -> Vec<Digest> {
+        self.deploy_and_transfer_hashes()
+            .map(|deploy_hash| *deploy_hash.inner())
+            .collect()
+    }
+
     /// Computes the body hash by hashing the serialized bytes.
     pub fn hash(&self) -> Digest {
         *self.hash.get_or_init(|| {
@@ -2725,6 +2752,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn deploy_inclusion_proof_should_match_merkle_root() {
+        let mut rng = TestRng::new();
+
+        let deploy_hashes: Vec<DeployHash> = (0..5).map(|_| DeployHash::random(&mut rng)).collect();
+        let transfer_hashes: Vec<DeployHash> =
+            (0..8).map(|_| DeployHash::random(&mut rng)).collect();
+        let body = BlockBody::new(
+            PublicKey::random(&mut rng),
+            deploy_hashes.clone(),
+            transfer_hashes.clone(),
+        );
+        let merkle_root = body.deploy_merkle_root();
+
+        for (index, deploy_hash) in deploy_hashes.iter().chain(&transfer_hashes).enumerate() {
+            let proof = body
+                .deploy_inclusion_proof(deploy_hash)
+                .expect("should have proof");
+            proof.verify().expect("proof should be well formed");
+            assert_eq!(proof.index(), index as u64);
+            assert_eq!(proof.merkle_proof()[0], *deploy_hash.inner());
+            assert_eq!(proof.root_hash(), merkle_root);
+        }
+
+        assert!(body
+            .deploy_inclusion_proof(&DeployHash::random(&mut rng))
+            .is_none());
+    }
+
     #[test]
     fn block_check_bad_block_hash_sad_path() {
         let mut rng = TestRng::new();
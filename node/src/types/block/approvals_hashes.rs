@@ -52,7 +52,7 @@ impl ApprovalsHashes {
         }
     }
 
-    fn verify(&self, block: &Block) -> Result<(), ApprovalsHashesValidationError> {
+    pub(crate) fn verify(&self, block: &Block) -> Result<(), ApprovalsHashesValidationError> {
         if *self.merkle_proof_approvals.key() != Key::ChecksumRegistry {
             return Err(ApprovalsHashesValidationError::InvalidKeyType);
         }
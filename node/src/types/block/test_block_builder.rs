@@ -21,6 +21,7 @@ pub(crate) struct TestBlockBuilder {
     protocol_version: ProtocolVersion,
     deploys: Vec<Deploy>,
     is_switch: Option<bool>,
+    next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
 }
 
 impl TestBlockBuilder {
@@ -35,6 +36,7 @@ impl TestBlockBuilder {
             state_root_hash: None,
             parent_hash: None,
             timestamp: None,
+            next_era_validator_weights: None,
         }
     }
 
@@ -92,6 +94,16 @@ impl TestBlockBuilder {
         self
     }
 
+    /// Sets the validator weights of the next era; only used if the block is a switch block.
+    #[allow(unused)]
+    pub(crate) fn next_era_validator_weights(
+        mut self,
+        next_era_validator_weights: BTreeMap<PublicKey, U512>,
+    ) -> Self {
+        self.next_era_validator_weights = Some(next_era_validator_weights);
+        self
+    }
+
     #[allow(unused)]
     pub(crate) fn build(self, rng: &mut TestRng) -> Block {
         let state_root_hash = if let Some(root_hash) = self.state_root_hash {
@@ -191,7 +203,7 @@ impl TestBlockBuilder {
         let parent_seed = rng.gen::<[u8; Digest::LENGTH]>().into();
         let next_era_validator_weights = finalized_block
             .era_report()
-            .map(|_| BTreeMap::<PublicKey, U512>::default());
+            .map(|_| self.next_era_validator_weights.unwrap_or_default());
 
         Block::new(
             parent_hash,
@@ -137,11 +137,49 @@ impl Chainspec {
             }
         }
 
+        if !self.is_global_state_import_valid() {
+            return false;
+        }
+
         self.protocol_config.is_valid()
             && self.core_config.is_valid()
             && self.deploy_config.is_valid()
     }
 
+    /// Checks a global state update which is imported at genesis rather than applied at an
+    /// upgrade.
+    ///
+    /// All its entries must be valid stored values, and if it specifies validators, they must be
+    /// the genesis validators with the same weights, as they will be used for the first era.
+    fn is_global_state_import_valid(&self) -> bool {
+        if self
+            .protocol_config
+            .activation_point
+            .genesis_timestamp()
+            .is_none()
+        {
+            return true;
+        }
+        let global_state_update = match &self.protocol_config.global_state_update {
+            Some(global_state_update) => global_state_update,
+            None => return true,
+        };
+        if let Err(error) = self.protocol_config.get_update_mapping() {
+            error!(%error, "global state imported at genesis contains an invalid stored value");
+            return false;
+        }
+        if let Some(validators) = &global_state_update.validators {
+            if *validators != self.network_config.accounts_config.validator_weights() {
+                error!(
+                    "validators of the global state imported at genesis don't match the genesis \
+                    validators in the accounts config"
+                );
+                return false;
+            }
+        }
+        true
+    }
+
     /// Serializes `self` and hashes the resulting bytes.
     pub fn hash(&self) -> Digest {
         let serialized_chainspec = self.to_bytes().unwrap_or_else(|error| {
@@ -301,7 +339,7 @@ impl From<&Chainspec> for ExecConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{collections::BTreeMap, fs, iter};
 
     use num_rational::Ratio;
     use once_cell::sync::Lazy;
@@ -312,7 +350,10 @@ mod tests {
         storage_costs::StorageCosts,
         wasm_config::WasmConfig,
     };
-    use casper_types::{EraId, Motes, ProtocolVersion, StoredValue, TimeDiff, Timestamp, U512};
+    use casper_types::{
+        bytesrepr::Bytes, EraId, Key, Motes, ProtocolVersion, StoredValue, TimeDiff, Timestamp,
+        U512,
+    };
 
     use super::*;
     use crate::{testing::init_logging, utils::RESOURCES_PATH};
@@ -532,6 +573,39 @@ mod tests {
         assert!(chainspec.is_valid());
     }
 
+    #[test]
+    fn should_validate_global_state_imported_at_genesis() {
+        let (mut chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        chainspec.protocol_config.activation_point = ActivationPoint::Genesis(Timestamp::now());
+        let genesis_validators = chainspec.network_config.accounts_config.validator_weights();
+        assert!(!genesis_validators.is_empty());
+
+        // Validators matching the genesis validators.
+        chainspec.protocol_config.global_state_update = Some(GlobalStateUpdate {
+            validators: Some(genesis_validators.clone()),
+            entries: BTreeMap::new(),
+        });
+        assert!(chainspec.is_valid());
+
+        // Validators with different weights.
+        let mut validators = genesis_validators;
+        validators
+            .values_mut()
+            .for_each(|weight| *weight += U512::one());
+        chainspec.protocol_config.global_state_update = Some(GlobalStateUpdate {
+            validators: Some(validators),
+            entries: BTreeMap::new(),
+        });
+        assert!(!chainspec.is_valid());
+
+        // An entry which isn't a valid stored value.
+        chainspec.protocol_config.global_state_update = Some(GlobalStateUpdate {
+            validators: None,
+            entries: iter::once((Key::Hash([1; 32]), Bytes::from(vec![255]))).collect(),
+        });
+        assert!(!chainspec.is_valid());
+    }
+
     #[ignore = "We probably need to reconsider our approach here"]
     #[test]
     fn should_have_deterministic_chainspec_hash() {
@@ -4,9 +4,14 @@ mod account_config;
 mod delegator_config;
 mod validator_config;
 
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    str,
+};
 
 use datasize::DataSize;
+use num::Zero;
 use serde::{Deserialize, Deserializer, Serialize};
 
 use casper_execution_engine::core::engine_state::{genesis::AdministratorAccount, GenesisAccount};
@@ -14,7 +19,7 @@ use casper_execution_engine::core::engine_state::{genesis::AdministratorAccount,
 use casper_types::testing::TestRng;
 use casper_types::{
     bytesrepr::{self, Bytes, FromBytes, ToBytes},
-    file_utils, PublicKey,
+    file_utils, AsymmetricType, Motes, PublicKey, U512,
 };
 
 use super::error::ChainspecAccountsLoadError;
@@ -23,6 +28,7 @@ pub use delegator_config::DelegatorConfig;
 pub use validator_config::ValidatorConfig;
 
 const CHAINSPEC_ACCOUNTS_FILENAME: &str = "accounts.toml";
+const CHAINSPEC_VALIDATOR_STAKES_FILENAME: &str = "validator_stakes.csv";
 
 fn sorted_vec_deserializer<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
@@ -92,21 +98,101 @@ impl AccountsConfig {
         }
     }
 
-    /// Returns `Self` and the raw bytes of the file.
+    /// Returns the weights of the genesis validators, i.e. their bonded amounts plus all amounts
+    /// delegated to them.
+    pub(crate) fn validator_weights(&self) -> BTreeMap<PublicKey, U512> {
+        let mut weights: BTreeMap<PublicKey, U512> = self
+            .accounts
+            .iter()
+            .filter(|account| account.is_genesis_validator())
+            .map(|account| (account.public_key(), account.bonded_amount().value()))
+            .collect();
+        for delegator in &self.delegators {
+            if let Some(weight) = weights.get_mut(&delegator.validator_public_key) {
+                *weight = weight.saturating_add(delegator.delegated_amount.value());
+            }
+        }
+        weights
+    }
+
+    /// Returns `Self` and the raw bytes of the accounts file.
+    ///
+    /// If the accounts file doesn't exist, the accounts are empty and `None` bytes are returned.
     ///
-    /// If the file doesn't exist, returns `Ok` with an empty `AccountsConfig` and `None` bytes.
+    /// If a validator stakes file exists alongside it, each of its records is added as a genesis
+    /// validator account.  The combined config is validated before being returned.
     pub(super) fn from_dir<P: AsRef<Path>>(
         dir_path: P,
     ) -> Result<(Self, Option<Bytes>), ChainspecAccountsLoadError> {
         let accounts_path = dir_path.as_ref().join(CHAINSPEC_ACCOUNTS_FILENAME);
-        if !accounts_path.is_file() {
-            let config = AccountsConfig::new(vec![], vec![], vec![]);
-            let maybe_bytes = None;
-            return Ok((config, maybe_bytes));
+        let (mut config, maybe_bytes) = if accounts_path.is_file() {
+            let bytes = file_utils::read_file(accounts_path)?;
+            let config: AccountsConfig = toml::from_slice(&bytes)?;
+            (config, Some(Bytes::from(bytes)))
+        } else {
+            (AccountsConfig::new(vec![], vec![], vec![]), None)
+        };
+
+        let validator_stakes_path = dir_path.as_ref().join(CHAINSPEC_VALIDATOR_STAKES_FILENAME);
+        if validator_stakes_path.is_file() {
+            let bytes = file_utils::read_file(validator_stakes_path)?;
+            config.accounts.extend(parse_validator_stakes(&bytes)?);
+            config.accounts.sort_unstable();
         }
-        let bytes = file_utils::read_file(accounts_path)?;
-        let config: AccountsConfig = toml::from_slice(&bytes)?;
-        Ok((config, Some(Bytes::from(bytes))))
+
+        config.validate()?;
+        Ok((config, maybe_bytes))
+    }
+
+    /// Checks that no account or delegation is specified twice, that every delegator delegates to
+    /// a genesis validator, and that the total stake is non-zero and doesn't overflow.
+    fn validate(&self) -> Result<(), ChainspecAccountsLoadError> {
+        let mut public_keys = BTreeSet::new();
+        let mut total_stake = Motes::zero();
+        for account in &self.accounts {
+            if !public_keys.insert(&account.public_key) {
+                return Err(ChainspecAccountsLoadError::DuplicateAccount(Box::new(
+                    account.public_key.clone(),
+                )));
+            }
+            if let Some(validator) = account.validator {
+                if validator.bonded_amount().value().is_zero() {
+                    return Err(ChainspecAccountsLoadError::ZeroBondedAmount(Box::new(
+                        account.public_key.clone(),
+                    )));
+                }
+                total_stake = total_stake
+                    .checked_add(validator.bonded_amount())
+                    .ok_or(ChainspecAccountsLoadError::StakeOverflow)?;
+            }
+        }
+
+        let mut delegations = BTreeSet::new();
+        for delegator in &self.delegators {
+            if !self.is_genesis_validator(&delegator.validator_public_key) {
+                return Err(ChainspecAccountsLoadError::DelegatorWithoutValidator {
+                    delegator: Box::new(delegator.delegator_public_key.clone()),
+                    validator: Box::new(delegator.validator_public_key.clone()),
+                });
+            }
+            if !delegations.insert((
+                &delegator.validator_public_key,
+                &delegator.delegator_public_key,
+            )) {
+                return Err(ChainspecAccountsLoadError::DuplicateDelegator {
+                    delegator: Box::new(delegator.delegator_public_key.clone()),
+                    validator: Box::new(delegator.validator_public_key.clone()),
+                });
+            }
+            total_stake = total_stake
+                .checked_add(delegator.delegated_amount)
+                .ok_or(ChainspecAccountsLoadError::StakeOverflow)?;
+        }
+
+        if !self.accounts.is_empty() && total_stake.value().is_zero() {
+            return Err(ChainspecAccountsLoadError::NoStake);
+        }
+        Ok(())
     }
 
     #[cfg(test)]
@@ -142,6 +228,65 @@ impl AccountsConfig {
     }
 }
 
+/// Parses the contents of a validator stakes file into genesis validator accounts.
+///
+/// Each non-empty line which doesn't start with `#` is a record of the form
+/// `public_key,balance,bonded_amount[,delegation_rate]`, with the public key in hex and the
+/// amounts in motes.  The delegation rate defaults to zero.
+fn parse_validator_stakes(bytes: &[u8]) -> Result<Vec<AccountConfig>, ChainspecAccountsLoadError> {
+    let contents = str::from_utf8(bytes).map_err(|error| {
+        ChainspecAccountsLoadError::InvalidValidatorStakesRecord {
+            line: 0,
+            reason: error.to_string(),
+        }
+    })?;
+
+    let mut accounts = vec![];
+    for (index, record) in contents.lines().enumerate() {
+        let record = record.trim();
+        if record.is_empty() || record.starts_with('#') {
+            continue;
+        }
+        let invalid_record =
+            |reason: String| ChainspecAccountsLoadError::InvalidValidatorStakesRecord {
+                line: index + 1,
+                reason,
+            };
+        let fields: Vec<&str> = record.split(',').map(str::trim).collect();
+        let (public_key, balance, bonded_amount, delegation_rate) = match fields.as_slice() {
+            [public_key, balance, bonded_amount] => (public_key, balance, bonded_amount, None),
+            [public_key, balance, bonded_amount, delegation_rate] => {
+                (public_key, balance, bonded_amount, Some(delegation_rate))
+            }
+            _ => {
+                return Err(invalid_record(format!(
+                    "expected 3 or 4 fields, found {}",
+                    fields.len()
+                )))
+            }
+        };
+        let public_key = PublicKey::from_hex(public_key)
+            .map_err(|error| invalid_record(format!("invalid public key: {}", error)))?;
+        let balance = U512::from_dec_str(balance)
+            .map_err(|error| invalid_record(format!("invalid balance: {}", error)))?;
+        let bonded_amount = U512::from_dec_str(bonded_amount)
+            .map_err(|error| invalid_record(format!("invalid bonded amount: {}", error)))?;
+        let delegation_rate = match delegation_rate {
+            Some(delegation_rate) => delegation_rate
+                .parse()
+                .map_err(|error| invalid_record(format!("invalid delegation rate: {}", error)))?,
+            None => 0,
+        };
+        let validator = ValidatorConfig::new(Motes::new(bonded_amount), delegation_rate);
+        accounts.push(AccountConfig::new(
+            public_key,
+            Motes::new(balance),
+            Some(validator),
+        ));
+    }
+    Ok(accounts)
+}
+
 impl ToBytes for AccountsConfig {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut buffer = bytesrepr::allocate_buffer(self)?;
@@ -199,4 +344,90 @@ mod tests {
         let accounts_config = AccountsConfig::random(&mut rng);
         bytesrepr::test_serialization_roundtrip(&accounts_config);
     }
+
+    #[test]
+    fn should_parse_validator_stakes() {
+        let mut rng = TestRng::new();
+        let alice = PublicKey::random(&mut rng);
+        let bob = PublicKey::random(&mut rng);
+        let contents = format!(
+            "# public_key,balance,bonded_amount,delegation_rate\n{},100,10\n\n{}, 200, 20, 5\n",
+            alice.to_hex(),
+            bob.to_hex()
+        );
+
+        let accounts = parse_validator_stakes(contents.as_bytes()).unwrap();
+        assert_eq!(
+            accounts,
+            vec![
+                AccountConfig::new(
+                    alice,
+                    Motes::new(U512::from(100)),
+                    Some(ValidatorConfig::new(Motes::new(U512::from(10)), 0))
+                ),
+                AccountConfig::new(
+                    bob,
+                    Motes::new(U512::from(200)),
+                    Some(ValidatorConfig::new(Motes::new(U512::from(20)), 5))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_validator_stakes_record() {
+        let mut rng = TestRng::new();
+        let contents = format!("{},100\n", PublicKey::random(&mut rng).to_hex());
+        assert!(matches!(
+            parse_validator_stakes(contents.as_bytes()),
+            Err(ChainspecAccountsLoadError::InvalidValidatorStakesRecord { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_duplicate_accounts() {
+        let mut rng = TestRng::new();
+        let mut account = AccountConfig::random(&mut rng);
+        account.validator = Some(ValidatorConfig::new(Motes::new(U512::one()), 0));
+        let accounts_config = AccountsConfig::new(vec![account.clone(), account], vec![], vec![]);
+        assert!(matches!(
+            accounts_config.validate(),
+            Err(ChainspecAccountsLoadError::DuplicateAccount(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_invalid_delegators() {
+        let mut rng = TestRng::new();
+        let mut validator = AccountConfig::random(&mut rng);
+        validator.validator = Some(ValidatorConfig::new(Motes::new(U512::one()), 0));
+        let mut delegator = DelegatorConfig::random(&mut rng);
+
+        let accounts_config =
+            AccountsConfig::new(vec![validator.clone()], vec![delegator.clone()], vec![]);
+        assert!(matches!(
+            accounts_config.validate(),
+            Err(ChainspecAccountsLoadError::DelegatorWithoutValidator { .. })
+        ));
+
+        delegator.validator_public_key = validator.public_key.clone();
+        let accounts_config =
+            AccountsConfig::new(vec![validator], vec![delegator.clone(), delegator], vec![]);
+        assert!(matches!(
+            accounts_config.validate(),
+            Err(ChainspecAccountsLoadError::DuplicateDelegator { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_accounts_without_stake() {
+        let mut rng = TestRng::new();
+        let mut account = AccountConfig::random(&mut rng);
+        account.validator = None;
+        let accounts_config = AccountsConfig::new(vec![account], vec![], vec![]);
+        assert!(matches!(
+            accounts_config.validate(),
+            Err(ChainspecAccountsLoadError::NoStake)
+        ));
+    }
 }
@@ -107,6 +107,14 @@ pub struct CoreConfig {
     /// Administrative accounts are valid option for a private chain only.
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub(crate) administrators: BTreeSet<PublicKey>,
+    /// The maximum tolerated offset of a validator's clock from the other validators' clocks.
+    /// Validators whose clocks are further off don't propose blocks.  Zero disables the check.
+    #[serde(default)]
+    pub(crate) max_clock_drift: TimeDiff,
+    /// Protocol version from which contracts can call the `casper_get_era_id` host function.  If
+    /// unset, the host function is not available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) start_protocol_version_with_get_era_id: Option<ProtocolVersion>,
     /// Refund handling.
     #[data_size(skip)]
     pub(crate) refund_handling: RefundHandling,
@@ -209,6 +217,10 @@ impl CoreConfig {
         } else {
             FeeHandling::Accumulate
         };
+        let max_clock_drift = TimeDiff::from_millis(rng.gen_range(0..60_000));
+        let start_protocol_version_with_get_era_id = rng
+            .gen::<bool>()
+            .then(|| ProtocolVersion::from_parts(1, rng.gen_range(6..10), rng.gen_range(0..100)));
 
         CoreConfig {
             era_duration,
@@ -237,6 +249,8 @@ impl CoreConfig {
             compute_rewards,
             refund_handling,
             fee_handling,
+            max_clock_drift,
+            start_protocol_version_with_get_era_id,
         }
     }
 }
@@ -273,6 +287,8 @@ impl ToBytes for CoreConfig {
         buffer.extend(self.administrators.to_bytes()?);
         buffer.extend(self.refund_handling.to_bytes()?);
         buffer.extend(self.fee_handling.to_bytes()?);
+        buffer.extend(self.max_clock_drift.to_bytes()?);
+        buffer.extend(self.start_protocol_version_with_get_era_id.to_bytes()?);
         Ok(buffer)
     }
 
@@ -305,6 +321,10 @@ impl ToBytes for CoreConfig {
             + self.administrators.serialized_length()
             + self.refund_handling.serialized_length()
             + self.fee_handling.serialized_length()
+            + self.max_clock_drift.serialized_length()
+            + self
+                .start_protocol_version_with_get_era_id
+                .serialized_length()
     }
 }
 
@@ -337,6 +357,8 @@ impl FromBytes for CoreConfig {
         let (administrative_accounts, remainder) = FromBytes::from_bytes(remainder)?;
         let (refund_handling, remainder) = FromBytes::from_bytes(remainder)?;
         let (fee_handling, remainder) = FromBytes::from_bytes(remainder)?;
+        let (max_clock_drift, remainder) = TimeDiff::from_bytes(remainder)?;
+        let (start_protocol_version_with_get_era_id, remainder) = FromBytes::from_bytes(remainder)?;
         let config = CoreConfig {
             era_duration,
             minimum_era_height,
@@ -364,6 +386,8 @@ impl FromBytes for CoreConfig {
             administrators: administrative_accounts,
             refund_handling,
             fee_handling,
+            max_clock_drift,
+            start_protocol_version_with_get_era_id,
         };
         Ok((config, remainder))
     }
@@ -14,7 +14,7 @@ use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
 use casper_types::testing::TestRng;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    Motes, TimeDiff, U512,
+    EraId, Motes, TimeDiff, U512,
 };
 
 /// Configuration values associated with deploys.
@@ -33,8 +33,14 @@ pub struct DeployConfig {
     pub(crate) block_gas_limit: u64,
     pub(crate) payment_args_max_length: u32,
     pub(crate) session_args_max_length: u32,
+    #[serde(default)]
+    pub(crate) payment_module_bytes_max_length: u32,
+    #[serde(default)]
+    pub(crate) session_module_bytes_max_length: u32,
     pub(crate) native_transfer_minimum_motes: u64,
     pub(crate) max_timestamp_leeway: TimeDiff,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dependencies_activation_era: Option<EraId>,
 }
 
 impl DeployConfig {
@@ -51,6 +57,13 @@ impl DeployConfig {
             false
         }
     }
+
+    /// Returns `true` if the deploys in a block of the given era may only depend on deploys which
+    /// have already been executed.
+    pub(crate) fn dependencies_enforced_in(&self, era_id: EraId) -> bool {
+        self.dependencies_activation_era
+            .map_or(false, |activation_era| era_id >= activation_era)
+    }
 }
 
 #[cfg(test)]
@@ -68,9 +81,13 @@ impl DeployConfig {
         let block_gas_limit = rng.gen_range(100_000_000_000..1_000_000_000_000_000);
         let payment_args_max_length = rng.gen();
         let session_args_max_length = rng.gen();
+        let payment_module_bytes_max_length = rng.gen();
+        let session_module_bytes_max_length = rng.gen();
         let native_transfer_minimum_motes =
             rng.gen_range(MAX_PAYMENT_AMOUNT..1_000_000_000_000_000);
         let max_timestamp_leeway = TimeDiff::from_seconds(rng.gen_range(0..6));
+        let dependencies_activation_era =
+            rng.gen::<bool>().then(|| EraId::new(rng.gen_range(0..100)));
 
         DeployConfig {
             max_payment_cost,
@@ -84,8 +101,11 @@ impl DeployConfig {
             block_gas_limit,
             payment_args_max_length,
             session_args_max_length,
+            payment_module_bytes_max_length,
+            session_module_bytes_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            dependencies_activation_era,
         }
     }
 }
@@ -105,8 +125,11 @@ impl Default for DeployConfig {
             block_gas_limit: 10_000_000_000_000,
             payment_args_max_length: 1024,
             session_args_max_length: 1024,
+            payment_module_bytes_max_length: 0,
+            session_module_bytes_max_length: 0,
             native_transfer_minimum_motes: MAX_PAYMENT_AMOUNT,
             max_timestamp_leeway: TimeDiff::from_str("5sec").unwrap(),
+            dependencies_activation_era: None,
         }
     }
 }
@@ -125,8 +148,11 @@ impl ToBytes for DeployConfig {
         buffer.extend(self.block_gas_limit.to_bytes()?);
         buffer.extend(self.payment_args_max_length.to_bytes()?);
         buffer.extend(self.session_args_max_length.to_bytes()?);
+        buffer.extend(self.payment_module_bytes_max_length.to_bytes()?);
+        buffer.extend(self.session_module_bytes_max_length.to_bytes()?);
         buffer.extend(self.native_transfer_minimum_motes.to_bytes()?);
         buffer.extend(self.max_timestamp_leeway.to_bytes()?);
+        buffer.extend(self.dependencies_activation_era.to_bytes()?);
         Ok(buffer)
     }
 
@@ -142,8 +168,11 @@ impl ToBytes for DeployConfig {
             + self.block_gas_limit.serialized_length()
             + self.payment_args_max_length.serialized_length()
             + self.session_args_max_length.serialized_length()
+            + self.payment_module_bytes_max_length.serialized_length()
+            + self.session_module_bytes_max_length.serialized_length()
             + self.native_transfer_minimum_motes.serialized_length()
             + self.max_timestamp_leeway.serialized_length()
+            + self.dependencies_activation_era.serialized_length()
     }
 }
 
@@ -161,8 +190,11 @@ impl FromBytes for DeployConfig {
         let (block_gas_limit, remainder) = u64::from_bytes(remainder)?;
         let (payment_args_max_length, remainder) = u32::from_bytes(remainder)?;
         let (session_args_max_length, remainder) = u32::from_bytes(remainder)?;
+        let (payment_module_bytes_max_length, remainder) = u32::from_bytes(remainder)?;
+        let (session_module_bytes_max_length, remainder) = u32::from_bytes(remainder)?;
         let (native_transfer_minimum_motes, remainder) = u64::from_bytes(remainder)?;
         let (max_timestamp_leeway, remainder) = TimeDiff::from_bytes(remainder)?;
+        let (dependencies_activation_era, remainder) = Option::<EraId>::from_bytes(remainder)?;
         let config = DeployConfig {
             max_payment_cost,
             max_ttl,
@@ -175,8 +207,11 @@ impl FromBytes for DeployConfig {
             block_gas_limit,
             payment_args_max_length,
             session_args_max_length,
+            payment_module_bytes_max_length,
+            session_module_bytes_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            dependencies_activation_era,
         };
         Ok((config, remainder))
     }
@@ -228,4 +263,17 @@ mod tests {
         };
         assert!(config.is_valid());
     }
+
+    #[test]
+    fn should_enforce_dependencies_only_from_activation_era() {
+        let config = DeployConfig::default();
+        assert!(!config.dependencies_enforced_in(EraId::new(u64::MAX)));
+
+        let config = DeployConfig {
+            dependencies_activation_era: Some(EraId::new(5)),
+            ..Default::default()
+        };
+        assert!(!config.dependencies_enforced_in(EraId::new(4)));
+        assert!(config.dependencies_enforced_in(EraId::new(5)));
+    }
 }
@@ -1,7 +1,7 @@
 use thiserror::Error;
 use uint::FromDecStrErr;
 
-use casper_types::file_utils::ReadFileError;
+use casper_types::{file_utils::ReadFileError, PublicKey};
 
 /// Error returned when loading the chainspec.
 #[derive(Debug, Error)]
@@ -49,6 +49,49 @@ pub enum ChainspecAccountsLoadError {
     /// Error while decoding a chainspec account's key hash from base-64 format.
     #[error("crypto module error: {0}")]
     Crypto(#[from] casper_types::crypto::ErrorExt),
+
+    /// Error while parsing a record of the validator stakes file.
+    #[error("invalid validator stakes record on line {line}: {reason}")]
+    InvalidValidatorStakesRecord {
+        /// The line of the invalid record.
+        line: usize,
+        /// The reason the record is invalid.
+        reason: String,
+    },
+
+    /// The same account is specified more than once.
+    #[error("duplicate account {0}")]
+    DuplicateAccount(Box<PublicKey>),
+
+    /// The same delegation is specified more than once.
+    #[error("duplicate delegation from {delegator} to {validator}")]
+    DuplicateDelegator {
+        /// The delegator's public key.
+        delegator: Box<PublicKey>,
+        /// The validator's public key.
+        validator: Box<PublicKey>,
+    },
+
+    /// A delegator delegates to an account which isn't a genesis validator.
+    #[error("delegator {delegator} delegates to {validator}, which is not a genesis validator")]
+    DelegatorWithoutValidator {
+        /// The delegator's public key.
+        delegator: Box<PublicKey>,
+        /// The validator's public key.
+        validator: Box<PublicKey>,
+    },
+
+    /// A genesis validator has a bonded amount of zero.
+    #[error("genesis validator {0} has a bonded amount of zero")]
+    ZeroBondedAmount(Box<PublicKey>),
+
+    /// The sum of all bonded and delegated amounts overflows.
+    #[error("total genesis stake overflows")]
+    StakeOverflow,
+
+    /// There are genesis accounts, but no stake at all.
+    #[error("genesis accounts specify no stake")]
+    NoStake,
 }
 
 /// Error loading global state update file.
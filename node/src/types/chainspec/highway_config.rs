@@ -9,7 +9,7 @@ use tracing::error;
 use casper_types::testing::TestRng;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    TimeDiff,
+    EraId, TimeDiff,
 };
 
 /// Configuration values relevant to the `PerformanceMeter`, a component of Highway deciding when
@@ -33,10 +33,23 @@ pub struct HighwayConfig {
     /// quorum, i.e. no finality.
     #[data_size(skip)]
     pub reduced_reward_multiplier: Ratio<u64>,
+    /// Validators who fail to propose a block in more than this fraction of the rounds they are
+    /// the leader of are reported as inactive at the end of the era, and get evicted. `0` disables
+    /// the check.
+    #[data_size(skip)]
+    #[serde(default = "default_max_missed_proposals")]
+    pub max_missed_proposals: Ratio<u64>,
+    /// The first era in which validators are checked for missed proposals.
+    #[serde(default)]
+    pub max_missed_proposals_activation_era: EraId,
     /// The configuration for the `PerformanceMeter`, controlling round exponent switching.
     pub performance_meter: PerformanceMeterConfig,
 }
 
+fn default_max_missed_proposals() -> Ratio<u64> {
+    Ratio::new(0, 1)
+}
+
 impl HighwayConfig {
     /// Checks whether the values set in the config make sense and returns `false` if they don't.
     pub fn is_valid(&self) -> bool {
@@ -48,8 +61,25 @@ impl HighwayConfig {
             return false;
         }
 
+        if self.max_missed_proposals > Ratio::new(1, 1) {
+            error!(
+                mmp = %self.max_missed_proposals,
+                "max missed proposals is not in the range [0, 1]",
+            );
+            return false;
+        }
+
         true
     }
+
+    /// Returns the fraction of missed proposals that causes validators to be reported as inactive
+    /// in the given era, or `0` if the check is disabled in that era.
+    pub(crate) fn max_missed_proposals_in(&self, era_id: EraId) -> Ratio<u64> {
+        if era_id < self.max_missed_proposals_activation_era {
+            return Ratio::new(0, 1);
+        }
+        self.max_missed_proposals
+    }
 }
 
 #[cfg(test)]
@@ -59,11 +89,15 @@ impl HighwayConfig {
         let maximum_round_length = TimeDiff::from_seconds(rng.gen_range(60..600));
         let reduced_reward_multiplier = Ratio::new(rng.gen_range(0..10), 10);
         let blocks_to_consider = rng.gen_range(5..=10);
+        let max_missed_proposals = Ratio::new(rng.gen_range(0..=10), 10);
+        let max_missed_proposals_activation_era = EraId::new(rng.gen_range(0..100));
 
         HighwayConfig {
             maximum_round_length,
             reduced_reward_multiplier,
             performance_meter: PerformanceMeterConfig { blocks_to_consider },
+            max_missed_proposals,
+            max_missed_proposals_activation_era,
         }
     }
 }
@@ -74,6 +108,8 @@ impl ToBytes for HighwayConfig {
         buffer.extend(self.maximum_round_length.to_bytes()?);
         buffer.extend(self.reduced_reward_multiplier.to_bytes()?);
         buffer.extend(self.performance_meter.blocks_to_consider.to_bytes()?);
+        buffer.extend(self.max_missed_proposals.to_bytes()?);
+        buffer.extend(self.max_missed_proposals_activation_era.to_bytes()?);
         Ok(buffer)
     }
 
@@ -84,6 +120,8 @@ impl ToBytes for HighwayConfig {
                 .performance_meter
                 .blocks_to_consider
                 .serialized_length()
+            + self.max_missed_proposals.serialized_length()
+            + self.max_missed_proposals_activation_era.serialized_length()
     }
 }
 
@@ -92,10 +130,14 @@ impl FromBytes for HighwayConfig {
         let (maximum_round_length, remainder) = TimeDiff::from_bytes(bytes)?;
         let (reduced_reward_multiplier, remainder) = Ratio::<u64>::from_bytes(remainder)?;
         let (blocks_to_consider, remainder) = u64::from_bytes(remainder)?;
+        let (max_missed_proposals, remainder) = Ratio::<u64>::from_bytes(remainder)?;
+        let (max_missed_proposals_activation_era, remainder) = EraId::from_bytes(remainder)?;
         let config = HighwayConfig {
             maximum_round_length,
             reduced_reward_multiplier,
             performance_meter: PerformanceMeterConfig { blocks_to_consider },
+            max_missed_proposals,
+            max_missed_proposals_activation_era,
         };
         Ok((config, remainder))
     }
@@ -138,4 +180,48 @@ mod tests {
         highway_config.reduced_reward_multiplier = Ratio::new(u64::MAX, u64::MAX - 1);
         assert!(!highway_config.is_valid());
     }
+
+    #[test]
+    fn should_validate_for_max_missed_proposals() {
+        let mut rng = crate::new_rng();
+        let mut highway_config = HighwayConfig::random(&mut rng);
+
+        highway_config.max_missed_proposals = Ratio::new(0, 1);
+        assert!(highway_config.is_valid());
+        highway_config.max_missed_proposals = Ratio::new(1, 1);
+        assert!(highway_config.is_valid());
+
+        highway_config.max_missed_proposals = Ratio::new(11, 10);
+        assert!(!highway_config.is_valid());
+    }
+
+    #[test]
+    fn should_check_missed_proposals_only_from_activation_era() {
+        let mut rng = crate::new_rng();
+        let mut highway_config = HighwayConfig::random(&mut rng);
+        highway_config.max_missed_proposals = Ratio::new(1, 2);
+        highway_config.max_missed_proposals_activation_era = EraId::new(5);
+
+        assert_eq!(
+            Ratio::new(0, 1),
+            highway_config.max_missed_proposals_in(EraId::new(4))
+        );
+        assert_eq!(
+            Ratio::new(1, 2),
+            highway_config.max_missed_proposals_in(EraId::new(5))
+        );
+    }
+
+    #[test]
+    fn should_default_to_not_checking_missed_proposals() {
+        let mut rng = crate::new_rng();
+        let highway_config = HighwayConfig::random(&mut rng);
+        let mut encoded: toml::value::Table =
+            toml::from_str(&toml::to_string_pretty(&highway_config).unwrap()).unwrap();
+        encoded.remove("max_missed_proposals");
+        encoded.remove("max_missed_proposals_activation_era");
+        let decoded: HighwayConfig = toml::Value::Table(encoded).try_into().unwrap();
+        assert_eq!(Ratio::new(0, 1), decoded.max_missed_proposals);
+        assert_eq!(EraId::new(0), decoded.max_missed_proposals_activation_era);
+    }
 }
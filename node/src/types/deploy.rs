@@ -42,6 +42,7 @@ use casper_types::bytesrepr::Bytes;
 #[cfg(any(feature = "testing", test))]
 use casper_types::testing::TestRng;
 use casper_types::{
+    account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes},
     crypto, runtime_args,
     system::standard_payment::ARG_AMOUNT,
@@ -142,12 +143,37 @@ impl Deploy {
         session: ExecutableDeployItem,
         secret_key: &SecretKey,
         account: Option<PublicKey>,
+    ) -> Deploy {
+        let account = account.unwrap_or_else(|| PublicKey::from(secret_key));
+        let mut deploy = Deploy::new_unsigned(
+            timestamp,
+            ttl,
+            gas_price,
+            dependencies,
+            chain_name,
+            payment,
+            session,
+            account,
+        );
+        deploy.sign(secret_key);
+        deploy
+    }
+
+    /// Constructs a new `Deploy` without approvals, to be signed elsewhere.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new_unsigned(
+        timestamp: Timestamp,
+        ttl: TimeDiff,
+        gas_price: u64,
+        dependencies: Vec<DeployHash>,
+        chain_name: String,
+        payment: ExecutableDeployItem,
+        session: ExecutableDeployItem,
+        account: PublicKey,
     ) -> Deploy {
         let serialized_body = serialize_body(&payment, &session);
         let body_hash = Digest::hash(serialized_body);
 
-        let account = account.unwrap_or_else(|| PublicKey::from(secret_key));
-
         // Remove duplicates.
         let dependencies = dependencies.into_iter().unique().collect();
         let header = DeployHeader::new(
@@ -162,17 +188,14 @@ impl Deploy {
         let serialized_header = serialize_header(&header);
         let hash = DeployHash::new(Digest::hash(serialized_header));
 
-        let mut deploy = Deploy {
+        Deploy {
             hash,
             header,
             payment,
             session,
             approvals: BTreeSet::new(),
             is_valid: OnceCell::new(),
-        };
-
-        deploy.sign(secret_key);
-        deploy
+        }
     }
 
     /// Adds a signature of this deploy's hash to its approvals.
@@ -216,9 +239,19 @@ impl Deploy {
         &self.approvals
     }
 
+    /// Returns the account hashes of the signers of this deploy's approvals.
+    pub(crate) fn authorization_keys(&self) -> BTreeSet<AccountHash> {
+        self.approvals
+            .iter()
+            .map(|approval| approval.signer().to_account_hash())
+            .collect()
+    }
+
     /// Replaces the set of approvals attached to this deploy.
     pub fn replace_approvals(&mut self, approvals: BTreeSet<Approval>) {
         self.approvals = approvals;
+        // The cached validity covers the approvals, so it must be determined afresh.
+        self.is_valid = OnceCell::new();
     }
 
     /// Returns the hash of this deploy wrapped in `DeployOrTransferHash`.
@@ -266,6 +299,80 @@ impl Deploy {
         Ok(())
     }
 
+    /// Returns Ok if and only if the deploy as a whole, its module bytes and its runtime args
+    /// don't exceed the size limits in the given config.
+    ///
+    /// Unlike the other checks in `is_config_compliant`, these don't depend on the time, so they
+    /// also apply to deploys in proposed blocks.
+    pub(crate) fn is_within_size_limits(
+        &self,
+        config: &DeployConfig,
+    ) -> Result<(), DeployConfigurationFailure> {
+        self.is_valid_size(config.max_deploy_size)?;
+
+        let payment_module_bytes_length = module_bytes_length(self.payment());
+        if config.payment_module_bytes_max_length != 0
+            && payment_module_bytes_length > config.payment_module_bytes_max_length as usize
+        {
+            debug!(
+                payment_module_bytes_length,
+                payment_module_bytes_max_length = config.payment_module_bytes_max_length,
+                "payment module bytes excessive"
+            );
+            return Err(
+                DeployConfigurationFailure::ExcessivePaymentModuleBytesLength {
+                    max_length: config.payment_module_bytes_max_length as usize,
+                    got: payment_module_bytes_length,
+                },
+            );
+        }
+
+        let session_module_bytes_length = module_bytes_length(self.session());
+        if config.session_module_bytes_max_length != 0
+            && session_module_bytes_length > config.session_module_bytes_max_length as usize
+        {
+            debug!(
+                session_module_bytes_length,
+                session_module_bytes_max_length = config.session_module_bytes_max_length,
+                "session module bytes excessive"
+            );
+            return Err(
+                DeployConfigurationFailure::ExcessiveSessionModuleBytesLength {
+                    max_length: config.session_module_bytes_max_length as usize,
+                    got: session_module_bytes_length,
+                },
+            );
+        }
+
+        let payment_args_length = self.payment().args().serialized_length();
+        if payment_args_length > config.payment_args_max_length as usize {
+            debug!(
+                payment_args_length,
+                payment_args_max_length = config.payment_args_max_length,
+                "payment args excessive"
+            );
+            return Err(DeployConfigurationFailure::ExcessivePaymentArgsLength {
+                max_length: config.payment_args_max_length as usize,
+                got: payment_args_length,
+            });
+        }
+
+        let session_args_length = self.session().args().serialized_length();
+        if session_args_length > config.session_args_max_length as usize {
+            debug!(
+                session_args_length,
+                session_args_max_length = config.session_args_max_length,
+                "session args excessive"
+            );
+            return Err(DeployConfigurationFailure::ExcessiveSessionArgsLength {
+                max_length: config.session_args_max_length as usize,
+                got: session_args_length,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Returns `Ok` if this block's body hashes to the value of `body_hash` in the header, and if
     /// this block's header hashes to the value claimed as the block hash.  Otherwise returns `Err`.
     pub(crate) fn has_valid_hash(&self) -> Result<(), DeployConfigurationFailure> {
@@ -305,7 +412,7 @@ impl Deploy {
         timestamp_leeway: TimeDiff,
         at: Timestamp,
     ) -> Result<(), DeployConfigurationFailure> {
-        self.is_valid_size(config.max_deploy_size)?;
+        self.is_within_size_limits(config)?;
 
         let header = self.header();
         if header.chain_name() != chain_name {
@@ -362,32 +469,6 @@ impl Deploy {
             }
         }
 
-        let payment_args_length = self.payment().args().serialized_length();
-        if payment_args_length > config.payment_args_max_length as usize {
-            debug!(
-                payment_args_length,
-                payment_args_max_length = config.payment_args_max_length,
-                "payment args excessive"
-            );
-            return Err(DeployConfigurationFailure::ExcessivePaymentArgsLength {
-                max_length: config.payment_args_max_length as usize,
-                got: payment_args_length,
-            });
-        }
-
-        let session_args_length = self.session().args().serialized_length();
-        if session_args_length > config.session_args_max_length as usize {
-            debug!(
-                session_args_length,
-                session_args_max_length = config.session_args_max_length,
-                "session args excessive"
-            );
-            return Err(DeployConfigurationFailure::ExcessiveSessionArgsLength {
-                max_length: config.session_args_max_length as usize,
-                got: session_args_length,
-            });
-        }
-
         if self.session().is_transfer() {
             let item = self.session().clone();
             let attempted = item
@@ -593,11 +674,7 @@ impl DocExample for Deploy {
 impl From<Deploy> for DeployItem {
     fn from(deploy: Deploy) -> Self {
         let address = deploy.header().account().to_account_hash();
-        let authorization_keys = deploy
-            .approvals()
-            .iter()
-            .map(|approval| approval.signer().to_account_hash())
-            .collect();
+        let authorization_keys = deploy.authorization_keys();
 
         DeployItem::new(
             address,
@@ -628,6 +705,18 @@ fn serialize_body(payment: &ExecutableDeployItem, session: &ExecutableDeployItem
     buffer
 }
 
+/// Returns the length of the item's module bytes, or `0` if it doesn't contain any.
+fn module_bytes_length(item: &ExecutableDeployItem) -> usize {
+    match item {
+        ExecutableDeployItem::ModuleBytes { module_bytes, .. } => module_bytes.len(),
+        ExecutableDeployItem::StoredContractByHash { .. }
+        | ExecutableDeployItem::StoredContractByName { .. }
+        | ExecutableDeployItem::StoredVersionedContractByHash { .. }
+        | ExecutableDeployItem::StoredVersionedContractByName { .. }
+        | ExecutableDeployItem::Transfer { .. } => 0,
+    }
+}
+
 /// Computationally expensive validity check for a given deploy instance, including
 /// asymmetric_key signing verification.
 fn validate_deploy(deploy: &Deploy) -> Result<(), DeployConfigurationFailure> {
@@ -1080,6 +1169,17 @@ impl Deploy {
         Self::random_transfer_with_payment(rng, payment)
     }
 
+    /// Returns a random deploy with standard payment of the given amount.
+    pub(crate) fn random_with_standard_payment_amount(rng: &mut TestRng, amount: u64) -> Self {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! {
+                "amount" => U512::from(amount),
+            },
+        };
+        Self::random_transfer_with_payment(rng, payment)
+    }
+
     fn random_transfer_with_payment(rng: &mut TestRng, payment: ExecutableDeployItem) -> Self {
         let deploy = Self::random_valid_native_transfer(rng);
         let secret_key = SecretKey::random(rng);
@@ -1540,6 +1640,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn not_acceptable_due_to_excessive_module_bytes() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let mut deploy_config = DeployConfig {
+            session_module_bytes_max_length: 10,
+            ..DeployConfig::default()
+        };
+
+        let mut deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl,
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+        deploy.session = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::from(vec![0; 11]),
+            args: RuntimeArgs::default(),
+        };
+
+        let current_timestamp = deploy.header().timestamp();
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                TimeDiff::default(),
+                current_timestamp
+            ),
+            Err(
+                DeployConfigurationFailure::ExcessiveSessionModuleBytesLength {
+                    max_length: 10,
+                    got: 11,
+                }
+            )
+        );
+
+        // A limit of zero means unlimited.
+        deploy_config.session_module_bytes_max_length = 0;
+        assert_eq!(deploy.is_within_size_limits(&deploy_config), Ok(()));
+    }
+
     #[test]
     fn not_acceptable_due_to_mangled_payment_amount() {
         let mut rng = crate::new_rng();
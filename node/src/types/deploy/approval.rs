@@ -31,6 +31,11 @@ impl Approval {
         Self { signer, signature }
     }
 
+    /// Creates an approval from a signature of a deploy hash made elsewhere.
+    pub(crate) fn from_parts(signer: PublicKey, signature: Signature) -> Self {
+        Self { signer, signature }
+    }
+
     /// Returns the public key of the approval's signer.
     pub fn signer(&self) -> &PublicKey {
         &self.signer
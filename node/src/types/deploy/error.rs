@@ -93,6 +93,24 @@ pub enum DeployConfigurationFailure {
         got: usize,
     },
 
+    /// Excessive length of deploy's payment module bytes.
+    #[error("payment module bytes of {got} exceed limit of {max_length}")]
+    ExcessivePaymentModuleBytesLength {
+        /// The byte size limit of payment module bytes.
+        max_length: usize,
+        /// The received length of payment module bytes.
+        got: usize,
+    },
+
+    /// Excessive length of deploy's session module bytes.
+    #[error("session module bytes of {got} exceed limit of {max_length}")]
+    ExcessiveSessionModuleBytesLength {
+        /// The byte size limit of session module bytes.
+        max_length: usize,
+        /// The received length of session module bytes.
+        got: usize,
+    },
+
     /// Missing payment "amount" runtime argument.
     #[error("missing payment 'amount' runtime argument ")]
     MissingPaymentAmount,
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use casper_types::ExecutionResult;
+use casper_types::{ExecutionResult, ExecutionResultFormat};
 
 use crate::types::{BlockHash, BlockHashAndHeight};
 
@@ -15,6 +15,22 @@ pub(crate) struct Metadata {
     /// The block hashes of blocks containing the related deploy, along with the results of
     /// executing the related deploy in the context of one or more blocks.
     pub(crate) execution_results: HashMap<BlockHash, ExecutionResult>,
+    /// The block hashes whose execution results are stored in compact form.
+    ///
+    /// Not part of the stored metadata; filled in by storage when the metadata is read.
+    #[serde(skip)]
+    pub(crate) compact_blocks: HashSet<BlockHash>,
+}
+
+impl Metadata {
+    /// Returns the format of the execution result for the given block.
+    pub(crate) fn execution_result_format(&self, block_hash: &BlockHash) -> ExecutionResultFormat {
+        if self.compact_blocks.contains(block_hash) {
+            ExecutionResultFormat::Compact
+        } else {
+            ExecutionResultFormat::Full
+        }
+    }
 }
 
 /// Additional information describing a deploy.
@@ -1,4 +1,5 @@
 use datasize::DataSize;
+use serde::Serialize;
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
 
 /// The offset Rust uses by default when generating an exit code after being interrupted by a
@@ -9,7 +10,7 @@ const SIGNAL_OFFSET: u8 = 128;
 /// binary.
 ///
 /// Note that a panic will result in the Rust process producing an exit code of 101.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, DataSize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, DataSize, Serialize)]
 #[repr(u8)]
 pub enum ExitCode {
     /// The process should exit with success.  The launcher should proceed to run the next
@@ -25,6 +26,15 @@ pub enum ExitCode {
     DowngradeVersion = 102,
     /// The process should exit with `103`.  The user requested a node shut down without restart.
     CleanExitDontRestart = 103,
+    /// The process should exit with `104`.  The node's local storage is corrupt or inconsistent.
+    /// Restarting will not help; the node's data should be removed so that it can resync.
+    ResyncRequired = 104,
+    /// The process should exit with `105`.  The configuration or the node's keys are unusable.
+    /// The node should not be restarted until the configuration has been fixed.
+    InvalidConfig = 105,
+    /// The process should exit with `106`.  A listening socket could not be bound, e.g. because
+    /// the port is in use.  Restarting may succeed once the address becomes available.
+    BindFailure = 106,
     /// The exit code Rust uses by default when interrupted via an `INT` signal.
     SigInt = SIGNAL_OFFSET + SIGINT as u8,
     /// The exit code Rust uses by default when interrupted via a `QUIT` signal.
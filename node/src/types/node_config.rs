@@ -10,6 +10,8 @@ const DEFAULT_MAX_ATTEMPTS: usize = 3;
 const DEFAULT_CONTROL_LOGIC_DEFAULT_DELAY: &str = "1sec";
 const DEFAULT_SHUTDOWN_FOR_UPGRADE_TIMEOUT: &str = "2min";
 const DEFAULT_UPGRADE_TIMEOUT: &str = "30sec";
+const DEFAULT_SHUTDOWN_TIMEOUT: &str = "30sec";
+const DEFAULT_HISTORICAL_SYNC_INTERVAL: &str = "0sec";
 
 /// Node sync configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize, Clone, Default)]
@@ -41,6 +43,59 @@ impl SyncHandling {
     }
 }
 
+/// The role the node runs in.
+#[derive(DataSize, Debug, Deserialize, Serialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeMode {
+    /// Participate in consensus whenever the configured secret key belongs to a validator.
+    #[default]
+    Validator,
+    /// Never participate in consensus.  The configured secret key is not loaded, and an ephemeral
+    /// key is generated instead.
+    Observer,
+    /// Like `Observer`, but only sync and verify block headers and their finality signatures.
+    /// Block bodies, deploys and global state are not synced, and no blocks are executed.
+    Light,
+}
+
+impl NodeMode {
+    /// Validator?
+    pub fn is_validator(&self) -> bool {
+        matches!(self, NodeMode::Validator)
+    }
+
+    /// Observer?
+    pub fn is_observer(&self) -> bool {
+        matches!(self, NodeMode::Observer)
+    }
+
+    /// Light client?
+    pub fn is_light(&self) -> bool {
+        matches!(self, NodeMode::Light)
+    }
+}
+
+/// Discovery of a trusted hash from peers, used when no trusted hash is configured and no local
+/// blocks are available.
+///
+/// Only peers which authenticated with the key of an active or upcoming validator known from the
+/// chainspec are taken into account.  The discovered hash is only as trustworthy as those peers:
+/// if `min_agreement` of them are malicious, they can direct the node to an arbitrary chain.
+#[derive(DataSize, Debug, Deserialize, Serialize, Clone, Copy)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct TrustedHashDiscoveryConfig {
+    /// Number of distinct validator peers which must have informed us about recent blocks before
+    /// a trusted hash is chosen.
+    pub peer_count: usize,
+
+    /// Minimum number of validator peers which must have informed us about the chosen block.
+    pub min_agreement: usize,
+
+    /// Maximum age of the chosen block.
+    pub max_block_age: TimeDiff,
+}
+
 /// Node fast-sync configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize, Clone)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -49,12 +104,20 @@ pub struct NodeConfig {
     /// Hash used as a trust anchor when joining, if any.
     pub trusted_hash: Option<BlockHash>,
 
+    /// If set and no trusted hash is configured, the trusted hash is discovered from peers.
+    #[serde(default)]
+    pub trusted_hash_discovery: Option<TrustedHashDiscoveryConfig>,
+
     /// Which historical sync option?
     ///  Genesis: sync all the way back to genesis
     ///  Ttl: sync the necessary number of historical blocks to satisfy TTL requirement.
     ///  NoSync: don't attempt to get any historical records; i.e. go forward only.
     pub sync_handling: SyncHandling,
 
+    /// Whether the node may act as a validator, or only observes the network.
+    #[serde(default)]
+    pub mode: NodeMode,
+
     /// Idle time after which the syncing process is considered stalled.
     pub idle_tolerance: TimeDiff,
 
@@ -75,22 +138,56 @@ pub struct NodeConfig {
     /// Maximum time a node will wait for an upgrade to commit.
     pub upgrade_timeout: TimeDiff,
 
+    /// Minimum number of fully connected peers required before the node starts validating.
+    #[serde(default)]
+    pub min_peers_to_validate: usize,
+
+    /// If set, the node only starts validating once its highest complete block is at most this
+    /// much older than the newest block reported by its peers, so that it doesn't propose on top
+    /// of a stale tip.
+    #[serde(default)]
+    pub max_tip_age_to_validate: Option<TimeDiff>,
+
     /// If true, prevents a node from shutting down if it is supposed to be a validator in the era.
     pub prevent_validator_shutdown: bool,
+
+    /// Maximum time to wait for all components to finalize when the node shuts down.
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: TimeDiff,
+
+    /// Minimum interval between starting to sync two consecutive historical blocks.  Historical
+    /// sync is only ever started while the node is keeping up with the network; this additionally
+    /// throttles how much of the node's bandwidth the backfill may take.
+    #[serde(default = "default_historical_sync_interval")]
+    pub historical_sync_interval: TimeDiff,
+}
+
+fn default_shutdown_timeout() -> TimeDiff {
+    DEFAULT_SHUTDOWN_TIMEOUT.parse().unwrap()
+}
+
+fn default_historical_sync_interval() -> TimeDiff {
+    DEFAULT_HISTORICAL_SYNC_INTERVAL.parse().unwrap()
 }
 
 impl Default for NodeConfig {
     fn default() -> NodeConfig {
         NodeConfig {
             trusted_hash: None,
+            trusted_hash_discovery: None,
             sync_handling: SyncHandling::default(),
+            mode: NodeMode::default(),
             idle_tolerance: DEFAULT_IDLE_TOLERANCE.parse().unwrap(),
             max_attempts: DEFAULT_MAX_ATTEMPTS,
             control_logic_default_delay: DEFAULT_CONTROL_LOGIC_DEFAULT_DELAY.parse().unwrap(),
             force_resync: false,
             shutdown_for_upgrade_timeout: DEFAULT_SHUTDOWN_FOR_UPGRADE_TIMEOUT.parse().unwrap(),
             upgrade_timeout: DEFAULT_UPGRADE_TIMEOUT.parse().unwrap(),
+            min_peers_to_validate: 0,
+            max_tip_age_to_validate: None,
             prevent_validator_shutdown: false,
+            shutdown_timeout: default_shutdown_timeout(),
+            historical_sync_interval: default_historical_sync_interval(),
         }
     }
 }
@@ -0,0 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::{crypto, EraId, PublicKey, U512};
+
+use crate::{
+    components::fetcher::{FetchItem, Tag},
+    types::{
+        error::BlockHeaderWithMetadataValidationError, BlockHeaderWithMetadata, EraValidatorWeights,
+    },
+    utils::{self, BlockSignatureError},
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum SignedEraValidatorsValidationError {
+    #[error("The block header is not a switch block.")]
+    NotASwitchBlock,
+    #[error(
+        "The switch block is in era {switch_block_era_id}, but the trusted validator weights are \
+        for era {trusted_era_id}."
+    )]
+    UnexpectedSwitchBlockEra {
+        switch_block_era_id: EraId,
+        trusted_era_id: EraId,
+    },
+    #[error(transparent)]
+    BlockWithMetadata(BlockHeaderWithMetadataValidationError),
+    #[error(transparent)]
+    SwitchBlockNotSufficientlySigned(BlockSignatureError),
+    #[error("The block signatures are not cryptographically valid: {0}")]
+    Crypto(crypto::Error),
+}
+
+/// The validator set of a single era, together with the proof that it is on the correct chain.
+///
+/// The validators of era `N` are recorded in the switch block of era `N - 1`, which in turn is
+/// finalized by the validators of era `N - 1`. Hence, given the trusted validator weights of era
+/// `N - 1`, the validators of era `N` can be verified without trusting the peer providing them.
+///
+/// This doesn't hold across an upgrade that changes the validator set, since the switch block of
+/// the last era before such an upgrade records the validators as they were before the change.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, DataSize)]
+pub(crate) struct SignedEraValidators {
+    /// The switch block of the era preceding the one whose validators are provided, along with
+    /// its finality signatures.
+    switch_block: BlockHeaderWithMetadata,
+}
+
+impl SignedEraValidators {
+    pub(crate) fn new(switch_block: BlockHeaderWithMetadata) -> Self {
+        SignedEraValidators { switch_block }
+    }
+
+    /// The era for which the validators are provided.
+    pub(crate) fn era_id(&self) -> EraId {
+        self.switch_block.block_header.next_block_era_id()
+    }
+
+    /// The validator weights of the era, or `None` if the header is not a switch block.
+    pub(crate) fn validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+        self.switch_block.block_header.next_era_validator_weights()
+    }
+
+    /// Returns the switch block header together with its finality signatures.
+    pub(crate) fn into_switch_block(self) -> BlockHeaderWithMetadata {
+        self.switch_block
+    }
+}
+
+impl Display for SignedEraValidators {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "validators of {} from switch block {}",
+            self.era_id(),
+            self.switch_block.block_header.block_hash()
+        )
+    }
+}
+
+impl FetchItem for SignedEraValidators {
+    type Id = EraId;
+    type ValidationError = SignedEraValidatorsValidationError;
+    // The trusted validator weights of the era of the switch block, i.e. of the era preceding the
+    // one being fetched.
+    type ValidationMetadata = EraValidatorWeights;
+
+    const TAG: Tag = Tag::EraValidators;
+
+    fn fetch_id(&self) -> Self::Id {
+        self.era_id()
+    }
+
+    fn validate(&self, trusted_weights: &EraValidatorWeights) -> Result<(), Self::ValidationError> {
+        let header = &self.switch_block.block_header;
+        if !header.is_switch_block() {
+            return Err(SignedEraValidatorsValidationError::NotASwitchBlock);
+        }
+        if header.era_id() != trusted_weights.era_id() {
+            return Err(
+                SignedEraValidatorsValidationError::UnexpectedSwitchBlockEra {
+                    switch_block_era_id: header.era_id(),
+                    trusted_era_id: trusted_weights.era_id(),
+                },
+            );
+        }
+        self.switch_block
+            .validate()
+            .map_err(SignedEraValidatorsValidationError::BlockWithMetadata)?;
+        utils::check_sufficient_block_signatures(
+            trusted_weights.validator_weights(),
+            trusted_weights.finality_threshold_fraction(),
+            Some(&self.switch_block.block_signatures),
+        )
+        .map_err(SignedEraValidatorsValidationError::SwitchBlockNotSufficientlySigned)?;
+
+        // defer cryptographic verification until last to avoid unnecessary computation
+        self.switch_block
+            .block_signatures
+            .verify()
+            .map_err(SignedEraValidatorsValidationError::Crypto)
+    }
+}
+
+mod specimen_support {
+    use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
+
+    use super::SignedEraValidators;
+
+    impl LargestSpecimen for SignedEraValidators {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            SignedEraValidators {
+                switch_block: LargestSpecimen::largest_specimen(estimator, cache),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use casper_types::{testing::TestRng, EraId, PublicKey, SecretKey, U512};
+    use num_rational::Ratio;
+
+    use super::{SignedEraValidators, SignedEraValidatorsValidationError};
+    use crate::{
+        components::fetcher::FetchItem,
+        types::{
+            BlockHeader, BlockHeaderWithMetadata, BlockSignatures, EraValidatorWeights,
+            FinalitySignature, TestBlockBuilder,
+        },
+    };
+
+    fn make_era_validators(
+        header: &BlockHeader,
+        signers: &[(SecretKey, PublicKey)],
+    ) -> SignedEraValidators {
+        let block_hash = header.block_hash();
+        let mut block_signatures = BlockSignatures::new(block_hash, header.era_id());
+        for (secret_key, public_key) in signers {
+            let signature = FinalitySignature::create(
+                block_hash,
+                header.era_id(),
+                secret_key,
+                public_key.clone(),
+            );
+            block_signatures.insert_proof(public_key.clone(), signature.signature);
+        }
+        SignedEraValidators::new(BlockHeaderWithMetadata {
+            block_header: header.clone(),
+            block_signatures,
+        })
+    }
+
+    fn setup(
+        rng: &mut TestRng,
+    ) -> (
+        BlockHeader,
+        Vec<(SecretKey, PublicKey)>,
+        EraValidatorWeights,
+    ) {
+        let validators: Vec<_> = (0..3)
+            .map(|_| {
+                let secret_key = SecretKey::random(rng);
+                let public_key = PublicKey::from(&secret_key);
+                (secret_key, public_key)
+            })
+            .collect();
+        let weights: BTreeMap<_, _> = validators
+            .iter()
+            .map(|(_, public_key)| (public_key.clone(), U512::from(100)))
+            .collect();
+        let trusted_weights = EraValidatorWeights::new(EraId::new(4), weights, Ratio::new(1, 3));
+        let header = TestBlockBuilder::new()
+            .era(4)
+            .switch_block(true)
+            .build(rng)
+            .take_header();
+        (header, validators, trusted_weights)
+    }
+
+    #[test]
+    fn should_validate_sufficiently_signed_switch_block() {
+        let mut rng = TestRng::new();
+        let (header, validators, trusted_weights) = setup(&mut rng);
+
+        let era_validators = make_era_validators(&header, &validators);
+
+        assert_eq!(era_validators.fetch_id(), EraId::new(5));
+        assert_eq!(
+            era_validators.validator_weights(),
+            header.next_era_validator_weights()
+        );
+        assert!(era_validators.validate(&trusted_weights).is_ok());
+    }
+
+    #[test]
+    fn should_reject_insufficiently_signed_switch_block() {
+        let mut rng = TestRng::new();
+        let (header, validators, trusted_weights) = setup(&mut rng);
+
+        let era_validators = make_era_validators(&header, &validators[..1]);
+
+        assert!(matches!(
+            era_validators.validate(&trusted_weights),
+            Err(SignedEraValidatorsValidationError::SwitchBlockNotSufficientlySigned(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_weights_of_unexpected_era() {
+        let mut rng = TestRng::new();
+        let (header, validators, _) = setup(&mut rng);
+        let weights = validators
+            .iter()
+            .map(|(_, public_key)| (public_key.clone(), U512::from(100)))
+            .collect();
+        let trusted_weights = EraValidatorWeights::new(EraId::new(3), weights, Ratio::new(1, 3));
+
+        let era_validators = make_era_validators(&header, &validators);
+
+        assert!(matches!(
+            era_validators.validate(&trusted_weights),
+            Err(SignedEraValidatorsValidationError::UnexpectedSwitchBlockEra { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_non_switch_block() {
+        let mut rng = TestRng::new();
+        let (_, validators, trusted_weights) = setup(&mut rng);
+        let header = TestBlockBuilder::new()
+            .era(4)
+            .switch_block(false)
+            .build(&mut rng)
+            .take_header();
+
+        let era_validators = make_era_validators(&header, &validators);
+
+        assert!(matches!(
+            era_validators.validate(&trusted_weights),
+            Err(SignedEraValidatorsValidationError::NotASwitchBlock)
+        ));
+    }
+}
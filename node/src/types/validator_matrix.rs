@@ -331,6 +331,14 @@ impl EraValidatorWeights {
         self.era_id
     }
 
+    pub(crate) fn validator_weights(&self) -> &BTreeMap<PublicKey, U512> {
+        &self.validator_weights
+    }
+
+    pub(crate) fn finality_threshold_fraction(&self) -> Ratio<u64> {
+        self.finality_threshold_fraction
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
         self.validator_weights.is_empty()
     }
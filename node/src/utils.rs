@@ -2,10 +2,12 @@
 //! being factored out into standalone crates.
 
 mod block_signatures;
+mod cors;
 mod display_error;
 pub(crate) mod ds;
 mod external;
 pub(crate) mod fmt_limit;
+mod http_incoming;
 pub(crate) mod opt_display;
 #[cfg(target_os = "linux")]
 pub(crate) mod rlimit;
@@ -30,7 +32,7 @@ use std::{
 };
 
 use datasize::DataSize;
-use hyper::server::{conn::AddrIncoming, Builder, Server};
+use hyper::server::conn::AddrIncoming;
 #[cfg(test)]
 use once_cell::sync::Lazy;
 use prometheus::{self, Histogram, HistogramOpts, Registry};
@@ -38,12 +40,18 @@ use serde::Serialize;
 use thiserror::Error;
 use tracing::{error, warn};
 
-use crate::types::{BlockHeader, NodeId};
+use crate::{
+    components::diagnostics_port,
+    types::{BlockHeader, NodeId},
+};
 pub(crate) use block_signatures::{check_sufficient_block_signatures, BlockSignatureError};
+pub(crate) use cors::cors;
 pub(crate) use display_error::display_error;
 #[cfg(test)]
 pub(crate) use external::RESOURCES_PATH;
 pub use external::{External, LoadError, Loadable};
+pub use http_incoming::HttpTlsConfig;
+pub(crate) use http_incoming::{HttpIncoming, HttpStream};
 pub(crate) use round_robin::WeightedRoundRobin;
 
 /// DNS resolution error.
@@ -133,23 +141,65 @@ pub(crate) enum ListeningError {
         /// The failure reason.
         error: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    /// Failed to listen on a unix socket.
+    #[error("failed to listen on {}: {error}", socket_path.display())]
+    ListenUnix {
+        /// The socket path attempted to listen on.
+        socket_path: PathBuf,
+        /// The failure reason.
+        error: io::Error,
+    },
+
+    /// Failed to set up TLS.
+    #[error("failed to set up TLS on {address}: {error}")]
+    Tls {
+        /// The address attempted to listen on.
+        address: SocketAddr,
+        /// The failure reason.
+        error: openssl::error::ErrorStack,
+    },
 }
 
-pub(crate) fn start_listening(address: &str) -> Result<Builder<AddrIncoming>, ListeningError> {
+pub(crate) fn start_listening(
+    address: &str,
+    tls: Option<&WithDir<HttpTlsConfig>>,
+) -> Result<HttpIncoming, ListeningError> {
     let address = resolve_address(address).map_err(|error| {
         warn!(%error, %address, "failed to start HTTP server, cannot parse address");
         ListeningError::ResolveAddress(error)
     })?;
 
-    Server::try_bind(&address).map_err(|error| {
+    let incoming = AddrIncoming::bind(&address).map_err(|error| {
         warn!(%error, %address, "failed to start HTTP server");
         ListeningError::Listen {
             address,
             error: Box::new(error),
         }
+    })?;
+
+    HttpIncoming::new(incoming, tls).map_err(|error| {
+        warn!(%error, %address, "failed to start HTTP server, cannot set up TLS");
+        ListeningError::Tls { address, error }
     })
 }
 
+/// Additionally starts listening for HTTP connections on a unix socket at `socket_path`, created
+/// with the given umask.
+pub(crate) fn start_listening_unix(
+    incoming: HttpIncoming,
+    socket_path: PathBuf,
+    socket_umask: umask::Mode,
+) -> Result<HttpIncoming, ListeningError> {
+    match diagnostics_port::setup_listener(&socket_path, socket_umask) {
+        Ok(listener) => Ok(incoming.with_unix_socket(listener, socket_path)),
+        Err(error) => {
+            warn!(%error, socket_path=%socket_path.display(), "failed to start HTTP server");
+            Err(ListeningError::ListenUnix { socket_path, error })
+        }
+    }
+}
+
 /// Moves a value to the heap and then forgets about, leaving only a static reference behind.
 #[inline]
 pub(crate) fn leak<T>(value: T) -> &'static T {
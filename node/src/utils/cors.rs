@@ -0,0 +1,48 @@
+//! CORS configuration shared by the node's HTTP servers.
+
+use http::Method;
+use tracing::warn;
+use warp::cors::Builder;
+
+/// Returns the CORS configuration for an HTTP server, or `None` if CORS is disabled.
+///
+/// `cors_origin` is either empty, which disables CORS, `*` to allow any origin, or a
+/// comma-separated list of allowed origins.  `allowed_methods` lists the HTTP methods allowed for
+/// cross-origin requests; if it is empty, `default_methods` are allowed instead.
+pub(crate) fn cors(
+    cors_origin: &str,
+    allowed_methods: &[String],
+    default_methods: &[Method],
+) -> Option<Builder> {
+    let cors_origin = cors_origin.trim();
+    if cors_origin.is_empty() {
+        return None;
+    }
+
+    let builder = if cors_origin == "*" {
+        warp::cors().allow_any_origin()
+    } else {
+        warp::cors().allow_origins(
+            cors_origin
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty()),
+        )
+    };
+
+    let mut methods: Vec<Method> = allowed_methods
+        .iter()
+        .filter_map(|method| match Method::from_bytes(method.as_bytes()) {
+            Ok(method) => Some(method),
+            Err(error) => {
+                warn!(%method, %error, "ignoring invalid CORS method");
+                None
+            }
+        })
+        .collect();
+    if methods.is_empty() {
+        methods = default_methods.to_vec();
+    }
+
+    Some(builder.allow_methods(methods))
+}
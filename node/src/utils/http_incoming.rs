@@ -0,0 +1,337 @@
+//! Incoming connections for the node's HTTP servers, accepted over TCP, optionally secured with
+//! TLS, and optionally over a unix socket as well.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    fs, io,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use hyper::server::{
+    accept::Accept,
+    conn::{AddrIncoming, AddrStream},
+};
+use openssl::{
+    error::ErrorStack,
+    ssl::{Ssl, SslAcceptor, SslFiletype, SslMethod, SslVerifyMode},
+    x509::X509Name,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{UnixListener, UnixStream},
+};
+use tokio_openssl::SslStream;
+use tracing::debug;
+
+use super::WithDir;
+
+/// Maximum time allowed for a client to complete the TLS handshake.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS configuration for an HTTP server.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct HttpTlsConfig {
+    /// Path to the PEM-encoded certificate chain presented to clients.
+    pub certificate_chain_path: PathBuf,
+    /// Path to the PEM-encoded private key of the certificate.
+    pub private_key_path: PathBuf,
+    /// Path to PEM-encoded CA certificates used to authenticate clients.  If set, only clients
+    /// presenting a certificate signed by one of these CAs are accepted.
+    #[serde(default)]
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl WithDir<HttpTlsConfig> {
+    /// Creates an acceptor from the configured certificate, key and optional client CAs.
+    fn create_acceptor(&self) -> Result<SslAcceptor, ErrorStack> {
+        let config = self.value();
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+        builder.set_certificate_chain_file(self.with_dir(config.certificate_chain_path.clone()))?;
+        builder.set_private_key_file(
+            self.with_dir(config.private_key_path.clone()),
+            SslFiletype::PEM,
+        )?;
+        builder.check_private_key()?;
+        if let Some(client_ca_path) = &config.client_ca_path {
+            let client_ca_path = self.with_dir(client_ca_path.clone());
+            builder.set_ca_file(&client_ca_path)?;
+            builder.set_client_ca_list(X509Name::load_client_ca_file(&client_ca_path)?);
+            builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        }
+        Ok(builder.build())
+    }
+}
+
+/// A connection accepted by an [`HttpIncoming`].
+pub(crate) enum HttpStream {
+    /// A plain TCP connection.
+    Plain(AddrStream),
+    /// A TCP connection on which the TLS handshake has completed.
+    Tls(Box<SslStream<AddrStream>>),
+    /// A unix socket connection.
+    Unix(UnixStream),
+}
+
+impl HttpStream {
+    /// Returns the remote address of the connection, or `None` for a unix socket connection.
+    pub(crate) fn remote_addr(&self) -> Option<SocketAddr> {
+        match self {
+            HttpStream::Plain(stream) => Some(stream.remote_addr()),
+            HttpStream::Tls(stream) => Some(stream.get_ref().remote_addr()),
+            HttpStream::Unix(_) => None,
+        }
+    }
+}
+
+impl AsyncRead for HttpStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            HttpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+            HttpStream::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for HttpStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            HttpStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            HttpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+            HttpStream::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            HttpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+            HttpStream::Unix(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            HttpStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+            HttpStream::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Performs the server side of the TLS handshake on a newly accepted connection.
+async fn tls_handshake(
+    acceptor: Arc<SslAcceptor>,
+    stream: AddrStream,
+) -> Result<HttpStream, (SocketAddr, io::Error)> {
+    let remote_address = stream.remote_addr();
+    let handshake = async {
+        let ssl = Ssl::new(acceptor.context())
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        let mut tls_stream = SslStream::new(ssl, stream)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        Pin::new(&mut tls_stream)
+            .accept()
+            .await
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        Ok(HttpStream::Tls(Box::new(tls_stream)))
+    };
+    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, handshake).await {
+        Ok(result) => result.map_err(|error| (remote_address, error)),
+        Err(_elapsed) => Err((
+            remote_address,
+            io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"),
+        )),
+    }
+}
+
+/// A unix socket listener, whose socket file is removed when it is dropped.
+struct UnixSocket {
+    listener: UnixListener,
+    socket_path: PathBuf,
+}
+
+impl Drop for UnixSocket {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.socket_path) {
+            let socket_path = self.socket_path.display();
+            debug!(%error, %socket_path, "could not remove socket file");
+        }
+    }
+}
+
+/// Incoming connections for an HTTP server, accepted over TCP, optionally secured with TLS, and
+/// optionally over a unix socket as well.
+///
+/// As connections from both listeners are served by the same server, they share its limits.
+///
+/// TLS handshakes are driven concurrently, so a slow client cannot stall the acceptance of other
+/// connections.  Connections failing the handshake are dropped.
+pub(crate) struct HttpIncoming {
+    tcp: AddrIncoming,
+    unix_socket: Option<UnixSocket>,
+    tls_acceptor: Option<Arc<SslAcceptor>>,
+    handshakes: FuturesUnordered<BoxFuture<'static, Result<HttpStream, (SocketAddr, io::Error)>>>,
+}
+
+impl HttpIncoming {
+    /// Creates a new set of incoming TCP connections.  If `tls` is provided, the TLS handshake is
+    /// performed on each connection before it is yielded.
+    pub(crate) fn new(
+        incoming: AddrIncoming,
+        tls: Option<&WithDir<HttpTlsConfig>>,
+    ) -> Result<Self, ErrorStack> {
+        let tls_acceptor = tls
+            .map(|tls| tls.create_acceptor().map(Arc::new))
+            .transpose()?;
+        Ok(HttpIncoming {
+            tcp: incoming,
+            unix_socket: None,
+            tls_acceptor,
+            handshakes: FuturesUnordered::new(),
+        })
+    }
+
+    /// Additionally accepts connections on the unix socket bound by `listener` at `socket_path`.
+    /// The socket file is removed once `self` is dropped.
+    pub(crate) fn with_unix_socket(mut self, listener: UnixListener, socket_path: PathBuf) -> Self {
+        self.unix_socket = Some(UnixSocket {
+            listener,
+            socket_path,
+        });
+        self
+    }
+
+    /// Returns the local address on which TCP connections are accepted.
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.tcp.local_addr()
+    }
+}
+
+impl Display for HttpIncoming {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.tcp.local_addr())?;
+        if let Some(unix_socket) = &self.unix_socket {
+            write!(formatter, " and {}", unix_socket.socket_path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl Accept for HttpIncoming {
+    type Conn = HttpStream;
+    type Error = io::Error;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let this = self.get_mut();
+        if let Some(unix_socket) = &this.unix_socket {
+            if let Poll::Ready(result) = unix_socket.listener.poll_accept(cx) {
+                return Poll::Ready(Some(result.map(|(stream, _)| HttpStream::Unix(stream))));
+            }
+        }
+        let incoming = &mut this.tcp;
+        let acceptor = match &this.tls_acceptor {
+            Some(acceptor) => Arc::clone(acceptor),
+            None => return Pin::new(incoming).poll_accept(cx).map_ok(HttpStream::Plain),
+        };
+
+        // Start handshakes on all connections which are ready to be accepted.
+        loop {
+            match Pin::new(&mut *incoming).poll_accept(cx) {
+                Poll::Ready(Some(Ok(stream))) => this
+                    .handshakes
+                    .push(tls_handshake(Arc::clone(&acceptor), stream).boxed()),
+                Poll::Ready(Some(Err(error))) => return Poll::Ready(Some(Err(error))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+
+        // Yield the first connection to complete its handshake.
+        loop {
+            match this.handshakes.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(stream))) => return Poll::Ready(Some(Ok(stream))),
+                Poll::Ready(Some(Err((remote_address, error)))) => {
+                    debug!(%remote_address, %error, "TLS handshake failed");
+                }
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl Stream for HttpIncoming {
+    type Item = Result<HttpStream, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_accept(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::{TcpStream, UnixStream};
+
+    use super::*;
+
+    fn tcp_and_unix_incoming(socket_path: &PathBuf) -> HttpIncoming {
+        let tcp = AddrIncoming::bind(&([127, 0, 0, 1], 0).into()).expect("should bind");
+        let listener = UnixListener::bind(socket_path).expect("should bind socket");
+        HttpIncoming::new(tcp, None)
+            .expect("should create incoming")
+            .with_unix_socket(listener, socket_path.clone())
+    }
+
+    #[tokio::test]
+    async fn should_accept_tcp_and_unix_connections() {
+        let tmpdir = tempfile::tempdir().expect("should create tempdir");
+        let socket_path = tmpdir.path().join("test.socket");
+        let mut incoming = tcp_and_unix_incoming(&socket_path);
+
+        let _unix_client = UnixStream::connect(&socket_path)
+            .await
+            .expect("should connect");
+        let stream = incoming.next().await.expect("should accept").unwrap();
+        assert!(matches!(stream, HttpStream::Unix(_)));
+        assert!(stream.remote_addr().is_none());
+
+        let _tcp_client = TcpStream::connect(incoming.local_addr())
+            .await
+            .expect("should connect");
+        let stream = incoming.next().await.expect("should accept").unwrap();
+        assert!(matches!(stream, HttpStream::Plain(_)));
+        assert!(stream.remote_addr().is_some());
+    }
+
+    #[tokio::test]
+    async fn should_remove_socket_file_when_dropped() {
+        let tmpdir = tempfile::tempdir().expect("should create tempdir");
+        let socket_path = tmpdir.path().join("test.socket");
+        let incoming = tcp_and_unix_incoming(&socket_path);
+        assert!(socket_path.exists());
+
+        drop(incoming);
+        assert!(!socket_path.exists());
+    }
+}
@@ -49,6 +49,16 @@ pub struct WeightedRoundRobin<I, K> {
     recent_event_count_peak: Option<AtomicUsize>,
 }
 
+/// Behavior of a bounded queue once it has reached its limit.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum OverflowPolicy {
+    /// Pushing waits until an item has been popped from the queue, applying backpressure to the
+    /// producer.
+    Block,
+    /// Pushed items are dropped immediately.
+    Shed,
+}
+
 /// State that wraps queue and its event count.
 #[derive(Debug)]
 struct QueueState<I> {
@@ -57,6 +67,10 @@ struct QueueState<I> {
     /// Do not modify this unless you are holding the `queue` lock.
     event_count: AtomicUsize,
     queue: Mutex<VecDeque<I>>,
+    /// Free slots of a bounded queue, along with the policy to apply once none are left.
+    capacity: Option<(Semaphore, OverflowPolicy)>,
+    /// Number of items dropped due to the queue being full.
+    shed_count: AtomicUsize,
 }
 
 impl<I> QueueState<I> {
@@ -64,6 +78,40 @@ impl<I> QueueState<I> {
         QueueState {
             event_count: AtomicUsize::new(0),
             queue: Mutex::new(VecDeque::new()),
+            capacity: None,
+            shed_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reserves a slot for a new item in a bounded queue.
+    ///
+    /// Returns `false` if the item should be dropped instead.
+    async fn reserve_slot(&self) -> bool {
+        match self.capacity {
+            None => true,
+            Some((ref free_slots, OverflowPolicy::Block)) => {
+                // Safe to `expect` here as the semaphore is never closed.
+                free_slots.acquire().await.expect("should acquire").forget();
+                true
+            }
+            Some((ref free_slots, OverflowPolicy::Shed)) => match free_slots.try_acquire() {
+                Ok(permit) => {
+                    permit.forget();
+                    true
+                }
+                Err(_) => {
+                    self.shed_count.fetch_add(1, Ordering::SeqCst);
+                    false
+                }
+            },
+        }
+    }
+
+    /// Returns slots previously reserved for now removed items.
+    #[inline]
+    fn release_slots(&self, count: usize) {
+        if let Some((ref free_slots, _)) = self.capacity {
+            free_slots.add_permits(count);
         }
     }
 
@@ -73,6 +121,7 @@ impl<I> QueueState<I> {
         let mut guard = self.queue.lock().await;
         let events: Vec<I> = guard.drain(..).collect();
         self.event_count.fetch_sub(events.len(), Ordering::SeqCst);
+        self.release_slots(events.len());
         events
     }
 
@@ -172,6 +221,24 @@ where
         }
     }
 
+    /// Bounds a queue to hold at most `limit` items, handling any overflow according to `policy`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the queue identified by key `queue` does not exist.
+    pub(crate) fn with_limit(
+        mut self,
+        queue: K,
+        limit: NonZeroUsize,
+        policy: OverflowPolicy,
+    ) -> Self {
+        self.queues
+            .get_mut(&queue)
+            .expect("tried to limit non-existent queue")
+            .capacity = Some((Semaphore::new(limit.get()), policy));
+        self
+    }
+
     /// Dump the queue contents to the given dumper function.
     pub async fn dump<F: FnOnce(&QueueDump<K, I>)>(&self, dumper: F)
     where
@@ -226,11 +293,17 @@ where
             return;
         }
 
-        self.queues
+        let queue_state = self
+            .queues
             .get(&queue)
-            .expect("tried to push to non-existent queue")
-            .push_back(item)
-            .await;
+            .expect("tried to push to non-existent queue");
+
+        if !queue_state.reserve_slot().await {
+            debug!(%queue, "queue full, shedding item");
+            return;
+        }
+
+        queue_state.push_back(item).await;
 
         // NOTE: Count may be off by one b/c of the way locking works when elements are popped.
         // It's fine for its purposes.
@@ -288,6 +361,7 @@ where
                 // We hold the queue's lock and checked `is_empty` earlier.
                 .expect("item disappeared. this should not happen");
             queue_state.dec_count();
+            queue_state.release_slots(1);
             break (item, inner.active_slot.key);
         }
     }
@@ -327,7 +401,6 @@ where
     /// Seals the queue, preventing it from accepting any more items.
     ///
     /// Items pushed into the queue via `push` will be dropped immediately.
-    #[cfg(test)]
     pub fn seal(&self) {
         self.sealed.store(true, Ordering::SeqCst);
     }
@@ -345,6 +418,14 @@ where
             .map(|(key, queue)| (*key, queue.event_count()))
             .collect()
     }
+
+    /// Returns the number of events dropped so far by each of the queues due to being full.
+    pub(crate) fn shed_counts(&self) -> HashMap<K, usize> {
+        self.queues
+            .iter()
+            .map(|(key, queue)| (*key, queue.shed_count.load(Ordering::SeqCst)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -427,6 +508,47 @@ mod tests {
         assert!(scheduler.drain_queues().await.is_empty());
     }
 
+    #[tokio::test]
+    async fn should_shed_items_when_full() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights(), None).with_limit(
+            QueueKind::One,
+            NonZeroUsize::new(2).unwrap(),
+            OverflowPolicy::Shed,
+        );
+
+        scheduler.push('a', QueueKind::One).await;
+        scheduler.push('b', QueueKind::One).await;
+        scheduler.push('c', QueueKind::One).await;
+        assert_eq!(scheduler.item_count(), 2);
+        assert_eq!(scheduler.shed_counts()[&QueueKind::One], 1);
+
+        // Unbounded queues are unaffected.
+        scheduler.push('d', QueueKind::Two).await;
+        assert_eq!(scheduler.item_count(), 3);
+
+        // Popping an item frees up a slot again.
+        assert_eq!(('a', QueueKind::One), scheduler.pop().await);
+        scheduler.push('e', QueueKind::One).await;
+        assert_eq!(scheduler.item_count(), 3);
+        assert_eq!(scheduler.shed_counts()[&QueueKind::One], 1);
+    }
+
+    #[tokio::test]
+    async fn should_block_pushing_when_full() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights(), None).with_limit(
+            QueueKind::One,
+            NonZeroUsize::new(1).unwrap(),
+            OverflowPolicy::Block,
+        );
+
+        scheduler.push('a', QueueKind::One).await;
+        assert!(scheduler.push('b', QueueKind::One).now_or_never().is_none());
+
+        assert_eq!(('a', QueueKind::One), scheduler.pop().await);
+        assert!(scheduler.push('c', QueueKind::One).now_or_never().is_some());
+        assert_eq!(scheduler.shed_counts()[&QueueKind::One], 0);
+    }
+
     #[test]
     fn should_calculate_dump_threshold() {
         let total = 0;
@@ -30,10 +30,12 @@ use crate::{
         fetcher::Tag,
     },
     protocol::Message,
+    tls::{KeyFingerprint, Sha512},
     types::{
         ApprovalsHash, ApprovalsHashes, Block, BlockExecutionResultsOrChunk, BlockHash,
         BlockHeader, BlockPayload, Deploy, DeployHashWithApprovals, DeployId, FinalitySignature,
-        FinalitySignatureId, FinalizedBlock, LegacyDeploy, SyncLeap, TrieOrChunk,
+        FinalitySignatureId, FinalizedBlock, LegacyDeploy, NodeId, SignedEraValidators, SyncLeap,
+        TrieOrChunk,
     },
 };
 
@@ -228,6 +230,13 @@ where
     T::large_unique_sequence(estimator, count, cache)
 }
 
+impl LargestSpecimen for NodeId {
+    fn largest_specimen<E: SizeEstimator>(_estimator: &E, _cache: &mut Cache) -> Self {
+        // All node IDs are fingerprints of the same size.
+        NodeId::from(KeyFingerprint::from(Sha512::new([])))
+    }
+}
+
 impl LargestSpecimen for SocketAddr {
     fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
         SocketAddr::V6(SocketAddrV6::largest_specimen(estimator, cache))
@@ -418,9 +427,9 @@ where
                 //
                 // 1. The required seed bytes for Ed25519 and Secp256k1 are both the same length of
                 //    32 bytes.
-                // 2. While Secp256k1 does not allow the most trivial seed bytes of 0x00..0001, a
-                //    a hash function output seems to satisfy it, and our current hashing scheme
-                //    also output 32 bytes.
+                // 2. While Secp256k1 does not allow the most trivial seed bytes of 0x00..0001, a a
+                //    hash function output seems to satisfy it, and our current hashing scheme also
+                //    output 32 bytes.
                 let seed_bytes = Digest::hash(seed.to_be_bytes()).value();
 
                 match variant {
@@ -771,6 +780,9 @@ pub(crate) fn largest_get_request<E: SizeEstimator>(estimator: &E, cache: &mut C
             Tag::BlockExecutionResults => Message::new_get_request::<BlockExecutionResultsOrChunk>(
                 &LargestSpecimen::largest_specimen(estimator, cache),
             ),
+            Tag::EraValidators => Message::new_get_request::<SignedEraValidators>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
         }
         .expect("did not expect new_get_request from largest deploy to fail")
     })
@@ -809,6 +821,9 @@ pub(crate) fn largest_get_response<E: SizeEstimator>(estimator: &E, cache: &mut
                     &LargestSpecimen::largest_specimen(estimator, cache),
                 )
             }
+            Tag::EraValidators => Message::new_get_response::<SignedEraValidators>(
+                &LargestSpecimen::largest_specimen(estimator, cache),
+            ),
         }
         .expect("did not expect new_get_response from largest deploy to fail")
     })
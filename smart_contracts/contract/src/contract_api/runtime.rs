@@ -6,10 +6,10 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, U64_SERIALIZED_LENGTH},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
-    ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
+    ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, EraId, Key, Phase,
     RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
 };
 
@@ -212,6 +212,22 @@ pub fn get_blocktime() -> BlockTime {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Returns the [`EraId`] of the era in which the current block is executed.
+///
+/// Only available from protocol version 1.6.0.
+pub fn get_era_id() -> EraId {
+    let dest_non_null_ptr = contract_api::alloc_bytes(U64_SERIALIZED_LENGTH);
+    let bytes = unsafe {
+        ext_ffi::casper_get_era_id(dest_non_null_ptr.as_ptr());
+        Vec::from_raw_parts(
+            dest_non_null_ptr.as_ptr(),
+            U64_SERIALIZED_LENGTH,
+            U64_SERIALIZED_LENGTH,
+        )
+    };
+    bytesrepr::deserialize(bytes).unwrap_or_revert()
+}
+
 /// Returns the current [`Phase`].
 pub fn get_phase() -> Phase {
     let dest_non_null_ptr = contract_api::alloc_bytes(PHASE_SERIALIZED_LENGTH);
@@ -240,6 +240,17 @@ extern "C" {
     ///
     /// * `dest_ptr` - pointer in wasm memory where to write the result
     pub fn casper_get_blocktime(dest_ptr: *const u8);
+    /// This function gets the id of the era in which the block this deploy is included in is
+    /// executed. The return value is always a 64-bit unsigned integer. It is up to the caller to
+    /// ensure there are 8 bytes allocated at `dest_ptr`, otherwise data corruption in the wasm
+    /// memory may occur.
+    ///
+    /// Only available from protocol version 1.6.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_era_id(dest_ptr: *const u8);
     /// This function uses the mint contract to create a new, empty purse. If the
     /// call is successful then the `URef` (in serialized form) is written
     /// to the indicated place in wasm memory. It is up to the caller to ensure at
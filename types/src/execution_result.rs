@@ -186,6 +186,36 @@ impl ExecutionResult {
         &EXECUTION_RESULT
     }
 
+    /// Returns a compact copy of the result, in which the execution effect's transforms are
+    /// summarized as operations.  See [`ExecutionEffect::compact`].
+    ///
+    /// The compact result is an [`ExecutionResultFormat::Compact`] result; callers presenting it
+    /// should label it as such, since it can't be told apart from a full result by its content.
+    pub fn compact(&self) -> Self {
+        match self {
+            ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => ExecutionResult::Failure {
+                effect: effect.compact(),
+                transfers: transfers.clone(),
+                cost: *cost,
+                error_message: error_message.clone(),
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+            } => ExecutionResult::Success {
+                effect: effect.compact(),
+                transfers: transfers.clone(),
+                cost: *cost,
+            },
+        }
+    }
+
     fn tag(&self) -> ExecutionResultTag {
         match self {
             ExecutionResult::Failure {
@@ -361,6 +391,51 @@ impl ExecutionEffect {
             operations: Default::default(),
         }
     }
+
+    /// Returns a compact copy of the effect, in which every transform is replaced by an operation
+    /// recording only the kind of change made to its key.
+    ///
+    /// The values written or added are dropped, which typically shrinks the effect by orders of
+    /// magnitude.  [`Transform::Failure`] entries are kept as they are, so that their error
+    /// messages are preserved.  Compacting an already compact effect returns an equal effect.
+    pub fn compact(&self) -> Self {
+        let mut operations = self.operations.clone();
+        let mut transforms = Vec::new();
+        for entry in &self.transforms {
+            match entry.transform.op_kind() {
+                Some(kind) => operations.push(Operation {
+                    key: entry.key.clone(),
+                    kind,
+                }),
+                None => transforms.push(entry.clone()),
+            }
+        }
+        ExecutionEffect {
+            operations,
+            transforms,
+        }
+    }
+}
+
+/// The format of an execution result.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub enum ExecutionResultFormat {
+    /// The execution result as produced by executing the deploy, holding every transform.
+    #[default]
+    Full,
+    /// The execution result compacted by [`ExecutionResult::compact`], in which every transform
+    /// other than a failure is replaced by the kind of change it made to its key.
+    Compact,
+}
+
+impl ExecutionResultFormat {
+    /// Returns `true` if this is the full format.
+    pub fn is_full(&self) -> bool {
+        *self == ExecutionResultFormat::Full
+    }
 }
 
 // TODO[goral09]: Add `write_bytes` impl.
@@ -557,6 +632,33 @@ pub enum Transform {
 }
 
 impl Transform {
+    /// Returns the kind of operation the transform performs on its key, or `None` for a
+    /// [`Transform::Failure`], which has no equivalent operation.
+    pub fn op_kind(&self) -> Option<OpKind> {
+        let kind = match self {
+            Transform::Identity => OpKind::Read,
+            Transform::WriteCLValue(_)
+            | Transform::WriteAccount(_)
+            | Transform::WriteContractWasm
+            | Transform::WriteContract
+            | Transform::WriteContractPackage
+            | Transform::WriteDeployInfo(_)
+            | Transform::WriteEraInfo(_)
+            | Transform::WriteTransfer(_)
+            | Transform::WriteBid(_)
+            | Transform::WriteWithdraw(_)
+            | Transform::WriteUnbonding(_) => OpKind::Write,
+            Transform::AddInt32(_)
+            | Transform::AddUInt64(_)
+            | Transform::AddUInt128(_)
+            | Transform::AddUInt256(_)
+            | Transform::AddUInt512(_)
+            | Transform::AddKeys(_) => OpKind::Add,
+            Transform::Failure(_) => return None,
+        };
+        Some(kind)
+    }
+
     fn tag(&self) -> TransformTag {
         match self {
             Transform::Identity => TransformTag::Identity,
@@ -798,4 +900,41 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn should_compact_execution_effect() {
+        let mut rng = get_rng();
+        let transforms: Vec<TransformEntry> = (0..10)
+            .map(|index| TransformEntry {
+                key: format!("key-{}", index),
+                transform: rng.gen(),
+            })
+            .collect();
+        let effect = ExecutionEffect::new(transforms.clone());
+
+        let compact = effect.compact();
+        let (failures, summarized): (Vec<_>, Vec<_>) = transforms
+            .iter()
+            .partition(|entry| entry.transform.op_kind().is_none());
+        assert_eq!(compact.transforms.iter().collect::<Vec<_>>(), failures);
+        assert_eq!(compact.operations.len(), summarized.len());
+        for (operation, entry) in compact.operations.iter().zip(summarized) {
+            assert_eq!(operation.key, entry.key);
+            assert_eq!(Some(operation.kind), entry.transform.op_kind());
+        }
+        assert_eq!(compact.compact(), compact);
+    }
+
+    #[test]
+    fn should_keep_failures_when_compacting() {
+        let failure = TransformEntry {
+            key: "key-0".to_string(),
+            transform: Transform::Failure("error".to_string()),
+        };
+        let effect = ExecutionEffect::new(vec![failure.clone()]);
+
+        let compact = effect.compact();
+        assert!(compact.operations.is_empty());
+        assert_eq!(compact.transforms, vec![failure]);
+    }
 }
@@ -77,7 +77,8 @@ pub use contracts::{
 pub use crypto::*;
 pub use deploy_info::DeployInfo;
 pub use execution_result::{
-    ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    ExecutionEffect, ExecutionResult, ExecutionResultFormat, OpKind, Operation, Transform,
+    TransformEntry,
 };
 pub use gas::Gas;
 pub use json_pretty_printer::json_pretty_print;